@@ -3,6 +3,8 @@ pub mod context;
 pub mod context_server_tool;
 pub mod context_store;
 pub mod history_store;
+pub mod hooks;
+mod subagent;
 pub mod thread;
 pub mod thread_store;
 pub mod tool_use;
@@ -10,11 +12,13 @@ pub mod tool_use;
 pub use context::{AgentContext, ContextId, ContextLoadResult};
 pub use context_store::ContextStore;
 pub use thread::{
-    LastRestoreCheckpoint, Message, MessageCrease, MessageId, MessageSegment, Thread, ThreadError,
-    ThreadEvent, ThreadFeedback, ThreadId, ThreadSummary, TokenUsageRatio,
+    DirectoryRules, LastRestoreCheckpoint, Message, MessageCrease, MessageId, MessageSegment,
+    Thread, ThreadBranchPoint, ThreadError, ThreadEvent, ThreadFeedback, ThreadId, ThreadSummary,
+    TokenUsageRatio,
 };
 pub use thread_store::{SerializedThread, TextThreadStore, ThreadStore};
 
 pub fn init(cx: &mut gpui::App) {
     thread_store::init(cx);
+    subagent::init(cx);
 }
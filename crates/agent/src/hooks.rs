@@ -0,0 +1,268 @@
+//! Runs the shell commands that users configure as hooks in the agent settings.
+
+use std::{path::PathBuf, process::Stdio, sync::Arc, time::Duration};
+
+use agent_settings::AgentHook;
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::FutureExt as _;
+use gpui::{App, Entity, Task};
+use project::{
+    Project,
+    terminals::{SshCommand, wrap_for_ssh},
+};
+use util::{command::new_smol_command, get_system_shell, markdown::MarkdownInlineCode};
+
+/// How much of a hook's output is shown to the agent and the user.
+const HOOK_OUTPUT_LIMIT: usize = 16 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookCommand {
+    pub command: String,
+    pub cwd: PathBuf,
+    pub env: HashMap<String, String>,
+    /// How long the command may run before it's killed.
+    pub timeout: Duration,
+    /// How to connect to the remote machine the command runs on, if the project is remote.
+    pub ssh_command: Option<SshCommand>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HookOutput {
+    /// Whether the command exited with a zero status.
+    pub success: bool,
+    /// The combined stdout and stderr of the command.
+    pub output: String,
+}
+
+/// Runs hook commands to completion.
+pub trait HookRunner: 'static + Send + Sync {
+    fn run(&self, command: HookCommand, cx: &App) -> Task<Result<HookOutput>>;
+}
+
+/// Runs hooks with the system shell, or with `sh` on the remote machine over SSH.
+pub struct ShellHookRunner;
+
+impl HookRunner for ShellHookRunner {
+    fn run(&self, command: HookCommand, cx: &App) -> Task<Result<HookOutput>> {
+        let timeout = cx.background_executor().timer(command.timeout);
+        cx.background_spawn(async move {
+            let mut process = match &command.ssh_command {
+                Some(ssh_command) => {
+                    let (program, args) = wrap_for_ssh(
+                        ssh_command,
+                        Some((
+                            &"sh".to_string(),
+                            &vec!["-c".to_string(), command.command.clone()],
+                        )),
+                        Some(&command.cwd),
+                        command.env.clone(),
+                        None,
+                    );
+                    let mut process = new_smol_command(program);
+                    process.args(args);
+                    process
+                }
+                None => {
+                    let mut process = new_smol_command(get_system_shell());
+                    process
+                        .arg("-c")
+                        .arg(&command.command)
+                        .current_dir(&command.cwd)
+                        .envs(&command.env);
+                    process
+                }
+            };
+            process.stdin(Stdio::null()).kill_on_drop(true);
+
+            // Dropping the output future when the timeout elapses kills the process.
+            let output = futures::select_biased! {
+                output = process.output().fuse() => output
+                    .with_context(|| format!("failed to run hook `{}`", command.command))?,
+                _ = timeout.fuse() => anyhow::bail!(
+                    "hook `{}` timed out after {} seconds",
+                    command.command,
+                    command.timeout.as_secs()
+                ),
+            };
+
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            Ok(HookOutput {
+                success: output.status.success(),
+                output: text,
+            })
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct HookResult {
+    pub command: String,
+    pub output: Result<HookOutput>,
+}
+
+impl HookResult {
+    pub fn succeeded(&self) -> bool {
+        self.output.as_ref().is_ok_and(|output| output.success)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let command = MarkdownInlineCode(&self.command);
+        let output = match &self.output {
+            Ok(output) => output,
+            Err(error) => return format!("Hook {command} failed to run: {error:#}"),
+        };
+
+        let text = output.output.trim_end();
+        let status = if output.success {
+            "succeeded"
+        } else {
+            "failed"
+        };
+        if text.is_empty() {
+            return format!("Hook {command} {status} with no output.");
+        }
+
+        let (text, truncated) = if text.len() > HOOK_OUTPUT_LIMIT {
+            let mut end_ix = HOOK_OUTPUT_LIMIT;
+            while !text.is_char_boundary(end_ix) {
+                end_ix -= 1;
+            }
+            (&text[..end_ix], true)
+        } else {
+            (text, false)
+        };
+        let mut markdown = format!("Hook {command} {status}:\n\n```\n{text}\n```");
+        if truncated {
+            markdown.push_str("\n\nThe output was too long, so it was truncated.");
+        }
+        markdown
+    }
+}
+
+/// Runs the given hooks one after another in the project's first worktree, and returns their
+/// results. In projects opened over SSH, hooks run on the remote machine. They can't run in
+/// projects shared by collaborators, so they fail there, which blocks the tool calls that
+/// `before_tool_call` hooks apply to.
+pub(crate) fn run_hooks(
+    hooks: Vec<AgentHook>,
+    env: HashMap<String, String>,
+    runner: Arc<dyn HookRunner>,
+    project: &Entity<Project>,
+    cx: &mut App,
+) -> Task<Vec<HookResult>> {
+    if hooks.is_empty() {
+        return Task::ready(Vec::new());
+    }
+    let ssh_command = if project.read(cx).is_local() {
+        None
+    } else if let Some((_, ssh_command)) = project.read(cx).ssh_details(cx) {
+        Some(ssh_command)
+    } else {
+        return Task::ready(
+            hooks
+                .into_iter()
+                .map(|hook| HookResult {
+                    command: hook.command,
+                    output: Err(anyhow!(
+                        "Hooks can't run in projects shared by collaborators."
+                    )),
+                })
+                .collect(),
+        );
+    };
+    let Some(cwd) = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .next()
+        .map(|worktree| worktree.read(cx).abs_path())
+    else {
+        return Task::ready(Vec::new());
+    };
+    // The remote shell sets up the environment of remote projects.
+    let directory_env = ssh_command.is_none().then(|| {
+        project.update(cx, |project, cx| {
+            project.directory_environment(cwd.clone(), cx)
+        })
+    });
+
+    cx.spawn(async move |cx| {
+        let mut command_env = match directory_env {
+            Some(directory_env) => directory_env.await.unwrap_or_default(),
+            None => HashMap::default(),
+        };
+        command_env.extend(env);
+
+        let mut results = Vec::with_capacity(hooks.len());
+        for hook in hooks {
+            let command = HookCommand {
+                command: hook.command.clone(),
+                cwd: cwd.to_path_buf(),
+                env: command_env.clone(),
+                timeout: hook.timeout(),
+                ssh_command: ssh_command.clone(),
+            };
+            let output = match cx.update(|cx| runner.run(command, cx)) {
+                Ok(task) => task.await,
+                Err(error) => Err(error),
+            };
+            results.push(HookResult {
+                command: hook.command,
+                output,
+            });
+        }
+        results
+    })
+}
+
+#[cfg(any(test, feature = "test-support"))]
+pub use fake::FakeHookRunner;
+
+#[cfg(any(test, feature = "test-support"))]
+mod fake {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records the hooks it's asked to run instead of running them.
+    #[derive(Default)]
+    pub struct FakeHookRunner {
+        outputs: Mutex<HashMap<String, HookOutput>>,
+        commands: Mutex<Vec<HookCommand>>,
+    }
+
+    impl FakeHookRunner {
+        /// Sets the output of the given command. Commands without an output succeed silently.
+        pub fn set_output(&self, command: impl Into<String>, success: bool, output: &str) {
+            self.outputs.lock().unwrap().insert(
+                command.into(),
+                HookOutput {
+                    success,
+                    output: output.to_string(),
+                },
+            );
+        }
+
+        /// Returns the commands that have been run so far.
+        pub fn commands(&self) -> Vec<HookCommand> {
+            self.commands.lock().unwrap().clone()
+        }
+    }
+
+    impl HookRunner for FakeHookRunner {
+        fn run(&self, command: HookCommand, _cx: &App) -> Task<Result<HookOutput>> {
+            let output = self
+                .outputs
+                .lock()
+                .unwrap()
+                .get(&command.command)
+                .cloned()
+                .unwrap_or(HookOutput {
+                    success: true,
+                    output: String::new(),
+                });
+            self.commands.lock().unwrap().push(command);
+            Task::ready(Ok(output))
+        }
+    }
+}
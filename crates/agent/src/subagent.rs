@@ -0,0 +1,173 @@
+//! Runs the tasks that agents delegate with the `delegate_task` tool in child threads.
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use assistant_tool::{SubagentRunner, SubagentTask};
+use collections::HashMap;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{App, Context, Global, Task, WeakEntity};
+use language_model::{Role, StopReason};
+use zed_llm_client::CompletionIntent;
+
+use crate::{Thread, ThreadEvent, ThreadId, context::ContextLoadResult, thread::MessageSegment};
+
+/// What the sub-agent is told about its situation, after the delegated instructions.
+const SUBAGENT_GUIDELINES: &str = "You are working on this task on behalf of another agent, \
+    which only gets to see your last message. Don't make any changes to the project. \
+    When you're done, reply with a concise report of your findings, including the paths and \
+    line numbers of the relevant code.";
+
+/// The threads that are loaded, so that tasks can be delegated from the thread they're
+/// requested in.
+#[derive(Default)]
+struct LoadedThreads(HashMap<ThreadId, WeakEntity<Thread>>);
+
+impl Global for LoadedThreads {}
+
+pub(crate) fn init(cx: &mut App) {
+    <dyn SubagentRunner>::set_global(Arc::new(ThreadSubagentRunner), cx);
+}
+
+pub(crate) fn track_thread(id: ThreadId, cx: &mut Context<Thread>) {
+    let this = cx.weak_entity();
+    let threads = &mut cx.default_global::<LoadedThreads>().0;
+    threads.retain(|_, thread| thread.is_upgradable());
+    threads.insert(id, this);
+}
+
+struct ThreadSubagentRunner;
+
+impl SubagentRunner for ThreadSubagentRunner {
+    fn run_subagent(&self, task: SubagentTask, cx: &mut App) -> Task<Result<String>> {
+        let parent = cx
+            .try_global::<LoadedThreads>()
+            .and_then(|threads| {
+                threads
+                    .0
+                    .get(&ThreadId::from(task.parent_thread_id.as_str()))
+            })
+            .and_then(|thread| thread.upgrade());
+        let Some(parent) = parent else {
+            return Task::ready(Err(anyhow!("The delegating thread is no longer loaded")));
+        };
+        if parent.read(cx).is_subagent() {
+            return Task::ready(Err(anyhow!("Sub-agents can't delegate tasks")));
+        }
+        let Some(configured_model) =
+            parent.update(cx, |parent, cx| parent.get_or_init_configured_model(cx))
+        else {
+            return Task::ready(Err(anyhow!("No language model configured")));
+        };
+
+        let child = parent.update(cx, |parent, cx| parent.new_subagent(cx));
+        let (tx, mut rx) = mpsc::unbounded();
+        let subscription = cx.subscribe(&child, {
+            let model = configured_model.model.clone();
+            move |child, event: &ThreadEvent, cx| match event {
+                ThreadEvent::ShowError(error) => {
+                    tx.unbounded_send(Err(anyhow!(error.clone()))).ok();
+                }
+                ThreadEvent::Stopped(Ok(StopReason::EndTurn | StopReason::MaxTokens)) => {
+                    tx.unbounded_send(Ok(())).ok();
+                }
+                ThreadEvent::Stopped(Ok(StopReason::ToolUse)) => {
+                    if child.read(cx).remaining_turns() == 0 {
+                        tx.unbounded_send(Ok(())).ok();
+                    }
+                }
+                ThreadEvent::Stopped(Ok(StopReason::Refusal)) => {
+                    tx.unbounded_send(Err(anyhow!("The model refused to work on the task")))
+                        .ok();
+                }
+                ThreadEvent::Stopped(Err(error)) => {
+                    tx.unbounded_send(Err(anyhow!(error.clone()))).ok();
+                }
+                ThreadEvent::CompletionCanceled => {
+                    tx.unbounded_send(Err(anyhow!("The task was canceled")))
+                        .ok();
+                }
+                ThreadEvent::ToolConfirmationNeeded => {
+                    // Nobody is watching the sub-agent, so it only gets to use tools that don't
+                    // need confirmation.
+                    child.update(cx, |child, cx| {
+                        let tool_uses = child
+                            .tools_needing_confirmation()
+                            .map(|tool_use| (tool_use.id.clone(), tool_use.name.clone()))
+                            .collect::<Vec<_>>();
+                        for (tool_use_id, tool_name) in tool_uses {
+                            child.deny_tool_use(tool_use_id, tool_name, None, cx);
+                        }
+                        if !child.has_pending_tool_uses() {
+                            child.send_to_model(
+                                model.clone(),
+                                CompletionIntent::ToolResults,
+                                None,
+                                cx,
+                            );
+                        }
+                    });
+                }
+                _ => {}
+            }
+        });
+
+        child.update(cx, |child, cx| {
+            child.insert_user_message(
+                format!("{}\n\n{SUBAGENT_GUIDELINES}", task.instructions),
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            child.send_to_model(
+                configured_model.model,
+                CompletionIntent::UserPrompt,
+                None,
+                cx,
+            );
+        });
+
+        let parent = parent.downgrade();
+        cx.spawn(async move |cx| {
+            let result = rx.next().await.unwrap_or(Ok(()));
+            drop(subscription);
+
+            let (report, token_usage, cost) = child.read_with(cx, |child, _| {
+                (
+                    final_report(child),
+                    child.cumulative_token_usage(),
+                    child.cost(),
+                )
+            })?;
+            parent
+                .update(cx, |parent, cx| {
+                    parent.add_delegated_token_usage(token_usage, cost, cx)
+                })
+                .ok();
+
+            result?;
+            report.ok_or_else(|| anyhow!("The sub-agent finished without reporting back"))
+        })
+    }
+}
+
+/// The text of the sub-agent's last response, which is where it's asked to report its findings.
+fn final_report(thread: &Thread) -> Option<String> {
+    let message = thread
+        .messages()
+        .filter(|message| message.role == Role::Assistant)
+        .last()?;
+    let report = message
+        .segments
+        .iter()
+        .filter_map(|segment| match segment {
+            MessageSegment::Text(text) => Some(text.as_str()),
+            MessageSegment::Thinking { .. } | MessageSegment::RedactedThinking(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    (!report.is_empty()).then_some(report)
+}
@@ -1,26 +1,34 @@
 use crate::{
     agent_profile::AgentProfile,
     context::{AgentContext, AgentContextHandle, ContextLoadResult, LoadedContext},
+    hooks::{self, HookResult, HookRunner, ShellHookRunner},
+    subagent,
     thread_store::{
-        SerializedCrease, SerializedLanguageModel, SerializedMessage, SerializedMessageSegment,
-        SerializedThread, SerializedToolResult, SerializedToolUse, SharedProjectContext,
-        ThreadStore,
+        self, SerializedCrease, SerializedLanguageModel, SerializedMessage,
+        SerializedMessageSegment, SerializedThread, SerializedToolResult, SerializedToolUse,
+        SharedProjectContext, ThreadStore,
     },
     tool_use::{PendingToolUse, ToolUse, ToolUseMetadata, ToolUseState},
 };
 use agent_settings::{AgentProfileId, AgentSettings, CompletionMode};
 use anyhow::{Result, anyhow};
-use assistant_tool::{ActionLog, AnyToolCard, Tool, ToolWorkingSet};
+use assistant_tool::{
+    ActionLog, AnyToolCard, Tool, ToolResultContent, ToolResultOutput, ToolWorkingSet,
+};
 use chrono::{DateTime, Utc};
 use client::{ModelRequestUsage, RequestUsage};
 use collections::{HashMap, HashSet};
 use feature_flags::{self, FeatureFlagAppExt};
-use futures::{FutureExt, StreamExt as _, future::Shared};
+use futures::{
+    FutureExt, StreamExt as _,
+    future::{self, Shared},
+};
 use git::repository::DiffType;
 use gpui::{
     AnyWindowHandle, App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task,
     WeakEntity, Window,
 };
+use language::Buffer;
 use language_model::{
     ConfiguredModel, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelKnownError, LanguageModelRegistry, LanguageModelRequest,
@@ -31,7 +39,7 @@ use language_model::{
 };
 use postage::stream::Stream as _;
 use project::{
-    Project,
+    Project, ProjectItem, ProjectPath,
     git_store::{GitStore, GitStoreCheckpoint, RepositoryState},
 };
 use prompt_store::{ModelContext, PromptBuilder};
@@ -42,6 +50,7 @@ use settings::Settings;
 use std::{
     io::Write,
     ops::Range,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -52,6 +61,8 @@ use zed_llm_client::{CompletionIntent, CompletionRequestStatus, UsageLimit};
 
 const MAX_RETRY_ATTEMPTS: u8 = 3;
 const BASE_RETRY_DELAY_SECS: u64 = 5;
+/// The most requests a sub-agent makes before it has to report back.
+const MAX_SUBAGENT_TURNS: u32 = 25;
 
 #[derive(
     Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize, JsonSchema,
@@ -107,6 +118,13 @@ impl MessageId {
     }
 }
 
+/// The message of another thread that a thread was branched from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadBranchPoint {
+    pub thread_id: ThreadId,
+    pub message_id: MessageId,
+}
+
 /// Stored information that can be used to resurrect a context crease when creating an editor for a past message.
 #[derive(Clone, Debug)]
 pub struct MessageCrease {
@@ -295,6 +313,8 @@ impl DetailedSummaryState {
 pub struct TotalTokenUsage {
     pub total: u64,
     pub max: u64,
+    /// The tokens used by sub-agents, which don't take up space in the thread's context window.
+    pub delegated: u64,
 }
 
 impl TotalTokenUsage {
@@ -324,6 +344,27 @@ impl TotalTokenUsage {
         TotalTokenUsage {
             total: self.total + tokens,
             max: self.max,
+            delegated: self.delegated,
+        }
+    }
+}
+
+/// The estimated cost of the requests made in a thread, in USD.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThreadCost {
+    pub amount: f64,
+    /// Whether some requests were made to models without known pricing,
+    /// in which case they aren't included in the amount.
+    pub has_unpriced_requests: bool,
+}
+
+impl std::ops::Add for ThreadCost {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            amount: self.amount + other.amount,
+            has_unpriced_requests: self.has_unpriced_requests || other.has_unpriced_requests,
         }
     }
 }
@@ -370,6 +411,8 @@ pub struct Thread {
     initial_project_snapshot: Shared<Task<Option<Arc<ProjectSnapshot>>>>,
     request_token_usage: Vec<TokenUsage>,
     cumulative_token_usage: TokenUsage,
+    delegated_token_usage: TokenUsage,
+    cost: ThreadCost,
     exceeded_window_error: Option<ExceededWindowError>,
     tool_use_limit_reached: bool,
     feedback: Option<ThreadFeedback>,
@@ -383,6 +426,20 @@ pub struct Thread {
     remaining_turns: u32,
     configured_model: Option<ConfiguredModel>,
     profile: AgentProfile,
+    branched_from: Option<ThreadBranchPoint>,
+    is_subagent: bool,
+    hook_runner: Arc<dyn HookRunner>,
+    directory_rules: Vec<DirectoryRules>,
+}
+
+/// A rules file in a subdirectory of a worktree, which is added to the thread the first time the
+/// agent reads or edits a file under that directory.
+#[derive(Debug, Clone)]
+pub struct DirectoryRules {
+    /// The path of the rules file, starting with the name of its worktree.
+    pub full_path: Arc<Path>,
+    /// The buffer of the rules file, once it has been opened.
+    pub buffer: Option<Entity<Buffer>>,
 }
 
 #[derive(Clone, Debug)]
@@ -438,9 +495,11 @@ impl Thread {
         let (detailed_summary_tx, detailed_summary_rx) = postage::watch::channel();
         let configured_model = LanguageModelRegistry::read_global(cx).default_model();
         let profile_id = AgentSettings::get_global(cx).default_profile.clone();
+        let id = ThreadId::new();
+        subagent::track_thread(id.clone(), cx);
 
         Self {
-            id: ThreadId::new(),
+            id,
             updated_at: Utc::now(),
             summary: ThreadSummary::Pending,
             pending_summary: Task::ready(None),
@@ -470,6 +529,8 @@ impl Thread {
             },
             request_token_usage: Vec::new(),
             cumulative_token_usage: TokenUsage::default(),
+            delegated_token_usage: TokenUsage::default(),
+            cost: ThreadCost::default(),
             exceeded_window_error: None,
             tool_use_limit_reached: false,
             feedback: None,
@@ -481,6 +542,10 @@ impl Thread {
             remaining_turns: u32::MAX,
             configured_model,
             profile: AgentProfile::new(profile_id, tools),
+            branched_from: None,
+            is_subagent: false,
+            hook_runner: Arc::new(ShellHookRunner),
+            directory_rules: Vec::new(),
         }
     }
 
@@ -530,8 +595,9 @@ impl Thread {
         let profile_id = serialized
             .profile
             .unwrap_or_else(|| AgentSettings::get_global(cx).default_profile.clone());
+        subagent::track_thread(id.clone(), cx);
 
-        Self {
+        let this = Self {
             id,
             updated_at: serialized.updated_at,
             summary: ThreadSummary::Ready(serialized.summary),
@@ -595,6 +661,8 @@ impl Thread {
             initial_project_snapshot: Task::ready(serialized.initial_project_snapshot).shared(),
             request_token_usage: serialized.request_token_usage,
             cumulative_token_usage: serialized.cumulative_token_usage,
+            delegated_token_usage: serialized.delegated_token_usage,
+            cost: serialized.cost,
             exceeded_window_error: None,
             tool_use_limit_reached: serialized.tool_use_limit_reached,
             feedback: None,
@@ -605,7 +673,20 @@ impl Thread {
             remaining_turns: u32::MAX,
             configured_model,
             profile: AgentProfile::new(profile_id, tools),
-        }
+            branched_from: serialized.branched_from,
+            is_subagent: false,
+            hook_runner: Arc::new(ShellHookRunner),
+            directory_rules: serialized
+                .directory_rules
+                .into_iter()
+                .map(|full_path| DirectoryRules {
+                    full_path,
+                    buffer: None,
+                })
+                .collect(),
+        };
+        this.open_directory_rules(cx);
+        this
     }
 
     pub fn set_request_callback(
@@ -616,6 +697,11 @@ impl Thread {
         self.request_callback = Some(Box::new(callback));
     }
 
+    /// Sets what runs the hooks configured in the agent settings.
+    pub fn set_hook_runner(&mut self, runner: Arc<dyn HookRunner>) {
+        self.hook_runner = runner;
+    }
+
     pub fn id(&self) -> &ThreadId {
         &self.id
     }
@@ -624,6 +710,61 @@ impl Thread {
         &self.profile
     }
 
+    /// The message this thread was branched from, if it was created with [`ThreadStore::branch_thread`].
+    ///
+    /// [`ThreadStore::branch_thread`]: crate::ThreadStore::branch_thread
+    pub fn branched_from(&self) -> Option<&ThreadBranchPoint> {
+        self.branched_from.as_ref()
+    }
+
+    /// Whether this thread is working on a task delegated by another thread.
+    pub fn is_subagent(&self) -> bool {
+        self.is_subagent
+    }
+
+    /// The rules files from subdirectories that apply to files the agent has read or edited.
+    pub fn directory_rules(&self) -> &[DirectoryRules] {
+        &self.directory_rules
+    }
+
+    /// Creates a thread to work on a task delegated by this one, with the same model and profile.
+    /// Sub-agents only get to use the profile's read-only tools, see [`Self::enabled_tools`].
+    pub(crate) fn new_subagent(&self, cx: &mut App) -> Entity<Thread> {
+        let project = self.project.clone();
+        let tools = self.tools.clone();
+        let prompt_builder = self.prompt_builder.clone();
+        let project_context = self.project_context.clone();
+        let configured_model = self.configured_model.clone();
+        let completion_mode = self.completion_mode;
+        let hook_runner = self.hook_runner.clone();
+        let profile = self.profile.clone();
+        cx.new(|cx| {
+            let mut thread = Thread::new(project, tools, prompt_builder, project_context, cx);
+            thread.is_subagent = true;
+            thread.hook_runner = hook_runner;
+            thread.configured_model = configured_model;
+            thread.completion_mode = completion_mode;
+            thread.remaining_turns = MAX_SUBAGENT_TURNS;
+            thread.profile = profile;
+            thread
+        })
+    }
+
+    /// Returns the tools that are enabled in the thread's profile. Nobody watches sub-agents
+    /// work, so they only get read-only tools.
+    pub fn enabled_tools(&self, cx: &App) -> Vec<Arc<dyn Tool>> {
+        let mut tools = self.profile.enabled_tools(cx);
+        if self.is_subagent {
+            tools.retain(|tool| tool.is_read_only());
+        }
+        tools
+    }
+
+    fn is_tool_enabled(&self, tool: &Arc<dyn Tool>, cx: &App) -> bool {
+        self.profile.is_tool_enabled(tool.source(), tool.name(), cx)
+            && (!self.is_subagent || tool.is_read_only())
+    }
+
     pub fn set_profile(&mut self, id: AgentProfileId, cx: &mut Context<Self>) {
         if &id != self.profile.id() {
             self.profile = AgentProfile::new(id, self.tools.clone());
@@ -961,7 +1102,7 @@ impl Thread {
         model: Arc<dyn LanguageModel>,
     ) -> Vec<LanguageModelRequestTool> {
         if model.supports_tools() {
-            resolve_tool_name_conflicts(self.profile.enabled_tools(cx).as_slice())
+            resolve_tool_name_conflicts(self.enabled_tools(cx).as_slice())
                 .into_iter()
                 .filter_map(|(name, tool)| {
                     // Skip tools that cannot be supported
@@ -1210,6 +1351,8 @@ impl Thread {
                     .collect(),
                 initial_project_snapshot,
                 cumulative_token_usage: this.cumulative_token_usage,
+                delegated_token_usage: this.delegated_token_usage,
+                cost: this.cost,
                 request_token_usage: this.request_token_usage.clone(),
                 detailed_summary_state: this.detailed_summary_rx.borrow().clone(),
                 exceeded_window_error: this.exceeded_window_error.clone(),
@@ -1223,6 +1366,12 @@ impl Thread {
                 completion_mode: Some(this.completion_mode),
                 tool_use_limit_reached: this.tool_use_limit_reached,
                 profile: Some(this.profile.id().clone()),
+                branched_from: this.branched_from.clone(),
+                directory_rules: this
+                    .directory_rules
+                    .iter()
+                    .map(|rules| rules.full_path.clone())
+                    .collect(),
             })
         })
     }
@@ -1618,10 +1767,14 @@ impl Thread {
                             }
                             LanguageModelCompletionEvent::UsageUpdate(token_usage) => {
                                 thread.update_token_usage_at_last_message(token_usage);
-                                thread.cumulative_token_usage = thread.cumulative_token_usage
-                                    + token_usage
-                                    - current_token_usage;
-                                current_token_usage = token_usage;
+                                // Providers may report fewer tokens of some kind than in their
+                                // previous usage update, which mustn't decrease the usage nor be
+                                // counted again once it is reported anew.
+                                let usage_delta = token_usage.saturating_sub(current_token_usage);
+                                thread.cumulative_token_usage =
+                                    thread.cumulative_token_usage + usage_delta;
+                                thread.add_request_cost(&model, usage_delta);
+                                current_token_usage = current_token_usage.max(token_usage);
                             }
                             LanguageModelCompletionEvent::Text(chunk) => {
                                 thread.received_chunk();
@@ -1964,6 +2117,9 @@ impl Thread {
                     }
 
                     if !retry_scheduled {
+                        if let Ok(stop_reason) = &result {
+                            thread.run_stop_hooks(*stop_reason, cx);
+                        }
                         cx.emit(ThreadEvent::Stopped(result.map_err(Arc::new)));
                     }
 
@@ -2379,10 +2535,68 @@ impl Thread {
             return self.handle_hallucinated_tool_use(tool_use.id, tool_use.name, window, cx);
         };
 
-        if !self.profile.is_tool_enabled(tool.source(), tool.name(), cx) {
+        if !self.is_tool_enabled(&tool, cx) {
             return self.handle_hallucinated_tool_use(tool_use.id, tool_use.name, window, cx);
         }
 
+        let hooks = AgentSettings::get_global(cx)
+            .hooks
+            .before_tool_call
+            .iter()
+            .filter(|hook| hook.applies_to_tool(&tool_use.name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if hooks.is_empty() {
+            return self.confirm_or_run_tool(tool_use, request, tool, model, window, cx);
+        }
+
+        let env = HashMap::from_iter([
+            ("ZED_AGENT_TOOL_NAME".to_string(), tool_use.name.to_string()),
+            (
+                "ZED_AGENT_TOOL_INPUT".to_string(),
+                tool_use.input.to_string(),
+            ),
+        ]);
+        let run_hooks = hooks::run_hooks(hooks, env, self.hook_runner.clone(), &self.project, cx);
+        let tool_use_id = tool_use.id.clone();
+        let ui_text = tool_use.ui_text.clone();
+        let task = cx.spawn(async move |thread, cx| {
+            let results = run_hooks.await;
+            thread
+                .update(cx, |thread, cx| {
+                    match results.iter().find(|result| !result.succeeded()) {
+                        Some(veto) => {
+                            let pending_tool_use = thread.tool_use.insert_tool_output(
+                                tool_use.id.clone(),
+                                tool_use.name,
+                                Err(anyhow!(
+                                    "The tool call was blocked by a hook.\n\n{}",
+                                    veto.to_markdown()
+                                )),
+                                thread.configured_model.as_ref(),
+                            );
+                            thread.tool_finished(tool_use.id, pending_tool_use, false, window, cx);
+                        }
+                        None => {
+                            thread.confirm_or_run_tool(tool_use, request, tool, model, window, cx)
+                        }
+                    }
+                })
+                .ok();
+        });
+        self.tool_use
+            .run_pending_tool(tool_use_id, ui_text.into(), task);
+    }
+
+    fn confirm_or_run_tool(
+        &mut self,
+        tool_use: PendingToolUse,
+        request: Arc<LanguageModelRequest>,
+        tool: Arc<dyn Tool>,
+        model: Arc<dyn LanguageModel>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut Context<Self>,
+    ) {
         if tool.needs_confirmation(&tool_use.input, cx)
             && !AgentSettings::get_global(cx).always_allow_tool_actions
         {
@@ -2415,7 +2629,7 @@ impl Thread {
         window: Option<AnyWindowHandle>,
         cx: &mut Context<Thread>,
     ) {
-        let available_tools = self.profile.enabled_tools(cx);
+        let available_tools = self.enabled_tools(cx);
 
         let tool_list = available_tools
             .iter()
@@ -2525,7 +2739,32 @@ impl Thread {
 
         cx.spawn({
             async move |thread: WeakEntity<Thread>, cx| {
-                let output = tool_result.output.await;
+                let mut output = tool_result.output.await;
+
+                let after_edit_hooks = thread
+                    .update(cx, |thread, cx| thread.run_after_edit_hooks(&tool_name, cx))
+                    .ok()
+                    .flatten();
+                if let Some(after_edit_hooks) = after_edit_hooks {
+                    let results = after_edit_hooks.await;
+                    output = output.map(|output| append_hook_results(output, &results));
+                }
+
+                // Rules are only added once they can be shown to the model, so a failed tool
+                // leaves them to be added by the next tool that touches their directory.
+                let directory_rules = output
+                    .is_ok()
+                    .then(|| {
+                        thread
+                            .update(cx, |thread, cx| thread.load_directory_rules(cx))
+                            .ok()
+                            .flatten()
+                    })
+                    .flatten();
+                if let Some(directory_rules) = directory_rules {
+                    let rules = directory_rules.await;
+                    output = output.map(|output| append_directory_rules(output, &rules));
+                }
 
                 thread
                     .update(cx, |thread, cx| {
@@ -2542,6 +2781,257 @@ impl Thread {
         })
     }
 
+    /// Runs the `after_edit` hooks if the tool that just finished edited any files.
+    fn run_after_edit_hooks(
+        &mut self,
+        tool_name: &str,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Vec<HookResult>>> {
+        let edited_buffers = self.action_log.update(cx, |action_log, _| {
+            action_log.take_recently_edited_buffers()
+        });
+        // Paths are resolved through their worktree so that files in remote projects are
+        // included too.
+        let project = self.project.read(cx);
+        let edited_paths = edited_buffers
+            .iter()
+            .filter_map(|buffer| {
+                let project_path = buffer.read(cx).project_path(cx)?;
+                let abs_path = project.absolute_path(&project_path, cx)?;
+                Some(abs_path.to_string_lossy().into_owned())
+            })
+            .collect::<Vec<_>>();
+        if edited_paths.is_empty() {
+            return None;
+        }
+
+        let hooks = AgentSettings::get_global(cx)
+            .hooks
+            .after_edit
+            .iter()
+            .filter(|hook| hook.applies_to_tool(tool_name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if hooks.is_empty() {
+            return None;
+        }
+
+        let env = HashMap::from_iter([(
+            "ZED_AGENT_EDITED_FILES".to_string(),
+            edited_paths.join("\n"),
+        )]);
+        Some(hooks::run_hooks(
+            hooks,
+            env,
+            self.hook_runner.clone(),
+            &self.project,
+            cx,
+        ))
+    }
+
+    /// Loads the rules files in the subdirectories of files the agent has read or edited, which
+    /// haven't been added to the thread yet.
+    ///
+    /// The rules file at the root of a worktree isn't included, since it's part of the system
+    /// prompt.
+    fn load_directory_rules(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Vec<LoadedDirectoryRules>>> {
+        let project = self.project.read(cx);
+        let mut rules_files = Vec::<(LoadedDirectoryRules, ProjectPath)>::new();
+        for buffer in self.action_log.read(cx).tracked_buffers() {
+            let Some(project_path) = buffer.read(cx).project_path(cx) else {
+                continue;
+            };
+            let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) else {
+                continue;
+            };
+            let worktree = worktree.read(cx);
+            let mut directories = project_path
+                .path
+                .ancestors()
+                .skip(1)
+                .filter(|directory| !directory.as_os_str().is_empty())
+                .collect::<Vec<_>>();
+            // Rules files from outer directories come first.
+            directories.reverse();
+            for directory in directories {
+                let Some(path) = thread_store::find_rules_file(worktree, directory) else {
+                    continue;
+                };
+                // The agent already has the contents of a rules file it read or edited itself.
+                if path == project_path.path {
+                    continue;
+                }
+                let root_name = Path::new(worktree.root_name());
+                let full_path: Arc<Path> = root_name.join(&path).into();
+                let is_added = self
+                    .directory_rules
+                    .iter()
+                    .any(|rules| rules.full_path == full_path)
+                    || rules_files
+                        .iter()
+                        .any(|(rules, _)| rules.full_path == full_path);
+                if !is_added {
+                    rules_files.push((
+                        LoadedDirectoryRules {
+                            directory: root_name.join(directory).into(),
+                            full_path,
+                            text: String::new(),
+                        },
+                        ProjectPath {
+                            worktree_id: project_path.worktree_id,
+                            path,
+                        },
+                    ));
+                }
+            }
+        }
+        if rules_files.is_empty() {
+            return None;
+        }
+
+        let project = self.project.clone();
+        let load_tasks = rules_files
+            .into_iter()
+            .map(|(rules, project_path)| {
+                self.directory_rules.push(DirectoryRules {
+                    full_path: rules.full_path.clone(),
+                    buffer: None,
+                });
+                let load_task = thread_store::load_rules_file(project_path, &project, cx);
+                async move { (rules, load_task.await) }
+            })
+            .collect::<Vec<_>>();
+        Some(cx.spawn(async move |thread, cx| {
+            let results = future::join_all(load_tasks).await;
+            thread
+                .update(cx, |thread, cx| {
+                    let mut loaded_rules = Vec::new();
+                    for (mut loaded, result) in results {
+                        match result {
+                            Ok((buffer, rules_file)) => {
+                                if let Some(rules) = thread
+                                    .directory_rules
+                                    .iter_mut()
+                                    .find(|rules| rules.full_path == loaded.full_path)
+                                {
+                                    rules.buffer = Some(buffer);
+                                }
+                                if !rules_file.text.is_empty() {
+                                    loaded.text = rules_file.text;
+                                    loaded_rules.push(loaded);
+                                }
+                            }
+                            Err(err) => {
+                                log::error!(
+                                    "failed to load rules file {}: {err:?}",
+                                    loaded.full_path.display()
+                                );
+                                // Try again the next time a file under its directory is touched.
+                                thread
+                                    .directory_rules
+                                    .retain(|rules| rules.full_path != loaded.full_path);
+                            }
+                        }
+                    }
+                    cx.emit(ThreadEvent::DirectoryRulesChanged);
+                    loaded_rules
+                })
+                .unwrap_or_default()
+        }))
+    }
+
+    /// Opens the buffers of the rules files that were added to the thread before it was saved, so
+    /// that they can be shown.
+    fn open_directory_rules(&self, cx: &mut Context<Self>) {
+        let open_tasks = self
+            .directory_rules
+            .iter()
+            .filter(|rules| rules.buffer.is_none())
+            .filter_map(|rules| {
+                let project_path = self
+                    .project
+                    .read(cx)
+                    .find_project_path(&rules.full_path, cx)?;
+                let open_task = self
+                    .project
+                    .update(cx, |project, cx| project.open_buffer(project_path, cx));
+                let full_path = rules.full_path.clone();
+                Some(async move { (full_path, open_task.await) })
+            })
+            .collect::<Vec<_>>();
+        if open_tasks.is_empty() {
+            return;
+        }
+
+        cx.spawn(async move |thread, cx| {
+            let results = future::join_all(open_tasks).await;
+            thread.update(cx, |thread, cx| {
+                for (full_path, buffer) in results {
+                    let Some(buffer) = buffer.log_err() else {
+                        continue;
+                    };
+                    if let Some(rules) = thread
+                        .directory_rules
+                        .iter_mut()
+                        .find(|rules| rules.full_path == full_path)
+                    {
+                        rules.buffer = Some(buffer);
+                    }
+                }
+                cx.emit(ThreadEvent::DirectoryRulesChanged);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Runs the `on_stop` hooks, and shows their results in the thread once they finish.
+    fn run_stop_hooks(&mut self, stop_reason: StopReason, cx: &mut Context<Self>) {
+        let stop_reason = match stop_reason {
+            StopReason::EndTurn => "end_turn",
+            StopReason::MaxTokens => "max_tokens",
+            StopReason::Refusal => "refusal",
+            StopReason::ToolUse => return,
+        };
+        let hooks = AgentSettings::get_global(cx).hooks.on_stop.clone();
+        if hooks.is_empty() {
+            return;
+        }
+
+        let env =
+            HashMap::from_iter([("ZED_AGENT_STOP_REASON".to_string(), stop_reason.to_string())]);
+        let run_hooks = hooks::run_hooks(hooks, env, self.hook_runner.clone(), &self.project, cx);
+        cx.spawn(async move |thread, cx| {
+            let results = run_hooks.await;
+            if results.is_empty() {
+                return;
+            }
+            thread
+                .update(cx, |thread, cx| {
+                    let text = results
+                        .iter()
+                        .map(HookResult::to_markdown)
+                        .collect::<Vec<_>>()
+                        .join("\n\n");
+                    let id = thread.next_message_id.post_inc();
+                    thread.messages.push(Message {
+                        id,
+                        role: Role::System,
+                        segments: vec![MessageSegment::Text(text)],
+                        loaded_context: LoadedContext::default(),
+                        creases: Vec::new(),
+                        is_hidden: false,
+                        ui_only: true,
+                    });
+                    cx.emit(ThreadEvent::MessageAdded(id));
+                })
+                .ok();
+        })
+        .detach();
+    }
+
     fn tool_finished(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -2922,6 +3412,93 @@ impl Thread {
         Ok(String::from_utf8_lossy(&markdown).to_string())
     }
 
+    /// Renders the thread as a Markdown transcript for sharing, in which each tool call is a
+    /// collapsible section along with its result.
+    pub fn to_exported_markdown(&self, cx: &App) -> Result<String> {
+        let mut markdown = Vec::new();
+
+        let summary = self.summary().or_default();
+        writeln!(markdown, "# {summary}\n")?;
+
+        for message in self.messages() {
+            writeln!(
+                markdown,
+                "## {role}\n",
+                role = match message.role {
+                    Role::User => "User",
+                    Role::Assistant => "Agent",
+                    Role::System => "System",
+                }
+            )?;
+
+            if !message.loaded_context.text.is_empty() {
+                writeln!(markdown, "{}", message.loaded_context.text)?;
+            }
+
+            if !message.loaded_context.images.is_empty() {
+                writeln!(
+                    markdown,
+                    "\n{} images attached as context.\n",
+                    message.loaded_context.images.len()
+                )?;
+            }
+
+            for segment in &message.segments {
+                match segment {
+                    MessageSegment::Text(text) => writeln!(markdown, "{}\n", text)?,
+                    MessageSegment::Thinking { text, .. } => {
+                        writeln!(markdown, "<think>\n{}\n</think>\n", text)?
+                    }
+                    MessageSegment::RedactedThinking(_) => {}
+                }
+            }
+
+            for tool_use in self.tool_uses_for_message(message.id, cx) {
+                let tool_result = self.tool_result(&tool_use.id);
+                let is_error = tool_result.map_or(false, |result| result.is_error);
+
+                // Tool calls are collapsed so the transcript stays readable when rendered.
+                writeln!(markdown, "<details>")?;
+                writeln!(
+                    markdown,
+                    "<summary>Tool call: <code>{}</code> ({}){}</summary>\n",
+                    tool_use.name,
+                    tool_use.id,
+                    if is_error { " (Error)" } else { "" }
+                )?;
+                let input = serde_json::to_string_pretty(&tool_use.input)?;
+                let fence = code_fence(&input);
+                writeln!(markdown, "{fence}json\n{input}\n{fence}\n")?;
+
+                if let Some(tool_result) = tool_result {
+                    writeln!(markdown, "**Result:**\n")?;
+                    match &tool_result.content {
+                        LanguageModelToolResultContent::Text(text) => {
+                            let text = text.trim_end();
+                            let fence = code_fence(text);
+                            writeln!(markdown, "{fence}\n{text}\n{fence}\n")?;
+                        }
+                        LanguageModelToolResultContent::Image(image) => {
+                            writeln!(markdown, "![Image](data:base64,{})\n", image.source)?;
+                        }
+                    }
+
+                    if let Some(output) = tool_result.output.as_ref() {
+                        let output = serde_json::to_string_pretty(output)?;
+                        let fence = code_fence(&output);
+                        writeln!(markdown, "**Output:**\n\n{fence}json\n{output}\n{fence}\n")?;
+                    }
+                } else {
+                    writeln!(markdown, "*No result.*\n")?;
+                }
+
+                writeln!(markdown, "</details>\n")?;
+            }
+        }
+
+        Ok(String::from_utf8_lossy(&markdown).to_string())
+    }
+
     pub fn keep_edits_in_range(
         &mut self,
         buffer: Entity<language::Buffer>,
@@ -3005,6 +3582,39 @@ impl Thread {
         self.cumulative_token_usage
     }
 
+    /// The tokens used by the sub-agents this thread delegated tasks to.
+    pub fn delegated_token_usage(&self) -> TokenUsage {
+        self.delegated_token_usage
+    }
+
+    pub(crate) fn add_delegated_token_usage(
+        &mut self,
+        usage: TokenUsage,
+        cost: ThreadCost,
+        cx: &mut Context<Self>,
+    ) {
+        self.delegated_token_usage = self.delegated_token_usage + usage;
+        self.cumulative_token_usage = self.cumulative_token_usage + usage;
+        self.cost = self.cost + cost;
+        cx.notify();
+    }
+
+    /// The estimated cost of the thread, including the one of its sub-agents.
+    pub fn cost(&self) -> ThreadCost {
+        self.cost
+    }
+
+    fn add_request_cost(&mut self, model: &Arc<dyn LanguageModel>, usage: TokenUsage) {
+        if usage.total_tokens() == 0 {
+            return;
+        }
+
+        match model.pricing() {
+            Some(pricing) => self.cost.amount += pricing.cost(&usage),
+            None => self.cost.has_unpriced_requests = true,
+        }
+    }
+
     pub fn token_usage_up_to_message(&self, message_id: MessageId) -> TotalTokenUsage {
         let Some(model) = self.configured_model.as_ref() else {
             return TotalTokenUsage::default();
@@ -3018,8 +3628,13 @@ impl Thread {
             .position(|msg| msg.id == message_id)
             .unwrap_or(0);
 
+        let delegated = self.delegated_token_usage.total_tokens();
         if index == 0 {
-            return TotalTokenUsage { total: 0, max };
+            return TotalTokenUsage {
+                total: 0,
+                max,
+                delegated,
+            };
         }
 
         let token_usage = &self
@@ -3031,6 +3646,7 @@ impl Thread {
         TotalTokenUsage {
             total: token_usage.total_tokens(),
             max,
+            delegated,
         }
     }
 
@@ -3038,12 +3654,14 @@ impl Thread {
         let model = self.configured_model.as_ref()?;
 
         let max = model.model.max_token_count();
+        let delegated = self.delegated_token_usage.total_tokens();
 
         if let Some(exceeded_error) = &self.exceeded_window_error {
             if model.model.id() == exceeded_error.model_id {
                 return Some(TotalTokenUsage {
                     total: exceeded_error.token_count,
                     max,
+                    delegated,
                 });
             }
         }
@@ -3053,7 +3671,11 @@ impl Thread {
             .unwrap_or_default()
             .total_tokens();
 
-        Some(TotalTokenUsage { total, max })
+        Some(TotalTokenUsage {
+            total,
+            max,
+            delegated,
+        })
     }
 
     fn token_usage_at_last_message(&self) -> Option<TokenUsage> {
@@ -3164,6 +3786,7 @@ pub enum ThreadEvent {
     CancelEditing,
     CompletionCanceled,
     ProfileChanged,
+    DirectoryRulesChanged,
     RetriesFailed {
         message: SharedString,
     },
@@ -3177,6 +3800,44 @@ struct PendingCompletion {
     _task: Task<()>,
 }
 
+/// Appends the results of `after_edit` hooks to a tool's output, so that the model sees them.
+fn append_hook_results(mut output: ToolResultOutput, results: &[HookResult]) -> ToolResultOutput {
+    if let ToolResultContent::Text(text) = &mut output.content {
+        for result in results {
+            text.push_str("\n\n");
+            text.push_str(&result.to_markdown());
+        }
+    }
+    output
+}
+
+/// The contents of a rules file in a subdirectory, which is appended to the output of the tool
+/// that first touched a file under that directory.
+struct LoadedDirectoryRules {
+    directory: Arc<Path>,
+    full_path: Arc<Path>,
+    text: String,
+}
+
+/// Appends rules files that apply to the files a tool read or edited to its output, so that the
+/// model follows them from then on.
+fn append_directory_rules(
+    mut output: ToolResultOutput,
+    rules: &[LoadedDirectoryRules],
+) -> ToolResultOutput {
+    if let ToolResultContent::Text(text) = &mut output.content {
+        for rules in rules {
+            text.push_str(&format!(
+                "\n\nThere are project rules that apply to files in `{}`:\n`{}`:\n``````\n{}\n``````",
+                rules.directory.display(),
+                rules.full_path.display(),
+                rules.text,
+            ));
+        }
+    }
+    output
+}
+
 /// Resolves tool name conflicts by ensuring all tool names are unique.
 ///
 /// When multiple tools have the same name, this function applies the following rules:
@@ -3256,6 +3917,17 @@ fn resolve_tool_name_conflicts(tools: &[Arc<dyn Tool>]) -> Vec<(String, Arc<dyn
         .collect()
 }
 
+/// Returns a Markdown code fence that is longer than any run of backticks in `text`, so that the
+/// text can't end the code block early.
+fn code_fence(text: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat((longest_run + 1).max(3))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3265,23 +3937,28 @@ mod tests {
 
     // Test-specific constants
     const TEST_RATE_LIMIT_RETRY_SECS: u64 = 30;
-    use agent_settings::{AgentProfileId, AgentSettings, LanguageModelParameters};
-    use assistant_tool::ToolRegistry;
+    use agent_settings::{
+        AgentHook, AgentHooks, AgentProfileId, AgentSettings, LanguageModelParameters,
+    };
+    use assistant_tool::{SubagentRunner, SubagentTask, ToolRegistry};
     use futures::StreamExt;
     use futures::future::BoxFuture;
     use futures::stream::BoxStream;
     use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
     use icons::IconName;
     use language_model::fake_provider::{FakeLanguageModel, FakeLanguageModelProvider};
     use language_model::{
-        LanguageModelCompletionError, LanguageModelName, LanguageModelProviderId,
-        LanguageModelProviderName, LanguageModelToolChoice,
+        LanguageModelCompletionError, LanguageModelName, LanguageModelPricing,
+        LanguageModelProviderId, LanguageModelProviderName, LanguageModelToolChoice,
+        LanguageModelToolUse,
     };
     use parking_lot::Mutex;
-    use project::{FakeFs, Project};
+    use project::{FakeFs, Project, WorktreeId};
     use prompt_store::PromptBuilder;
     use serde_json::json;
     use settings::{Settings, SettingsStore};
+    use std::path::{Path, PathBuf};
     use std::sync::Arc;
     use std::time::Duration;
     use theme::ThemeSettings;
@@ -3685,6 +4362,7 @@ fn main() {{
                         provider: Some(model.provider_id().0.to_string().into()),
                         model: Some(model.id().0.clone()),
                         temperature: Some(0.66),
+                        edit_format: None,
                     }],
                     ..AgentSettings::get_global(cx).clone()
                 },
@@ -3705,6 +4383,7 @@ fn main() {{
                         provider: None,
                         model: Some(model.id().0.clone()),
                         temperature: Some(0.66),
+                        edit_format: None,
                     }],
                     ..AgentSettings::get_global(cx).clone()
                 },
@@ -3725,6 +4404,7 @@ fn main() {{
                         provider: Some(model.provider_id().0.to_string().into()),
                         model: None,
                         temperature: Some(0.66),
+                        edit_format: None,
                     }],
                     ..AgentSettings::get_global(cx).clone()
                 },
@@ -3745,6 +4425,7 @@ fn main() {{
                         provider: Some("anthropic".into()),
                         model: Some(model.id().0.clone()),
                         temperature: Some(0.66),
+                        edit_format: None,
                     }],
                     ..AgentSettings::get_global(cx).clone()
                 },
@@ -5270,6 +5951,792 @@ fn main() {{
         });
     }
 
+    #[gpui::test]
+    async fn test_delegated_task(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        cx.update(subagent::init);
+
+        let parent_thread_id = thread.read_with(cx, |thread, _| thread.id().to_string());
+        let report = cx.update(|cx| {
+            let runner = <dyn SubagentRunner>::try_global(cx).unwrap();
+            runner.run_subagent(
+                SubagentTask {
+                    parent_thread_id,
+                    instructions: "Find the main function".to_string(),
+                },
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        // The sub-agent works in a thread of its own.
+        let fake_model = model.as_fake();
+        let pending = fake_model.pending_completions();
+        assert_eq!(pending.len(), 1);
+        let instructions = pending[0].messages.last().unwrap().string_contents();
+        assert!(instructions.starts_with("Find the main function"));
+
+        fake_model.stream_last_completion_response("It's in `src/main.rs:1`.");
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        assert_eq!(report.await.unwrap(), "It's in `src/main.rs:1`.");
+        thread.read_with(cx, |thread, _| {
+            assert!(thread.is_empty());
+            assert_eq!(thread.delegated_token_usage(), TokenUsage::default());
+            assert_eq!(thread.cost(), ThreadCost::default());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_concurrent_delegated_tasks(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        cx.update(subagent::init);
+
+        let parent_thread_id = thread.read_with(cx, |thread, _| thread.id().to_string());
+        let [main_report, tests_report] =
+            ["Find the main function", "Find the tests"].map(|instructions| {
+                let parent_thread_id = parent_thread_id.clone();
+                cx.update(|cx| {
+                    let runner = <dyn SubagentRunner>::try_global(cx).unwrap();
+                    runner.run_subagent(
+                        SubagentTask {
+                            parent_thread_id,
+                            instructions: instructions.to_string(),
+                        },
+                        cx,
+                    )
+                })
+            });
+        cx.run_until_parked();
+
+        // Both sub-agents work at the same time, each in a thread of its own.
+        let fake_model = model.as_fake();
+        let pending = fake_model.pending_completions();
+        assert_eq!(pending.len(), 2);
+        let request_for = |instructions: &str| {
+            pending
+                .iter()
+                .find(|request| {
+                    request
+                        .messages
+                        .last()
+                        .unwrap()
+                        .string_contents()
+                        .starts_with(instructions)
+                })
+                .unwrap()
+        };
+        let main_request = request_for("Find the main function");
+        let tests_request = request_for("Find the tests");
+
+        let main_usage = TokenUsage {
+            input_tokens: 1000,
+            output_tokens: 100,
+            ..Default::default()
+        };
+        let tests_usage = TokenUsage {
+            input_tokens: 2000,
+            output_tokens: 200,
+            ..Default::default()
+        };
+        fake_model.send_completion_stream_event(
+            main_request,
+            LanguageModelCompletionEvent::UsageUpdate(main_usage),
+        );
+        fake_model.send_completion_stream_event(
+            tests_request,
+            LanguageModelCompletionEvent::UsageUpdate(tests_usage),
+        );
+        fake_model.stream_completion_response(tests_request, "They're in `tests/`.");
+        fake_model.stream_completion_response(main_request, "It's in `src/main.rs:1`.");
+        fake_model.end_completion_stream(tests_request);
+        cx.run_until_parked();
+        fake_model.end_completion_stream(main_request);
+        cx.run_until_parked();
+
+        assert_eq!(tests_report.await.unwrap(), "They're in `tests/`.");
+        assert_eq!(main_report.await.unwrap(), "It's in `src/main.rs:1`.");
+
+        // The parent is charged for the work of both sub-agents.
+        thread.read_with(cx, |thread, _| {
+            assert!(thread.is_empty());
+            assert_eq!(thread.delegated_token_usage(), main_usage + tests_usage);
+            assert_eq!(thread.cumulative_token_usage(), main_usage + tests_usage);
+            assert!(thread.cost().has_unpriced_requests);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_cost(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        cx.update(subagent::init);
+
+        let fake_model = model.as_fake();
+        fake_model.set_pricing(Some(LanguageModelPricing {
+            input: 3.,
+            output: 15.,
+            cache_creation_input: None,
+            cache_read_input: Some(0.3),
+        }));
+        let end_pending_completions = |cx: &mut TestAppContext| {
+            for request in fake_model.pending_completions() {
+                fake_model.end_completion_stream(&request);
+            }
+            cx.run_until_parked();
+        };
+
+        // Usage updates report the usage of the request so far, which may decrease.
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Hello!", ContextLoadResult::default(), None, vec![], cx);
+            thread.send_to_model(model.clone(), CompletionIntent::UserPrompt, None, cx);
+        });
+        cx.run_until_parked();
+        for usage in [
+            TokenUsage {
+                input_tokens: 1000,
+                output_tokens: 10,
+                ..Default::default()
+            },
+            TokenUsage {
+                input_tokens: 1000,
+                output_tokens: 100,
+                ..Default::default()
+            },
+            TokenUsage {
+                input_tokens: 900,
+                output_tokens: 100,
+                ..Default::default()
+            },
+            TokenUsage {
+                input_tokens: 1000,
+                output_tokens: 100,
+                ..Default::default()
+            },
+        ] {
+            fake_model.send_last_completion_stream_event(
+                LanguageModelCompletionEvent::UsageUpdate(usage),
+            );
+        }
+        fake_model.stream_last_completion_response("Hi!");
+        end_pending_completions(cx);
+
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Thanks!", ContextLoadResult::default(), None, vec![], cx);
+            thread.send_to_model(model.clone(), CompletionIntent::UserPrompt, None, cx);
+        });
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            TokenUsage {
+                input_tokens: 2000,
+                output_tokens: 50,
+                cache_read_input_tokens: 1000,
+                ..Default::default()
+            },
+        ));
+        fake_model.stream_last_completion_response("You're welcome!");
+        end_pending_completions(cx);
+
+        // The cost of sub-agents is added to the one of the thread that delegated to them.
+        let parent_thread_id = thread.read_with(cx, |thread, _| thread.id().to_string());
+        let report = cx.update(|cx| {
+            let runner = <dyn SubagentRunner>::try_global(cx).unwrap();
+            runner.run_subagent(
+                SubagentTask {
+                    parent_thread_id,
+                    instructions: "Find the main function".to_string(),
+                },
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            TokenUsage {
+                input_tokens: 500,
+                output_tokens: 20,
+                ..Default::default()
+            },
+        ));
+        fake_model.stream_last_completion_response("It's in `src/main.rs:1`.");
+        end_pending_completions(cx);
+        assert_eq!(report.await.unwrap(), "It's in `src/main.rs:1`.");
+
+        thread.read_with(cx, |thread, _| {
+            let cost = thread.cost();
+            let expected_amount = (1000. * 3. + 100. * 15.)
+                + (2000. * 3. + 50. * 15. + 1000. * 0.3)
+                + (500. * 3. + 20. * 15.);
+            assert!((cost.amount - expected_amount / 1_000_000.).abs() < 1e-12);
+            assert!(!cost.has_unpriced_requests);
+            assert_eq!(
+                thread.cumulative_token_usage(),
+                TokenUsage {
+                    input_tokens: 3500,
+                    output_tokens: 170,
+                    cache_creation_input_tokens: 0,
+                    cache_read_input_tokens: 1000,
+                }
+            );
+        });
+
+        // Requests to models without pricing are pointed out.
+        fake_model.set_pricing(None);
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Bye!", ContextLoadResult::default(), None, vec![], cx);
+            thread.send_to_model(model.clone(), CompletionIntent::UserPrompt, None, cx);
+        });
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            TokenUsage {
+                input_tokens: 3000,
+                output_tokens: 10,
+                ..Default::default()
+            },
+        ));
+        end_pending_completions(cx);
+        thread.read_with(cx, |thread, _| {
+            assert!(thread.cost().has_unpriced_requests);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_subagent_tools(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+        cx.update(|cx| assistant_tools::init(FakeHttpClient::with_404_response(), cx));
+
+        let project = create_test_project(cx, json!({})).await;
+        let (_, _thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+        let child = thread.update(cx, |thread, cx| thread.new_subagent(cx));
+
+        cx.read(|cx| {
+            let tool_names = |thread: &Entity<Thread>| {
+                thread
+                    .read(cx)
+                    .enabled_tools(cx)
+                    .iter()
+                    .map(|tool| tool.name())
+                    .collect::<Vec<_>>()
+            };
+            let parent_tools = tool_names(&thread);
+            let child_tools = tool_names(&child);
+
+            // Sub-agents can't edit the project, run tools that need confirmation, or delegate
+            // tasks of their own, whatever the profile allows.
+            for tool in ["edit_file", "rename_symbol", "terminal", "delegate_task"] {
+                assert!(parent_tools.iter().any(|name| name == tool), "{tool}");
+                assert!(!child_tools.iter().any(|name| name == tool), "{tool}");
+            }
+            for tool in ["read_file", "grep", "go_to_definition"] {
+                assert!(child_tools.iter().any(|name| name == tool), "{tool}");
+            }
+            assert!(
+                child
+                    .read(cx)
+                    .enabled_tools(cx)
+                    .iter()
+                    .all(|tool| tool.is_read_only() && !tool.may_perform_edits())
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_before_tool_call_hook_blocks_tool_call(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        let hook_runner = Arc::new(crate::hooks::FakeHookRunner::default());
+        hook_runner.set_output(
+            "./check-tool-call",
+            false,
+            "Editing is not allowed right now",
+        );
+        thread.update(cx, |thread, _| thread.set_hook_runner(hook_runner.clone()));
+        set_hooks(
+            AgentHooks {
+                before_tool_call: vec![
+                    AgentHook {
+                        command: "./check-tool-call".to_string(),
+                        tools: Some(vec!["test_edit".to_string()]),
+                        timeout: None,
+                    },
+                    AgentHook {
+                        command: "./check-other-tool-call".to_string(),
+                        tools: Some(vec!["terminal".to_string()]),
+                        timeout: None,
+                    },
+                ],
+                ..AgentHooks::default()
+            },
+            cx,
+        );
+
+        let tool_use_id = use_test_edit_tool(&thread, &model, "code.rs", cx);
+        cx.run_until_parked();
+
+        let commands = hook_runner.commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].command, "./check-tool-call");
+        assert_eq!(commands[0].cwd.as_path(), Path::new(path!("/test")));
+        assert_eq!(commands[0].timeout, std::time::Duration::from_secs(60));
+        assert_eq!(commands[0].ssh_command, None);
+        assert_eq!(commands[0].env["ZED_AGENT_TOOL_NAME"], "test_edit");
+        assert_eq!(
+            commands[0].env["ZED_AGENT_TOOL_INPUT"],
+            r#"{"path":"code.rs"}"#
+        );
+
+        thread.read_with(cx, |thread, _| {
+            let result = thread.tool_result(&tool_use_id).unwrap();
+            assert!(result.is_error);
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert!(output.contains("Editing is not allowed right now"));
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                let path = project.find_project_path("test/code.rs", cx).unwrap();
+                project.open_buffer(path, cx)
+            })
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "fn main() {}"));
+    }
+
+    #[gpui::test]
+    async fn test_after_edit_hooks(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        let hook_runner = Arc::new(crate::hooks::FakeHookRunner::default());
+        hook_runner.set_output("cargo fmt", true, "Formatted code.rs");
+        thread.update(cx, |thread, _| thread.set_hook_runner(hook_runner.clone()));
+        set_hooks(
+            AgentHooks {
+                after_edit: vec![AgentHook {
+                    command: "cargo fmt".to_string(),
+                    tools: None,
+                    timeout: None,
+                }],
+                ..AgentHooks::default()
+            },
+            cx,
+        );
+
+        let tool_use_id = use_test_edit_tool(&thread, &model, "code.rs", cx);
+        cx.run_until_parked();
+
+        let commands = hook_runner.commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(
+            commands[0].env["ZED_AGENT_EDITED_FILES"],
+            path!("/test/code.rs")
+        );
+
+        // The hook's output is given to the model along with the tool's result.
+        thread.read_with(cx, |thread, _| {
+            let result = thread.tool_result(&tool_use_id).unwrap();
+            assert!(!result.is_error);
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert!(output.starts_with("Edited code.rs"));
+            assert!(output.contains("Formatted code.rs"));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_after_edit_hooks_for_remote_files(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, _thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+        let hook_runner = Arc::new(crate::hooks::FakeHookRunner::default());
+        thread.update(cx, |thread, _| thread.set_hook_runner(hook_runner.clone()));
+        set_hooks(
+            AgentHooks {
+                after_edit: vec![AgentHook {
+                    command: "cargo fmt".to_string(),
+                    tools: None,
+                    timeout: None,
+                }],
+                ..AgentHooks::default()
+            },
+            cx,
+        );
+
+        // Files in remote projects aren't local, so their paths come from their worktree.
+        let buffer = project
+            .update(cx, |project, cx| {
+                let path = project.find_project_path("test/code.rs", cx).unwrap();
+                project.open_buffer(path, cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| {
+            let file = buffer.file().unwrap();
+            let remote_file = RemoteFile {
+                worktree_id: file.worktree_id(cx),
+                path: file.path().clone(),
+            };
+            buffer.file_updated(Arc::new(remote_file), cx);
+        });
+        let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone());
+        action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+
+        let hooks = thread
+            .update(cx, |thread, cx| {
+                thread.run_after_edit_hooks("test_edit", cx)
+            })
+            .unwrap();
+        hooks.await;
+        let commands = hook_runner.commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(
+            commands[0].env["ZED_AGENT_EDITED_FILES"],
+            path!("/test/code.rs")
+        );
+    }
+
+    /// A file that isn't on this machine, like the files of a remote project.
+    struct RemoteFile {
+        worktree_id: WorktreeId,
+        path: Arc<Path>,
+    }
+
+    impl language::File for RemoteFile {
+        fn as_local(&self) -> Option<&dyn language::LocalFile> {
+            None
+        }
+
+        fn disk_state(&self) -> language::DiskState {
+            language::DiskState::New
+        }
+
+        fn path(&self) -> &Arc<Path> {
+            &self.path
+        }
+
+        fn full_path(&self, _: &App) -> PathBuf {
+            self.path.to_path_buf()
+        }
+
+        fn file_name<'a>(&'a self, _: &'a App) -> &'a std::ffi::OsStr {
+            self.path.file_name().unwrap()
+        }
+
+        fn worktree_id(&self, _: &App) -> WorktreeId {
+            self.worktree_id
+        }
+
+        fn to_proto(&self, _: &App) -> proto::File {
+            unimplemented!()
+        }
+
+        fn is_private(&self) -> bool {
+            false
+        }
+    }
+
+    #[gpui::test]
+    async fn test_stop_hooks(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        let hook_runner = Arc::new(crate::hooks::FakeHookRunner::default());
+        hook_runner.set_output("./notify", true, "Notified");
+        thread.update(cx, |thread, _| thread.set_hook_runner(hook_runner.clone()));
+        set_hooks(
+            AgentHooks {
+                on_stop: vec![AgentHook {
+                    command: "./notify".to_string(),
+                    tools: None,
+                    timeout: None,
+                }],
+                ..AgentHooks::default()
+            },
+            cx,
+        );
+
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Hello", ContextLoadResult::default(), None, vec![], cx);
+            thread.send_to_model(model.clone(), CompletionIntent::UserPrompt, None, cx);
+        });
+        simulate_successful_response(model.as_fake(), cx);
+
+        let commands = hook_runner.commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].env["ZED_AGENT_STOP_REASON"], "end_turn");
+
+        // The hook's output is shown in the thread, but isn't sent to the model.
+        thread.read_with(cx, |thread, _| {
+            let message = thread.messages().last().unwrap();
+            assert_eq!(message.role, Role::System);
+            assert!(message.ui_only);
+            assert!(message.to_string().contains("Notified"));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_directory_rules(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(
+            cx,
+            json!({
+                "AGENTS.md": "Write tests.",
+                "code.rs": "fn main() {}",
+                "services": {
+                    "billing": {
+                        "AGENTS.md": "Store amounts in cents.",
+                        "src": {
+                            "invoice.rs": "",
+                            "lib.rs": "",
+                        },
+                    },
+                },
+            }),
+        )
+        .await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        set_hooks(AgentHooks::default(), cx);
+
+        // The rules file at the root of the worktree is part of the system prompt instead.
+        let tool_use_id = use_test_edit_tool(&thread, &model, "code.rs", cx);
+        simulate_successful_response(model.as_fake(), cx);
+        thread.read_with(cx, |thread, _| {
+            assert!(thread.directory_rules().is_empty());
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert!(!output.contains("Write tests."));
+        });
+
+        let tool_use_id = use_test_edit_tool(&thread, &model, "services/billing/src/lib.rs", cx);
+        simulate_successful_response(model.as_fake(), cx);
+        let rules_path = Path::new(path!("test/services/billing/AGENTS.md"));
+        thread.read_with(cx, |thread, _| {
+            let directory_rules = thread.directory_rules();
+            assert_eq!(directory_rules.len(), 1);
+            assert_eq!(directory_rules[0].full_path.as_ref(), rules_path);
+            assert!(directory_rules[0].buffer.is_some());
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert!(output.starts_with("Edited services/billing/src/lib.rs"));
+            assert!(output.contains("Store amounts in cents."));
+        });
+
+        // The rules are only added the first time a file under their directory is touched.
+        let tool_use_id =
+            use_test_edit_tool(&thread, &model, "services/billing/src/invoice.rs", cx);
+        simulate_successful_response(model.as_fake(), cx);
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.directory_rules().len(), 1);
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert!(!output.contains("Store amounts in cents."));
+        });
+
+        let serialized = thread
+            .update(cx, |thread, cx| thread.serialize(cx))
+            .await
+            .unwrap();
+        assert_eq!(serialized.directory_rules, vec![Arc::from(rules_path)]);
+    }
+
+    #[gpui::test]
+    async fn test_directory_rules_after_failed_tool(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(
+            cx,
+            json!({
+                "services": {
+                    "billing": {
+                        "AGENTS.md": "Store amounts in cents.",
+                        "src": { "lib.rs": "" },
+                    },
+                    "payroll": {
+                        "AGENTS.md": "Pay on Fridays.",
+                    },
+                },
+            }),
+        )
+        .await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        set_hooks(AgentHooks::default(), cx);
+
+        // The rules aren't marked as added when the tool fails.
+        let tool_use_id = use_test_edit_tool_with_input(
+            &thread,
+            &model,
+            json!({ "path": "services/billing/src/lib.rs", "fail": true }),
+            cx,
+        );
+        simulate_successful_response(model.as_fake(), cx);
+        thread.read_with(cx, |thread, _| {
+            assert!(thread.directory_rules().is_empty());
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert!(output.contains("Failed to edit services/billing/src/lib.rs"));
+        });
+
+        // So they're added by the next tool that succeeds.
+        let tool_use_id = use_test_edit_tool(&thread, &model, "services/billing/src/lib.rs", cx);
+        simulate_successful_response(model.as_fake(), cx);
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.directory_rules().len(), 1);
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert!(output.contains("Store amounts in cents."));
+        });
+
+        // A rules file the agent touches directly isn't added on top of it.
+        let tool_use_id = use_test_edit_tool(&thread, &model, "services/payroll/AGENTS.md", cx);
+        simulate_successful_response(model.as_fake(), cx);
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.directory_rules().len(), 1);
+            let output = thread.output_for_tool(&tool_use_id).unwrap();
+            assert_eq!(output.as_ref(), "Edited services/payroll/AGENTS.md");
+        });
+    }
+
+    /// Configures the given hooks, and enables [`TestEditTool`] in the default profile.
+    fn set_hooks(hooks: AgentHooks, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            ToolRegistry::global(cx).register_tool(TestEditTool);
+            let mut settings = AgentSettings::get_global(cx).clone();
+            if let Some(profile) = settings.profiles.get_mut(&settings.default_profile) {
+                profile.tools.insert("test_edit".into(), true);
+            }
+            settings.hooks = hooks;
+            AgentSettings::override_global(settings, cx);
+        });
+    }
+
+    /// Makes the thread use [`TestEditTool`] on the given path, as if the model had asked for it.
+    fn use_test_edit_tool(
+        thread: &Entity<Thread>,
+        model: &Arc<dyn LanguageModel>,
+        path: &str,
+        cx: &mut TestAppContext,
+    ) -> LanguageModelToolUseId {
+        use_test_edit_tool_with_input(thread, model, json!({ "path": path }), cx)
+    }
+
+    fn use_test_edit_tool_with_input(
+        thread: &Entity<Thread>,
+        model: &Arc<dyn LanguageModel>,
+        input: serde_json::Value,
+        cx: &mut TestAppContext,
+    ) -> LanguageModelToolUseId {
+        let path = input["path"].as_str().unwrap_or_default().to_string();
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message(
+                format!("Edit {path}"),
+                ContextLoadResult::default(),
+                None,
+                vec![],
+                cx,
+            );
+            let message_id = thread.insert_assistant_message(
+                vec![MessageSegment::Text(format!("Editing {path}"))],
+                cx,
+            );
+            let tool_use_id = LanguageModelToolUseId::from(format!("test_edit_{}", message_id.0));
+            let metadata = ToolUseMetadata {
+                model: model.clone(),
+                thread_id: thread.id.clone(),
+                prompt_id: thread.last_prompt_id.clone(),
+            };
+            thread.tool_use.request_tool_use(
+                message_id,
+                LanguageModelToolUse {
+                    id: tool_use_id.clone(),
+                    name: "test_edit".into(),
+                    raw_input: input.to_string(),
+                    input,
+                    is_input_complete: true,
+                },
+                metadata,
+                cx,
+            );
+            thread.use_pending_tools(None, model.clone(), cx);
+            tool_use_id
+        })
+    }
+
+    /// Replaces the contents of the file at the given path.
+    struct TestEditTool;
+
+    impl Tool for TestEditTool {
+        fn name(&self) -> String {
+            "test_edit".to_string()
+        }
+
+        fn icon(&self) -> IconName {
+            IconName::Pencil
+        }
+
+        fn may_perform_edits(&self) -> bool {
+            true
+        }
+
+        fn needs_confirmation(&self, _input: &serde_json::Value, _cx: &App) -> bool {
+            false
+        }
+
+        fn description(&self) -> String {
+            "Test edit tool".to_string()
+        }
+
+        fn ui_text(&self, input: &serde_json::Value) -> String {
+            format!("Edit {}", input["path"].as_str().unwrap_or_default())
+        }
+
+        fn run(
+            self: Arc<Self>,
+            input: serde_json::Value,
+            _request: Arc<LanguageModelRequest>,
+            project: Entity<Project>,
+            action_log: Entity<ActionLog>,
+            _model: Arc<dyn LanguageModel>,
+            _window: Option<AnyWindowHandle>,
+            cx: &mut App,
+        ) -> assistant_tool::ToolResult {
+            let path = input["path"].as_str().unwrap_or_default().to_string();
+            let buffer = project.update(cx, |project, cx| {
+                let project_path = project
+                    .find_project_path(Path::new("test").join(&path), cx)
+                    .unwrap();
+                project.open_buffer(project_path, cx)
+            });
+            cx.spawn(async move |cx| {
+                let buffer = buffer.await?;
+                action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx))?;
+                if input["fail"].as_bool().unwrap_or(false) {
+                    anyhow::bail!("Failed to edit {path}");
+                }
+                buffer.update(cx, |buffer, cx| buffer.set_text("fn main() {}\n", cx))?;
+                action_log.update(cx, |log, cx| log.buffer_edited(buffer, cx))?;
+                Ok(format!("Edited {path}").into())
+            })
+            .into()
+        }
+    }
+
     fn simulate_successful_response(fake_model: &FakeLanguageModel, cx: &mut TestAppContext) {
         cx.run_until_parked();
         fake_model.stream_last_completion_response("Assistant response");
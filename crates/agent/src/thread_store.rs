@@ -1,7 +1,8 @@
 use crate::{
     context_server_tool::ContextServerTool,
     thread::{
-        DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadId,
+        DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread,
+        ThreadBranchPoint, ThreadCost, ThreadId,
     },
 };
 use agent_settings::{AgentProfileId, CompletionMode};
@@ -20,6 +21,7 @@ use gpui::{
     Subscription, Task, Window, prelude::*,
 };
 use indoc::indoc;
+use language::Buffer;
 use language_model::{LanguageModelToolResultContent, LanguageModelToolUseId, Role, TokenUsage};
 use project::context_server_store::{ContextServerStatus, ContextServerStore};
 use project::{Project, ProjectItem, ProjectPath, Worktree};
@@ -29,7 +31,7 @@ use prompt_store::{
 };
 use serde::{Deserialize, Serialize};
 use sqlez::{
-    bindable::{Bind, Column},
+    bindable::{Bind, Column, StaticColumnCount},
     connection::Connection,
     statement::Statement,
 };
@@ -87,6 +89,51 @@ pub fn init(cx: &mut App) {
     ThreadsDatabase::init(cx);
 }
 
+/// Returns the path of the rules file in the given directory of the worktree, if there is one.
+pub(crate) fn find_rules_file(worktree: &Worktree, directory: &Path) -> Option<Arc<Path>> {
+    // Note that Cline supports `.clinerules` being a directory, but that is not currently
+    // supported. This doesn't seem to occur often in GitHub repositories.
+    RULES_FILE_NAMES.into_iter().find_map(|name| {
+        worktree
+            .entry_for_path(directory.join(name))
+            .filter(|entry| entry.is_file())
+            .map(|entry| entry.path.clone())
+    })
+}
+
+/// Opens a rules file, returning its buffer along with its contents.
+pub(crate) fn load_rules_file(
+    project_path: ProjectPath,
+    project: &Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<(Entity<Buffer>, RulesFileContext)>> {
+    let path_in_worktree = project_path.path.clone();
+    let buffer_task = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let rope_task = cx.spawn(async move |cx| {
+        let buffer = buffer_task.await?;
+        buffer.read_with(cx, |buffer_ref, cx| {
+            let project_entry_id = buffer_ref.entry_id(cx).context("buffer has no file")?;
+            anyhow::Ok((
+                buffer.clone(),
+                project_entry_id,
+                buffer_ref.as_rope().clone(),
+            ))
+        })?
+    });
+    // Build a string from the rope on a background thread.
+    cx.background_spawn(async move {
+        let (buffer, project_entry_id, rope) = rope_task.await?;
+        anyhow::Ok((
+            buffer,
+            RulesFileContext {
+                path_in_worktree,
+                text: rope.to_string().trim().to_string(),
+                project_entry_id: project_entry_id.to_usize(),
+            },
+        ))
+    })
+}
+
 /// A system prompt shared by all threads created by this ThreadStore
 #[derive(Clone, Default)]
 pub struct SharedProjectContext(Rc<RefCell<Option<ProjectContext>>>);
@@ -345,41 +392,16 @@ impl ThreadStore {
     ) -> Option<Task<Result<RulesFileContext>>> {
         let worktree = worktree.read(cx);
         let worktree_id = worktree.id();
-        let selected_rules_file = RULES_FILE_NAMES
-            .into_iter()
-            .filter_map(|name| {
-                worktree
-                    .entry_for_path(name)
-                    .filter(|entry| entry.is_file())
-                    .map(|entry| entry.path.clone())
-            })
-            .next();
-
-        // Note that Cline supports `.clinerules` being a directory, but that is not currently
-        // supported. This doesn't seem to occur often in GitHub repositories.
-        selected_rules_file.map(|path_in_worktree| {
-            let project_path = ProjectPath {
-                worktree_id,
-                path: path_in_worktree.clone(),
-            };
-            let buffer_task =
-                project.update(cx, |project, cx| project.open_buffer(project_path, cx));
-            let rope_task = cx.spawn(async move |cx| {
-                buffer_task.await?.read_with(cx, |buffer, cx| {
-                    let project_entry_id = buffer.entry_id(cx).context("buffer has no file")?;
-                    anyhow::Ok((project_entry_id, buffer.as_rope().clone()))
-                })?
-            });
-            // Build a string from the rope on a background thread.
-            cx.background_spawn(async move {
-                let (project_entry_id, rope) = rope_task.await?;
-                anyhow::Ok(RulesFileContext {
-                    path_in_worktree,
-                    text: rope.to_string().trim().to_string(),
-                    project_entry_id: project_entry_id.to_usize(),
-                })
-            })
-        })
+        let path_in_worktree = find_rules_file(worktree, Path::new(""))?;
+        let project_path = ProjectPath {
+            worktree_id,
+            path: path_in_worktree,
+        };
+        let load_task = load_rules_file(project_path, &project, cx);
+        Some(cx.background_spawn(async move {
+            let (_, rules_file) = load_task.await?;
+            Ok(rules_file)
+        }))
     }
 
     pub fn prompt_store(&self) -> &Option<Entity<PromptStore>> {
@@ -431,6 +453,71 @@ impl ThreadStore {
         })
     }
 
+    /// Creates and saves a new thread containing `thread`'s messages up to and including
+    /// `message_id`, so that the conversation can continue in a different direction without
+    /// losing the rest of the original thread.
+    pub fn branch_thread(
+        &mut self,
+        thread: &Entity<Thread>,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Thread>>> {
+        let parent_id = thread.read(cx).id().clone();
+        let serialized = thread.update(cx, |thread, cx| thread.serialize(cx));
+        cx.spawn(async move |this, cx| {
+            let mut serialized = serialized.await?;
+            let message_ix = serialized
+                .messages
+                .iter()
+                .position(|message| message.id == message_id)
+                .with_context(|| format!("no message {message_id:?} in thread {parent_id}"))?;
+            serialized.messages.truncate(message_ix + 1);
+            serialized.request_token_usage.truncate(message_ix + 1);
+            // The requests were made, and paid for, by the thread it's branched from.
+            serialized.cumulative_token_usage = TokenUsage::default();
+            serialized.delegated_token_usage = TokenUsage::default();
+            serialized.cost = ThreadCost::default();
+            serialized.updated_at = Utc::now();
+            serialized.detailed_summary_state = DetailedSummaryState::default();
+            serialized.exceeded_window_error = None;
+            serialized.tool_use_limit_reached = false;
+            // Rules files may have been added after the branch point, so let them be added again.
+            serialized.directory_rules.clear();
+            serialized.branched_from = Some(ThreadBranchPoint {
+                thread_id: parent_id,
+                message_id,
+            });
+
+            let thread = this.update(cx, |this, cx| {
+                this.create_thread_from_serialized(serialized, cx)
+            })?;
+            this.update(cx, |this, cx| this.save_thread(&thread, cx))?
+                .await?;
+            Ok(thread)
+        })
+    }
+
+    /// Saves a thread exported from another history, such as a shared JSON transcript,
+    /// under a new ID.
+    pub fn import_thread(
+        &mut self,
+        mut serialized: SerializedThread,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ThreadId>> {
+        // The thread it was branched from only exists in the history it was exported from.
+        serialized.branched_from = None;
+
+        let id = ThreadId::new();
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.save_thread(id.clone(), serialized).await?;
+
+            this.update(cx, |this, cx| this.reload(cx))?.await?;
+            Ok(id)
+        })
+    }
+
     pub fn open_thread(
         &self,
         id: &ThreadId,
@@ -603,6 +690,9 @@ pub struct SerializedThreadMetadata {
     pub id: ThreadId,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    /// The thread this one was branched from.
+    #[serde(default)]
+    pub parent_id: Option<ThreadId>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -615,6 +705,11 @@ pub struct SerializedThread {
     pub initial_project_snapshot: Option<Arc<ProjectSnapshot>>,
     #[serde(default)]
     pub cumulative_token_usage: TokenUsage,
+    /// The part of the cumulative token usage that was spent by sub-agents.
+    #[serde(default)]
+    pub delegated_token_usage: TokenUsage,
+    #[serde(default)]
+    pub cost: ThreadCost,
     #[serde(default)]
     pub request_token_usage: Vec<TokenUsage>,
     #[serde(default)]
@@ -629,6 +724,12 @@ pub struct SerializedThread {
     pub tool_use_limit_reached: bool,
     #[serde(default)]
     pub profile: Option<AgentProfileId>,
+    #[serde(default)]
+    pub branched_from: Option<ThreadBranchPoint>,
+    /// The rules files from subdirectories of worktrees that were added to the thread, as paths
+    /// that start with the name of their worktree.
+    #[serde(default)]
+    pub directory_rules: Vec<Arc<Path>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -769,6 +870,8 @@ impl LegacySerializedThread {
             messages: self.messages.into_iter().map(|msg| msg.upgrade()).collect(),
             initial_project_snapshot: self.initial_project_snapshot,
             cumulative_token_usage: TokenUsage::default(),
+            delegated_token_usage: TokenUsage::default(),
+            cost: ThreadCost::default(),
             request_token_usage: Vec::new(),
             detailed_summary_state: DetailedSummaryState::default(),
             exceeded_window_error: None,
@@ -776,6 +879,8 @@ impl LegacySerializedThread {
             completion_mode: None,
             tool_use_limit_reached: false,
             profile: None,
+            branched_from: None,
+            directory_rules: Vec::new(),
         }
     }
 }
@@ -839,6 +944,8 @@ impl Bind for ThreadId {
     }
 }
 
+impl StaticColumnCount for ThreadId {}
+
 impl Column for ThreadId {
     fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
         let (id_str, next_index) = String::column(statement, start_index)?;
@@ -889,6 +996,13 @@ impl ThreadsDatabase {
             "})?()
         .map_err(|e| anyhow!("Failed to create threads table: {}", e))?;
 
+        let columns =
+            connection.select::<String>("SELECT name FROM pragma_table_info('threads')")?()?;
+        if !columns.iter().any(|column| column == "parent_id") {
+            connection.exec("ALTER TABLE threads ADD COLUMN parent_id TEXT")?()
+                .map_err(|e| anyhow!("Failed to add parent_id to threads table: {}", e))?;
+        }
+
         let db = Self {
             executor: executor.clone(),
             connection: Arc::new(Mutex::new(connection)),
@@ -972,6 +1086,10 @@ impl ThreadsDatabase {
         let json_data = serde_json::to_string(&thread)?;
         let summary = thread.summary.to_string();
         let updated_at = thread.updated_at.to_rfc3339();
+        let parent_id = thread
+            .branched_from
+            .as_ref()
+            .map(|branch_point| branch_point.thread_id.clone());
 
         let connection = connection.lock().unwrap();
 
@@ -979,11 +1097,11 @@ impl ThreadsDatabase {
         let data_type = DataType::Zstd;
         let data = compressed;
 
-        let mut insert = connection.exec_bound::<(ThreadId, String, String, DataType, Vec<u8>)>(indoc! {"
-            INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data) VALUES (?, ?, ?, ?, ?)
+        let mut insert = connection.exec_bound::<(ThreadId, String, String, DataType, Vec<u8>, Option<ThreadId>)>(indoc! {"
+            INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data, parent_id) VALUES (?, ?, ?, ?, ?, ?)
         "})?;
 
-        insert((id, summary, updated_at, data_type, data))?;
+        insert((id, summary, updated_at, data_type, data, parent_id))?;
 
         Ok(())
     }
@@ -993,19 +1111,20 @@ impl ThreadsDatabase {
 
         self.executor.spawn(async move {
             let connection = connection.lock().unwrap();
-            let mut select =
-                connection.select_bound::<(), (ThreadId, String, String)>(indoc! {"
-                SELECT id, summary, updated_at FROM threads ORDER BY updated_at DESC
+            let mut select = connection
+                .select_bound::<(), (ThreadId, String, String, Option<ThreadId>)>(indoc! {"
+                SELECT id, summary, updated_at, parent_id FROM threads ORDER BY updated_at DESC
             "})?;
 
             let rows = select(())?;
             let mut threads = Vec::new();
 
-            for (id, summary, updated_at) in rows {
+            for (id, summary, updated_at, parent_id) in rows {
                 threads.push(SerializedThreadMetadata {
                     id,
                     summary: summary.into(),
                     updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+                    parent_id,
                 });
             }
 
@@ -1068,9 +1187,17 @@ impl ThreadsDatabase {
 mod tests {
     use super::*;
     use crate::thread::{DetailedSummaryState, MessageId};
+    use agent_settings::AgentSettings;
+    use assistant_tool::ToolRegistry;
     use chrono::Utc;
+    use gpui::TestAppContext;
     use language_model::{Role, TokenUsage};
     use pretty_assertions::assert_eq;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use theme::ThemeSettings;
+    use util::path;
 
     #[test]
     fn test_legacy_serialized_thread_upgrade() {
@@ -1110,13 +1237,17 @@ mod tests {
                 version: SerializedThread::VERSION.to_string(),
                 initial_project_snapshot: None,
                 cumulative_token_usage: TokenUsage::default(),
+                delegated_token_usage: TokenUsage::default(),
+                cost: ThreadCost::default(),
                 request_token_usage: vec![],
                 detailed_summary_state: DetailedSummaryState::default(),
                 exceeded_window_error: None,
                 model: None,
                 completion_mode: None,
                 tool_use_limit_reached: false,
-                profile: None
+                profile: None,
+                branched_from: None,
+                directory_rules: Vec::new(),
             }
         )
     }
@@ -1177,6 +1308,8 @@ mod tests {
             version: SerializedThreadV0_1_0::VERSION.to_string(),
             initial_project_snapshot: None,
             cumulative_token_usage: TokenUsage::default(),
+            delegated_token_usage: TokenUsage::default(),
+            cost: ThreadCost::default(),
             request_token_usage: vec![],
             detailed_summary_state: DetailedSummaryState::default(),
             exceeded_window_error: None,
@@ -1184,6 +1317,8 @@ mod tests {
             completion_mode: None,
             tool_use_limit_reached: false,
             profile: None,
+            branched_from: None,
+            directory_rules: Vec::new(),
         });
         let upgraded = thread_v0_1_0.upgrade();
 
@@ -1230,14 +1365,340 @@ mod tests {
                 version: SerializedThread::VERSION.to_string(),
                 initial_project_snapshot: None,
                 cumulative_token_usage: TokenUsage::default(),
+                delegated_token_usage: TokenUsage::default(),
+                cost: ThreadCost::default(),
                 request_token_usage: vec![],
                 detailed_summary_state: DetailedSummaryState::default(),
                 exceeded_window_error: None,
                 model: None,
                 completion_mode: None,
                 tool_use_limit_reached: false,
-                profile: None
+                profile: None,
+                branched_from: None,
+                directory_rules: Vec::new(),
             }
         )
     }
+
+    #[test]
+    fn test_exported_thread_json_round_trip() {
+        let thread = exported_thread();
+        let json = serde_json::to_string_pretty(&thread).unwrap();
+        assert_eq!(
+            SerializedThread::from_json(json.as_bytes()).unwrap(),
+            thread
+        );
+    }
+
+    #[test]
+    fn test_exported_thread_version_check() {
+        let mut json = serde_json::to_value(exported_thread()).unwrap();
+
+        json["version"] = SerializedThreadV0_1_0::VERSION.into();
+        let upgraded = SerializedThread::from_json(json.to_string().as_bytes()).unwrap();
+        assert_eq!(upgraded.version, SerializedThread::VERSION);
+        assert_eq!(upgraded.summary, "Exported conversation");
+
+        json["version"] = "99.0.0".into();
+        let error = SerializedThread::from_json(json.to_string().as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unrecognized serialized thread version: \"99.0.0\""
+        );
+
+        json["version"] = 2.into();
+        assert!(SerializedThread::from_json(json.to_string().as_bytes()).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_import_thread(cx: &mut TestAppContext) {
+        let thread_store = init_thread_store(cx).await;
+
+        let mut exported = exported_thread();
+        exported.branched_from = Some(ThreadBranchPoint {
+            thread_id: ThreadId::new(),
+            message_id: MessageId(1),
+        });
+        let first_id = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.import_thread(exported, cx)
+            })
+            .await
+            .unwrap();
+        let second_id = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.import_thread(exported_thread(), cx)
+            })
+            .await
+            .unwrap();
+        assert_ne!(first_id, second_id);
+
+        // The imported threads are listed in the history right away.
+        thread_store.read_with(cx, |thread_store, _| {
+            let imported = thread_store
+                .reverse_chronological_threads()
+                .filter(|thread| thread.id == first_id || thread.id == second_id)
+                .collect::<Vec<_>>();
+            assert_eq!(imported.len(), 2);
+            for thread in imported {
+                assert_eq!(thread.summary, "Exported conversation");
+                assert_eq!(thread.parent_id, None);
+            }
+        });
+
+        // The thread it was branched from isn't part of this history.
+        let database = cx.update(ThreadsDatabase::global_future).await.unwrap();
+        let saved = database.try_find_thread(first_id).await.unwrap().unwrap();
+        assert_eq!(saved.branched_from, None);
+        assert_eq!(saved.messages, exported_thread().messages);
+    }
+
+    #[gpui::test]
+    async fn test_branch_thread(cx: &mut TestAppContext) {
+        let thread_store = init_thread_store(cx).await;
+        let usage = |input_tokens| TokenUsage {
+            input_tokens,
+            output_tokens: 10,
+            ..Default::default()
+        };
+        let thread = thread_store.update(cx, |thread_store, cx| {
+            thread_store.create_thread_from_serialized(
+                SerializedThread {
+                    cumulative_token_usage: usage(3000),
+                    delegated_token_usage: usage(500),
+                    cost: ThreadCost {
+                        amount: 0.01,
+                        has_unpriced_requests: true,
+                    },
+                    request_token_usage: vec![usage(1000), usage(1000), usage(2000), usage(2000)],
+                    ..exported_thread()
+                },
+                cx,
+            )
+        });
+
+        let branch = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.branch_thread(&thread, MessageId(1), cx)
+            })
+            .await
+            .unwrap();
+        let branch_id = branch.read_with(cx, |branch, _| branch.id().clone());
+
+        // The usage of the parent thread isn't counted again in its branches.
+        let database = cx.update(ThreadsDatabase::global_future).await.unwrap();
+        let saved = database.try_find_thread(branch_id).await.unwrap().unwrap();
+        assert_eq!(saved.messages, exported_thread().messages[..2]);
+        assert_eq!(saved.request_token_usage, vec![usage(1000), usage(1000)]);
+        assert_eq!(saved.cumulative_token_usage, TokenUsage::default());
+        assert_eq!(saved.delegated_token_usage, TokenUsage::default());
+        assert_eq!(saved.cost, ThreadCost::default());
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.cumulative_token_usage(), usage(3000));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_thread_markdown(cx: &mut TestAppContext) {
+        let thread_store = init_thread_store(cx).await;
+        let thread = thread_store.update(cx, |thread_store, cx| {
+            thread_store.create_thread_from_serialized(exported_thread(), cx)
+        });
+
+        let markdown = thread
+            .read_with(cx, |thread, cx| thread.to_markdown(cx))
+            .unwrap();
+        assert_eq!(
+            markdown,
+            indoc! {r#"
+                # Exported conversation
+
+                ## User
+
+                Print the main file
+
+                ## Agent
+
+                Reading the file
+
+                **Use Tool: read_file (abc)**
+                ```json
+                {
+                  "path": "src/main.rs"
+                }
+                ```
+
+                **Tool Results: abc (Error)**
+
+                Couldn't read the file:
+                ```
+                not found
+                ```
+
+                ## User
+
+                ## Agent
+
+                The file doesn't exist.
+
+            "#}
+        );
+
+        let markdown = thread
+            .read_with(cx, |thread, cx| thread.to_exported_markdown(cx))
+            .unwrap();
+        // The tool result contains a code block, so it's wrapped in a longer fence.
+        assert_eq!(
+            markdown,
+            indoc! {r#"
+                # Exported conversation
+
+                ## User
+
+                Print the main file
+
+                ## Agent
+
+                Reading the file
+
+                <details>
+                <summary>Tool call: <code>read_file</code> (abc) (Error)</summary>
+
+                ```json
+                {
+                  "path": "src/main.rs"
+                }
+                ```
+
+                **Result:**
+
+                ````
+                Couldn't read the file:
+                ```
+                not found
+                ```
+                ````
+
+                </details>
+
+                ## User
+
+                ## Agent
+
+                The file doesn't exist.
+
+            "#}
+        );
+    }
+
+    async fn init_thread_store(cx: &mut TestAppContext) -> Entity<ThreadStore> {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AgentSettings::register(cx);
+            prompt_store::init(cx);
+            init(cx);
+            workspace::init_settings(cx);
+            language_model::init_settings(cx);
+            ThemeSettings::register(cx);
+            ToolRegistry::default_global(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/test"), json!({})).await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        cx.update(|cx| {
+            ThreadStore::load(
+                project,
+                cx.new(|_| ToolWorkingSet::default()),
+                None,
+                Arc::new(PromptBuilder::new(None).unwrap()),
+                cx,
+            )
+        })
+        .await
+        .unwrap()
+    }
+
+    fn exported_thread() -> SerializedThread {
+        SerializedThread {
+            summary: "Exported conversation".into(),
+            updated_at: Utc::now(),
+            messages: vec![
+                SerializedMessage {
+                    id: MessageId(0),
+                    role: Role::User,
+                    segments: vec![SerializedMessageSegment::Text {
+                        text: "Print the main file".to_string(),
+                    }],
+                    tool_uses: vec![],
+                    tool_results: vec![],
+                    context: "".to_string(),
+                    creases: vec![],
+                    is_hidden: false,
+                },
+                SerializedMessage {
+                    id: MessageId(1),
+                    role: Role::Assistant,
+                    segments: vec![SerializedMessageSegment::Text {
+                        text: "Reading the file".to_string(),
+                    }],
+                    tool_uses: vec![SerializedToolUse {
+                        id: "abc".into(),
+                        name: "read_file".into(),
+                        input: serde_json::json!({ "path": "src/main.rs" }),
+                    }],
+                    tool_results: vec![],
+                    context: "".to_string(),
+                    creases: vec![],
+                    is_hidden: false,
+                },
+                SerializedMessage {
+                    id: MessageId(2),
+                    role: Role::User,
+                    segments: vec![],
+                    tool_uses: vec![],
+                    tool_results: vec![SerializedToolResult {
+                        tool_use_id: "abc".into(),
+                        is_error: true,
+                        content: LanguageModelToolResultContent::Text(
+                            "Couldn't read the file:\n```\nnot found\n```\n".into(),
+                        ),
+                        output: None,
+                    }],
+                    context: "".to_string(),
+                    creases: vec![],
+                    is_hidden: false,
+                },
+                SerializedMessage {
+                    id: MessageId(3),
+                    role: Role::Assistant,
+                    segments: vec![SerializedMessageSegment::Text {
+                        text: "The file doesn't exist.".to_string(),
+                    }],
+                    tool_uses: vec![],
+                    tool_results: vec![],
+                    context: "".to_string(),
+                    creases: vec![],
+                    is_hidden: false,
+                },
+            ],
+            version: SerializedThread::VERSION.to_string(),
+            initial_project_snapshot: None,
+            cumulative_token_usage: TokenUsage::default(),
+            delegated_token_usage: TokenUsage::default(),
+            cost: ThreadCost::default(),
+            request_token_usage: vec![],
+            detailed_summary_state: DetailedSummaryState::default(),
+            exceeded_window_error: None,
+            model: None,
+            completion_mode: None,
+            tool_use_limit_reached: false,
+            profile: None,
+            branched_from: None,
+            directory_rules: Vec::new(),
+        }
+    }
 }
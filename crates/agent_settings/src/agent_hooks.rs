@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Shell commands that the agent runs at certain points while it works.
+///
+/// Hooks run in the root directory of the project's first worktree, on the remote machine in
+/// projects opened over SSH. They can't run in projects shared by collaborators.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AgentHooks {
+    /// Commands to run before the agent uses a tool. When a command exits with a non-zero
+    /// status, the tool isn't run, and the command's output is given to the agent instead.
+    ///
+    /// The tool's name and JSON input are available in the `ZED_AGENT_TOOL_NAME` and
+    /// `ZED_AGENT_TOOL_INPUT` environment variables.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub before_tool_call: Vec<AgentHook>,
+    /// Commands to run after the agent edits files, such as a formatter or a linter. Their
+    /// output is given to the agent along with the result of the edit.
+    ///
+    /// The edited paths are available in the `ZED_AGENT_EDITED_FILES` environment variable,
+    /// one per line.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub after_edit: Vec<AgentHook>,
+    /// Commands to run when the agent stops working.
+    ///
+    /// The reason it stopped (`end_turn`, `max_tokens` or `refusal`) is available in the
+    /// `ZED_AGENT_STOP_REASON` environment variable.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub on_stop: Vec<AgentHook>,
+}
+
+impl AgentHooks {
+    pub fn is_empty(&self) -> bool {
+        self.before_tool_call.is_empty() && self.after_edit.is_empty() && self.on_stop.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AgentHook {
+    /// The command to run in the system shell.
+    pub command: String,
+    /// The names of the tools that the hook applies to. Only used by `before_tool_call` and
+    /// `after_edit` hooks; when omitted, the hook applies to every tool.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+    /// How many seconds the command may run before it's stopped and treated as failed.
+    ///
+    /// Default: 60
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+impl AgentHook {
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+            .map_or(Self::DEFAULT_TIMEOUT, Duration::from_secs)
+    }
+
+    pub fn applies_to_tool(&self, tool_name: &str) -> bool {
+        self.tools
+            .as_ref()
+            .is_none_or(|tools| tools.iter().any(|tool| tool == tool_name))
+    }
+}
@@ -1,3 +1,4 @@
+mod agent_hooks;
 mod agent_profile;
 
 use std::sync::Arc;
@@ -10,6 +11,7 @@ use schemars::{JsonSchema, schema::Schema};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 
+pub use crate::agent_hooks::*;
 pub use crate::agent_profile::*;
 
 pub fn init(cx: &mut App) {
@@ -66,6 +68,7 @@ pub struct AgentSettings {
     pub model_parameters: Vec<LanguageModelParameters>,
     pub preferred_completion_mode: CompletionMode,
     pub enable_feedback: bool,
+    pub hooks: AgentHooks,
 }
 
 impl AgentSettings {
@@ -78,6 +81,19 @@ impl AgentSettings {
             .and_then(|m| m.temperature)
     }
 
+    pub fn edit_format_for_model(
+        model: &Arc<dyn LanguageModel>,
+        cx: &App,
+    ) -> Option<EditFormatSetting> {
+        let settings = Self::get_global(cx);
+        settings
+            .model_parameters
+            .iter()
+            .rev()
+            .filter(|setting| setting.matches(model))
+            .find_map(|setting| setting.edit_format)
+    }
+
     pub fn set_inline_assistant_model(&mut self, provider: String, model: String) {
         self.inline_assistant_model = Some(LanguageModelSelection {
             provider: provider.into(),
@@ -105,6 +121,19 @@ pub struct LanguageModelParameters {
     pub provider: Option<LanguageModelProviderSetting>,
     pub model: Option<SharedString>,
     pub temperature: Option<f32>,
+    /// The format in which the model is asked to describe its edits to files.
+    pub edit_format: Option<EditFormatSetting>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EditFormatSetting {
+    /// `<old_text>` and `<new_text>` tags.
+    XmlTags,
+    /// `<<<<<<< SEARCH` and `>>>>>>> REPLACE` markers.
+    DiffFenced,
+    /// Unified diff hunks.
+    UnifiedDiff,
 }
 
 impl LanguageModelParameters {
@@ -291,6 +320,11 @@ pub struct AgentSettingsContent {
     ///
     /// Default: true
     enable_feedback: Option<bool>,
+    /// Shell commands to run before the agent uses a tool, after it edits files, and when it
+    /// stops.
+    ///
+    /// Default: {}
+    hooks: Option<AgentHooks>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
@@ -449,6 +483,7 @@ impl Settings for AgentSettings {
                 value.preferred_completion_mode,
             );
             merge(&mut settings.enable_feedback, value.enable_feedback);
+            merge(&mut settings.hooks, value.hooks.clone());
 
             settings
                 .model_parameters
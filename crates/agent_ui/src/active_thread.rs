@@ -1148,6 +1148,9 @@ impl ActiveThread {
                 self.save_thread(cx);
                 cx.notify();
             }
+            ThreadEvent::DirectoryRulesChanged => {
+                cx.notify();
+            }
             ThreadEvent::RetriesFailed { message } => {
                 self.show_notification(message, ui::IconName::Warning, window, cx);
             }
@@ -1866,6 +1869,8 @@ impl ActiveThread {
                 }
             });
 
+        let branch_from_here = self.render_branch_from_here_button(message_id, ix, cx);
+
         let scroll_to_top = IconButton::new(("scroll_to_top", ix), IconName::ArrowUpAlt)
             .icon_size(IconSize::XSmall)
             .icon_color(Color::Ignored)
@@ -1939,6 +1944,7 @@ impl ActiveThread {
                                     );
                                 })),
                         )
+                        .child(branch_from_here)
                         .child(open_as_markdown),
                 )
                 .into_any_element(),
@@ -1983,17 +1989,30 @@ impl ActiveThread {
                                     );
                                 })),
                         )
+                        .child(branch_from_here)
                         .child(open_as_markdown)
                         .child(scroll_to_top),
                 )
                 .into_any_element(),
             None => feedback_container
                 .child(h_flex()
+                    .child(branch_from_here)
                     .child(open_as_markdown))
                     .child(scroll_to_top)
                 .into_any_element(),
         };
 
+        // Messages in the middle of a turn don't have the feedback controls, but can still be
+        // branched from.
+        let message_controls = (!show_feedback && !message.ui_only).then(|| {
+            h_flex()
+                .px(RESPONSE_PADDING_X)
+                .mr_1()
+                .justify_end()
+                .visible_on_hover("message_container")
+                .child(self.render_branch_from_here_button(message_id, ix, cx))
+        });
+
         let message_is_empty = message.should_display_content();
         let has_content = !message_is_empty || !added_context.is_empty();
 
@@ -2213,6 +2232,7 @@ impl ActiveThread {
             .on_click(cx.listener(Self::handle_cancel_click));
 
         v_flex()
+            .group("message_container")
             .w_full()
             .map(|parent| {
                 if let Some(checkpoint) = checkpoint.filter(|_| !is_generating) {
@@ -2291,6 +2311,7 @@ impl ActiveThread {
                 parent.child(self.render_rules_item(cx))
             })
             .child(styled_message)
+            .children(message_controls)
             .children(loading_dots)
             .when(show_feedback, move |parent| {
                 parent.child(feedback_items).when_some(
@@ -2531,6 +2552,21 @@ impl ActiveThread {
             .blend(cx.theme().colors().editor_foreground.opacity(0.025))
     }
 
+    fn render_branch_from_here_button(
+        &self,
+        message_id: MessageId,
+        ix: usize,
+        cx: &Context<Self>,
+    ) -> IconButton {
+        IconButton::new(("branch-from-here", ix), IconName::GitBranch)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Ignored)
+            .tooltip(Tooltip::text("Branch From Here"))
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.branch_from_message(message_id, cx);
+            }))
+    }
+
     fn render_ui_notification(
         &self,
         message_content: impl IntoIterator<Item = impl IntoElement>,
@@ -3550,10 +3586,25 @@ impl ActiveThread {
         self.list_state.reset(self.messages.len());
         cx.notify();
     }
+
+    fn branch_from_message(&mut self, message_id: MessageId, cx: &mut Context<Self>) {
+        let branch = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.branch_thread(&self.thread, message_id, cx)
+        });
+        cx.spawn(async move |this, cx| {
+            let thread = branch.await?;
+            this.update(cx, |_, cx| {
+                cx.emit(ActiveThreadEvent::ThreadBranched(thread));
+            })
+        })
+        .detach_and_log_err(cx);
+    }
 }
 
 pub enum ActiveThreadEvent {
     EditingMessageTokenCountChanged,
+    /// A new thread was branched off this one and should be opened.
+    ThreadBranched(Entity<Thread>),
 }
 
 impl EventEmitter<ActiveThreadEvent> for ActiveThread {}
@@ -4037,6 +4088,76 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_branching_from_mid_turn_message(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+
+        let (cx, active_thread, _, thread, _) = setup_test_environment(cx, project.clone()).await;
+
+        let mid_turn_message_id = thread.update(cx, |thread, cx| {
+            thread.insert_user_message(
+                "Fix the failing tests",
+                ContextLoadResult::default(),
+                None,
+                vec![],
+                cx,
+            );
+            let mid_turn_message_id = thread.insert_assistant_message(
+                vec![MessageSegment::Text("Let me run them first.".into())],
+                cx,
+            );
+            thread.insert_assistant_message(
+                vec![MessageSegment::Text("All of the tests pass now.".into())],
+                cx,
+            );
+            mid_turn_message_id
+        });
+        cx.run_until_parked();
+
+        // The first response is followed by another one in the same turn, so it doesn't get the
+        // feedback controls.
+        thread.read_with(cx, |thread, _| {
+            assert!(!thread.is_turn_end(1));
+            assert!(thread.is_turn_end(2));
+        });
+
+        let branched_thread = Arc::new(std::sync::Mutex::new(None));
+        let _subscription = cx.update(|_, cx| {
+            let branched_thread = branched_thread.clone();
+            cx.subscribe(&active_thread, move |_, event: &ActiveThreadEvent, _| {
+                if let ActiveThreadEvent::ThreadBranched(thread) = event {
+                    *branched_thread.lock().unwrap() = Some(thread.clone());
+                }
+            })
+        });
+
+        active_thread.update(cx, |active_thread, cx| {
+            active_thread.branch_from_message(mid_turn_message_id, cx);
+        });
+        cx.run_until_parked();
+
+        let branched_thread = branched_thread.lock().unwrap().take().unwrap();
+        branched_thread.read_with(cx, |branched_thread, _| {
+            assert_eq!(
+                branched_thread
+                    .messages()
+                    .map(|message| message.to_string())
+                    .collect::<Vec<_>>(),
+                vec!["Fix the failing tests", "Let me run them first."]
+            );
+            assert_eq!(
+                branched_thread
+                    .branched_from()
+                    .map(|branch_point| branch_point.message_id),
+                Some(mid_turn_message_id)
+            );
+            assert!(!branched_thread.is_turn_end(0));
+            assert!(branched_thread.is_turn_end(1));
+        });
+    }
+
     fn init_test_settings(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
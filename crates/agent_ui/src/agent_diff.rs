@@ -1376,7 +1376,8 @@ impl AgentDiff {
             | ThreadEvent::ToolUseLimitReached
             | ThreadEvent::CancelEditing
             | ThreadEvent::RetriesFailed { .. }
-            | ThreadEvent::ProfileChanged => {}
+            | ThreadEvent::ProfileChanged
+            | ThreadEvent::DirectoryRulesChanged => {}
         }
     }
 
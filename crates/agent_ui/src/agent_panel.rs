@@ -10,9 +10,10 @@ use serde::{Deserialize, Serialize};
 use crate::language_model_selector::ToggleModelSelector;
 use crate::{
     AddContextServer, AgentDiffPane, ContinueThread, ContinueWithBurnMode,
-    DeleteRecentlyOpenThread, ExpandMessageEditor, Follow, InlineAssistant, NewTextThread,
-    NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory, ResetTrialEndUpsell,
-    ResetTrialUpsell, ToggleBurnMode, ToggleContextPicker, ToggleNavigationMenu, ToggleOptionsMenu,
+    DeleteRecentlyOpenThread, ExpandMessageEditor, ExportThreadAsJson, ExportThreadAsMarkdown,
+    Follow, ImportThread, InlineAssistant, NewTextThread, NewThread, OpenActiveThreadAsMarkdown,
+    OpenAgentDiff, OpenHistory, ResetTrialEndUpsell, ResetTrialUpsell, ToggleBurnMode,
+    ToggleContextPicker, ToggleNavigationMenu, ToggleOptionsMenu,
     active_thread::{self, ActiveThread, ActiveThreadEvent},
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
     agent_diff::AgentDiff,
@@ -29,10 +30,10 @@ use agent::{
     Thread, ThreadError, ThreadEvent, ThreadId, ThreadSummary, TokenUsageRatio,
     context_store::ContextStore,
     history_store::{HistoryEntryId, HistoryStore},
-    thread_store::{TextThreadStore, ThreadStore},
+    thread_store::{SerializedThread, TextThreadStore, ThreadStore},
 };
 use agent_settings::{AgentDockPosition, AgentSettings, CompletionMode, DefaultView};
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use assistant_context::{AssistantContext, ContextEvent, ContextSummary};
 use assistant_slash_command::SlashCommandWorkingSet;
 use assistant_tool::ToolWorkingSet;
@@ -42,14 +43,14 @@ use fs::Fs;
 use gpui::{
     Action, Animation, AnimationExt as _, AnyElement, App, AsyncWindowContext, ClipboardItem,
     Corner, DismissEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, FontWeight,
-    KeyContext, Pixels, Subscription, Task, UpdateGlobal, WeakEntity, linear_color_stop,
-    linear_gradient, prelude::*, pulsating_between,
+    KeyContext, PathPromptOptions, Pixels, Subscription, Task, UpdateGlobal, WeakEntity,
+    linear_color_stop, linear_gradient, prelude::*, pulsating_between,
 };
 use language::LanguageRegistry;
 use language_model::{
     ConfigurationError, LanguageModelProviderTosView, LanguageModelRegistry, ZED_CLOUD_PROVIDER_ID,
 };
-use project::{Project, ProjectPath, Worktree};
+use project::{DirectoryLister, Project, ProjectPath, Worktree};
 use prompt_store::{PromptBuilder, PromptStore, UserPromptId};
 use proto::Plan;
 use rules_library::{RulesLibrary, open_rules_library};
@@ -66,6 +67,7 @@ use util::ResultExt as _;
 use workspace::{
     CollaboratorId, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView, Workspace,
     dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr,
 };
 use zed_actions::{
     DecreaseBufferFontSize, IncreaseBufferFontSize, ResetBufferFontSize,
@@ -131,6 +133,25 @@ pub fn init(cx: &mut App) {
                         }
                     }
                 })
+                .register_action(|workspace, _: &ExportThreadAsMarkdown, window, cx| {
+                    AgentPanel::export_active_thread(
+                        workspace,
+                        ThreadExportFormat::Markdown,
+                        window,
+                        cx,
+                    );
+                })
+                .register_action(|workspace, _: &ExportThreadAsJson, window, cx| {
+                    AgentPanel::export_active_thread(
+                        workspace,
+                        ThreadExportFormat::Json,
+                        window,
+                        cx,
+                    );
+                })
+                .register_action(|workspace, _: &ImportThread, window, cx| {
+                    AgentPanel::import_thread(workspace, window, cx);
+                })
                 .register_action(|workspace, _: &Follow, window, cx| {
                     workspace.follow(CollaboratorId::Agent, window, cx);
                 })
@@ -181,6 +202,12 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+#[derive(Clone, Copy)]
+enum ThreadExportFormat {
+    Markdown,
+    Json,
+}
+
 enum ActiveView {
     Thread {
         thread: Entity<ActiveThread>,
@@ -269,11 +296,18 @@ impl ActiveView {
                     }
                 }
             }),
-            cx.subscribe(&active_thread, |_, _, event, cx| match &event {
-                ActiveThreadEvent::EditingMessageTokenCountChanged => {
-                    cx.notify();
-                }
-            }),
+            cx.subscribe_in(
+                &active_thread,
+                window,
+                |this, _, event, window, cx| match &event {
+                    ActiveThreadEvent::EditingMessageTokenCountChanged => {
+                        cx.notify();
+                    }
+                    ActiveThreadEvent::ThreadBranched(thread) => {
+                        this.open_thread(thread.clone(), window, cx);
+                    }
+                },
+            ),
             cx.subscribe_in(&active_thread.read(cx).thread().clone(), window, {
                 let editor = editor.clone();
                 move |_, thread, event, window, cx| match event {
@@ -1201,6 +1235,83 @@ impl AgentPanel {
         }
     }
 
+    fn export_active_thread(
+        workspace: &mut Workspace,
+        format: ThreadExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(thread) = workspace
+            .panel::<AgentPanel>(cx)
+            .and_then(|panel| panel.read(cx).active_thread(cx))
+        else {
+            return;
+        };
+
+        let contents = match format {
+            ThreadExportFormat::Markdown => Task::ready(thread.read(cx).to_exported_markdown(cx)),
+            ThreadExportFormat::Json => {
+                let serialized = thread.update(cx, |thread, cx| thread.serialize(cx));
+                cx.background_spawn(async move {
+                    anyhow::Ok(serde_json::to_string_pretty(&serialized.await?)?)
+                })
+            }
+        };
+        // Transcripts are written to this machine, even when the project is remote.
+        let fs = workspace.app_state().fs.clone();
+        let lister = DirectoryLister::Local(workspace.project().clone(), fs.clone());
+        let path = workspace.prompt_for_new_path(lister, window, cx);
+        cx.spawn_in(window, async move |_, _| {
+            let contents = contents.await?;
+            let Some(path) = path.await.ok().flatten().into_iter().flatten().next() else {
+                return Ok(());
+            };
+            fs.atomic_write(path, contents).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+    }
+
+    fn import_thread(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(panel) = workspace.panel::<AgentPanel>(cx) else {
+            return;
+        };
+        workspace.focus_panel::<AgentPanel>(window, cx);
+
+        let fs = workspace.app_state().fs.clone();
+        let paths = workspace.prompt_for_open_path(
+            PathPromptOptions {
+                files: true,
+                directories: false,
+                multiple: false,
+            },
+            DirectoryLister::Local(workspace.project().clone(), fs.clone()),
+            window,
+            cx,
+        );
+        let panel = panel.downgrade();
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(path) = paths.await.ok().flatten().into_iter().flatten().next() else {
+                return Ok(());
+            };
+            let json = fs.load(&path).await?;
+            let serialized = SerializedThread::from_json(json.as_bytes())
+                .with_context(|| format!("{} is not an exported thread", path.display()))?;
+
+            let import_task = panel.update(cx, |panel, cx| {
+                panel.thread_store.update(cx, |thread_store, cx| {
+                    thread_store.import_thread(serialized, cx)
+                })
+            })?;
+            let thread_id = import_task.await?;
+            panel
+                .update_in(cx, |panel, window, cx| {
+                    panel.open_thread_by_id(&thread_id, window, cx)
+                })?
+                .await
+        })
+        .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+    }
+
     fn handle_agent_configuration_event(
         &mut self,
         _entity: &Entity<AgentConfiguration>,
@@ -1755,7 +1866,7 @@ impl AgentPanel {
                     menu = menu
                         .action("New Thread", NewThread::default().boxed_clone())
                         .action("New Text Thread", NewTextThread.boxed_clone())
-                        .when_some(active_thread, |this, active_thread| {
+                        .when_some(active_thread.clone(), |this, active_thread| {
                             let thread = active_thread.read(cx);
                             if !thread.is_empty() {
                                 this.action(
@@ -1770,6 +1881,21 @@ impl AgentPanel {
                         })
                         .separator();
 
+                    menu = menu
+                        .when_some(active_thread, |this, active_thread| {
+                            if !active_thread.read(cx).is_empty() {
+                                this.action(
+                                    "Export Thread as Markdown…",
+                                    Box::new(ExportThreadAsMarkdown),
+                                )
+                                .action("Export Thread as JSON…", Box::new(ExportThreadAsJson))
+                            } else {
+                                this
+                            }
+                        })
+                        .action("Import Thread…", Box::new(ImportThread))
+                        .separator();
+
                     menu = menu
                         .header("MCP Servers")
                         .action(
@@ -1923,6 +2049,8 @@ impl AgentPanel {
             };
 
         let is_waiting_to_update_token_count = message_editor.is_waiting_to_update_token_count();
+        let delegated_tokens = conversation_token_usage.delegated;
+        let cost = thread.cost();
 
         match &self.active_view {
             ActiveView::Thread { .. } => {
@@ -1941,6 +2069,12 @@ impl AgentPanel {
                     .id("token-count")
                     .flex_shrink_0()
                     .gap_0p5()
+                    .when(delegated_tokens > 0, |parent| {
+                        parent.tooltip(Tooltip::text(format!(
+                            "Sub-agents used another {} tokens",
+                            humanize_token_count(delegated_tokens)
+                        )))
+                    })
                     .when(!is_generating && is_estimating, |parent| {
                         parent
                             .child(
@@ -1993,6 +2127,32 @@ impl AgentPanel {
                             .size(LabelSize::Small)
                             .color(Color::Muted),
                     )
+                    .when(cost.amount > 0., |parent| {
+                        parent.child(
+                            h_flex()
+                                .id("thread-cost")
+                                .ml_1()
+                                .child(
+                                    Label::new(format!("${:.2}", cost.amount))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                                .tooltip(move |window, cx| {
+                                    Tooltip::with_meta(
+                                        "Estimated Thread Cost",
+                                        None,
+                                        if cost.has_unpriced_requests {
+                                            "Based on the models' list prices. Requests to models \
+                                            without known pricing aren't included."
+                                        } else {
+                                            "Based on the models' list prices."
+                                        },
+                                        window,
+                                        cx,
+                                    )
+                                }),
+                        )
+                    })
                     .into_any();
 
                 Some(token_count)
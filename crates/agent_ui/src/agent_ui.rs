@@ -76,6 +76,9 @@ actions!(
         RemoveFocusedContext,
         AcceptSuggestedContext,
         OpenActiveThreadAsMarkdown,
+        ExportThreadAsMarkdown,
+        ExportThreadAsJson,
+        ImportThread,
         OpenAgentDiff,
         Keep,
         Reject,
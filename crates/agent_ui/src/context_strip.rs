@@ -6,7 +6,8 @@ use crate::{
 };
 use agent::context_store::SuggestedContext;
 use agent::{
-    context::AgentContextHandle,
+    ThreadEvent,
+    context::{AgentContextHandle, ContextId, FileContextHandle},
     context_store::ContextStore,
     thread_store::{TextThreadStore, ThreadStore},
 };
@@ -34,6 +35,8 @@ pub struct ContextStrip {
     _subscriptions: Vec<Subscription>,
     focused_index: Option<usize>,
     children_bounds: Option<Vec<Bounds<Pixels>>>,
+    /// The number of pills that can be focused, which are the added context and the suggestion.
+    focusable_pill_count: usize,
     model_usage_context: ModelUsageContext,
 }
 
@@ -62,12 +65,19 @@ impl ContextStrip {
 
         let focus_handle = cx.focus_handle();
 
-        let subscriptions = vec![
+        let mut subscriptions = vec![
             cx.observe(&context_store, |_, _, cx| cx.notify()),
             cx.subscribe_in(&context_picker, window, Self::handle_context_picker_event),
             cx.on_focus(&focus_handle, window, Self::handle_focus),
             cx.on_blur(&focus_handle, window, Self::handle_blur),
         ];
+        if let ModelUsageContext::Thread(thread) = &model_usage_context {
+            subscriptions.push(cx.subscribe(thread, |_, _, event, cx| {
+                if let ThreadEvent::DirectoryRulesChanged = event {
+                    cx.notify();
+                }
+            }));
+        }
 
         Self {
             context_store: context_store.clone(),
@@ -80,6 +90,7 @@ impl ContextStrip {
             _subscriptions: subscriptions,
             focused_index: None,
             children_bounds: None,
+            focusable_pill_count: 0,
             model_usage_context,
         }
     }
@@ -119,6 +130,31 @@ impl ContextStrip {
         }
     }
 
+    /// The rules files from subdirectories that the thread's agent has added while it worked.
+    fn directory_rules(&self, cx: &App) -> Vec<AddedContext> {
+        let ModelUsageContext::Thread(thread) = &self.model_usage_context else {
+            return Vec::new();
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Vec::new();
+        };
+        let project = workspace.read(cx).project().read(cx);
+
+        let mut context_id = ContextId::zero();
+        thread
+            .read(cx)
+            .directory_rules()
+            .iter()
+            .filter_map(|rules| {
+                let handle = AgentContextHandle::File(FileContextHandle {
+                    buffer: rules.buffer.clone()?,
+                    context_id: context_id.post_inc(),
+                });
+                AddedContext::new_pending(handle, None, project, None, cx)
+            })
+            .collect()
+    }
+
     fn suggested_context(&self, cx: &App) -> Option<SuggestedContext> {
         match self.suggest_context_kind {
             SuggestContextKind::File => self.suggested_file(cx),
@@ -286,8 +322,7 @@ impl ContextStrip {
 
     fn pill_bounds(&self) -> Option<&[Bounds<Pixels>]> {
         let bounds = self.children_bounds.as_ref()?;
-        let eraser = if bounds.len() < 3 { 0 } else { 1 };
-        let pills = &bounds[1..bounds.len() - eraser];
+        let pills = bounds.get(1..1 + self.focusable_pill_count)?;
 
         if pills.is_empty() { None } else { Some(pills) }
     }
@@ -411,6 +446,8 @@ impl Render for ContextStrip {
                 self.is_suggested_focused(&added_contexts),
             )
         });
+        self.focusable_pill_count = added_contexts.len() + suggested_context.iter().count();
+        let directory_rules = self.directory_rules(cx);
 
         h_flex()
             .flex_wrap()
@@ -529,6 +566,23 @@ impl Render for ContextStrip {
                     ))),
                 )
             })
+            .when(!directory_rules.is_empty(), |parent| {
+                // These are part of the thread rather than the next message, so they can't be
+                // removed or focused.
+                parent.child(h_flex().id("directory-rules").flex_wrap().gap_1().children(
+                    directory_rules.into_iter().map(|rules| {
+                        let context = rules.handle.clone();
+                        // Rules files tend to share a name, so always show their directory.
+                        ContextPill::added(rules, true, false, None).on_click(Rc::new(cx.listener(
+                            move |this, event: &ClickEvent, window, cx| {
+                                if event.down.click_count > 1 {
+                                    this.open_context(&context, window, cx);
+                                }
+                            },
+                        )))
+                    }),
+                ))
+            })
             .when(!no_added_context, {
                 move |parent| {
                     parent.child(
@@ -1,6 +1,7 @@
 use crate::{AgentPanel, RemoveSelectedThread};
 use agent::history_store::{HistoryEntry, HistoryStore};
-use chrono::{Datelike as _, Local, NaiveDate, TimeDelta};
+use chrono::{DateTime, Datelike as _, Local, NaiveDate, TimeDelta, Utc};
+use collections::HashMap;
 use editor::{Editor, EditorEvent};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -52,6 +53,8 @@ enum ListItemType {
     Entry {
         index: usize,
         format: EntryTimeFormat,
+        /// How many threads this one was branched from, transitively.
+        depth: usize,
     },
 }
 
@@ -113,26 +116,23 @@ impl ThreadHistory {
     }
 
     fn update_all_entries(&mut self, cx: &mut Context<Self>) {
-        let new_entries: Arc<Vec<HistoryEntry>> = self
-            .history_store
-            .update(cx, |store, cx| store.entries(cx))
-            .into();
+        let entries = self.history_store.update(cx, |store, cx| store.entries(cx));
 
         self._separated_items_task.take();
 
-        let mut items = Vec::with_capacity(new_entries.len() + 1);
-        let mut indexes = Vec::with_capacity(new_entries.len() + 1);
+        let mut items = Vec::with_capacity(entries.len() + 1);
+        let mut indexes = Vec::with_capacity(entries.len() + 1);
 
         let bg_task = cx.background_spawn(async move {
             let mut bucket = None;
             let today = Local::now().naive_local().date();
+            let arranged = arrange_branches(&entries);
+            let mut new_entries = Vec::with_capacity(entries.len());
 
-            for (index, entry) in new_entries.iter().enumerate() {
-                let entry_date = entry
-                    .updated_at()
-                    .with_timezone(&Local)
-                    .naive_local()
-                    .date();
+            for (index, (entry_ix, depth, tree_updated_at)) in arranged.into_iter().enumerate() {
+                // Branches are listed under the thread they were branched from, so they're
+                // bucketed by the latest activity in their tree rather than their own.
+                let entry_date = tree_updated_at.with_timezone(&Local).naive_local().date();
                 let entry_bucket = TimeBucket::from_dates(today, entry_date);
 
                 if Some(entry_bucket) != bucket {
@@ -144,9 +144,11 @@ impl ThreadHistory {
                 items.push(ListItemType::Entry {
                     index,
                     format: entry_bucket.into(),
+                    depth,
                 });
+                new_entries.push(entries[entry_ix].clone());
             }
-            (new_entries, items, indexes)
+            (Arc::new(new_entries), items, indexes)
         });
 
         let task = cx.spawn(async move |this, cx| {
@@ -472,6 +474,7 @@ impl ThreadHistory {
                         &ListItemType::Entry {
                             index: m.candidate_id,
                             format: EntryTimeFormat::DateAndTime,
+                            depth: 0,
                         },
                         m.positions.clone(),
                         cx,
@@ -492,7 +495,11 @@ impl ThreadHistory {
         cx: &Context<Self>,
     ) -> AnyElement {
         match item {
-            ListItemType::Entry { index, format } => match self.all_entries.get(*index) {
+            ListItemType::Entry {
+                index,
+                format,
+                depth,
+            } => match self.all_entries.get(*index) {
                 Some(entry) => h_flex()
                     .w_full()
                     .pb_1()
@@ -500,6 +507,7 @@ impl ThreadHistory {
                         HistoryEntryElement::new(entry.clone(), self.agent_panel.clone())
                             .highlight_positions(highlight_positions)
                             .timestamp_format(*format)
+                            .depth(*depth)
                             .selected(list_entry_ix == Some(self.selected_index))
                             .hovered(list_entry_ix == self.hovered_index)
                             .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
@@ -617,6 +625,7 @@ pub struct HistoryEntryElement {
     hovered: bool,
     highlight_positions: Vec<usize>,
     timestamp_format: EntryTimeFormat,
+    depth: usize,
     on_hover: Box<dyn Fn(&bool, &mut Window, &mut App) + 'static>,
 }
 
@@ -629,6 +638,7 @@ impl HistoryEntryElement {
             hovered: false,
             highlight_positions: vec![],
             timestamp_format: EntryTimeFormat::DateAndTime,
+            depth: 0,
             on_hover: Box::new(|_, _, _| {}),
         }
     }
@@ -657,6 +667,12 @@ impl HistoryEntryElement {
         self.timestamp_format = format;
         self
     }
+
+    /// Indents the entry to show that it's a branch of the entry above it.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
 }
 
 impl RenderOnce for HistoryEntryElement {
@@ -678,19 +694,36 @@ impl RenderOnce for HistoryEntryElement {
             self.timestamp_format
                 .format_timestamp(&self.agent_panel, timestamp, cx);
 
+        let is_branch =
+            matches!(&self.entry, HistoryEntry::Thread(thread) if thread.parent_id.is_some());
+
         ListItem::new(SharedString::from(id))
             .rounded()
             .toggle_state(self.selected)
             .spacing(ListItemSpacing::Sparse)
+            .indent_level(self.depth)
+            .indent_step_size(px(12.))
             .start_slot(
                 h_flex()
                     .w_full()
                     .gap_2()
                     .justify_between()
                     .child(
-                        HighlightedLabel::new(summary, self.highlight_positions)
-                            .size(LabelSize::Small)
-                            .truncate(),
+                        h_flex()
+                            .gap_1()
+                            .min_w_0()
+                            .when(is_branch, |this| {
+                                this.child(
+                                    Icon::new(IconName::GitBranch)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .child(
+                                HighlightedLabel::new(summary, self.highlight_positions)
+                                    .size(LabelSize::Small)
+                                    .truncate(),
+                            ),
                     )
                     .child(
                         Label::new(thread_timestamp)
@@ -777,6 +810,77 @@ impl RenderOnce for HistoryEntryElement {
     }
 }
 
+/// Orders history entries so that every thread is directly followed by the threads branched from
+/// it, most recently updated first. Returns the index of each entry in `entries`, its depth in the
+/// branch tree and the latest update time of the tree it belongs to.
+///
+/// Expects `entries` to be sorted by their update time, most recent first.
+fn arrange_branches(entries: &[HistoryEntry]) -> Vec<(usize, usize, DateTime<Utc>)> {
+    let thread_indices = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, entry)| match entry {
+            HistoryEntry::Thread(thread) => Some((&thread.id, ix)),
+            HistoryEntry::Context(_) => None,
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut roots = Vec::new();
+    let mut children = HashMap::<usize, Vec<usize>>::default();
+    for (ix, entry) in entries.iter().enumerate() {
+        let parent_ix = match entry {
+            HistoryEntry::Thread(thread) => thread
+                .parent_id
+                .as_ref()
+                .and_then(|parent_id| thread_indices.get(parent_id).copied()),
+            HistoryEntry::Context(_) => None,
+        };
+        match parent_ix {
+            // Branches of a deleted thread are shown at the top level.
+            Some(parent_ix) if parent_ix != ix => children.entry(parent_ix).or_default().push(ix),
+            _ => roots.push(ix),
+        }
+    }
+
+    let mut arranged = Vec::with_capacity(entries.len());
+    let mut visited = vec![false; entries.len()];
+    let mut trees = roots
+        .into_iter()
+        .map(|root| {
+            let mut tree = Vec::new();
+            let mut stack = vec![(root, 0)];
+            while let Some((ix, depth)) = stack.pop() {
+                if std::mem::replace(&mut visited[ix], true) {
+                    continue;
+                }
+                tree.push((ix, depth));
+                if let Some(children) = children.get(&ix) {
+                    stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+                }
+            }
+            let updated_at = tree
+                .iter()
+                .map(|(ix, _)| entries[*ix].updated_at())
+                .max()
+                .unwrap_or_else(|| entries[root].updated_at());
+            (tree, updated_at)
+        })
+        .collect::<Vec<_>>();
+    trees.sort_by_key(|(_, updated_at)| std::cmp::Reverse(*updated_at));
+
+    for (tree, updated_at) in trees {
+        arranged.extend(tree.into_iter().map(|(ix, depth)| (ix, depth, updated_at)));
+    }
+
+    // Threads whose branch points form a cycle can't be reached from a root; list them as-is.
+    for (ix, entry) in entries.iter().enumerate() {
+        if !visited[ix] {
+            arranged.push((ix, 0, entry.updated_at()));
+        }
+    }
+    arranged
+}
+
 #[derive(Clone, Copy)]
 pub enum EntryTimeFormat {
     DateAndTime,
@@ -869,7 +973,8 @@ impl Display for TimeBucket {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use agent::{ThreadId, thread_store::SerializedThreadMetadata};
+    use chrono::{NaiveDate, TimeZone as _};
 
     #[test]
     fn test_time_bucket_from_dates() {
@@ -909,4 +1014,42 @@ mod tests {
         let date = NaiveDate::from_ymd_opt(2022, 12, 28).unwrap();
         assert_eq!(TimeBucket::from_dates(new_year, date), TimeBucket::ThisWeek);
     }
+
+    #[test]
+    fn test_arrange_branches() {
+        let thread = |id: &str, hour: u32, parent_id: Option<&str>| {
+            HistoryEntry::Thread(SerializedThreadMetadata {
+                id: ThreadId::from(id),
+                summary: id.to_string().into(),
+                updated_at: Utc.with_ymd_and_hms(2023, 1, 15, hour, 0, 0).unwrap(),
+                parent_id: parent_id.map(ThreadId::from),
+            })
+        };
+        // Sorted by update time, most recent first, like `HistoryStore::entries`.
+        let entries = vec![
+            thread("branch-of-old", 9, Some("old")),
+            thread("new", 8, None),
+            thread("branch-of-branch", 7, Some("branch-of-old")),
+            thread("branch-of-deleted", 6, Some("deleted")),
+            thread("second-branch-of-old", 5, Some("old")),
+            thread("old", 1, None),
+        ];
+
+        let arranged = arrange_branches(&entries)
+            .into_iter()
+            .map(|(ix, depth, updated_at)| (entries[ix].title().to_string(), depth, updated_at))
+            .collect::<Vec<_>>();
+        let at = |hour| Utc.with_ymd_and_hms(2023, 1, 15, hour, 0, 0).unwrap();
+        assert_eq!(
+            arranged,
+            vec![
+                ("old".to_string(), 0, at(9)),
+                ("branch-of-old".to_string(), 1, at(9)),
+                ("branch-of-branch".to_string(), 2, at(9)),
+                ("second-branch-of-old".to_string(), 1, at(9)),
+                ("new".to_string(), 0, at(8)),
+                ("branch-of-deleted".to_string(), 0, at(6)),
+            ]
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use anyhow::{Context as _, Result};
 use buffer_diff::BufferDiff;
-use collections::BTreeMap;
+use collections::{BTreeMap, BTreeSet};
 use futures::{FutureExt, StreamExt, channel::mpsc};
 use gpui::{App, AppContext, AsyncApp, Context, Entity, Subscription, Task, WeakEntity};
 use language::{Anchor, Buffer, BufferEvent, DiskState, Point, ToPoint};
@@ -15,6 +15,8 @@ pub struct ActionLog {
     tracked_buffers: BTreeMap<Entity<Buffer>, TrackedBuffer>,
     /// Has the model edited a file since it last checked diagnostics?
     edited_since_project_diagnostics_check: bool,
+    /// Buffers that the model has created or edited since they were last taken.
+    recently_edited_buffers: BTreeSet<Entity<Buffer>>,
     /// The project this action log is associated with
     project: Entity<Project>,
 }
@@ -25,6 +27,7 @@ impl ActionLog {
         Self {
             tracked_buffers: BTreeMap::default(),
             edited_since_project_diagnostics_check: false,
+            recently_edited_buffers: BTreeSet::default(),
             project,
         }
     }
@@ -43,6 +46,13 @@ impl ActionLog {
         self.edited_since_project_diagnostics_check
     }
 
+    /// Returns the buffers that the model has created or edited since this was last called.
+    pub fn take_recently_edited_buffers(&mut self) -> Vec<Entity<Buffer>> {
+        std::mem::take(&mut self.recently_edited_buffers)
+            .into_iter()
+            .collect()
+    }
+
     fn track_buffer_internal(
         &mut self,
         buffer: Entity<Buffer>,
@@ -263,14 +273,30 @@ impl ActionLog {
                 let new_snapshot = buffer_snapshot.clone();
                 let unreviewed_edits = tracked_buffer.unreviewed_edits.clone();
                 async move {
-                    let edits = diff_snapshots(&old_snapshot, &new_snapshot);
-                    if let ChangeAuthor::User = author {
-                        apply_non_conflicting_edits(
-                            &unreviewed_edits,
-                            edits,
-                            &mut base_text,
-                            new_snapshot.as_rope(),
-                        );
+                    match author {
+                        ChangeAuthor::User => {
+                            let edits = diff_snapshots(&old_snapshot, &new_snapshot);
+                            apply_non_conflicting_edits(
+                                &unreviewed_edits,
+                                edits,
+                                &mut base_text,
+                                new_snapshot.as_rope(),
+                            );
+                        }
+                        ChangeAuthor::Agent => {}
+                        ChangeAuthor::UserThenAgent { user_text } => {
+                            let edits =
+                                language::line_diff(&old_snapshot.text(), &user_text.to_string())
+                                    .into_iter()
+                                    .map(|(old, new)| Edit { old, new })
+                                    .collect();
+                            apply_non_conflicting_edits(
+                                &unreviewed_edits,
+                                edits,
+                                &mut base_text,
+                                &user_text,
+                            );
+                        }
                     }
                     (Arc::new(base_text.to_string()), base_text)
                 }
@@ -464,21 +490,46 @@ impl ActionLog {
     /// Mark a buffer as created by agent, so we can refresh it in the context
     pub fn buffer_created(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
         self.edited_since_project_diagnostics_check = true;
+        self.recently_edited_buffers.insert(buffer.clone());
         self.track_buffer_internal(buffer.clone(), true, cx);
     }
 
     /// Mark a buffer as edited by agent, so we can refresh it in the context
     pub fn buffer_edited(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        self.buffer_edited_internal(buffer, ChangeAuthor::Agent, cx);
+    }
+
+    /// Mark the edits of a transaction that was applied on the agent's behalf, such as by a
+    /// language server, as made by the agent. Any other edits made to the buffer since the
+    /// transaction started are still attributed to the user.
+    pub fn buffer_edited_in_transaction(
+        &mut self,
+        buffer: Entity<Buffer>,
+        transaction: &text::Transaction,
+        cx: &mut Context<Self>,
+    ) {
+        let user_text = text_without_transaction(buffer.read(cx), transaction);
+        self.buffer_edited_internal(buffer, ChangeAuthor::UserThenAgent { user_text }, cx);
+    }
+
+    fn buffer_edited_internal(
+        &mut self,
+        buffer: Entity<Buffer>,
+        author: ChangeAuthor,
+        cx: &mut Context<Self>,
+    ) {
         self.edited_since_project_diagnostics_check = true;
+        self.recently_edited_buffers.insert(buffer.clone());
 
         let tracked_buffer = self.track_buffer_internal(buffer.clone(), false, cx);
         if let TrackedBufferStatus::Deleted = tracked_buffer.status {
             tracked_buffer.status = TrackedBufferStatus::Modified;
         }
-        tracked_buffer.schedule_diff_update(ChangeAuthor::Agent, cx);
+        tracked_buffer.schedule_diff_update(author, cx);
     }
 
     pub fn will_delete_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        self.recently_edited_buffers.remove(&buffer);
         let tracked_buffer = self.track_buffer_internal(buffer.clone(), false, cx);
         match tracked_buffer.status {
             TrackedBufferStatus::Created { .. } => {
@@ -713,6 +764,11 @@ impl ActionLog {
             .collect()
     }
 
+    /// Iterate over buffers that the model has read or edited
+    pub fn tracked_buffers(&self) -> impl Iterator<Item = &Entity<Buffer>> {
+        self.tracked_buffers.keys()
+    }
+
     /// Iterate over buffers changed since last read or edited by the model
     pub fn stale_buffers<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = &'a Entity<Buffer>> {
         self.tracked_buffers
@@ -792,6 +848,31 @@ fn apply_non_conflicting_edits(
     }
 }
 
+/// Returns the buffer's text with the edits of the given transaction reverted.
+fn text_without_transaction(buffer: &Buffer, transaction: &text::Transaction) -> Rope {
+    let old_text = buffer.rope_for_version(&transaction.start);
+    let transaction_ranges = buffer
+        .edited_ranges_for_transaction::<usize>(transaction)
+        .collect::<Vec<_>>();
+    let transaction_edits = buffer
+        .edits_since::<usize>(&transaction.start)
+        .filter(|edit| {
+            transaction_ranges
+                .iter()
+                .any(|range| range.start <= edit.new.end && edit.new.start <= range.end)
+        })
+        .collect::<Vec<_>>();
+
+    let mut text = buffer.as_rope().clone();
+    for edit in transaction_edits.into_iter().rev() {
+        text.replace(
+            edit.new,
+            &old_text.chunks_in_range(edit.old).collect::<String>(),
+        );
+    }
+    text
+}
+
 fn diff_snapshots(
     old_snapshot: &text::BufferSnapshot,
     new_snapshot: &text::BufferSnapshot,
@@ -841,10 +922,15 @@ fn point_to_row_edit(edit: Edit<Point>, old_text: &Rope, new_text: &Rope) -> Edi
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 enum ChangeAuthor {
     User,
     Agent,
+    /// The user made the edits that turn the previous snapshot into `user_text`, and the agent
+    /// made the rest.
+    UserThenAgent {
+        user_text: Rope,
+    },
 }
 
 enum TrackedBufferStatus {
@@ -992,6 +1078,91 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test(iterations = 10)]
+    async fn test_edits_applied_on_behalf_of_agent(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({"read": "abc\ndef\nghi", "unread": "jkl\nmno"}),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let open_buffer = |path: &str, cx: &mut TestAppContext| {
+            let project_path = project
+                .read_with(cx, |project, cx| project.find_project_path(path, cx))
+                .unwrap();
+            project.update(cx, |project, cx| project.open_buffer(project_path, cx))
+        };
+        let read_buffer = open_buffer("dir/read", cx).await.unwrap();
+        let unread_buffer = open_buffer("dir/unread", cx).await.unwrap();
+        action_log.update(cx, |log, cx| log.buffer_read(read_buffer.clone(), cx));
+        cx.run_until_parked();
+
+        // The user keeps typing while the agent's edits are being applied.
+        let transactions = cx.update(|cx| {
+            [&read_buffer, &unread_buffer].map(|buffer| {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.start_transaction();
+                    buffer.edit([(Point::new(1, 0)..Point::new(1, 3), "X")], None, cx);
+                    buffer.end_transaction(cx);
+                    let transaction = buffer.finalize_last_transaction().unwrap().clone();
+                    buffer.edit([(Point::new(0, 0)..Point::new(0, 0), "Z")], None, cx);
+                    transaction
+                })
+            })
+        });
+        cx.run_until_parked();
+        action_log.update(cx, |log, cx| {
+            for (buffer, transaction) in [&read_buffer, &unread_buffer]
+                .into_iter()
+                .zip(&transactions)
+            {
+                log.buffer_edited_in_transaction(buffer.clone(), transaction, cx);
+            }
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            unreviewed_hunks(&action_log, cx),
+            vec![
+                (
+                    read_buffer.clone(),
+                    vec![HunkStatus {
+                        range: Point::new(1, 0)..Point::new(2, 0),
+                        diff_status: DiffHunkStatusKind::Modified,
+                        old_text: "def\n".into(),
+                    }],
+                ),
+                (
+                    unread_buffer.clone(),
+                    vec![HunkStatus {
+                        range: Point::new(1, 0)..Point::new(1, 1),
+                        diff_status: DiffHunkStatusKind::Modified,
+                        old_text: "mno".into(),
+                    }],
+                ),
+            ]
+        );
+
+        // Later edits are attributed to the user.
+        cx.update(|cx| {
+            read_buffer.update(cx, |buffer, cx| {
+                buffer.edit([(Point::new(0, 0)..Point::new(0, 3), "Y")], None, cx)
+            })
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            unreviewed_hunks(&action_log, cx)[0].1,
+            vec![HunkStatus {
+                range: Point::new(1, 0)..Point::new(2, 0),
+                diff_status: DiffHunkStatusKind::Modified,
+                old_text: "def\n".into(),
+            }]
+        );
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_deletions(cx: &mut TestAppContext) {
         init_test(cx);
@@ -1,5 +1,6 @@
 mod action_log;
 pub mod outline;
+mod subagent;
 mod tool_registry;
 mod tool_schema;
 mod tool_working_set;
@@ -26,6 +27,7 @@ use project::Project;
 use workspace::Workspace;
 
 pub use crate::action_log::*;
+pub use crate::subagent::*;
 pub use crate::tool_registry::*;
 pub use crate::tool_schema::*;
 pub use crate::tool_working_set::*;
@@ -221,6 +223,12 @@ pub trait Tool: 'static + Send + Sync {
     /// Returns true if the tool may perform edits.
     fn may_perform_edits(&self) -> bool;
 
+    /// Returns true if the tool only reads the project or the web, without changing anything or
+    /// needing the user's confirmation, so that it's safe to run without anyone watching.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
     /// Returns the JSON schema that describes the tool's input.
     fn input_schema(&self, _: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
         Ok(serde_json::Value::Object(serde_json::Map::default()))
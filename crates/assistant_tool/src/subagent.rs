@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use gpui::{App, Global, Task};
+
+/// The name of the tool that delegates tasks to sub-agents, which sub-agents can't use themselves.
+pub const DELEGATE_TASK_TOOL_NAME: &str = "delegate_task";
+
+/// A task that an agent hands off to a sub-agent.
+#[derive(Debug, Clone)]
+pub struct SubagentTask {
+    /// The ID of the thread that delegated the task.
+    pub parent_thread_id: String,
+    /// What the sub-agent should do, in the delegating agent's words.
+    pub instructions: String,
+}
+
+/// Runs delegated tasks to completion in child threads.
+pub trait SubagentRunner: 'static + Send + Sync {
+    /// Runs the sub-agent and returns its final report.
+    fn run_subagent(&self, task: SubagentTask, cx: &mut App) -> Task<Result<String>>;
+}
+
+struct GlobalSubagentRunner(Arc<dyn SubagentRunner>);
+
+impl Global for GlobalSubagentRunner {}
+
+impl dyn SubagentRunner {
+    /// Returns the global [`SubagentRunner`], if one has been set.
+    pub fn try_global(cx: &App) -> Option<Arc<Self>> {
+        cx.try_global::<GlobalSubagentRunner>()
+            .map(|runner| runner.0.clone())
+    }
+
+    /// Sets the global [`SubagentRunner`].
+    pub fn set_global(runner: Arc<Self>, cx: &mut App) {
+        cx.set_global(GlobalSubagentRunner(runner));
+    }
+}
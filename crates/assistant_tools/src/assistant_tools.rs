@@ -1,18 +1,30 @@
+mod code_navigation;
 mod copy_path_tool;
 mod create_directory_tool;
+mod debug_tool;
+mod delegate_task_tool;
 mod delete_path_tool;
 mod diagnostics_tool;
 mod edit_agent;
 mod edit_file_tool;
 mod fetch_tool;
 mod find_path_tool;
+mod find_references_tool;
+mod git_blame_tool;
+mod git_history;
+mod git_log_tool;
+mod git_show_tool;
+mod go_to_definition_tool;
 mod grep_tool;
+mod hover_tool;
 mod list_directory_tool;
 mod move_path_tool;
 mod now_tool;
 mod open_tool;
 mod read_file_tool;
+mod rename_symbol_tool;
 mod schema;
+mod symbols_tool;
 mod templates;
 mod terminal_tool;
 mod thinking_tool;
@@ -32,13 +44,23 @@ use web_search_tool::WebSearchTool;
 pub(crate) use templates::*;
 
 use crate::create_directory_tool::CreateDirectoryTool;
+use crate::debug_tool::DebugTool;
+use crate::delegate_task_tool::DelegateTaskTool;
 use crate::delete_path_tool::DeletePathTool;
 use crate::diagnostics_tool::DiagnosticsTool;
 use crate::edit_file_tool::EditFileTool;
 use crate::fetch_tool::FetchTool;
 use crate::find_path_tool::FindPathTool;
+use crate::find_references_tool::FindReferencesTool;
+use crate::git_blame_tool::GitBlameTool;
+use crate::git_log_tool::GitLogTool;
+use crate::git_show_tool::GitShowTool;
+use crate::go_to_definition_tool::GoToDefinitionTool;
+use crate::hover_tool::HoverTool;
 use crate::list_directory_tool::ListDirectoryTool;
 use crate::now_tool::NowTool;
+use crate::rename_symbol_tool::RenameSymbolTool;
+use crate::symbols_tool::SymbolsTool;
 use crate::thinking_tool::ThinkingTool;
 
 pub use edit_file_tool::{EditFileMode, EditFileToolInput};
@@ -54,6 +76,7 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
     let registry = ToolRegistry::global(cx);
     registry.register_tool(TerminalTool::new(cx));
     registry.register_tool(CreateDirectoryTool);
+    registry.register_tool(DebugTool);
     registry.register_tool(CopyPathTool);
     registry.register_tool(DeletePathTool);
     registry.register_tool(MovePathTool);
@@ -64,7 +87,16 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
     registry.register_tool(FindPathTool);
     registry.register_tool(ReadFileTool);
     registry.register_tool(GrepTool);
+    registry.register_tool(GoToDefinitionTool);
+    registry.register_tool(FindReferencesTool);
+    registry.register_tool(SymbolsTool);
+    registry.register_tool(HoverTool);
+    registry.register_tool(RenameSymbolTool);
+    registry.register_tool(GitLogTool);
+    registry.register_tool(GitBlameTool);
+    registry.register_tool(GitShowTool);
     registry.register_tool(ThinkingTool);
+    registry.register_tool(DelegateTaskTool);
     registry.register_tool(FetchTool::new(http_client));
     registry.register_tool(EditFileTool);
 
@@ -0,0 +1,406 @@
+//! Shared pieces of the tools that navigate code through the project's language servers.
+
+use crate::ui::ToolCallCardHeader;
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{ToolCard, ToolUseStatus};
+use editor::Editor;
+use futures::channel::oneshot;
+use gpui::{App, AsyncApp, Context, Entity, IntoElement, Task, WeakEntity, Window};
+use language::{Anchor, Buffer, BufferSnapshot, Point, ToPoint as _};
+use project::Project;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, path::PathBuf};
+use ui::{Disclosure, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+/// Opens the buffer at `path` and finds the first occurrence of `symbol` on its 1-based `line`.
+pub(crate) fn symbol_position(
+    project: &Entity<Project>,
+    path: &str,
+    line: u32,
+    symbol: &str,
+    cx: &mut App,
+) -> Task<Result<(Entity<Buffer>, Anchor)>> {
+    let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+        return Task::ready(Err(anyhow!("Could not find path {path} in project")));
+    };
+    let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let path = path.to_string();
+    let symbol = symbol.to_string();
+    cx.spawn(async move |cx| {
+        let buffer = buffer.await?;
+        let anchor = buffer.read_with(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            let row = line.checked_sub(1).context("Line numbers start at 1")?;
+            anyhow::ensure!(
+                row <= snapshot.max_point().row,
+                "{path} only has {} lines",
+                snapshot.max_point().row + 1
+            );
+            let line_text = line_text(&snapshot, row);
+            let column = line_text
+                .find(&symbol)
+                .with_context(|| format!("Line {line} of {path} does not contain `{symbol}`"))?;
+            anyhow::Ok(snapshot.anchor_after(Point::new(row, column as u32)))
+        })??;
+        Ok((buffer, anchor))
+    })
+}
+
+pub(crate) fn line_text(snapshot: &BufferSnapshot, row: u32) -> String {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect()
+}
+
+/// A location in the project, as reported to the model and listed in tool cards.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CodeLocation {
+    /// The path of the file, starting with the name of its worktree.
+    pub path: PathBuf,
+    /// The 1-based line number.
+    pub line: u32,
+    /// The trimmed text of the line.
+    pub text: String,
+}
+
+impl CodeLocation {
+    pub fn from_anchor(buffer: &Entity<Buffer>, anchor: &Anchor, cx: &App) -> Option<Self> {
+        let buffer = buffer.read(cx);
+        let path = buffer.file()?.full_path(cx);
+        let snapshot = buffer.snapshot();
+        let row = anchor.to_point(&snapshot).row;
+        Some(Self {
+            path,
+            line: row + 1,
+            text: line_text(&snapshot, row).trim().to_string(),
+        })
+    }
+
+    pub fn from_location(location: &language::Location, cx: &App) -> Option<Self> {
+        Self::from_anchor(&location.buffer, &location.range.start, cx)
+    }
+}
+
+pub(crate) fn write_locations(output: &mut String, locations: &[CodeLocation]) {
+    for location in locations {
+        writeln!(
+            output,
+            "{}:{}: {}",
+            location.path.display(),
+            location.line,
+            location.text
+        )
+        .ok();
+    }
+}
+
+/// What the code navigation tools store as their output, to restore their cards.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct CodeNavigationOutput {
+    pub title: String,
+    pub symbol: String,
+    pub locations: Vec<CodeLocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl CodeNavigationOutput {
+    pub fn to_value(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+/// Shows the locations found by a code navigation tool, each of them opening the file when clicked.
+pub(crate) struct CodeNavigationCard {
+    icon: IconName,
+    output: Option<CodeNavigationOutput>,
+    symbol: String,
+    expanded: bool,
+    _receiver_task: Option<Task<Result<()>>>,
+}
+
+impl CodeNavigationCard {
+    pub fn new(
+        icon: IconName,
+        symbol: String,
+        receiver: oneshot::Receiver<CodeNavigationOutput>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let receiver_task = cx.spawn(async move |this, cx| {
+            let output = receiver.await?;
+            this.update(cx, |this, cx| {
+                this.output = Some(output);
+                cx.notify();
+            })
+            .log_err();
+            Ok(())
+        });
+        Self {
+            icon,
+            output: None,
+            symbol,
+            expanded: false,
+            _receiver_task: Some(receiver_task),
+        }
+    }
+
+    pub fn from_output(icon: IconName, output: CodeNavigationOutput) -> Self {
+        Self {
+            icon,
+            symbol: output.symbol.clone(),
+            output: Some(output),
+            expanded: false,
+            _receiver_task: None,
+        }
+    }
+}
+
+impl ToolCard for CodeNavigationCard {
+    fn render(
+        &mut self,
+        status: &ToolUseStatus,
+        _window: &mut Window,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let title: SharedString = match (&self.output, status) {
+            (Some(output), _) => output.title.clone().into(),
+            (None, ToolUseStatus::Error(_)) => "Language server request failed".into(),
+            (None, _) => "Asking the language server".into(),
+        };
+        let has_content = self.output.as_ref().map_or(false, |output| {
+            !output.locations.is_empty() || output.details.is_some()
+        });
+
+        let content = self
+            .output
+            .as_ref()
+            .filter(|_| has_content && self.expanded)
+            .map(|output| {
+                v_flex()
+                    .relative()
+                    .ml_1p5()
+                    .px_1p5()
+                    .gap_0p5()
+                    .border_l_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .children(output.details.clone().map(|details| {
+                        Label::new(details)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx)
+                    }))
+                    .children(output.locations.iter().enumerate().map(|(ix, location)| {
+                        let workspace = workspace.clone();
+                        let path = location.path.clone();
+                        let row = location.line.saturating_sub(1);
+                        Button::new(
+                            ("location", ix),
+                            format!("{}:{}", location.path.display(), location.line),
+                        )
+                        .icon(IconName::ArrowUpRight)
+                        .icon_size(IconSize::XSmall)
+                        .icon_position(IconPosition::End)
+                        .label_size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .tooltip(Tooltip::text(location.text.clone()))
+                        .on_click(move |_, window, cx| {
+                            workspace
+                                .update(cx, |workspace, cx| {
+                                    let Some(project_path) =
+                                        workspace.project().read(cx).find_project_path(&path, cx)
+                                    else {
+                                        return;
+                                    };
+                                    let open_task =
+                                        workspace.open_path(project_path, None, true, window, cx);
+                                    window
+                                        .spawn(cx, async move |cx| {
+                                            let item = open_task.await?;
+                                            if let Some(editor) = item.downcast::<Editor>() {
+                                                editor
+                                                    .update_in(cx, |editor, window, cx| {
+                                                        editor.go_to_singleton_buffer_point(
+                                                            Point::new(row, 0),
+                                                            window,
+                                                            cx,
+                                                        );
+                                                    })
+                                                    .log_err();
+                                            }
+                                            anyhow::Ok(())
+                                        })
+                                        .detach_and_log_err(cx);
+                                })
+                                .ok();
+                        })
+                    }))
+                    .into_any()
+            });
+
+        v_flex()
+            .mb_2()
+            .gap_1()
+            .child(
+                ToolCallCardHeader::new(self.icon, title)
+                    .with_code_path(self.symbol.clone())
+                    .disclosure_slot(
+                        Disclosure::new("code-navigation-disclosure", self.expanded)
+                            .opened_icon(IconName::ChevronUp)
+                            .closed_icon(IconName::ChevronDown)
+                            .disabled(!has_content)
+                            .on_click(cx.listener(move |this, _, _, _cx| {
+                                this.expanded = !this.expanded;
+                            })),
+                    ),
+            )
+            .children(content)
+    }
+}
+
+/// Sends the output to the tool's card and turns it into the tool's result.
+pub(crate) fn finish(
+    output: CodeNavigationOutput,
+    message: String,
+    sender: oneshot::Sender<CodeNavigationOutput>,
+) -> Result<assistant_tool::ToolResultOutput> {
+    let value = output.to_value()?;
+    sender.send(output).ok();
+    Ok(assistant_tool::ToolResultOutput {
+        content: assistant_tool::ToolResultContent::Text(message),
+        output: Some(value),
+    })
+}
+
+pub(crate) async fn locations_for(
+    locations: Vec<language::Location>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<CodeLocation>> {
+    cx.update(|cx| {
+        locations
+            .iter()
+            .filter_map(|location| CodeLocation::from_location(location, cx))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use assistant_tool::{ActionLog, Tool};
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use language_model::fake_provider::FakeLanguageModel;
+    use lsp::FakeLanguageServer;
+    use project::{FakeFs, lsp_store::OpenLspBufferHandle};
+    use settings::SettingsStore;
+    use std::sync::Arc;
+    use util::path;
+
+    pub(crate) const MAIN_RS: &str =
+        "fn helper() -> i32 {\n    42\n}\n\nfn main() {\n    let value = helper();\n}\n";
+
+    pub(crate) struct TestProject {
+        pub fs: Arc<FakeFs>,
+        pub project: Entity<Project>,
+        pub action_log: Entity<ActionLog>,
+        pub server: FakeLanguageServer,
+        pub buffer: Entity<Buffer>,
+        _handle: OpenLspBufferHandle,
+    }
+
+    /// Creates a project containing `files` under `/root`, with a fake Rust language server that
+    /// has the given capabilities running for `/root/src/main.rs`.
+    pub(crate) async fn test_project(
+        files: serde_json::Value,
+        capabilities: lsp::ServerCapabilities,
+        cx: &mut TestAppContext,
+    ) -> TestProject {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/root"), files).await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities,
+                ..Default::default()
+            },
+        );
+
+        let (buffer, handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let server = fake_servers.next().await.unwrap();
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+
+        TestProject {
+            fs,
+            project,
+            action_log,
+            server,
+            buffer,
+            _handle: handle,
+        }
+    }
+
+    /// Runs a code navigation tool, returning the message for the model and the card's output.
+    pub(crate) async fn run_tool(
+        tool: impl Tool,
+        input: serde_json::Value,
+        test: &TestProject,
+        cx: &mut TestAppContext,
+    ) -> Result<(String, CodeNavigationOutput)> {
+        let model = Arc::new(FakeLanguageModel::default());
+        let output = cx
+            .update(|cx| {
+                Arc::new(tool).run(
+                    input,
+                    Arc::default(),
+                    test.project.clone(),
+                    test.action_log.clone(),
+                    model,
+                    None,
+                    cx,
+                )
+            })
+            .output
+            .await?;
+        let message = output.content.as_str().unwrap().to_string();
+        let output = serde_json::from_value(output.output.unwrap())?;
+        Ok((message, output))
+    }
+
+    pub(crate) fn main_rs_url() -> lsp::Url {
+        lsp::Url::from_file_path(path!("/root/src/main.rs")).unwrap()
+    }
+
+    pub(crate) fn lsp_range(row: u32, start: u32, end: u32) -> lsp::Range {
+        lsp::Range::new(lsp::Position::new(row, start), lsp::Position::new(row, end))
+    }
+}
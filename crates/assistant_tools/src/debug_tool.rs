@@ -0,0 +1,1074 @@
+use crate::schema::json_schema_for;
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult, ToolResultContent, ToolResultOutput};
+use dap::{
+    adapters::{DebugAdapterName, DebugTaskDefinition},
+    client::SessionId,
+    debugger_settings::DebuggerSettings,
+};
+use futures::{FutureExt as _, StreamExt as _, channel::mpsc};
+use gpui::{AnyWindowHandle, App, AsyncApp, Entity, Task};
+use language::Point;
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{
+    Project,
+    debugger::{
+        breakpoint_store::{
+            Breakpoint, BreakpointEditAction, BreakpointStore, BreakpointWithPosition,
+        },
+        session::{Session, SessionEvent, SessionStateEvent, ThreadId, ThreadStatus},
+    },
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use std::{fmt::Write as _, path::Path, sync::Arc, time::Duration};
+use task::{TaskContext, TaskVariables, VariableName, substitute_variables_in_str};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+/// How long to wait for the program to stop after starting or resuming it.
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many stack frames are shown, so that deep recursion doesn't fill up the context window.
+const MAX_STACK_FRAMES: u64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugAction {
+    SetBreakpoint,
+    RemoveBreakpoint,
+    Start,
+    Continue,
+    StepOver,
+    StepIn,
+    StepOut,
+    Pause,
+    StackTrace,
+    Variables,
+    Stop,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DebugToolInput {
+    /// What to do. Possible values:
+    /// - 'set_breakpoint': Set a breakpoint at `path` and `line`, optionally with a `condition`.
+    /// - 'remove_breakpoint': Remove the breakpoint at `path` and `line`.
+    /// - 'start': Start a debug session with `adapter` and `config`, and wait for the program to stop.
+    /// - 'continue': Resume the stopped thread, and wait for the program to stop again.
+    /// - 'step_over', 'step_in', 'step_out': Step through the code of the stopped thread.
+    /// - 'pause': Pause a running thread.
+    /// - 'stack_trace': List the stack frames of the stopped thread.
+    /// - 'variables': List the variables of a stack frame, or the children of a variable.
+    /// - 'stop': End the debug session.
+    pub action: DebugAction,
+
+    /// For `set_breakpoint` and `remove_breakpoint`: the path of the file.
+    ///
+    /// This path should never be absolute, and the first component of the path should always
+    /// be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// For `set_breakpoint` and `remove_breakpoint`: the 1-based line number.
+    #[serde(default)]
+    pub line: Option<u32>,
+
+    /// For `set_breakpoint`: an expression that must be true for the program to stop.
+    #[serde(default)]
+    pub condition: Option<String>,
+
+    /// For `start`: the name of the debug adapter, like `CodeLLDB`, `Debugpy`, `JavaScript`,
+    /// `Delve` or `GDB`.
+    #[serde(default)]
+    pub adapter: Option<String>,
+
+    /// For `start`: the configuration sent to the debug adapter, as it would appear in a
+    /// scenario of `.zed/debug.json`, without `adapter`, `label` and `build`. It must contain
+    /// `"request": "launch"` or `"request": "attach"`.
+    #[serde(default)]
+    pub config: Option<serde_json::Map<String, serde_json::Value>>,
+
+    /// For `start`: a name for the debug session.
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// The debug session to act on. Defaults to the most recently started session.
+    #[serde(default)]
+    pub session_id: Option<u32>,
+
+    /// The thread to act on. Defaults to the first stopped thread, or for `pause`, the first
+    /// thread.
+    #[serde(default)]
+    pub thread_id: Option<u64>,
+
+    /// For `variables`: the stack frame to list the variables of. Defaults to the topmost frame
+    /// of the stopped thread.
+    #[serde(default)]
+    pub frame_id: Option<u64>,
+
+    /// For `variables`: the reference of a structured variable or a scope, to list its children.
+    #[serde(default)]
+    pub variables_reference: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DebugToolOutput {
+    session_id: Option<u32>,
+    status: Option<DebuggeeStatus>,
+    thread_id: Option<u64>,
+    stack_frames: Vec<DebugStackFrame>,
+    variables: Vec<DebugVariable>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DebuggeeStatus {
+    Running,
+    Stopped,
+    Terminated,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DebugStackFrame {
+    id: u64,
+    name: String,
+    /// The path of the frame's file, starting with the name of its worktree when it's in the
+    /// project.
+    path: Option<String>,
+    line: u64,
+    column: u64,
+}
+
+impl DebugStackFrame {
+    fn new(frame: dap::StackFrame, project: &Project, cx: &App) -> Self {
+        let path = frame
+            .source
+            .as_ref()
+            .and_then(|source| source.path.as_deref())
+            .map(|path| {
+                let path = Path::new(path);
+                match project.find_worktree(path, cx) {
+                    Some((worktree, relative_path)) => Path::new(worktree.read(cx).root_name())
+                        .join(relative_path)
+                        .display()
+                        .to_string(),
+                    None => path.display().to_string(),
+                }
+            });
+        Self {
+            id: frame.id,
+            name: frame.name,
+            path,
+            line: frame.line,
+            column: frame.column,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DebugVariable {
+    scope: Option<String>,
+    name: String,
+    value: String,
+    r#type: Option<String>,
+    /// When non-zero, the variable has children that can be listed with this reference.
+    variables_reference: u64,
+}
+
+impl DebugVariable {
+    fn new(variable: dap::Variable, scope: Option<String>) -> Self {
+        Self {
+            scope,
+            name: variable.name,
+            value: variable.value,
+            r#type: variable.type_,
+            variables_reference: variable.variables_reference,
+        }
+    }
+}
+
+enum StopEvent {
+    Stopped(Option<ThreadId>),
+    Terminated,
+}
+
+pub struct DebugTool;
+
+impl Tool for DebugTool {
+    fn name(&self) -> String {
+        "debug".into()
+    }
+
+    fn needs_confirmation(&self, input: &serde_json::Value, _: &App) -> bool {
+        // Starting a session runs a program, so it's confirmed like a terminal command.
+        match serde_json::from_value::<DebugToolInput>(input.clone()) {
+            Ok(input) => input.action == DebugAction::Start,
+            Err(_) => true,
+        }
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./debug_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Debug
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<DebugToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        let Ok(input) = serde_json::from_value::<DebugToolInput>(input.clone()) else {
+            return "Debug".to_string();
+        };
+        let location = match (&input.path, input.line) {
+            (Some(path), Some(line)) => MarkdownInlineCode(&format!("{path}:{line}")).to_string(),
+            _ => String::new(),
+        };
+        match input.action {
+            DebugAction::SetBreakpoint => format!("Set breakpoint at {location}"),
+            DebugAction::RemoveBreakpoint => format!("Remove breakpoint at {location}"),
+            DebugAction::Start => match input.label.or(input.adapter) {
+                Some(label) => format!("Start debugging {}", MarkdownInlineCode(&label)),
+                None => "Start debugging".to_string(),
+            },
+            DebugAction::Continue => "Continue debugging".to_string(),
+            DebugAction::StepOver => "Step over".to_string(),
+            DebugAction::StepIn => "Step in".to_string(),
+            DebugAction::StepOut => "Step out".to_string(),
+            DebugAction::Pause => "Pause debugging".to_string(),
+            DebugAction::StackTrace => "Read stack trace".to_string(),
+            DebugAction::Variables => "Read variables".to_string(),
+            DebugAction::Stop => "Stop debugging".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<DebugToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        match input.action {
+            DebugAction::SetBreakpoint | DebugAction::RemoveBreakpoint => {
+                edit_breakpoint(input, project, cx)
+            }
+            DebugAction::Start => start(input, project, cx),
+            DebugAction::Continue
+            | DebugAction::StepOver
+            | DebugAction::StepIn
+            | DebugAction::StepOut
+            | DebugAction::Pause => resume(input, project, cx),
+            DebugAction::StackTrace => stack_trace(input, project, cx),
+            DebugAction::Variables => variables(input, project, cx),
+            DebugAction::Stop => stop(input, project, cx),
+        }
+        .into()
+    }
+}
+
+fn edit_breakpoint(
+    input: DebugToolInput,
+    project: Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<ToolResultOutput>> {
+    let (Some(path), Some(line)) = (input.path, input.line) else {
+        return Task::ready(Err(anyhow!(
+            "Setting or removing a breakpoint requires a path and a line"
+        )));
+    };
+    let Some(project_path) = project.read(cx).find_project_path(&path, cx) else {
+        return Task::ready(Err(anyhow!("Could not find path {path} in project")));
+    };
+    let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let breakpoint_store = project.read(cx).breakpoint_store();
+
+    cx.spawn(async move |cx| {
+        let buffer = buffer.await?;
+        let row = line.checked_sub(1).context("Line numbers start at 1")?;
+        let message = breakpoint_store.update(cx, |store, cx| {
+            let abs_path = BreakpointStore::abs_path_from_buffer(&buffer, cx)
+                .with_context(|| format!("{path} isn't a local file"))?;
+            let max_row = buffer.read(cx).max_point().row;
+            anyhow::ensure!(row <= max_row, "{path} only has {} lines", max_row + 1);
+
+            let existing = store.breakpoint_at_row(&abs_path, row, cx);
+            let location = MarkdownInlineCode(&format!("{path}:{line}"));
+            match (input.action, existing, input.condition) {
+                (DebugAction::SetBreakpoint, Some((buffer, breakpoint)), Some(condition)) => {
+                    store.toggle_breakpoint(
+                        buffer,
+                        breakpoint,
+                        BreakpointEditAction::EditCondition(condition.into()),
+                        cx,
+                    );
+                    Ok(format!(
+                        "Changed the condition of the breakpoint at {location}."
+                    ))
+                }
+                (DebugAction::SetBreakpoint, Some(_), None) => {
+                    Ok(format!("There already is a breakpoint at {location}."))
+                }
+                (DebugAction::SetBreakpoint, None, condition) => {
+                    let position = buffer.read(cx).anchor_before(Point::new(row, 0));
+                    let breakpoint = BreakpointWithPosition {
+                        position,
+                        bp: Breakpoint {
+                            condition: condition.map(Into::into),
+                            ..Breakpoint::new_standard()
+                        },
+                    };
+                    store.toggle_breakpoint(
+                        buffer.clone(),
+                        breakpoint,
+                        BreakpointEditAction::Toggle,
+                        cx,
+                    );
+                    Ok(format!("Set a breakpoint at {location}."))
+                }
+                (_, Some((buffer, breakpoint)), _) => {
+                    store.toggle_breakpoint(buffer, breakpoint, BreakpointEditAction::Toggle, cx);
+                    Ok(format!("Removed the breakpoint at {location}."))
+                }
+                (_, None, _) => Err(anyhow!("There is no breakpoint at {location}.")),
+            }
+        })??;
+        Ok(message.into())
+    })
+}
+
+fn start(
+    input: DebugToolInput,
+    project: Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<ToolResultOutput>> {
+    let Some(adapter) = input.adapter else {
+        return Task::ready(Err(anyhow!("Starting a debug session requires an adapter")));
+    };
+    if project.read(cx).is_via_collab() {
+        return Task::ready(Err(anyhow!(
+            "Debug sessions can't be started in a shared project"
+        )));
+    }
+    let Some(worktree) = project.read(cx).visible_worktrees(cx).next() else {
+        return Task::ready(Err(anyhow!("The project has no worktrees to debug")));
+    };
+
+    let worktree_root = worktree.read(cx).abs_path();
+    let task_context = TaskContext {
+        cwd: Some(worktree_root.to_path_buf()),
+        task_variables: TaskVariables::from_iter([(
+            VariableName::WorktreeRoot,
+            worktree_root.to_string_lossy().into_owned(),
+        )]),
+        project_env: Default::default(),
+    };
+    let mut config = serde_json::Value::Object(input.config.unwrap_or_default());
+    substitute_variables(&mut config, &task_context);
+    let label = input.label.unwrap_or_else(|| adapter.clone());
+    let definition = DebugTaskDefinition {
+        label: label.clone().into(),
+        adapter: DebugAdapterName(adapter.into()),
+        config,
+        tcp_connection: None,
+    };
+
+    let dap_store = project.read(cx).dap_store();
+    let session = dap_store.update(cx, |dap_store, cx| {
+        dap_store.new_session(
+            label.into(),
+            definition.adapter.clone(),
+            task_context,
+            None,
+            cx,
+        )
+    });
+    let stop_event = wait_for_stop(&session, cx);
+    let boot = dap_store.update(cx, |dap_store, cx| {
+        dap_store.boot_session(session.clone(), definition, worktree, cx)
+    });
+
+    cx.spawn(async move |cx| {
+        if let Err(error) = boot.await {
+            session
+                .update(cx, |session, cx| session.shutdown(cx))?
+                .await;
+            return Err(error.context("Failed to start the debug session"));
+        }
+        describe_stop(&session, stop_event.await, &project, cx).await
+    })
+}
+
+fn resume(
+    input: DebugToolInput,
+    project: Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<ToolResultOutput>> {
+    let session = match session_for_input(&project, input.session_id, cx) {
+        Ok(session) => session,
+        Err(err) => return Task::ready(Err(err)),
+    };
+    let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+
+    cx.spawn(async move |cx| {
+        let thread_id = if input.action == DebugAction::Pause {
+            running_thread(&session, input.thread_id, cx).await?
+        } else {
+            stopped_thread(&session, input.thread_id, cx).await?
+        };
+        let stop_event = cx.update(|cx| {
+            let stop_event = wait_for_stop(&session, cx);
+            session.update(cx, |session, cx| match input.action {
+                DebugAction::Continue => session.continue_thread(thread_id, cx),
+                DebugAction::StepOver => session.step_over(thread_id, granularity, cx),
+                DebugAction::StepIn => session.step_in(thread_id, granularity, cx),
+                DebugAction::StepOut => session.step_out(thread_id, granularity, cx),
+                _ => session.pause_thread(thread_id, cx),
+            });
+            stop_event
+        })?;
+        describe_stop(&session, stop_event.await, &project, cx).await
+    })
+}
+
+fn stack_trace(
+    input: DebugToolInput,
+    project: Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<ToolResultOutput>> {
+    let session = match session_for_input(&project, input.session_id, cx) {
+        Ok(session) => session,
+        Err(err) => return Task::ready(Err(err)),
+    };
+
+    cx.spawn(async move |cx| {
+        let thread_id = stopped_thread(&session, input.thread_id, cx).await?;
+        describe_stop(
+            &session,
+            Some(StopEvent::Stopped(Some(thread_id))),
+            &project,
+            cx,
+        )
+        .await
+    })
+}
+
+fn variables(
+    input: DebugToolInput,
+    project: Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<ToolResultOutput>> {
+    let session = match session_for_input(&project, input.session_id, cx) {
+        Ok(session) => session,
+        Err(err) => return Task::ready(Err(err)),
+    };
+
+    cx.spawn(async move |cx| {
+        let session_id = session.read_with(cx, |session, _| session.session_id().0)?;
+        let mut thread_id = input.thread_id.map(ThreadId);
+        let mut variables = Vec::new();
+        let mut skipped_scopes = Vec::new();
+        if let Some(variables_reference) = input.variables_reference {
+            let children = session
+                .read_with(cx, |session, _| session.fetch_variables(variables_reference))?
+                .await?;
+            variables.extend(
+                children
+                    .into_iter()
+                    .map(|variable| DebugVariable::new(variable, None)),
+            );
+        } else {
+            let frame_id = match input.frame_id {
+                Some(frame_id) => frame_id,
+                None => {
+                    let stopped_thread_id = stopped_thread(&session, input.thread_id, cx).await?;
+                    thread_id = Some(stopped_thread_id);
+                    session
+                        .read_with(cx, |session, _| {
+                            session.fetch_stack_frames(stopped_thread_id, Some(1))
+                        })?
+                        .await?
+                        .first()
+                        .context("The stopped thread has no stack frames")?
+                        .id
+                }
+            };
+            let scopes = session
+                .read_with(cx, |session, _| session.fetch_scopes(frame_id))?
+                .await?;
+            for scope in scopes {
+                // Expensive scopes, like registers, are only listed when asked for explicitly.
+                if scope.expensive {
+                    skipped_scopes.push(scope);
+                    continue;
+                }
+                let children = session
+                    .read_with(cx, |session, _| {
+                        session.fetch_variables(scope.variables_reference)
+                    })?
+                    .await?;
+                variables.extend(
+                    children
+                        .into_iter()
+                        .map(|variable| DebugVariable::new(variable, Some(scope.name.clone()))),
+                );
+            }
+        }
+
+        let mut message = String::new();
+        let mut current_scope = None;
+        for variable in &variables {
+            if variable.scope != current_scope {
+                current_scope = variable.scope.clone();
+                if let Some(scope) = &current_scope {
+                    writeln!(message, "{scope}:")?;
+                }
+            }
+            write!(message, "- `{}`", variable.name)?;
+            if let Some(ty) = &variable.r#type {
+                write!(message, ": {ty}")?;
+            }
+            write!(message, " = {}", variable.value)?;
+            if variable.variables_reference != 0 {
+                write!(
+                    message,
+                    " (variables_reference: {})",
+                    variable.variables_reference
+                )?;
+            }
+            writeln!(message)?;
+        }
+        if variables.is_empty() {
+            writeln!(message, "No variables.")?;
+        }
+        for scope in skipped_scopes {
+            writeln!(
+                message,
+                "\nThe {} scope is expensive to read and was skipped. Its variables_reference is {}.",
+                scope.name, scope.variables_reference
+            )?;
+        }
+
+        finish(
+            message,
+            DebugToolOutput {
+                session_id: Some(session_id),
+                status: Some(DebuggeeStatus::Stopped),
+                thread_id: thread_id.map(|thread_id| thread_id.0),
+                variables,
+                ..Default::default()
+            },
+        )
+    })
+}
+
+fn stop(
+    input: DebugToolInput,
+    project: Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<ToolResultOutput>> {
+    let session = match session_for_input(&project, input.session_id, cx) {
+        Ok(session) => session,
+        Err(err) => return Task::ready(Err(err)),
+    };
+    let session_id = session.read(cx).session_id().0;
+    let shutdown = session.update(cx, |session, cx| session.shutdown(cx));
+
+    cx.spawn(async move |_| {
+        shutdown.await;
+        finish(
+            format!("Stopped debug session {session_id}."),
+            DebugToolOutput {
+                session_id: Some(session_id),
+                status: Some(DebuggeeStatus::Terminated),
+                ..Default::default()
+            },
+        )
+    })
+}
+
+/// Finds the session that the input refers to, or the most recently started one.
+fn session_for_input(
+    project: &Entity<Project>,
+    session_id: Option<u32>,
+    cx: &App,
+) -> Result<Entity<Session>> {
+    let dap_store = project.read(cx).dap_store();
+    let dap_store = dap_store.read(cx);
+    match session_id {
+        Some(session_id) => dap_store
+            .session_by_id(SessionId(session_id))
+            .with_context(|| format!("There is no debug session with id {session_id}")),
+        None => dap_store
+            .sessions()
+            .filter(|session| !session.read(cx).is_terminated())
+            .max_by_key(|session| session.read(cx).session_id())
+            .cloned()
+            .context("No debug session is running. Start one with the `start` action."),
+    }
+}
+
+async fn stopped_thread(
+    session: &Entity<Session>,
+    thread_id: Option<u64>,
+    cx: &mut AsyncApp,
+) -> Result<ThreadId> {
+    if let Some(thread_id) = thread_id {
+        return Ok(ThreadId(thread_id));
+    }
+    let threads = session
+        .read_with(cx, |session, _| session.fetch_threads())?
+        .await?;
+    session.read_with(cx, |session, _| {
+        threads
+            .iter()
+            .map(|thread| ThreadId(thread.id))
+            .find(|thread_id| session.thread_status(*thread_id) == ThreadStatus::Stopped)
+            .context("No thread is stopped. Set a breakpoint and continue, or pause the program.")
+    })?
+}
+
+async fn running_thread(
+    session: &Entity<Session>,
+    thread_id: Option<u64>,
+    cx: &mut AsyncApp,
+) -> Result<ThreadId> {
+    if let Some(thread_id) = thread_id {
+        return Ok(ThreadId(thread_id));
+    }
+    let threads = session
+        .read_with(cx, |session, _| session.fetch_threads())?
+        .await?;
+    threads
+        .first()
+        .map(|thread| ThreadId(thread.id))
+        .context("The program has no threads")
+}
+
+/// Resolves once the session stops or ends, or with `None` if it keeps running for longer than
+/// [`STOP_TIMEOUT`]. Events are recorded right away, but the timeout only starts once the future
+/// is awaited, so that it doesn't count the time spent booting the session.
+fn wait_for_stop(
+    session: &Entity<Session>,
+    cx: &mut App,
+) -> impl Future<Output = Option<StopEvent>> + use<> {
+    let (tx, mut rx) = mpsc::unbounded();
+    let subscriptions = [
+        cx.subscribe(session, {
+            let tx = tx.clone();
+            move |_, event: &SessionEvent, _| {
+                if let SessionEvent::Stopped(thread_id) = event {
+                    tx.unbounded_send(StopEvent::Stopped(*thread_id)).ok();
+                }
+            }
+        }),
+        cx.subscribe(session, move |_, event: &SessionStateEvent, _| {
+            if *event == SessionStateEvent::Shutdown {
+                tx.unbounded_send(StopEvent::Terminated).ok();
+            }
+        }),
+    ];
+    let executor = cx.background_executor().clone();
+    async move {
+        let _subscriptions = subscriptions;
+        let timeout = executor.timer(STOP_TIMEOUT);
+        futures::select_biased! {
+            event = rx.next() => event,
+            _ = timeout.fuse() => None,
+        }
+    }
+}
+
+async fn describe_stop(
+    session: &Entity<Session>,
+    event: Option<StopEvent>,
+    project: &Entity<Project>,
+    cx: &mut AsyncApp,
+) -> Result<ToolResultOutput> {
+    let session_id = session.read_with(cx, |session, _| session.session_id().0)?;
+    let mut output = DebugToolOutput {
+        session_id: Some(session_id),
+        ..Default::default()
+    };
+    let thread_id = match event {
+        Some(StopEvent::Stopped(thread_id)) => {
+            stopped_thread(session, thread_id.map(|thread_id| thread_id.0), cx).await?
+        }
+        Some(StopEvent::Terminated) => {
+            output.status = Some(DebuggeeStatus::Terminated);
+            return finish(format!("Debug session {session_id} ended."), output);
+        }
+        None => {
+            output.status = Some(DebuggeeStatus::Running);
+            return finish(
+                format!(
+                    "Debug session {session_id} is running and didn't stop within {} seconds. \
+                    Set a breakpoint and continue, or pause the program.",
+                    STOP_TIMEOUT.as_secs()
+                ),
+                output,
+            );
+        }
+    };
+
+    let stack_frames = session
+        .read_with(cx, |session, _| {
+            session.fetch_stack_frames(thread_id, Some(MAX_STACK_FRAMES))
+        })?
+        .await?;
+    output.status = Some(DebuggeeStatus::Stopped);
+    output.thread_id = Some(thread_id.0);
+    output.stack_frames = project.read_with(cx, |project, cx| {
+        stack_frames
+            .into_iter()
+            .map(|frame| DebugStackFrame::new(frame, project, cx))
+            .collect()
+    })?;
+
+    let mut message = format!(
+        "Debug session {session_id} is stopped in thread {}.\n\nStack trace:\n",
+        thread_id.0
+    );
+    for frame in &output.stack_frames {
+        write!(message, "- Frame {}: `{}`", frame.id, frame.name)?;
+        if let Some(path) = &frame.path {
+            write!(message, " at {path}:{}:{}", frame.line, frame.column)?;
+        }
+        writeln!(message)?;
+    }
+    finish(message, output)
+}
+
+fn finish(message: String, output: DebugToolOutput) -> Result<ToolResultOutput> {
+    Ok(ToolResultOutput {
+        content: ToolResultContent::Text(message),
+        output: Some(serde_json::to_value(output)?),
+    })
+}
+
+/// Replaces variables like `$ZED_WORKTREE_ROOT` in the strings of a debug configuration.
+fn substitute_variables(config: &mut serde_json::Value, task_context: &TaskContext) {
+    match config {
+        serde_json::Value::Object(object) => object
+            .values_mut()
+            .for_each(|value| substitute_variables(value, task_context)),
+        serde_json::Value::Array(array) => array
+            .iter_mut()
+            .for_each(|value| substitute_variables(value, task_context)),
+        serde_json::Value::String(string) => {
+            if let Some(substituted) = substitute_variables_in_str(string, task_context) {
+                *string = substituted;
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap::{
+        DapRegistry, FakeAdapter,
+        client::DebugAdapterClient,
+        requests::{Next, Scopes, StackTrace, Threads, Variables},
+    };
+    use gpui::{AppContext, TestAppContext};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            DebuggerSettings::register(cx);
+            DapRegistry::global(cx).add_adapter(Arc::new(FakeAdapter::new()));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_debug_session(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "main.rs": "fn main() {\n    let answer = 42;\n    let names = vec![\"a\"];\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+
+        let output = run_tool(
+            json!({ "action": "set_breakpoint", "path": "project/main.rs", "line": 3 }),
+            &project,
+            cx,
+        )
+        .await;
+        assert_eq!(text(&output), "Set a breakpoint at `project/main.rs:3`.");
+        let breakpoints = project.read_with(cx, |project, cx| {
+            project
+                .breakpoint_store()
+                .read(cx)
+                .all_source_breakpoints(cx)
+        });
+        assert_eq!(
+            breakpoints[Path::new(path!("/project/main.rs"))]
+                .iter()
+                .map(|breakpoint| breakpoint.row)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+
+        let line = Arc::new(AtomicU64::new(3));
+        let client = Rc::new(RefCell::new(None));
+        let _subscription = project::debugger::test::intercept_debug_sessions(cx, {
+            let line = line.clone();
+            let client = client.clone();
+            move |adapter_client| {
+                register_handlers(adapter_client, line.clone());
+                client.replace(Some(adapter_client.clone()));
+            }
+        });
+
+        let start = run_tool(
+            json!({
+                "action": "start",
+                "adapter": FakeAdapter::ADAPTER_NAME,
+                "config": { "request": "launch" },
+            }),
+            &project,
+            cx,
+        );
+        cx.run_until_parked();
+        let client: Arc<DebugAdapterClient> = client.borrow().clone().unwrap();
+        client
+            .fake_event(dap::messages::Events::Stopped(stopped_event(
+                dap::StoppedEventReason::Breakpoint,
+            )))
+            .await;
+        let output = start.await;
+        assert_eq!(
+            text(&output),
+            "Debug session 0 is stopped in thread 1.\n\n\
+            Stack trace:\n\
+            - Frame 1: `main` at project/main.rs:3:5\n"
+        );
+        let structured_output = output.output.unwrap();
+        assert_eq!(structured_output["status"], "stopped");
+        assert_eq!(
+            structured_output["stack_frames"][0]["path"],
+            "project/main.rs"
+        );
+
+        let output = run_tool(json!({ "action": "variables" }), &project, cx).await;
+        assert_eq!(
+            text(&output),
+            "Locals:\n\
+            - `answer`: i32 = 42\n\
+            - `names`: Vec<&str> = size=1 (variables_reference: 3)\n\
+            \n\
+            The Registers scope is expensive to read and was skipped. Its variables_reference is 4.\n"
+        );
+
+        let output = run_tool(
+            json!({ "action": "variables", "variables_reference": 3 }),
+            &project,
+            cx,
+        )
+        .await;
+        assert_eq!(text(&output), "- `[0]`: &str = \"a\"\n");
+
+        let step = run_tool(json!({ "action": "step_over" }), &project, cx);
+        cx.run_until_parked();
+        client
+            .fake_event(dap::messages::Events::Stopped(stopped_event(
+                dap::StoppedEventReason::Step,
+            )))
+            .await;
+        let output = step.await;
+        assert_eq!(
+            text(&output),
+            "Debug session 0 is stopped in thread 1.\n\n\
+            Stack trace:\n\
+            - Frame 1: `main` at project/main.rs:4:5\n"
+        );
+
+        let output = run_tool(json!({ "action": "stop" }), &project, cx).await;
+        assert_eq!(text(&output), "Stopped debug session 0.");
+        cx.run_until_parked();
+
+        let result = cx
+            .update(|cx| tool_output(json!({ "action": "variables" }), &project, cx))
+            .await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "No debug session is running. Start one with the `start` action."
+        );
+    }
+
+    fn register_handlers(client: &Arc<DebugAdapterClient>, line: Arc<AtomicU64>) {
+        client.on_request::<Threads, _>(|_, _| {
+            Ok(dap::ThreadsResponse {
+                threads: vec![dap::Thread {
+                    id: 1,
+                    name: "main".into(),
+                }],
+            })
+        });
+        client.on_request::<StackTrace, _>({
+            let line = line.clone();
+            move |_, args| {
+                assert_eq!(args.thread_id, 1);
+                Ok(dap::StackTraceResponse {
+                    stack_frames: vec![dap::StackFrame {
+                        id: 1,
+                        name: "main".into(),
+                        source: Some(dap::Source {
+                            name: Some("main.rs".into()),
+                            path: Some(path!("/project/main.rs").into()),
+                            source_reference: None,
+                            presentation_hint: None,
+                            origin: None,
+                            sources: None,
+                            adapter_data: None,
+                            checksums: None,
+                        }),
+                        line: line.load(Ordering::SeqCst),
+                        column: 5,
+                        end_line: None,
+                        end_column: None,
+                        can_restart: None,
+                        instruction_pointer_reference: None,
+                        module_id: None,
+                        presentation_hint: None,
+                    }],
+                    total_frames: None,
+                })
+            }
+        });
+        client.on_request::<Scopes, _>(|_, args| {
+            assert_eq!(args.frame_id, 1);
+            Ok(dap::ScopesResponse {
+                scopes: vec![scope("Locals", 2, false), scope("Registers", 4, true)],
+            })
+        });
+        client.on_request::<Variables, _>(|_, args| {
+            let variables = match args.variables_reference {
+                2 => vec![
+                    variable("answer", "42", "i32", 0),
+                    variable("names", "size=1", "Vec<&str>", 3),
+                ],
+                3 => vec![variable("[0]", "\"a\"", "&str", 0)],
+                reference => panic!("unexpected variables reference {reference}"),
+            };
+            Ok(dap::VariablesResponse { variables })
+        });
+        client.on_request::<Next, _>(move |_, _| {
+            line.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+    }
+
+    fn stopped_event(reason: dap::StoppedEventReason) -> dap::StoppedEvent {
+        dap::StoppedEvent {
+            reason,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }
+    }
+
+    fn scope(name: &str, variables_reference: u64, expensive: bool) -> dap::Scope {
+        dap::Scope {
+            name: name.into(),
+            presentation_hint: None,
+            variables_reference,
+            named_variables: None,
+            indexed_variables: None,
+            expensive,
+            source: None,
+            line: None,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    fn variable(name: &str, value: &str, ty: &str, variables_reference: u64) -> dap::Variable {
+        dap::Variable {
+            name: name.into(),
+            value: value.into(),
+            type_: Some(ty.into()),
+            presentation_hint: None,
+            evaluate_name: None,
+            variables_reference,
+            named_variables: None,
+            indexed_variables: None,
+            memory_reference: None,
+            declaration_location_reference: None,
+            value_location_reference: None,
+        }
+    }
+
+    fn tool_output(
+        input: serde_json::Value,
+        project: &Entity<Project>,
+        cx: &mut App,
+    ) -> Task<Result<ToolResultOutput>> {
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let model = Arc::new(FakeLanguageModel::default());
+        Arc::new(DebugTool)
+            .run(
+                input,
+                Arc::default(),
+                project.clone(),
+                action_log,
+                model,
+                None,
+                cx,
+            )
+            .output
+    }
+
+    fn run_tool(
+        input: serde_json::Value,
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> impl Future<Output = ToolResultOutput> + use<> {
+        let output = cx.update(|cx| tool_output(input, project, cx));
+        async move { output.await.unwrap() }
+    }
+
+    fn text(output: &ToolResultOutput) -> &str {
+        output.content.as_str().unwrap()
+    }
+}
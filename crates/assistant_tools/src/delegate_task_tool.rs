@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use crate::schema::json_schema_for;
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{
+    ActionLog, DELEGATE_TASK_TOOL_NAME, SubagentRunner, SubagentTask, Tool, ToolResult,
+};
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DelegateTaskToolInput {
+    /// A short description of the task, shown to the user while the sub-agent works on it.
+    ///
+    /// <example>
+    /// Find the implementations of the `Fs` trait
+    /// </example>
+    pub title: String,
+
+    /// Everything the sub-agent needs to know to complete the task, and what its report should
+    /// contain. The sub-agent doesn't see the rest of this conversation.
+    pub instructions: String,
+}
+
+pub struct DelegateTaskTool;
+
+impl Tool for DelegateTaskTool {
+    fn name(&self) -> String {
+        DELEGATE_TASK_TOOL_NAME.to_string()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./delegate_task_tool/description.md").to_string()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::UserGroup
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<DelegateTaskToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<DelegateTaskToolInput>(input.clone()) {
+            Ok(input) => format!("Delegate {}", MarkdownInlineCode(&input.title)),
+            Err(_) => "Delegate task".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        request: Arc<LanguageModelRequest>,
+        _project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<DelegateTaskToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let Some(parent_thread_id) = request.thread_id.clone() else {
+            return Task::ready(Err(anyhow!("Tasks can only be delegated from a thread"))).into();
+        };
+        let Some(runner) = <dyn SubagentRunner>::try_global(cx) else {
+            return Task::ready(Err(anyhow!("Sub-agents are not available"))).into();
+        };
+
+        let report = runner.run_subagent(
+            SubagentTask {
+                parent_thread_id,
+                instructions: input.instructions,
+            },
+            cx,
+        );
+        cx.background_spawn(async move {
+            let report = report
+                .await
+                .with_context(|| format!("Delegated task failed: {}", input.title))?;
+            Ok(report.into())
+        })
+        .into()
+    }
+}
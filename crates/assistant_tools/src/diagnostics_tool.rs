@@ -54,6 +54,10 @@ impl Tool for DiagnosticsTool {
         false
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> String {
         include_str!("./diagnostics_tool/description.md").into()
     }
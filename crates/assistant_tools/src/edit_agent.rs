@@ -61,6 +61,16 @@ impl Template for EditFileDiffFencedPromptTemplate {
     const TEMPLATE_NAME: &'static str = "edit_file_prompt_diff_fenced.hbs";
 }
 
+#[derive(Serialize)]
+struct EditFileUnifiedDiffPromptTemplate {
+    path: Option<PathBuf>,
+    edit_description: String,
+}
+
+impl Template for EditFileUnifiedDiffPromptTemplate {
+    const TEMPLATE_NAME: &'static str = "edit_file_prompt_unified_diff.hbs";
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum EditAgentOutputEvent {
     ResolvingEditRange(Range<Anchor>),
@@ -238,6 +248,11 @@ impl EditAgent {
                     edit_description,
                 }
                 .render(&this.templates)?,
+                EditFormat::UnifiedDiff => EditFileUnifiedDiffPromptTemplate {
+                    path,
+                    edit_description,
+                }
+                .render(&this.templates)?,
             };
 
             let edit_chunks = this
@@ -398,6 +413,9 @@ impl EditAgent {
                     }
                 }
             }
+            for event in parser.flush() {
+                tx.unbounded_send(Ok(event))?;
+            }
             Ok(EditAgentOutput {
                 raw_edits,
                 parser_metrics: parser.finish(),
@@ -903,6 +921,72 @@ mod tests {
         );
     }
 
+    #[gpui::test(iterations = 100)]
+    async fn test_unified_diff(cx: &mut TestAppContext, mut rng: StdRng) {
+        let agent = EditAgent {
+            edit_format: EditFormat::UnifiedDiff,
+            ..init_test(cx).await
+        };
+        let buffer = cx.new(|cx| {
+            Buffer::local(
+                indoc! {"
+                    fn main() {
+                        let x = 1;
+                        println!(\"{}\", x);
+                    }
+
+                    fn helper() {
+                        let y = 1;
+                    }
+                "},
+                cx,
+            )
+        });
+        let (apply, _events) = agent.edit(
+            buffer.clone(),
+            String::new(),
+            &LanguageModelRequest::default(),
+            &mut cx.to_async(),
+        );
+        cx.run_until_parked();
+
+        // Hunks are located by their content, even when their line numbers
+        // are wrong or missing.
+        simulate_llm_output(
+            &agent,
+            indoc! {"
+                ```diff
+                @@ -40,3 +40,3 @@
+                 fn helper() {
+                -    let y = 1;
+                +    let y = 2;
+                 }
+                @@ ... @@
+                     let x = 1;
+                -    println!(\"{}\", x);
+                +    println!(\"x = {}\", x);
+                ```
+            "},
+            &mut rng,
+            cx,
+        );
+        apply.await.unwrap();
+
+        pretty_assertions::assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.snapshot().text()),
+            indoc! {"
+                fn main() {
+                    let x = 1;
+                    println!(\"x = {}\", x);
+                }
+
+                fn helper() {
+                    let y = 2;
+                }
+            "}
+        );
+    }
+
     #[gpui::test(iterations = 100)]
     async fn test_old_text_hallucination(cx: &mut TestAppContext, mut rng: StdRng) {
         let agent = init_test(cx).await;
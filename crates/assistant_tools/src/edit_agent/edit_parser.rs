@@ -1,3 +1,4 @@
+use agent_settings::EditFormatSetting;
 use anyhow::bail;
 use derive_more::{Add, AddAssign};
 use language_model::LanguageModel;
@@ -5,7 +6,7 @@ use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
-use std::{mem, ops::Range, str::FromStr, sync::Arc};
+use std::{iter, mem, ops::Range, str::FromStr, sync::Arc};
 
 const OLD_TEXT_END_TAG: &str = "</old_text>";
 const NEW_TEXT_END_TAG: &str = "</new_text>";
@@ -13,6 +14,10 @@ const EDITS_END_TAG: &str = "</edits>";
 const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
 const SEPARATOR_MARKER: &str = "=======";
 const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+const HUNK_MARKER: &str = "@@";
+const OLD_FILE_MARKER: &str = "--- ";
+const NEW_FILE_MARKER: &str = "+++ ";
+const CODE_FENCE: &str = "```";
 const END_TAGS: [&str; 3] = [OLD_TEXT_END_TAG, NEW_TEXT_END_TAG, EDITS_END_TAG];
 
 #[derive(Debug)]
@@ -58,6 +63,20 @@ pub enum EditFormat {
     /// >>>>>>> REPLACE
     /// ```
     DiffFenced,
+    /// Unified diff hunks, in which:
+    /// - Text before the first hunk header is ignored, including file headers
+    /// - Line numbers in hunk headers are optional, and only used as a hint
+    /// - Line counts in hunk headers are ignored
+    ///
+    /// Example:
+    ///
+    /// ```diff
+    /// @@ -42,3 +42,3 @@
+    ///  ...
+    /// -...
+    /// +...
+    /// ```
+    UnifiedDiff,
 }
 
 impl FromStr for EditFormat {
@@ -67,11 +86,22 @@ impl FromStr for EditFormat {
         match s.to_lowercase().as_str() {
             "xml_tags" | "xml" => Ok(EditFormat::XmlTags),
             "diff_fenced" | "diff-fenced" | "diff" => Ok(EditFormat::DiffFenced),
+            "unified_diff" | "unified-diff" | "udiff" => Ok(EditFormat::UnifiedDiff),
             _ => bail!("Unknown EditFormat: {}", s),
         }
     }
 }
 
+impl From<EditFormatSetting> for EditFormat {
+    fn from(setting: EditFormatSetting) -> Self {
+        match setting {
+            EditFormatSetting::XmlTags => EditFormat::XmlTags,
+            EditFormatSetting::DiffFenced => EditFormat::DiffFenced,
+            EditFormatSetting::UnifiedDiff => EditFormat::UnifiedDiff,
+        }
+    }
+}
+
 impl EditFormat {
     /// Return an optimal edit format for the language model
     pub fn from_model(model: Arc<dyn LanguageModel>) -> anyhow::Result<Self> {
@@ -94,6 +124,11 @@ impl EditFormat {
 
 pub trait EditFormatParser: Send + std::fmt::Debug {
     fn push(&mut self, chunk: &str) -> SmallVec<[EditParserEvent; 1]>;
+    /// Called once the model is done streaming, for formats in which the last
+    /// edit is only terminated by the end of the output.
+    fn flush(&mut self) -> SmallVec<[EditParserEvent; 1]> {
+        SmallVec::new()
+    }
     fn take_metrics(&mut self) -> EditParserMetrics;
 }
 
@@ -126,6 +161,31 @@ enum DiffParserState {
     WithinReplace { start: bool },
 }
 
+#[derive(Debug)]
+pub struct UnifiedDiffEditParser {
+    state: UnifiedDiffParserState,
+    buffer: String,
+    metrics: EditParserMetrics,
+}
+
+#[derive(Debug, PartialEq)]
+enum UnifiedDiffParserState {
+    Pending,
+    WithinHunk {
+        line_hint: Option<u32>,
+        has_old_text: bool,
+        new_lines: Vec<String>,
+        /// Blank lines are only treated as context once the hunk continues
+        /// after them, as they usually just separate the hunk from what follows.
+        pending_blank_lines: usize,
+        /// Whether the hunk contains any added or removed lines.
+        has_changes: bool,
+        /// A `--- ` line is only known to be a file header rather than a
+        /// removed line once it's followed by a `+++ ` line.
+        pending_old_file_header: Option<String>,
+    },
+}
+
 /// Main parser that delegates to format-specific parsers
 pub struct EditParser {
     parser: Box<dyn EditFormatParser>,
@@ -418,11 +478,201 @@ impl EditFormatParser for DiffFencedEditParser {
     }
 }
 
+impl UnifiedDiffEditParser {
+    pub fn new() -> Self {
+        UnifiedDiffEditParser {
+            state: UnifiedDiffParserState::Pending,
+            buffer: String::new(),
+            metrics: EditParserMetrics::default(),
+        }
+    }
+
+    fn push_line(&mut self, line: &str, edit_events: &mut SmallVec<[EditParserEvent; 1]>) {
+        if line.starts_with(HUNK_MARKER) {
+            self.end_hunk(false, edit_events);
+            self.metrics.tags += 1;
+            self.state = UnifiedDiffParserState::WithinHunk {
+                line_hint: self.parse_line_hint(line),
+                has_old_text: false,
+                new_lines: Vec::new(),
+                pending_blank_lines: 0,
+                has_changes: false,
+                pending_old_file_header: None,
+            };
+            return;
+        }
+
+        let UnifiedDiffParserState::WithinHunk {
+            pending_old_file_header,
+            ..
+        } = &mut self.state
+        else {
+            return;
+        };
+
+        if let Some(old_file_header) = pending_old_file_header.take() {
+            if line.starts_with(NEW_FILE_MARKER) {
+                self.end_hunk(false, edit_events);
+                return;
+            }
+            self.push_hunk_line(&old_file_header, edit_events);
+        }
+
+        if line.starts_with(OLD_FILE_MARKER) {
+            if let UnifiedDiffParserState::WithinHunk {
+                pending_old_file_header,
+                ..
+            } = &mut self.state
+            {
+                *pending_old_file_header = Some(line.to_string());
+            }
+        } else {
+            self.push_hunk_line(line, edit_events);
+        }
+    }
+
+    fn push_hunk_line(&mut self, line: &str, edit_events: &mut SmallVec<[EditParserEvent; 1]>) {
+        let UnifiedDiffParserState::WithinHunk {
+            line_hint,
+            has_old_text,
+            new_lines,
+            pending_blank_lines,
+            has_changes,
+            ..
+        } = &mut self.state
+        else {
+            return;
+        };
+
+        // Models often drop the leading space of blank context lines.
+        if line.is_empty() {
+            *pending_blank_lines += 1;
+            return;
+        }
+
+        let (old_line, new_line) = if let Some(context) = line.strip_prefix(' ') {
+            (Some(context), Some(context))
+        } else if let Some(deleted) = line.strip_prefix('-') {
+            *has_changes = true;
+            (Some(deleted), None)
+        } else if let Some(inserted) = line.strip_prefix('+') {
+            *has_changes = true;
+            (None, Some(inserted))
+        } else if line.starts_with('\\') {
+            // "\ No newline at end of file"
+            return;
+        } else {
+            // A closing code fence is how the diff is expected to end, any
+            // other text means the hunk was cut short.
+            self.end_hunk(!line.starts_with(CODE_FENCE), edit_events);
+            return;
+        };
+
+        let blank_lines = iter::repeat("").take(mem::take(pending_blank_lines));
+        for (old_line, new_line) in blank_lines
+            .map(|line| (Some(line), Some(line)))
+            .chain([(old_line, new_line)])
+        {
+            if let Some(old_line) = old_line {
+                let mut chunk = String::new();
+                if *has_old_text {
+                    chunk.push('\n');
+                }
+                chunk.push_str(old_line);
+                *has_old_text = true;
+                edit_events.push(EditParserEvent::OldTextChunk {
+                    chunk,
+                    done: false,
+                    line_hint: *line_hint,
+                });
+            }
+            if let Some(new_line) = new_line {
+                new_lines.push(new_line.to_string());
+            }
+        }
+    }
+
+    fn end_hunk(&mut self, interrupted: bool, edit_events: &mut SmallVec<[EditParserEvent; 1]>) {
+        // A `--- ` line that wasn't followed by a file header was a removed line.
+        if let UnifiedDiffParserState::WithinHunk {
+            pending_old_file_header,
+            ..
+        } = &mut self.state
+        {
+            if let Some(old_file_header) = pending_old_file_header.take() {
+                self.push_hunk_line(&old_file_header, edit_events);
+            }
+        }
+
+        if let UnifiedDiffParserState::WithinHunk {
+            line_hint,
+            new_lines,
+            has_changes,
+            ..
+        } = mem::replace(&mut self.state, UnifiedDiffParserState::Pending)
+        {
+            if interrupted || !has_changes {
+                self.metrics.mismatched_tags += 1;
+            }
+            edit_events.push(EditParserEvent::OldTextChunk {
+                chunk: String::new(),
+                done: true,
+                line_hint,
+            });
+            edit_events.push(EditParserEvent::NewTextChunk {
+                chunk: new_lines.join("\n"),
+                done: true,
+            });
+        }
+    }
+
+    fn parse_line_hint(&self, hunk_header: &str) -> Option<u32> {
+        use std::sync::LazyLock;
+        static LINE_HINT_REGEX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r#"^@@ -(\d+)"#).unwrap());
+
+        LINE_HINT_REGEX
+            .captures(hunk_header)
+            .and_then(|caps| caps.get(1))
+            .and_then(|m| m.as_str().parse::<u32>().ok())
+    }
+}
+
+impl EditFormatParser for UnifiedDiffEditParser {
+    fn push(&mut self, chunk: &str) -> SmallVec<[EditParserEvent; 1]> {
+        self.buffer.push_str(chunk);
+
+        // Hunks are line-based, so only complete lines are parsed.
+        let mut edit_events = SmallVec::new();
+        while let Some(newline_ix) = self.buffer.find('\n') {
+            let line = self.buffer[..newline_ix].to_string();
+            self.buffer.drain(..newline_ix + 1);
+            self.push_line(&line, &mut edit_events);
+        }
+        edit_events
+    }
+
+    fn flush(&mut self) -> SmallVec<[EditParserEvent; 1]> {
+        let mut edit_events = SmallVec::new();
+        let line = mem::take(&mut self.buffer);
+        if !line.is_empty() {
+            self.push_line(&line, &mut edit_events);
+        }
+        self.end_hunk(false, &mut edit_events);
+        edit_events
+    }
+
+    fn take_metrics(&mut self) -> EditParserMetrics {
+        std::mem::take(&mut self.metrics)
+    }
+}
+
 impl EditParser {
     pub fn new(format: EditFormat) -> Self {
         let parser: Box<dyn EditFormatParser> = match format {
             EditFormat::XmlTags => Box::new(XmlEditParser::new()),
             EditFormat::DiffFenced => Box::new(DiffFencedEditParser::new()),
+            EditFormat::UnifiedDiff => Box::new(UnifiedDiffEditParser::new()),
         };
         EditParser { parser }
     }
@@ -431,6 +681,10 @@ impl EditParser {
         self.parser.push(chunk)
     }
 
+    pub fn flush(&mut self) -> SmallVec<[EditParserEvent; 1]> {
+        self.parser.flush()
+    }
+
     pub fn finish(mut self) -> EditParserMetrics {
         self.parser.take_metrics()
     }
@@ -988,6 +1242,220 @@ mod tests {
         assert_eq!(edits[0].new_text, "new");
     }
 
+    #[gpui::test(iterations = 1000)]
+    fn test_unified_diff_single_hunk(mut rng: StdRng) {
+        let mut parser = EditParser::new(EditFormat::UnifiedDiff);
+        assert_eq!(
+            parse_random_chunks(
+                indoc! {"
+                    @@ -1,3 +1,3 @@
+                     fn main() {
+                    -    println!(\"Hello\");
+                    +    println!(\"Hello, world!\");
+                     }
+                "},
+                &mut parser,
+                &mut rng
+            ),
+            vec![Edit {
+                old_text: "fn main() {\n    println!(\"Hello\");\n}".to_string(),
+                new_text: "fn main() {\n    println!(\"Hello, world!\");\n}".to_string(),
+                line_hint: Some(1),
+            }]
+        );
+        assert_eq!(
+            parser.finish(),
+            EditParserMetrics {
+                tags: 1,
+                mismatched_tags: 0
+            }
+        );
+    }
+
+    #[gpui::test(iterations = 100)]
+    fn test_unified_diff_multiple_hunks(mut rng: StdRng) {
+        let mut parser = EditParser::new(EditFormat::UnifiedDiff);
+        assert_eq!(
+            parse_random_chunks(
+                indoc! {"
+                    Here are the edits:
+
+                    ```diff
+                    --- a/src/app.py
+                    +++ b/src/app.py
+                    @@ -1 +1,2 @@
+                    +import math
+                     from flask import Flask
+                    @@ -325,2 +326,2 @@
+                    -return 0
+                    +return 1
+                    \\ No newline at end of file
+                    ```
+                "},
+                &mut parser,
+                &mut rng
+            ),
+            vec![
+                Edit {
+                    old_text: "from flask import Flask".to_string(),
+                    new_text: "import math\nfrom flask import Flask".to_string(),
+                    line_hint: Some(1),
+                },
+                Edit {
+                    old_text: "return 0".to_string(),
+                    new_text: "return 1".to_string(),
+                    line_hint: Some(325),
+                },
+            ]
+        );
+        assert_eq!(
+            parser.finish(),
+            EditParserMetrics {
+                tags: 2,
+                mismatched_tags: 0
+            }
+        );
+    }
+
+    #[gpui::test(iterations = 100)]
+    fn test_unified_diff_blank_lines(mut rng: StdRng) {
+        let mut parser = EditParser::new(EditFormat::UnifiedDiff);
+        assert_eq!(
+            parse_random_chunks(
+                // The blank line within the hunk lacks its leading space, and
+                // the ones after it aren't part of the hunk.
+                indoc! {"
+                    @@ -10,4 +10,4 @@
+                     let a = 1;
+
+                    -let b = 2;
+                    +let b = 3;
+
+
+                    @@ -20 +20 @@
+                    -let c = 4;
+                    +let c = 5;
+                "},
+                &mut parser,
+                &mut rng
+            ),
+            vec![
+                Edit {
+                    old_text: "let a = 1;\n\nlet b = 2;".to_string(),
+                    new_text: "let a = 1;\n\nlet b = 3;".to_string(),
+                    line_hint: Some(10),
+                },
+                Edit {
+                    old_text: "let c = 4;".to_string(),
+                    new_text: "let c = 5;".to_string(),
+                    line_hint: Some(20),
+                },
+            ]
+        );
+    }
+
+    #[gpui::test(iterations = 100)]
+    fn test_unified_diff_without_line_numbers(mut rng: StdRng) {
+        let mut parser = EditParser::new(EditFormat::UnifiedDiff);
+        assert_eq!(
+            parse_random_chunks(
+                // The last hunk is only terminated by the end of the output.
+                "@@ ... @@\n-old line\n+new line\n+another new line",
+                &mut parser,
+                &mut rng
+            ),
+            vec![Edit {
+                old_text: "old line".to_string(),
+                new_text: "new line\nanother new line".to_string(),
+                line_hint: None,
+            }]
+        );
+    }
+
+    #[gpui::test(iterations = 100)]
+    fn test_unified_diff_multiple_files(mut rng: StdRng) {
+        let mut parser = EditParser::new(EditFormat::UnifiedDiff);
+        assert_eq!(
+            parse_random_chunks(
+                // The first hunk ends at the next file's header, while the
+                // `--- ` line in the second hunk is a removed line.
+                indoc! {"
+                    --- a/schema.sql
+                    +++ b/schema.sql
+                    @@ -1,2 +1,2 @@
+                    -CREATE TABLE users (id INT);
+                    +CREATE TABLE users (id BIGINT);
+                    --- a/seed.sql
+                    +++ b/seed.sql
+                    @@ -4,2 +4,1 @@
+                    --- seed users
+                     INSERT INTO users VALUES (1);
+                "},
+                &mut parser,
+                &mut rng
+            ),
+            vec![
+                Edit {
+                    old_text: "CREATE TABLE users (id INT);".to_string(),
+                    new_text: "CREATE TABLE users (id BIGINT);".to_string(),
+                    line_hint: Some(1),
+                },
+                Edit {
+                    old_text: "-- seed users\nINSERT INTO users VALUES (1);".to_string(),
+                    new_text: "INSERT INTO users VALUES (1);".to_string(),
+                    line_hint: Some(4),
+                },
+            ]
+        );
+        assert_eq!(
+            parser.finish(),
+            EditParserMetrics {
+                tags: 2,
+                mismatched_tags: 0
+            }
+        );
+    }
+
+    #[gpui::test(iterations = 100)]
+    fn test_unified_diff_mismatched_hunks(mut rng: StdRng) {
+        let mut parser = EditParser::new(EditFormat::UnifiedDiff);
+        assert_eq!(
+            parse_random_chunks(
+                // The first hunk has no changes and the second one is cut
+                // short by prose rather than a closing code fence.
+                indoc! {"
+                    @@ -1 +1 @@
+                     unchanged
+                    @@ -5 +5 @@
+                    -old
+                    +new
+                    That's all!
+                "},
+                &mut parser,
+                &mut rng
+            ),
+            vec![
+                Edit {
+                    old_text: "unchanged".to_string(),
+                    new_text: "unchanged".to_string(),
+                    line_hint: Some(1),
+                },
+                Edit {
+                    old_text: "old".to_string(),
+                    new_text: "new".to_string(),
+                    line_hint: Some(5),
+                },
+            ]
+        );
+        assert_eq!(
+            parser.finish(),
+            EditParserMetrics {
+                tags: 2,
+                mismatched_tags: 2
+            }
+        );
+    }
+
     #[derive(Default, Debug, PartialEq, Eq)]
     struct Edit {
         old_text: String,
@@ -1006,33 +1474,37 @@ mod tests {
         let mut pending_edit = Edit::default();
         let mut edits = Vec::new();
         let mut last_ix = 0;
+        let mut events = Vec::new();
         for chunk_ix in chunk_indices {
-            for event in parser.push(&input[last_ix..chunk_ix]) {
-                match event {
-                    EditParserEvent::OldTextChunk {
-                        chunk,
-                        done,
-                        line_hint,
-                    } => {
-                        old_text.as_mut().unwrap().push_str(&chunk);
-                        if done {
-                            pending_edit.old_text = old_text.take().unwrap();
-                            pending_edit.line_hint = line_hint;
-                            new_text = Some(String::new());
-                        }
+            events.extend(parser.push(&input[last_ix..chunk_ix]));
+            last_ix = chunk_ix;
+        }
+        events.extend(parser.flush());
+
+        for event in events {
+            match event {
+                EditParserEvent::OldTextChunk {
+                    chunk,
+                    done,
+                    line_hint,
+                } => {
+                    old_text.as_mut().unwrap().push_str(&chunk);
+                    if done {
+                        pending_edit.old_text = old_text.take().unwrap();
+                        pending_edit.line_hint = line_hint;
+                        new_text = Some(String::new());
                     }
-                    EditParserEvent::NewTextChunk { chunk, done } => {
-                        new_text.as_mut().unwrap().push_str(&chunk);
-                        if done {
-                            pending_edit.new_text = new_text.take().unwrap();
-                            edits.push(pending_edit);
-                            pending_edit = Edit::default();
-                            old_text = Some(String::new());
-                        }
+                }
+                EditParserEvent::NewTextChunk { chunk, done } => {
+                    new_text.as_mut().unwrap().push_str(&chunk);
+                    if done {
+                        pending_edit.new_text = new_text.take().unwrap();
+                        edits.push(pending_edit);
+                        pending_edit = Edit::default();
+                        old_text = Some(String::new());
                     }
                 }
             }
-            last_ix = chunk_ix;
         }
 
         edits
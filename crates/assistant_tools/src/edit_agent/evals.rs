@@ -171,6 +171,71 @@ fn eval_delete_run_git_blame() {
     );
 }
 
+#[test]
+#[cfg_attr(not(feature = "eval"), ignore)]
+fn eval_delete_run_git_blame_unified_diff() {
+    // Same as `eval_delete_run_git_blame`, but with edits in the unified diff format.
+    //
+    // Model                       | Pass rate
+    // ----------------------------|----------
+    // claude-3.7-sonnet           |
+    // claude-sonnet-4             |
+    // gemini-2.5-pro-06-05        |
+    // gemini-2.5-flash            |
+    // gpt-4.1                     |
+    let input_file_path = "root/blame.rs";
+    let input_file_content = include_str!("evals/fixtures/delete_run_git_blame/before.rs");
+    let output_file_content = include_str!("evals/fixtures/delete_run_git_blame/after.rs");
+    let edit_description = "Delete the `run_git_blame` function.";
+    eval(
+        100,
+        0.95,
+        0.05,
+        EvalInput::from_conversation(
+            vec![
+                message(
+                    User,
+                    [text(formatdoc! {"
+                        Read the `{input_file_path}` file and delete `run_git_blame`. Just that
+                        one function, not its usages.
+                    "})],
+                ),
+                message(
+                    Assistant,
+                    [tool_use(
+                        "tool_1",
+                        "read_file",
+                        ReadFileToolInput {
+                            path: input_file_path.into(),
+                            start_line: None,
+                            end_line: None,
+                        },
+                    )],
+                ),
+                message(
+                    User,
+                    [tool_result("tool_1", "read_file", input_file_content)],
+                ),
+                message(
+                    Assistant,
+                    [tool_use(
+                        "tool_2",
+                        "edit_file",
+                        EditFileToolInput {
+                            display_description: edit_description.into(),
+                            path: input_file_path.into(),
+                            mode: EditFileMode::Edit,
+                        },
+                    )],
+                ),
+            ],
+            Some(input_file_content.into()),
+            EvalAssertion::assert_eq(output_file_content),
+        )
+        .with_edit_format(EditFormat::UnifiedDiff),
+    );
+}
+
 #[test]
 #[cfg_attr(not(feature = "eval"), ignore)]
 fn eval_translate_doc_comments() {
@@ -1124,6 +1189,8 @@ struct EvalInput {
     edit_file_input: EditFileToolInput,
     input_content: Option<String>,
     assertion: EvalAssertion,
+    /// Overrides the edit format that would be chosen for the model.
+    edit_format: Option<EditFormat>,
 }
 
 impl EvalInput {
@@ -1157,8 +1224,14 @@ impl EvalInput {
             edit_file_input,
             input_content,
             assertion,
+            edit_format: None,
         }
     }
+
+    fn with_edit_format(mut self, edit_format: EditFormat) -> Self {
+        self.edit_format = Some(edit_format);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -1601,12 +1674,19 @@ impl EditAgentTest {
             ..Default::default()
         };
 
+        let agent = match eval.edit_format {
+            Some(edit_format) => EditAgent {
+                edit_format,
+                ..self.agent.clone()
+            },
+            None => self.agent.clone(),
+        };
         let edit_output = if matches!(eval.edit_file_input.mode, EditFileMode::Edit) {
             if let Some(input_content) = eval.input_content.as_deref() {
                 buffer.update(cx, |buffer, cx| buffer.set_text(input_content, cx));
             }
             retry_on_rate_limit(async || {
-                self.agent
+                agent
                     .edit(
                         buffer.clone(),
                         eval.edit_file_input.display_description.clone(),
@@ -1619,7 +1699,7 @@ impl EditAgentTest {
             .await?
         } else {
             retry_on_rate_limit(async || {
-                self.agent
+                agent
                     .overwrite(
                         buffer.clone(),
                         eval.edit_file_input.display_description.clone(),
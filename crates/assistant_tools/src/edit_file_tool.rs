@@ -4,6 +4,7 @@ use crate::{
     schema::json_schema_for,
     ui::{COLLAPSED_LINES, ToolOutputPreview},
 };
+use agent_settings::AgentSettings;
 use anyhow::{Context as _, Result, anyhow};
 use assistant_tool::{
     ActionLog, AnyToolCard, Tool, ToolCard, ToolResult, ToolResultContent, ToolResultOutput,
@@ -198,10 +199,14 @@ impl Tool for EditFileTool {
                 .ok()
         });
 
+        let edit_format_setting = AgentSettings::edit_format_for_model(&model, cx);
         let card_clone = card.clone();
         let action_log_clone = action_log.clone();
         let task = cx.spawn(async move |cx: &mut AsyncApp| {
-            let edit_format = EditFormat::from_model(model.clone())?;
+            let edit_format = match edit_format_setting {
+                Some(edit_format) => edit_format.into(),
+                None => EditFormat::from_model(model.clone())?,
+            };
             let edit_agent = EditAgent::new(
                 model,
                 project.clone(),
@@ -125,6 +125,10 @@ impl Tool for FetchTool {
         false
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> String {
         include_str!("./fetch_tool/description.md").to_string()
     }
@@ -63,6 +63,10 @@ impl Tool for FindPathTool {
         false
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> String {
         include_str!("./find_path_tool/description.md").into()
     }
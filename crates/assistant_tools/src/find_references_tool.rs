@@ -0,0 +1,221 @@
+use crate::code_navigation::{
+    CodeNavigationCard, CodeNavigationOutput, finish, locations_for, symbol_position,
+    write_locations,
+};
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use futures::channel::oneshot;
+use gpui::{AnyWindowHandle, App, AppContext, Entity, Task, Window};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{cmp, fmt::Write, sync::Arc};
+use ui::IconName;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesToolInput {
+    /// The relative path of a file containing the symbol, starting with one of the project's root directories.
+    pub path: String,
+
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+
+    /// The name of the symbol, exactly as it's written on that line.
+    pub symbol: String,
+
+    /// Optional starting position for paginated results (0-based).
+    /// When not provided, starts from the beginning.
+    #[serde(default)]
+    pub offset: usize,
+}
+
+const RESULTS_PER_PAGE: usize = 50;
+
+pub struct FindReferencesTool;
+
+impl Tool for FindReferencesTool {
+    fn name(&self) -> String {
+        "find_references".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./find_references_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Link
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<FindReferencesToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<FindReferencesToolInput>(input.clone()) {
+            Ok(input) => format!("Find references to `{}`", input.symbol),
+            Err(_) => "Find references".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<FindReferencesToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        let card = cx
+            .new(|cx| CodeNavigationCard::new(IconName::Link, input.symbol.clone(), receiver, cx));
+        let position = symbol_position(&project, &input.path, input.line, &input.symbol, cx);
+
+        let task = cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let references = project
+                .update(cx, |project, cx| project.references(&buffer, position, cx))?
+                .await?;
+            let mut locations = locations_for(references, cx).await?;
+            locations.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+            locations.dedup_by(|a, b| a.path == b.path && a.line == b.line);
+
+            let total = locations.len();
+            let offset = cmp::min(input.offset, total);
+            let page_end = cmp::min(offset + RESULTS_PER_PAGE, total);
+            let locations = locations[offset..page_end].to_vec();
+
+            let title = match total {
+                0 => "No references".to_string(),
+                1 => "1 reference".to_string(),
+                count => format!("{count} references"),
+            };
+            let mut message = if total == 0 {
+                format!(
+                    "The language server found no references to `{}`.",
+                    input.symbol
+                )
+            } else {
+                format!("Found {total} references to `{}`.", input.symbol)
+            };
+            if total > RESULTS_PER_PAGE {
+                write!(
+                    &mut message,
+                    "\nShowing results {}-{} (provide 'offset' parameter for more results):",
+                    offset + 1,
+                    page_end
+                )?;
+            }
+            if !locations.is_empty() {
+                message.push('\n');
+            }
+            write_locations(&mut message, &locations);
+
+            let output = CodeNavigationOutput {
+                title,
+                symbol: input.symbol,
+                locations,
+                details: None,
+            };
+            finish(output, message, sender)
+        });
+
+        ToolResult {
+            output: task,
+            card: Some(card.into()),
+        }
+    }
+
+    fn deserialize_card(
+        self: Arc<Self>,
+        output: serde_json::Value,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Option<assistant_tool::AnyToolCard> {
+        let output = serde_json::from_value::<CodeNavigationOutput>(output).ok()?;
+        let card = cx.new(|_| CodeNavigationCard::from_output(IconName::Link, output));
+        Some(card.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_navigation::tests::{MAIN_RS, lsp_range, main_rs_url, run_tool, test_project};
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_find_references(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS } }),
+            lsp::ServerCapabilities {
+                references_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        test.server
+            .set_request_handler::<lsp::request::References, _, _>(|params, _| async move {
+                let params = params.text_document_position;
+                assert_eq!(params.text_document.uri, main_rs_url());
+                assert_eq!(params.position, lsp::Position::new(0, 3));
+                // Duplicates on the same line are only listed once.
+                Ok(Some(vec![
+                    lsp::Location::new(main_rs_url(), lsp_range(5, 16, 22)),
+                    lsp::Location::new(main_rs_url(), lsp_range(0, 3, 9)),
+                    lsp::Location::new(main_rs_url(), lsp_range(5, 16, 22)),
+                ]))
+            });
+
+        let input = json!({ "path": path!("root/src/main.rs"), "line": 1, "symbol": "helper" });
+        let (message, output) = run_tool(FindReferencesTool, input.clone(), &test, cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            message,
+            format!(
+                "Found 2 references to `helper`.\n\
+                {0}:1: fn helper() -> i32 {{\n\
+                {0}:6: let value = helper();\n",
+                path!("root/src/main.rs")
+            )
+        );
+        assert_eq!(output.title, "2 references");
+
+        let input = json!({
+            "path": path!("root/src/main.rs"),
+            "line": 1,
+            "symbol": "helper",
+            "offset": 5,
+        });
+        let (_, output) = run_tool(FindReferencesTool, input, &test, cx)
+            .await
+            .unwrap();
+        assert!(output.locations.is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{
+    git_history::{format_date, short_sha},
+    schema::json_schema_for,
+};
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitBlameToolInput {
+    /// The path of the file to blame.
+    ///
+    /// This path should never be absolute, and the first component of the path should always
+    /// be a root directory in a project.
+    pub path: String,
+
+    /// The 1-based line number to start blaming at. Defaults to the start of the file.
+    #[serde(default)]
+    pub start_line: Option<u32>,
+
+    /// The 1-based, inclusive line number to stop blaming at. Defaults to the end of the file.
+    #[serde(default)]
+    pub end_line: Option<u32>,
+}
+
+pub struct GitBlameTool;
+
+impl Tool for GitBlameTool {
+    fn name(&self) -> String {
+        "git_blame".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./git_blame_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::GitBranch
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<GitBlameToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<GitBlameToolInput>(input.clone()) {
+            Ok(input) => {
+                let path = MarkdownInlineCode(&input.path);
+                match (input.start_line, input.end_line) {
+                    (Some(start), Some(end)) => format!("Git blame {path} (lines {start}-{end})"),
+                    (Some(start), None) => format!("Git blame {path} (from line {start})"),
+                    _ => format!("Git blame {path}"),
+                }
+            }
+            Err(_) => "Git blame".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<GitBlameToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let Some(project_path) = project.read(cx).find_project_path(&input.path, cx) else {
+            return Task::ready(Err(anyhow!(
+                "Could not find path {} in project",
+                input.path
+            )))
+            .into();
+        };
+        let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+
+        cx.spawn(async move |cx| {
+            let buffer = buffer.await?;
+            let blame = project
+                .update(cx, |project, cx| {
+                    project.git_store().read(cx).blame_buffer(&buffer, None, cx)
+                })?
+                .await?
+                .with_context(|| format!("{} isn't in a git repository", input.path))?;
+
+            // Blame ranges are 0-based and exclusive, while the input is 1-based and inclusive.
+            let start_row = input.start_line.unwrap_or(1).saturating_sub(1);
+            let end_row = input.end_line.unwrap_or(u32::MAX);
+            let mut entries = blame
+                .entries
+                .iter()
+                .filter(|entry| entry.range.start < end_row && entry.range.end > start_row)
+                .collect::<Vec<_>>();
+            entries.sort_by_key(|entry| entry.range.start);
+            if entries.is_empty() {
+                return Ok(format!("No blame information for {}.", input.path).into());
+            }
+
+            let mut output = String::new();
+            for entry in entries {
+                let first_line = entry.range.start.max(start_row) + 1;
+                let last_line = entry.range.end.min(end_row);
+                if first_line == last_line {
+                    write!(output, "- Line {first_line}: ")?;
+                } else {
+                    write!(output, "- Lines {first_line}-{last_line}: ")?;
+                }
+                if entry.sha.is_zero() {
+                    writeln!(output, "not committed yet")?;
+                    continue;
+                }
+                writeln!(
+                    output,
+                    "`{}` {} {}: {}",
+                    short_sha(&entry.sha.to_string()),
+                    entry
+                        .author_time
+                        .map(format_date)
+                        .unwrap_or_else(|| "unknown date".to_string()),
+                    entry.author.as_deref().unwrap_or("unknown author"),
+                    entry.summary.as_deref().unwrap_or_default()
+                )?;
+            }
+            Ok(output.into())
+        })
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::blame::{Blame, BlameEntry};
+    use gpui::{AppContext, TestAppContext};
+    use indoc::indoc;
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{ops::Range, path::Path};
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    fn blame_entry(sha: &str, range: Range<u32>, summary: &str) -> BlameEntry {
+        BlameEntry {
+            sha: sha.parse().unwrap(),
+            range,
+            author: Some("Zed".to_string()),
+            author_time: Some(1_700_000_000),
+            summary: Some(summary.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_git_blame(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "main.rs": "fn main() {\n    println!(\"Hello\");\n    println!(\"World\");\n}\n",
+            }),
+        )
+        .await;
+        fs.set_blame_for_repo(
+            Path::new(path!("/project/.git")),
+            vec![(
+                "main.rs".into(),
+                Blame {
+                    entries: vec![
+                        blame_entry("1b1b1b1b", 0..2, "Add main"),
+                        blame_entry("3a3a3a3a", 2..3, "Greet the world"),
+                        blame_entry("1b1b1b1b", 3..4, "Add main"),
+                    ],
+                    ..Default::default()
+                },
+            )],
+        );
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+
+        let output = run_tool(json!({ "path": "project/main.rs" }), &project, cx).await;
+        assert_eq!(
+            output,
+            indoc! {"
+                - Lines 1-2: `1b1b1b1` 2023-11-14 Zed: Add main
+                - Line 3: `3a3a3a3` 2023-11-14 Zed: Greet the world
+                - Line 4: `1b1b1b1` 2023-11-14 Zed: Add main
+            "}
+        );
+
+        let output = run_tool(
+            json!({ "path": "project/main.rs", "start_line": 2, "end_line": 3 }),
+            &project,
+            cx,
+        )
+        .await;
+        assert_eq!(
+            output,
+            indoc! {"
+                - Line 2: `1b1b1b1` 2023-11-14 Zed: Add main
+                - Line 3: `3a3a3a3` 2023-11-14 Zed: Greet the world
+            "}
+        );
+    }
+
+    async fn run_tool(
+        input: serde_json::Value,
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> String {
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let model = Arc::new(FakeLanguageModel::default());
+        let output = cx
+            .update(|cx| {
+                Arc::new(GitBlameTool).run(
+                    input,
+                    Arc::default(),
+                    project.clone(),
+                    action_log,
+                    model,
+                    None,
+                    cx,
+                )
+            })
+            .output
+            .await
+            .unwrap();
+        output.content.as_str().unwrap().to_string()
+    }
+}
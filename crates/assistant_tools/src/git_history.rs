@@ -0,0 +1,56 @@
+//! Shared pieces of the tools that look into the project's git history.
+
+use anyhow::{Context as _, Result};
+use git::repository::{CommitDetails, RepoPath};
+use gpui::{App, Entity};
+use project::{Project, git_store::Repository};
+
+/// Finds the repository containing `path`, along with the path relative to the repository's root.
+/// Without a path, uses the active repository.
+///
+/// The repository path is `None` when `path` is the repository's root.
+pub(crate) fn repository_for_path(
+    project: &Entity<Project>,
+    path: Option<&str>,
+    cx: &App,
+) -> Result<(Entity<Repository>, Option<RepoPath>)> {
+    let project = project.read(cx);
+    let git_store = project.git_store().read(cx);
+    let Some(path) = path else {
+        let repository = git_store
+            .active_repository()
+            .or_else(|| git_store.repositories().values().next().cloned())
+            .context("The project isn't in a git repository")?;
+        return Ok((repository, None));
+    };
+
+    let project_path = project
+        .find_project_path(path, cx)
+        .with_context(|| format!("Could not find path {path} in project"))?;
+    let (repository, repo_path) = git_store
+        .repository_and_path_for_project_path(&project_path, cx)
+        .with_context(|| format!("{path} isn't in a git repository"))?;
+    let repo_path = (!repo_path.as_os_str().is_empty()).then_some(repo_path);
+    Ok((repository, repo_path))
+}
+
+pub(crate) fn short_sha(sha: &str) -> &str {
+    sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(sha)
+}
+
+pub(crate) fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "unknown date".to_string())
+}
+
+/// Formats a commit as a single line, with the first line of its message.
+pub(crate) fn commit_summary(commit: &CommitDetails) -> String {
+    format!(
+        "`{}` {} {}: {}",
+        short_sha(&commit.sha),
+        format_date(commit.commit_timestamp),
+        commit.author_name,
+        commit.message.lines().next().unwrap_or_default()
+    )
+}
@@ -0,0 +1,236 @@
+use crate::{
+    git_history::{commit_summary, repository_for_path},
+    schema::json_schema_for,
+};
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use git::repository::LogQuery;
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+const COMMITS_PER_PAGE: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitLogToolInput {
+    /// Only list the commits that changed this file or directory. If not provided, lists the
+    /// commits of the project's repository.
+    ///
+    /// This path should never be absolute, and the first component of the path should always
+    /// be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// How many of the most recent commits to skip, for listing older commits.
+    #[serde(default)]
+    pub offset: usize,
+}
+
+pub struct GitLogTool;
+
+impl Tool for GitLogTool {
+    fn name(&self) -> String {
+        "git_log".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./git_log_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::GitBranch
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<GitLogToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<GitLogToolInput>(input.clone()) {
+            Ok(GitLogToolInput {
+                path: Some(path), ..
+            }) if !path.is_empty() => format!("Git history of {}", MarkdownInlineCode(&path)),
+            _ => "Git history".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<GitLogToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let path = input.path.as_deref().filter(|path| !path.is_empty());
+        let (repository, repo_path) = match repository_for_path(&project, path, cx) {
+            Ok(repository) => repository,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
+
+        // Ask for one more commit than we show, to know whether there are more.
+        let commits = repository.update(cx, |repository, _| {
+            repository.log(LogQuery {
+                path: repo_path,
+                skip: input.offset,
+                limit: COMMITS_PER_PAGE + 1,
+            })
+        });
+        cx.background_spawn(async move {
+            let mut commits = commits.await??;
+            if commits.is_empty() {
+                return Ok(if input.offset == 0 {
+                    "No commits found.".to_string().into()
+                } else {
+                    format!("No commits found after the first {}.", input.offset).into()
+                });
+            }
+
+            let has_more = commits.len() > COMMITS_PER_PAGE;
+            commits.truncate(COMMITS_PER_PAGE);
+            let mut output = String::new();
+            for commit in &commits {
+                writeln!(output, "- {}", commit_summary(commit))?;
+            }
+            if has_more {
+                writeln!(
+                    output,
+                    "\nThere are older commits. To list them, use an offset of {}.",
+                    input.offset + commits.len()
+                )?;
+            }
+            Ok(output.into())
+        })
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::{CommitDetails, RepoPath};
+    use gpui::{AppContext, TestAppContext};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_git_log(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "README.md": "# Project",
+                "src": { "main.rs": "fn main() {}" },
+            }),
+        )
+        .await;
+        // Even commits change `src/main.rs`, odd ones `README.md`.
+        let commits = (0..25)
+            .rev()
+            .map(|ix| {
+                let details = CommitDetails {
+                    sha: format!("{ix:02}{}", "a".repeat(38)).into(),
+                    message: format!("Commit {ix}\n\nMore details.").into(),
+                    commit_timestamp: 1_700_000_000,
+                    author_email: "hi@zed.dev".into(),
+                    author_name: "Zed".into(),
+                };
+                let path = if ix % 2 == 0 {
+                    "src/main.rs"
+                } else {
+                    "README.md"
+                };
+                (details, vec![RepoPath::from_str(path)])
+            })
+            .collect();
+        fs.set_commits_for_repo(Path::new(path!("/project/.git")), commits);
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+
+        let output = run_tool(json!({}), &project, cx).await;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 22);
+        assert_eq!(lines[0], "- `24aaaaa` 2023-11-14 Zed: Commit 24");
+        assert_eq!(lines[19], "- `05aaaaa` 2023-11-14 Zed: Commit 5");
+        assert_eq!(
+            lines[21],
+            "There are older commits. To list them, use an offset of 20."
+        );
+
+        let output = run_tool(json!({ "offset": 20 }), &project, cx).await;
+        assert_eq!(output.lines().count(), 5);
+        assert!(output.ends_with("- `00aaaaa` 2023-11-14 Zed: Commit 0\n"));
+
+        let output = run_tool(json!({ "path": "project/src/main.rs" }), &project, cx).await;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[1], "- `22aaaaa` 2023-11-14 Zed: Commit 22");
+
+        let output = run_tool(json!({ "offset": 30 }), &project, cx).await;
+        assert_eq!(output, "No commits found after the first 30.");
+    }
+
+    async fn run_tool(
+        input: serde_json::Value,
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> String {
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let model = Arc::new(FakeLanguageModel::default());
+        let output = cx
+            .update(|cx| {
+                Arc::new(GitLogTool).run(
+                    input,
+                    Arc::default(),
+                    project.clone(),
+                    action_log,
+                    model,
+                    None,
+                    cx,
+                )
+            })
+            .output
+            .await
+            .unwrap();
+        output.content.as_str().unwrap().to_string()
+    }
+}
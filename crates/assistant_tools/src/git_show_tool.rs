@@ -0,0 +1,211 @@
+use crate::{
+    git_history::{format_date, repository_for_path, short_sha},
+    schema::json_schema_for,
+};
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language::unified_diff;
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+/// How much of a commit's diff is shown, so that large commits don't fill up the context window.
+const DIFF_LIMIT: usize = 16 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GitShowToolInput {
+    /// The SHA of the commit to show, or any other revision that git understands, like `HEAD~2`.
+    pub commit: String,
+
+    /// A file or directory in the repository that the commit belongs to. When provided, only the
+    /// changes to it are shown. If not provided, the commit is looked up in the project's
+    /// repository and all of its changes are shown.
+    ///
+    /// This path should never be absolute, and the first component of the path should always
+    /// be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+pub struct GitShowTool;
+
+impl Tool for GitShowTool {
+    fn name(&self) -> String {
+        "git_show".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./git_show_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::GitBranch
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<GitShowToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<GitShowToolInput>(input.clone()) {
+            Ok(input) => format!(
+                "Show commit {}",
+                MarkdownInlineCode(short_sha(&input.commit))
+            ),
+            Err(_) => "Show commit".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<GitShowToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        // Git would interpret a revision starting with `-` as an option.
+        if input.commit.is_empty() || input.commit.starts_with('-') {
+            return Task::ready(Err(anyhow!("{:?} is not a valid revision", input.commit))).into();
+        }
+        let path = input.path.as_deref().filter(|path| !path.is_empty());
+        let (repository, repo_path) = match repository_for_path(&project, path, cx) {
+            Ok(repository) => repository,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
+
+        let (details, diff) = repository.update(cx, |repository, _| {
+            (
+                repository.show(input.commit.clone()),
+                repository.load_commit_diff(input.commit.clone()),
+            )
+        });
+        cx.background_spawn(async move {
+            let details = details.await??;
+            let diff = diff.await??;
+
+            let mut output = String::new();
+            writeln!(output, "Commit {}", details.sha)?;
+            writeln!(
+                output,
+                "Author: {} <{}>",
+                details.author_name, details.author_email
+            )?;
+            writeln!(output, "Date: {}", format_date(details.commit_timestamp))?;
+            writeln!(output, "\n{}\n", details.message.trim_end())?;
+
+            let files = diff
+                .files
+                .iter()
+                .filter(|file| {
+                    repo_path
+                        .as_ref()
+                        .is_none_or(|path| file.path.starts_with(path))
+                })
+                .collect::<Vec<_>>();
+            if files.is_empty() {
+                output.push_str("No changes.");
+                return Ok(output.into());
+            }
+
+            let mut omitted_files = Vec::new();
+            for file in files {
+                if output.len() > DIFF_LIMIT {
+                    omitted_files.push(file.path.to_string());
+                    continue;
+                }
+                let old_text = file.old_text.as_deref().unwrap_or_default();
+                let new_text = file.new_text.as_deref().unwrap_or_default();
+                writeln!(output, "```diff")?;
+                writeln!(output, "--- a/{}", file.path)?;
+                writeln!(output, "+++ b/{}", file.path)?;
+                output.push_str(&unified_diff(old_text, new_text));
+                writeln!(output, "```")?;
+            }
+            if !omitted_files.is_empty() {
+                writeln!(
+                    output,
+                    "\nThe diff is too large to show in full. These files also changed: {}. \
+                    To see their changes, pass one of them as the path.",
+                    omitted_files.join(", ")
+                )?;
+            }
+            Ok(output.into())
+        })
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_git_show_rejects_options(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({ ".git": {}, "README.md": "# Project" }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+
+        for commit in ["--output=/tmp/pwned", "-p", ""] {
+            let action_log = cx.new(|_| ActionLog::new(project.clone()));
+            let model = Arc::new(FakeLanguageModel::default());
+            let result = cx
+                .update(|cx| {
+                    Arc::new(GitShowTool).run(
+                        json!({ "commit": commit }),
+                        Arc::default(),
+                        project.clone(),
+                        action_log,
+                        model,
+                        None,
+                        cx,
+                    )
+                })
+                .output
+                .await;
+            let error = result.err().unwrap().to_string();
+            assert_eq!(error, format!("{commit:?} is not a valid revision"));
+        }
+    }
+}
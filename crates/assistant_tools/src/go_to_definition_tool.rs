@@ -0,0 +1,225 @@
+use crate::code_navigation::{
+    CodeLocation, CodeNavigationCard, CodeNavigationOutput, finish, symbol_position,
+    write_locations,
+};
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use futures::channel::oneshot;
+use gpui::{AnyWindowHandle, App, AppContext, Entity, Task, Window};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use ui::IconName;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoToDefinitionToolInput {
+    /// The relative path of the file containing a usage of the symbol.
+    ///
+    /// <example>
+    /// If the project has the following root directories:
+    ///
+    /// - directory1
+    /// - directory2
+    ///
+    /// If you want to look up a symbol used in `directory1/src/main.rs`, you should use the path "directory1/src/main.rs".
+    /// </example>
+    pub path: String,
+
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+
+    /// The name of the symbol, exactly as it's written on that line.
+    pub symbol: String,
+}
+
+pub struct GoToDefinitionTool;
+
+impl Tool for GoToDefinitionTool {
+    fn name(&self) -> String {
+        "go_to_definition".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./go_to_definition_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Crosshair
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<GoToDefinitionToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<GoToDefinitionToolInput>(input.clone()) {
+            Ok(input) => format!("Go to definition of `{}`", input.symbol),
+            Err(_) => "Go to definition".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<GoToDefinitionToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        let card = cx.new(|cx| {
+            CodeNavigationCard::new(IconName::Crosshair, input.symbol.clone(), receiver, cx)
+        });
+        let position = symbol_position(&project, &input.path, input.line, &input.symbol, cx);
+
+        let task = cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let links = project
+                .update(cx, |project, cx| project.definition(&buffer, position, cx))?
+                .await?;
+            let locations = cx.update(|cx| {
+                links
+                    .iter()
+                    .filter_map(|link| CodeLocation::from_location(&link.target, cx))
+                    .collect::<Vec<_>>()
+            })?;
+
+            let (title, mut message) = match locations.len() {
+                0 => (
+                    "No definition found".to_string(),
+                    format!(
+                        "The language server found no definition of `{}`.",
+                        input.symbol
+                    ),
+                ),
+                1 => (
+                    "1 definition".to_string(),
+                    format!("`{}` is defined at:\n", input.symbol),
+                ),
+                count => (
+                    format!("{count} definitions"),
+                    format!("`{}` has {count} definitions:\n", input.symbol),
+                ),
+            };
+            write_locations(&mut message, &locations);
+
+            let output = CodeNavigationOutput {
+                title,
+                symbol: input.symbol,
+                locations,
+                details: None,
+            };
+            finish(output, message, sender)
+        });
+
+        ToolResult {
+            output: task,
+            card: Some(card.into()),
+        }
+    }
+
+    fn deserialize_card(
+        self: Arc<Self>,
+        output: serde_json::Value,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Option<assistant_tool::AnyToolCard> {
+        let output = serde_json::from_value::<CodeNavigationOutput>(output).ok()?;
+        let card = cx.new(|_| CodeNavigationCard::from_output(IconName::Crosshair, output));
+        Some(card.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_navigation::tests::{MAIN_RS, lsp_range, main_rs_url, run_tool, test_project};
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_go_to_definition(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS } }),
+            lsp::ServerCapabilities {
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        test.server
+            .set_request_handler::<lsp::request::GotoDefinition, _, _>(|params, _| async move {
+                let params = params.text_document_position_params;
+                assert_eq!(params.text_document.uri, main_rs_url());
+                assert_eq!(params.position, lsp::Position::new(5, 16));
+                Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+                    lsp::Location::new(main_rs_url(), lsp_range(0, 3, 9)),
+                )))
+            });
+
+        let input = json!({ "path": path!("root/src/main.rs"), "line": 6, "symbol": "helper" });
+        let (message, output) = run_tool(GoToDefinitionTool, input, &test, cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            message,
+            format!(
+                "`helper` is defined at:\n{}:1: fn helper() -> i32 {{\n",
+                path!("root/src/main.rs")
+            )
+        );
+        assert_eq!(output.title, "1 definition");
+        assert_eq!(output.locations.len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_go_to_definition_of_missing_symbol(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS } }),
+            lsp::ServerCapabilities {
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let input = json!({ "path": path!("root/src/main.rs"), "line": 6, "symbol": "missing" });
+        let error = run_tool(GoToDefinitionTool, input, &test, cx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Line 6 of {} does not contain `missing`",
+                path!("root/src/main.rs")
+            )
+        );
+    }
+}
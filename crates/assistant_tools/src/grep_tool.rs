@@ -65,6 +65,10 @@ impl Tool for GrepTool {
         false
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> String {
         include_str!("./grep_tool/description.md").into()
     }
@@ -0,0 +1,195 @@
+use crate::code_navigation::{
+    CodeLocation, CodeNavigationCard, CodeNavigationOutput, finish, symbol_position,
+};
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use futures::channel::oneshot;
+use gpui::{AnyWindowHandle, App, AppContext, Entity, Task, Window};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use ui::IconName;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HoverToolInput {
+    /// The relative path of the file containing the symbol, starting with one of the project's root directories.
+    pub path: String,
+
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+
+    /// The name of the symbol, exactly as it's written on that line.
+    pub symbol: String,
+}
+
+pub struct HoverTool;
+
+impl Tool for HoverTool {
+    fn name(&self) -> String {
+        "hover".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./hover_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Info
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<HoverToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<HoverToolInput>(input.clone()) {
+            Ok(input) => format!("Get type info for `{}`", input.symbol),
+            Err(_) => "Get type info".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<HoverToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        let card = cx
+            .new(|cx| CodeNavigationCard::new(IconName::Info, input.symbol.clone(), receiver, cx));
+        let position = symbol_position(&project, &input.path, input.line, &input.symbol, cx);
+
+        let task = cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let hovers = project
+                .update(cx, |project, cx| project.hover(&buffer, position, cx))?
+                .await;
+            let text = hovers
+                .iter()
+                .flat_map(|hover| &hover.contents)
+                .map(|block| block.text.trim())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            let location = cx.update(|cx| CodeLocation::from_anchor(&buffer, &position, cx))?;
+
+            let (title, message, details) = if text.is_empty() {
+                (
+                    "No type info".to_string(),
+                    format!(
+                        "The language server has no information about `{}`.",
+                        input.symbol
+                    ),
+                    None,
+                )
+            } else {
+                ("Type info".to_string(), text.clone(), Some(text))
+            };
+
+            let output = CodeNavigationOutput {
+                title,
+                symbol: input.symbol,
+                locations: location.into_iter().collect(),
+                details,
+            };
+            finish(output, message, sender)
+        });
+
+        ToolResult {
+            output: task,
+            card: Some(card.into()),
+        }
+    }
+
+    fn deserialize_card(
+        self: Arc<Self>,
+        output: serde_json::Value,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Option<assistant_tool::AnyToolCard> {
+        let output = serde_json::from_value::<CodeNavigationOutput>(output).ok()?;
+        let card = cx.new(|_| CodeNavigationCard::from_output(IconName::Info, output));
+        Some(card.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_navigation::tests::{MAIN_RS, main_rs_url, run_tool, test_project};
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_hover(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS } }),
+            lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        test.server
+            .set_request_handler::<lsp::request::HoverRequest, _, _>(|params, _| async move {
+                let params = params.text_document_position_params;
+                assert_eq!(params.text_document.uri, main_rs_url());
+                assert_eq!(params.position, lsp::Position::new(5, 8));
+                Ok(Some(lsp::Hover {
+                    contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                        kind: lsp::MarkupKind::PlainText,
+                        value: "let value: i32\n".to_string(),
+                    }),
+                    range: None,
+                }))
+            });
+
+        let input = json!({ "path": path!("root/src/main.rs"), "line": 6, "symbol": "value" });
+        let (message, output) = run_tool(HoverTool, input, &test, cx).await.unwrap();
+        assert_eq!(message, "let value: i32");
+        assert_eq!(output.title, "Type info");
+        assert_eq!(output.details.as_deref(), Some("let value: i32"));
+        assert_eq!(output.locations.len(), 1);
+        assert_eq!(output.locations[0].line, 6);
+
+        test.server
+            .set_request_handler::<lsp::request::HoverRequest, _, _>(
+                |_, _| async move { Ok(None) },
+            );
+        let input = json!({ "path": path!("root/src/main.rs"), "line": 6, "symbol": "value" });
+        let (message, output) = run_tool(HoverTool, input, &test, cx).await.unwrap();
+        assert_eq!(
+            message,
+            "The language server has no information about `value`."
+        );
+        assert_eq!(output.details, None);
+    }
+}
@@ -53,6 +53,10 @@ impl Tool for ListDirectoryTool {
         false
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> String {
         include_str!("./list_directory_tool/description.md").into()
     }
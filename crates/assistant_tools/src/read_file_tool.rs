@@ -63,6 +63,10 @@ impl Tool for ReadFileTool {
         false
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> String {
         include_str!("./read_file_tool/description.md").into()
     }
@@ -0,0 +1,462 @@
+use crate::code_navigation::{
+    CodeLocation, CodeNavigationCard, CodeNavigationOutput, finish, line_text, symbol_position,
+    write_locations,
+};
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use collections::{BTreeSet, HashSet};
+use futures::channel::oneshot;
+use gpui::{AnyWindowHandle, App, AppContext, Entity, Task, Window};
+use language::Point;
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{PrepareRenameResponse, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, sync::Arc};
+use ui::IconName;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    /// The relative path of a file containing the symbol, starting with one of the project's root directories.
+    pub path: String,
+
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+
+    /// The current name of the symbol, exactly as it's written on that line.
+    pub symbol: String,
+
+    /// The new name for the symbol.
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool;
+
+impl Tool for RenameSymbolTool {
+    fn name(&self) -> String {
+        "rename_symbol".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./rename_symbol_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Pencil
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<RenameSymbolToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<RenameSymbolToolInput>(input.clone()) {
+            Ok(input) => format!("Rename `{}` to `{}`", input.symbol, input.new_name),
+            Err(_) => "Rename symbol".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<RenameSymbolToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        let card = cx.new(|cx| {
+            CodeNavigationCard::new(IconName::Pencil, input.symbol.clone(), receiver, cx)
+        });
+        let position = symbol_position(&project, &input.path, input.line, &input.symbol, cx);
+
+        let task = cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let response = project
+                .update(cx, |project, cx| {
+                    project.prepare_rename(buffer.clone(), position, cx)
+                })?
+                .await?;
+            if let PrepareRenameResponse::InvalidPosition = response {
+                return Err(anyhow!(
+                    "The language server can't rename `{}` on line {} of {}",
+                    input.symbol,
+                    input.line,
+                    input.path
+                ));
+            }
+
+            // Only save the files that had no unsaved changes before the rename, so that the user's
+            // own edits are never written to disk behind their back.
+            let dirty_buffers = project.read_with(cx, |project, cx| {
+                project
+                    .opened_buffers(cx)
+                    .into_iter()
+                    .filter(|buffer| buffer.read(cx).is_dirty())
+                    .collect::<HashSet<_>>()
+            })?;
+
+            let transaction = project
+                .update(cx, |project, cx| {
+                    project.perform_rename(buffer.clone(), position, input.new_name.clone(), cx)
+                })?
+                .await?;
+
+            let locations = cx.update(|cx| {
+                let mut locations = Vec::new();
+                for (buffer, transaction) in &transaction.0 {
+                    // The language server's edits were applied as they arrived, so only now can
+                    // they be attributed to the agent.
+                    action_log.update(cx, |log, cx| {
+                        log.buffer_edited_in_transaction(buffer.clone(), transaction, cx)
+                    });
+
+                    let buffer = buffer.read(cx);
+                    let Some(file) = buffer.file() else {
+                        continue;
+                    };
+                    let path = file.full_path(cx);
+                    let snapshot = buffer.snapshot();
+                    let mut rows = buffer
+                        .edited_ranges_for_transaction::<Point>(transaction)
+                        .map(|range| range.start.row)
+                        .collect::<Vec<_>>();
+                    rows.sort_unstable();
+                    rows.dedup();
+                    locations.extend(rows.into_iter().map(|row| CodeLocation {
+                        path: path.clone(),
+                        line: row + 1,
+                        text: line_text(&snapshot, row).trim().to_string(),
+                    }));
+                }
+                locations.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+                locations
+            })?;
+
+            let file_count = transaction.0.len();
+            let (unsaved_buffers, buffers): (HashSet<_>, HashSet<_>) = transaction
+                .0
+                .keys()
+                .cloned()
+                .partition(|buffer| dirty_buffers.contains(buffer));
+            project
+                .update(cx, |project, cx| project.save_buffers(buffers, cx))?
+                .await?;
+            let unsaved_paths = cx.update(|cx| {
+                unsaved_buffers
+                    .iter()
+                    .filter_map(|buffer| Some(buffer.read(cx).file()?.full_path(cx)))
+                    .collect::<BTreeSet<_>>()
+            })?;
+
+            let (title, mut message) = if locations.is_empty() {
+                (
+                    "Nothing renamed".to_string(),
+                    format!(
+                        "The language server made no edits to rename `{}`.",
+                        input.symbol
+                    ),
+                )
+            } else {
+                (
+                    format!("Renamed to {}", input.new_name),
+                    format!(
+                        "Renamed `{}` to `{}` in {file_count} file(s). Edited lines:\n",
+                        input.symbol, input.new_name
+                    ),
+                )
+            };
+            write_locations(&mut message, &locations);
+            if !unsaved_paths.is_empty() {
+                message.push_str(
+                    "\nThese files already had unsaved changes, so they were left unsaved:\n",
+                );
+                for path in &unsaved_paths {
+                    writeln!(&mut message, "{}", path.display()).ok();
+                }
+            }
+
+            let output = CodeNavigationOutput {
+                title,
+                symbol: input.symbol,
+                locations,
+                details: None,
+            };
+            finish(output, message, sender)
+        });
+
+        ToolResult {
+            output: task,
+            card: Some(card.into()),
+        }
+    }
+
+    fn deserialize_card(
+        self: Arc<Self>,
+        output: serde_json::Value,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Option<assistant_tool::AnyToolCard> {
+        let output = serde_json::from_value::<CodeNavigationOutput>(output).ok()?;
+        let card = cx.new(|_| CodeNavigationCard::from_output(IconName::Pencil, output));
+        Some(card.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_navigation::tests::{MAIN_RS, lsp_range, main_rs_url, run_tool, test_project};
+    use fs::Fs as _;
+    use gpui::TestAppContext;
+    use language::Buffer;
+    use language_model::fake_provider::FakeLanguageModel;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use util::path;
+
+    const UTIL_RS: &str = "use crate::helper;\n\npub fn twice() -> i32 {\n    helper() * 2\n}\n";
+
+    #[gpui::test]
+    async fn test_rename_symbol(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS, "util.rs": UTIL_RS } }),
+            lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        let util_rs_url = lsp::Url::from_file_path(path!("/root/src/util.rs")).unwrap();
+        test.server
+            .set_request_handler::<lsp::request::Rename, _, _>({
+                let util_rs_url = util_rs_url.clone();
+                move |params, _| {
+                    let util_rs_url = util_rs_url.clone();
+                    async move {
+                        let position = params.text_document_position;
+                        assert_eq!(position.text_document.uri, main_rs_url());
+                        assert_eq!(position.position, lsp::Position::new(0, 3));
+                        assert_eq!(params.new_name, "assist");
+                        let edit = |range| lsp::TextEdit::new(range, "assist".to_string());
+                        Ok(Some(lsp::WorkspaceEdit {
+                            changes: Some(
+                                [
+                                    (
+                                        main_rs_url(),
+                                        vec![edit(lsp_range(0, 3, 9)), edit(lsp_range(5, 16, 22))],
+                                    ),
+                                    (
+                                        util_rs_url,
+                                        vec![edit(lsp_range(0, 11, 17)), edit(lsp_range(3, 4, 10))],
+                                    ),
+                                ]
+                                .into_iter()
+                                .collect(),
+                            ),
+                            ..Default::default()
+                        }))
+                    }
+                }
+            });
+
+        // The user has unsaved changes in one of the files that the rename touches.
+        test.buffer.update(cx, |buffer, cx| {
+            let end = buffer.len();
+            buffer.edit([(end..end, "// unsaved\n")], None, cx);
+        });
+
+        let input = json!({
+            "path": path!("root/src/main.rs"),
+            "line": 1,
+            "symbol": "helper",
+            "new_name": "assist",
+        });
+        let (message, output) = run_tool(RenameSymbolTool, input, &test, cx).await.unwrap();
+        assert_eq!(
+            message,
+            format!(
+                "Renamed `helper` to `assist` in 2 file(s). Edited lines:\n\
+                {0}:1: fn assist() -> i32 {{\n\
+                {0}:6: let value = assist();\n\
+                {1}:1: use crate::assist;\n\
+                {1}:4: assist() * 2\n\
+                \n\
+                These files already had unsaved changes, so they were left unsaved:\n\
+                {0}\n",
+                path!("root/src/main.rs"),
+                path!("root/src/util.rs"),
+            )
+        );
+        assert_eq!(output.title, "Renamed to assist");
+        cx.run_until_parked();
+
+        // Only the file that was clean before the rename was saved.
+        let util_buffer = test
+            .project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/util.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let renamed_util_rs = UTIL_RS.replace("helper", "assist");
+        assert_eq!(
+            test.fs
+                .load(path!("/root/src/util.rs").as_ref())
+                .await
+                .unwrap(),
+            renamed_util_rs
+        );
+        assert_eq!(
+            test.fs
+                .load(path!("/root/src/main.rs").as_ref())
+                .await
+                .unwrap(),
+            MAIN_RS
+        );
+        test.buffer.read_with(cx, |buffer, _| {
+            assert!(buffer.is_dirty());
+            assert_eq!(
+                buffer.text(),
+                format!("{}// unsaved\n", MAIN_RS.replace("helper", "assist"))
+            );
+        });
+        util_buffer.read_with(cx, |buffer, _| assert!(!buffer.is_dirty()));
+
+        // The rename is attributed to the agent, but the user's own edit isn't.
+        assert_eq!(
+            unreviewed_hunks(&test.action_log, &test.buffer, cx),
+            vec![
+                (0, "fn helper() -> i32 {\n".to_string()),
+                (5, "    let value = helper();\n".to_string()),
+            ]
+        );
+        assert_eq!(
+            unreviewed_hunks(&test.action_log, &util_buffer, cx),
+            vec![
+                (0, "use crate::helper;\n".to_string()),
+                (3, "    helper() * 2\n".to_string()),
+            ]
+        );
+
+        // Rejecting the agent's edits keeps the user's.
+        test.action_log
+            .update(cx, |log, cx| {
+                log.reject_edits_in_ranges(
+                    test.buffer.clone(),
+                    vec![language::Anchor::MIN..language::Anchor::MAX],
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        test.buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.text(), format!("{MAIN_RS}// unsaved\n"));
+        });
+
+        // The rename is still a single undoable transaction.
+        util_buffer.update(cx, |buffer, cx| {
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), UTIL_RS);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_cancel_rename_symbol(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS } }),
+            lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        // The language server never finishes the rename.
+        test.server
+            .set_request_handler::<lsp::request::Rename, _, _>(|_, _| async move {
+                futures::future::pending().await
+            });
+        test.action_log.update(cx, |log, cx| {
+            log.buffer_read(test.buffer.clone(), cx);
+        });
+
+        let input = json!({
+            "path": path!("root/src/main.rs"),
+            "line": 1,
+            "symbol": "helper",
+            "new_name": "assist",
+        });
+        let result = cx.update(|cx| {
+            Arc::new(RenameSymbolTool).run(
+                input,
+                Arc::default(),
+                test.project.clone(),
+                test.action_log.clone(),
+                Arc::new(FakeLanguageModel::default()),
+                None,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        // Neither the user's edits during the rename nor after it was cancelled are
+        // attributed to the agent.
+        test.buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "// during\n")], None, cx);
+        });
+        cx.run_until_parked();
+        drop(result);
+        cx.run_until_parked();
+        test.buffer.update(cx, |buffer, cx| {
+            let end = buffer.len();
+            buffer.edit([(end..end, "// after\n")], None, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            unreviewed_hunks(&test.action_log, &test.buffer, cx),
+            Vec::new()
+        );
+    }
+
+    fn unreviewed_hunks(
+        action_log: &Entity<ActionLog>,
+        buffer: &Entity<Buffer>,
+        cx: &TestAppContext,
+    ) -> Vec<(u32, String)> {
+        cx.read(|cx| {
+            let Some(diff) = action_log.read(cx).changed_buffers(cx).remove(buffer) else {
+                return Vec::new();
+            };
+            let snapshot = buffer.read(cx).snapshot();
+            let diff = diff.read(cx);
+            diff.hunks(&snapshot, cx)
+                .map(|hunk| {
+                    let old_text = diff
+                        .base_text()
+                        .text_for_range(hunk.diff_base_byte_range)
+                        .collect();
+                    (hunk.range.start.row, old_text)
+                })
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,374 @@
+use crate::code_navigation::{
+    CodeLocation, CodeNavigationCard, CodeNavigationOutput, finish, write_locations,
+};
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use futures::channel::oneshot;
+use gpui::{AnyWindowHandle, App, AppContext, Entity, Task, Window};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{DocumentSymbol, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp,
+    fmt::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::IconName;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolsToolInput {
+    /// Only return symbols whose name contains this text (case-insensitive).
+    ///
+    /// When searching the whole project, this is passed to the language server's workspace symbol search,
+    /// which may also match fuzzily.
+    #[serde(default)]
+    pub query: String,
+
+    /// Optional relative path of a file to outline, starting with one of the project's root directories.
+    ///
+    /// When provided, the symbols defined in that file are listed, nested under their parents.
+    /// When omitted, the whole project is searched for symbols matching `query`.
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Optional starting position for paginated results (0-based).
+    /// When not provided, starts from the beginning.
+    #[serde(default)]
+    pub offset: usize,
+}
+
+const RESULTS_PER_PAGE: usize = 100;
+
+pub struct SymbolsTool;
+
+impl Tool for SymbolsTool {
+    fn name(&self) -> String {
+        "symbols".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./symbols_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::ListTree
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<SymbolsToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<SymbolsToolInput>(input.clone()) {
+            Ok(SymbolsToolInput {
+                path: Some(path), ..
+            }) => format!("List symbols in `{path}`"),
+            Ok(input) => format!("Search symbols matching “`{}`”", input.query),
+            Err(_) => "Search symbols".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<SymbolsToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+
+        let label = input.path.clone().unwrap_or_else(|| input.query.clone());
+        let (sender, receiver) = oneshot::channel();
+        let card =
+            cx.new(|cx| CodeNavigationCard::new(IconName::ListTree, label.clone(), receiver, cx));
+
+        let symbols = match &input.path {
+            Some(path) => document_symbols(&project, path, &input.query, cx),
+            None => workspace_symbols(&project, &input.query, cx),
+        };
+
+        let task = cx.background_spawn(async move {
+            let symbols = symbols.await?;
+            let total = symbols.len();
+            let offset = cmp::min(input.offset, total);
+            let page_end = cmp::min(offset + RESULTS_PER_PAGE, total);
+            let locations = symbols[offset..page_end].to_vec();
+
+            let title = match total {
+                0 => "No symbols".to_string(),
+                1 => "1 symbol".to_string(),
+                count => format!("{count} symbols"),
+            };
+            let mut message = if total == 0 {
+                "The language server found no matching symbols.".to_string()
+            } else {
+                format!("Found {total} symbols.")
+            };
+            if total > RESULTS_PER_PAGE {
+                write!(
+                    &mut message,
+                    "\nShowing results {}-{} (provide 'offset' parameter for more results):",
+                    offset + 1,
+                    page_end
+                )?;
+            }
+            if !locations.is_empty() {
+                message.push('\n');
+            }
+            write_locations(&mut message, &locations);
+
+            let output = CodeNavigationOutput {
+                title,
+                symbol: label,
+                locations,
+                details: None,
+            };
+            finish(output, message, sender)
+        });
+
+        ToolResult {
+            output: task,
+            card: Some(card.into()),
+        }
+    }
+
+    fn deserialize_card(
+        self: Arc<Self>,
+        output: serde_json::Value,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Option<assistant_tool::AnyToolCard> {
+        let output = serde_json::from_value::<CodeNavigationOutput>(output).ok()?;
+        let card = cx.new(|_| CodeNavigationCard::from_output(IconName::ListTree, output));
+        Some(card.into())
+    }
+}
+
+fn kind_label(kind: lsp::SymbolKind) -> String {
+    format!("{kind:?}").to_lowercase()
+}
+
+fn workspace_symbols(
+    project: &Entity<Project>,
+    query: &str,
+    cx: &mut App,
+) -> Task<Result<Vec<CodeLocation>>> {
+    let symbols = project.update(cx, |project, cx| project.symbols(query, cx));
+    let project = project.downgrade();
+    cx.spawn(async move |cx| {
+        let symbols = symbols.await?;
+        project.read_with(cx, |project, cx| {
+            symbols
+                .into_iter()
+                .filter_map(|symbol| {
+                    let worktree = project.worktree_for_id(symbol.path.worktree_id, cx)?;
+                    let path = PathBuf::from(worktree.read(cx).root_name()).join(&symbol.path.path);
+                    Some(CodeLocation {
+                        path,
+                        line: symbol.range.start.0.row + 1,
+                        text: format!("{} {}", kind_label(symbol.kind), symbol.label.text),
+                    })
+                })
+                .collect()
+        })
+    })
+}
+
+fn document_symbols(
+    project: &Entity<Project>,
+    path: &str,
+    query: &str,
+    cx: &mut App,
+) -> Task<Result<Vec<CodeLocation>>> {
+    let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+        return Task::ready(Err(anyhow!("Could not find path {path} in project")));
+    };
+    let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let project = project.clone();
+    let query = query.to_lowercase();
+    cx.spawn(async move |cx| {
+        let buffer = buffer.await?;
+        let path = buffer
+            .read_with(cx, |buffer, cx| {
+                buffer.file().map(|file| file.full_path(cx))
+            })?
+            .ok_or_else(|| anyhow!("{path} is not a file on disk"))?;
+        let symbols = project
+            .update(cx, |project, cx| project.document_symbols(&buffer, cx))?
+            .await?;
+
+        let mut locations = Vec::new();
+        collect_document_symbols(&symbols, 0, &query, &path, &mut locations);
+        Ok(locations)
+    })
+}
+
+fn collect_document_symbols(
+    symbols: &[DocumentSymbol],
+    depth: usize,
+    query: &str,
+    path: &Path,
+    locations: &mut Vec<CodeLocation>,
+) {
+    for symbol in symbols {
+        if symbol.name.to_lowercase().contains(query) {
+            locations.push(CodeLocation {
+                path: path.to_path_buf(),
+                line: symbol.selection_range.start.0.row + 1,
+                text: format!(
+                    "{}{} {}",
+                    "  ".repeat(depth),
+                    kind_label(symbol.kind),
+                    symbol.name
+                ),
+            });
+        }
+        collect_document_symbols(&symbol.children, depth + 1, query, path, locations);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_navigation::tests::{MAIN_RS, lsp_range, main_rs_url, run_tool, test_project};
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_document_symbols(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS } }),
+            lsp::ServerCapabilities {
+                document_symbol_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        test.server
+            .set_request_handler::<lsp::request::DocumentSymbolRequest, _, _>(
+                |params, _| async move {
+                    assert_eq!(params.text_document.uri, main_rs_url());
+                    Ok(Some(lsp::DocumentSymbolResponse::Nested(vec![
+                        document_symbol("helper", lsp::SymbolKind::FUNCTION, 0, Vec::new()),
+                        document_symbol(
+                            "main",
+                            lsp::SymbolKind::FUNCTION,
+                            4,
+                            vec![document_symbol(
+                                "value",
+                                lsp::SymbolKind::VARIABLE,
+                                5,
+                                Vec::new(),
+                            )],
+                        ),
+                    ])))
+                },
+            );
+
+        let input = json!({ "path": path!("root/src/main.rs") });
+        let (message, output) = run_tool(SymbolsTool, input, &test, cx).await.unwrap();
+        assert_eq!(
+            message,
+            format!(
+                "Found 3 symbols.\n\
+                {0}:1: function helper\n\
+                {0}:5: function main\n\
+                {0}:6:   variable value\n",
+                path!("root/src/main.rs")
+            )
+        );
+        assert_eq!(output.title, "3 symbols");
+
+        let input = json!({ "path": path!("root/src/main.rs"), "query": "VAL" });
+        let (message, _) = run_tool(SymbolsTool, input, &test, cx).await.unwrap();
+        assert_eq!(
+            message,
+            format!(
+                "Found 1 symbols.\n{}:6:   variable value\n",
+                path!("root/src/main.rs")
+            )
+        );
+    }
+
+    #[gpui::test]
+    async fn test_workspace_symbols(cx: &mut TestAppContext) {
+        let test = test_project(
+            json!({ "src": { "main.rs": MAIN_RS } }),
+            lsp::ServerCapabilities {
+                workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        test.server
+            .set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(|params, _| async move {
+                assert_eq!(params.query, "help");
+                #[allow(deprecated)]
+                let symbol = lsp::SymbolInformation {
+                    name: "helper".to_string(),
+                    kind: lsp::SymbolKind::FUNCTION,
+                    tags: None,
+                    deprecated: None,
+                    container_name: None,
+                    location: lsp::Location::new(main_rs_url(), lsp_range(0, 3, 9)),
+                };
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![symbol])))
+            });
+
+        let input = json!({ "query": "help" });
+        let (message, output) = run_tool(SymbolsTool, input, &test, cx).await.unwrap();
+        assert_eq!(
+            message,
+            format!(
+                "Found 1 symbols.\n{}:1: function helper\n",
+                path!("root/src/main.rs")
+            )
+        );
+        assert_eq!(output.title, "1 symbol");
+    }
+
+    fn document_symbol(
+        name: &str,
+        kind: lsp::SymbolKind,
+        row: u32,
+        children: Vec<lsp::DocumentSymbol>,
+    ) -> lsp::DocumentSymbol {
+        #[allow(deprecated)]
+        lsp::DocumentSymbol {
+            name: name.to_string(),
+            detail: None,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: lsp_range(row, 0, 0),
+            selection_range: lsp_range(row, 0, 0),
+            children: Some(children),
+        }
+    }
+}
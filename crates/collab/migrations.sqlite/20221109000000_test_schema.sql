@@ -466,6 +466,7 @@ CREATE TABLE extension_versions (
     provides_indexed_docs_providers BOOLEAN NOT NULL DEFAULT FALSE,
     provides_snippets BOOLEAN NOT NULL DEFAULT FALSE,
     provides_debug_adapters BOOLEAN NOT NULL DEFAULT FALSE,
+    archive_sha256 TEXT,
     PRIMARY KEY (extension_id, version)
 );
 
@@ -0,0 +1,2 @@
+alter table extension_versions
+add column archive_sha256 text
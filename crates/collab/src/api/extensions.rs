@@ -414,6 +414,7 @@ async fn fetch_extension_manifest(
         schema_version: manifest.schema_version.unwrap_or(0),
         wasm_api_version: manifest.wasm_api_version,
         provides: manifest.provides,
+        archive_sha256: manifest.archive_sha256,
         published_at,
     })
 }
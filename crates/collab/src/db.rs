@@ -804,6 +804,7 @@ pub struct NewExtensionVersion {
     pub schema_version: i32,
     pub wasm_api_version: Option<String>,
     pub provides: BTreeSet<ExtensionProvides>,
+    pub archive_sha256: Option<String>,
     pub published_at: PrimitiveDateTime,
 }
 
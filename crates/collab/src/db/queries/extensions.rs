@@ -324,6 +324,7 @@ impl Database {
                         provides_debug_adapters: ActiveValue::Set(
                             version.provides.contains(&ExtensionProvides::DebugAdapters),
                         ),
+                        archive_sha256: ActiveValue::Set(version.archive_sha256.clone()),
                         download_count: ActiveValue::NotSet,
                     }
                 }))
@@ -462,6 +463,7 @@ fn metadata_from_extension_and_version(
             schema_version: Some(version.schema_version),
             wasm_api_version: version.wasm_api_version,
             provides,
+            archive_sha256: version.archive_sha256,
         },
 
         published_at: convert_time_to_chrono(version.published_at),
@@ -28,6 +28,7 @@ pub struct Model {
     pub provides_indexed_docs_providers: bool,
     pub provides_snippets: bool,
     pub provides_debug_adapters: bool,
+    pub archive_sha256: Option<String>,
 }
 
 impl Model {
@@ -42,6 +42,7 @@ async fn test_extensions(db: &Arc<Database>) {
                         schema_version: 1,
                         wasm_api_version: None,
                         provides: BTreeSet::default(),
+                        archive_sha256: None,
                         published_at: t0,
                     },
                     NewExtensionVersion {
@@ -53,6 +54,7 @@ async fn test_extensions(db: &Arc<Database>) {
                         schema_version: 1,
                         wasm_api_version: None,
                         provides: BTreeSet::default(),
+                        archive_sha256: None,
                         published_at: t0,
                     },
                 ],
@@ -68,6 +70,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: 0,
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                     published_at: t0,
                 }],
             ),
@@ -105,6 +108,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(1),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                 },
                 published_at: t0_chrono,
                 download_count: 0,
@@ -120,6 +124,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(0),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                 },
                 published_at: t0_chrono,
                 download_count: 0
@@ -142,6 +147,7 @@ async fn test_extensions(db: &Arc<Database>) {
                 schema_version: Some(0),
                 wasm_api_version: None,
                 provides: BTreeSet::default(),
+                archive_sha256: None,
             },
             published_at: t0_chrono,
             download_count: 0
@@ -184,6 +190,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(0),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                 },
                 published_at: t0_chrono,
                 download_count: 7
@@ -199,6 +206,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(1),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                 },
                 published_at: t0_chrono,
                 download_count: 5,
@@ -221,6 +229,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: 1,
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                     published_at: t0,
                 }],
             ),
@@ -235,6 +244,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: 0,
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                     published_at: t0,
                 }],
             ),
@@ -274,6 +284,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(0),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                 },
                 published_at: t0_chrono,
                 download_count: 7
@@ -289,6 +300,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(1),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                 },
                 published_at: t0_chrono,
                 download_count: 5,
@@ -332,6 +344,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                             ExtensionProvides::Grammars,
                             ExtensionProvides::Languages,
                         ]),
+                        archive_sha256: None,
                         published_at: t0,
                     },
                     NewExtensionVersion {
@@ -347,6 +360,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                             ExtensionProvides::Languages,
                             ExtensionProvides::LanguageServers,
                         ]),
+                        archive_sha256: None,
                         published_at: t0,
                     },
                     NewExtensionVersion {
@@ -362,6 +376,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                             ExtensionProvides::Languages,
                             ExtensionProvides::LanguageServers,
                         ]),
+                        archive_sha256: None,
                         published_at: t0,
                     },
                 ],
@@ -377,6 +392,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                     schema_version: 0,
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_sha256: None,
                     published_at: t0,
                 }],
             ),
@@ -415,6 +431,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                     ExtensionProvides::Languages,
                     ExtensionProvides::LanguageServers,
                 ]),
+                archive_sha256: None,
             },
             published_at: t0_chrono,
             download_count: 0,
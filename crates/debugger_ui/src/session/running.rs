@@ -1119,6 +1119,8 @@ impl RunningState {
                 show_summary: false,
                 show_command: false,
                 show_rerun: false,
+                problem_matchers: Vec::new(),
+                ready_pattern: None,
             })
         } else {
             TerminalKind::Shell(cwd.map(|c| c.to_path_buf()))
@@ -12067,6 +12067,44 @@ impl Editor {
         }
     }
 
+    /// Moves a singleton buffer to any state of its undo tree, including states on branches
+    /// that are no longer reachable through undo and redo.
+    pub fn go_to_undo_tree_state(
+        &mut self,
+        state: Option<TransactionId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.read_only(cx) {
+            return false;
+        }
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return false;
+        };
+
+        let previous_state = buffer.read(cx).undo_tree_state();
+        if !buffer.update(cx, |buffer, cx| buffer.go_to_undo_tree_state(state, cx)) {
+            return false;
+        }
+
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        if let Some((_, Some(selections))) = state
+            .and_then(|transaction_id| self.selection_history.transaction(transaction_id))
+            .cloned()
+        {
+            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_anchors(selections.to_vec());
+            });
+        }
+        self.request_autoscroll(Autoscroll::fit(), cx);
+        self.unmark_text(window, cx);
+        self.refresh_inline_completion(true, false, window, cx);
+        if let Some(transaction_id) = state.or(previous_state) {
+            cx.emit(EditorEvent::Edited { transaction_id });
+        }
+        true
+    }
+
     pub fn finalize_last_transaction(&mut self, cx: &mut Context<Self>) {
         self.buffer
             .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
@@ -572,33 +572,34 @@ async fn parse_commit_messages(
 ) -> HashMap<Oid, ParsedCommitMessage> {
     let mut commit_details = HashMap::default();
 
-    let parsed_remote_url = remote_url
-        .as_deref()
-        .and_then(|remote_url| parse_git_remote_url(provider_registry, remote_url));
+    let parsed_remote_url = if let Some(remote_url) = remote_url.as_deref() {
+        parse_git_remote_url(provider_registry, remote_url).await
+    } else {
+        None
+    };
 
     for (oid, message) in messages {
-        let permalink = if let Some((provider, git_remote)) = parsed_remote_url.as_ref() {
-            Some(provider.build_commit_permalink(
-                git_remote,
-                git::BuildCommitPermalinkParams {
-                    sha: oid.to_string().as_str(),
-                },
-            ))
-        } else {
-            None
-        };
-
-        let remote = parsed_remote_url
-            .as_ref()
-            .map(|(provider, remote)| GitRemote {
+        let mut permalink = None;
+        let mut remote = None;
+        let mut pull_request = None;
+        if let Some((provider, git_remote)) = parsed_remote_url.as_ref() {
+            permalink = Some(
+                provider
+                    .build_commit_permalink(
+                        git_remote,
+                        git::BuildCommitPermalinkParams {
+                            sha: oid.to_string().as_str(),
+                        },
+                    )
+                    .await,
+            );
+            remote = Some(GitRemote {
                 host: provider.clone(),
-                owner: remote.owner.to_string(),
-                repo: remote.repo.to_string(),
+                owner: git_remote.owner.to_string(),
+                repo: git_remote.repo.to_string(),
             });
-
-        let pull_request = parsed_remote_url
-            .as_ref()
-            .and_then(|(provider, remote)| provider.extract_pull_request(remote, &message));
+            pull_request = provider.extract_pull_request(git_remote, &message).await;
+        }
 
         commit_details.insert(
             oid,
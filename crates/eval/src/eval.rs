@@ -4,10 +4,12 @@ mod examples;
 mod explorer;
 mod ids;
 mod instance;
+mod replay;
 mod tool_metrics;
 
 use assertions::{AssertionsReport, display_error_row};
 use instance::{ExampleInstance, JudgeOutput, RunOutput, run_git};
+use replay::{Recording, RecordingLanguageModel, ReplayLanguageModel};
 pub(crate) use tool_metrics::*;
 
 use ::fs::RealFs;
@@ -60,6 +62,14 @@ struct Args {
     /// Maximum number of examples to run concurrently.
     #[arg(long, default_value = "4")]
     concurrency: usize,
+    /// Records the agent model's completions to `src/examples/recordings`, so that examples can be
+    /// replayed with `--replay`.
+    #[arg(long, conflicts_with = "replay")]
+    record: bool,
+    /// Plays back recorded completions instead of using live models. Judge assertions are skipped,
+    /// since they need a live model.
+    #[arg(long)]
+    replay: bool,
 }
 
 fn main() {
@@ -87,6 +97,7 @@ fn main() {
     let repos_dir = eval_crate_dir.join("repos");
     let worktrees_dir = eval_crate_dir.join("worktrees");
     let examples_dir = eval_crate_dir.join("src").join("examples");
+    let recordings_dir = examples_dir.join("recordings");
     let run_dir = eval_crate_dir
         .join("runs")
         .join(format!("{}", run_timestamp));
@@ -100,6 +111,12 @@ fn main() {
     let zed_branch_name = git_branch_for_path(&root_dir);
     let args = Args::parse();
     let languages: HashSet<String> = args.languages.into_iter().collect();
+    // Recorded runs are deterministic, so there's no point in repeating them.
+    let repetitions = if args.record || args.replay {
+        1
+    } else {
+        args.repetitions
+    };
 
     let http_client = Arc::new(ReqwestClient::new());
     let app = Application::headless().with_http_client(http_client.clone());
@@ -125,19 +142,30 @@ fn main() {
 
         let mut cumulative_tool_metrics = ToolMetrics::default();
 
-        let agent_model = load_model(&args.model, cx).unwrap();
-        let judge_model = load_model(&args.judge_model, cx).unwrap();
+        // When replaying, each example gets its own model that plays back its recording.
+        let live_models = (!args.replay).then(|| {
+            let agent_model = load_model(&args.model, cx).unwrap();
+            let judge_model = load_model(&args.judge_model, cx).unwrap();
+
+            LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+                registry.set_default_model(Some(agent_model.clone()), cx);
+            });
 
-        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
-            registry.set_default_model(Some(agent_model.clone()), cx);
+            (agent_model, judge_model)
         });
 
-        let auth1 = agent_model.provider.authenticate(cx);
-        let auth2 = judge_model.provider.authenticate(cx);
+        let auth_tasks = live_models.as_ref().map(|(agent_model, judge_model)| {
+            (
+                agent_model.provider.authenticate(cx),
+                judge_model.provider.authenticate(cx),
+            )
+        });
 
         cx.spawn(async move |cx| {
-            auth1.await?;
-            auth2.await?;
+            if let Some((auth1, auth2)) = auth_tasks {
+                auth1.await?;
+                auth2.await?;
+            }
 
             let mut examples = Vec::new();
 
@@ -179,7 +207,7 @@ fn main() {
                 // TODO: This creates a worktree per repetition. Ideally these examples should
                 // either be run sequentially on the same worktree, or reuse worktrees when there
                 // are more examples to run than the concurrency limit.
-                for repetition_number in 0..args.repetitions {
+                for repetition_number in 0..repetitions {
                     let example_instance = ExampleInstance::new(
                         thread.clone(),
                         &repos_dir,
@@ -268,8 +296,8 @@ fn main() {
 
             future::join_all((0..args.concurrency).map(|_| {
                 let app_state = app_state.clone();
-                let model = agent_model.model.clone();
-                let judge_model = judge_model.model.clone();
+                let live_models = live_models.clone();
+                let recordings_dir = recordings_dir.clone();
                 let zed_commit_sha = zed_commit_sha.clone();
                 let zed_branch_name = zed_branch_name.clone();
                 let run_id = run_id.clone();
@@ -282,20 +310,51 @@ fn main() {
                         };
                         let result = async {
                             example.setup().await?;
+
+                            let recording_path =
+                                recordings_dir.join(format!("{}.json", example.name));
+                            let mut recording_model = None;
+                            let model = match &live_models {
+                                Some((agent_model, _)) if args.record => {
+                                    let model = Arc::new(RecordingLanguageModel::new(
+                                        agent_model.model.clone(),
+                                    ));
+                                    recording_model = Some(model.clone());
+                                    ConfiguredModel {
+                                        provider: agent_model.provider.clone(),
+                                        model,
+                                    }
+                                }
+                                Some((agent_model, _)) => agent_model.clone(),
+                                None => {
+                                    ReplayLanguageModel::new(Recording::load(&recording_path)?)
+                                        .into_configured_model()
+                                }
+                            };
+
                             let run_output = cx
-                                .update(|cx| example.run(model.clone(), app_state.clone(), cx))?
+                                .update(|cx| example.run(model, app_state.clone(), cx))?
                                 .await?;
-                            let judge_output = judge_example(
-                                example.clone(),
-                                judge_model.clone(),
-                                &zed_commit_sha,
-                                &zed_branch_name,
-                                &run_id,
-                                &run_output,
-                                enable_telemetry,
-                                cx,
-                            )
-                            .await;
+
+                            if let Some(recording_model) = recording_model {
+                                recording_model.save(&recording_path)?;
+                            }
+
+                            let judge_output = if let Some((_, judge_model)) = &live_models {
+                                judge_example(
+                                    example.clone(),
+                                    judge_model.model.clone(),
+                                    &zed_commit_sha,
+                                    &zed_branch_name,
+                                    &run_id,
+                                    &run_output,
+                                    enable_telemetry,
+                                    cx,
+                                )
+                                .await
+                            } else {
+                                JudgeOutput::default()
+                            };
                             anyhow::Ok((run_output, judge_output))
                         }
                         .await;
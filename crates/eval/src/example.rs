@@ -298,6 +298,7 @@ impl ExampleContext {
                 | ThreadEvent::SummaryChanged
                 | ThreadEvent::SummaryGenerated
                 | ThreadEvent::ProfileChanged
+                | ThreadEvent::DirectoryRulesChanged
                 | ThreadEvent::ReceivedTextChunk
                 | ThreadEvent::StreamedToolUse { .. }
                 | ThreadEvent::CheckpointChanged
@@ -8,8 +8,8 @@ use gpui::{App, AppContext as _, AsyncApp, Entity, Task};
 use handlebars::Handlebars;
 use language::{Buffer, DiagnosticSeverity, OffsetRangeExt as _};
 use language_model::{
-    LanguageModel, LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelRequestMessage,
-    LanguageModelToolResultContent, MessageContent, Role, TokenUsage,
+    ConfiguredModel, LanguageModel, LanguageModelCompletionEvent, LanguageModelRequest,
+    LanguageModelRequestMessage, LanguageModelToolResultContent, MessageContent, Role, TokenUsage,
 };
 use project::lsp_store::OpenLspBufferHandle;
 use project::{DiagnosticSummary, Project, ProjectPath};
@@ -76,7 +76,7 @@ pub struct JudgeThreadInput {
     pub assertion: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct JudgeOutput {
     pub thread: AssertionsReport,
     pub diff: AssertionsReport,
@@ -199,7 +199,7 @@ impl ExampleInstance {
 
     pub fn run(
         &self,
-        model: Arc<dyn LanguageModel>,
+        model: ConfiguredModel,
         app_state: Arc<AgentAppState>,
         cx: &mut App,
     ) -> Task<Result<RunOutput>> {
@@ -317,6 +317,7 @@ impl ExampleInstance {
                     };
                     thread.update(cx, |thread, cx| {
                         thread.set_profile(meta.profile_id.clone(), cx);
+                        thread.set_configured_model(Some(model.clone()), cx);
                     });
                     thread
                 })?;
@@ -371,7 +372,7 @@ impl ExampleInstance {
                 meta.clone(),
                 this.log_prefix.clone(),
                 thread.clone(),
-                model.clone(),
+                model.model.clone(),
                 cx.clone(),
             );
             let result = this.thread.conversation(&mut example_cx).await;
@@ -0,0 +1,546 @@
+//! Records the completions of a live language model, and plays them back later, so that examples
+//! can run deterministically and without network access.
+
+use anyhow::{Context as _, Result, anyhow};
+use futures::{FutureExt as _, StreamExt as _, future::BoxFuture, stream::BoxStream};
+use gpui::{AnyView, App, AppContext as _, AsyncApp, Empty, Task, Window};
+use language_model::{
+    AuthenticateError, ConfiguredModel, LanguageModel, LanguageModelCacheConfiguration,
+    LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
+    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolSchemaFormat,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use zed_llm_client::CompletionIntent;
+
+/// The completions a model streamed while an example ran, in the order they were requested.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub provider: String,
+    pub model: String,
+    pub max_token_count: u64,
+    pub completions: Vec<RecordedCompletion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCompletion {
+    /// What the completion was requested for. The agent's requests are interleaved with those of
+    /// tools like `edit_file`, so completions are replayed in order for each intent.
+    pub intent: Option<CompletionIntent>,
+    pub events: Vec<Result<LanguageModelCompletionEvent, String>>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("no recording at {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("failed to parse recording at {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Wraps a live model, recording the completions it streams.
+pub struct RecordingLanguageModel {
+    model: Arc<dyn LanguageModel>,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl RecordingLanguageModel {
+    pub fn new(model: Arc<dyn LanguageModel>) -> Self {
+        let recording = Recording {
+            provider: model.provider_id().0.to_string(),
+            model: model.id().0.to_string(),
+            max_token_count: model.max_token_count(),
+            completions: Vec::new(),
+        };
+        Self {
+            model,
+            recording: Arc::new(Mutex::new(recording)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        self.recording.lock().unwrap().save(path)
+    }
+}
+
+impl LanguageModel for RecordingLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.model.id()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        self.model.name()
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.model.provider_id()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.model.provider_name()
+    }
+
+    fn telemetry_id(&self) -> String {
+        self.model.telemetry_id()
+    }
+
+    fn api_key(&self, cx: &App) -> Option<String> {
+        self.model.api_key(cx)
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.supports_images()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools()
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        self.model.supports_tool_choice(choice)
+    }
+
+    fn supports_burn_mode(&self) -> bool {
+        self.model.supports_burn_mode()
+    }
+
+    fn tool_input_format(&self) -> LanguageModelToolSchemaFormat {
+        self.model.tool_input_format()
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.model.max_token_count()
+    }
+
+    fn max_output_tokens(&self) -> Option<u64> {
+        self.model.max_output_tokens()
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        self.model.count_tokens(request, cx)
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        // Reserve the completion's place now, so that completions are recorded in the order
+        // they were requested rather than the order they finished in.
+        let recording = self.recording.clone();
+        let completion_ix = {
+            let mut recording = recording.lock().unwrap();
+            recording.completions.push(RecordedCompletion {
+                intent: request.intent,
+                events: Vec::new(),
+            });
+            recording.completions.len() - 1
+        };
+        let record = move |event: Result<LanguageModelCompletionEvent, String>| {
+            recording.lock().unwrap().completions[completion_ix]
+                .events
+                .push(event);
+        };
+
+        let future = self.model.stream_completion(request, cx);
+        async move {
+            match future.await {
+                Ok(events) => Ok(events
+                    .inspect(move |event| {
+                        record(event.as_ref().cloned().map_err(|error| error.to_string()))
+                    })
+                    .boxed()),
+                Err(error) => {
+                    record(Err(error.to_string()));
+                    Err(error)
+                }
+            }
+        }
+        .boxed()
+    }
+
+    fn cache_configuration(&self) -> Option<LanguageModelCacheConfiguration> {
+        self.model.cache_configuration()
+    }
+}
+
+/// Plays back the completions of a [`Recording`] instead of querying a live model.
+pub struct ReplayLanguageModel {
+    provider: String,
+    model: String,
+    max_token_count: u64,
+    completions: Mutex<VecDeque<RecordedCompletion>>,
+}
+
+impl ReplayLanguageModel {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            provider: recording.provider,
+            model: recording.model,
+            max_token_count: recording.max_token_count,
+            completions: Mutex::new(recording.completions.into()),
+        }
+    }
+
+    pub fn into_configured_model(self) -> ConfiguredModel {
+        let model = Arc::new(self);
+        ConfiguredModel {
+            provider: Arc::new(ReplayLanguageModelProvider {
+                model: model.clone(),
+            }),
+            model,
+        }
+    }
+
+    /// Takes the next recorded completion that was requested for the same reason.
+    fn next_completion(&self, intent: Option<CompletionIntent>) -> Option<RecordedCompletion> {
+        let mut completions = self.completions.lock().unwrap();
+        let ix = completions
+            .iter()
+            .position(|completion| completion.intent == intent)?;
+        completions.remove(ix)
+    }
+}
+
+impl LanguageModel for ReplayLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        LanguageModelId::from(self.model.clone())
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(format!("{} (replay)", self.model))
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId::from(self.provider.clone())
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        LanguageModelProviderName::from(self.provider.clone())
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("replay/{}/{}", self.provider, self.model)
+    }
+
+    fn supports_images(&self) -> bool {
+        false
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_tool_choice(&self, _choice: LanguageModelToolChoice) -> bool {
+        true
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.max_token_count
+    }
+
+    fn count_tokens(&self, _: LanguageModelRequest, _: &App) -> BoxFuture<'static, Result<u64>> {
+        futures::future::ready(Ok(0)).boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        _: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        let Some(completion) = self.next_completion(request.intent) else {
+            let error = anyhow!(
+                "no recorded completions left for {:?} requests; re-record the example with `--record`",
+                request.intent
+            );
+            return futures::future::ready(Err(LanguageModelCompletionError::Other(error))).boxed();
+        };
+
+        let events = completion.events.into_iter().map(|event| {
+            event.map_err(|error| LanguageModelCompletionError::Other(anyhow!(error)))
+        });
+        futures::future::ready(Ok(futures::stream::iter(events).boxed())).boxed()
+    }
+}
+
+struct ReplayLanguageModelProvider {
+    model: Arc<ReplayLanguageModel>,
+}
+
+impl LanguageModelProvider for ReplayLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        self.model.provider_id()
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.model.provider_name()
+    }
+
+    fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        Some(self.model.clone())
+    }
+
+    fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        Some(self.model.clone())
+    }
+
+    fn provided_models(&self, _cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        vec![self.model.clone()]
+    }
+
+    fn is_authenticated(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn authenticate(&self, _cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        Task::ready(Ok(()))
+    }
+
+    fn configuration_view(&self, _window: &mut Window, cx: &mut App) -> AnyView {
+        cx.new(|_| Empty).into()
+    }
+
+    fn reset_credentials(&self, _cx: &mut App) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AgentAppState;
+    use crate::example::{Example, ExampleContext, ExampleMetadata};
+    use crate::instance::{ExampleInstance, repo_path_for_url, run_git};
+    use agent_settings::{AgentProfileId, AgentSettings};
+    use async_trait::async_trait;
+    use client::{Client, UserStore};
+    use clock::FakeSystemClock;
+    use fs::RealFs;
+    use gpui::{SemanticVersion, TestAppContext, UpdateGlobal as _};
+    use http_client::FakeHttpClient;
+    use language::LanguageRegistry;
+    use language_model::{StopReason, TokenUsage};
+    use node_runtime::NodeRuntime;
+    use project::Project;
+    use prompt_store::PromptBuilder;
+    use settings::{Settings as _, SettingsStore};
+    use std::rc::Rc;
+
+    /// An example that runs against a local repository, so that it can be replayed offline.
+    struct UpdateGreetingExample;
+
+    #[async_trait(?Send)]
+    impl Example for UpdateGreetingExample {
+        fn meta(&self) -> ExampleMetadata {
+            ExampleMetadata {
+                name: "update_greeting".to_string(),
+                url: "https://example.com/greeting.git".to_string(),
+                revision: "HEAD".to_string(),
+                language_server: None,
+                max_assertions: Some(1),
+                profile_id: AgentProfileId::default(),
+                existing_thread_json: None,
+                max_turns: None,
+            }
+        }
+
+        async fn conversation(&self, cx: &mut ExampleContext) -> Result<()> {
+            cx.push_user_message("Change the greeting in greeting.txt to \"Hello, world!\".");
+            let response = cx.run_to_end().await?;
+            response.expect_tool("edit_file", cx)?;
+            Ok(())
+        }
+    }
+
+    #[gpui::test]
+    async fn test_replay_example(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let app_state = cx.update(init_test);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repos_dir = temp_dir.path().join("repos");
+        let example = Rc::new(UpdateGreetingExample);
+        let repo_path = repo_path_for_url(&repos_dir, &example.meta().url);
+        std::fs::create_dir_all(&repo_path).unwrap();
+        std::fs::write(repo_path.join("greeting.txt"), "Hello\n").unwrap();
+        run_git(&repo_path, &["init"]).await.unwrap();
+        run_git(&repo_path, &["add", "."]).await.unwrap();
+        run_git(
+            &repo_path,
+            &[
+                "-c",
+                "user.name=Zed",
+                "-c",
+                "user.email=hi@zed.dev",
+                "commit",
+                "-m",
+                "Initial commit",
+            ],
+        )
+        .await
+        .unwrap();
+
+        let mut instance = ExampleInstance::new(
+            example,
+            &repos_dir,
+            &temp_dir.path().join("runs"),
+            &temp_dir.path().join("worktrees"),
+            0,
+        );
+        instance.setup().await.unwrap();
+
+        let recording_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/examples/recordings")
+            .join(format!("{}.json", instance.name));
+        let model = ReplayLanguageModel::new(Recording::load(&recording_path).unwrap())
+            .into_configured_model();
+        let output = cx
+            .update(|cx| instance.run(model, app_state, cx))
+            .await
+            .unwrap();
+
+        assert!(
+            output.repository_diff.contains("-Hello\n+Hello, world!\n"),
+            "unexpected diff: {}",
+            output.repository_diff
+        );
+        assert_eq!(output.response_count, 2);
+        assert_eq!(
+            output.token_usage,
+            TokenUsage {
+                input_tokens: 2500,
+                output_tokens: 52,
+                ..Default::default()
+            }
+        );
+        assert_eq!(output.programmatic_assertions.run_count(), 1);
+        assert_eq!(output.programmatic_assertions.passed_count(), 1);
+        assert_eq!(output.tool_metrics.use_counts["edit_file"], 1);
+        assert!(output.tool_metrics.failure_counts.is_empty());
+    }
+
+    fn init_test(cx: &mut App) -> Arc<AgentAppState> {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        release_channel::init(SemanticVersion::default(), cx);
+        client::init_settings(cx);
+        language::init(cx);
+        Project::init_settings(cx);
+        AgentSettings::register(cx);
+        language_model::init_settings(cx);
+        prompt_store::init(cx);
+        agent::init(cx);
+
+        let http_client = FakeHttpClient::with_404_response();
+        assistant_tools::init(http_client.clone(), cx);
+        SettingsStore::update_global(cx, |store, cx| {
+            store.set_user_settings(include_str!("../runner_settings.json"), cx)
+        })
+        .unwrap();
+
+        let client = Client::new(Arc::new(FakeSystemClock::new()), http_client, cx);
+        let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+        Arc::new(AgentAppState {
+            languages: Arc::new(LanguageRegistry::test(cx.background_executor().clone())),
+            client,
+            user_store,
+            fs: Arc::new(RealFs::new(None, cx.background_executor().clone())),
+            node_runtime: NodeRuntime::unavailable(),
+            prompt_builder: Arc::new(PromptBuilder::new(None).unwrap()),
+        })
+    }
+
+    #[gpui::test]
+    async fn test_replay_completions_by_intent(cx: &mut TestAppContext) {
+        let recording = Recording {
+            provider: "anthropic".into(),
+            model: "claude-3-7-sonnet-latest".into(),
+            max_token_count: 200_000,
+            completions: vec![
+                RecordedCompletion {
+                    intent: Some(CompletionIntent::UserPrompt),
+                    events: vec![
+                        Ok(LanguageModelCompletionEvent::Text("Editing".into())),
+                        Ok(LanguageModelCompletionEvent::Stop(StopReason::ToolUse)),
+                    ],
+                },
+                RecordedCompletion {
+                    intent: Some(CompletionIntent::EditFile),
+                    events: vec![Ok(LanguageModelCompletionEvent::Text(
+                        "<new_text>fn main() {}</new_text>".into(),
+                    ))],
+                },
+                RecordedCompletion {
+                    intent: Some(CompletionIntent::ToolResults),
+                    events: vec![Err("overloaded".into())],
+                },
+            ],
+        };
+        let model = ReplayLanguageModel::new(recording)
+            .into_configured_model()
+            .model;
+        let cx = cx.to_async();
+
+        let replay = async |intent| {
+            let request = LanguageModelRequest {
+                intent: Some(intent),
+                ..Default::default()
+            };
+            let events = model.stream_completion(request, &cx).await?;
+            anyhow::Ok(
+                events
+                    .map(|event| event.map_err(|error| error.to_string()))
+                    .collect::<Vec<_>>()
+                    .await,
+            )
+        };
+
+        // Completions for other intents are skipped over.
+        assert_eq!(
+            replay(CompletionIntent::EditFile).await.unwrap(),
+            vec![Ok(LanguageModelCompletionEvent::Text(
+                "<new_text>fn main() {}</new_text>".into()
+            ))]
+        );
+        assert_eq!(
+            replay(CompletionIntent::UserPrompt).await.unwrap(),
+            vec![
+                Ok(LanguageModelCompletionEvent::Text("Editing".into())),
+                Ok(LanguageModelCompletionEvent::Stop(StopReason::ToolUse)),
+            ]
+        );
+        assert_eq!(
+            replay(CompletionIntent::ToolResults).await.unwrap(),
+            vec![Err("overloaded".into())]
+        );
+        assert!(replay(CompletionIntent::UserPrompt).await.is_err());
+    }
+}
@@ -2,6 +2,8 @@ pub mod extension_builder;
 mod extension_events;
 mod extension_host_proxy;
 mod extension_manifest;
+#[cfg(any(test, feature = "test-support"))]
+mod fake_extension;
 mod types;
 
 use std::path::{Path, PathBuf};
@@ -19,6 +21,8 @@ use task::{SpawnInTerminal, ZedDebugConfig};
 pub use crate::extension_events::*;
 pub use crate::extension_host_proxy::*;
 pub use crate::extension_manifest::*;
+#[cfg(any(test, feature = "test-support"))]
+pub use crate::fake_extension::FakeExtension;
 pub use crate::types::*;
 
 /// Initializes the `extension` crate.
@@ -165,6 +169,111 @@ pub trait Extension: Send + Sync + 'static {
         locator_name: String,
         config: SpawnInTerminal,
     ) -> Result<DebugRequest>;
+
+    async fn llm_provider_models(&self, provider_id: Arc<str>) -> Result<Vec<LlmModel>>;
+
+    /// Starts streaming a completion, returning the ID of the stream.
+    async fn llm_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+        api_key: Option<String>,
+    ) -> Result<u64>;
+
+    /// Returns the next events from a completion stream, or `None` once the stream has ended.
+    async fn llm_next_completion_events(
+        &self,
+        stream_id: u64,
+    ) -> Result<Option<Vec<LlmCompletionEvent>>>;
+
+    async fn llm_drop_completion_stream(&self, stream_id: u64) -> Result<()>;
+
+    async fn format_buffer(
+        &self,
+        formatter_id: Arc<str>,
+        request: FormatRequest,
+    ) -> Result<FormatResult>;
+
+    async fn code_actions(
+        &self,
+        provider_id: Arc<str>,
+        request: CodeActionRequest,
+    ) -> Result<Vec<ExtensionCodeAction>>;
+
+    async fn task_templates(
+        &self,
+        provider_id: Arc<str>,
+        path: Option<String>,
+    ) -> Result<Vec<TaskTemplate>>;
+
+    /// Returns additional task variables for the given location.
+    async fn task_variables(
+        &self,
+        provider_id: Arc<str>,
+        variables: EnvVars,
+        location: TaskLocation,
+    ) -> Result<EnvVars>;
+
+    async fn list_toolchains(
+        &self,
+        provider_id: Arc<str>,
+        worktree_root: String,
+        subroot_relative_path: Option<String>,
+        env: EnvVars,
+    ) -> Result<ToolchainList>;
+
+    async fn predict_edits(
+        &self,
+        provider_id: Arc<str>,
+        request: EditPredictionRequest,
+    ) -> Result<Option<EditPrediction>>;
+
+    async fn accept_edit_prediction(
+        &self,
+        provider_id: Arc<str>,
+        prediction_id: String,
+    ) -> Result<()>;
+
+    async fn reject_edit_prediction(
+        &self,
+        provider_id: Arc<str>,
+        prediction_id: String,
+    ) -> Result<()>;
+
+    async fn parse_git_remote_url(
+        &self,
+        provider_id: Arc<str>,
+        url: String,
+    ) -> Result<Option<GitHostingRemote>>;
+
+    async fn build_git_commit_permalink(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        sha: String,
+    ) -> Result<String>;
+
+    async fn build_git_permalink(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        params: GitHostingPermalinkParams,
+    ) -> Result<String>;
+
+    async fn extract_git_pull_request(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        message: String,
+    ) -> Result<Option<GitHostingPullRequest>>;
+
+    async fn git_commit_author_avatar_url(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        sha: String,
+    ) -> Result<Option<String>>;
 }
 
 pub fn parse_wasm_extension_version(
@@ -1,14 +1,18 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use fs::Fs;
 use gpui::{App, Global, ReadGlobal, SharedString, Task};
 use language::{BinaryStatus, LanguageMatcher, LanguageName, LoadedLanguage};
 use lsp::LanguageServerName;
 use parking_lot::RwLock;
 
-use crate::{Extension, SlashCommand};
+use crate::{
+    EditPredictionProviderManifestEntry, Extension, ExtensionCapability,
+    GitHostingProviderManifestEntry, LanguageModelProviderManifestEntry, SlashCommand,
+    ToolchainProviderManifestEntry,
+};
 
 #[derive(Default)]
 struct GlobalExtensionHostProxy(Arc<ExtensionHostProxy>);
@@ -30,6 +34,14 @@ pub struct ExtensionHostProxy {
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     indexed_docs_provider_proxy: RwLock<Option<Arc<dyn ExtensionIndexedDocsProviderProxy>>>,
     debug_adapter_provider_proxy: RwLock<Option<Arc<dyn ExtensionDebugAdapterProviderProxy>>>,
+    language_model_provider_proxy: RwLock<Option<Arc<dyn ExtensionLanguageModelProviderProxy>>>,
+    formatter_proxy: RwLock<Option<Arc<dyn ExtensionFormatterProxy>>>,
+    code_action_provider_proxy: RwLock<Option<Arc<dyn ExtensionCodeActionProviderProxy>>>,
+    task_provider_proxy: RwLock<Option<Arc<dyn ExtensionTaskProviderProxy>>>,
+    toolchain_provider_proxy: RwLock<Option<Arc<dyn ExtensionToolchainProviderProxy>>>,
+    edit_prediction_provider_proxy: RwLock<Option<Arc<dyn ExtensionEditPredictionProviderProxy>>>,
+    git_hosting_provider_proxy: RwLock<Option<Arc<dyn ExtensionGitHostingProviderProxy>>>,
+    capability_proxy: RwLock<Option<Arc<dyn ExtensionCapabilityProxy>>>,
 }
 
 impl ExtensionHostProxy {
@@ -56,6 +68,14 @@ impl ExtensionHostProxy {
             context_server_proxy: RwLock::default(),
             indexed_docs_provider_proxy: RwLock::default(),
             debug_adapter_provider_proxy: RwLock::default(),
+            language_model_provider_proxy: RwLock::default(),
+            formatter_proxy: RwLock::default(),
+            code_action_provider_proxy: RwLock::default(),
+            task_provider_proxy: RwLock::default(),
+            toolchain_provider_proxy: RwLock::default(),
+            edit_prediction_provider_proxy: RwLock::default(),
+            git_hosting_provider_proxy: RwLock::default(),
+            capability_proxy: RwLock::default(),
         }
     }
 
@@ -100,6 +120,60 @@ impl ExtensionHostProxy {
             .write()
             .replace(Arc::new(proxy));
     }
+
+    pub fn register_language_model_provider_proxy(
+        &self,
+        proxy: impl ExtensionLanguageModelProviderProxy,
+    ) {
+        self.language_model_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_formatter_proxy(&self, proxy: impl ExtensionFormatterProxy) {
+        self.formatter_proxy.write().replace(Arc::new(proxy));
+    }
+
+    pub fn register_code_action_provider_proxy(
+        &self,
+        proxy: impl ExtensionCodeActionProviderProxy,
+    ) {
+        self.code_action_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_task_provider_proxy(&self, proxy: impl ExtensionTaskProviderProxy) {
+        self.task_provider_proxy.write().replace(Arc::new(proxy));
+    }
+
+    pub fn register_toolchain_provider_proxy(&self, proxy: impl ExtensionToolchainProviderProxy) {
+        self.toolchain_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_edit_prediction_provider_proxy(
+        &self,
+        proxy: impl ExtensionEditPredictionProviderProxy,
+    ) {
+        self.edit_prediction_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_git_hosting_provider_proxy(
+        &self,
+        proxy: impl ExtensionGitHostingProviderProxy,
+    ) {
+        self.git_hosting_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_capability_proxy(&self, proxy: impl ExtensionCapabilityProxy) {
+        self.capability_proxy.write().replace(Arc::new(proxy));
+    }
 }
 
 pub trait ExtensionThemeProxy: Send + Sync + 'static {
@@ -458,3 +532,297 @@ impl ExtensionDebugAdapterProviderProxy for ExtensionHostProxy {
         proxy.unregister_debug_locator(locator_name)
     }
 }
+
+pub trait ExtensionLanguageModelProviderProxy: Send + Sync + 'static {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    );
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    );
+}
+
+impl ExtensionLanguageModelProviderProxy for ExtensionHostProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.language_model_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_language_model_provider(extension, provider_id, provider, cx)
+    }
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.language_model_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_language_model_provider(extension_id, provider_id, cx)
+    }
+}
+
+pub trait ExtensionFormatterProxy: Send + Sync + 'static {
+    fn register_formatter(&self, extension: Arc<dyn Extension>, formatter_id: Arc<str>);
+
+    fn unregister_formatter(&self, formatter_id: Arc<str>);
+}
+
+impl ExtensionFormatterProxy for ExtensionHostProxy {
+    fn register_formatter(&self, extension: Arc<dyn Extension>, formatter_id: Arc<str>) {
+        let Some(proxy) = self.formatter_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_formatter(extension, formatter_id)
+    }
+
+    fn unregister_formatter(&self, formatter_id: Arc<str>) {
+        let Some(proxy) = self.formatter_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_formatter(formatter_id)
+    }
+}
+
+pub trait ExtensionCodeActionProviderProxy: Send + Sync + 'static {
+    fn register_code_action_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        languages: Vec<LanguageName>,
+    );
+
+    fn unregister_code_action_provider(&self, provider_id: Arc<str>);
+}
+
+impl ExtensionCodeActionProviderProxy for ExtensionHostProxy {
+    fn register_code_action_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        languages: Vec<LanguageName>,
+    ) {
+        let Some(proxy) = self.code_action_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_code_action_provider(extension, provider_id, languages)
+    }
+
+    fn unregister_code_action_provider(&self, provider_id: Arc<str>) {
+        let Some(proxy) = self.code_action_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_code_action_provider(provider_id)
+    }
+}
+
+pub trait ExtensionTaskProviderProxy: Send + Sync + 'static {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        language: LanguageName,
+    );
+
+    fn remove_task_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        language: &LanguageName,
+    );
+}
+
+impl ExtensionTaskProviderProxy for ExtensionHostProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        language: LanguageName,
+    ) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_task_provider(extension, provider_id, language)
+    }
+
+    fn remove_task_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        language: &LanguageName,
+    ) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.remove_task_provider(extension_id, provider_id, language)
+    }
+}
+
+pub trait ExtensionToolchainProviderProxy: Send + Sync + 'static {
+    fn register_toolchain_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: ToolchainProviderManifestEntry,
+        language: LanguageName,
+    );
+
+    fn remove_toolchain_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        language: &LanguageName,
+    );
+}
+
+impl ExtensionToolchainProviderProxy for ExtensionHostProxy {
+    fn register_toolchain_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: ToolchainProviderManifestEntry,
+        language: LanguageName,
+    ) {
+        let Some(proxy) = self.toolchain_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_toolchain_provider(extension, provider_id, provider, language)
+    }
+
+    fn remove_toolchain_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        language: &LanguageName,
+    ) {
+        let Some(proxy) = self.toolchain_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.remove_toolchain_provider(extension_id, provider_id, language)
+    }
+}
+
+pub trait ExtensionEditPredictionProviderProxy: Send + Sync + 'static {
+    fn register_edit_prediction_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: EditPredictionProviderManifestEntry,
+    );
+
+    fn unregister_edit_prediction_provider(&self, provider_id: Arc<str>);
+}
+
+impl ExtensionEditPredictionProviderProxy for ExtensionHostProxy {
+    fn register_edit_prediction_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: EditPredictionProviderManifestEntry,
+    ) {
+        let Some(proxy) = self.edit_prediction_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_edit_prediction_provider(extension, provider_id, provider)
+    }
+
+    fn unregister_edit_prediction_provider(&self, provider_id: Arc<str>) {
+        let Some(proxy) = self.edit_prediction_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_edit_prediction_provider(provider_id)
+    }
+}
+
+pub trait ExtensionGitHostingProviderProxy: Send + Sync + 'static {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: GitHostingProviderManifestEntry,
+    );
+
+    fn unregister_git_hosting_provider(&self, provider_id: Arc<str>);
+}
+
+impl ExtensionGitHostingProviderProxy for ExtensionHostProxy {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: GitHostingProviderManifestEntry,
+    ) {
+        let Some(proxy) = self.git_hosting_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_git_hosting_provider(extension, provider_id, provider)
+    }
+
+    fn unregister_git_hosting_provider(&self, provider_id: Arc<str>) {
+        let Some(proxy) = self.git_hosting_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_git_hosting_provider(provider_id)
+    }
+}
+
+pub trait ExtensionCapabilityProxy: Send + Sync + 'static {
+    /// Asks the user whether to grant the given capability to the extension,
+    /// persisting their decision.
+    fn request_capability_consent(
+        &self,
+        extension_id: Arc<str>,
+        extension_name: SharedString,
+        capability: ExtensionCapability,
+        cx: &mut App,
+    ) -> Task<Result<bool>>;
+}
+
+impl ExtensionCapabilityProxy for ExtensionHostProxy {
+    fn request_capability_consent(
+        &self,
+        extension_id: Arc<str>,
+        extension_name: SharedString,
+        capability: ExtensionCapability,
+        cx: &mut App,
+    ) -> Task<Result<bool>> {
+        // Without a way to prompt the user (e.g., when running headless), capabilities
+        // are denied unless they were granted in the settings.
+        let Some(proxy) = self.capability_proxy.read().clone() else {
+            return Task::ready(Err(anyhow!(
+                "cannot ask for consent to {capability}, so it must be granted in the `extension_capabilities` setting"
+            )));
+        };
+
+        proxy.request_capability_consent(extension_id, extension_name, capability, cx)
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{Context as _, Result, bail};
 use collections::{BTreeMap, HashMap};
 use fs::Fs;
+use http_client::Url;
 use language::LanguageName;
 use lsp::LanguageServerName;
 use semantic_version::SemanticVersion;
@@ -8,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     ffi::OsStr,
     fmt,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 
@@ -91,44 +92,172 @@ pub struct ExtensionManifest {
     pub debug_adapters: BTreeMap<Arc<str>, DebugAdapterManifestEntry>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub debug_locators: BTreeMap<Arc<str>, DebugLocatorManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub language_model_providers: BTreeMap<Arc<str>, LanguageModelProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub formatters: BTreeMap<Arc<str>, FormatterManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub code_action_providers: BTreeMap<Arc<str>, CodeActionProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub task_providers: BTreeMap<Arc<str>, TaskProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub toolchain_providers: BTreeMap<Arc<str>, ToolchainProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub edit_prediction_providers: BTreeMap<Arc<str>, EditPredictionProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub git_hosting_providers: BTreeMap<Arc<str>, GitHostingProviderManifestEntry>,
 }
 
 impl ExtensionManifest {
+    /// Returns the capability that allows the extension to run the given command.
     pub fn allow_exec(
         &self,
         desired_command: &str,
         desired_args: &[impl AsRef<str> + std::fmt::Debug],
-    ) -> Result<()> {
-        let is_allowed = self.capabilities.iter().any(|capability| match capability {
-            ExtensionCapability::ProcessExec { command, args } if command == desired_command => {
-                for (ix, arg) in args.iter().enumerate() {
-                    if arg == "**" {
-                        return true;
-                    }
-
-                    if ix >= desired_args.len() {
-                        return false;
-                    }
-
-                    if arg != "*" && arg != desired_args[ix].as_ref() {
-                        return false;
-                    }
-                }
-                if args.len() < desired_args.len() {
-                    return false;
+    ) -> Result<ExtensionCapability> {
+        let capability = self
+            .capabilities
+            .iter()
+            .find(|capability| match capability {
+                ExtensionCapability::ProcessExec { command, args }
+                    if command == desired_command =>
+                {
+                    pattern_matches(args, desired_args)
                 }
-                true
-            }
-            _ => false,
-        });
+                _ => false,
+            });
 
-        if !is_allowed {
+        let Some(capability) = capability else {
             bail!(
                 "capability for process:exec {desired_command} {desired_args:?} was not listed in the extension manifest",
             );
+        };
+
+        Ok(capability.clone())
+    }
+
+    /// Returns the capability that allows the extension to make requests to the given URL.
+    pub fn allow_network_fetch(&self, url: &str) -> Result<ExtensionCapability> {
+        let desired_host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_ascii_lowercase()))
+            .with_context(|| format!("invalid URL {url:?}"))?;
+
+        let capability = self
+            .capabilities
+            .iter()
+            .find(|capability| match capability {
+                ExtensionCapability::NetworkFetch { host } => host_matches(host, &desired_host),
+                _ => false,
+            });
+
+        if let Some(capability) = capability {
+            return Ok(capability.clone());
+        }
+
+        if self.has_unscoped_access(|capability| {
+            matches!(capability, ExtensionCapability::NetworkFetch { .. })
+        }) {
+            return Ok(ExtensionCapability::NetworkFetch {
+                host: "*".to_string(),
+            });
+        }
+
+        bail!(
+            "capability for network:fetch {desired_host} was not listed in the extension manifest"
+        );
+    }
+
+    /// Returns the capability that allows the extension to read the file at the given path,
+    /// relative to the root of a worktree.
+    pub fn allow_file_read(&self, desired_path: &Path) -> Result<ExtensionCapability> {
+        let components = desired_path
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .map(|component| match component {
+                Component::Normal(component) => component.to_str(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .with_context(|| {
+                format!("cannot read path {desired_path:?} outside of the worktree")
+            })?;
+
+        let capability = self
+            .capabilities
+            .iter()
+            .find(|capability| match capability {
+                ExtensionCapability::FileRead { path } => {
+                    pattern_matches(&path.split('/').collect::<Vec<_>>(), &components)
+                }
+                _ => false,
+            });
+
+        if let Some(capability) = capability {
+            return Ok(capability.clone());
+        }
+
+        if self.has_unscoped_access(|capability| {
+            matches!(capability, ExtensionCapability::FileRead { .. })
+        }) {
+            return Ok(ExtensionCapability::FileRead {
+                path: "**".to_string(),
+            });
+        }
+
+        bail!(
+            "capability for fs:read {} was not listed in the extension manifest",
+            desired_path.display()
+        );
+    }
+
+    /// Returns whether the extension predates scoped network and file capabilities,
+    /// and doesn't declare any capability of the given kind.
+    ///
+    /// Extensions built against an extension API prior to v0.7.0 couldn't declare these
+    /// capabilities, so they are granted unscoped access, subject to the user's consent.
+    fn has_unscoped_access(&self, is_same_kind: impl Fn(&ExtensionCapability) -> bool) -> bool {
+        self.lib
+            .version
+            .is_some_and(|version| version < SemanticVersion::new(0, 7, 0))
+            && !self.capabilities.iter().any(is_same_kind)
+    }
+}
+
+/// Returns whether the given values match the pattern.
+///
+/// A `*` in the pattern matches a single value, and a trailing `**` matches any remaining values.
+fn pattern_matches(pattern: &[impl AsRef<str>], values: &[impl AsRef<str>]) -> bool {
+    for (ix, part) in pattern.iter().enumerate() {
+        let part = part.as_ref();
+        if part == "**" {
+            return true;
+        }
+
+        if ix >= values.len() {
+            return false;
+        }
+
+        if part != "*" && part != values[ix].as_ref() {
+            return false;
         }
+    }
+    pattern.len() >= values.len()
+}
+
+/// Returns whether the given host matches the pattern.
+///
+/// The pattern `*` matches any host, and a pattern starting with `*.` matches any subdomain.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
 
-        Ok(())
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(&domain.to_ascii_lowercase())
+            .is_some_and(|subdomain| subdomain.ends_with('.')),
+        None => pattern.eq_ignore_ascii_case(host),
     }
 }
 
@@ -144,6 +273,37 @@ pub enum ExtensionCapability {
         /// If the last element is `**`, then any trailing arguments are allowed.
         args: Vec<String>,
     },
+    /// Downloading files and making HTTP requests.
+    #[serde(rename = "network:fetch")]
+    NetworkFetch {
+        /// The host to make requests to. Use `*` for any host, or `*.example.com` for
+        /// any subdomain of `example.com`.
+        host: String,
+    },
+    /// Reading files in the worktree.
+    #[serde(rename = "fs:read")]
+    FileRead {
+        /// The path of the file, relative to the root of the worktree. Use `*` for a
+        /// single wildcard path component. If the last component is `**`, then any
+        /// file in the directory is allowed.
+        path: String,
+    },
+}
+
+impl fmt::Display for ExtensionCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProcessExec { command, args } => {
+                write!(f, "process:exec {command}")?;
+                for arg in args {
+                    write!(f, " {arg}")?;
+                }
+                Ok(())
+            }
+            Self::NetworkFetch { host } => write!(f, "network:fetch {host}"),
+            Self::FileRead { path } => write!(f, "fs:read {path}"),
+        }
+    }
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -218,6 +378,68 @@ pub struct DebugAdapterManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct DebugLocatorManifestEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LanguageModelProviderManifestEntry {
+    /// The name of the provider, as displayed in the UI.
+    pub name: String,
+    /// The environment variable from which to read the provider's API key.
+    #[serde(default)]
+    pub api_key_env_var: Option<String>,
+    /// Whether the provider can be used without an API key.
+    #[serde(default)]
+    pub api_key_optional: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct FormatterManifestEntry {}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct CodeActionProviderManifestEntry {
+    /// The list of languages this code action provider should work with.
+    ///
+    /// If empty, the provider is queried for buffers of every language.
+    #[serde(default)]
+    pub languages: Vec<LanguageName>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TaskProviderManifestEntry {
+    /// The list of languages this task provider should work with.
+    pub languages: Vec<LanguageName>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ToolchainProviderManifestEntry {
+    /// The list of languages this toolchain provider should work with.
+    pub languages: Vec<LanguageName>,
+    /// The term used in the UI to refer to a toolchain, such as "Virtual Environment".
+    #[serde(default = "default_toolchain_term")]
+    pub term: String,
+    /// The name of the manifest file that marks the root of a project, such as `pyproject.toml`.
+    pub manifest_name: String,
+}
+
+fn default_toolchain_term() -> String {
+    "Toolchain".to_string()
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct EditPredictionProviderManifestEntry {
+    /// The name of the provider, as displayed in the UI.
+    pub name: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct GitHostingProviderManifestEntry {
+    /// The name of the provider, as displayed in the UI.
+    pub name: String,
+    /// The base URL of the provider, such as `https://review.example.com`.
+    pub base_url: String,
+    /// Whether the provider can look up the avatars of commit authors.
+    #[serde(default)]
+    pub supports_avatars: bool,
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -288,6 +510,13 @@ fn manifest_from_old_manifest(
         capabilities: Vec::new(),
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        language_model_providers: Default::default(),
+        formatters: Default::default(),
+        code_action_providers: Default::default(),
+        task_providers: Default::default(),
+        toolchain_providers: Default::default(),
+        edit_prediction_providers: Default::default(),
+        git_hosting_providers: Default::default(),
     }
 }
 
@@ -317,6 +546,13 @@ mod tests {
             capabilities: vec![],
             debug_adapters: Default::default(),
             debug_locators: Default::default(),
+            language_model_providers: Default::default(),
+            formatters: Default::default(),
+            code_action_providers: Default::default(),
+            task_providers: Default::default(),
+            toolchain_providers: Default::default(),
+            edit_prediction_providers: Default::default(),
+            git_hosting_providers: Default::default(),
         }
     }
 
@@ -395,4 +631,98 @@ mod tests {
         );
         assert!(manifest.allow_exec("docker", &["ps"]).is_err()); // wrong first arg
     }
+
+    #[test]
+    fn test_allow_network_fetch() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![
+                ExtensionCapability::NetworkFetch {
+                    host: "github.com".to_string(),
+                },
+                ExtensionCapability::NetworkFetch {
+                    host: "*.githubusercontent.com".to_string(),
+                },
+            ],
+            ..extension_manifest()
+        };
+
+        assert!(
+            manifest
+                .allow_network_fetch("https://github.com/owner/repo/releases")
+                .is_ok()
+        );
+        assert!(
+            manifest
+                .allow_network_fetch("https://objects.githubusercontent.com/asset")
+                .is_ok()
+        );
+        assert!(
+            manifest
+                .allow_network_fetch("https://githubusercontent.com/asset")
+                .is_err()
+        ); // wildcard only matches subdomains
+        assert!(
+            manifest
+                .allow_network_fetch("https://github.com.example.com/")
+                .is_err()
+        ); // wrong host
+        assert!(manifest.allow_network_fetch("not a url").is_err());
+    }
+
+    #[test]
+    fn test_allow_file_read() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![
+                ExtensionCapability::FileRead {
+                    path: "package.json".to_string(),
+                },
+                ExtensionCapability::FileRead {
+                    path: ".config/**".to_string(),
+                },
+            ],
+            ..extension_manifest()
+        };
+
+        assert!(manifest.allow_file_read(Path::new("package.json")).is_ok());
+        assert!(
+            manifest
+                .allow_file_read(Path::new("./package.json"))
+                .is_ok()
+        );
+        assert!(
+            manifest
+                .allow_file_read(Path::new(".config/tool/settings.toml"))
+                .is_ok()
+        );
+        assert!(manifest.allow_file_read(Path::new("src/main.rs")).is_err()); // not listed
+        assert!(
+            manifest
+                .allow_file_read(Path::new(".config/../secrets.txt"))
+                .is_err()
+        ); // outside of the allowed directory
+    }
+
+    #[test]
+    fn test_unscoped_access_for_older_extensions() {
+        let mut manifest = extension_manifest();
+        manifest.lib.version = Some(SemanticVersion::new(0, 6, 0));
+
+        assert_eq!(
+            manifest
+                .allow_network_fetch("https://example.com/file.zip")
+                .unwrap(),
+            ExtensionCapability::NetworkFetch {
+                host: "*".to_string()
+            }
+        );
+        assert!(manifest.allow_file_read(Path::new("src/main.rs")).is_ok());
+
+        manifest.lib.version = Some(SemanticVersion::new(0, 7, 0));
+        assert!(
+            manifest
+                .allow_network_fetch("https://example.com/file.zip")
+                .is_err()
+        );
+        assert!(manifest.allow_file_read(Path::new("src/main.rs")).is_err());
+    }
 }
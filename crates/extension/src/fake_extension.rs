@@ -0,0 +1,462 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ::lsp::LanguageServerName;
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use language::LanguageName;
+use parking_lot::Mutex;
+use task::{SpawnInTerminal, ZedDebugConfig};
+
+use crate::*;
+
+type Handler<Args, Output> = Arc<dyn Fn(Args) -> Result<Output> + Send + Sync>;
+
+/// An [`Extension`] whose features are implemented by handlers that tests provide.
+///
+/// Calling a method without a handler returns an error, as a Wasm extension that doesn't
+/// implement the feature would.
+pub struct FakeExtension {
+    manifest: Arc<ExtensionManifest>,
+    handlers: Mutex<Handlers>,
+}
+
+#[derive(Default)]
+struct Handlers {
+    llm_provider_models: Option<Handler<Arc<str>, Vec<LlmModel>>>,
+    llm_stream_completion: Option<Handler<(Arc<str>, Arc<str>, LlmCompletionRequest), u64>>,
+    llm_next_completion_events: Option<Handler<u64, Option<Vec<LlmCompletionEvent>>>>,
+    llm_drop_completion_stream: Option<Handler<u64, ()>>,
+    format_buffer: Option<Handler<(Arc<str>, FormatRequest), FormatResult>>,
+    code_actions: Option<Handler<(Arc<str>, CodeActionRequest), Vec<ExtensionCodeAction>>>,
+    task_templates: Option<Handler<(Arc<str>, Option<String>), Vec<TaskTemplate>>>,
+    task_variables: Option<Handler<(Arc<str>, EnvVars, TaskLocation), EnvVars>>,
+    list_toolchains: Option<Handler<(Arc<str>, String, Option<String>), ToolchainList>>,
+    predict_edits: Option<Handler<(Arc<str>, EditPredictionRequest), Option<EditPrediction>>>,
+    accept_edit_prediction: Option<Handler<(Arc<str>, String), ()>>,
+    reject_edit_prediction: Option<Handler<(Arc<str>, String), ()>>,
+    parse_git_remote_url: Option<Handler<(Arc<str>, String), Option<GitHostingRemote>>>,
+    build_git_commit_permalink: Option<Handler<(Arc<str>, GitHostingRemote, String), String>>,
+    build_git_permalink:
+        Option<Handler<(Arc<str>, GitHostingRemote, GitHostingPermalinkParams), String>>,
+    extract_git_pull_request:
+        Option<Handler<(Arc<str>, GitHostingRemote, String), Option<GitHostingPullRequest>>>,
+}
+
+macro_rules! handler_setters {
+    ($($setter:ident => $field:ident($($arg:ty),*) -> $output:ty;)*) => {
+        impl FakeExtension {
+            $(
+                pub fn $setter(
+                    &self,
+                    handler: impl Fn($($arg),*) -> Result<$output> + Send + Sync + 'static,
+                ) {
+                    #[allow(unused_parens)]
+                    let handler: Handler<($($arg),*), $output> = Arc::new(
+                        move |args: ($($arg),*)| handler_args!(handler, args, $($arg),*),
+                    );
+                    self.handlers.lock().$field = Some(handler);
+                }
+            )*
+        }
+    };
+}
+
+macro_rules! handler_args {
+    ($handler:ident, $args:ident, $a:ty) => {
+        $handler($args)
+    };
+    ($handler:ident, $args:ident, $a:ty, $b:ty) => {
+        $handler($args.0, $args.1)
+    };
+    ($handler:ident, $args:ident, $a:ty, $b:ty, $c:ty) => {
+        $handler($args.0, $args.1, $args.2)
+    };
+}
+
+handler_setters! {
+    on_llm_provider_models => llm_provider_models(Arc<str>) -> Vec<LlmModel>;
+    on_llm_stream_completion =>
+        llm_stream_completion(Arc<str>, Arc<str>, LlmCompletionRequest) -> u64;
+    on_llm_next_completion_events =>
+        llm_next_completion_events(u64) -> Option<Vec<LlmCompletionEvent>>;
+    on_llm_drop_completion_stream => llm_drop_completion_stream(u64) -> ();
+    on_format_buffer => format_buffer(Arc<str>, FormatRequest) -> FormatResult;
+    on_code_actions => code_actions(Arc<str>, CodeActionRequest) -> Vec<ExtensionCodeAction>;
+    on_task_templates => task_templates(Arc<str>, Option<String>) -> Vec<TaskTemplate>;
+    on_task_variables => task_variables(Arc<str>, EnvVars, TaskLocation) -> EnvVars;
+    on_list_toolchains => list_toolchains(Arc<str>, String, Option<String>) -> ToolchainList;
+    on_predict_edits =>
+        predict_edits(Arc<str>, EditPredictionRequest) -> Option<EditPrediction>;
+    on_accept_edit_prediction => accept_edit_prediction(Arc<str>, String) -> ();
+    on_reject_edit_prediction => reject_edit_prediction(Arc<str>, String) -> ();
+    on_parse_git_remote_url =>
+        parse_git_remote_url(Arc<str>, String) -> Option<GitHostingRemote>;
+    on_build_git_commit_permalink =>
+        build_git_commit_permalink(Arc<str>, GitHostingRemote, String) -> String;
+    on_build_git_permalink =>
+        build_git_permalink(Arc<str>, GitHostingRemote, GitHostingPermalinkParams) -> String;
+    on_extract_git_pull_request =>
+        extract_git_pull_request(Arc<str>, GitHostingRemote, String)
+            -> Option<GitHostingPullRequest>;
+}
+
+impl FakeExtension {
+    /// Creates an extension with the given ID, which provides no features until handlers are set.
+    pub fn new(id: &str) -> Self {
+        let manifest = toml::from_str(&format!(
+            "id = {id:?}\nname = {id:?}\nversion = \"0.1.0\"\nschema_version = 1"
+        ))
+        .expect("invalid extension ID");
+        Self::with_manifest(manifest)
+    }
+
+    pub fn with_manifest(manifest: ExtensionManifest) -> Self {
+        Self {
+            manifest: Arc::new(manifest),
+            handlers: Mutex::default(),
+        }
+    }
+
+    fn handler<Args, Output>(
+        &self,
+        method: &str,
+        handler: impl FnOnce(&Handlers) -> &Option<Handler<Args, Output>>,
+    ) -> Result<Handler<Args, Output>> {
+        match handler(&self.handlers.lock()) {
+            Some(handler) => Ok(handler.clone()),
+            None => bail!("{method} isn't implemented by {}", self.manifest.id),
+        }
+    }
+}
+
+#[async_trait]
+impl Extension for FakeExtension {
+    fn manifest(&self) -> Arc<ExtensionManifest> {
+        self.manifest.clone()
+    }
+
+    fn work_dir(&self) -> Arc<Path> {
+        Path::new("/extensions/work")
+            .join(self.manifest.id.as_ref())
+            .into()
+    }
+
+    async fn language_server_command(
+        &self,
+        _: LanguageServerName,
+        _: LanguageName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Command> {
+        bail!(
+            "language_server_command isn't implemented by {}",
+            self.manifest.id
+        )
+    }
+
+    async fn language_server_initialization_options(
+        &self,
+        _: LanguageServerName,
+        _: LanguageName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn language_server_workspace_configuration(
+        &self,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn language_server_additional_initialization_options(
+        &self,
+        _: LanguageServerName,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn language_server_additional_workspace_configuration(
+        &self,
+        _: LanguageServerName,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn labels_for_completions(
+        &self,
+        _: LanguageServerName,
+        completions: Vec<Completion>,
+    ) -> Result<Vec<Option<CodeLabel>>> {
+        Ok(vec![None; completions.len()])
+    }
+
+    async fn labels_for_symbols(
+        &self,
+        _: LanguageServerName,
+        symbols: Vec<Symbol>,
+    ) -> Result<Vec<Option<CodeLabel>>> {
+        Ok(vec![None; symbols.len()])
+    }
+
+    async fn complete_slash_command_argument(
+        &self,
+        _: SlashCommand,
+        _: Vec<String>,
+    ) -> Result<Vec<SlashCommandArgumentCompletion>> {
+        Ok(Vec::new())
+    }
+
+    async fn run_slash_command(
+        &self,
+        _: SlashCommand,
+        _: Vec<String>,
+        _: Option<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<SlashCommandOutput> {
+        bail!(
+            "run_slash_command isn't implemented by {}",
+            self.manifest.id
+        )
+    }
+
+    async fn context_server_command(
+        &self,
+        _: Arc<str>,
+        _: Arc<dyn ProjectDelegate>,
+    ) -> Result<Command> {
+        bail!(
+            "context_server_command isn't implemented by {}",
+            self.manifest.id
+        )
+    }
+
+    async fn context_server_configuration(
+        &self,
+        _: Arc<str>,
+        _: Arc<dyn ProjectDelegate>,
+    ) -> Result<Option<ContextServerConfiguration>> {
+        Ok(None)
+    }
+
+    async fn suggest_docs_packages(&self, _: Arc<str>) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn index_docs(
+        &self,
+        _: Arc<str>,
+        _: Arc<str>,
+        _: Arc<dyn KeyValueStoreDelegate>,
+    ) -> Result<()> {
+        bail!("index_docs isn't implemented by {}", self.manifest.id)
+    }
+
+    async fn get_dap_binary(
+        &self,
+        _: Arc<str>,
+        _: DebugTaskDefinition,
+        _: Option<PathBuf>,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<DebugAdapterBinary> {
+        bail!("get_dap_binary isn't implemented by {}", self.manifest.id)
+    }
+
+    async fn dap_request_kind(
+        &self,
+        _: Arc<str>,
+        _: serde_json::Value,
+    ) -> Result<StartDebuggingRequestArgumentsRequest> {
+        bail!("dap_request_kind isn't implemented by {}", self.manifest.id)
+    }
+
+    async fn dap_config_to_scenario(&self, _: ZedDebugConfig) -> Result<DebugScenario> {
+        bail!(
+            "dap_config_to_scenario isn't implemented by {}",
+            self.manifest.id
+        )
+    }
+
+    async fn dap_locator_create_scenario(
+        &self,
+        _: String,
+        _: BuildTaskTemplate,
+        _: String,
+        _: String,
+    ) -> Result<Option<DebugScenario>> {
+        Ok(None)
+    }
+
+    async fn run_dap_locator(&self, _: String, _: SpawnInTerminal) -> Result<DebugRequest> {
+        bail!("run_dap_locator isn't implemented by {}", self.manifest.id)
+    }
+
+    async fn llm_provider_models(&self, provider_id: Arc<str>) -> Result<Vec<LlmModel>> {
+        self.handler("llm_provider_models", |h| &h.llm_provider_models)?(provider_id)
+    }
+
+    async fn llm_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+        _api_key: Option<String>,
+    ) -> Result<u64> {
+        self.handler("llm_stream_completion", |h| &h.llm_stream_completion)?((
+            provider_id,
+            model_id,
+            request,
+        ))
+    }
+
+    async fn llm_next_completion_events(
+        &self,
+        stream_id: u64,
+    ) -> Result<Option<Vec<LlmCompletionEvent>>> {
+        self.handler("llm_next_completion_events", |h| {
+            &h.llm_next_completion_events
+        })?(stream_id)
+    }
+
+    async fn llm_drop_completion_stream(&self, stream_id: u64) -> Result<()> {
+        self.handler("llm_drop_completion_stream", |h| {
+            &h.llm_drop_completion_stream
+        })?(stream_id)
+    }
+
+    async fn format_buffer(
+        &self,
+        formatter_id: Arc<str>,
+        request: FormatRequest,
+    ) -> Result<FormatResult> {
+        self.handler("format_buffer", |h| &h.format_buffer)?((formatter_id, request))
+    }
+
+    async fn code_actions(
+        &self,
+        provider_id: Arc<str>,
+        request: CodeActionRequest,
+    ) -> Result<Vec<ExtensionCodeAction>> {
+        self.handler("code_actions", |h| &h.code_actions)?((provider_id, request))
+    }
+
+    async fn task_templates(
+        &self,
+        provider_id: Arc<str>,
+        path: Option<String>,
+    ) -> Result<Vec<TaskTemplate>> {
+        self.handler("task_templates", |h| &h.task_templates)?((provider_id, path))
+    }
+
+    async fn task_variables(
+        &self,
+        provider_id: Arc<str>,
+        variables: EnvVars,
+        location: TaskLocation,
+    ) -> Result<EnvVars> {
+        self.handler("task_variables", |h| &h.task_variables)?((provider_id, variables, location))
+    }
+
+    async fn list_toolchains(
+        &self,
+        provider_id: Arc<str>,
+        worktree_root: String,
+        subroot_relative_path: Option<String>,
+        _env: EnvVars,
+    ) -> Result<ToolchainList> {
+        self.handler("list_toolchains", |h| &h.list_toolchains)?((
+            provider_id,
+            worktree_root,
+            subroot_relative_path,
+        ))
+    }
+
+    async fn predict_edits(
+        &self,
+        provider_id: Arc<str>,
+        request: EditPredictionRequest,
+    ) -> Result<Option<EditPrediction>> {
+        self.handler("predict_edits", |h| &h.predict_edits)?((provider_id, request))
+    }
+
+    async fn accept_edit_prediction(
+        &self,
+        provider_id: Arc<str>,
+        prediction_id: String,
+    ) -> Result<()> {
+        self.handler("accept_edit_prediction", |h| &h.accept_edit_prediction)?((
+            provider_id,
+            prediction_id,
+        ))
+    }
+
+    async fn reject_edit_prediction(
+        &self,
+        provider_id: Arc<str>,
+        prediction_id: String,
+    ) -> Result<()> {
+        self.handler("reject_edit_prediction", |h| &h.reject_edit_prediction)?((
+            provider_id,
+            prediction_id,
+        ))
+    }
+
+    async fn parse_git_remote_url(
+        &self,
+        provider_id: Arc<str>,
+        url: String,
+    ) -> Result<Option<GitHostingRemote>> {
+        self.handler("parse_git_remote_url", |h| &h.parse_git_remote_url)?((provider_id, url))
+    }
+
+    async fn build_git_commit_permalink(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        sha: String,
+    ) -> Result<String> {
+        self.handler("build_git_commit_permalink", |h| {
+            &h.build_git_commit_permalink
+        })?((provider_id, remote, sha))
+    }
+
+    async fn build_git_permalink(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        params: GitHostingPermalinkParams,
+    ) -> Result<String> {
+        self.handler("build_git_permalink", |h| &h.build_git_permalink)?((
+            provider_id,
+            remote,
+            params,
+        ))
+    }
+
+    async fn extract_git_pull_request(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        message: String,
+    ) -> Result<Option<GitHostingPullRequest>> {
+        self.handler("extract_git_pull_request", |h| &h.extract_git_pull_request)?((
+            provider_id,
+            remote,
+            message,
+        ))
+    }
+
+    async fn git_commit_author_avatar_url(
+        &self,
+        _: Arc<str>,
+        _: GitHostingRemote,
+        _: String,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
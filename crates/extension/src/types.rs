@@ -1,16 +1,30 @@
+mod code_action;
 mod context_server;
 mod dap;
+mod edit_prediction;
+mod formatter;
+mod git_hosting;
+mod llm_provider;
 mod lsp;
 mod slash_command;
+mod task_provider;
+mod toolchain;
 
 use std::ops::Range;
 
 use util::redact::should_redact;
 
+pub use code_action::*;
 pub use context_server::*;
 pub use dap::*;
+pub use edit_prediction::*;
+pub use formatter::*;
+pub use git_hosting::*;
+pub use llm_provider::*;
 pub use lsp::*;
 pub use slash_command::*;
+pub use task_provider::*;
+pub use toolchain::*;
 
 /// A list of environment variables.
 pub type EnvVars = Vec<(String, String)>;
@@ -0,0 +1,27 @@
+use std::ops::Range;
+
+use crate::FormatEdit;
+
+/// A request for the code actions available in a range of a buffer.
+#[derive(Debug, Clone)]
+pub struct CodeActionRequest {
+    /// The text of the buffer.
+    pub text: String,
+    /// The byte range within the text for which code actions are requested.
+    pub range: Range<usize>,
+    /// The name of the buffer's language, if it has one.
+    pub language_name: Option<String>,
+    /// The absolute path of the buffer's file, if it has one.
+    pub path: Option<String>,
+}
+
+/// A code action provided by an extension.
+#[derive(Debug, Clone)]
+pub struct ExtensionCodeAction {
+    /// The title of the code action, as displayed in the UI.
+    pub title: String,
+    /// The kind of the code action, such as `quickfix` or `refactor.extract`.
+    pub kind: Option<String>,
+    /// The edits the code action makes, as non-overlapping ranges of the requested text.
+    pub edits: Vec<FormatEdit>,
+}
@@ -0,0 +1,32 @@
+use std::ops::Range;
+
+/// A request for an edit prediction.
+#[derive(Debug, Clone)]
+pub struct EditPredictionRequest {
+    /// The absolute path of the buffer's file, if it has one.
+    pub path: Option<String>,
+    /// The name of the buffer's language, if it has one.
+    pub language_name: Option<String>,
+    /// An excerpt of the buffer's text around the cursor.
+    pub excerpt: String,
+    /// The byte offset of the cursor within the excerpt.
+    pub cursor_offset: usize,
+}
+
+/// An edit to the text of a buffer.
+#[derive(Debug, Clone)]
+pub struct EditPredictionEdit {
+    /// The byte range within the excerpt to replace.
+    pub range: Range<usize>,
+    /// The text to replace the range with.
+    pub new_text: String,
+}
+
+/// A predicted set of edits.
+#[derive(Debug, Clone)]
+pub struct EditPrediction {
+    /// The ID of the prediction, which is passed back when the prediction is accepted or rejected.
+    pub id: Option<String>,
+    /// The edits to apply, as non-overlapping ranges of the excerpt.
+    pub edits: Vec<EditPredictionEdit>,
+}
@@ -0,0 +1,40 @@
+use std::ops::Range;
+
+/// A request to format a buffer.
+#[derive(Debug, Clone)]
+pub struct FormatRequest {
+    /// The text of the buffer.
+    pub text: String,
+    /// The byte ranges within the text to format.
+    ///
+    /// If empty, the whole buffer should be formatted.
+    pub ranges: Vec<Range<usize>>,
+    /// The name of the buffer's language, if it has one.
+    pub language_name: Option<String>,
+    /// The absolute path of the buffer's file, if it has one.
+    pub path: Option<String>,
+    /// The number of columns a tab character spans.
+    pub tab_size: u32,
+    /// Whether to indent with tabs instead of spaces.
+    pub hard_tabs: bool,
+}
+
+/// An edit to the text of a buffer.
+#[derive(Debug, Clone)]
+pub struct FormatEdit {
+    /// The byte range within the original text to replace.
+    pub range: Range<usize>,
+    /// The text to replace the range with.
+    pub new_text: String,
+}
+
+/// The result of formatting a buffer.
+#[derive(Debug, Clone)]
+pub enum FormatResult {
+    /// The buffer is already formatted.
+    Unchanged,
+    /// The formatted text of the whole buffer.
+    Text(String),
+    /// The edits that format the buffer, as non-overlapping ranges of the original text.
+    Edits(Vec<FormatEdit>),
+}
@@ -0,0 +1,30 @@
+use std::ops::Range;
+
+/// A Git remote, as parsed by a Git hosting provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHostingRemote {
+    /// The owner of the repository.
+    pub owner: String,
+    /// The name of the repository.
+    pub repo: String,
+}
+
+/// The parameters for building a permalink to a file.
+#[derive(Debug, Clone)]
+pub struct GitHostingPermalinkParams {
+    /// The SHA of the commit.
+    pub sha: String,
+    /// The path of the file, relative to the root of the repository.
+    pub path: String,
+    /// The zero-based range of selected lines, if any.
+    pub selection: Option<Range<u32>>,
+}
+
+/// A pull request referenced in a commit message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHostingPullRequest {
+    /// The number of the pull request.
+    pub number: u32,
+    /// The URL of the pull request.
+    pub url: String,
+}
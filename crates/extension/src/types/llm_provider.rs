@@ -0,0 +1,151 @@
+/// A language model offered by an extension's language model provider.
+#[derive(Debug, Clone)]
+pub struct LlmModel {
+    /// The ID of the model, as sent to the provider's API.
+    pub id: String,
+    /// The human-readable name of the model.
+    pub name: String,
+    /// The maximum number of tokens in the model's context window.
+    pub max_token_count: u64,
+    /// The maximum number of tokens the model can produce in a single response.
+    pub max_output_tokens: Option<u64>,
+    /// Whether the model accepts images in requests.
+    pub supports_images: bool,
+    /// Whether the model supports tool use.
+    pub supports_tools: bool,
+    /// Whether this model should be used by default for the provider.
+    pub is_default: bool,
+    /// Whether this model should be used by default for quick tasks, like summarization.
+    pub is_default_fast: bool,
+}
+
+/// The role of a message in a conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmRole {
+    User,
+    Assistant,
+    System,
+}
+
+/// An image attached to a message.
+#[derive(Debug, Clone)]
+pub struct LlmImage {
+    /// The base64-encoded PNG data of the image.
+    pub source: String,
+    /// The width of the image, in pixels.
+    pub width: u32,
+    /// The height of the image, in pixels.
+    pub height: u32,
+}
+
+/// A request from the model to use a tool.
+#[derive(Debug, Clone)]
+pub struct LlmToolUse {
+    /// The ID of the tool use, used to correlate it with its result.
+    pub id: String,
+    /// The name of the tool.
+    pub name: String,
+    /// The input to the tool, as a JSON string.
+    pub input: String,
+}
+
+/// The result of using a tool.
+#[derive(Debug, Clone)]
+pub struct LlmToolResult {
+    /// The ID of the tool use this is the result of.
+    pub tool_use_id: String,
+    /// The name of the tool.
+    pub tool_name: String,
+    /// Whether the tool use failed.
+    pub is_error: bool,
+    /// The output of the tool.
+    pub content: String,
+}
+
+/// A piece of content within a message.
+#[derive(Debug, Clone)]
+pub enum LlmMessageContent {
+    Text(String),
+    Thinking(String),
+    Image(LlmImage),
+    ToolUse(LlmToolUse),
+    ToolResult(LlmToolResult),
+}
+
+/// A message in a completion request.
+#[derive(Debug, Clone)]
+pub struct LlmRequestMessage {
+    /// The role of the message author.
+    pub role: LlmRole,
+    /// The content of the message.
+    pub content: Vec<LlmMessageContent>,
+    /// Whether the provider should cache the conversation up to and including this message.
+    pub cache: bool,
+}
+
+/// A tool the model may use.
+#[derive(Debug, Clone)]
+pub struct LlmTool {
+    /// The name of the tool.
+    pub name: String,
+    /// The description of the tool.
+    pub description: String,
+    /// The JSON schema for the tool's input, as a JSON string.
+    pub input_schema: String,
+}
+
+/// How the model should choose which tools to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmToolChoice {
+    Auto,
+    Any,
+    None,
+}
+
+/// A request for a completion.
+#[derive(Debug, Clone)]
+pub struct LlmCompletionRequest {
+    /// The messages in the conversation.
+    pub messages: Vec<LlmRequestMessage>,
+    /// The tools available to the model.
+    pub tools: Vec<LlmTool>,
+    /// How the model should choose which tools to use.
+    pub tool_choice: Option<LlmToolChoice>,
+    /// Sequences that stop generation when produced.
+    pub stop: Vec<String>,
+    /// The sampling temperature.
+    pub temperature: Option<f32>,
+}
+
+/// The reason a completion stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmStopReason {
+    EndTurn,
+    MaxTokens,
+    ToolUse,
+    Refusal,
+}
+
+/// The number of tokens used by a completion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LlmTokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+/// An event produced while streaming a completion.
+#[derive(Debug, Clone)]
+pub enum LlmCompletionEvent {
+    /// A chunk of text.
+    Text(String),
+    /// A chunk of the model's reasoning.
+    Thinking(String),
+    /// A complete tool use.
+    ToolUse(LlmToolUse),
+    /// The completion stopped.
+    Stop(LlmStopReason),
+    /// The token usage of the completion so far.
+    Usage(LlmTokenUsage),
+}
@@ -0,0 +1,10 @@
+pub use task::TaskTemplate;
+
+/// The location for which task variables are built.
+#[derive(Debug, Clone, Default)]
+pub struct TaskLocation {
+    /// The absolute path of the worktree root, if any.
+    pub worktree_root: Option<String>,
+    /// The path of the active toolchain for the location, if any.
+    pub active_toolchain_path: Option<String>,
+}
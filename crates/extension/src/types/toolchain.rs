@@ -0,0 +1,19 @@
+/// A toolchain found by an extension's toolchain provider.
+#[derive(Debug, Clone)]
+pub struct Toolchain {
+    /// The human-readable name of the toolchain.
+    pub name: String,
+    /// The path of the toolchain.
+    pub path: String,
+    /// Additional details about the toolchain, as a JSON string.
+    pub metadata: Option<String>,
+}
+
+/// The toolchains found for a worktree.
+#[derive(Debug, Clone, Default)]
+pub struct ToolchainList {
+    /// The toolchains.
+    pub toolchains: Vec<Toolchain>,
+    /// The index of the toolchain to use by default, if any.
+    pub default_index: Option<usize>,
+}
@@ -1,6 +1,7 @@
 //! The Zed Rust Extension API allows you write extensions for [Zed](https://zed.dev/) in Rust.
 
 pub mod http_client;
+pub mod llm_provider;
 pub mod process;
 pub mod settings;
 
@@ -54,6 +55,40 @@ pub mod lsp {
     };
 }
 
+/// Constructs for predicting edits.
+pub mod edit_prediction {
+    pub use crate::wit::zed::extension::edit_prediction::{
+        Prediction, PredictionEdit, PredictionRequest,
+    };
+}
+
+/// Constructs for implementing Git hosting providers.
+pub mod git_hosting {
+    pub use crate::wit::zed::extension::git_hosting::{
+        ParsedGitRemote, PermalinkParams, PullRequest,
+    };
+}
+
+/// Constructs for formatting buffers.
+pub mod formatter {
+    pub use crate::wit::zed::extension::formatter::{FormatRequest, FormatResult, TextEdit};
+}
+
+/// Constructs for providing code actions.
+pub mod code_action {
+    pub use crate::wit::zed::extension::code_action::{CodeAction, CodeActionRequest};
+}
+
+/// Constructs for providing tasks.
+pub mod task_provider {
+    pub use crate::wit::zed::extension::task_provider::{TaskLocation, TaskTemplate};
+}
+
+/// Constructs for providing toolchains.
+pub mod toolchain {
+    pub use crate::wit::zed::extension::toolchain::{Toolchain, ToolchainList};
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -259,6 +294,142 @@ pub trait Extension: Send + Sync {
     ) -> Result<DebugRequest, String> {
         Err("`run_dap_locator` not implemented".to_string())
     }
+
+    /// Returns the models offered by the specified language model provider.
+    fn llm_provider_models(&mut self, _provider_id: &str) -> Result<Vec<llm_provider::Model>> {
+        Err("`llm_provider_models` not implemented".to_string())
+    }
+
+    /// Starts streaming a completion from the specified language model provider.
+    ///
+    /// The API key is the one the user configured for the provider in Zed, if any.
+    fn llm_stream_completion(
+        &mut self,
+        _provider_id: &str,
+        _model_id: &str,
+        _request: llm_provider::CompletionRequest,
+        _api_key: Option<String>,
+    ) -> Result<Box<dyn llm_provider::CompletionStream>> {
+        Err("`llm_stream_completion` not implemented".to_string())
+    }
+
+    /// Formats a buffer with the specified formatter.
+    fn format_buffer(
+        &mut self,
+        _formatter_id: &str,
+        _request: formatter::FormatRequest,
+    ) -> Result<formatter::FormatResult> {
+        Err("`format_buffer` not implemented".to_string())
+    }
+
+    /// Returns the code actions offered by the given code action provider for a range of a buffer.
+    fn code_actions(
+        &mut self,
+        _provider_id: &str,
+        _request: code_action::CodeActionRequest,
+    ) -> Result<Vec<code_action::CodeAction>> {
+        Err("`code_actions` not implemented".to_string())
+    }
+
+    /// Returns the task templates offered by the given task provider for the file at the given path.
+    fn task_templates(
+        &mut self,
+        _provider_id: &str,
+        _path: Option<String>,
+    ) -> Result<Vec<task_provider::TaskTemplate>> {
+        Err("`task_templates` not implemented".to_string())
+    }
+
+    /// Returns additional task variables for the given location.
+    ///
+    /// The returned variables are available to tasks as `$ZED_CUSTOM_<name>`.
+    fn task_variables(
+        &mut self,
+        _provider_id: &str,
+        _variables: EnvVars,
+        _location: task_provider::TaskLocation,
+    ) -> Result<EnvVars> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the toolchains found by the given toolchain provider for a worktree.
+    fn list_toolchains(
+        &mut self,
+        _provider_id: &str,
+        _worktree_root: String,
+        _subroot_relative_path: Option<String>,
+        _env: EnvVars,
+    ) -> Result<toolchain::ToolchainList> {
+        Err("`list_toolchains` not implemented".to_string())
+    }
+
+    /// Predicts the next edits in a buffer with the given edit prediction provider.
+    ///
+    /// Returns `Ok(None)` if there is no prediction.
+    fn predict_edits(
+        &mut self,
+        _provider_id: &str,
+        _request: edit_prediction::PredictionRequest,
+    ) -> Result<Option<edit_prediction::Prediction>> {
+        Err("`predict_edits` not implemented".to_string())
+    }
+
+    /// Notifies the given edit prediction provider that a prediction was accepted.
+    fn accept_edit_prediction(&mut self, _provider_id: &str, _prediction_id: &str) {}
+
+    /// Notifies the given edit prediction provider that a prediction was rejected.
+    fn reject_edit_prediction(&mut self, _provider_id: &str, _prediction_id: &str) {}
+
+    /// Parses the given Git remote URL with the given Git hosting provider.
+    ///
+    /// Returns `None` if the remote is not hosted by the provider.
+    fn parse_git_remote_url(
+        &mut self,
+        _provider_id: &str,
+        _url: &str,
+    ) -> Option<git_hosting::ParsedGitRemote> {
+        None
+    }
+
+    /// Returns a permalink to a commit on the given Git hosting provider.
+    fn build_git_commit_permalink(
+        &mut self,
+        _provider_id: &str,
+        _remote: git_hosting::ParsedGitRemote,
+        _sha: &str,
+    ) -> Result<String> {
+        Err("`build_git_commit_permalink` not implemented".to_string())
+    }
+
+    /// Returns a permalink to a file and/or selection on the given Git hosting provider.
+    fn build_git_permalink(
+        &mut self,
+        _provider_id: &str,
+        _remote: git_hosting::ParsedGitRemote,
+        _params: git_hosting::PermalinkParams,
+    ) -> Result<String> {
+        Err("`build_git_permalink` not implemented".to_string())
+    }
+
+    /// Returns the pull request referenced in the given commit message, if any.
+    fn extract_git_pull_request(
+        &mut self,
+        _provider_id: &str,
+        _remote: git_hosting::ParsedGitRemote,
+        _message: &str,
+    ) -> Option<git_hosting::PullRequest> {
+        None
+    }
+
+    /// Returns the URL of the avatar of the author of the given commit, if any.
+    fn git_commit_author_avatar_url(
+        &mut self,
+        _provider_id: &str,
+        _remote: git_hosting::ParsedGitRemote,
+        _sha: &str,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -300,7 +471,7 @@ mod wit {
 
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.6.0",
+        path: "./wit/since_v0.7.0",
     });
 }
 
@@ -484,6 +655,123 @@ impl wit::Guest for Component {
     ) -> Result<DebugRequest, String> {
         extension().run_dap_locator(locator_name, build_task)
     }
+
+    fn llm_provider_models(provider_id: String) -> Result<Vec<llm_provider::Model>, String> {
+        extension().llm_provider_models(&provider_id)
+    }
+
+    fn llm_stream_completion(
+        provider_id: String,
+        model_id: String,
+        request: llm_provider::CompletionRequest,
+        api_key: Option<String>,
+    ) -> Result<u64, String> {
+        let stream =
+            extension().llm_stream_completion(&provider_id, &model_id, request, api_key)?;
+        Ok(llm_provider::insert_completion_stream(stream))
+    }
+
+    fn llm_next_completion_events(
+        stream_id: u64,
+    ) -> Result<Option<Vec<llm_provider::CompletionEvent>>, String> {
+        llm_provider::next_completion_events(stream_id)
+    }
+
+    fn llm_drop_completion_stream(stream_id: u64) {
+        llm_provider::drop_completion_stream(stream_id)
+    }
+
+    fn format_buffer(
+        formatter_id: String,
+        request: formatter::FormatRequest,
+    ) -> Result<formatter::FormatResult, String> {
+        extension().format_buffer(&formatter_id, request)
+    }
+
+    fn code_actions(
+        provider_id: String,
+        request: code_action::CodeActionRequest,
+    ) -> Result<Vec<code_action::CodeAction>, String> {
+        extension().code_actions(&provider_id, request)
+    }
+
+    fn task_templates(
+        provider_id: String,
+        path: Option<String>,
+    ) -> Result<Vec<task_provider::TaskTemplate>, String> {
+        extension().task_templates(&provider_id, path)
+    }
+
+    fn task_variables(
+        provider_id: String,
+        variables: EnvVars,
+        location: task_provider::TaskLocation,
+    ) -> Result<EnvVars, String> {
+        extension().task_variables(&provider_id, variables, location)
+    }
+
+    fn list_toolchains(
+        provider_id: String,
+        worktree_root: String,
+        subroot_relative_path: Option<String>,
+        env: EnvVars,
+    ) -> Result<toolchain::ToolchainList, String> {
+        extension().list_toolchains(&provider_id, worktree_root, subroot_relative_path, env)
+    }
+
+    fn predict_edits(
+        provider_id: String,
+        request: edit_prediction::PredictionRequest,
+    ) -> Result<Option<edit_prediction::Prediction>, String> {
+        extension().predict_edits(&provider_id, request)
+    }
+
+    fn accept_edit_prediction(provider_id: String, prediction_id: String) {
+        extension().accept_edit_prediction(&provider_id, &prediction_id)
+    }
+
+    fn reject_edit_prediction(provider_id: String, prediction_id: String) {
+        extension().reject_edit_prediction(&provider_id, &prediction_id)
+    }
+
+    fn parse_git_remote_url(
+        provider_id: String,
+        url: String,
+    ) -> Option<git_hosting::ParsedGitRemote> {
+        extension().parse_git_remote_url(&provider_id, &url)
+    }
+
+    fn build_git_commit_permalink(
+        provider_id: String,
+        remote: git_hosting::ParsedGitRemote,
+        sha: String,
+    ) -> Result<String, String> {
+        extension().build_git_commit_permalink(&provider_id, remote, &sha)
+    }
+
+    fn build_git_permalink(
+        provider_id: String,
+        remote: git_hosting::ParsedGitRemote,
+        params: git_hosting::PermalinkParams,
+    ) -> Result<String, String> {
+        extension().build_git_permalink(&provider_id, remote, params)
+    }
+
+    fn extract_git_pull_request(
+        provider_id: String,
+        remote: git_hosting::ParsedGitRemote,
+        message: String,
+    ) -> Option<git_hosting::PullRequest> {
+        extension().extract_git_pull_request(&provider_id, remote, &message)
+    }
+
+    fn git_commit_author_avatar_url(
+        provider_id: String,
+        remote: git_hosting::ParsedGitRemote,
+        sha: String,
+    ) -> Result<Option<String>, String> {
+        extension().git_commit_author_avatar_url(&provider_id, remote, &sha)
+    }
 }
 
 /// The ID of a language server.
@@ -0,0 +1,53 @@
+//! Constructs for implementing language model providers.
+
+use std::collections::BTreeMap;
+
+pub use crate::wit::zed::extension::llm_provider::{
+    CompletionEvent, CompletionRequest, Image, MessageContent, Model, RequestMessage, Role,
+    StopReason, TokenUsage, Tool, ToolChoice, ToolResult, ToolUse,
+};
+
+/// A stream of events for a completion that is in progress.
+///
+/// Zed polls the stream by calling [`CompletionStream::next_events`] until it
+/// returns `Ok(None)`, or until the completion is no longer needed, at which
+/// point the stream is dropped.
+pub trait CompletionStream {
+    /// Returns the next events in the completion.
+    ///
+    /// Returns `Ok(None)` once the completion has ended.
+    fn next_events(&mut self) -> Result<Option<Vec<CompletionEvent>>, String>;
+}
+
+static mut COMPLETION_STREAMS: BTreeMap<u64, Box<dyn CompletionStream>> = BTreeMap::new();
+static mut NEXT_COMPLETION_STREAM_ID: u64 = 0;
+
+pub(crate) fn insert_completion_stream(stream: Box<dyn CompletionStream>) -> u64 {
+    #[expect(static_mut_refs)]
+    unsafe {
+        let stream_id = NEXT_COMPLETION_STREAM_ID;
+        NEXT_COMPLETION_STREAM_ID += 1;
+        COMPLETION_STREAMS.insert(stream_id, stream);
+        stream_id
+    }
+}
+
+pub(crate) fn next_completion_events(
+    stream_id: u64,
+) -> Result<Option<Vec<CompletionEvent>>, String> {
+    #[expect(static_mut_refs)]
+    let stream = unsafe { COMPLETION_STREAMS.get_mut(&stream_id) }
+        .ok_or_else(|| format!("no completion stream with ID {stream_id}"))?;
+    let events = stream.next_events();
+    if !matches!(events, Ok(Some(_))) {
+        drop_completion_stream(stream_id);
+    }
+    events
+}
+
+pub(crate) fn drop_completion_stream(stream_id: u64) {
+    #[expect(static_mut_refs)]
+    unsafe {
+        COMPLETION_STREAMS.remove(&stream_id);
+    }
+}
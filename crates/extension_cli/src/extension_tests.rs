@@ -0,0 +1,820 @@
+//! Runs an extension's tests against fixtures, without launching Zed.
+//!
+//! Tests are described in a TOML file, which provides the contents of a fake
+//! worktree, canned HTTP responses and canned command outputs, along with the
+//! extension exports to call and their expected results.
+
+use std::any::type_name;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use async_trait::async_trait;
+use extension::{
+    Command, Extension, ExtensionCapability, ExtensionCapabilityProxy, ExtensionHostProxy,
+    ExtensionManifest, SlashCommand, WorktreeDelegate,
+};
+use extension_host::wasm_host::{CommandOutput, CommandRunner, WasmExtension, WasmHost};
+use futures::FutureExt as _;
+use futures::future::BoxFuture;
+use gpui::{App, Application, AsyncApp, SemanticVersion, SharedString, Task};
+use http_client::{AsyncBody, HttpClient, Request, Response, Url};
+use language::LanguageName;
+use lsp::LanguageServerName;
+use node_runtime::NodeRuntime;
+use project::Project;
+use serde::Deserialize;
+use task::TaskTemplate;
+
+/// A file describing the tests for an extension.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestFile {
+    /// The worktree passed to the extension.
+    #[serde(default)]
+    worktree: WorktreeFixture,
+    /// The responses to the HTTP requests made by the extension.
+    #[serde(default)]
+    http: Vec<HttpFixture>,
+    /// The outputs of the commands run by the extension.
+    #[serde(default)]
+    process: Vec<ProcessFixture>,
+    #[serde(default, rename = "test")]
+    tests: Vec<TestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WorktreeFixture {
+    root_path: String,
+    /// The contents of the files in the worktree, keyed by their paths relative to the root.
+    files: BTreeMap<String, String>,
+    /// The paths of the binaries found on the `PATH`, keyed by their names.
+    binaries: BTreeMap<String, String>,
+    shell_env: BTreeMap<String, String>,
+}
+
+impl Default for WorktreeFixture {
+    fn default() -> Self {
+        Self {
+            root_path: "/worktree".to_string(),
+            files: BTreeMap::default(),
+            binaries: BTreeMap::default(),
+            shell_env: BTreeMap::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HttpFixture {
+    url: String,
+    #[serde(default = "default_http_status")]
+    status: u16,
+    #[serde(default)]
+    body: String,
+    /// The path of a file containing the body, relative to the test file.
+    #[serde(default)]
+    body_file: Option<PathBuf>,
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProcessFixture {
+    command: String,
+    /// The arguments the command must be run with.
+    ///
+    /// If omitted, the fixture matches the command regardless of its arguments.
+    #[serde(default)]
+    args: Option<Vec<String>>,
+    #[serde(default)]
+    status: i32,
+    #[serde(default)]
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TestCase {
+    name: String,
+    #[serde(flatten)]
+    kind: TestKind,
+    /// If set, the test expects the call to fail with an error containing this text.
+    #[serde(default)]
+    expect_error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TestKind {
+    LanguageServerCommand {
+        language_server_id: String,
+        language_name: String,
+        #[serde(default)]
+        expect_command: Option<String>,
+        #[serde(default)]
+        expect_args: Option<Vec<String>>,
+        #[serde(default)]
+        expect_env: Option<BTreeMap<String, String>>,
+    },
+    SlashCommand {
+        command: String,
+        #[serde(default)]
+        arguments: Vec<String>,
+        #[serde(default)]
+        expect_text: Option<String>,
+        #[serde(default)]
+        expect_text_contains: Option<String>,
+    },
+    DapLocator {
+        locator: String,
+        build_task: TaskTemplate,
+        resolved_label: String,
+        debug_adapter: String,
+        /// The fields the created debug scenario must have.
+        #[serde(default)]
+        expect_scenario: Option<serde_json::Value>,
+        /// Whether the locator is expected to not create a scenario.
+        #[serde(default)]
+        expect_no_scenario: bool,
+    },
+}
+
+/// Runs the tests in the given test file against the compiled extension.
+///
+/// Returns whether all of the tests passed.
+pub fn run_tests(
+    manifest: ExtensionManifest,
+    wasm_path: PathBuf,
+    test_file_path: PathBuf,
+    work_dir: PathBuf,
+) -> Result<bool> {
+    let test_file_contents = std::fs::read_to_string(&test_file_path)
+        .with_context(|| format!("failed to read test file {test_file_path:?}"))?;
+    let test_file: TestFile = toml::from_str(&test_file_contents)
+        .with_context(|| format!("failed to parse test file {test_file_path:?}"))?;
+    let fixtures_dir = test_file_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let http_client = Arc::new(FixtureHttpClient::new(&test_file.http, &fixtures_dir)?);
+    let wasm_bytes = std::fs::read(&wasm_path)
+        .with_context(|| format!("failed to read extension wasm {wasm_path:?}"))?;
+
+    let outcome = Rc::new(RefCell::new(None));
+    Application::headless().run({
+        let outcome = outcome.clone();
+        move |cx| {
+            init(cx);
+
+            let fs = Arc::new(::fs::RealFs::new(None, cx.background_executor().clone()));
+            let proxy = Arc::new(ExtensionHostProxy::new());
+            proxy.register_capability_proxy(FixtureCapabilityConsent);
+            let wasm_host = WasmHost::with_command_runner(
+                fs,
+                http_client,
+                NodeRuntime::unavailable(),
+                proxy,
+                work_dir,
+                Arc::new(FixtureCommandRunner {
+                    fixtures: test_file.process.clone(),
+                }),
+                cx,
+            );
+
+            cx.spawn(async move |cx| {
+                let result =
+                    run_test_cases(wasm_host, wasm_bytes, Arc::new(manifest), test_file, cx).await;
+                outcome.replace(Some(result));
+                cx.update(|cx| cx.quit()).ok();
+            })
+            .detach();
+        }
+    });
+
+    outcome
+        .take()
+        .context("test run ended before the tests completed")?
+}
+
+fn init(cx: &mut App) {
+    settings::init(cx);
+    release_channel::init(SemanticVersion::default(), cx);
+    language::init(cx);
+    Project::init_settings(cx);
+}
+
+async fn run_test_cases(
+    wasm_host: Arc<WasmHost>,
+    wasm_bytes: Vec<u8>,
+    manifest: Arc<ExtensionManifest>,
+    test_file: TestFile,
+    cx: &mut AsyncApp,
+) -> Result<bool> {
+    let extension = wasm_host
+        .load_extension(wasm_bytes, &manifest, cx.background_executor().clone())
+        .await
+        .context("failed to load extension")?;
+    let worktree: Arc<dyn WorktreeDelegate> = Arc::new(FixtureWorktree(test_file.worktree));
+
+    let mut failures = Vec::new();
+    for test in &test_file.tests {
+        let result = run_test_case(&extension, &manifest, &worktree, test).await;
+        match result {
+            Ok(()) => println!("test {} ... ok", test.name),
+            Err(error) => {
+                println!("test {} ... FAILED", test.name);
+                failures.push((test.name.as_str(), error));
+            }
+        }
+    }
+
+    for (name, error) in &failures {
+        println!("\n---- {name} ----\n{error:#}");
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        test_file.tests.len() - failures.len(),
+        failures.len()
+    );
+
+    Ok(failures.is_empty())
+}
+
+async fn run_test_case(
+    extension: &WasmExtension,
+    manifest: &ExtensionManifest,
+    worktree: &Arc<dyn WorktreeDelegate>,
+    test: &TestCase,
+) -> Result<()> {
+    let result = match &test.kind {
+        TestKind::LanguageServerCommand {
+            language_server_id,
+            language_name,
+            expect_command,
+            expect_args,
+            expect_env,
+        } => extension
+            .language_server_command(
+                LanguageServerName::from_proto(language_server_id.clone()),
+                LanguageName::new(language_name),
+                worktree.clone(),
+            )
+            .await
+            .and_then(|command| {
+                check_command(
+                    &command,
+                    expect_command.as_deref(),
+                    expect_args.as_deref(),
+                    expect_env.as_ref(),
+                )
+            }),
+        TestKind::SlashCommand {
+            command,
+            arguments,
+            expect_text,
+            expect_text_contains,
+        } => {
+            let entry = manifest
+                .slash_commands
+                .get(command.as_str())
+                .with_context(|| format!("extension does not provide slash command {command:?}"))?;
+            extension
+                .run_slash_command(
+                    SlashCommand {
+                        name: command.clone(),
+                        description: entry.description.clone(),
+                        tooltip_text: entry.description.clone(),
+                        requires_argument: entry.requires_argument,
+                    },
+                    arguments.clone(),
+                    Some(worktree.clone()),
+                )
+                .await
+                .and_then(|output| {
+                    if let Some(expected) = expect_text {
+                        check_eq("text", output.text.as_str(), expected.as_str())?;
+                    }
+                    if let Some(expected) = expect_text_contains {
+                        anyhow::ensure!(
+                            output.text.contains(expected.as_str()),
+                            "expected text to contain {expected:?}, but got {:?}",
+                            output.text
+                        );
+                    }
+                    Ok(())
+                })
+        }
+        TestKind::DapLocator {
+            locator,
+            build_task,
+            resolved_label,
+            debug_adapter,
+            expect_scenario,
+            expect_no_scenario,
+        } => extension
+            .dap_locator_create_scenario(
+                locator.clone(),
+                build_task.clone(),
+                resolved_label.clone(),
+                debug_adapter.clone(),
+            )
+            .await
+            .and_then(|scenario| match (scenario, expect_no_scenario) {
+                (None, true) => Ok(()),
+                (None, false) => Err(anyhow!("expected a debug scenario, but got none")),
+                (Some(scenario), true) => {
+                    Err(anyhow!("expected no debug scenario, but got {scenario:?}"))
+                }
+                (Some(scenario), false) => {
+                    if let Some(expected) = expect_scenario {
+                        let actual = serde_json::to_value(&scenario)?;
+                        anyhow::ensure!(
+                            json_contains(&actual, expected),
+                            "expected debug scenario to match {expected}, but got {actual}"
+                        );
+                    }
+                    Ok(())
+                }
+            }),
+    };
+
+    match (result, &test.expect_error) {
+        (Ok(()), None) => Ok(()),
+        (Ok(()), Some(expected)) => bail!("expected an error containing {expected:?}"),
+        (Err(error), None) => Err(error),
+        (Err(error), Some(expected)) => {
+            let message = format!("{error:#}");
+            anyhow::ensure!(
+                message.contains(expected.as_str()),
+                "expected an error containing {expected:?}, but got {message:?}"
+            );
+            Ok(())
+        }
+    }
+}
+
+fn check_command(
+    command: &Command,
+    expected_command: Option<&str>,
+    expected_args: Option<&[String]>,
+    expected_env: Option<&BTreeMap<String, String>>,
+) -> Result<()> {
+    if let Some(expected) = expected_command {
+        check_eq("command", command.command.as_str(), expected)?;
+    }
+    if let Some(expected) = expected_args {
+        check_eq("args", command.args.as_slice(), expected)?;
+    }
+    if let Some(expected) = expected_env {
+        let env = command.env.iter().cloned().collect::<BTreeMap<_, _>>();
+        check_eq("env", &env, expected)?;
+    }
+    Ok(())
+}
+
+fn check_eq<T: PartialEq + std::fmt::Debug + ?Sized>(
+    name: &str,
+    actual: &T,
+    expected: &T,
+) -> Result<()> {
+    anyhow::ensure!(
+        actual == expected,
+        "expected {name} to be {expected:?}, but got {actual:?}"
+    );
+    Ok(())
+}
+
+/// Returns whether `actual` contains all of the fields in `expected`.
+fn json_contains(actual: &serde_json::Value, expected: &serde_json::Value) -> bool {
+    match (actual, expected) {
+        (serde_json::Value::Object(actual), serde_json::Value::Object(expected)) => {
+            expected.iter().all(|(key, expected)| {
+                actual
+                    .get(key)
+                    .is_some_and(|actual| json_contains(actual, expected))
+            })
+        }
+        _ => actual == expected,
+    }
+}
+
+struct FixtureWorktree(WorktreeFixture);
+
+#[async_trait]
+impl WorktreeDelegate for FixtureWorktree {
+    fn id(&self) -> u64 {
+        0
+    }
+
+    fn root_path(&self) -> String {
+        self.0.root_path.clone()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        let path = path.to_string_lossy();
+        self.0
+            .files
+            .get(path.as_ref())
+            .cloned()
+            .with_context(|| format!("no file {path:?} in the worktree fixture"))
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        self.0.binaries.get(&binary_name).cloned()
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.0.shell_env.clone().into_iter().collect()
+    }
+}
+
+/// Grants every capability requested by the extension, as the processes and HTTP
+/// requests it makes are served by fixtures.
+struct FixtureCapabilityConsent;
+
+impl ExtensionCapabilityProxy for FixtureCapabilityConsent {
+    fn request_capability_consent(
+        &self,
+        _extension_id: Arc<str>,
+        _extension_name: SharedString,
+        _capability: ExtensionCapability,
+        _cx: &mut App,
+    ) -> Task<Result<bool>> {
+        Task::ready(Ok(true))
+    }
+}
+
+struct FixtureCommandRunner {
+    fixtures: Vec<ProcessFixture>,
+}
+
+#[async_trait]
+impl CommandRunner for FixtureCommandRunner {
+    async fn run_command(&self, command: Command) -> Result<CommandOutput> {
+        let fixture = self
+            .fixtures
+            .iter()
+            .find(|fixture| {
+                fixture.command == command.command
+                    && fixture
+                        .args
+                        .as_ref()
+                        .is_none_or(|args| *args == command.args)
+            })
+            .with_context(|| {
+                format!(
+                    "no process fixture for {:?} with arguments {:?}",
+                    command.command, command.args
+                )
+            })?;
+
+        Ok(CommandOutput {
+            status: Some(fixture.status),
+            stdout: fixture.stdout.clone().into_bytes(),
+            stderr: fixture.stderr.clone().into_bytes(),
+        })
+    }
+}
+
+struct FixtureHttpClient {
+    responses: BTreeMap<String, (u16, Vec<u8>)>,
+}
+
+impl FixtureHttpClient {
+    fn new(fixtures: &[HttpFixture], fixtures_dir: &Path) -> Result<Self> {
+        let mut responses = BTreeMap::new();
+        for fixture in fixtures {
+            let body = match &fixture.body_file {
+                Some(path) => {
+                    let path = fixtures_dir.join(path);
+                    std::fs::read(&path)
+                        .with_context(|| format!("failed to read HTTP fixture {path:?}"))?
+                }
+                None => fixture.body.clone().into_bytes(),
+            };
+            responses.insert(fixture.url.clone(), (fixture.status, body));
+        }
+        Ok(Self { responses })
+    }
+}
+
+impl HttpClient for FixtureHttpClient {
+    fn type_name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    fn send(&self, request: Request<AsyncBody>) -> BoxFuture<'static, Result<Response<AsyncBody>>> {
+        let url = request.uri().to_string();
+        let response = self.responses.get(&url).cloned();
+        async move {
+            let (status, body) =
+                response.with_context(|| format!("no HTTP fixture for {url:?}"))?;
+            Ok(Response::builder().status(status).body(body.into())?)
+        }
+        .boxed()
+    }
+
+    fn proxy(&self) -> Option<&Url> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::AsyncReadExt as _;
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_test_file() {
+        let test_file: TestFile = toml::from_str(
+            r#"
+            [worktree]
+            files = { "package.json" = "{}" }
+            binaries = { acme-lsp = "/usr/bin/acme-lsp" }
+
+            [[http]]
+            url = "https://example.com/releases"
+            body = "[]"
+
+            [[http]]
+            url = "https://example.com/missing"
+            status = 404
+
+            [[process]]
+            command = "acme-lsp"
+            args = ["--version"]
+            stdout = "1.0.0"
+
+            [[test]]
+            name = "starts the language server"
+            type = "language_server_command"
+            language_server_id = "acme-lsp"
+            language_name = "Acme"
+            expect_command = "/usr/bin/acme-lsp"
+            expect_args = ["--stdio"]
+
+            [[test]]
+            name = "runs the slash command"
+            type = "slash_command"
+            command = "acme"
+            arguments = ["docs"]
+            expect_error = "not found"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(test_file.worktree.root_path, "/worktree");
+        assert_eq!(test_file.worktree.files["package.json"], "{}");
+        assert_eq!(test_file.worktree.binaries["acme-lsp"], "/usr/bin/acme-lsp");
+        assert_eq!(
+            test_file
+                .http
+                .iter()
+                .map(|fixture| (fixture.url.as_str(), fixture.status))
+                .collect::<Vec<_>>(),
+            [
+                ("https://example.com/releases", 200),
+                ("https://example.com/missing", 404)
+            ]
+        );
+        assert_eq!(test_file.process[0].args, Some(vec!["--version".into()]));
+        assert_eq!(test_file.process[0].status, 0);
+
+        assert_eq!(test_file.tests.len(), 2);
+        assert!(matches!(
+            &test_file.tests[0].kind,
+            TestKind::LanguageServerCommand {
+                language_server_id,
+                expect_command: Some(expect_command),
+                expect_env: None,
+                ..
+            } if language_server_id == "acme-lsp" && expect_command == "/usr/bin/acme-lsp"
+        ));
+        assert_eq!(test_file.tests[0].expect_error, None);
+        assert!(matches!(
+            &test_file.tests[1].kind,
+            TestKind::SlashCommand { command, arguments, .. }
+                if command == "acme" && *arguments == ["docs"]
+        ));
+        assert_eq!(
+            test_file.tests[1].expect_error.as_deref(),
+            Some("not found")
+        );
+
+        // Typos in the test file are reported rather than ignored.
+        assert!(toml::from_str::<TestFile>("[worktree]\nroot = \"/project\"").is_err());
+        assert!(
+            toml::from_str::<TestFile>("[[test]]\nname = \"test\"\ntype = \"unknown\"").is_err()
+        );
+    }
+
+    #[test]
+    fn test_json_contains() {
+        let actual = json!({
+            "label": "Debug acme",
+            "adapter": "CodeLLDB",
+            "build": { "command": "cargo", "args": ["build"] },
+        });
+
+        assert!(json_contains(&actual, &json!({})));
+        assert!(json_contains(&actual, &json!({ "adapter": "CodeLLDB" })));
+        assert!(json_contains(
+            &actual,
+            &json!({ "build": { "command": "cargo" } })
+        ));
+        assert!(!json_contains(&actual, &json!({ "adapter": "GDB" })));
+        assert!(!json_contains(&actual, &json!({ "program": "acme" })));
+        // Arrays must match exactly.
+        assert!(!json_contains(&actual, &json!({ "build": { "args": [] } })));
+    }
+
+    #[test]
+    fn test_check_command() {
+        let command = Command {
+            command: "/usr/bin/acme-lsp".into(),
+            args: vec!["--stdio".into()],
+            env: vec![("ACME_LOG".into(), "debug".into())],
+        };
+        let env = BTreeMap::from_iter([("ACME_LOG".to_string(), "debug".to_string())]);
+
+        check_command(&command, None, None, None).unwrap();
+        check_command(
+            &command,
+            Some("/usr/bin/acme-lsp"),
+            Some(&["--stdio".into()]),
+            Some(&env),
+        )
+        .unwrap();
+
+        let error = check_command(&command, Some("acme-lsp"), None, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"expected command to be "acme-lsp", but got "/usr/bin/acme-lsp""#
+        );
+        let error = check_command(&command, None, Some(&[]), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"expected args to be [], but got ["--stdio"]"#
+        );
+        let error = check_command(&command, None, None, Some(&BTreeMap::new())).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"expected env to be {}, but got {"ACME_LOG": "debug"}"#
+        );
+    }
+
+    #[test]
+    fn test_fixture_command_runner() {
+        let runner = FixtureCommandRunner {
+            fixtures: vec![
+                ProcessFixture {
+                    command: "acme".into(),
+                    args: Some(vec!["--version".into()]),
+                    status: 0,
+                    stdout: "acme 1.0.0".into(),
+                    stderr: String::new(),
+                },
+                ProcessFixture {
+                    command: "acme".into(),
+                    args: None,
+                    status: 1,
+                    stdout: String::new(),
+                    stderr: "unknown command".into(),
+                },
+            ],
+        };
+        let run = |args: &[&str]| {
+            block_on(runner.run_command(Command {
+                command: "acme".into(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                env: Vec::new(),
+            }))
+        };
+
+        let output = run(&["--version"]).unwrap();
+        assert_eq!(output.status, Some(0));
+        assert_eq!(output.stdout, b"acme 1.0.0");
+
+        // Fixtures without arguments match any arguments.
+        let output = run(&["install"]).unwrap();
+        assert_eq!(output.status, Some(1));
+        assert_eq!(output.stderr, b"unknown command");
+
+        let error = block_on(runner.run_command(Command {
+            command: "other".into(),
+            args: Vec::new(),
+            env: Vec::new(),
+        }))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"no process fixture for "other" with arguments []"#
+        );
+    }
+
+    #[test]
+    fn test_fixture_http_client() {
+        let fixtures_dir = tempfile::tempdir().unwrap();
+        std::fs::write(fixtures_dir.path().join("releases.json"), "[1, 2]").unwrap();
+        let client = FixtureHttpClient::new(
+            &[
+                HttpFixture {
+                    url: "https://example.com/releases".into(),
+                    status: 200,
+                    body: String::new(),
+                    body_file: Some("releases.json".into()),
+                },
+                HttpFixture {
+                    url: "https://example.com/missing".into(),
+                    status: 404,
+                    body: "not found".into(),
+                    body_file: None,
+                },
+            ],
+            fixtures_dir.path(),
+        )
+        .unwrap();
+        let get = |url: &str| {
+            block_on(async {
+                let response = client
+                    .send(Request::get(url).body(AsyncBody::empty()).unwrap())
+                    .await?;
+                let status = response.status().as_u16();
+                let mut body = String::new();
+                response.into_body().read_to_string(&mut body).await?;
+                anyhow::Ok((status, body))
+            })
+        };
+
+        assert_eq!(
+            get("https://example.com/releases").unwrap(),
+            (200, "[1, 2]".to_string())
+        );
+        assert_eq!(
+            get("https://example.com/missing").unwrap(),
+            (404, "not found".to_string())
+        );
+        assert_eq!(
+            get("https://example.com/other").unwrap_err().to_string(),
+            r#"no HTTP fixture for "https://example.com/other""#
+        );
+
+        // Missing body files are reported when loading the fixtures.
+        assert!(
+            FixtureHttpClient::new(
+                &[HttpFixture {
+                    url: "https://example.com/releases".into(),
+                    status: 200,
+                    body: String::new(),
+                    body_file: Some("missing.json".into()),
+                }],
+                fixtures_dir.path(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_fixture_worktree() {
+        let worktree = FixtureWorktree(
+            toml::from_str(
+                r#"
+                files = { "src/main.acme" = "main" }
+                binaries = { acme = "/usr/bin/acme" }
+                shell_env = { PATH = "/usr/bin" }
+                "#,
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(worktree.root_path(), "/worktree");
+        assert_eq!(
+            block_on(worktree.read_text_file("src/main.acme".into())).unwrap(),
+            "main"
+        );
+        assert!(block_on(worktree.read_text_file("missing".into())).is_err());
+        assert_eq!(
+            block_on(worktree.which("acme".into())).as_deref(),
+            Some("/usr/bin/acme")
+        );
+        assert_eq!(block_on(worktree.which("other".into())), None);
+        assert_eq!(
+            block_on(worktree.shell_env()),
+            [("PATH".to_string(), "/usr/bin".to_string())]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+mod extension_tests;
+
 use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::fs;
@@ -13,20 +15,42 @@ use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use language::LanguageConfig;
 use reqwest_client::ReqwestClient;
 use rpc::ExtensionProvides;
+use sha2::{Digest as _, Sha256};
 use tree_sitter::{Language, Query, WasmStore};
 
 #[derive(Parser, Debug)]
-#[command(name = "zed-extension")]
+#[command(name = "zed-extension", subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
     /// The path to the extension directory
-    #[arg(long)]
-    source_dir: PathBuf,
+    #[arg(long, required = true)]
+    source_dir: Option<PathBuf>,
     /// The output directory to place the packaged extension.
+    #[arg(long, required = true)]
+    output_dir: Option<PathBuf>,
+    /// The path to a directory where build dependencies are downloaded
+    #[arg(long, required = true)]
+    scratch_dir: Option<PathBuf>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum CliCommand {
+    /// Compiles the extension and runs the tests described in its test file
+    Test(TestArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct TestArgs {
+    /// The path to the extension directory
     #[arg(long)]
-    output_dir: PathBuf,
+    source_dir: PathBuf,
     /// The path to a directory where build dependencies are downloaded
     #[arg(long)]
     scratch_dir: PathBuf,
+    /// The path to the test file, relative to the extension directory
+    #[arg(long, default_value = "extension_tests.toml")]
+    test_file: PathBuf,
 }
 
 #[tokio::main]
@@ -34,22 +58,30 @@ async fn main() -> Result<()> {
     env_logger::init();
 
     let args = Args::parse();
+    if let Some(CliCommand::Test(test_args)) = args.command {
+        return test_extension(test_args).await;
+    }
+
+    let (Some(source_dir), Some(output_dir), Some(scratch_dir)) =
+        (args.source_dir, args.output_dir, args.scratch_dir)
+    else {
+        bail!("--source-dir, --output-dir and --scratch-dir are required");
+    };
+
     let fs = Arc::new(RealFs::new(None, gpui::background_executor()));
     let engine = wasmtime::Engine::default();
     let mut wasm_store = WasmStore::new(&engine)?;
 
-    let extension_path = args
-        .source_dir
+    let extension_path = source_dir
         .canonicalize()
         .context("failed to canonicalize source_dir")?;
-    let scratch_dir = args
-        .scratch_dir
+    let scratch_dir = scratch_dir
         .canonicalize()
         .context("failed to canonicalize scratch_dir")?;
-    let output_dir = if args.output_dir.is_relative() {
-        env::current_dir()?.join(&args.output_dir)
+    let output_dir = if output_dir.is_relative() {
+        env::current_dir()?.join(&output_dir)
     } else {
-        args.output_dir
+        output_dir
     };
 
     log::info!("loading extension manifest");
@@ -57,16 +89,7 @@ async fn main() -> Result<()> {
 
     log::info!("compiling extension");
 
-    let user_agent = format!(
-        "Zed Extension CLI/{} ({}; {})",
-        env!("CARGO_PKG_VERSION"),
-        std::env::consts::OS,
-        std::env::consts::ARCH
-    );
-    let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
-
-    let builder = ExtensionBuilder::new(http_client, scratch_dir);
-    builder
+    extension_builder(scratch_dir)?
         .compile_extension(
             &extension_path,
             &mut manifest,
@@ -97,6 +120,10 @@ async fn main() -> Result<()> {
         );
     }
 
+    let archive_bytes =
+        fs::read(output_dir.join("archive.tar.gz")).context("failed to read archive.tar.gz")?;
+    let archive_sha256 = hex::encode(Sha256::digest(&archive_bytes));
+
     let extension_provides = extension_provides(&manifest);
 
     let manifest_json = serde_json::to_string(&rpc::ExtensionApiManifest {
@@ -110,6 +137,7 @@ async fn main() -> Result<()> {
             .context("missing repository in extension manifest")?,
         wasm_api_version: manifest.lib.version.map(|version| version.to_string()),
         provides: extension_provides,
+        archive_sha256: Some(archive_sha256),
     })?;
     fs::remove_dir_all(&archive_dir)?;
     fs::write(output_dir.join("manifest.json"), manifest_json.as_bytes())?;
@@ -117,6 +145,60 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+async fn test_extension(args: TestArgs) -> Result<()> {
+    let fs = Arc::new(RealFs::new(None, gpui::background_executor()));
+
+    let extension_path = args
+        .source_dir
+        .canonicalize()
+        .context("failed to canonicalize source_dir")?;
+    let scratch_dir = args
+        .scratch_dir
+        .canonicalize()
+        .context("failed to canonicalize scratch_dir")?;
+
+    log::info!("loading extension manifest");
+    let mut manifest = ExtensionManifest::load(fs, &extension_path).await?;
+    if manifest.lib.kind.is_none() {
+        bail!("extension {} has no Rust library to test", manifest.id);
+    }
+
+    log::info!("compiling extension");
+    extension_builder(scratch_dir)?
+        .compile_extension(
+            &extension_path,
+            &mut manifest,
+            CompileExtensionOptions { release: false },
+        )
+        .await
+        .context("failed to compile extension")?;
+
+    let work_dir = tempfile::tempdir().context("failed to create extension work dir")?;
+    let passed = extension_tests::run_tests(
+        manifest,
+        extension_path.join("extension.wasm"),
+        extension_path.join(&args.test_file),
+        work_dir.path().to_path_buf(),
+    )?;
+    if !passed {
+        bail!("extension tests failed");
+    }
+
+    Ok(())
+}
+
+fn extension_builder(scratch_dir: PathBuf) -> Result<ExtensionBuilder> {
+    let user_agent = format!(
+        "Zed Extension CLI/{} ({}; {})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
+
+    Ok(ExtensionBuilder::new(http_client, scratch_dir))
+}
+
 /// Returns the set of features provided by the extension.
 fn extension_provides(manifest: &ExtensionManifest) -> BTreeSet<ExtensionProvides> {
     let mut provides = BTreeSet::default();
@@ -0,0 +1,628 @@
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use collections::HashMap;
+use extension::{
+    EditPrediction, EditPredictionProviderManifestEntry, EditPredictionRequest, Extension,
+    ExtensionEditPredictionProviderProxy, ExtensionHostProxy,
+};
+use gpui::{App, Context, Entity, EntityId, Global, Task};
+use inline_completion::{Direction, EditPredictionProvider, InlineCompletion};
+use language::{Anchor, Bias, Buffer, BufferSnapshot, Point, ToOffset as _, ToPoint as _};
+use parking_lot::RwLock;
+use project::Project;
+
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
+const EXCERPT_ROWS_BEFORE_CURSOR: u32 = 32;
+const EXCERPT_ROWS_AFTER_CURSOR: u32 = 16;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_edit_prediction_provider_proxy(ExtensionEditPredictionRegistryProxy {
+        registry: ExtensionEditPredictionRegistry::default_global(cx),
+    });
+}
+
+#[derive(Default)]
+struct GlobalExtensionEditPredictionRegistry(Arc<ExtensionEditPredictionRegistry>);
+
+impl Global for GlobalExtensionEditPredictionRegistry {}
+
+/// The edit prediction providers offered by extensions, keyed by their IDs.
+#[derive(Default)]
+pub struct ExtensionEditPredictionRegistry {
+    providers: RwLock<HashMap<Arc<str>, RegisteredProvider>>,
+}
+
+#[derive(Clone)]
+struct RegisteredProvider {
+    extension: Arc<dyn Extension>,
+    manifest_entry: EditPredictionProviderManifestEntry,
+}
+
+impl ExtensionEditPredictionRegistry {
+    /// Returns the global [`ExtensionEditPredictionRegistry`], if it exists.
+    pub fn try_global(cx: &App) -> Option<Arc<Self>> {
+        cx.try_global::<GlobalExtensionEditPredictionRegistry>()
+            .map(|registry| registry.0.clone())
+    }
+
+    /// Returns the global [`ExtensionEditPredictionRegistry`].
+    ///
+    /// Inserts a default [`ExtensionEditPredictionRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut App) -> Arc<Self> {
+        cx.default_global::<GlobalExtensionEditPredictionRegistry>()
+            .0
+            .clone()
+    }
+
+    /// Returns the IDs and names of the registered providers.
+    pub fn providers(&self) -> Vec<(Arc<str>, String)> {
+        let mut providers = self
+            .providers
+            .read()
+            .iter()
+            .map(|(id, provider)| (id.clone(), provider.manifest_entry.name.clone()))
+            .collect::<Vec<_>>();
+        providers.sort();
+        providers
+    }
+
+    /// Creates an [`ExtensionEditPredictionProvider`] for the provider with the given ID.
+    pub fn build_provider(&self, provider_id: &str) -> Option<ExtensionEditPredictionProvider> {
+        let (provider_id, provider) = self
+            .providers
+            .read()
+            .get_key_value(provider_id)
+            .map(|(provider_id, provider)| (provider_id.clone(), provider.clone()))?;
+        Some(ExtensionEditPredictionProvider::new(
+            provider.extension,
+            provider_id,
+        ))
+    }
+
+    fn register(
+        &self,
+        provider_id: Arc<str>,
+        extension: Arc<dyn Extension>,
+        manifest_entry: EditPredictionProviderManifestEntry,
+    ) {
+        self.providers.write().insert(
+            provider_id,
+            RegisteredProvider {
+                extension,
+                manifest_entry,
+            },
+        );
+    }
+
+    fn unregister(&self, provider_id: &str) {
+        self.providers.write().remove(provider_id);
+    }
+}
+
+struct ExtensionEditPredictionRegistryProxy {
+    registry: Arc<ExtensionEditPredictionRegistry>,
+}
+
+impl ExtensionEditPredictionProviderProxy for ExtensionEditPredictionRegistryProxy {
+    fn register_edit_prediction_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: EditPredictionProviderManifestEntry,
+    ) {
+        self.registry.register(provider_id, extension, provider);
+    }
+
+    fn unregister_edit_prediction_provider(&self, provider_id: Arc<str>) {
+        self.registry.unregister(&provider_id);
+    }
+}
+
+struct CurrentPrediction {
+    id: Option<String>,
+    buffer_id: EntityId,
+    edits: Vec<(Range<Anchor>, String)>,
+}
+
+/// An [`EditPredictionProvider`] backed by an extension's edit prediction provider.
+pub struct ExtensionEditPredictionProvider {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    pending_refresh: Option<Task<Result<()>>>,
+    current_prediction: Option<CurrentPrediction>,
+}
+
+impl ExtensionEditPredictionProvider {
+    fn new(extension: Arc<dyn Extension>, provider_id: Arc<str>) -> Self {
+        Self {
+            extension,
+            provider_id,
+            pending_refresh: None,
+            current_prediction: None,
+        }
+    }
+
+    fn replace_current_prediction(
+        &mut self,
+        prediction: Option<CurrentPrediction>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(prediction_id) = std::mem::replace(&mut self.current_prediction, prediction)
+            .and_then(|prediction| prediction.id)
+        else {
+            return;
+        };
+
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        cx.background_spawn(async move {
+            extension
+                .reject_edit_prediction(provider_id, prediction_id)
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl EditPredictionProvider for ExtensionEditPredictionProvider {
+    fn name() -> &'static str {
+        "extension"
+    }
+
+    fn display_name() -> &'static str {
+        "Extension"
+    }
+
+    fn show_completions_in_menu() -> bool {
+        true
+    }
+
+    fn is_enabled(
+        &self,
+        _buffer: &Entity<Buffer>,
+        _cursor_position: language::Anchor,
+        _cx: &App,
+    ) -> bool {
+        true
+    }
+
+    fn is_refreshing(&self) -> bool {
+        self.pending_refresh.is_some()
+    }
+
+    fn refresh(
+        &mut self,
+        _project: Option<Entity<Project>>,
+        buffer: Entity<Buffer>,
+        cursor_position: language::Anchor,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        self.pending_refresh = Some(cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor().timer(DEBOUNCE_TIMEOUT).await;
+            }
+
+            let (snapshot, excerpt_start, request) = buffer.read_with(cx, |buffer, cx| {
+                let snapshot = buffer.snapshot();
+                let (excerpt_range, cursor_offset) = excerpt_for_cursor(&snapshot, cursor_position);
+                let request = EditPredictionRequest {
+                    path: buffer
+                        .file()
+                        .and_then(|file| file.as_local())
+                        .map(|file| file.abs_path(cx).to_string_lossy().into_owned()),
+                    language_name: buffer
+                        .language()
+                        .map(|language| language.name().to_string()),
+                    excerpt: snapshot.text_for_range(excerpt_range.clone()).collect(),
+                    cursor_offset,
+                };
+                (snapshot, excerpt_range.start, request)
+            })?;
+
+            let prediction = extension.predict_edits(provider_id, request).await;
+
+            this.update(cx, |this, cx| {
+                this.pending_refresh = None;
+                cx.notify();
+
+                let prediction = prediction?
+                    .map(|prediction| {
+                        let edits = prediction_edits(&snapshot, excerpt_start, &prediction)?;
+                        anyhow::Ok(CurrentPrediction {
+                            id: prediction.id,
+                            buffer_id: buffer.entity_id(),
+                            edits,
+                        })
+                    })
+                    .transpose()?;
+                this.replace_current_prediction(prediction, cx);
+
+                Ok(())
+            })?
+        }));
+    }
+
+    fn cycle(
+        &mut self,
+        _buffer: Entity<Buffer>,
+        _cursor_position: language::Anchor,
+        _direction: Direction,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn accept(&mut self, cx: &mut Context<Self>) {
+        let Some(prediction_id) = self
+            .current_prediction
+            .take()
+            .and_then(|prediction| prediction.id)
+        else {
+            return;
+        };
+
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        cx.background_spawn(async move {
+            extension
+                .accept_edit_prediction(provider_id, prediction_id)
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn discard(&mut self, cx: &mut Context<Self>) {
+        self.replace_current_prediction(None, cx);
+    }
+
+    fn suggest(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        _cursor_position: language::Anchor,
+        cx: &mut Context<Self>,
+    ) -> Option<InlineCompletion> {
+        let prediction = self.current_prediction.as_ref()?;
+        if prediction.buffer_id != buffer.entity_id() {
+            return None;
+        }
+
+        let buffer = buffer.read(cx);
+        let edits = prediction
+            .edits
+            .iter()
+            .filter(|(range, new_text)| {
+                range.start.is_valid(buffer)
+                    && range.end.is_valid(buffer)
+                    && buffer.text_for_range(range.clone()).collect::<String>() != *new_text
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            return None;
+        }
+
+        Some(InlineCompletion {
+            id: prediction.id.clone().map(Into::into),
+            edits,
+            edit_preview: None,
+        })
+    }
+}
+
+/// Returns the range of the excerpt sent to the provider, along with the
+/// offset of the cursor within it.
+fn excerpt_for_cursor(snapshot: &BufferSnapshot, cursor_position: Anchor) -> (Range<usize>, usize) {
+    let cursor_point = cursor_position.to_point(snapshot);
+    let start_row = cursor_point.row.saturating_sub(EXCERPT_ROWS_BEFORE_CURSOR);
+    let end_row = (cursor_point.row + EXCERPT_ROWS_AFTER_CURSOR).min(snapshot.max_point().row);
+    let start = Point::new(start_row, 0).to_offset(snapshot);
+    let end = Point::new(end_row, snapshot.line_len(end_row)).to_offset(snapshot);
+    let cursor_offset = cursor_position.to_offset(snapshot) - start;
+    (start..end, cursor_offset)
+}
+
+/// Converts the edits of a prediction, which are relative to the excerpt, into
+/// anchor ranges within the buffer.
+fn prediction_edits(
+    snapshot: &BufferSnapshot,
+    excerpt_start: usize,
+    prediction: &EditPrediction,
+) -> Result<Vec<(Range<Anchor>, String)>> {
+    let mut edits = prediction.edits.clone();
+    edits.sort_by_key(|edit| edit.range.start);
+
+    let mut previous_end = excerpt_start;
+    let mut anchored_edits = Vec::with_capacity(edits.len());
+    for edit in edits {
+        let range = excerpt_start + edit.range.start..excerpt_start + edit.range.end;
+        anyhow::ensure!(
+            range.start >= previous_end
+                && range.start <= range.end
+                && range.end <= snapshot.len()
+                && snapshot.clip_offset(range.start, Bias::Left) == range.start
+                && snapshot.clip_offset(range.end, Bias::Left) == range.end,
+            "edit prediction provider returned an invalid edit range: {:?}",
+            edit.range
+        );
+        previous_end = range.end;
+
+        let range = if range.is_empty() {
+            let anchor = snapshot.anchor_after(range.start);
+            anchor..anchor
+        } else {
+            snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end)
+        };
+        anchored_edits.push((range, edit.new_text));
+    }
+
+    Ok(anchored_edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use extension::{EditPredictionEdit, FakeExtension};
+    use gpui::{AppContext as _, TestAppContext};
+    use language::ToOffset;
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    fn init_test(
+        cx: &mut TestAppContext,
+    ) -> (Arc<FakeExtension>, Entity<ExtensionEditPredictionProvider>) {
+        let extension = Arc::new(FakeExtension::new("acme"));
+        let provider = cx.update(|cx| {
+            let registry = ExtensionEditPredictionRegistry::default_global(cx);
+            let proxy = ExtensionEditPredictionRegistryProxy {
+                registry: registry.clone(),
+            };
+            proxy.register_edit_prediction_provider(
+                extension.clone(),
+                "acme-predict".into(),
+                EditPredictionProviderManifestEntry {
+                    name: "Acme Predict".into(),
+                },
+            );
+            assert_eq!(
+                registry.providers(),
+                [("acme-predict".into(), "Acme Predict".to_string())]
+            );
+            cx.new(|_| registry.build_provider("acme-predict").unwrap())
+        });
+        (extension, provider)
+    }
+
+    fn refresh(
+        provider: &Entity<ExtensionEditPredictionProvider>,
+        buffer: &Entity<Buffer>,
+        cursor: Point,
+        debounce: bool,
+        cx: &mut TestAppContext,
+    ) {
+        provider.update(cx, |provider, cx| {
+            let cursor = buffer.read(cx).anchor_before(cursor);
+            provider.refresh(None, buffer.clone(), cursor, debounce, cx);
+        });
+    }
+
+    fn suggested_edits(
+        provider: &Entity<ExtensionEditPredictionProvider>,
+        buffer: &Entity<Buffer>,
+        cx: &mut TestAppContext,
+    ) -> Option<Vec<(Range<usize>, String)>> {
+        provider.update(cx, |provider, cx| {
+            let completion = provider.suggest(buffer, Anchor::MIN, cx)?;
+            let snapshot = buffer.read(cx).snapshot();
+            Some(
+                completion
+                    .edits
+                    .into_iter()
+                    .map(|(range, new_text)| {
+                        (
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot),
+                            new_text,
+                        )
+                    })
+                    .collect(),
+            )
+        })
+    }
+
+    #[gpui::test]
+    async fn test_refresh_and_suggest(cx: &mut TestAppContext) {
+        let (extension, provider) = init_test(cx);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        extension.on_predict_edits({
+            let requests = requests.clone();
+            move |provider_id, request| {
+                assert_eq!(provider_id.as_ref(), "acme-predict");
+                let cursor_offset = request.cursor_offset;
+                requests.lock().push(request);
+                Ok(Some(EditPrediction {
+                    id: Some("prediction-1".into()),
+                    edits: vec![
+                        EditPredictionEdit {
+                            range: cursor_offset..cursor_offset + 2,
+                            new_text: "NE".into(),
+                        },
+                        EditPredictionEdit {
+                            range: 0..0,
+                            new_text: "// ".into(),
+                        },
+                    ],
+                }))
+            }
+        });
+
+        let text = (0..100)
+            .map(|row| format!("line {row}\n"))
+            .collect::<String>();
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        refresh(&provider, &buffer, Point::new(50, 1), false, cx);
+        assert!(provider.read_with(cx, |provider, _| provider.is_refreshing()));
+        cx.run_until_parked();
+        assert!(!provider.read_with(cx, |provider, _| provider.is_refreshing()));
+
+        // The excerpt spans the rows around the cursor.
+        let request = requests.lock().pop().unwrap();
+        assert!(request.excerpt.starts_with("line 18\n"));
+        assert!(request.excerpt.ends_with("\nline 66"));
+        assert_eq!(request.cursor_offset, 32 * "line 18\n".len() + 1);
+        assert_eq!(request.path, None);
+        assert_eq!(request.language_name, None);
+
+        let (excerpt_start, cursor) = buffer.read_with(cx, |buffer, _| {
+            (
+                Point::new(18, 0).to_offset(buffer),
+                Point::new(50, 1).to_offset(buffer),
+            )
+        });
+        assert_eq!(
+            suggested_edits(&provider, &buffer, cx),
+            Some(vec![
+                (excerpt_start..excerpt_start, "// ".to_string()),
+                (cursor..cursor + 2, "NE".to_string()),
+            ])
+        );
+
+        // Predictions only apply to the buffer they were made for.
+        let other_buffer = cx.new(|cx| Buffer::local("other", cx));
+        assert_eq!(suggested_edits(&provider, &other_buffer, cx), None);
+    }
+
+    #[gpui::test]
+    async fn test_refresh_debounce(cx: &mut TestAppContext) {
+        let (extension, provider) = init_test(cx);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        extension.on_predict_edits({
+            let requests = requests.clone();
+            move |_, request| {
+                requests.lock().push(request);
+                Ok(None)
+            }
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("abc", cx));
+        refresh(&provider, &buffer, Point::new(0, 1), true, cx);
+        refresh(&provider, &buffer, Point::new(0, 2), true, cx);
+        cx.run_until_parked();
+        assert!(requests.lock().is_empty());
+        assert!(provider.read_with(cx, |provider, _| provider.is_refreshing()));
+
+        // Only the latest refresh reaches the extension.
+        cx.executor().advance_clock(DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+        let requests = std::mem::take(&mut *requests.lock());
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| request.cursor_offset)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+        assert!(!provider.read_with(cx, |provider, _| provider.is_refreshing()));
+        assert_eq!(suggested_edits(&provider, &buffer, cx), None);
+    }
+
+    #[gpui::test]
+    async fn test_accept_and_reject(cx: &mut TestAppContext) {
+        let (extension, provider) = init_test(cx);
+        let prediction_count = Arc::new(Mutex::new(0));
+        extension.on_predict_edits(move |_, _| {
+            let mut prediction_count = prediction_count.lock();
+            *prediction_count += 1;
+            Ok(Some(EditPrediction {
+                id: Some(format!("prediction-{prediction_count}")),
+                edits: vec![EditPredictionEdit {
+                    range: 0..0,
+                    new_text: "x".into(),
+                }],
+            }))
+        });
+        let accepted = Arc::new(Mutex::new(Vec::new()));
+        extension.on_accept_edit_prediction({
+            let accepted = accepted.clone();
+            move |provider_id, prediction_id| {
+                assert_eq!(provider_id.as_ref(), "acme-predict");
+                accepted.lock().push(prediction_id);
+                Ok(())
+            }
+        });
+        let rejected = Arc::new(Mutex::new(Vec::new()));
+        extension.on_reject_edit_prediction({
+            let rejected = rejected.clone();
+            move |provider_id, prediction_id| {
+                assert_eq!(provider_id.as_ref(), "acme-predict");
+                rejected.lock().push(prediction_id);
+                Ok(())
+            }
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("abc", cx));
+        refresh(&provider, &buffer, Point::new(0, 0), false, cx);
+        cx.run_until_parked();
+        provider.update(cx, |provider, cx| provider.accept(cx));
+        cx.run_until_parked();
+        assert_eq!(*accepted.lock(), ["prediction-1"]);
+        assert!(rejected.lock().is_empty());
+
+        // An accepted prediction is no longer current.
+        provider.update(cx, |provider, cx| provider.accept(cx));
+        cx.run_until_parked();
+        assert_eq!(*accepted.lock(), ["prediction-1"]);
+        assert_eq!(suggested_edits(&provider, &buffer, cx), None);
+
+        // Predictions that are replaced or discarded are rejected.
+        refresh(&provider, &buffer, Point::new(0, 0), false, cx);
+        cx.run_until_parked();
+        refresh(&provider, &buffer, Point::new(0, 0), false, cx);
+        cx.run_until_parked();
+        assert_eq!(*rejected.lock(), ["prediction-2"]);
+        provider.update(cx, |provider, cx| provider.discard(cx));
+        cx.run_until_parked();
+        assert_eq!(*rejected.lock(), ["prediction-2", "prediction-3"]);
+        assert_eq!(*accepted.lock(), ["prediction-1"]);
+    }
+
+    #[gpui::test]
+    async fn test_invalid_prediction_edits(cx: &mut TestAppContext) {
+        let (extension, provider) = init_test(cx);
+        extension.on_predict_edits(|_, _| {
+            Ok(Some(EditPrediction {
+                id: Some("prediction-1".into()),
+                edits: vec![
+                    EditPredictionEdit {
+                        range: 0..2,
+                        new_text: "x".into(),
+                    },
+                    EditPredictionEdit {
+                        range: 1..3,
+                        new_text: "y".into(),
+                    },
+                ],
+            }))
+        });
+        let rejected = Arc::new(Mutex::new(Vec::new()));
+        extension.on_reject_edit_prediction({
+            let rejected = rejected.clone();
+            move |_, prediction_id| {
+                rejected.lock().push(prediction_id);
+                Ok(())
+            }
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("abc", cx));
+        refresh(&provider, &buffer, Point::new(0, 0), false, cx);
+        cx.run_until_parked();
+        assert_eq!(suggested_edits(&provider, &buffer, cx), None);
+        provider.update(cx, |provider, cx| provider.discard(cx));
+        cx.run_until_parked();
+        assert!(rejected.lock().is_empty());
+    }
+}
@@ -140,6 +140,13 @@ fn manifest() -> ExtensionManifest {
         }],
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        language_model_providers: Default::default(),
+        formatters: Default::default(),
+        code_action_providers: Default::default(),
+        task_providers: Default::default(),
+        toolchain_providers: Default::default(),
+        edit_prediction_providers: Default::default(),
+        git_hosting_providers: Default::default(),
     }
 }
 
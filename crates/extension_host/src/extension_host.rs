@@ -9,15 +9,21 @@ use anyhow::{Context as _, Result, anyhow, bail};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use client::ExtensionProvides;
-use client::{Client, ExtensionMetadata, GetExtensionsResponse, proto, telemetry::Telemetry};
+use client::{
+    Client, ExtensionApiManifest, ExtensionMetadata, GetExtensionsResponse, TypedEnvelope, proto,
+    telemetry::Telemetry,
+};
 use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map};
 pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
-    ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy, ExtensionEvents,
-    ExtensionGrammarProxy, ExtensionHostProxy, ExtensionIndexedDocsProviderProxy,
-    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionSlashCommandProxy,
-    ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionCapability, ExtensionCapabilityProxy, ExtensionCodeActionProviderProxy,
+    ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy,
+    ExtensionEditPredictionProviderProxy, ExtensionEvents, ExtensionFormatterProxy,
+    ExtensionGitHostingProviderProxy, ExtensionGrammarProxy, ExtensionHostProxy,
+    ExtensionIndexedDocsProviderProxy, ExtensionLanguageModelProviderProxy, ExtensionLanguageProxy,
+    ExtensionLanguageServerProxy, ExtensionSlashCommandProxy, ExtensionSnippetProxy,
+    ExtensionTaskProviderProxy, ExtensionThemeProxy, ExtensionToolchainProviderProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -45,6 +51,7 @@ use remote::SshRemoteClient;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use sha2::{Digest as _, Sha256};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::{
@@ -652,7 +659,7 @@ impl ExtensionStore {
         query: &[(&str, &str)],
         cx: &mut Context<ExtensionStore>,
     ) -> Task<Result<Vec<ExtensionMetadata>>> {
-        let url = self.http_client.build_zed_api_url(path, query);
+        let url = self.build_registry_url(path, query, cx);
         let http_client = self.http_client.clone();
         cx.spawn(async move |_, _| {
             let mut response = http_client
@@ -679,6 +686,22 @@ impl ExtensionStore {
         })
     }
 
+    /// Builds a URL for the given path of the extension registry's API.
+    ///
+    /// This is zed.dev, unless a different registry is configured in the settings.
+    fn build_registry_url(&self, path: &str, query: &[(&str, &str)], cx: &App) -> Result<Url> {
+        match ExtensionSettings::get_global(cx)
+            .extension_registry_url
+            .as_deref()
+        {
+            Some(registry_url) => Ok(Url::parse_with_params(
+                &format!("{}{path}", registry_url.trim_end_matches('/')),
+                query,
+            )?),
+            None => self.http_client.build_zed_api_url(path, query),
+        }
+    }
+
     pub fn install_extension(
         &mut self,
         extension_id: Arc<str>,
@@ -692,6 +715,7 @@ impl ExtensionStore {
     fn install_or_upgrade_extension_at_endpoint(
         &mut self,
         extension_id: Arc<str>,
+        version: Option<Arc<str>>,
         url: Url,
         operation: ExtensionOperation,
         cx: &mut Context<Self>,
@@ -706,6 +730,9 @@ impl ExtensionStore {
         };
         cx.notify();
 
+        let published_versions = self.fetch_extension_versions(&extension_id, cx);
+        let allow_unverified = ExtensionSettings::get_global(cx).allow_unverified_extensions;
+
         cx.spawn(async move |this, cx| {
             let _finish = cx.on_drop(&this, {
                 let extension_id = extension_id.clone();
@@ -720,15 +747,6 @@ impl ExtensionStore {
                 .await
                 .context("downloading extension")?;
 
-            fs.remove_dir(
-                &extension_dir,
-                RemoveOptions {
-                    recursive: true,
-                    ignore_if_not_exists: true,
-                },
-            )
-            .await?;
-
             let content_length = response
                 .headers()
                 .get(http_client::http::header::CONTENT_LENGTH)
@@ -744,35 +762,116 @@ impl ExtensionStore {
                     bail!("downloaded extension size {actual_len} does not match content length {content_length}");
                 }
             }
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
-            let archive = Archive::new(decompressed_bytes);
-            archive.unpack(extension_dir).await?;
+
+            // Verify against the version that was actually downloaded, as installing
+            // without a version downloads whichever one is the latest.
+            let downloaded_version = manifest_from_archive(&tar_gz_bytes).await?.version;
+            if let Some(version) = &version {
+                anyhow::ensure!(
+                    downloaded_version == *version,
+                    "downloaded version {downloaded_version} of extension {extension_id} does not match the requested version {version}"
+                );
+            }
+            let published_versions = published_versions
+                .await
+                .context("fetching published extension versions to verify the download")?;
+            verify_extension_archive(
+                &extension_id,
+                &tar_gz_bytes,
+                &downloaded_version,
+                &published_versions,
+                allow_unverified,
+            )?;
+
+            unpack_extension_archive(fs.as_ref(), &tar_gz_bytes, &extension_dir).await?;
             this.update( cx, |this, cx| {
                 this.reload(Some(extension_id.clone()), cx)
             })?
             .await;
 
             if let ExtensionOperation::Install = operation {
-                this.update( cx, |this, cx| {
-                    cx.emit(Event::ExtensionInstalled(extension_id.clone()));
-                    if let Some(events) = ExtensionEvents::try_global(cx) {
-                        if let Some(manifest) = this.extension_manifest_for_id(&extension_id) {
-                            events.update(cx, |this, cx| {
-                                this.emit(
-                                    extension::Event::ExtensionInstalled(manifest.clone()),
-                                    cx,
-                                )
-                            });
-                        }
-                    }
-                })
-                .ok();
+                this.update(cx, |this, cx| this.emit_extension_installed(&extension_id, cx))
+                    .ok();
             }
 
             anyhow::Ok(())
         })
     }
 
+    /// Installs the extension in the given `archive.tar.gz`, as produced by the extension CLI.
+    ///
+    /// If the archive is next to a `manifest.json` that lists its checksum, the archive
+    /// is verified against it.
+    pub fn install_extension_from_archive(
+        &mut self,
+        archive_path: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let installed_dir = self.installed_dir.clone();
+        let fs = self.fs.clone();
+
+        cx.spawn(async move |this, cx| {
+            let tar_gz_bytes = fs
+                .load_bytes(&archive_path)
+                .await
+                .with_context(|| format!("failed to read extension archive {archive_path:?}"))?;
+
+            let registry_manifest_path = archive_path.with_file_name("manifest.json");
+            if fs.is_file(&registry_manifest_path).await {
+                let registry_manifest: ExtensionApiManifest =
+                    serde_json::from_str(&fs.load(&registry_manifest_path).await?)
+                        .with_context(|| format!("invalid {registry_manifest_path:?}"))?;
+                if let Some(expected_sha256) = registry_manifest.archive_sha256 {
+                    anyhow::ensure!(
+                        archive_sha256(&tar_gz_bytes).eq_ignore_ascii_case(&expected_sha256),
+                        "checksum of extension archive {archive_path:?} does not match {registry_manifest_path:?}"
+                    );
+                }
+            }
+
+            let manifest = manifest_from_archive(&tar_gz_bytes).await?;
+            let extension_id = manifest.id.clone();
+            validate_extension_id(&extension_id)?;
+            if !this.update(cx, |this, cx| {
+                match this.outstanding_operations.entry(extension_id.clone()) {
+                    btree_map::Entry::Occupied(_) => return false,
+                    btree_map::Entry::Vacant(e) => e.insert(ExtensionOperation::Install),
+                };
+                cx.notify();
+                true
+            })? {
+                return Ok(());
+            }
+
+            let _finish = cx.on_drop(&this, {
+                let extension_id = extension_id.clone();
+                move |this, cx| {
+                    this.outstanding_operations.remove(extension_id.as_ref());
+                    cx.notify();
+                }
+            });
+
+            let extension_dir = installed_dir.join(extension_id.as_ref());
+            unpack_extension_archive(fs.as_ref(), &tar_gz_bytes, &extension_dir).await?;
+            this.update(cx, |this, cx| this.reload(Some(extension_id.clone()), cx))?
+                .await;
+            this.update(cx, |this, cx| this.emit_extension_installed(&extension_id, cx))?;
+
+            Ok(())
+        })
+    }
+
+    fn emit_extension_installed(&self, extension_id: &Arc<str>, cx: &mut Context<Self>) {
+        cx.emit(Event::ExtensionInstalled(extension_id.clone()));
+        if let Some(events) = ExtensionEvents::try_global(cx) {
+            if let Some(manifest) = self.extension_manifest_for_id(extension_id) {
+                events.update(cx, |this, cx| {
+                    this.emit(extension::Event::ExtensionInstalled(manifest.clone()), cx)
+                });
+            }
+        }
+    }
+
     pub fn install_latest_extension(&mut self, extension_id: Arc<str>, cx: &mut Context<Self>) {
         log::info!("installing extension {extension_id} latest version");
 
@@ -780,8 +879,7 @@ impl ExtensionStore {
         let wasm_api_versions = wasm_api_version_range(ReleaseChannel::global(cx));
 
         let Some(url) = self
-            .http_client
-            .build_zed_api_url(
+            .build_registry_url(
                 &format!("/extensions/{extension_id}/download"),
                 &[
                     ("min_schema_version", &schema_versions.start().to_string()),
@@ -792,6 +890,7 @@ impl ExtensionStore {
                     ),
                     ("max_wasm_api_version", &wasm_api_versions.end().to_string()),
                 ],
+                cx,
             )
             .log_err()
         else {
@@ -800,6 +899,7 @@ impl ExtensionStore {
 
         self.install_or_upgrade_extension_at_endpoint(
             extension_id,
+            None,
             url,
             ExtensionOperation::Install,
             cx,
@@ -825,17 +925,23 @@ impl ExtensionStore {
     ) -> Task<Result<()>> {
         log::info!("installing extension {extension_id} {version}");
         let Some(url) = self
-            .http_client
-            .build_zed_api_url(
+            .build_registry_url(
                 &format!("/extensions/{extension_id}/{version}/download"),
                 &[],
+                cx,
             )
             .log_err()
         else {
             return Task::ready(Ok(()));
         };
 
-        self.install_or_upgrade_extension_at_endpoint(extension_id, url, operation, cx)
+        self.install_or_upgrade_extension_at_endpoint(
+            extension_id,
+            Some(version),
+            url,
+            operation,
+            cx,
+        )
     }
 
     pub fn uninstall_extension(
@@ -1158,6 +1264,46 @@ impl ExtensionStore {
             for (locator, _) in extension.manifest.debug_locators.iter() {
                 self.proxy.unregister_debug_locator(locator.clone());
             }
+            for (provider_id, _) in extension.manifest.language_model_providers.iter() {
+                self.proxy.unregister_language_model_provider(
+                    extension_id.clone(),
+                    provider_id.clone(),
+                    cx,
+                );
+            }
+            for (formatter_id, _) in extension.manifest.formatters.iter() {
+                self.proxy.unregister_formatter(formatter_id.clone());
+            }
+            for (provider_id, _) in extension.manifest.code_action_providers.iter() {
+                self.proxy
+                    .unregister_code_action_provider(provider_id.clone());
+            }
+            for (provider_id, provider) in extension.manifest.task_providers.iter() {
+                for language in &provider.languages {
+                    self.proxy.remove_task_provider(
+                        extension_id.clone(),
+                        provider_id.clone(),
+                        language,
+                    );
+                }
+            }
+            for (provider_id, provider) in extension.manifest.toolchain_providers.iter() {
+                for language in &provider.languages {
+                    self.proxy.remove_toolchain_provider(
+                        extension_id.clone(),
+                        provider_id.clone(),
+                        language,
+                    );
+                }
+            }
+            for (provider_id, _) in extension.manifest.edit_prediction_providers.iter() {
+                self.proxy
+                    .unregister_edit_prediction_provider(provider_id.clone());
+            }
+            for (provider_id, _) in extension.manifest.git_hosting_providers.iter() {
+                self.proxy
+                    .unregister_git_hosting_provider(provider_id.clone());
+            }
         }
 
         self.wasm_extensions
@@ -1374,6 +1520,65 @@ impl ExtensionStore {
                         this.proxy
                             .register_debug_locator(extension.clone(), debug_adapter.clone());
                     }
+
+                    for (provider_id, provider) in &manifest.language_model_providers {
+                        this.proxy.register_language_model_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.clone(),
+                            cx,
+                        );
+                    }
+
+                    for formatter_id in manifest.formatters.keys() {
+                        this.proxy
+                            .register_formatter(extension.clone(), formatter_id.clone());
+                    }
+
+                    for (provider_id, provider) in &manifest.code_action_providers {
+                        this.proxy.register_code_action_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.languages.clone(),
+                        );
+                    }
+
+                    for (provider_id, provider) in &manifest.task_providers {
+                        for language in &provider.languages {
+                            this.proxy.register_task_provider(
+                                extension.clone(),
+                                provider_id.clone(),
+                                language.clone(),
+                            );
+                        }
+                    }
+
+                    for (provider_id, provider) in &manifest.toolchain_providers {
+                        for language in &provider.languages {
+                            this.proxy.register_toolchain_provider(
+                                extension.clone(),
+                                provider_id.clone(),
+                                provider.clone(),
+                                language.clone(),
+                            );
+                        }
+                    }
+
+                    for (provider_id, provider) in &manifest.edit_prediction_providers {
+                        this.proxy.register_edit_prediction_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.clone(),
+                        );
+                    }
+
+                    for (provider_id, provider) in &manifest.git_hosting_providers {
+                        this.proxy.register_git_hosting_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.clone(),
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -1730,9 +1935,144 @@ impl ExtensionStore {
             }
         }
 
+        client
+            .read(cx)
+            .proto_client()
+            .add_request_handler(cx.weak_entity(), Self::handle_request_capability_consent);
         self.ssh_clients.insert(ssh_url, client.downgrade());
         self.ssh_registered_tx.unbounded_send(()).ok();
     }
+
+    /// Asks the user for their consent to a capability used by an extension on a remote
+    /// server, unless they already made a decision about it on this machine.
+    async fn handle_request_capability_consent(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::RequestExtensionCapabilityConsent>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RequestExtensionCapabilityConsentResponse> {
+        let capability: ExtensionCapability =
+            serde_json::from_str(&envelope.payload.capability_json)
+                .context("invalid extension capability")?;
+        let extension_id: Arc<str> = envelope.payload.extension_id.into();
+        let granted = this
+            .update(&mut cx, |this, cx| {
+                if let Some(granted) = ExtensionSettings::get_global(cx)
+                    .capability_decision(&extension_id, &capability)
+                {
+                    return Task::ready(Ok(granted));
+                }
+                this.proxy.request_capability_consent(
+                    extension_id,
+                    envelope.payload.extension_name.into(),
+                    capability,
+                    cx,
+                )
+            })?
+            .await?;
+        Ok(proto::RequestExtensionCapabilityConsentResponse { granted })
+    }
+}
+
+/// Returns the hex-encoded SHA-256 checksum of an extension archive.
+fn archive_sha256(tar_gz_bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(tar_gz_bytes))
+}
+
+/// Verifies that a downloaded extension archive matches the checksum published by the
+/// registry for the given version.
+///
+/// Versions without a published checksum are rejected, unless unverified extensions
+/// are allowed in the settings.
+fn verify_extension_archive(
+    extension_id: &str,
+    tar_gz_bytes: &[u8],
+    version: &str,
+    published_versions: &[ExtensionMetadata],
+    allow_unverified: bool,
+) -> Result<()> {
+    let Some(expected_checksum) = published_versions
+        .iter()
+        .find(|extension| *extension.manifest.version == *version)
+        .and_then(|extension| extension.manifest.archive_sha256.as_deref())
+    else {
+        anyhow::ensure!(
+            allow_unverified,
+            "the registry does not publish a checksum for version {version} of extension {extension_id}, \
+            so it can only be installed with `allow_unverified_extensions` enabled"
+        );
+        log::warn!(
+            "installing extension {extension_id} {version} without verifying it, as the registry does not publish a checksum for it"
+        );
+        return Ok(());
+    };
+
+    let actual_checksum = archive_sha256(tar_gz_bytes);
+    anyhow::ensure!(
+        expected_checksum.eq_ignore_ascii_case(&actual_checksum),
+        "checksum of downloaded extension archive {actual_checksum} does not match the registry"
+    );
+    Ok(())
+}
+
+/// Ensures that an extension ID from an untrusted archive only uses the characters
+/// allowed by the registry, so that it names a directory within the installed
+/// extensions directory.
+fn validate_extension_id(extension_id: &str) -> Result<()> {
+    let mut components = Path::new(extension_id).components();
+    let is_single_component = matches!(
+        (components.next(), components.next()),
+        (Some(path::Component::Normal(_)), None)
+    );
+    anyhow::ensure!(
+        is_single_component
+            && extension_id.chars().all(|char| {
+                char.is_ascii_lowercase() || char.is_ascii_digit() || char == '-' || char == '_'
+            }),
+        "invalid extension ID {extension_id:?}: must only contain lowercase letters, digits, hyphens and underscores"
+    );
+    Ok(())
+}
+
+/// Reads the manifest of the extension in the given archive.
+async fn manifest_from_archive(tar_gz_bytes: &[u8]) -> Result<ExtensionManifest> {
+    let archive = Archive::new(GzipDecoder::new(BufReader::new(tar_gz_bytes)));
+    let mut entries = archive.entries()?;
+    while let Some(entry) = entries.next().await {
+        let mut entry = entry?;
+        let is_manifest = {
+            let path = entry.path()?;
+            path.strip_prefix(".").unwrap_or(path.as_ref()) == Path::new("extension.toml")
+        };
+        if !is_manifest {
+            continue;
+        }
+
+        let mut manifest_content = String::new();
+        entry.read_to_string(&mut manifest_content).await?;
+        return toml::from_str(&manifest_content).context("invalid extension.toml in archive");
+    }
+
+    bail!("extension archive does not contain an extension.toml")
+}
+
+/// Replaces the contents of the extension directory with the given archive.
+async fn unpack_extension_archive(
+    fs: &dyn Fs,
+    tar_gz_bytes: &[u8],
+    extension_dir: &Path,
+) -> Result<()> {
+    fs.remove_dir(
+        extension_dir,
+        RemoveOptions {
+            recursive: true,
+            ignore_if_not_exists: true,
+        },
+    )
+    .await?;
+
+    let archive = Archive::new(GzipDecoder::new(BufReader::new(tar_gz_bytes)));
+    archive.unpack(extension_dir).await?;
+    Ok(())
 }
 
 fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use collections::HashMap;
+use extension::ExtensionCapability;
 use gpui::App;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -18,6 +19,28 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     #[serde(default)]
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    /// The decisions made about the capabilities used by extensions, keyed by
+    /// extension ID and then by capability.
+    ///
+    /// The user is prompted the first time an extension uses a capability
+    /// without a decision.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub extension_capabilities: HashMap<Arc<str>, HashMap<String, bool>>,
+    /// The URL of the registry to install and update extensions from, instead of zed.dev.
+    ///
+    /// The registry must implement the same API as zed.dev's extension API.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub extension_registry_url: Option<String>,
+    /// Whether to install extension versions that the registry doesn't publish a
+    /// checksum for, without verifying the downloaded archive.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub allow_unverified_extensions: bool,
 }
 
 impl ExtensionSettings {
@@ -35,6 +58,19 @@ impl ExtensionSettings {
             .copied()
             .unwrap_or(true)
     }
+
+    /// Returns whether the given capability was granted to the extension, or `None`
+    /// if no decision has been made yet.
+    pub fn capability_decision(
+        &self,
+        extension_id: &str,
+        capability: &ExtensionCapability,
+    ) -> Option<bool> {
+        self.extension_capabilities
+            .get(extension_id)?
+            .get(&capability.to_string())
+            .copied()
+    }
 }
 
 impl Settings for ExtensionSettings {
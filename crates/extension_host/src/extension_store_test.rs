@@ -1,14 +1,17 @@
 use crate::{
     Event, ExtensionIndex, ExtensionIndexEntry, ExtensionIndexLanguageEntry,
     ExtensionIndexThemeEntry, ExtensionManifest, ExtensionSettings, ExtensionStore,
-    GrammarManifestEntry, RELOAD_DEBOUNCE_DURATION, SchemaVersion,
+    GrammarManifestEntry, RELOAD_DEBOUNCE_DURATION, SchemaVersion, archive_sha256,
+    verify_extension_archive, wasm_host::WasmHost,
 };
+use anyhow::{Result, anyhow};
 use async_compression::futures::bufread::GzipEncoder;
+use client::ExtensionMetadata;
 use collections::{BTreeMap, HashSet};
-use extension::ExtensionHostProxy;
+use extension::{ExtensionCapability, ExtensionCapabilityProxy, ExtensionHostProxy};
 use fs::{FakeFs, Fs, RealFs};
 use futures::{AsyncReadExt, StreamExt, io::BufReader};
-use gpui::{AppContext as _, SemanticVersion, TestAppContext};
+use gpui::{App, AppContext as _, SemanticVersion, SharedString, Task, TestAppContext};
 use http_client::{FakeHttpClient, Response};
 use language::{BinaryStatus, LanguageMatcher, LanguageRegistry};
 use lsp::LanguageServerName;
@@ -20,6 +23,7 @@ use reqwest_client::ReqwestClient;
 use serde_json::json;
 use settings::{Settings as _, SettingsStore};
 use std::{
+    collections::VecDeque,
     ffi::OsString,
     path::{Path, PathBuf},
     sync::Arc,
@@ -164,6 +168,13 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        language_model_providers: Default::default(),
+                        formatters: Default::default(),
+                        code_action_providers: Default::default(),
+                        task_providers: Default::default(),
+                        toolchain_providers: Default::default(),
+                        edit_prediction_providers: Default::default(),
+                        git_hosting_providers: Default::default(),
                     }),
                     dev: false,
                 },
@@ -195,6 +206,13 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        language_model_providers: Default::default(),
+                        formatters: Default::default(),
+                        code_action_providers: Default::default(),
+                        task_providers: Default::default(),
+                        toolchain_providers: Default::default(),
+                        edit_prediction_providers: Default::default(),
+                        git_hosting_providers: Default::default(),
                     }),
                     dev: false,
                 },
@@ -371,6 +389,13 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
                 debug_locators: Default::default(),
+                language_model_providers: Default::default(),
+                formatters: Default::default(),
+                code_action_providers: Default::default(),
+                task_providers: Default::default(),
+                toolchain_providers: Default::default(),
+                edit_prediction_providers: Default::default(),
+                git_hosting_providers: Default::default(),
             }),
             dev: false,
         },
@@ -551,6 +576,7 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     let project = Project::test(fs.clone(), [project_dir.as_path()], cx).await;
 
     let proxy = Arc::new(ExtensionHostProxy::new());
+    proxy.register_capability_proxy(FakeCapabilityConsent::default());
     let theme_registry = Arc::new(ThemeRegistry::new(Box::new(())));
     theme_extension::init(proxy.clone(), theme_registry.clone(), cx.executor());
     let language_registry = project.read_with(cx, |project, _cx| project.languages().clone());
@@ -837,6 +863,281 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     assert!(fs.metadata(&expected_server_path).await.unwrap().is_none());
 }
 
+#[test]
+fn test_verify_extension_archive() {
+    let archive = b"archive contents";
+    let other_archive = b"other archive contents";
+    let extension_version = |version: &str, archive_sha256: Option<String>| {
+        serde_json::from_value::<ExtensionMetadata>(json!({
+            "id": "test",
+            "name": "Test",
+            "version": version,
+            "description": null,
+            "authors": [],
+            "repository": "https://github.com/zed-industries/zed",
+            "schema_version": 1,
+            "wasm_api_version": null,
+            "archive_sha256": archive_sha256,
+            "published_at": "2025-01-01T00:00:00Z",
+            "download_count": 0,
+        }))
+        .unwrap()
+    };
+    let published_versions = [
+        extension_version("1.0.0", Some(archive_sha256(archive))),
+        extension_version("2.0.0", Some(archive_sha256(other_archive))),
+    ];
+
+    let verify = |archive: &[u8], version, published_versions: &[_], allow_unverified| {
+        verify_extension_archive(
+            "test",
+            archive,
+            version,
+            published_versions,
+            allow_unverified,
+        )
+    };
+    assert!(verify(archive, "1.0.0", &published_versions, false).is_ok());
+    assert!(verify(archive, "2.0.0", &published_versions, false).is_err());
+    assert!(verify(b"tampered", "1.0.0", &published_versions, false).is_err());
+    assert!(verify(b"tampered", "1.0.0", &published_versions, true).is_err());
+
+    // Versions without a published checksum are only installed when explicitly allowed.
+    let published_versions = [
+        extension_version("1.0.0", None),
+        extension_version("2.0.0", Some(archive_sha256(other_archive))),
+    ];
+    assert!(verify(b"tampered", "1.0.0", &published_versions, false).is_err());
+    assert!(verify(b"tampered", "3.0.0", &published_versions, false).is_err());
+    assert!(verify(b"tampered", "1.0.0", &published_versions, true).is_ok());
+    assert!(verify(b"tampered", "3.0.0", &published_versions, true).is_ok());
+}
+
+#[gpui::test]
+async fn test_install_extension_from_archive(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let extensions_dir = TempTree::new(json!({
+        "installed": {},
+        "archives": {}
+    }));
+    let extensions_dir = extensions_dir.path().canonicalize().unwrap();
+    let archive_path = extensions_dir.join("archives").join("extension.tar.gz");
+
+    let fs = Arc::new(RealFs::new(None, cx.executor()));
+    let http_client = FakeHttpClient::with_404_response();
+    let store = cx.new(|cx| {
+        ExtensionStore::new(
+            extensions_dir.clone(),
+            None,
+            Arc::new(ExtensionHostProxy::new()),
+            fs,
+            http_client.clone(),
+            http_client,
+            None,
+            NodeRuntime::unavailable(),
+            cx,
+        )
+    });
+
+    // Ensure that debounces fire.
+    let mut events = cx.events(&store);
+    let executor = cx.executor();
+    let _task = cx.executor().spawn(async move {
+        while let Some(event) = events.next().await {
+            if let Event::StartedReloading = event {
+                executor.advance_clock(RELOAD_DEBOUNCE_DURATION);
+            }
+        }
+    });
+
+    // Extension IDs that don't name a directory within the installed extensions
+    // directory are rejected before anything is removed or unpacked.
+    for extension_id in [
+        "..",
+        "../archives",
+        "/tmp",
+        "",
+        "nested/extension",
+        "Uppercase",
+    ] {
+        std::fs::write(&archive_path, extension_archive(extension_id).await).unwrap();
+        let result = store
+            .update(cx, |store, cx| {
+                store.install_extension_from_archive(archive_path.clone(), cx)
+            })
+            .await;
+        assert!(
+            result.is_err(),
+            "installed extension with ID {extension_id:?}"
+        );
+        assert!(archive_path.exists());
+        assert!(
+            extensions_dir
+                .join("installed")
+                .read_dir()
+                .unwrap()
+                .next()
+                .is_none()
+        );
+    }
+
+    std::fs::write(&archive_path, extension_archive("my-extension").await).unwrap();
+    store
+        .update(cx, |store, cx| {
+            store.install_extension_from_archive(archive_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert!(
+        extensions_dir
+            .join("installed/my-extension/extension.toml")
+            .exists()
+    );
+    store.read_with(cx, |store, _| {
+        assert!(store.installed_extensions().contains_key("my-extension"));
+        assert!(store.outstanding_operations().is_empty());
+    });
+}
+
+/// Returns a `.tar.gz` archive of an extension with the given ID.
+async fn extension_archive(extension_id: &str) -> Vec<u8> {
+    let extension_toml = format!(
+        "id = \"{extension_id}\"\nname = \"My Extension\"\nversion = \"1.0.0\"\nschema_version = 1\n"
+    );
+    let mut bytes = Vec::<u8>::new();
+    let mut archive = async_tar::Builder::new(&mut bytes);
+    let mut header = async_tar::Header::new_gnu();
+    header.set_size(extension_toml.len() as u64);
+    header.set_mode(0o644);
+    archive
+        .append_data(&mut header, "extension.toml", extension_toml.as_bytes())
+        .await
+        .unwrap();
+    archive.into_inner().await.unwrap();
+    let mut gzipped_bytes = Vec::new();
+    let mut encoder = GzipEncoder::new(BufReader::new(bytes.as_slice()));
+    encoder.read_to_end(&mut gzipped_bytes).await.unwrap();
+    gzipped_bytes
+}
+
+#[gpui::test]
+async fn test_capability_consent(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let consent = FakeCapabilityConsent::default();
+    let proxy = Arc::new(ExtensionHostProxy::new());
+    proxy.register_capability_proxy(consent.clone());
+    let fs = FakeFs::new(cx.executor());
+    let host = cx.update(|cx| {
+        WasmHost::new(
+            fs.clone(),
+            FakeHttpClient::with_404_response(),
+            NodeRuntime::unavailable(),
+            proxy,
+            PathBuf::from("/work"),
+            cx,
+        )
+    });
+
+    let manifest: ExtensionManifest = toml::from_str(
+        r#"
+        id = "fetcher"
+        name = "Fetcher"
+        version = "1.0.0"
+        schema_version = 1
+
+        [[capabilities]]
+        kind = "network:fetch"
+        host = "example.com"
+        "#,
+    )
+    .unwrap();
+    let capability = manifest.capabilities[0].clone();
+    let request = |host: &Arc<WasmHost>, cx: &mut TestAppContext| {
+        cx.update(|cx| host.request_capability(&manifest, capability.clone(), cx))
+    };
+
+    // Failing to ask for consent isn't remembered as a decision.
+    consent
+        .answers
+        .lock()
+        .push_back(Err(anyhow!("failed to show the prompt")));
+    assert!(request(&host, cx).await.is_err());
+    assert_eq!(consent.requests.lock().len(), 1);
+
+    // Concurrent requests share a single prompt, and the decision is remembered.
+    consent.answers.lock().push_back(Ok(true));
+    let first_request = request(&host, cx);
+    let second_request = request(&host, cx);
+    assert!(first_request.await.unwrap());
+    assert!(second_request.await.unwrap());
+    assert!(request(&host, cx).await.unwrap());
+    assert_eq!(
+        *consent.requests.lock(),
+        vec!["network:fetch example.com"; 2]
+    );
+
+    // Decisions in the settings are used without asking for consent.
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ExtensionSettings>(cx, |settings| {
+                settings.extension_capabilities.insert(
+                    "fetcher".into(),
+                    [("network:fetch example.com".to_string(), false)]
+                        .into_iter()
+                        .collect(),
+                );
+            });
+        });
+    });
+    assert!(!request(&host, cx).await.unwrap());
+    assert_eq!(consent.requests.lock().len(), 2);
+
+    // Capabilities can't be granted without a way to ask for consent.
+    let headless_host = cx.update(|cx| {
+        WasmHost::new(
+            fs.clone(),
+            FakeHttpClient::with_404_response(),
+            NodeRuntime::unavailable(),
+            Arc::new(ExtensionHostProxy::new()),
+            PathBuf::from("/work"),
+            cx,
+        )
+    });
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ExtensionSettings>(cx, |settings| {
+                settings.extension_capabilities.clear();
+            });
+        });
+    });
+    let error = request(&headless_host, cx).await.unwrap_err();
+    assert!(error.to_string().contains("extension_capabilities"));
+}
+
+/// Answers requests for consent to capabilities with the queued answers, granting
+/// the capabilities once the queue is empty.
+#[derive(Clone, Default)]
+struct FakeCapabilityConsent {
+    answers: Arc<Mutex<VecDeque<Result<bool>>>>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl ExtensionCapabilityProxy for FakeCapabilityConsent {
+    fn request_capability_consent(
+        &self,
+        _extension_id: Arc<str>,
+        _extension_name: SharedString,
+        capability: ExtensionCapability,
+        _cx: &mut App,
+    ) -> Task<Result<bool>> {
+        self.requests.lock().push(capability.to_string());
+        Task::ready(self.answers.lock().pop_front().unwrap_or(Ok(true)))
+    }
+}
+
 fn init_test(cx: &mut TestAppContext) {
     cx.update(|cx| {
         let store = SettingsStore::test(cx);
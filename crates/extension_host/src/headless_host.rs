@@ -1,14 +1,14 @@
 use std::{path::PathBuf, sync::Arc};
 
 use anyhow::{Context as _, Result};
-use client::{TypedEnvelope, proto};
+use client::{AnyProtoClient, TypedEnvelope, proto};
 use collections::{HashMap, HashSet};
 use extension::{
-    Extension, ExtensionHostProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionManifest,
+    Extension, ExtensionCapability, ExtensionCapabilityProxy, ExtensionHostProxy,
+    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionManifest,
 };
 use fs::{Fs, RemoveOptions, RenameOptions};
-use gpui::{App, AppContext as _, AsyncApp, Context, Entity, Task, WeakEntity};
+use gpui::{App, AppContext as _, AsyncApp, Context, Entity, SharedString, Task, WeakEntity};
 use http_client::HttpClient;
 use language::{LanguageConfig, LanguageName, LanguageQueries, LoadedLanguage};
 use lsp::LanguageServerName;
@@ -314,3 +314,38 @@ impl HeadlessExtensionStore {
         Ok(proto::Ack {})
     }
 }
+
+/// Asks the user of the connected client for their consent to capabilities, as
+/// the remote server has no way to prompt them itself.
+pub struct RemoteCapabilityConsent {
+    client: AnyProtoClient,
+}
+
+impl RemoteCapabilityConsent {
+    pub fn new(client: AnyProtoClient) -> Self {
+        Self { client }
+    }
+}
+
+impl ExtensionCapabilityProxy for RemoteCapabilityConsent {
+    fn request_capability_consent(
+        &self,
+        extension_id: Arc<str>,
+        extension_name: SharedString,
+        capability: ExtensionCapability,
+        cx: &mut App,
+    ) -> Task<Result<bool>> {
+        let client = self.client.clone();
+        cx.background_spawn(async move {
+            let response = client
+                .request(proto::RequestExtensionCapabilityConsent {
+                    extension_id: extension_id.to_string(),
+                    extension_name: extension_name.to_string(),
+                    capability_json: serde_json::to_string(&capability)?,
+                })
+                .await
+                .with_context(|| format!("failed to ask the client for consent to {capability}"))?;
+            Ok(response.granted)
+        })
+    }
+}
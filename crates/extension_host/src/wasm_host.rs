@@ -1,13 +1,18 @@
 pub mod wit;
 
-use crate::ExtensionManifest;
+use crate::{ExtensionManifest, ExtensionSettings};
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
+use collections::HashMap;
 use dap::{DebugRequest, StartDebuggingRequestArgumentsRequest};
 use extension::{
-    CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
-    DebugTaskDefinition, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate, SlashCommand,
-    SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
+    CodeActionRequest, CodeLabel, Command, Completion, ContextServerConfiguration,
+    DebugAdapterBinary, DebugTaskDefinition, EditPrediction, EditPredictionRequest, EnvVars,
+    ExtensionCapability, ExtensionCapabilityProxy as _, ExtensionCodeAction, ExtensionHostProxy,
+    FormatRequest, FormatResult, GitHostingPermalinkParams, GitHostingPullRequest,
+    GitHostingRemote, KeyValueStoreDelegate, LlmCompletionEvent, LlmCompletionRequest, LlmModel,
+    ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol,
+    TaskLocation, TaskTemplate, ToolchainList, WorktreeDelegate,
 };
 use fs::{Fs, normalize_path};
 use futures::future::LocalBoxFuture;
@@ -17,7 +22,7 @@ use futures::{
         mpsc::{self, UnboundedSender},
         oneshot,
     },
-    future::BoxFuture,
+    future::{BoxFuture, Shared},
 };
 use gpui::{App, AsyncApp, BackgroundExecutor, Task, Timer};
 use http_client::HttpClient;
@@ -25,8 +30,10 @@ use language::LanguageName;
 use lsp::LanguageServerName;
 use moka::sync::Cache;
 use node_runtime::NodeRuntime;
+use parking_lot::Mutex;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
+use settings::Settings as _;
 use std::borrow::Cow;
 use std::sync::{LazyLock, OnceLock};
 use std::time::Duration;
@@ -49,11 +56,52 @@ pub struct WasmHost {
     node_runtime: NodeRuntime,
     pub(crate) proxy: Arc<ExtensionHostProxy>,
     fs: Arc<dyn Fs>,
+    command_runner: Arc<dyn CommandRunner>,
+    /// The pending requests for the user's consent to capabilities and the user's
+    /// decisions, keyed by extension ID and capability.
+    capability_requests: Mutex<HashMap<(Arc<str>, String), CapabilityRequest>>,
     pub work_dir: PathBuf,
     _main_thread_message_task: Task<()>,
     main_thread_message_tx: mpsc::UnboundedSender<MainThreadCall>,
 }
 
+type CapabilityRequest = Shared<Task<Result<bool, Arc<anyhow::Error>>>>;
+
+/// The output of a command run on behalf of an extension.
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    /// The exit code of the command, if it exited normally.
+    pub status: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// Runs the commands that extensions execute through the `process` interface.
+#[async_trait]
+pub trait CommandRunner: Send + Sync + 'static {
+    async fn run_command(&self, command: Command) -> Result<CommandOutput>;
+}
+
+/// A [`CommandRunner`] that runs commands as processes on the host.
+struct SystemCommandRunner;
+
+#[async_trait]
+impl CommandRunner for SystemCommandRunner {
+    async fn run_command(&self, command: Command) -> Result<CommandOutput> {
+        let output = util::command::new_smol_command(command.command.as_str())
+            .args(&command.args)
+            .envs(command.env)
+            .output()
+            .await?;
+
+        Ok(CommandOutput {
+            status: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct WasmExtension {
     tx: UnboundedSender<ExtensionCall>,
@@ -473,6 +521,328 @@ impl extension::Extension for WasmExtension {
         })
         .await
     }
+
+    async fn llm_provider_models(&self, provider_id: Arc<str>) -> Result<Vec<LlmModel>> {
+        self.call(|extension, store| {
+            async move {
+                let models = extension
+                    .call_llm_provider_models(store, &provider_id)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(models.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+        api_key: Option<String>,
+    ) -> Result<u64> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_llm_stream_completion(
+                        store,
+                        &provider_id,
+                        &model_id,
+                        request.into(),
+                        api_key.as_deref(),
+                    )
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_next_completion_events(
+        &self,
+        stream_id: u64,
+    ) -> Result<Option<Vec<LlmCompletionEvent>>> {
+        self.call(|extension, store| {
+            async move {
+                let events = extension
+                    .call_llm_next_completion_events(store, stream_id)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(events.map(|events| events.into_iter().map(Into::into).collect()))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_drop_completion_stream(&self, stream_id: u64) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_llm_drop_completion_stream(store, stream_id)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn format_buffer(
+        &self,
+        formatter_id: Arc<str>,
+        request: FormatRequest,
+    ) -> Result<FormatResult> {
+        self.call(|extension, store| {
+            async move {
+                let result = extension
+                    .call_format_buffer(store, &formatter_id, request.into())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(result.into())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn code_actions(
+        &self,
+        provider_id: Arc<str>,
+        request: CodeActionRequest,
+    ) -> Result<Vec<ExtensionCodeAction>> {
+        self.call(|extension, store| {
+            async move {
+                let actions = extension
+                    .call_code_actions(store, &provider_id, request.into())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(actions.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn task_templates(
+        &self,
+        provider_id: Arc<str>,
+        path: Option<String>,
+    ) -> Result<Vec<TaskTemplate>> {
+        self.call(|extension, store| {
+            async move {
+                let templates = extension
+                    .call_task_templates(store, &provider_id, path.as_deref())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(templates.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn task_variables(
+        &self,
+        provider_id: Arc<str>,
+        variables: EnvVars,
+        location: TaskLocation,
+    ) -> Result<EnvVars> {
+        self.call(|extension, store| {
+            async move {
+                let variables = extension
+                    .call_task_variables(store, &provider_id, &variables, location.into())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(variables)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn list_toolchains(
+        &self,
+        provider_id: Arc<str>,
+        worktree_root: String,
+        subroot_relative_path: Option<String>,
+        env: EnvVars,
+    ) -> Result<ToolchainList> {
+        self.call(|extension, store| {
+            async move {
+                let toolchains = extension
+                    .call_list_toolchains(
+                        store,
+                        &provider_id,
+                        &worktree_root,
+                        subroot_relative_path.as_deref(),
+                        &env,
+                    )
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(toolchains.into())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn predict_edits(
+        &self,
+        provider_id: Arc<str>,
+        request: EditPredictionRequest,
+    ) -> Result<Option<EditPrediction>> {
+        self.call(|extension, store| {
+            async move {
+                let prediction = extension
+                    .call_predict_edits(store, &provider_id, request.into())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(prediction.map(Into::into))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn accept_edit_prediction(
+        &self,
+        provider_id: Arc<str>,
+        prediction_id: String,
+    ) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_accept_edit_prediction(store, &provider_id, &prediction_id)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn reject_edit_prediction(
+        &self,
+        provider_id: Arc<str>,
+        prediction_id: String,
+    ) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_reject_edit_prediction(store, &provider_id, &prediction_id)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn parse_git_remote_url(
+        &self,
+        provider_id: Arc<str>,
+        url: String,
+    ) -> Result<Option<GitHostingRemote>> {
+        self.call(|extension, store| {
+            async move {
+                let remote = extension
+                    .call_parse_git_remote_url(store, &provider_id, &url)
+                    .await?;
+
+                Ok(remote.map(Into::into))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn build_git_commit_permalink(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        sha: String,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                let permalink = extension
+                    .call_build_git_commit_permalink(store, &provider_id, remote.into(), &sha)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(permalink)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn build_git_permalink(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        params: GitHostingPermalinkParams,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                let permalink = extension
+                    .call_build_git_permalink(store, &provider_id, remote.into(), params.into())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(permalink)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn extract_git_pull_request(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        message: String,
+    ) -> Result<Option<GitHostingPullRequest>> {
+        self.call(|extension, store| {
+            async move {
+                let pull_request = extension
+                    .call_extract_git_pull_request(store, &provider_id, remote.into(), &message)
+                    .await?;
+
+                Ok(pull_request.map(Into::into))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn git_commit_author_avatar_url(
+        &self,
+        provider_id: Arc<str>,
+        remote: GitHostingRemote,
+        sha: String,
+    ) -> Result<Option<String>> {
+        self.call(|extension, store| {
+            async move {
+                let avatar_url = extension
+                    .call_git_commit_author_avatar_url(store, &provider_id, remote.into(), &sha)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(avatar_url)
+            }
+            .boxed()
+        })
+        .await
+    }
 }
 
 pub struct WasmState {
@@ -551,6 +921,30 @@ impl WasmHost {
         work_dir: PathBuf,
         cx: &mut App,
     ) -> Arc<Self> {
+        Self::with_command_runner(
+            fs,
+            http_client,
+            node_runtime,
+            proxy,
+            work_dir,
+            Arc::new(SystemCommandRunner),
+            cx,
+        )
+    }
+
+    /// Returns a new [`WasmHost`] that runs the commands executed by extensions
+    /// with the given [`CommandRunner`].
+    pub fn with_command_runner(
+        fs: Arc<dyn Fs>,
+        http_client: Arc<dyn HttpClient>,
+        node_runtime: NodeRuntime,
+        proxy: Arc<ExtensionHostProxy>,
+        work_dir: PathBuf,
+        command_runner: Arc<dyn CommandRunner>,
+        cx: &mut App,
+    ) -> Arc<Self> {
+        ExtensionSettings::register(cx);
+
         let (tx, mut rx) = mpsc::unbounded::<MainThreadCall>();
         let task = cx.spawn(async move |cx| {
             while let Some(message) = rx.next().await {
@@ -560,6 +954,8 @@ impl WasmHost {
         Arc::new(Self {
             engine: wasm_engine(cx.background_executor()),
             fs,
+            command_runner,
+            capability_requests: Mutex::default(),
             work_dir,
             http_client,
             node_runtime,
@@ -653,6 +1049,48 @@ impl WasmHost {
             .build())
     }
 
+    /// Returns whether the user granted the capability to the extension, asking
+    /// for their consent if they haven't made a decision about it yet.
+    pub(crate) fn request_capability(
+        self: &Arc<Self>,
+        manifest: &ExtensionManifest,
+        capability: ExtensionCapability,
+        cx: &mut App,
+    ) -> CapabilityRequest {
+        if let Some(granted) =
+            ExtensionSettings::get_global(cx).capability_decision(&manifest.id, &capability)
+        {
+            return Task::ready(Ok(granted)).shared();
+        }
+
+        let key = (manifest.id.clone(), capability.to_string());
+        self.capability_requests
+            .lock()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let consent = self.proxy.request_capability_consent(
+                    manifest.id.clone(),
+                    manifest.name.clone().into(),
+                    capability,
+                    cx,
+                );
+                let this = Arc::downgrade(self);
+                cx.background_spawn(async move {
+                    let consent = consent.await;
+                    if consent.is_err() {
+                        // Only the user's decisions are remembered, so the next use of the
+                        // capability asks for their consent again.
+                        if let Some(this) = this.upgrade() {
+                            this.capability_requests.lock().remove(&key);
+                        }
+                    }
+                    consent.map_err(Arc::new)
+                })
+                .shared()
+            })
+            .clone()
+    }
+
     pub fn writeable_path_from_extension(&self, id: &Arc<str>, path: &Path) -> Result<PathBuf> {
         let extension_work_dir = self.work_dir.join(id.as_ref());
         let path = normalize_path(&extension_work_dir.join(path));
@@ -780,6 +1218,34 @@ impl WasmState {
         self.host.work_dir.join(self.manifest.id.as_ref())
     }
 
+    /// Returns an error unless the user granted the capability to the extension.
+    async fn ensure_capability_granted(&self, capability: ExtensionCapability) -> Result<()> {
+        let description = capability.to_string();
+        let host = self.host.clone();
+        let manifest = self.manifest.clone();
+        let granted = self
+            .on_main_thread({
+                let description = description.clone();
+                move |cx| {
+                    async move {
+                        let request =
+                            cx.update(|cx| host.request_capability(&manifest, capability, cx))?;
+                        request.await.map_err(|error| {
+                            anyhow!("failed to request consent for {description}: {error:#}")
+                        })
+                    }
+                    .boxed_local()
+                }
+            })
+            .await?;
+
+        anyhow::ensure!(
+            granted,
+            "capability for {description} was denied by the user"
+        );
+        Ok(())
+    }
+
     fn extension_error(&self, message: String) -> anyhow::Error {
         anyhow!(
             "from extension \"{}\" version {}: {}",
@@ -7,6 +7,7 @@ mod since_v0_3_0;
 mod since_v0_4_0;
 mod since_v0_5_0;
 mod since_v0_6_0;
+mod since_v0_7_0;
 use dap::DebugRequest;
 use extension::{DebugTaskDefinition, KeyValueStoreDelegate, WorktreeDelegate};
 use gpui::BackgroundExecutor;
@@ -15,12 +16,12 @@ use lsp::LanguageServerName;
 use release_channel::ReleaseChannel;
 use task::{DebugScenario, SpawnInTerminal, TaskTemplate, ZedDebugConfig};
 
-use crate::wasm_host::wit::since_v0_6_0::dap::StartDebuggingRequestArgumentsRequest;
+use crate::wasm_host::wit::since_v0_7_0::dap::StartDebuggingRequestArgumentsRequest;
 
 use super::{WasmState, wasm_engine};
 use anyhow::{Context as _, Result, anyhow};
 use semantic_version::SemanticVersion;
-use since_v0_6_0 as latest;
+use since_v0_7_0 as latest;
 use std::{ops::RangeInclusive, path::PathBuf, sync::Arc};
 use wasmtime::{
     Store,
@@ -65,11 +66,9 @@ pub fn is_supported_wasm_api_version(
 #[inline(always)]
 pub fn wasm_api_version_range(release_channel: ReleaseChannel) -> RangeInclusive<SemanticVersion> {
     // Note: The release channel can be used to stage a new version of the extension API.
-    let _ = release_channel;
-
     let max_version = match release_channel {
         ReleaseChannel::Dev | ReleaseChannel::Nightly => latest::MAX_VERSION,
-        ReleaseChannel::Stable | ReleaseChannel::Preview => latest::MAX_VERSION,
+        ReleaseChannel::Stable | ReleaseChannel::Preview => since_v0_6_0::MAX_VERSION,
     };
 
     since_v0_0_1::MIN_VERSION..=max_version
@@ -98,6 +97,7 @@ pub fn authorize_access_to_unreleased_wasm_api_version(
 }
 
 pub enum Extension {
+    V0_7_0(since_v0_7_0::Extension),
     V0_6_0(since_v0_6_0::Extension),
     V0_5_0(since_v0_5_0::Extension),
     V0_4_0(since_v0_4_0::Extension),
@@ -118,13 +118,22 @@ impl Extension {
         component: &Component,
     ) -> Result<Self> {
         // Note: The release channel can be used to stage a new version of the extension API.
-        let _ = release_channel;
-
         if version >= latest::MIN_VERSION {
+            authorize_access_to_unreleased_wasm_api_version(release_channel)?;
+
             let extension =
                 latest::Extension::instantiate_async(store, component, latest::linker(executor))
                     .await
                     .context("failed to instantiate wasm extension")?;
+            Ok(Self::V0_7_0(extension))
+        } else if version >= since_v0_6_0::MIN_VERSION {
+            let extension = since_v0_6_0::Extension::instantiate_async(
+                store,
+                component,
+                since_v0_6_0::linker(executor),
+            )
+            .await
+            .context("failed to instantiate wasm extension")?;
             Ok(Self::V0_6_0(extension))
         } else if version >= since_v0_5_0::MIN_VERSION {
             let extension = since_v0_5_0::Extension::instantiate_async(
@@ -203,6 +212,7 @@ impl Extension {
 
     pub async fn call_init_extension(&self, store: &mut Store<WasmState>) -> Result<()> {
         match self {
+            Extension::V0_7_0(ext) => ext.call_init_extension(store).await,
             Extension::V0_6_0(ext) => ext.call_init_extension(store).await,
             Extension::V0_5_0(ext) => ext.call_init_extension(store).await,
             Extension::V0_4_0(ext) => ext.call_init_extension(store).await,
@@ -223,6 +233,10 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Command, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_language_server_command(store, &language_server_id.0, resource)
+                    .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_language_server_command(store, &language_server_id.0, resource)
                     .await
@@ -285,6 +299,14 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_language_server_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_language_server_initialization_options(
                     store,
@@ -374,6 +396,14 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_language_server_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_language_server_workspace_configuration(
                     store,
@@ -442,6 +472,15 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_language_server_additional_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    &target_language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_language_server_additional_initialization_options(
                     store,
@@ -486,6 +525,15 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_language_server_additional_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    &target_language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_language_server_additional_workspace_configuration(
                     store,
@@ -529,10 +577,19 @@ impl Extension {
         completions: Vec<latest::Completion>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V0_6_0(ext) => {
+            Extension::V0_7_0(ext) => {
                 ext.call_labels_for_completions(store, &language_server_id.0, &completions)
                     .await
             }
+            Extension::V0_6_0(ext) => Ok(ext
+                .call_labels_for_completions(store, &language_server_id.0, &completions)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V0_5_0(ext) => Ok(ext
                 .call_labels_for_completions(
                     store,
@@ -622,10 +679,19 @@ impl Extension {
         symbols: Vec<latest::Symbol>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V0_6_0(ext) => {
+            Extension::V0_7_0(ext) => {
                 ext.call_labels_for_symbols(store, &language_server_id.0, &symbols)
                     .await
             }
+            Extension::V0_6_0(ext) => Ok(ext
+                .call_labels_for_symbols(store, &language_server_id.0, &symbols)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V0_5_0(ext) => Ok(ext
                 .call_labels_for_symbols(
                     store,
@@ -715,6 +781,10 @@ impl Extension {
         arguments: &[String],
     ) -> Result<Result<Vec<SlashCommandArgumentCompletion>, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_complete_slash_command_argument(store, command, arguments)
                     .await
@@ -753,6 +823,10 @@ impl Extension {
         resource: Option<Resource<Arc<dyn WorktreeDelegate>>>,
     ) -> Result<Result<SlashCommandOutput, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_run_slash_command(store, command, arguments, resource)
                     .await
@@ -790,6 +864,10 @@ impl Extension {
         project: Resource<ExtensionProject>,
     ) -> Result<Result<Command, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_context_server_command(store, &context_server_id, project)
+                    .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_context_server_command(store, &context_server_id, project)
                     .await
@@ -826,6 +904,10 @@ impl Extension {
         project: Resource<ExtensionProject>,
     ) -> Result<Result<Option<ContextServerConfiguration>, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_context_server_configuration(store, &context_server_id, project)
+                    .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_context_server_configuration(store, &context_server_id, project)
                     .await
@@ -852,6 +934,7 @@ impl Extension {
         provider: &str,
     ) -> Result<Result<Vec<String>, String>> {
         match self {
+            Extension::V0_7_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V0_6_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V0_5_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V0_4_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
@@ -872,6 +955,10 @@ impl Extension {
         kv_store: Resource<Arc<dyn KeyValueStoreDelegate>>,
     ) -> Result<Result<(), String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_index_docs(store, provider, package_name, kv_store)
+                    .await
+            }
             Extension::V0_6_0(ext) => {
                 ext.call_index_docs(store, provider, package_name, kv_store)
                     .await
@@ -910,6 +997,20 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<DebugAdapterBinary, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                let dap_binary = ext
+                    .call_get_dap_binary(
+                        store,
+                        &adapter_name,
+                        &task.try_into()?,
+                        user_installed_path.as_ref().and_then(|p| p.to_str()),
+                        resource,
+                    )
+                    .await?
+                    .map_err(|e| anyhow!("{e:?}"))?;
+
+                Ok(Ok(dap_binary))
+            }
             Extension::V0_6_0(ext) => {
                 let dap_binary = ext
                     .call_get_dap_binary(
@@ -934,6 +1035,16 @@ impl Extension {
         config: serde_json::Value,
     ) -> Result<Result<StartDebuggingRequestArgumentsRequest, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                let config =
+                    serde_json::to_string(&config).context("Adapter config is not a valid JSON")?;
+                let dap_binary = ext
+                    .call_dap_request_kind(store, &adapter_name, &config)
+                    .await?
+                    .map_err(|e| anyhow!("{e:?}"))?;
+
+                Ok(Ok(dap_binary))
+            }
             Extension::V0_6_0(ext) => {
                 let config =
                     serde_json::to_string(&config).context("Adapter config is not a valid JSON")?;
@@ -953,6 +1064,15 @@ impl Extension {
         config: ZedDebugConfig,
     ) -> Result<Result<DebugScenario, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                let config = config.into();
+                let dap_binary = ext
+                    .call_dap_config_to_scenario(store, &config)
+                    .await?
+                    .map_err(|e| anyhow!("{e:?}"))?;
+
+                Ok(Ok(dap_binary.try_into()?))
+            }
             Extension::V0_6_0(ext) => {
                 let config = config.into();
                 let dap_binary = ext
@@ -974,6 +1094,20 @@ impl Extension {
         debug_adapter_name: String,
     ) -> Result<Option<DebugScenario>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                let build_config_template = build_config_template.into();
+                let dap_binary = ext
+                    .call_dap_locator_create_scenario(
+                        store,
+                        &locator_name,
+                        &build_config_template,
+                        &resolved_label,
+                        &debug_adapter_name,
+                    )
+                    .await?;
+
+                Ok(dap_binary.map(TryInto::try_into).transpose()?)
+            }
             Extension::V0_6_0(ext) => {
                 let build_config_template = build_config_template.into();
                 let dap_binary = ext
@@ -998,6 +1132,15 @@ impl Extension {
         resolved_build_task: SpawnInTerminal,
     ) -> Result<Result<DebugRequest, String>> {
         match self {
+            Extension::V0_7_0(ext) => {
+                let build_config_template = resolved_build_task.into();
+                let dap_request = ext
+                    .call_run_dap_locator(store, &locator_name, &build_config_template)
+                    .await?
+                    .map_err(|e| anyhow!("{e:?}"))?;
+
+                Ok(Ok(dap_request.into()))
+            }
             Extension::V0_6_0(ext) => {
                 let build_config_template = resolved_build_task.into();
                 let dap_request = ext
@@ -1010,6 +1153,249 @@ impl Extension {
             _ => anyhow::bail!("`dap_locator_create_scenario` not available prior to v0.6.0"),
         }
     }
+
+    pub async fn call_llm_provider_models(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+    ) -> Result<Result<Vec<latest::llm_provider::Model>, String>> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_llm_provider_models(store, provider_id).await,
+            _ => anyhow::bail!("`llm_provider_models` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_llm_stream_completion(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        model_id: &str,
+        request: latest::llm_provider::CompletionRequest,
+        api_key: Option<&str>,
+    ) -> Result<Result<u64, String>> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_llm_stream_completion(store, provider_id, model_id, &request, api_key)
+                    .await
+            }
+            _ => anyhow::bail!("`llm_stream_completion` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_llm_next_completion_events(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<Result<Option<Vec<latest::llm_provider::CompletionEvent>>, String>> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_llm_next_completion_events(store, stream_id).await,
+            _ => anyhow::bail!("`llm_next_completion_events` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_llm_drop_completion_stream(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<()> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_llm_drop_completion_stream(store, stream_id).await,
+            _ => anyhow::bail!("`llm_drop_completion_stream` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_format_buffer(
+        &self,
+        store: &mut Store<WasmState>,
+        formatter_id: &str,
+        request: latest::formatter::FormatRequest,
+    ) -> Result<Result<latest::formatter::FormatResult, String>> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_format_buffer(store, formatter_id, &request).await,
+            _ => anyhow::bail!("`format_buffer` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_code_actions(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        request: latest::code_action::CodeActionRequest,
+    ) -> Result<Result<Vec<latest::code_action::CodeAction>, String>> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_code_actions(store, provider_id, &request).await,
+            _ => anyhow::bail!("`code_actions` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_task_templates(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        path: Option<&str>,
+    ) -> Result<Result<Vec<latest::task_provider::TaskTemplate>, String>> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_task_templates(store, provider_id, path).await,
+            _ => anyhow::bail!("`task_templates` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_task_variables(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        variables: &[(String, String)],
+        location: latest::task_provider::TaskLocation,
+    ) -> Result<Result<Vec<(String, String)>, String>> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_task_variables(store, provider_id, variables, &location)
+                    .await
+            }
+            _ => anyhow::bail!("`task_variables` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_list_toolchains(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        worktree_root: &str,
+        subroot_relative_path: Option<&str>,
+        env: &[(String, String)],
+    ) -> Result<Result<latest::toolchain::ToolchainList, String>> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_list_toolchains(
+                    store,
+                    provider_id,
+                    worktree_root,
+                    subroot_relative_path,
+                    env,
+                )
+                .await
+            }
+            _ => anyhow::bail!("`list_toolchains` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_predict_edits(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        request: latest::edit_prediction::PredictionRequest,
+    ) -> Result<Result<Option<latest::edit_prediction::Prediction>, String>> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_predict_edits(store, provider_id, &request).await,
+            _ => anyhow::bail!("`predict_edits` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_accept_edit_prediction(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        prediction_id: &str,
+    ) -> Result<()> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_accept_edit_prediction(store, provider_id, prediction_id)
+                    .await
+            }
+            _ => anyhow::bail!("`accept_edit_prediction` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_reject_edit_prediction(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        prediction_id: &str,
+    ) -> Result<()> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_reject_edit_prediction(store, provider_id, prediction_id)
+                    .await
+            }
+            _ => anyhow::bail!("`reject_edit_prediction` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_parse_git_remote_url(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        url: &str,
+    ) -> Result<Option<latest::git_hosting::ParsedGitRemote>> {
+        match self {
+            Extension::V0_7_0(ext) => ext.call_parse_git_remote_url(store, provider_id, url).await,
+            _ => anyhow::bail!("`parse_git_remote_url` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_build_git_commit_permalink(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        remote: latest::git_hosting::ParsedGitRemote,
+        sha: &str,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_build_git_commit_permalink(store, provider_id, &remote, sha)
+                    .await
+            }
+            _ => anyhow::bail!("`build_git_commit_permalink` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_build_git_permalink(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        remote: latest::git_hosting::ParsedGitRemote,
+        params: latest::git_hosting::PermalinkParams,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_build_git_permalink(store, provider_id, &remote, &params)
+                    .await
+            }
+            _ => anyhow::bail!("`build_git_permalink` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_extract_git_pull_request(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        remote: latest::git_hosting::ParsedGitRemote,
+        message: &str,
+    ) -> Result<Option<latest::git_hosting::PullRequest>> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_extract_git_pull_request(store, provider_id, &remote, message)
+                    .await
+            }
+            _ => anyhow::bail!("`extract_git_pull_request` not available prior to v0.7.0"),
+        }
+    }
+
+    pub async fn call_git_commit_author_avatar_url(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        remote: latest::git_hosting::ParsedGitRemote,
+        sha: &str,
+    ) -> Result<Result<Option<String>, String>> {
+        match self {
+            Extension::V0_7_0(ext) => {
+                ext.call_git_commit_author_avatar_url(store, provider_id, &remote, sha)
+                    .await
+            }
+            _ => anyhow::bail!("`git_commit_author_avatar_url` not available prior to v0.7.0"),
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
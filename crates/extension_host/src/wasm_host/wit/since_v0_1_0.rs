@@ -295,6 +295,9 @@ impl http_client::Host for WasmState {
     ) -> wasmtime::Result<Result<http_client::HttpResponse, String>> {
         maybe!(async {
             let url = &request.url;
+            let capability = self.manifest.allow_network_fetch(url)?;
+            self.ensure_capability_granted(capability).await?;
+
             let request = convert_request(&request)?;
             let mut response = self.host.http_client.send(request).await?;
 
@@ -311,10 +314,12 @@ impl http_client::Host for WasmState {
         &mut self,
         request: http_client::HttpRequest,
     ) -> wasmtime::Result<Result<Resource<ExtensionHttpResponseStream>, String>> {
-        let request = convert_request(&request)?;
-        let response = self.host.http_client.send(request);
         maybe!(async {
-            let response = response.await?;
+            let capability = self.manifest.allow_network_fetch(&request.url)?;
+            self.ensure_capability_granted(capability).await?;
+
+            let request = convert_request(&request)?;
+            let response = self.host.http_client.send(request).await?;
             let stream = Arc::new(Mutex::new(response));
             let resource = self.table.push(stream)?;
             Ok(resource)
@@ -495,6 +500,9 @@ impl ExtensionImports for WasmState {
         file_type: DownloadedFileType,
     ) -> wasmtime::Result<Result<(), String>> {
         maybe!(async {
+            let capability = self.manifest.allow_network_fetch(&url)?;
+            self.ensure_capability_granted(capability).await?;
+
             let path = PathBuf::from(path);
             let extension_work_dir = self.host.work_dir.join(self.manifest.id.as_ref());
 
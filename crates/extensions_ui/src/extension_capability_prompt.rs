@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use extension::{ExtensionCapability, ExtensionCapabilityProxy, ExtensionHostProxy};
+use extension_host::ExtensionSettings;
+use fs::Fs;
+use gpui::{App, PromptLevel, SharedString, Task};
+use settings::update_settings_file;
+
+pub(crate) fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_capability_proxy(ExtensionCapabilityPrompt);
+}
+
+/// Asks the user for their consent the first time an extension uses a capability,
+/// and persists their decision in the settings.
+struct ExtensionCapabilityPrompt;
+
+impl ExtensionCapabilityProxy for ExtensionCapabilityPrompt {
+    fn request_capability_consent(
+        &self,
+        extension_id: Arc<str>,
+        extension_name: SharedString,
+        capability: ExtensionCapability,
+        cx: &mut App,
+    ) -> Task<Result<bool>> {
+        let Some(window) = cx
+            .active_window()
+            .or_else(|| cx.windows().into_iter().next())
+        else {
+            return Task::ready(Err(anyhow!(
+                "no window to ask for consent to {capability} in"
+            )));
+        };
+
+        let message = format!(
+            "Allow the {extension_name} extension to {}?",
+            capability_description(&capability)
+        );
+        let detail = format!(
+            "The extension is requesting the \"{capability}\" capability. \
+            Your choice will be saved in your settings."
+        );
+        let answer = match window.update(cx, |_, window, cx| {
+            window.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some(&detail),
+                &["Allow", "Deny"],
+                cx,
+            )
+        }) {
+            Ok(answer) => answer,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let fs = <dyn Fs>::global(cx);
+        cx.spawn(async move |cx| {
+            let granted = answer.await? == 0;
+            cx.update(|cx| {
+                update_settings_file::<ExtensionSettings>(fs, cx, move |settings, _| {
+                    settings
+                        .extension_capabilities
+                        .entry(extension_id)
+                        .or_default()
+                        .insert(capability.to_string(), granted);
+                });
+            })?;
+            Ok(granted)
+        })
+    }
+}
+
+fn capability_description(capability: &ExtensionCapability) -> String {
+    match capability {
+        ExtensionCapability::ProcessExec { command, args } => {
+            let mut command = command.clone();
+            for arg in args {
+                command.push(' ');
+                command.push_str(arg);
+            }
+            format!("run `{command}`")
+        }
+        ExtensionCapability::NetworkFetch { host } if host == "*" => {
+            "make network requests to any host".to_string()
+        }
+        ExtensionCapability::NetworkFetch { host } => {
+            format!("make network requests to {host}")
+        }
+        ExtensionCapability::FileRead { path } if path == "**" => {
+            "read any file in your projects".to_string()
+        }
+        ExtensionCapability::FileRead { path } => format!("read `{path}` in your projects"),
+    }
+}
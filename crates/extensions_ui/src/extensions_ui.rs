@@ -1,4 +1,5 @@
 mod components;
+mod extension_capability_prompt;
 mod extension_suggest;
 mod extension_version_selector;
 
@@ -38,9 +39,11 @@ use crate::extension_version_selector::{
     ExtensionVersionSelector, ExtensionVersionSelectorDelegate,
 };
 
-actions!(zed, [InstallDevExtension]);
+actions!(zed, [InstallDevExtension, InstallExtensionFromArchive]);
 
 pub fn init(cx: &mut App) {
+    extension_capability_prompt::init(cx);
+
     cx.observe_new(move |workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
@@ -153,7 +156,67 @@ pub fn init(cx: &mut App) {
                         Some(())
                     })
                     .detach();
-            });
+            })
+            .register_action(
+                move |workspace, _: &InstallExtensionFromArchive, window, cx| {
+                    let store = ExtensionStore::global(cx);
+                    let prompt = workspace.prompt_for_open_path(
+                        gpui::PathPromptOptions {
+                            files: true,
+                            directories: false,
+                            multiple: false,
+                        },
+                        DirectoryLister::Local(
+                            workspace.project().clone(),
+                            workspace.app_state().fs.clone(),
+                        ),
+                        window,
+                        cx,
+                    );
+
+                    let workspace_handle = cx.entity().downgrade();
+                    window
+                        .spawn(cx, async move |cx| {
+                            let archive_path =
+                                match Flatten::flatten(prompt.await.map_err(|e| e.into())) {
+                                    Ok(Some(mut paths)) => paths.pop()?,
+                                    Ok(None) => return None,
+                                    Err(err) => {
+                                        workspace_handle
+                                            .update(cx, |workspace, cx| {
+                                                workspace.show_portal_error(err.to_string(), cx);
+                                            })
+                                            .ok();
+                                        return None;
+                                    }
+                                };
+
+                            let install_task = store
+                                .update(cx, |store, cx| {
+                                    store.install_extension_from_archive(archive_path, cx)
+                                })
+                                .ok()?;
+
+                            if let Err(err) = install_task.await {
+                                log::error!("Failed to install extension from archive: {:?}", err);
+                                workspace_handle
+                                    .update(cx, |workspace, cx| {
+                                        workspace.show_error(
+                                            &format!(
+                                                "Failed to install extension from archive: {}",
+                                                err
+                                            ),
+                                            cx,
+                                        );
+                                    })
+                                    .ok();
+                            }
+
+                            Some(())
+                        })
+                        .detach();
+                },
+            );
 
         cx.subscribe_in(workspace.project(), window, |_, _, event, window, cx| {
             if let project::Event::LanguageNotFound(buffer) = event {
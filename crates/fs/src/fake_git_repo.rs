@@ -6,7 +6,7 @@ use git::{
     blame::Blame,
     repository::{
         AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        GitRepositoryCheckpoint, LogQuery, PushOptions, Remote, RepoPath, ResetMode,
     },
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
@@ -32,6 +32,8 @@ pub struct FakeGitRepositoryState {
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
     pub blames: HashMap<RepoPath, Blame>,
+    /// The commits reachable from `HEAD`, most recent first, with the paths that each one changed.
+    pub commits: Vec<(CommitDetails, Vec<RepoPath>)>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
@@ -46,6 +48,7 @@ impl FakeGitRepositoryState {
             index_contents: Default::default(),
             unmerged_paths: Default::default(),
             blames: Default::default(),
+            commits: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
@@ -144,13 +147,35 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>> {
-        async {
-            Ok(CommitDetails {
+        self.with_state_async(false, move |state| {
+            let details = state
+                .commits
+                .iter()
+                .find(|(details, _)| details.sha.starts_with(&commit))
+                .map(|(details, _)| details.clone());
+            Ok(details.unwrap_or_else(|| CommitDetails {
                 sha: commit.into(),
                 ..Default::default()
-            })
-        }
-        .boxed()
+            }))
+        })
+    }
+
+    fn log(&self, query: LogQuery) -> BoxFuture<'_, Result<Vec<CommitDetails>>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .commits
+                .iter()
+                .filter(|(_, paths)| {
+                    query
+                        .path
+                        .as_ref()
+                        .is_none_or(|path| paths.iter().any(|changed| changed.starts_with(path)))
+                })
+                .skip(query.skip)
+                .take(query.limit)
+                .map(|(details, _)| details.clone())
+                .collect())
+        })
     }
 
     fn reset(
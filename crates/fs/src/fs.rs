@@ -1545,6 +1545,17 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_commits_for_repo(
+        &self,
+        dot_git: &Path,
+        commits: Vec<(git::repository::CommitDetails, Vec<RepoPath>)>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commits = commits;
+        })
+        .unwrap();
+    }
+
     /// Put the given git repository into a state with the given status,
     /// by mutating the head, index, and unmerged state.
     pub fn set_status_for_repo(&self, dot_git: &Path, statuses: &[(&Path, FileStatus)]) {
@@ -86,7 +86,7 @@ impl Oid {
         self.0.as_bytes()
     }
 
-    pub(crate) fn is_zero(&self) -> bool {
+    pub fn is_zero(&self) -> bool {
         self.0.is_zero()
     }
 
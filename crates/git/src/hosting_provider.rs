@@ -1,4 +1,4 @@
-use std::{ops::Range, sync::Arc};
+use std::{collections::BTreeMap, ops::Range, sync::Arc};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -69,14 +69,14 @@ pub trait GitHostingProvider {
     fn base_url(&self) -> Url;
 
     /// Returns a permalink to a Git commit on this hosting provider.
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
     ) -> Url;
 
     /// Returns a permalink to a file and/or selection on this hosting provider.
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url;
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url;
 
     /// Returns whether this provider supports avatars.
     fn supports_avatars(&self) -> bool;
@@ -101,9 +101,9 @@ pub trait GitHostingProvider {
     /// Returns a formatted range of line numbers to be placed in a permalink URL.
     fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String;
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote>;
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote>;
 
-    fn extract_pull_request(
+    async fn extract_pull_request(
         &self,
         _remote: &ParsedGitRemote,
         _message: &str,
@@ -131,6 +131,7 @@ impl Global for GlobalGitHostingProviderRegistry {}
 struct GitHostingProviderRegistryState {
     default_providers: Vec<Arc<dyn GitHostingProvider + Send + Sync + 'static>>,
     setting_providers: Vec<Arc<dyn GitHostingProvider + Send + Sync + 'static>>,
+    extension_providers: BTreeMap<Arc<str>, Arc<dyn GitHostingProvider + Send + Sync + 'static>>,
 }
 
 #[derive(Default)]
@@ -171,6 +172,7 @@ impl GitHostingProviderRegistry {
             state: RwLock::new(GitHostingProviderRegistryState {
                 setting_providers: Vec::default(),
                 default_providers: Vec::default(),
+                extension_providers: BTreeMap::default(),
             }),
         }
     }
@@ -185,6 +187,7 @@ impl GitHostingProviderRegistry {
             .iter()
             .cloned()
             .chain(state.setting_providers.iter().cloned())
+            .chain(state.extension_providers.values().cloned())
             .collect()
     }
 
@@ -204,6 +207,25 @@ impl GitHostingProviderRegistry {
     ) {
         self.state.write().default_providers.push(provider);
     }
+
+    /// Adds the [`GitHostingProvider`] with the given ID, as provided by an extension.
+    ///
+    /// Replaces any existing extension-provided provider with the same ID.
+    pub fn register_extension_provider(
+        &self,
+        provider_id: Arc<str>,
+        provider: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    ) {
+        self.state
+            .write()
+            .extension_providers
+            .insert(provider_id, provider);
+    }
+
+    /// Removes the extension-provided [`GitHostingProvider`] with the given ID.
+    pub fn unregister_extension_provider(&self, provider_id: &str) {
+        self.state.write().extension_providers.remove(provider_id);
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -212,19 +234,17 @@ pub struct ParsedGitRemote {
     pub repo: Arc<str>,
 }
 
-pub fn parse_git_remote_url(
+pub async fn parse_git_remote_url(
     provider_registry: Arc<GitHostingProviderRegistry>,
     url: &str,
 ) -> Option<(
     Arc<dyn GitHostingProvider + Send + Sync + 'static>,
     ParsedGitRemote,
 )> {
-    provider_registry
-        .list_hosting_providers()
-        .into_iter()
-        .find_map(|provider| {
-            provider
-                .parse_remote_url(url)
-                .map(|parsed_remote| (provider, parsed_remote))
-        })
+    for provider in provider_registry.list_hosting_providers() {
+        if let Some(parsed_remote) = provider.parse_remote_url(url).await {
+            return Some((provider, parsed_remote));
+        }
+    }
+    None
 }
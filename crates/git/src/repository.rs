@@ -161,6 +161,17 @@ pub struct CommitDetails {
     pub author_name: SharedString,
 }
 
+/// Which commits [`GitRepository::log`] lists, starting from `HEAD`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogQuery {
+    /// Only list commits that changed this path.
+    pub path: Option<RepoPath>,
+    /// How many of the most recent commits to leave out.
+    pub skip: usize,
+    /// The maximum number of commits to list.
+    pub limit: usize,
+}
+
 #[derive(Debug)]
 pub struct CommitDiff {
     pub files: Vec<CommitFile>,
@@ -361,6 +372,10 @@ pub trait GitRepository: Send + Sync {
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
+
+    /// Lists commits reachable from `HEAD`, most recent first.
+    fn log(&self, query: LogQuery) -> BoxFuture<'_, Result<Vec<CommitDetails>>>;
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -574,6 +589,7 @@ impl GitRepository for RealGitRepository {
         self.executor
             .spawn(async move {
                 let working_directory = working_directory?;
+                let sha = resolve_commit(&working_directory, &commit)?;
                 let output = new_std_command("git")
                     .current_dir(&working_directory)
                     .args([
@@ -581,7 +597,7 @@ impl GitRepository for RealGitRepository {
                         "show",
                         "--no-patch",
                         "--format=%H%x00%B%x00%at%x00%ae%x00%an%x00",
-                        &commit,
+                        &sha,
                     ])
                     .output()?;
                 let output = std::str::from_utf8(&output.stdout)?;
@@ -605,12 +621,61 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn log(&self, query: LogQuery) -> BoxFuture<'_, Result<Vec<CommitDetails>>> {
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let mut command = new_std_command("git");
+                command
+                    .current_dir(&working_directory)
+                    .args([
+                        "--no-optional-locks",
+                        "log",
+                        "--format=%H%x00%B%x00%at%x00%ae%x00%an%x1e",
+                    ])
+                    .arg(format!("--skip={}", query.skip))
+                    .arg(format!("--max-count={}", query.limit));
+                if let Some(path) = &query.path {
+                    command.arg("--").arg(path.as_ref());
+                }
+                let output = command.stdin(Stdio::null()).output()?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "git log failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+
+                let output = std::str::from_utf8(&output.stdout)?;
+                output
+                    .split('\x1e')
+                    .map(|record| record.trim_start_matches('\n'))
+                    .filter(|record| !record.is_empty())
+                    .map(|record| {
+                        let fields = record.split('\0').collect::<Vec<_>>();
+                        if fields.len() != 5 {
+                            bail!("unexpected git-log output: {record:?}")
+                        }
+                        Ok(CommitDetails {
+                            sha: fields[0].to_string().into(),
+                            message: fields[1].trim_end().to_string().into(),
+                            commit_timestamp: fields[2].parse()?,
+                            author_email: fields[3].to_string().into(),
+                            author_name: fields[4].to_string().into(),
+                        })
+                    })
+                    .collect()
+            })
+            .boxed()
+    }
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
         let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
         else {
             return future::ready(Err(anyhow!("no working directory"))).boxed();
         };
         cx.background_spawn(async move {
+            let commit = resolve_commit(&working_directory, &commit)?;
             let show_output = util::command::new_std_command("git")
                 .current_dir(&working_directory)
                 .args([
@@ -1742,6 +1807,29 @@ impl GitBinary {
     }
 }
 
+/// Resolves a revision to the SHA of the commit it names, so that the revision can't be
+/// interpreted as an option by the commands it is passed to.
+fn resolve_commit(working_directory: &Path, revision: &str) -> Result<String> {
+    let output = new_std_command("git")
+        .current_dir(working_directory)
+        .args([
+            "--no-optional-locks",
+            "rev-parse",
+            "--verify",
+            "--end-of-options",
+        ])
+        .arg(format!("{revision}^{{commit}}"))
+        .stdin(Stdio::null())
+        .output()
+        .context("starting git rev-parse process")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "{revision:?} is not a commit: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
 #[derive(Error, Debug)]
 #[error("Git command failed: {stdout}")]
 struct GitBinaryCommandError {
@@ -2246,6 +2334,117 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_log(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+
+        for (path, message) in [
+            ("a", "Add a"),
+            ("b", "Add b\n\nWith a longer description."),
+            ("a", "Change a"),
+        ] {
+            smol::fs::write(repo_dir.path().join(path), message)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(path)], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                Arc::new(checkpoint_author_envs()),
+            )
+            .await
+            .unwrap();
+        }
+
+        let messages = |commits: Vec<CommitDetails>| {
+            commits
+                .into_iter()
+                .map(|commit| commit.message.to_string())
+                .collect::<Vec<_>>()
+        };
+        let log = repo
+            .log(LogQuery {
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(log[0].author_name.as_ref(), "Zed");
+        assert_eq!(log[0].sha.len(), 40);
+        assert_eq!(
+            messages(log),
+            ["Change a", "Add b\n\nWith a longer description.", "Add a"]
+        );
+
+        let log = repo
+            .log(LogQuery {
+                skip: 1,
+                limit: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(messages(log), ["Add b\n\nWith a longer description."]);
+
+        let log = repo
+            .log(LogQuery {
+                path: Some(RepoPath::from_str("a")),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(messages(log), ["Change a", "Add a"]);
+    }
+
+    #[gpui::test]
+    async fn test_show_resolves_revisions(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+
+        smol::fs::write(repo_dir.path().join("a"), "a")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Add a".into(),
+            None,
+            CommitOptions::default(),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        let details = repo.show("HEAD".into()).await.unwrap();
+        assert_eq!(details.sha.len(), 40);
+        assert_eq!(details.message.trim_end(), "Add a");
+        let diff = repo
+            .load_commit(details.sha.to_string(), cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(diff.files.len(), 1);
+
+        let output_path = repo_dir.path().join("output");
+        let revision = format!("--output={}", output_path.display());
+        assert!(repo.show(revision.clone()).await.is_err());
+        assert!(repo.load_commit(revision, cx.to_async()).await.is_err());
+        assert!(!output_path.exists());
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
@@ -0,0 +1,365 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use extension::{
+    Extension, ExtensionGitHostingProviderProxy, ExtensionHostProxy, GitHostingPermalinkParams,
+    GitHostingProviderManifestEntry, GitHostingRemote,
+};
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider,
+    GitHostingProviderRegistry, ParsedGitRemote, PullRequest,
+};
+use gpui::{App, SharedString};
+use http_client::HttpClient;
+use url::Url;
+use util::ResultExt as _;
+
+pub(crate) fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_git_hosting_provider_proxy(ExtensionGitHostingProviderRegistryProxy {
+        provider_registry: GitHostingProviderRegistry::global(cx),
+    });
+}
+
+struct ExtensionGitHostingProviderRegistryProxy {
+    provider_registry: Arc<GitHostingProviderRegistry>,
+}
+
+impl ExtensionGitHostingProviderProxy for ExtensionGitHostingProviderRegistryProxy {
+    fn register_git_hosting_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: GitHostingProviderManifestEntry,
+    ) {
+        let Some(base_url) = Url::parse(&provider.base_url).log_err() else {
+            return;
+        };
+
+        self.provider_registry.register_extension_provider(
+            provider_id.clone(),
+            Arc::new(ExtensionGitHostingProvider {
+                extension,
+                provider_id,
+                name: provider.name,
+                base_url,
+                supports_avatars: provider.supports_avatars,
+            }),
+        );
+    }
+
+    fn unregister_git_hosting_provider(&self, provider_id: Arc<str>) {
+        self.provider_registry
+            .unregister_extension_provider(&provider_id);
+    }
+}
+
+/// A Git hosting provider implemented by an extension.
+struct ExtensionGitHostingProvider {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    name: String,
+    base_url: Url,
+    supports_avatars: bool,
+}
+
+fn to_extension_remote(remote: &ParsedGitRemote) -> GitHostingRemote {
+    GitHostingRemote {
+        owner: remote.owner.to_string(),
+        repo: remote.repo.to_string(),
+    }
+}
+
+#[async_trait]
+impl GitHostingProvider for ExtensionGitHostingProvider {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        self.supports_avatars
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        format!("L{line}")
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        format!("L{start_line}-{end_line}")
+    }
+
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        let remote = self
+            .extension
+            .parse_git_remote_url(self.provider_id.clone(), url.to_string())
+            .await
+            .log_err()??;
+
+        Some(ParsedGitRemote {
+            owner: remote.owner.into(),
+            repo: remote.repo.into(),
+        })
+    }
+
+    async fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        self.extension
+            .build_git_commit_permalink(
+                self.provider_id.clone(),
+                to_extension_remote(remote),
+                params.sha.to_string(),
+            )
+            .await
+            .and_then(|permalink| Ok(Url::parse(&permalink)?))
+            .log_err()
+            .unwrap_or_else(|| self.base_url())
+    }
+
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        self.extension
+            .build_git_permalink(
+                self.provider_id.clone(),
+                to_extension_remote(&remote),
+                GitHostingPermalinkParams {
+                    sha: params.sha.to_string(),
+                    path: params.path.to_string(),
+                    selection: params.selection,
+                },
+            )
+            .await
+            .and_then(|permalink| Ok(Url::parse(&permalink)?))
+            .log_err()
+            .unwrap_or_else(|| self.base_url())
+    }
+
+    async fn extract_pull_request(
+        &self,
+        remote: &ParsedGitRemote,
+        message: &str,
+    ) -> Option<PullRequest> {
+        let pull_request = self
+            .extension
+            .extract_git_pull_request(
+                self.provider_id.clone(),
+                to_extension_remote(remote),
+                message.to_string(),
+            )
+            .await
+            .log_err()??;
+
+        Some(PullRequest {
+            number: pull_request.number,
+            url: Url::parse(&pull_request.url).log_err()?,
+        })
+    }
+
+    async fn commit_author_avatar_url(
+        &self,
+        repo_owner: &str,
+        repo: &str,
+        commit: SharedString,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<Url>> {
+        let avatar_url = self
+            .extension
+            .git_commit_author_avatar_url(
+                self.provider_id.clone(),
+                GitHostingRemote {
+                    owner: repo_owner.to_string(),
+                    repo: repo.to_string(),
+                },
+                commit.to_string(),
+            )
+            .await?;
+
+        Ok(avatar_url.map(|url| Url::parse(&url)).transpose()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extension::{FakeExtension, GitHostingPullRequest};
+    use git::parse_git_remote_url;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn register_provider(registry: &Arc<GitHostingProviderRegistry>) -> Arc<FakeExtension> {
+        let extension = Arc::new(FakeExtension::new("acme"));
+        extension.on_parse_git_remote_url(|provider_id, url| {
+            assert_eq!(provider_id.as_ref(), "acme-review");
+            let Some(path) = url.strip_prefix("https://review.example.com/") else {
+                return Ok(None);
+            };
+            let (owner, repo) = path.split_once('/').unwrap();
+            Ok(Some(GitHostingRemote {
+                owner: owner.into(),
+                repo: repo.into(),
+            }))
+        });
+        extension.on_build_git_commit_permalink(|_, remote, sha| {
+            Ok(format!(
+                "https://review.example.com/{}/{}/commit/{sha}",
+                remote.owner, remote.repo
+            ))
+        });
+        extension.on_build_git_permalink(|_, remote, params| {
+            let selection = params.selection.unwrap();
+            Ok(format!(
+                "https://review.example.com/{}/{}/blob/{}/{}#{}-{}",
+                remote.owner, remote.repo, params.sha, params.path, selection.start, selection.end
+            ))
+        });
+        extension.on_extract_git_pull_request(|_, remote, message| {
+            let Some((_, number)) = message.split_once("Change !") else {
+                return Ok(None);
+            };
+            let number = number.trim().parse()?;
+            Ok(Some(GitHostingPullRequest {
+                number,
+                url: format!(
+                    "https://review.example.com/{}/{}/changes/{number}",
+                    remote.owner, remote.repo
+                ),
+            }))
+        });
+
+        let proxy = ExtensionGitHostingProviderRegistryProxy {
+            provider_registry: registry.clone(),
+        };
+        proxy.register_git_hosting_provider(
+            extension.clone(),
+            "acme-review".into(),
+            GitHostingProviderManifestEntry {
+                name: "Acme Review".into(),
+                base_url: "https://review.example.com".into(),
+                supports_avatars: false,
+            },
+        );
+        extension
+    }
+
+    #[gpui::test]
+    async fn test_extension_git_hosting_provider() {
+        let registry = Arc::new(GitHostingProviderRegistry::new());
+        register_provider(&registry);
+
+        let (provider, remote) =
+            parse_git_remote_url(registry.clone(), "https://review.example.com/acme/widgets")
+                .await
+                .unwrap();
+        assert_eq!(provider.name(), "Acme Review");
+        assert!(!provider.supports_avatars());
+        assert_eq!(
+            remote,
+            ParsedGitRemote {
+                owner: "acme".into(),
+                repo: "widgets".into(),
+            }
+        );
+        assert!(
+            parse_git_remote_url(registry.clone(), "https://example.com/acme/widgets")
+                .await
+                .is_none()
+        );
+
+        let permalink = provider
+            .build_commit_permalink(&remote, BuildCommitPermalinkParams { sha: "abc123" })
+            .await;
+        assert_eq!(
+            permalink.as_str(),
+            "https://review.example.com/acme/widgets/commit/abc123"
+        );
+
+        let permalink = provider
+            .build_permalink(
+                remote,
+                BuildPermalinkParams {
+                    sha: "abc123",
+                    path: "src/main.rs",
+                    selection: Some(6..9),
+                },
+            )
+            .await;
+        assert_eq!(
+            permalink.as_str(),
+            "https://review.example.com/acme/widgets/blob/abc123/src/main.rs#6-9"
+        );
+
+        let remote = ParsedGitRemote {
+            owner: "acme".into(),
+            repo: "widgets".into(),
+        };
+        assert_eq!(
+            provider
+                .extract_pull_request(&remote, "Fix the widgets\n\nChange !42")
+                .await,
+            Some(PullRequest {
+                number: 42,
+                url: Url::parse("https://review.example.com/acme/widgets/changes/42").unwrap(),
+            })
+        );
+        assert_eq!(
+            provider
+                .extract_pull_request(&remote, "Fix the widgets")
+                .await,
+            None
+        );
+
+        let proxy = ExtensionGitHostingProviderRegistryProxy {
+            provider_registry: registry.clone(),
+        };
+        proxy.unregister_git_hosting_provider("acme-review".into());
+        assert!(
+            parse_git_remote_url(registry, "https://review.example.com/acme/widgets")
+                .await
+                .is_none()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_extension_git_hosting_provider_errors() {
+        let registry = Arc::new(GitHostingProviderRegistry::new());
+        let extension = register_provider(&registry);
+        let (provider, remote) =
+            parse_git_remote_url(registry.clone(), "https://review.example.com/acme/widgets")
+                .await
+                .unwrap();
+
+        // Invalid permalinks fall back to the provider's base URL.
+        extension.on_build_git_commit_permalink(|_, _, _| Ok("not a URL".into()));
+        let permalink = provider
+            .build_commit_permalink(&remote, BuildCommitPermalinkParams { sha: "abc123" })
+            .await;
+        assert_eq!(permalink.as_str(), "https://review.example.com/");
+
+        extension.on_build_git_permalink(|_, _, _| anyhow::bail!("no permalinks"));
+        let permalink = provider
+            .build_permalink(
+                remote,
+                BuildPermalinkParams {
+                    sha: "abc123",
+                    path: "src/main.rs",
+                    selection: None,
+                },
+            )
+            .await;
+        assert_eq!(permalink.as_str(), "https://review.example.com/");
+
+        // Remotes that the extension fails to parse aren't hosted by its provider.
+        extension.on_parse_git_remote_url(|_, _| anyhow::bail!("parsing failed"));
+        assert!(
+            parse_git_remote_url(registry, "https://review.example.com/acme/widgets")
+                .await
+                .is_none()
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod extension_provider;
 mod providers;
 mod settings;
 
@@ -26,6 +27,8 @@ pub fn init(cx: &mut App) {
     provider_registry.register_hosting_provider(Arc::new(Github::public_instance()));
     provider_registry.register_hosting_provider(Arc::new(Gitlab::public_instance()));
     provider_registry.register_hosting_provider(Arc::new(Sourcehut));
+
+    crate::extension_provider::init(cx);
 }
 
 /// Registers additional Git hosting providers.
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use async_trait::async_trait;
 use url::Url;
 
 use git::{
@@ -25,6 +26,7 @@ impl Bitbucket {
     }
 }
 
+#[async_trait]
 impl GitHostingProvider for Bitbucket {
     fn name(&self) -> String {
         self.name.clone()
@@ -46,7 +48,7 @@ impl GitHostingProvider for Bitbucket {
         format!("lines-{start_line}:{end_line}")
     }
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
@@ -64,7 +66,7 @@ impl GitHostingProvider for Bitbucket {
         })
     }
 
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
@@ -77,7 +79,7 @@ impl GitHostingProvider for Bitbucket {
             .unwrap()
     }
 
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
         let ParsedGitRemote { owner, repo } = remote;
         let BuildPermalinkParams {
             sha,
@@ -104,10 +106,11 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_parse_remote_url_given_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_ssh_url() {
         let parsed_remote = Bitbucket::public_instance()
             .parse_remote_url("git@bitbucket.org:zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -119,10 +122,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url() {
         let parsed_remote = Bitbucket::public_instance()
             .parse_remote_url("https://bitbucket.org/zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -134,10 +138,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url_with_username() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url_with_username() {
         let parsed_remote = Bitbucket::public_instance()
             .parse_remote_url("https://thorstenballzed@bitbucket.org/zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -149,55 +154,61 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_bitbucket_permalink() {
-        let permalink = Bitbucket::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "f00b4r",
-                path: "main.rs",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_bitbucket_permalink() {
+        let permalink = Bitbucket::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "f00b4r",
+                    path: "main.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://bitbucket.org/zed-industries/zed/src/f00b4r/main.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_bitbucket_permalink_with_single_line_selection() {
-        let permalink = Bitbucket::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "f00b4r",
-                path: "main.rs",
-                selection: Some(6..6),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_bitbucket_permalink_with_single_line_selection() {
+        let permalink = Bitbucket::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "f00b4r",
+                    path: "main.rs",
+                    selection: Some(6..6),
+                },
+            )
+            .await;
 
         let expected_url = "https://bitbucket.org/zed-industries/zed/src/f00b4r/main.rs#lines-7";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_bitbucket_permalink_with_multi_line_selection() {
-        let permalink = Bitbucket::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "f00b4r",
-                path: "main.rs",
-                selection: Some(23..47),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_bitbucket_permalink_with_multi_line_selection() {
+        let permalink = Bitbucket::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "f00b4r",
+                    path: "main.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .await;
 
         let expected_url =
             "https://bitbucket.org/zed-industries/zed/src/f00b4r/main.rs#lines-24:48";
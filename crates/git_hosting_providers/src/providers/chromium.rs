@@ -102,7 +102,7 @@ impl GitHostingProvider for Chromium {
         format!("{start_line}")
     }
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
@@ -120,7 +120,7 @@ impl GitHostingProvider for Chromium {
         })
     }
 
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
@@ -131,7 +131,7 @@ impl GitHostingProvider for Chromium {
         self.base_url().join(&format!("{repo}/+/{sha}")).unwrap()
     }
 
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
         let ParsedGitRemote { owner: _, repo } = remote;
         let BuildPermalinkParams {
             sha,
@@ -151,7 +151,11 @@ impl GitHostingProvider for Chromium {
         permalink
     }
 
-    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+    async fn extract_pull_request(
+        &self,
+        remote: &ParsedGitRemote,
+        message: &str,
+    ) -> Option<PullRequest> {
         let capture = pull_request_regex().captures(message)?;
         let url = Url::parse(capture.get(1)?.as_str()).unwrap();
         let repo = capture.get(2)?.as_str();
@@ -196,10 +200,11 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_parse_remote_url_given_https_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url() {
         let parsed_remote = Chromium
             .parse_remote_url("https://chromium.googlesource.com/chromium/src")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -211,69 +216,80 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_chromium_permalink() {
-        let permalink = Chromium.build_permalink(
-            ParsedGitRemote {
-                owner: Arc::from(""),
-                repo: "chromium/src".into(),
-            },
-            BuildPermalinkParams {
-                sha: "fea5080b182fc92e3be0c01c5dece602fe70b588",
-                path: "ui/base/cursor/cursor.h",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_chromium_permalink() {
+        let permalink = Chromium
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: Arc::from(""),
+                    repo: "chromium/src".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "fea5080b182fc92e3be0c01c5dece602fe70b588",
+                    path: "ui/base/cursor/cursor.h",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://chromium.googlesource.com/chromium/src/+/fea5080b182fc92e3be0c01c5dece602fe70b588/ui/base/cursor/cursor.h";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_chromium_permalink_with_single_line_selection() {
-        let permalink = Chromium.build_permalink(
-            ParsedGitRemote {
-                owner: Arc::from(""),
-                repo: "chromium/src".into(),
-            },
-            BuildPermalinkParams {
-                sha: "fea5080b182fc92e3be0c01c5dece602fe70b588",
-                path: "ui/base/cursor/cursor.h",
-                selection: Some(18..18),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_chromium_permalink_with_single_line_selection() {
+        let permalink = Chromium
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: Arc::from(""),
+                    repo: "chromium/src".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "fea5080b182fc92e3be0c01c5dece602fe70b588",
+                    path: "ui/base/cursor/cursor.h",
+                    selection: Some(18..18),
+                },
+            )
+            .await;
 
         let expected_url = "https://chromium.googlesource.com/chromium/src/+/fea5080b182fc92e3be0c01c5dece602fe70b588/ui/base/cursor/cursor.h#19";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_chromium_permalink_with_multi_line_selection() {
-        let permalink = Chromium.build_permalink(
-            ParsedGitRemote {
-                owner: Arc::from(""),
-                repo: "chromium/src".into(),
-            },
-            BuildPermalinkParams {
-                sha: "fea5080b182fc92e3be0c01c5dece602fe70b588",
-                path: "ui/base/cursor/cursor.h",
-                selection: Some(18..30),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_chromium_permalink_with_multi_line_selection() {
+        let permalink = Chromium
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: Arc::from(""),
+                    repo: "chromium/src".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "fea5080b182fc92e3be0c01c5dece602fe70b588",
+                    path: "ui/base/cursor/cursor.h",
+                    selection: Some(18..30),
+                },
+            )
+            .await;
 
         let expected_url = "https://chromium.googlesource.com/chromium/src/+/fea5080b182fc92e3be0c01c5dece602fe70b588/ui/base/cursor/cursor.h#19";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_chromium_pull_requests() {
+    #[gpui::test]
+    async fn test_chromium_pull_requests() {
         let remote = ParsedGitRemote {
             owner: Arc::from(""),
             repo: "chromium/src".into(),
         };
 
         let message = "This does not contain a pull request";
-        assert!(Chromium.extract_pull_request(&remote, message).is_none());
+        assert!(
+            Chromium
+                .extract_pull_request(&remote, message)
+                .await
+                .is_none()
+        );
 
         // Pull request number at end of "Reviewed-on:" line
         let message = indoc! {r#"
@@ -293,6 +309,7 @@ mod tests {
         assert_eq!(
             Chromium
                 .extract_pull_request(&remote, &message)
+                .await
                 .unwrap()
                 .url
                 .as_str(),
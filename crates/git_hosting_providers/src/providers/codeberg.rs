@@ -106,7 +106,7 @@ impl GitHostingProvider for Codeberg {
         format!("L{start_line}-L{end_line}")
     }
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
@@ -124,7 +124,7 @@ impl GitHostingProvider for Codeberg {
         })
     }
 
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
@@ -137,7 +137,7 @@ impl GitHostingProvider for Codeberg {
             .unwrap()
     }
 
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
         let ParsedGitRemote { owner, repo } = remote;
         let BuildPermalinkParams {
             sha,
@@ -180,10 +180,11 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_parse_remote_url_given_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_ssh_url() {
         let parsed_remote = Codeberg
             .parse_remote_url("git@codeberg.org:zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -195,10 +196,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url() {
         let parsed_remote = Codeberg
             .parse_remote_url("https://codeberg.org/zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -210,55 +212,61 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_codeberg_permalink() {
-        let permalink = Codeberg.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_codeberg_permalink() {
+        let permalink = Codeberg
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://codeberg.org/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_codeberg_permalink_with_single_line_selection() {
-        let permalink = Codeberg.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(6..6),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_codeberg_permalink_with_single_line_selection() {
+        let permalink = Codeberg
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(6..6),
+                },
+            )
+            .await;
 
         let expected_url = "https://codeberg.org/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L7";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_codeberg_permalink_with_multi_line_selection() {
-        let permalink = Codeberg.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(23..47),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_codeberg_permalink_with_multi_line_selection() {
+        let permalink = Codeberg
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .await;
 
         let expected_url = "https://codeberg.org/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L24-L48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
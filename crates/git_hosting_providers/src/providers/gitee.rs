@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use async_trait::async_trait;
 use url::Url;
 
 use git::{
@@ -9,6 +10,7 @@ use git::{
 
 pub struct Gitee;
 
+#[async_trait]
 impl GitHostingProvider for Gitee {
     fn name(&self) -> String {
         "Gitee".to_string()
@@ -30,7 +32,7 @@ impl GitHostingProvider for Gitee {
         format!("L{start_line}-{end_line}")
     }
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
@@ -48,7 +50,7 @@ impl GitHostingProvider for Gitee {
         })
     }
 
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
@@ -61,7 +63,7 @@ impl GitHostingProvider for Gitee {
             .unwrap()
     }
 
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
         let ParsedGitRemote { owner, repo } = remote;
         let BuildPermalinkParams {
             sha,
@@ -88,10 +90,11 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_parse_remote_url_given_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_ssh_url() {
         let parsed_remote = Gitee
             .parse_remote_url("git@gitee.com:zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -103,10 +106,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url() {
         let parsed_remote = Gitee
             .parse_remote_url("https://gitee.com/zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -118,55 +122,61 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_gitee_permalink() {
-        let permalink = Gitee.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e5fe811d7ad0fc26934edd76f891d20bdc3bb194",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_gitee_permalink() {
+        let permalink = Gitee
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e5fe811d7ad0fc26934edd76f891d20bdc3bb194",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://gitee.com/zed-industries/zed/blob/e5fe811d7ad0fc26934edd76f891d20bdc3bb194/crates/editor/src/git/permalink.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_gitee_permalink_with_single_line_selection() {
-        let permalink = Gitee.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e5fe811d7ad0fc26934edd76f891d20bdc3bb194",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(6..6),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_gitee_permalink_with_single_line_selection() {
+        let permalink = Gitee
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e5fe811d7ad0fc26934edd76f891d20bdc3bb194",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(6..6),
+                },
+            )
+            .await;
 
         let expected_url = "https://gitee.com/zed-industries/zed/blob/e5fe811d7ad0fc26934edd76f891d20bdc3bb194/crates/editor/src/git/permalink.rs#L7";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_gitee_permalink_with_multi_line_selection() {
-        let permalink = Gitee.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e5fe811d7ad0fc26934edd76f891d20bdc3bb194",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(23..47),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_gitee_permalink_with_multi_line_selection() {
+        let permalink = Gitee
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e5fe811d7ad0fc26934edd76f891d20bdc3bb194",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .await;
 
         let expected_url = "https://gitee.com/zed-industries/zed/blob/e5fe811d7ad0fc26934edd76f891d20bdc3bb194/crates/editor/src/git/permalink.rs#L24-48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
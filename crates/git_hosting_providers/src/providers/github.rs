@@ -150,7 +150,7 @@ impl GitHostingProvider for Github {
         format!("L{start_line}-L{end_line}")
     }
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
@@ -168,7 +168,7 @@ impl GitHostingProvider for Github {
         })
     }
 
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
@@ -181,7 +181,7 @@ impl GitHostingProvider for Github {
             .unwrap()
     }
 
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
         let ParsedGitRemote { owner, repo } = remote;
         let BuildPermalinkParams {
             sha,
@@ -204,7 +204,11 @@ impl GitHostingProvider for Github {
         permalink
     }
 
-    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+    async fn extract_pull_request(
+        &self,
+        remote: &ParsedGitRemote,
+        message: &str,
+    ) -> Option<PullRequest> {
         let line = message.lines().next()?;
         let capture = pull_request_number_regex().captures(line)?;
         let number = capture.get(1)?.as_str().parse::<u32>().ok()?;
@@ -277,12 +281,13 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_self_hosted_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_self_hosted_ssh_url() {
         let remote_url = "git@github.my-enterprise.com:zed-industries/zed.git";
         let parsed_remote = Github::from_remote_url(remote_url)
             .unwrap()
             .parse_remote_url(remote_url)
+            .await
             .unwrap();
 
         assert_eq!(
@@ -294,12 +299,13 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_self_hosted_https_url_with_subgroup() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_self_hosted_https_url_with_subgroup() {
         let remote_url = "https://github.my-enterprise.com/zed-industries/zed.git";
         let parsed_remote = Github::from_remote_url(remote_url)
             .unwrap()
             .parse_remote_url(remote_url)
+            .await
             .unwrap();
 
         assert_eq!(
@@ -311,10 +317,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_ssh_url() {
         let parsed_remote = Github::public_instance()
             .parse_remote_url("git@github.com:zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -326,10 +333,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url() {
         let parsed_remote = Github::public_instance()
             .parse_remote_url("https://github.com/zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -341,10 +349,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url_with_username() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url_with_username() {
         let parsed_remote = Github::public_instance()
             .parse_remote_url("https://jlannister@github.com/some-org/some-repo.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -356,81 +365,89 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_github_permalink_from_ssh_url() {
+    #[gpui::test]
+    async fn test_build_github_permalink_from_ssh_url() {
         let remote = ParsedGitRemote {
             owner: "zed-industries".into(),
             repo: "zed".into(),
         };
-        let permalink = Github::public_instance().build_permalink(
-            remote,
-            BuildPermalinkParams {
-                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: None,
-            },
-        );
+        let permalink = Github::public_instance()
+            .build_permalink(
+                remote,
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://github.com/zed-industries/zed/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_github_permalink() {
-        let permalink = Github::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "b2efec9824c45fcc90c9a7eb107a50d1772a60aa",
-                path: "crates/zed/src/main.rs",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_github_permalink() {
+        let permalink = Github::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "b2efec9824c45fcc90c9a7eb107a50d1772a60aa",
+                    path: "crates/zed/src/main.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://github.com/zed-industries/zed/blob/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/zed/src/main.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_github_permalink_with_single_line_selection() {
-        let permalink = Github::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(6..6),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_github_permalink_with_single_line_selection() {
+        let permalink = Github::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(6..6),
+                },
+            )
+            .await;
 
         let expected_url = "https://github.com/zed-industries/zed/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L7";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_github_permalink_with_multi_line_selection() {
-        let permalink = Github::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(23..47),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_github_permalink_with_multi_line_selection() {
+        let permalink = Github::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .await;
 
         let expected_url = "https://github.com/zed-industries/zed/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L24-L48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_github_pull_requests() {
+    #[gpui::test]
+    async fn test_github_pull_requests() {
         let remote = ParsedGitRemote {
             owner: "zed-industries".into(),
             repo: "zed".into(),
@@ -438,7 +455,12 @@ mod tests {
 
         let github = Github::public_instance();
         let message = "This does not contain a pull request";
-        assert!(github.extract_pull_request(&remote, message).is_none());
+        assert!(
+            github
+                .extract_pull_request(&remote, message)
+                .await
+                .is_none()
+        );
 
         // Pull request number at end of first line
         let message = indoc! {r#"
@@ -455,6 +477,7 @@ mod tests {
         assert_eq!(
             github
                 .extract_pull_request(&remote, &message)
+                .await
                 .unwrap()
                 .url
                 .as_str(),
@@ -468,6 +491,6 @@ mod tests {
             See the original PR, this is a fix.
             "#
         };
-        assert_eq!(github.extract_pull_request(&remote, &message), None);
+        assert_eq!(github.extract_pull_request(&remote, &message).await, None);
     }
 }
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use anyhow::{Result, bail};
+use async_trait::async_trait;
 use url::Url;
 
 use git::{
@@ -48,6 +49,7 @@ impl Gitlab {
     }
 }
 
+#[async_trait]
 impl GitHostingProvider for Gitlab {
     fn name(&self) -> String {
         self.name.clone()
@@ -69,7 +71,7 @@ impl GitHostingProvider for Gitlab {
         format!("L{start_line}-{end_line}")
     }
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
@@ -87,7 +89,7 @@ impl GitHostingProvider for Gitlab {
         })
     }
 
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
@@ -100,7 +102,7 @@ impl GitHostingProvider for Gitlab {
             .unwrap()
     }
 
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
         let ParsedGitRemote { owner, repo } = remote;
         let BuildPermalinkParams {
             sha,
@@ -137,10 +139,11 @@ mod tests {
         assert!(github.is_err());
     }
 
-    #[test]
-    fn test_parse_remote_url_given_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_ssh_url() {
         let parsed_remote = Gitlab::public_instance()
             .parse_remote_url("git@gitlab.com:zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -152,10 +155,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url() {
         let parsed_remote = Gitlab::public_instance()
             .parse_remote_url("https://gitlab.com/zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -167,13 +171,14 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_self_hosted_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_self_hosted_ssh_url() {
         let remote_url = "git@gitlab.my-enterprise.com:zed-industries/zed.git";
 
         let parsed_remote = Gitlab::from_remote_url(remote_url)
             .unwrap()
             .parse_remote_url(remote_url)
+            .await
             .unwrap();
 
         assert_eq!(
@@ -185,12 +190,13 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_self_hosted_https_url_with_subgroup() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_self_hosted_https_url_with_subgroup() {
         let remote_url = "https://gitlab.my-enterprise.com/group/subgroup/zed.git";
         let parsed_remote = Gitlab::from_remote_url(remote_url)
             .unwrap()
             .parse_remote_url(remote_url)
+            .await
             .unwrap();
 
         assert_eq!(
@@ -202,97 +208,107 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_gitlab_permalink() {
-        let permalink = Gitlab::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_gitlab_permalink() {
+        let permalink = Gitlab::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://gitlab.com/zed-industries/zed/-/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_gitlab_permalink_with_single_line_selection() {
-        let permalink = Gitlab::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(6..6),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_gitlab_permalink_with_single_line_selection() {
+        let permalink = Gitlab::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(6..6),
+                },
+            )
+            .await;
 
         let expected_url = "https://gitlab.com/zed-industries/zed/-/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L7";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_gitlab_permalink_with_multi_line_selection() {
-        let permalink = Gitlab::public_instance().build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(23..47),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_gitlab_permalink_with_multi_line_selection() {
+        let permalink = Gitlab::public_instance()
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .await;
 
         let expected_url = "https://gitlab.com/zed-industries/zed/-/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L24-48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_gitlab_self_hosted_permalink_from_ssh_url() {
+    #[gpui::test]
+    async fn test_build_gitlab_self_hosted_permalink_from_ssh_url() {
         let gitlab =
             Gitlab::from_remote_url("git@gitlab.some-enterprise.com:zed-industries/zed.git")
                 .unwrap();
-        let permalink = gitlab.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: None,
-            },
-        );
+        let permalink = gitlab
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://gitlab.some-enterprise.com/zed-industries/zed/-/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_gitlab_self_hosted_permalink_from_https_url() {
+    #[gpui::test]
+    async fn test_build_gitlab_self_hosted_permalink_from_https_url() {
         let gitlab =
             Gitlab::from_remote_url("https://gitlab-instance.big-co.com/zed-industries/zed.git")
                 .unwrap();
-        let permalink = gitlab.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "b2efec9824c45fcc90c9a7eb107a50d1772a60aa",
-                path: "crates/zed/src/main.rs",
-                selection: None,
-            },
-        );
+        let permalink = gitlab
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "b2efec9824c45fcc90c9a7eb107a50d1772a60aa",
+                    path: "crates/zed/src/main.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://gitlab-instance.big-co.com/zed-industries/zed/-/blob/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/zed/src/main.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
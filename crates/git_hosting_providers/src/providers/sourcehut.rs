@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use async_trait::async_trait;
 use url::Url;
 
 use git::{
@@ -9,6 +10,7 @@ use git::{
 
 pub struct Sourcehut;
 
+#[async_trait]
 impl GitHostingProvider for Sourcehut {
     fn name(&self) -> String {
         "SourceHut".to_string()
@@ -30,7 +32,7 @@ impl GitHostingProvider for Sourcehut {
         format!("L{start_line}-{end_line}")
     }
 
-    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+    async fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
@@ -53,7 +55,7 @@ impl GitHostingProvider for Sourcehut {
         })
     }
 
-    fn build_commit_permalink(
+    async fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
@@ -66,7 +68,7 @@ impl GitHostingProvider for Sourcehut {
             .unwrap()
     }
 
-    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+    async fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
         let ParsedGitRemote { owner, repo } = remote;
         let BuildPermalinkParams {
             sha,
@@ -93,10 +95,11 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_parse_remote_url_given_ssh_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_ssh_url() {
         let parsed_remote = Sourcehut
             .parse_remote_url("git@git.sr.ht:~zed-industries/zed")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -108,10 +111,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_ssh_url_with_git_suffix() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_ssh_url_with_git_suffix() {
         let parsed_remote = Sourcehut
             .parse_remote_url("git@git.sr.ht:~zed-industries/zed.git")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -123,10 +127,11 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_parse_remote_url_given_https_url() {
+    #[gpui::test]
+    async fn test_parse_remote_url_given_https_url() {
         let parsed_remote = Sourcehut
             .parse_remote_url("https://git.sr.ht/~zed-industries/zed")
+            .await
             .unwrap();
 
         assert_eq!(
@@ -138,73 +143,81 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_sourcehut_permalink() {
-        let permalink = Sourcehut.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_sourcehut_permalink() {
+        let permalink = Sourcehut
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://git.sr.ht/~zed-industries/zed/tree/faa6f979be417239b2e070dbbf6392b909224e0b/item/crates/editor/src/git/permalink.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_sourcehut_permalink_with_git_suffix() {
-        let permalink = Sourcehut.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed.git".into(),
-            },
-            BuildPermalinkParams {
-                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: None,
-            },
-        );
+    #[gpui::test]
+    async fn test_build_sourcehut_permalink_with_git_suffix() {
+        let permalink = Sourcehut
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed.git".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: None,
+                },
+            )
+            .await;
 
         let expected_url = "https://git.sr.ht/~zed-industries/zed.git/tree/faa6f979be417239b2e070dbbf6392b909224e0b/item/crates/editor/src/git/permalink.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_sourcehut_permalink_with_single_line_selection() {
-        let permalink = Sourcehut.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(6..6),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_sourcehut_permalink_with_single_line_selection() {
+        let permalink = Sourcehut
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(6..6),
+                },
+            )
+            .await;
 
         let expected_url = "https://git.sr.ht/~zed-industries/zed/tree/faa6f979be417239b2e070dbbf6392b909224e0b/item/crates/editor/src/git/permalink.rs#L7";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
-    #[test]
-    fn test_build_sourcehut_permalink_with_multi_line_selection() {
-        let permalink = Sourcehut.build_permalink(
-            ParsedGitRemote {
-                owner: "zed-industries".into(),
-                repo: "zed".into(),
-            },
-            BuildPermalinkParams {
-                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
-                path: "crates/editor/src/git/permalink.rs",
-                selection: Some(23..47),
-            },
-        );
+    #[gpui::test]
+    async fn test_build_sourcehut_permalink_with_multi_line_selection() {
+        let permalink = Sourcehut
+            .build_permalink(
+                ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .await;
 
         let expected_url = "https://git.sr.ht/~zed-industries/zed/tree/faa6f979be417239b2e070dbbf6392b909224e0b/item/crates/editor/src/git/permalink.rs#L24-48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
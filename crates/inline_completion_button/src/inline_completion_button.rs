@@ -224,6 +224,32 @@ impl Render for InlineCompletionButton {
                 );
             }
 
+            EditPredictionProvider::Extension => {
+                let icon = if self.editor_enabled.unwrap_or(true) {
+                    IconName::ZedPredict
+                } else {
+                    IconName::ZedPredictDisabled
+                };
+                let this = cx.entity().clone();
+
+                div().child(
+                    PopoverMenu::new("extension-edit-prediction")
+                        .menu(move |window, cx| {
+                            Some(this.update(cx, |this, cx| {
+                                this.build_extension_context_menu(window, cx)
+                            }))
+                        })
+                        .anchor(Corner::BottomRight)
+                        .trigger_with_tooltip(
+                            IconButton::new("extension-edit-prediction-icon", icon),
+                            |window, cx| {
+                                Tooltip::for_action("Edit Prediction", &ToggleMenu, window, cx)
+                            },
+                        )
+                        .with_handle(self.popover_menu_handle.clone()),
+                )
+            }
+
             EditPredictionProvider::Zed => {
                 let enabled = self.editor_enabled.unwrap_or(true);
 
@@ -690,6 +716,16 @@ impl InlineCompletionButton {
         })
     }
 
+    fn build_extension_context_menu(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ContextMenu> {
+        ContextMenu::build(window, cx, |menu, window, cx| {
+            self.build_language_settings_menu(menu, window, cx)
+        })
+    }
+
     fn build_zeta_context_menu(
         &self,
         window: &mut Window,
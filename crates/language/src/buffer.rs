@@ -60,7 +60,7 @@ pub use text::{
     Anchor, Bias, Buffer as TextBuffer, BufferId, BufferSnapshot as TextBufferSnapshot, Edit,
     LineIndent, OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection,
     SelectionGoal, Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint,
-    ToPointUtf16, Transaction, TransactionId, Unclipped,
+    ToPointUtf16, Transaction, TransactionId, Unclipped, UndoTree, UndoTreeNode,
 };
 use theme::{ActiveTheme as _, SyntaxTheme};
 #[cfg(any(test, feature = "test-support"))]
@@ -2731,6 +2731,25 @@ impl Buffer {
         redone
     }
 
+    /// Moves the buffer to the given state of its undo tree, which may be on a different branch
+    /// than the current one.
+    pub fn go_to_undo_tree_state(
+        &mut self,
+        state: Option<TransactionId>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        if let Some(operation) = self.text.go_to_undo_tree_state(state) {
+            self.send_operation(Operation::Buffer(operation), true, cx);
+            self.did_edit(&old_version, was_dirty, cx);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Override current completion triggers with the user-provided completion triggers.
     pub fn set_completion_triggers(
         &mut self,
@@ -4,11 +4,12 @@ use crate::{
     language_settings::{
         AllLanguageSettingsContent, LanguageSettingsContent, all_language_settings,
     },
-    task_context::ContextProvider,
+    task_context::{CombinedContextProvider, ContextProvider},
+    toolchain::CombinedToolchainLister,
     with_parser,
 };
 use anyhow::{Context as _, Result, anyhow};
-use collections::{FxHashMap, HashMap, HashSet, hash_map};
+use collections::{BTreeMap, FxHashMap, HashMap, HashSet, hash_map};
 
 use futures::{
     Future,
@@ -120,6 +121,8 @@ struct LanguageRegistryState {
     all_lsp_adapters: HashMap<LanguageServerName, Arc<CachedLspAdapter>>,
     available_lsp_adapters:
         HashMap<LanguageServerName, Arc<dyn Fn() -> Arc<CachedLspAdapter> + 'static + Send + Sync>>,
+    context_providers: HashMap<LanguageName, BTreeMap<Arc<str>, Arc<dyn ContextProvider>>>,
+    toolchain_listers: HashMap<LanguageName, BTreeMap<Arc<str>, Arc<dyn ToolchainLister>>>,
     loading_languages: HashMap<LanguageId, Vec<oneshot::Sender<Result<Arc<Language>>>>>,
     subscription: (watch::Sender<()>, watch::Receiver<()>),
     theme: Option<Arc<Theme>>,
@@ -274,6 +277,8 @@ impl LanguageRegistry {
                 lsp_adapters: Default::default(),
                 all_lsp_adapters: Default::default(),
                 available_lsp_adapters: HashMap::default(),
+                context_providers: HashMap::default(),
+                toolchain_listers: HashMap::default(),
                 subscription: watch::channel(),
                 theme: Default::default(),
                 version: 0,
@@ -339,6 +344,70 @@ impl LanguageRegistry {
         *state.subscription.0.borrow_mut() = ();
     }
 
+    /// Registers a context provider for the given language, identified by the given ID.
+    ///
+    /// Its context and tasks are added to the ones of the context provider the language is
+    /// loaded with.
+    pub fn register_context_provider(
+        &self,
+        language_name: LanguageName,
+        id: Arc<str>,
+        context_provider: Arc<dyn ContextProvider>,
+    ) {
+        let mut state = self.state.write();
+        state
+            .context_providers
+            .entry(language_name.clone())
+            .or_default()
+            .insert(id, context_provider);
+        state.reload_language(&language_name);
+    }
+
+    pub fn remove_context_provider(&self, language_name: &LanguageName, id: &str) {
+        let mut state = self.state.write();
+        let Some(providers) = state.context_providers.get_mut(language_name) else {
+            return;
+        };
+        if providers.remove(id).is_some() {
+            if providers.is_empty() {
+                state.context_providers.remove(language_name);
+            }
+            state.reload_language(language_name);
+        }
+    }
+
+    /// Registers a toolchain lister for the given language, identified by the given ID.
+    ///
+    /// Its toolchains are listed after the ones of the toolchain lister the language is loaded
+    /// with.
+    pub fn register_toolchain_lister(
+        &self,
+        language_name: LanguageName,
+        id: Arc<str>,
+        toolchain_lister: Arc<dyn ToolchainLister>,
+    ) {
+        let mut state = self.state.write();
+        state
+            .toolchain_listers
+            .entry(language_name.clone())
+            .or_default()
+            .insert(id, toolchain_lister);
+        state.reload_language(&language_name);
+    }
+
+    pub fn remove_toolchain_lister(&self, language_name: &LanguageName, id: &str) {
+        let mut state = self.state.write();
+        let Some(listers) = state.toolchain_listers.get_mut(language_name) else {
+            return;
+        };
+        if listers.remove(id).is_some() {
+            if listers.is_empty() {
+                state.toolchain_listers.remove(language_name);
+            }
+            state.reload_language(language_name);
+        }
+    }
+
     #[cfg(any(feature = "test-support", test))]
     pub fn register_test_language(&self, config: LanguageConfig) {
         self.register_language(
@@ -928,7 +997,30 @@ impl LanguageRegistry {
                 self.executor
                     .spawn(async move {
                         let language = async {
-                            let loaded_language = (language_load)()?;
+                            let mut loaded_language = (language_load)()?;
+                            {
+                                let state = this.state.read();
+                                if let Some(providers) = state.context_providers.get(&name) {
+                                    let providers = loaded_language
+                                        .context_provider
+                                        .take()
+                                        .into_iter()
+                                        .chain(providers.values().cloned())
+                                        .collect();
+                                    loaded_language.context_provider =
+                                        Some(Arc::new(CombinedContextProvider(providers)));
+                                }
+                                if let Some(listers) = state.toolchain_listers.get(&name) {
+                                    let listers = loaded_language
+                                        .toolchain_provider
+                                        .take()
+                                        .into_iter()
+                                        .chain(listers.values().cloned())
+                                        .collect();
+                                    loaded_language.toolchain_provider =
+                                        Some(Arc::new(CombinedToolchainLister(listers)));
+                                }
+                            }
                             if let Some(grammar) = loaded_language.config.grammar.clone() {
                                 let grammar = Some(this.get_or_load_grammar(grammar).await?);
 
@@ -1196,6 +1288,20 @@ impl LanguageRegistryState {
         *self.subscription.0.borrow_mut() = ();
     }
 
+    /// Unloads the given language, so that it is loaded again the next time it is used.
+    fn reload_language(&mut self, language_name: &LanguageName) {
+        self.languages
+            .retain(|language| &language.name() != language_name);
+        for language in &mut self.available_languages {
+            if &language.name == language_name {
+                language.loaded = false;
+            }
+        }
+        self.version += 1;
+        self.reload_count += 1;
+        *self.subscription.0.borrow_mut() = ();
+    }
+
     /// Reorders the list of language servers for the given language.
     ///
     /// Uses the provided list of ordered [`CachedLspAdapters`] as the desired order.
@@ -216,6 +216,8 @@ pub enum EditPredictionProvider {
     Copilot,
     Supermaven,
     Zed,
+    /// Use an edit prediction provider from an extension, as set by `edit_predictions.extension_provider`.
+    Extension,
 }
 
 impl EditPredictionProvider {
@@ -224,7 +226,8 @@ impl EditPredictionProvider {
             EditPredictionProvider::Zed => true,
             EditPredictionProvider::None
             | EditPredictionProvider::Copilot
-            | EditPredictionProvider::Supermaven => false,
+            | EditPredictionProvider::Supermaven
+            | EditPredictionProvider::Extension => false,
         }
     }
 }
@@ -243,6 +246,8 @@ pub struct EditPredictionSettings {
     pub mode: EditPredictionsMode,
     /// Settings specific to GitHub Copilot.
     pub copilot: CopilotSettings,
+    /// The ID of the extension-provided edit prediction provider to use.
+    pub extension_provider: Option<Arc<str>>,
     /// Whether edit predictions are enabled in the assistant panel.
     /// This setting has no effect if globally disabled.
     pub enabled_in_text_threads: bool,
@@ -591,6 +596,12 @@ pub struct EditPredictionSettingsContent {
     /// Settings specific to GitHub Copilot.
     #[serde(default)]
     pub copilot: CopilotSettingsContent,
+    /// The ID of the extension-provided edit prediction provider to use
+    /// when the edit prediction provider is set to `extension`.
+    ///
+    /// Default: none
+    #[serde(default)]
+    pub extension_provider: Option<Arc<str>>,
     /// Whether edit predictions are enabled in the assistant prompt editor.
     /// This has no effect if globally disabled.
     #[serde(default = "default_true")]
@@ -914,6 +925,11 @@ pub enum Formatter {
         /// The arguments to pass to the program.
         arguments: Option<Arc<[String]>>,
     },
+    /// Format code using a formatter provided by an extension.
+    Extension {
+        /// The ID of the formatter, as declared in the extension's manifest.
+        name: Arc<str>,
+    },
     /// Files should be formatted using code actions executed by language servers.
     CodeActions(HashMap<String, bool>),
 }
@@ -1242,6 +1258,11 @@ impl settings::Settings for AllLanguageSettings {
             })
             .unwrap_or_default();
 
+        let mut extension_edit_prediction_provider = default_value
+            .edit_predictions
+            .as_ref()
+            .and_then(|settings| settings.extension_provider.clone());
+
         let mut enabled_in_text_threads = default_value
             .edit_predictions
             .as_ref()
@@ -1302,6 +1323,14 @@ impl settings::Settings for AllLanguageSettings {
                 copilot_settings.enterprise_uri = Some(enterprise_uri);
             }
 
+            if let Some(extension_provider) = user_settings
+                .edit_predictions
+                .as_ref()
+                .and_then(|settings| settings.extension_provider.clone())
+            {
+                extension_edit_prediction_provider = Some(extension_provider);
+            }
+
             // A user's global settings override the default global settings and
             // all default language-specific settings.
             merge_settings(&mut defaults, &user_settings.defaults);
@@ -1358,6 +1387,7 @@ impl settings::Settings for AllLanguageSettings {
                     .collect(),
                 mode: edit_predictions_mode,
                 copilot: copilot_settings,
+                extension_provider: extension_edit_prediction_provider,
                 enabled_in_text_threads,
             },
             defaults,
@@ -58,3 +58,79 @@ pub struct ContextLocation<'a> {
     pub worktree_root: Option<PathBuf>,
     pub file_location: &'a Location,
 }
+
+/// A [`ContextProvider`] that combines the context and tasks of several others, such as the one a
+/// language is loaded with and the ones registered for it by extensions.
+pub(crate) struct CombinedContextProvider(pub(crate) Vec<Arc<dyn ContextProvider>>);
+
+impl ContextProvider for CombinedContextProvider {
+    fn build_context(
+        &self,
+        variables: &TaskVariables,
+        location: ContextLocation<'_>,
+        project_env: Option<HashMap<String, String>>,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut App,
+    ) -> Task<Result<TaskVariables>> {
+        let providers = self.0.clone();
+        let mut variables = variables.clone();
+        let fs = location.fs;
+        let worktree_root = location.worktree_root;
+        let file_location = location.file_location.clone();
+        cx.spawn(async move |cx| {
+            // Each provider sees the variables provided by the ones before it.
+            let mut context = TaskVariables::default();
+            for provider in providers {
+                let provided = cx
+                    .update(|cx| {
+                        provider.build_context(
+                            &variables,
+                            ContextLocation {
+                                fs: fs.clone(),
+                                worktree_root: worktree_root.clone(),
+                                file_location: &file_location,
+                            },
+                            project_env.clone(),
+                            toolchains.clone(),
+                            cx,
+                        )
+                    })?
+                    .await?;
+                variables.extend(provided.clone());
+                context.extend(provided);
+            }
+            Ok(context)
+        })
+    }
+
+    fn associated_tasks(
+        &self,
+        fs: Arc<dyn Fs>,
+        file: Option<Arc<dyn File>>,
+        cx: &App,
+    ) -> Task<Option<TaskTemplates>> {
+        let tasks = self
+            .0
+            .iter()
+            .map(|provider| provider.associated_tasks(fs.clone(), file.clone(), cx))
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let mut templates = None;
+            for task in tasks {
+                if let Some(provided) = task.await {
+                    templates
+                        .get_or_insert_with(TaskTemplates::default)
+                        .0
+                        .extend(provided.0);
+                }
+            }
+            templates
+        })
+    }
+
+    fn lsp_task_source(&self) -> Option<LanguageServerName> {
+        self.0
+            .iter()
+            .find_map(|provider| provider.lsp_task_source())
+    }
+}
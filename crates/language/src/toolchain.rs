@@ -91,3 +91,52 @@ impl ToolchainList {
             .cloned()
     }
 }
+
+/// A [`ToolchainLister`] that lists the toolchains of several others, such as the one a language
+/// is loaded with and the ones registered for it by extensions. The first one names the toolchains
+/// and their manifest.
+pub(crate) struct CombinedToolchainLister(pub(crate) Vec<Arc<dyn ToolchainLister>>);
+
+#[async_trait]
+impl ToolchainLister for CombinedToolchainLister {
+    async fn list(
+        &self,
+        worktree_root: PathBuf,
+        subroot_relative_path: Option<Arc<Path>>,
+        project_env: Option<HashMap<String, String>>,
+    ) -> ToolchainList {
+        let mut toolchains = Vec::new();
+        let mut default = None;
+        let mut groups = Vec::new();
+        for lister in &self.0 {
+            let list = lister
+                .list(
+                    worktree_root.clone(),
+                    subroot_relative_path.clone(),
+                    project_env.clone(),
+                )
+                .await;
+            let offset = toolchains.len();
+            default = default.or(list.default.map(|ix| ix + offset));
+            groups.extend(
+                list.groups
+                    .into_iter()
+                    .map(|(ix, group)| (ix + offset, group)),
+            );
+            toolchains.extend(list.toolchains);
+        }
+        ToolchainList {
+            toolchains,
+            default,
+            groups: groups.into(),
+        }
+    }
+
+    fn term(&self) -> SharedString {
+        self.0[0].term()
+    }
+
+    fn manifest_name(&self) -> ManifestName {
+        self.0[0].manifest_name()
+    }
+}
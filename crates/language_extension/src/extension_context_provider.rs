@@ -0,0 +1,317 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use collections::HashMap;
+use extension::{Extension, ExtensionTaskProviderProxy, TaskLocation};
+use fs::Fs;
+use gpui::{App, Task};
+use language::{ContextLocation, ContextProvider, File, LanguageName, LanguageToolchainStore};
+use task::{TaskTemplates, TaskVariables, VariableName};
+use util::ResultExt as _;
+
+use crate::{LanguageServerRegistryProxy, extension_provider_id};
+
+impl ExtensionTaskProviderProxy for LanguageServerRegistryProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        language: LanguageName,
+    ) {
+        self.language_registry.register_context_provider(
+            language.clone(),
+            extension_provider_id(&extension.manifest().id, &provider_id),
+            Arc::new(ExtensionContextProvider {
+                extension,
+                provider_id,
+                language,
+            }),
+        );
+    }
+
+    fn remove_task_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        language: &LanguageName,
+    ) {
+        self.language_registry.remove_context_provider(
+            language,
+            &extension_provider_id(&extension_id, &provider_id),
+        );
+    }
+}
+
+/// A [`ContextProvider`] backed by an extension's task provider.
+struct ExtensionContextProvider {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    language: LanguageName,
+}
+
+impl ContextProvider for ExtensionContextProvider {
+    fn build_context(
+        &self,
+        variables: &TaskVariables,
+        location: ContextLocation<'_>,
+        _project_env: Option<HashMap<String, String>>,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut App,
+    ) -> Task<Result<TaskVariables>> {
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        let language = self.language.clone();
+        let variables = variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect::<Vec<_>>();
+        let worktree_root = location
+            .worktree_root
+            .map(|worktree_root| worktree_root.to_string_lossy().into_owned());
+        let file = location.file_location.buffer.read(cx).file().cloned();
+        let worktree_id = file.as_ref().map(|file| file.worktree_id(cx));
+
+        cx.spawn(async move |cx| {
+            let active_toolchain_path = if let Some(worktree_id) = worktree_id {
+                let file_path = file
+                    .as_ref()
+                    .and_then(|file| file.path().parent())
+                    .map(Arc::from)
+                    .unwrap_or_else(|| Arc::from("".as_ref()));
+
+                toolchains
+                    .active_toolchain(worktree_id, file_path, language, cx)
+                    .await
+                    .map(|toolchain| toolchain.path.to_string())
+            } else {
+                None
+            };
+
+            let variables = extension
+                .task_variables(
+                    provider_id,
+                    variables,
+                    TaskLocation {
+                        worktree_root,
+                        active_toolchain_path,
+                    },
+                )
+                .await?;
+
+            Ok(variables
+                .into_iter()
+                .map(|(name, value)| (VariableName::Custom(name.into()), value))
+                .collect())
+        })
+    }
+
+    fn associated_tasks(
+        &self,
+        _: Arc<dyn Fs>,
+        file: Option<Arc<dyn File>>,
+        cx: &App,
+    ) -> Task<Option<TaskTemplates>> {
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        let path = file
+            .as_ref()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx).to_string_lossy().into_owned());
+
+        cx.background_spawn(async move {
+            let templates = extension
+                .task_templates(provider_id, path)
+                .await
+                .log_err()?;
+
+            Some(TaskTemplates(templates))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use async_trait::async_trait;
+    use extension::{FakeExtension, TaskTemplate};
+    use fs::FakeFs;
+    use gpui::{AppContext as _, AsyncApp, TestAppContext};
+    use language::{
+        Buffer, ContextLocation, LanguageConfig, LanguageRegistry, Location, TestFile, Toolchain,
+    };
+    use parking_lot::Mutex;
+    use settings::WorktreeId;
+    use task::TaskContext;
+
+    use super::*;
+
+    struct FakeToolchainStore;
+
+    #[async_trait(?Send)]
+    impl LanguageToolchainStore for FakeToolchainStore {
+        async fn active_toolchain(
+            self: Arc<Self>,
+            _: WorktreeId,
+            relative_path: Arc<Path>,
+            language_name: LanguageName,
+            _: &mut AsyncApp,
+        ) -> Option<Toolchain> {
+            assert_eq!(relative_path.as_ref(), Path::new("src"));
+            Some(Toolchain {
+                name: "Acme 1.0".into(),
+                path: "/toolchains/acme-1.0".into(),
+                language_name,
+                as_json: serde_json::Value::Null,
+            })
+        }
+    }
+
+    #[gpui::test]
+    async fn test_extension_context_provider(cx: &mut TestAppContext) {
+        let extension = Arc::new(FakeExtension::new("acme"));
+        extension.on_task_templates(|provider_id, path| {
+            assert_eq!(provider_id.as_ref(), "acme-tasks");
+            assert_eq!(path.as_deref(), Some("/project/src/main.acme"));
+            Ok(vec![TaskTemplate {
+                label: "check $ZED_FILENAME".into(),
+                command: "$ZED_CUSTOM_ACME_BIN".into(),
+                args: vec!["check".into(), "$ZED_FILE".into()],
+                ..Default::default()
+            }])
+        });
+        let task_locations = Arc::new(Mutex::new(Vec::new()));
+        extension.on_task_variables({
+            let task_locations = task_locations.clone();
+            move |_, variables, location| {
+                assert!(variables.contains(&("ZED_FILE".into(), "/project/src/main.acme".into())));
+                let acme_bin = format!(
+                    "{}/bin/acme",
+                    location.active_toolchain_path.as_ref().unwrap()
+                );
+                task_locations.lock().push(location);
+                Ok(vec![("ACME_BIN".into(), acme_bin)])
+            }
+        });
+
+        let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
+        language_registry.register_test_language(LanguageConfig {
+            name: "Acme".into(),
+            ..Default::default()
+        });
+        let proxy = LanguageServerRegistryProxy {
+            language_registry: language_registry.clone(),
+        };
+        proxy.register_task_provider(extension.clone(), "acme-tasks".into(), "Acme".into());
+        let context_provider = language_registry
+            .language_for_name("Acme")
+            .await
+            .unwrap()
+            .context_provider()
+            .unwrap();
+
+        let file: Arc<dyn File> = Arc::new(TestFile {
+            path: Path::new("src/main.acme").into(),
+            root_name: "project".into(),
+            local_root: Some(PathBuf::from("/")),
+        });
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local("main", cx);
+            buffer.file_updated(file.clone(), cx);
+            buffer
+        });
+        let range = buffer.read_with(cx, |buffer, _| {
+            buffer.anchor_before(0)..buffer.anchor_before(0)
+        });
+        let location = Location { buffer, range };
+
+        let templates = cx
+            .update(|cx| {
+                context_provider.associated_tasks(
+                    FakeFs::new(cx.background_executor().clone()),
+                    Some(file.clone()),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let variables = TaskVariables::from_iter([
+            (VariableName::File, "/project/src/main.acme".into()),
+            (VariableName::Filename, "main.acme".into()),
+        ]);
+        let extension_variables = cx
+            .update(|cx| {
+                context_provider.build_context(
+                    &variables,
+                    ContextLocation {
+                        fs: None,
+                        worktree_root: Some(PathBuf::from("/project")),
+                        file_location: &location,
+                    },
+                    None,
+                    Arc::new(FakeToolchainStore),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        let task_location = task_locations.lock().pop().unwrap();
+        assert_eq!(task_location.worktree_root.as_deref(), Some("/project"));
+        assert_eq!(
+            task_location.active_toolchain_path.as_deref(),
+            Some("/toolchains/acme-1.0")
+        );
+
+        let mut task_variables = variables;
+        task_variables.extend(extension_variables);
+        let task = templates.0[0]
+            .resolve_task(
+                "acme",
+                &TaskContext {
+                    task_variables,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(task.resolved_label, "check main.acme");
+        assert_eq!(task.resolved.command, "/toolchains/acme-1.0/bin/acme");
+        assert_eq!(task.resolved.args, ["check", "/project/src/main.acme"]);
+
+        // Providers of the same ID from other extensions add their tasks, and are removed on their own.
+        let other_extension = Arc::new(FakeExtension::new("other"));
+        other_extension.on_task_templates(|_, _| {
+            Ok(vec![TaskTemplate {
+                label: "lint".into(),
+                command: "lint".into(),
+                ..Default::default()
+            }])
+        });
+        proxy.register_task_provider(other_extension, "acme-tasks".into(), "Acme".into());
+        let task_labels = async |cx: &mut TestAppContext| {
+            let context_provider = language_registry
+                .language_for_name("Acme")
+                .await
+                .unwrap()
+                .context_provider()
+                .unwrap();
+            cx.update(|cx| {
+                context_provider.associated_tasks(
+                    FakeFs::new(cx.background_executor().clone()),
+                    Some(file.clone()),
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|template| template.label)
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(task_labels(cx).await, ["check $ZED_FILENAME", "lint"]);
+
+        proxy.remove_task_provider("acme".into(), "acme-tasks".into(), &"Acme".into());
+        assert_eq!(task_labels(cx).await, ["lint"]);
+    }
+}
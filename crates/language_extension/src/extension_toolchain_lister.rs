@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use collections::HashMap;
+use extension::{Extension, ExtensionToolchainProviderProxy, ToolchainProviderManifestEntry};
+use gpui::SharedString;
+use language::{LanguageName, ManifestName, Toolchain, ToolchainList, ToolchainLister};
+use util::ResultExt as _;
+
+use crate::{LanguageServerRegistryProxy, extension_provider_id};
+
+impl ExtensionToolchainProviderProxy for LanguageServerRegistryProxy {
+    fn register_toolchain_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: ToolchainProviderManifestEntry,
+        language: LanguageName,
+    ) {
+        self.language_registry.register_toolchain_lister(
+            language.clone(),
+            extension_provider_id(&extension.manifest().id, &provider_id),
+            Arc::new(ExtensionToolchainLister {
+                extension,
+                provider_id,
+                language,
+                term: provider.term.into(),
+                manifest_name: SharedString::from(provider.manifest_name).into(),
+            }),
+        );
+    }
+
+    fn remove_toolchain_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        language: &LanguageName,
+    ) {
+        self.language_registry.remove_toolchain_lister(
+            language,
+            &extension_provider_id(&extension_id, &provider_id),
+        );
+    }
+}
+
+/// A [`ToolchainLister`] backed by an extension's toolchain provider.
+struct ExtensionToolchainLister {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    language: LanguageName,
+    term: SharedString,
+    manifest_name: ManifestName,
+}
+
+#[async_trait]
+impl ToolchainLister for ExtensionToolchainLister {
+    async fn list(
+        &self,
+        worktree_root: PathBuf,
+        subroot_relative_path: Option<Arc<Path>>,
+        project_env: Option<HashMap<String, String>>,
+    ) -> ToolchainList {
+        let Some(list) = self
+            .extension
+            .list_toolchains(
+                self.provider_id.clone(),
+                worktree_root.to_string_lossy().into_owned(),
+                subroot_relative_path.map(|path| path.to_string_lossy().into_owned()),
+                project_env.unwrap_or_default().into_iter().collect(),
+            )
+            .await
+            .log_err()
+        else {
+            return ToolchainList::default();
+        };
+
+        let toolchains = list
+            .toolchains
+            .into_iter()
+            .map(|toolchain| Toolchain {
+                name: toolchain.name.into(),
+                path: toolchain.path.into(),
+                language_name: self.language.clone(),
+                as_json: toolchain
+                    .metadata
+                    .and_then(|metadata| serde_json::from_str(&metadata).log_err())
+                    .unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        let default = list.default_index.filter(|&index| index < toolchains.len());
+
+        ToolchainList {
+            toolchains,
+            default,
+            groups: Default::default(),
+        }
+    }
+
+    fn term(&self) -> SharedString {
+        self.term.clone()
+    }
+
+    fn manifest_name(&self) -> ManifestName {
+        self.manifest_name.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extension::{
+        FakeExtension, Toolchain as ExtensionToolchain, ToolchainList as ExtensionToolchainList,
+    };
+    use gpui::TestAppContext;
+    use language::{LanguageConfig, LanguageRegistry};
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_extension_toolchain_lister(cx: &mut TestAppContext) {
+        let extension = Arc::new(FakeExtension::new("acme"));
+        extension.on_list_toolchains(|provider_id, worktree_root, subroot_relative_path| {
+            assert_eq!(provider_id.as_ref(), "acme-toolchains");
+            assert_eq!(worktree_root, "/project");
+            assert_eq!(subroot_relative_path.as_deref(), Some("app"));
+            Ok(ExtensionToolchainList {
+                toolchains: vec![
+                    ExtensionToolchain {
+                        name: "Acme 1.0".into(),
+                        path: "/toolchains/acme-1.0".into(),
+                        metadata: Some(r#"{"version":"1.0"}"#.into()),
+                    },
+                    ExtensionToolchain {
+                        name: "Acme 2.0".into(),
+                        path: "/toolchains/acme-2.0".into(),
+                        metadata: Some("not json".into()),
+                    },
+                ],
+                default_index: Some(1),
+            })
+        });
+
+        let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
+        language_registry.register_test_language(LanguageConfig {
+            name: "Acme".into(),
+            ..Default::default()
+        });
+        let proxy = LanguageServerRegistryProxy {
+            language_registry: language_registry.clone(),
+        };
+        proxy.register_toolchain_provider(
+            extension.clone(),
+            "acme-toolchains".into(),
+            ToolchainProviderManifestEntry {
+                languages: vec!["Acme".into()],
+                term: "Acme Version".into(),
+                manifest_name: "acme.toml".into(),
+            },
+            "Acme".into(),
+        );
+        let lister = language_registry
+            .language_for_name("Acme")
+            .await
+            .unwrap()
+            .toolchain_lister()
+            .unwrap();
+        assert_eq!(lister.term().as_ref(), "Acme Version");
+        assert_eq!(
+            lister.manifest_name(),
+            ManifestName::from(SharedString::from("acme.toml"))
+        );
+
+        let list = lister
+            .list(
+                PathBuf::from("/project"),
+                Some(Path::new("app").into()),
+                None,
+            )
+            .await;
+        assert_eq!(
+            list.toolchains
+                .iter()
+                .map(|toolchain| (toolchain.name.as_ref(), toolchain.path.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                ("Acme 1.0", "/toolchains/acme-1.0"),
+                ("Acme 2.0", "/toolchains/acme-2.0")
+            ]
+        );
+        assert_eq!(list.toolchains[0].language_name, "Acme".into());
+        assert_eq!(
+            list.toolchains[0].as_json,
+            serde_json::json!({ "version": "1.0" })
+        );
+        // Invalid metadata is ignored rather than failing the whole list.
+        assert_eq!(list.toolchains[1].as_json, serde_json::Value::Null);
+        assert_eq!(list.default, Some(1));
+
+        // Toolchain providers that fail list no toolchains.
+        extension.on_list_toolchains(|_, _, _| anyhow::bail!("no toolchains"));
+        let list = lister.list(PathBuf::from("/project"), None, None).await;
+        assert!(list.toolchains.is_empty());
+        assert_eq!(list.default, None);
+    }
+}
@@ -1,4 +1,6 @@
+mod extension_context_provider;
 mod extension_lsp_adapter;
+mod extension_toolchain_lister;
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -14,7 +16,14 @@ pub fn init(
     let language_server_registry_proxy = LanguageServerRegistryProxy { language_registry };
     extension_host_proxy.register_grammar_proxy(language_server_registry_proxy.clone());
     extension_host_proxy.register_language_proxy(language_server_registry_proxy.clone());
-    extension_host_proxy.register_language_server_proxy(language_server_registry_proxy);
+    extension_host_proxy.register_language_server_proxy(language_server_registry_proxy.clone());
+    extension_host_proxy.register_task_provider_proxy(language_server_registry_proxy.clone());
+    extension_host_proxy.register_toolchain_provider_proxy(language_server_registry_proxy);
+}
+
+/// Identifies a provider of an extension among the ones registered for a language by any extension.
+fn extension_provider_id(extension_id: &str, provider_id: &str) -> Arc<str> {
+    format!("{extension_id}/{provider_id}").into()
 }
 
 #[derive(Clone)]
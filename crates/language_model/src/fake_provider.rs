@@ -1,8 +1,8 @@
 use crate::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
-    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice,
+    LanguageModelId, LanguageModelName, LanguageModelPricing, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelToolChoice,
 };
 use futures::{FutureExt, StreamExt, channel::mpsc, future::BoxFuture, stream::BoxStream};
 use gpui::{AnyView, App, AsyncApp, Entity, Task, Window};
@@ -91,10 +91,20 @@ pub struct ToolUseRequest {
 
 #[derive(Default)]
 pub struct FakeLanguageModel {
-    current_completion_txs: Mutex<Vec<(LanguageModelRequest, mpsc::UnboundedSender<String>)>>,
+    current_completion_txs: Mutex<
+        Vec<(
+            LanguageModelRequest,
+            mpsc::UnboundedSender<LanguageModelCompletionEvent>,
+        )>,
+    >,
+    pricing: Mutex<Option<LanguageModelPricing>>,
 }
 
 impl FakeLanguageModel {
+    pub fn set_pricing(&self, pricing: Option<LanguageModelPricing>) {
+        *self.pricing.lock() = pricing;
+    }
+
     pub fn pending_completions(&self) -> Vec<LanguageModelRequest> {
         self.current_completion_txs
             .lock()
@@ -111,6 +121,17 @@ impl FakeLanguageModel {
         &self,
         request: &LanguageModelRequest,
         chunk: impl Into<String>,
+    ) {
+        self.send_completion_stream_event(
+            request,
+            LanguageModelCompletionEvent::Text(chunk.into()),
+        );
+    }
+
+    pub fn send_completion_stream_event(
+        &self,
+        request: &LanguageModelRequest,
+        event: LanguageModelCompletionEvent,
     ) {
         let current_completion_txs = self.current_completion_txs.lock();
         let tx = current_completion_txs
@@ -118,7 +139,7 @@ impl FakeLanguageModel {
             .find(|(req, _)| req == request)
             .map(|(_, tx)| tx)
             .unwrap();
-        tx.unbounded_send(chunk.into()).unwrap();
+        tx.unbounded_send(event).unwrap();
     }
 
     pub fn end_completion_stream(&self, request: &LanguageModelRequest) {
@@ -131,6 +152,10 @@ impl FakeLanguageModel {
         self.stream_completion_response(self.pending_completions().last().unwrap(), chunk);
     }
 
+    pub fn send_last_completion_stream_event(&self, event: LanguageModelCompletionEvent) {
+        self.send_completion_stream_event(self.pending_completions().last().unwrap(), event);
+    }
+
     pub fn end_last_completion_stream(&self) {
         self.end_completion_stream(self.pending_completions().last().unwrap());
     }
@@ -173,6 +198,10 @@ impl LanguageModel for FakeLanguageModel {
         1000000
     }
 
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        *self.pricing.lock()
+    }
+
     fn count_tokens(&self, _: LanguageModelRequest, _: &App) -> BoxFuture<'static, Result<u64>> {
         futures::future::ready(Ok(0)).boxed()
     }
@@ -190,12 +219,7 @@ impl LanguageModel for FakeLanguageModel {
     > {
         let (tx, rx) = mpsc::unbounded();
         self.current_completion_txs.lock().push((request, tx));
-        async move {
-            Ok(rx
-                .map(|text| Ok(LanguageModelCompletionEvent::Text(text)))
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(rx.map(Ok).boxed()) }.boxed()
     }
 
     fn as_fake(&self) -> &Self {
@@ -57,6 +57,32 @@ pub struct LanguageModelCacheConfiguration {
     pub min_total_token: u64,
 }
 
+/// The price of a language model, in US dollars per million tokens.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LanguageModelPricing {
+    pub input: f64,
+    pub output: f64,
+    /// The price of input tokens written to the prompt cache. Defaults to the input price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input: Option<f64>,
+    /// The price of input tokens read from the prompt cache. Defaults to the input price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input: Option<f64>,
+}
+
+impl LanguageModelPricing {
+    /// Returns the estimated cost of the given token usage, in US dollars.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cache_creation_input = self.cache_creation_input.unwrap_or(self.input);
+        let cache_read_input = self.cache_read_input.unwrap_or(self.input);
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * cache_creation_input
+            + usage.cache_read_input_tokens as f64 * cache_read_input)
+            / 1_000_000.0
+    }
+}
+
 /// A completion event from a language model.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum LanguageModelCompletionEvent {
@@ -199,6 +225,34 @@ impl TokenUsage {
             + self.cache_read_input_tokens
             + self.cache_creation_input_tokens
     }
+
+    /// Subtracts the other usage from this one, field by field, stopping at zero.
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self {
+            input_tokens: self.input_tokens.saturating_sub(other.input_tokens),
+            output_tokens: self.output_tokens.saturating_sub(other.output_tokens),
+            cache_creation_input_tokens: self
+                .cache_creation_input_tokens
+                .saturating_sub(other.cache_creation_input_tokens),
+            cache_read_input_tokens: self
+                .cache_read_input_tokens
+                .saturating_sub(other.cache_read_input_tokens),
+        }
+    }
+
+    /// Returns the larger of the two usages, field by field.
+    pub fn max(self, other: Self) -> Self {
+        Self {
+            input_tokens: self.input_tokens.max(other.input_tokens),
+            output_tokens: self.output_tokens.max(other.output_tokens),
+            cache_creation_input_tokens: self
+                .cache_creation_input_tokens
+                .max(other.cache_creation_input_tokens),
+            cache_read_input_tokens: self
+                .cache_read_input_tokens
+                .max(other.cache_read_input_tokens),
+        }
+    }
 }
 
 impl Add<TokenUsage> for TokenUsage {
@@ -389,6 +443,11 @@ pub trait LanguageModel: Send + Sync {
         None
     }
 
+    /// Returns the price of this model, if it's known, to estimate the cost of requests.
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        None
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     fn as_fake(&self) -> &fake_provider::FakeLanguageModel {
         unimplemented!()
@@ -538,3 +597,35 @@ impl From<String> for LanguageModelProviderName {
         Self(SharedString::from(value))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pricing_cost() {
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 200_000,
+            cache_creation_input_tokens: 100_000,
+            cache_read_input_tokens: 2_000_000,
+        };
+
+        let pricing = LanguageModelPricing {
+            input: 3.,
+            output: 15.,
+            cache_creation_input: Some(3.75),
+            cache_read_input: Some(0.3),
+        };
+        assert_eq!(pricing.cost(&usage), 6.975);
+
+        // Cached tokens are priced as input tokens when the cache has no price of its own.
+        let pricing = LanguageModelPricing {
+            input: 1.,
+            output: 2.,
+            cache_creation_input: None,
+            cache_read_input: None,
+        };
+        assert_eq!(pricing.cost(&usage), 3.5);
+    }
+}
@@ -26,8 +26,10 @@ pub fn init(user_store: Entity<UserStore>, client: Arc<Client>, cx: &mut App) {
     crate::settings::init(cx);
     let registry = LanguageModelRegistry::global(cx);
     registry.update(cx, |registry, cx| {
-        register_language_model_providers(registry, user_store, client, cx);
+        register_language_model_providers(registry, user_store, client.clone(), cx);
     });
+    provider::open_ai_compatible::init(client.http_client(), cx);
+    provider::extension::init(cx);
 }
 
 fn register_language_model_providers(
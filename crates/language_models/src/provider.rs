@@ -3,10 +3,12 @@ pub mod bedrock;
 pub mod cloud;
 pub mod copilot_chat;
 pub mod deepseek;
+pub mod extension;
 pub mod google;
 pub mod lmstudio;
 pub mod mistral;
 pub mod ollama;
 pub mod open_ai;
+pub mod open_ai_compatible;
 pub mod open_router;
 pub mod vercel;
@@ -16,10 +16,10 @@ use gpui::{
 use http_client::HttpClient;
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCacheConfiguration,
-    LanguageModelCompletionError, LanguageModelId, LanguageModelName, LanguageModelProvider,
-    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent, MessageContent,
-    RateLimiter, Role,
+    LanguageModelCompletionError, LanguageModelId, LanguageModelName, LanguageModelPricing,
+    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
+    LanguageModelProviderState, LanguageModelRequest, LanguageModelToolChoice,
+    LanguageModelToolResultContent, MessageContent, RateLimiter, Role,
 };
 use language_model::{LanguageModelCompletionEvent, LanguageModelToolUse, StopReason};
 use schemars::JsonSchema;
@@ -61,6 +61,8 @@ pub struct AvailableModel {
     pub extra_beta_headers: Vec<String>,
     /// The model's mode (e.g. thinking)
     pub mode: Option<ModelMode>,
+    /// The model's price, to estimate the cost of requests. Defaults to the price of the Anthropic model with the same name, if any.
+    pub pricing: Option<LanguageModelPricing>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -197,9 +199,14 @@ impl AnthropicLanguageModelProvider {
         Self { http_client, state }
     }
 
-    fn create_language_model(&self, model: anthropic::Model) -> Arc<dyn LanguageModel> {
+    fn create_language_model(
+        &self,
+        model: anthropic::Model,
+        pricing: Option<LanguageModelPricing>,
+    ) -> Arc<dyn LanguageModel> {
         Arc::new(AnthropicModel {
             id: LanguageModelId::from(model.id().to_string()),
+            pricing: pricing.or_else(|| anthropic_model_pricing(&model)),
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
@@ -230,11 +237,11 @@ impl LanguageModelProvider for AnthropicLanguageModelProvider {
     }
 
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(self.create_language_model(anthropic::Model::default()))
+        Some(self.create_language_model(anthropic::Model::default(), None))
     }
 
     fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(self.create_language_model(anthropic::Model::default_fast()))
+        Some(self.create_language_model(anthropic::Model::default_fast(), None))
     }
 
     fn recommended_models(&self, _cx: &App) -> Vec<Arc<dyn LanguageModel>> {
@@ -243,7 +250,7 @@ impl LanguageModelProvider for AnthropicLanguageModelProvider {
             anthropic::Model::ClaudeSonnet4Thinking,
         ]
         .into_iter()
-        .map(|model| self.create_language_model(model))
+        .map(|model| self.create_language_model(model, None))
         .collect()
     }
 
@@ -253,7 +260,7 @@ impl LanguageModelProvider for AnthropicLanguageModelProvider {
         // Add base models from anthropic::Model::iter()
         for model in anthropic::Model::iter() {
             if !matches!(model, anthropic::Model::Custom { .. }) {
-                models.insert(model.id().to_string(), model);
+                models.insert(model.id().to_string(), (model, None));
             }
         }
 
@@ -265,29 +272,32 @@ impl LanguageModelProvider for AnthropicLanguageModelProvider {
         {
             models.insert(
                 model.name.clone(),
-                anthropic::Model::Custom {
-                    name: model.name.clone(),
-                    display_name: model.display_name.clone(),
-                    max_tokens: model.max_tokens,
-                    tool_override: model.tool_override.clone(),
-                    cache_configuration: model.cache_configuration.as_ref().map(|config| {
-                        anthropic::AnthropicModelCacheConfiguration {
-                            max_cache_anchors: config.max_cache_anchors,
-                            should_speculate: config.should_speculate,
-                            min_total_token: config.min_total_token,
-                        }
-                    }),
-                    max_output_tokens: model.max_output_tokens,
-                    default_temperature: model.default_temperature,
-                    extra_beta_headers: model.extra_beta_headers.clone(),
-                    mode: model.mode.clone().unwrap_or_default().into(),
-                },
+                (
+                    anthropic::Model::Custom {
+                        name: model.name.clone(),
+                        display_name: model.display_name.clone(),
+                        max_tokens: model.max_tokens,
+                        tool_override: model.tool_override.clone(),
+                        cache_configuration: model.cache_configuration.as_ref().map(|config| {
+                            anthropic::AnthropicModelCacheConfiguration {
+                                max_cache_anchors: config.max_cache_anchors,
+                                should_speculate: config.should_speculate,
+                                min_total_token: config.min_total_token,
+                            }
+                        }),
+                        max_output_tokens: model.max_output_tokens,
+                        default_temperature: model.default_temperature,
+                        extra_beta_headers: model.extra_beta_headers.clone(),
+                        mode: model.mode.clone().unwrap_or_default().into(),
+                    },
+                    model.pricing,
+                ),
             );
         }
 
         models
             .into_values()
-            .map(|model| self.create_language_model(model))
+            .map(|(model, pricing)| self.create_language_model(model, pricing))
             .collect()
     }
 
@@ -312,6 +322,7 @@ impl LanguageModelProvider for AnthropicLanguageModelProvider {
 pub struct AnthropicModel {
     id: LanguageModelId,
     model: anthropic::Model,
+    pricing: Option<LanguageModelPricing>,
     state: gpui::Entity<State>,
     http_client: Arc<dyn HttpClient>,
     request_limiter: RateLimiter,
@@ -504,6 +515,39 @@ impl LanguageModel for AnthropicModel {
                 min_total_token: config.min_total_token,
             })
     }
+
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        self.pricing
+    }
+}
+
+/// Returns the price of the given model, or of the Anthropic model a custom model is named after.
+fn anthropic_model_pricing(model: &anthropic::Model) -> Option<LanguageModelPricing> {
+    let (input, output, cache_creation_input, cache_read_input) = match model {
+        anthropic::Model::ClaudeOpus4
+        | anthropic::Model::ClaudeOpus4Thinking
+        | anthropic::Model::Claude3Opus => (15., 75., 18.75, 1.5),
+        anthropic::Model::ClaudeSonnet4
+        | anthropic::Model::ClaudeSonnet4Thinking
+        | anthropic::Model::Claude3_7Sonnet
+        | anthropic::Model::Claude3_7SonnetThinking
+        | anthropic::Model::Claude3_5Sonnet
+        | anthropic::Model::Claude3Sonnet => (3., 15., 3.75, 0.3),
+        anthropic::Model::Claude3_5Haiku => (0.8, 4., 1., 0.08),
+        anthropic::Model::Claude3Haiku => (0.25, 1.25, 0.3, 0.03),
+        anthropic::Model::Custom { name, .. } => {
+            return anthropic::Model::from_id(name)
+                .ok()
+                .filter(|model| !matches!(model, anthropic::Model::Custom { .. }))
+                .and_then(|model| anthropic_model_pricing(&model));
+        }
+    };
+    Some(LanguageModelPricing {
+        input,
+        output,
+        cache_creation_input: Some(cache_creation_input),
+        cache_read_input: Some(cache_read_input),
+    })
 }
 
 pub fn into_anthropic(
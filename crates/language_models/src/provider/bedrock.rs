@@ -34,10 +34,10 @@ use http_client::HttpClient;
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCacheConfiguration,
     LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
-    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, LanguageModelToolChoice,
-    LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, RateLimiter, Role,
-    TokenUsage,
+    LanguageModelPricing, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
+    RateLimiter, Role, TokenUsage,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -93,6 +93,8 @@ pub struct AvailableModel {
     pub max_output_tokens: Option<u64>,
     pub default_temperature: Option<f32>,
     pub mode: Option<ModelMode>,
+    /// The model's price, to estimate the cost of requests. Defaults to the price of the Bedrock model with the same name, if any.
+    pub pricing: Option<LanguageModelPricing>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -270,9 +272,14 @@ impl BedrockLanguageModelProvider {
         }
     }
 
-    fn create_language_model(&self, model: bedrock::Model) -> Arc<dyn LanguageModel> {
+    fn create_language_model(
+        &self,
+        model: bedrock::Model,
+        pricing: Option<LanguageModelPricing>,
+    ) -> Arc<dyn LanguageModel> {
         Arc::new(BedrockModel {
             id: LanguageModelId::from(model.id().to_string()),
+            pricing: pricing.or_else(|| bedrock_model_pricing(&model)),
             model,
             http_client: self.http_client.clone(),
             handler: self.handler.clone(),
@@ -297,12 +304,12 @@ impl LanguageModelProvider for BedrockLanguageModelProvider {
     }
 
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(self.create_language_model(bedrock::Model::default()))
+        Some(self.create_language_model(bedrock::Model::default(), None))
     }
 
     fn default_fast_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
         let region = self.state.read(cx).get_region();
-        Some(self.create_language_model(bedrock::Model::default_fast(region.as_str())))
+        Some(self.create_language_model(bedrock::Model::default_fast(region.as_str()), None))
     }
 
     fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
@@ -311,7 +318,7 @@ impl LanguageModelProvider for BedrockLanguageModelProvider {
         for model in bedrock::Model::iter() {
             if !matches!(model, bedrock::Model::Custom { .. }) {
                 // TODO: Sonnet 3.7 vs. 3.7 Thinking bug is here.
-                models.insert(model.id().to_string(), model);
+                models.insert(model.id().to_string(), (model, None));
             }
         }
 
@@ -323,25 +330,28 @@ impl LanguageModelProvider for BedrockLanguageModelProvider {
         {
             models.insert(
                 model.name.clone(),
-                bedrock::Model::Custom {
-                    name: model.name.clone(),
-                    display_name: model.display_name.clone(),
-                    max_tokens: model.max_tokens,
-                    max_output_tokens: model.max_output_tokens,
-                    default_temperature: model.default_temperature,
-                    cache_configuration: model.cache_configuration.as_ref().map(|config| {
-                        bedrock::BedrockModelCacheConfiguration {
-                            max_cache_anchors: config.max_cache_anchors,
-                            min_total_token: config.min_total_token,
-                        }
-                    }),
-                },
+                (
+                    bedrock::Model::Custom {
+                        name: model.name.clone(),
+                        display_name: model.display_name.clone(),
+                        max_tokens: model.max_tokens,
+                        max_output_tokens: model.max_output_tokens,
+                        default_temperature: model.default_temperature,
+                        cache_configuration: model.cache_configuration.as_ref().map(|config| {
+                            bedrock::BedrockModelCacheConfiguration {
+                                max_cache_anchors: config.max_cache_anchors,
+                                min_total_token: config.min_total_token,
+                            }
+                        }),
+                    },
+                    model.pricing,
+                ),
             );
         }
 
         models
             .into_values()
-            .map(|model| self.create_language_model(model))
+            .map(|(model, pricing)| self.create_language_model(model, pricing))
             .collect()
     }
 
@@ -375,6 +385,7 @@ impl LanguageModelProviderState for BedrockLanguageModelProvider {
 struct BedrockModel {
     id: LanguageModelId,
     model: Model,
+    pricing: Option<LanguageModelPricing>,
     http_client: AwsHttpClient,
     handler: tokio::runtime::Handle,
     client: OnceCell<BedrockClient>,
@@ -596,6 +607,48 @@ impl LanguageModel for BedrockModel {
                 min_total_token: config.min_total_token,
             })
     }
+
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        self.pricing
+    }
+}
+
+/// Returns the on-demand price of the given model in `us-east-1`, or of the Bedrock model a custom
+/// model is named after.
+///
+/// Only Anthropic and Amazon Nova models have a known price. Prices differ between regions.
+fn bedrock_model_pricing(model: &Model) -> Option<LanguageModelPricing> {
+    let (input, output, cache_creation_input, cache_read_input) = match model {
+        Model::ClaudeOpus4 | Model::ClaudeOpus4Thinking | Model::Claude3Opus => {
+            (15., 75., Some(18.75), Some(1.5))
+        }
+        Model::ClaudeSonnet4
+        | Model::ClaudeSonnet4Thinking
+        | Model::Claude3_7Sonnet
+        | Model::Claude3_7SonnetThinking
+        | Model::Claude3_5SonnetV2
+        | Model::Claude3_5Sonnet
+        | Model::Claude3Sonnet => (3., 15., Some(3.75), Some(0.3)),
+        Model::Claude3_5Haiku => (0.8, 4., Some(1.), Some(0.08)),
+        Model::Claude3Haiku => (0.25, 1.25, Some(0.3), Some(0.03)),
+        Model::AmazonNovaMicro => (0.035, 0.14, None, Some(0.00875)),
+        Model::AmazonNovaLite => (0.06, 0.24, None, Some(0.015)),
+        Model::AmazonNovaPro => (0.8, 3.2, None, Some(0.2)),
+        Model::AmazonNovaPremier => (2.5, 12.5, None, None),
+        Model::Custom { name, .. } => {
+            return Model::iter()
+                .filter(|model| !matches!(model, Model::Custom { .. }))
+                .find(|model| model.id() == name)
+                .and_then(|model| bedrock_model_pricing(&model));
+        }
+        _ => return None,
+    };
+    Some(LanguageModelPricing {
+        input,
+        output,
+        cache_creation_input,
+        cache_read_input,
+    })
 }
 
 fn deny_tool_use_events(
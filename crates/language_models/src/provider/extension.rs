@@ -0,0 +1,1142 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use credentials_provider::CredentialsProvider;
+use editor::{Editor, EditorElement, EditorStyle};
+use extension::{
+    Extension, ExtensionHostProxy, ExtensionLanguageModelProviderProxy,
+    LanguageModelProviderManifestEntry, LlmCompletionEvent, LlmCompletionRequest, LlmImage,
+    LlmMessageContent, LlmModel, LlmRequestMessage, LlmRole, LlmStopReason, LlmTool, LlmToolChoice,
+    LlmToolResult, LlmToolUse,
+};
+use futures::{FutureExt, Stream, StreamExt, future::BoxFuture, stream::BoxStream};
+use gpui::{
+    AnyView, App, AsyncApp, BackgroundExecutor, Context, Entity, FontStyle, Task, TextStyle,
+    WhiteSpace,
+};
+use language_model::{
+    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRegistry,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent,
+    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, TokenUsage,
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use theme::ThemeSettings;
+use ui::{Icon, IconName, Tooltip, prelude::*};
+use util::ResultExt;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_language_model_provider_proxy(LanguageModelProviderRegistryProxy::new(
+        LanguageModelRegistry::global(cx),
+    ));
+}
+
+struct LanguageModelProviderRegistryProxy {
+    language_model_registry: Entity<LanguageModelRegistry>,
+    /// The extensions that registered each provider, so that unloading an extension never
+    /// unregisters a built-in provider or another extension's provider with the same ID.
+    registered_providers: Mutex<HashMap<Arc<str>, Arc<str>>>,
+}
+
+impl LanguageModelProviderRegistryProxy {
+    fn new(language_model_registry: Entity<LanguageModelRegistry>) -> Self {
+        Self {
+            language_model_registry,
+            registered_providers: Mutex::default(),
+        }
+    }
+}
+
+impl ExtensionLanguageModelProviderProxy for LanguageModelProviderRegistryProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        let id = LanguageModelProviderId(provider_id.clone().into());
+        let extension_id = extension.manifest().id.clone();
+        let owner = self.registered_providers.lock().get(&provider_id).cloned();
+        let is_taken = match owner {
+            Some(owner) => owner != extension_id,
+            None => self
+                .language_model_registry
+                .read(cx)
+                .provider(&id)
+                .is_some(),
+        };
+        if is_taken {
+            log::error!(
+                "not registering language model provider {provider_id:?} of extension {extension_id}, because a provider with the same ID already exists"
+            );
+            return;
+        }
+
+        let provider =
+            ExtensionLanguageModelProvider::new(extension, provider_id.clone(), provider, cx);
+        self.language_model_registry
+            .update(cx, |registry, cx| registry.register_provider(provider, cx));
+        self.registered_providers
+            .lock()
+            .insert(provider_id, extension_id);
+    }
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        {
+            let mut registered_providers = self.registered_providers.lock();
+            if registered_providers.get(&provider_id) != Some(&extension_id) {
+                return;
+            }
+            registered_providers.remove(&provider_id);
+        }
+        self.language_model_registry.update(cx, |registry, cx| {
+            registry.unregister_provider(LanguageModelProviderId(provider_id.into()), cx)
+        });
+    }
+}
+
+/// A [`LanguageModelProvider`] implemented by an extension.
+pub struct ExtensionLanguageModelProvider {
+    id: LanguageModelProviderId,
+    name: LanguageModelProviderName,
+    extension: Arc<dyn Extension>,
+    state: Entity<State>,
+}
+
+pub struct State {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    provider: LanguageModelProviderManifestEntry,
+    credentials_url: String,
+    api_key: Option<String>,
+    api_key_from_env: bool,
+    available_models: Vec<LlmModel>,
+    fetch_models_task: Option<Task<Result<()>>>,
+}
+
+impl State {
+    fn is_authenticated(&self) -> bool {
+        self.api_key.is_some() || self.provider.api_key_optional
+    }
+
+    fn reset_api_key(&self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url.clone();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .delete_credentials(&credentials_url, &cx)
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.api_key = None;
+                this.api_key_from_env = false;
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn set_api_key(&mut self, api_key: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url.clone();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .write_credentials(&credentials_url, "Bearer", api_key.as_bytes(), &cx)
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.api_key = Some(api_key);
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn authenticate(&self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.api_key.is_some() {
+            return Task::ready(Ok(()));
+        }
+
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url.clone();
+        let api_key_env_var = self.provider.api_key_env_var.clone();
+        let api_key_optional = self.provider.api_key_optional;
+        let provider_name = self.provider.name.clone();
+        cx.spawn(async move |this, cx| {
+            let api_key = if let Some(api_key) = api_key_env_var
+                .as_ref()
+                .and_then(|env_var| std::env::var(env_var).ok())
+            {
+                Some((api_key, true))
+            } else if let Some((_, api_key)) = credentials_provider
+                .read_credentials(&credentials_url, &cx)
+                .await?
+            {
+                Some((
+                    String::from_utf8(api_key)
+                        .context(format!("invalid {provider_name} API key"))?,
+                    false,
+                ))
+            } else {
+                None
+            };
+
+            if api_key.is_none() && !api_key_optional {
+                return Err(AuthenticateError::CredentialsNotFound);
+            }
+
+            this.update(cx, |this, cx| {
+                if let Some((api_key, from_env)) = api_key {
+                    this.api_key = Some(api_key);
+                    this.api_key_from_env = from_env;
+                    this.restart_fetch_models_task(cx);
+                } else if this.fetch_models_task.is_none() {
+                    this.restart_fetch_models_task(cx);
+                }
+                cx.notify();
+            })?;
+
+            Ok(())
+        })
+    }
+
+    fn fetch_models(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        cx.spawn(async move |this, cx| {
+            let models = extension.llm_provider_models(provider_id).await?;
+
+            this.update(cx, |this, cx| {
+                this.available_models = models;
+                cx.notify();
+            })
+        })
+    }
+
+    fn restart_fetch_models_task(&mut self, cx: &mut Context<Self>) {
+        if self.is_authenticated() {
+            let task = self.fetch_models(cx);
+            self.fetch_models_task.replace(task);
+        } else {
+            self.available_models.clear();
+        }
+    }
+}
+
+impl ExtensionLanguageModelProvider {
+    pub fn new(
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) -> Self {
+        let credentials_url = format!(
+            "zed-extension://{}/{}",
+            extension.manifest().id,
+            provider_id
+        );
+        let name = LanguageModelProviderName(provider.name.clone().into());
+        let state = cx.new(|_| State {
+            extension: extension.clone(),
+            provider_id: provider_id.clone(),
+            provider,
+            credentials_url,
+            api_key: None,
+            api_key_from_env: false,
+            available_models: Vec::new(),
+            fetch_models_task: None,
+        });
+
+        Self {
+            id: LanguageModelProviderId(provider_id.into()),
+            name,
+            extension,
+            state,
+        }
+    }
+
+    fn create_language_model(&self, model: LlmModel) -> Arc<dyn LanguageModel> {
+        Arc::new(ExtensionLanguageModel {
+            id: LanguageModelId::from(model.id.clone()),
+            model,
+            provider_id: self.id.clone(),
+            provider_name: self.name.clone(),
+            extension: self.extension.clone(),
+            state: self.state.clone(),
+            request_limiter: RateLimiter::new(4),
+        })
+    }
+}
+
+impl LanguageModelProviderState for ExtensionLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<gpui::Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for ExtensionLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.name.clone()
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        let models = &self.state.read(cx).available_models;
+        let model = models
+            .iter()
+            .find(|model| model.is_default)
+            .or_else(|| models.first())?;
+        Some(self.create_language_model(model.clone()))
+    }
+
+    fn default_fast_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        let model = self
+            .state
+            .read(cx)
+            .available_models
+            .iter()
+            .find(|model| model.is_default_fast)?;
+        Some(self.create_language_model(model.clone()))
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        self.state
+            .read(cx)
+            .available_models
+            .iter()
+            .map(|model| self.create_language_model(model.clone()))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, window: &mut Window, cx: &mut App) -> AnyView {
+        cx.new(|cx| ConfigurationView::new(self.state.clone(), window, cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.reset_api_key(cx))
+    }
+}
+
+pub struct ExtensionLanguageModel {
+    id: LanguageModelId,
+    model: LlmModel,
+    provider_id: LanguageModelProviderId,
+    provider_name: LanguageModelProviderName,
+    extension: Arc<dyn Extension>,
+    state: Entity<State>,
+    request_limiter: RateLimiter,
+}
+
+impl LanguageModel for ExtensionLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.provider_id.clone()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools
+    }
+
+    fn supports_tool_choice(&self, _choice: LanguageModelToolChoice) -> bool {
+        self.model.supports_tools
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.supports_images
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!(
+            "extension/{}/{}/{}",
+            self.extension.manifest().id,
+            self.provider_id.0,
+            self.model.id
+        )
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.model.max_token_count
+    }
+
+    fn max_output_tokens(&self) -> Option<u64> {
+        self.model.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        cx.background_spawn(async move {
+            let messages = request
+                .messages
+                .into_iter()
+                .map(|message| tiktoken_rs::ChatCompletionRequestMessage {
+                    role: match message.role {
+                        Role::User => "user".into(),
+                        Role::Assistant => "assistant".into(),
+                        Role::System => "system".into(),
+                    },
+                    content: Some(message.string_contents()),
+                    name: None,
+                    function_call: None,
+                })
+                .collect::<Vec<_>>();
+
+            tiktoken_rs::num_tokens_from_messages("gpt-4o", &messages).map(|tokens| tokens as u64)
+        })
+        .boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        let Ok(api_key) = cx.read_entity(&self.state, |state, _| state.api_key.clone()) else {
+            return futures::future::ready(Err(anyhow!(
+                "App state dropped: Unable to read API key from the application state"
+            )
+            .into()))
+            .boxed();
+        };
+
+        let request = into_extension_request(request);
+        let extension = self.extension.clone();
+        let provider_id: Arc<str> = self.provider_id.0.as_ref().into();
+        let model_id: Arc<str> = self.model.id.as_str().into();
+        let executor = cx.background_executor().clone();
+        let future = self.request_limiter.stream(async move {
+            let stream_id = extension
+                .llm_stream_completion(provider_id, model_id, request, api_key)
+                .await?;
+            Ok(completion_events(extension, stream_id, executor))
+        });
+
+        async move { Ok(future.await?.boxed()) }.boxed()
+    }
+}
+
+pub fn into_extension_request(request: LanguageModelRequest) -> LlmCompletionRequest {
+    LlmCompletionRequest {
+        messages: request
+            .messages
+            .into_iter()
+            .map(|message| LlmRequestMessage {
+                role: match message.role {
+                    Role::User => LlmRole::User,
+                    Role::Assistant => LlmRole::Assistant,
+                    Role::System => LlmRole::System,
+                },
+                content: message
+                    .content
+                    .into_iter()
+                    .filter_map(|content| match content {
+                        MessageContent::Text(text) => Some(LlmMessageContent::Text(text)),
+                        MessageContent::Thinking { text, .. } => {
+                            Some(LlmMessageContent::Thinking(text))
+                        }
+                        MessageContent::RedactedThinking(_) => None,
+                        MessageContent::Image(image) => Some(LlmMessageContent::Image(LlmImage {
+                            source: image.source.to_string(),
+                            width: image.size.width.0 as u32,
+                            height: image.size.height.0 as u32,
+                        })),
+                        MessageContent::ToolUse(tool_use) => {
+                            Some(LlmMessageContent::ToolUse(LlmToolUse {
+                                id: tool_use.id.to_string(),
+                                name: tool_use.name.to_string(),
+                                input: tool_use.input.to_string(),
+                            }))
+                        }
+                        MessageContent::ToolResult(tool_result) => {
+                            Some(LlmMessageContent::ToolResult(LlmToolResult {
+                                tool_use_id: tool_result.tool_use_id.to_string(),
+                                tool_name: tool_result.tool_name.to_string(),
+                                is_error: tool_result.is_error,
+                                content: match tool_result.content {
+                                    LanguageModelToolResultContent::Text(text) => text.to_string(),
+                                    LanguageModelToolResultContent::Image(_) => {
+                                        "[Tool responded with an image]".to_string()
+                                    }
+                                },
+                            }))
+                        }
+                    })
+                    .collect(),
+                cache: message.cache,
+            })
+            .collect(),
+        tools: request
+            .tools
+            .into_iter()
+            .map(|tool| LlmTool {
+                name: tool.name,
+                description: tool.description,
+                input_schema: tool.input_schema.to_string(),
+            })
+            .collect(),
+        tool_choice: request.tool_choice.map(|choice| match choice {
+            LanguageModelToolChoice::Auto => LlmToolChoice::Auto,
+            LanguageModelToolChoice::Any => LlmToolChoice::Any,
+            LanguageModelToolChoice::None => LlmToolChoice::None,
+        }),
+        stop: request.stop,
+        temperature: request.temperature,
+    }
+}
+
+/// A completion stream that is in progress within an extension.
+///
+/// Dropping it before the stream has ended drops the stream in the extension, too.
+struct ExtensionCompletionStream {
+    extension: Arc<dyn Extension>,
+    stream_id: u64,
+    executor: BackgroundExecutor,
+    ended: bool,
+}
+
+impl Drop for ExtensionCompletionStream {
+    fn drop(&mut self) {
+        if self.ended {
+            return;
+        }
+
+        let extension = self.extension.clone();
+        let stream_id = self.stream_id;
+        self.executor
+            .spawn(async move {
+                extension
+                    .llm_drop_completion_stream(stream_id)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
+
+fn completion_events(
+    extension: Arc<dyn Extension>,
+    stream_id: u64,
+    executor: BackgroundExecutor,
+) -> impl Stream<Item = Result<LanguageModelCompletionEvent, LanguageModelCompletionError>> {
+    let stream = ExtensionCompletionStream {
+        extension,
+        stream_id,
+        executor,
+        ended: false,
+    };
+
+    futures::stream::unfold(stream, |mut stream| async move {
+        if stream.ended {
+            return None;
+        }
+
+        match stream
+            .extension
+            .llm_next_completion_events(stream.stream_id)
+            .await
+        {
+            Ok(Some(events)) => Some((events.into_iter().map(map_event).collect(), stream)),
+            Ok(None) => {
+                stream.ended = true;
+                None
+            }
+            Err(error) => {
+                stream.ended = true;
+                Some((vec![Err(error.into())], stream))
+            }
+        }
+    })
+    .flat_map(futures::stream::iter)
+}
+
+fn map_event(
+    event: LlmCompletionEvent,
+) -> Result<LanguageModelCompletionEvent, LanguageModelCompletionError> {
+    Ok(match event {
+        LlmCompletionEvent::Text(text) => LanguageModelCompletionEvent::Text(text),
+        LlmCompletionEvent::Thinking(text) => LanguageModelCompletionEvent::Thinking {
+            text,
+            signature: None,
+        },
+        LlmCompletionEvent::ToolUse(tool_use) => {
+            let input = serde_json::from_str(&tool_use.input).map_err(|error| {
+                LanguageModelCompletionError::BadInputJson {
+                    id: tool_use.id.clone().into(),
+                    tool_name: tool_use.name.clone().into(),
+                    raw_input: tool_use.input.clone().into(),
+                    json_parse_error: error.to_string(),
+                }
+            })?;
+            LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                id: tool_use.id.into(),
+                name: tool_use.name.into(),
+                raw_input: tool_use.input,
+                input,
+                is_input_complete: true,
+            })
+        }
+        LlmCompletionEvent::Stop(reason) => LanguageModelCompletionEvent::Stop(match reason {
+            LlmStopReason::EndTurn => StopReason::EndTurn,
+            LlmStopReason::MaxTokens => StopReason::MaxTokens,
+            LlmStopReason::ToolUse => StopReason::ToolUse,
+            LlmStopReason::Refusal => StopReason::Refusal,
+        }),
+        LlmCompletionEvent::Usage(usage) => LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_input_tokens: usage.cache_creation_input_tokens,
+            cache_read_input_tokens: usage.cache_read_input_tokens,
+        }),
+    })
+}
+
+struct ConfigurationView {
+    api_key_editor: Entity<Editor>,
+    state: Entity<State>,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: Entity<State>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let api_key_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Paste your API key here", cx);
+            editor
+        });
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn_in(window, {
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    let _ = task.await;
+                }
+
+                this.update(cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let api_key = self.api_key_editor.read(cx).text(cx);
+        if api_key.is_empty() {
+            return;
+        }
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(api_key, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn reset_api_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.api_key_editor
+            .update(cx, |editor, cx| editor.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state.update(cx, |state, cx| state.reset_api_key(cx))?.await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn render_api_key_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            white_space: WhiteSpace::Normal,
+            ..Default::default()
+        };
+        EditorElement::new(
+            &self.api_key_editor,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let provider_name = state.provider.name.clone();
+        let api_key_env_var = state.provider.api_key_env_var.clone();
+        let api_key_optional = state.provider.api_key_optional;
+        let has_api_key = state.api_key.is_some();
+        let env_var_set = state.api_key_from_env;
+
+        if self.load_credentials_task.is_some() {
+            div().child(Label::new("Loading credentials...")).into_any()
+        } else if !has_api_key {
+            v_flex()
+                .size_full()
+                .on_action(cx.listener(Self::save_api_key))
+                .child(Label::new(if api_key_optional {
+                    format!("You can optionally add an API key to use {provider_name}.")
+                } else {
+                    format!(
+                        "To use Zed's assistant with {provider_name}, you need to add an API key."
+                    )
+                }))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .my_2()
+                        .px_2()
+                        .py_1()
+                        .bg(cx.theme().colors().editor_background)
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .rounded_sm()
+                        .child(self.render_api_key_editor(cx)),
+                )
+                .when_some(api_key_env_var, |this, env_var| {
+                    this.child(
+                        Label::new(format!(
+                            "You can also assign the {env_var} environment variable and restart Zed."
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                })
+                .into_any()
+        } else {
+            h_flex()
+                .mt_1()
+                .p_1()
+                .justify_between()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().background)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(Label::new(match api_key_env_var.as_ref() {
+                            Some(env_var) if env_var_set => {
+                                format!("API key set in {env_var} environment variable.")
+                            }
+                            _ => "API key configured.".to_string(),
+                        })),
+                )
+                .child(
+                    Button::new("reset-key", "Reset Key")
+                        .label_size(LabelSize::Small)
+                        .icon(Some(IconName::Trash))
+                        .icon_size(IconSize::Small)
+                        .icon_position(IconPosition::Start)
+                        .disabled(env_var_set)
+                        .when_some(api_key_env_var.filter(|_| env_var_set), |this, env_var| {
+                            this.tooltip(Tooltip::text(format!(
+                                "To reset your API key, unset the {env_var} environment variable."
+                            )))
+                        })
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.reset_api_key(window, cx)),
+                        ),
+                )
+                .into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use extension::{FakeExtension, LlmTokenUsage};
+    use gpui::TestAppContext;
+    use language_model::{
+        LanguageModelRequestMessage, LanguageModelRequestTool, LanguageModelToolResult,
+        fake_provider,
+    };
+    use std::collections::VecDeque;
+
+    fn llm_model(id: &str, is_default: bool) -> LlmModel {
+        LlmModel {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            max_token_count: 100_000,
+            max_output_tokens: Some(4_096),
+            supports_images: false,
+            supports_tools: true,
+            is_default,
+            is_default_fast: false,
+        }
+    }
+
+    fn provider_entry(name: &str) -> LanguageModelProviderManifestEntry {
+        LanguageModelProviderManifestEntry {
+            name: name.to_string(),
+            api_key_env_var: None,
+            api_key_optional: true,
+        }
+    }
+
+    fn tool_use(input: &str) -> LlmCompletionEvent {
+        LlmCompletionEvent::ToolUse(LlmToolUse {
+            id: "tool-1".into(),
+            name: "read_file".into(),
+            input: input.into(),
+        })
+    }
+
+    #[gpui::test]
+    async fn test_extension_language_model(cx: &mut TestAppContext) {
+        let extension = Arc::new(FakeExtension::new("acme"));
+        extension.on_llm_provider_models(|provider_id| {
+            assert_eq!(provider_id.as_ref(), "acme-llm");
+            Ok(vec![llm_model("small", false), llm_model("large", true)])
+        });
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        extension.on_llm_stream_completion({
+            let requests = requests.clone();
+            move |provider_id, model_id, request| {
+                requests.lock().push((provider_id, model_id, request));
+                Ok(7)
+            }
+        });
+        let batches = Arc::new(Mutex::new(VecDeque::new()));
+        extension.on_llm_next_completion_events({
+            let batches = batches.clone();
+            move |stream_id| {
+                assert_eq!(stream_id, 7);
+                Ok(batches.lock().pop_front())
+            }
+        });
+        let dropped_streams = Arc::new(Mutex::new(Vec::new()));
+        extension.on_llm_drop_completion_stream({
+            let dropped_streams = dropped_streams.clone();
+            move |stream_id| {
+                dropped_streams.lock().push(stream_id);
+                Ok(())
+            }
+        });
+
+        let provider = cx.update(|cx| {
+            ExtensionLanguageModelProvider::new(
+                extension.clone(),
+                "acme-llm".into(),
+                provider_entry("Acme"),
+                cx,
+            )
+        });
+        provider
+            .state
+            .update(cx, |state, cx| state.restart_fetch_models_task(cx));
+        cx.run_until_parked();
+
+        let model = cx.update(|cx| {
+            let models = provider.provided_models(cx);
+            assert_eq!(
+                models
+                    .iter()
+                    .map(|model| model.id().0.to_string())
+                    .collect::<Vec<_>>(),
+                ["small", "large"]
+            );
+            assert!(provider.default_fast_model(cx).is_none());
+            provider.default_model(cx).unwrap()
+        });
+        assert_eq!(model.id().0.as_ref(), "large");
+        assert_eq!(model.name().0.as_ref(), "LARGE");
+        assert_eq!(model.provider_name().0.as_ref(), "Acme");
+        assert_eq!(model.telemetry_id(), "extension/acme/acme-llm/large");
+        assert_eq!(model.max_output_tokens(), Some(4_096));
+
+        batches.lock().extend([
+            vec![
+                LlmCompletionEvent::Text("Reading".into()),
+                tool_use(r#"{"path":"a.rs"}"#),
+            ],
+            vec![
+                LlmCompletionEvent::Usage(LlmTokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cache_creation_input_tokens: 0,
+                    cache_read_input_tokens: 2,
+                }),
+                LlmCompletionEvent::Stop(LlmStopReason::ToolUse),
+            ],
+        ]);
+        let request = LanguageModelRequest {
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Read a.rs".into()],
+                cache: true,
+            }],
+            temperature: Some(0.5),
+            ..Default::default()
+        };
+        let async_cx = cx.to_async();
+        let events = model
+            .stream_completion(request, &async_cx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::Text("Reading".into()),
+                LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                    id: "tool-1".into(),
+                    name: "read_file".into(),
+                    raw_input: r#"{"path":"a.rs"}"#.into(),
+                    input: serde_json::json!({ "path": "a.rs" }),
+                    is_input_complete: true,
+                }),
+                LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cache_creation_input_tokens: 0,
+                    cache_read_input_tokens: 2,
+                }),
+                LanguageModelCompletionEvent::Stop(StopReason::ToolUse),
+            ]
+        );
+
+        {
+            let requests = requests.lock();
+            let (provider_id, model_id, request) = &requests[0];
+            assert_eq!(provider_id.as_ref(), "acme-llm");
+            assert_eq!(model_id.as_ref(), "large");
+            assert_eq!(request.temperature, Some(0.5));
+            assert_eq!(request.messages.len(), 1);
+            assert_eq!(request.messages[0].role, LlmRole::User);
+            assert!(request.messages[0].cache);
+            assert!(matches!(
+                request.messages[0].content.as_slice(),
+                [LlmMessageContent::Text(text)] if text == "Read a.rs"
+            ));
+        }
+        // Streams that ran to the end are already gone from the extension.
+        cx.run_until_parked();
+        assert!(dropped_streams.lock().is_empty());
+
+        // Dropping a stream before it ends drops it in the extension, too.
+        batches.lock().extend([
+            vec![LlmCompletionEvent::Text("Hello".into())],
+            vec![LlmCompletionEvent::Stop(LlmStopReason::EndTurn)],
+        ]);
+        let mut events = model
+            .stream_completion(LanguageModelRequest::default(), &async_cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            events.next().await.unwrap().unwrap(),
+            LanguageModelCompletionEvent::Text("Hello".into())
+        );
+        drop(events);
+        cx.run_until_parked();
+        assert_eq!(*dropped_streams.lock(), [7]);
+    }
+
+    #[test]
+    fn test_map_event() {
+        assert_eq!(
+            map_event(LlmCompletionEvent::Thinking("Hmm".into())).unwrap(),
+            LanguageModelCompletionEvent::Thinking {
+                text: "Hmm".into(),
+                signature: None,
+            }
+        );
+        assert_eq!(
+            map_event(LlmCompletionEvent::Stop(LlmStopReason::Refusal)).unwrap(),
+            LanguageModelCompletionEvent::Stop(StopReason::Refusal)
+        );
+        assert!(matches!(
+            map_event(tool_use("{\"path\":")),
+            Err(LanguageModelCompletionError::BadInputJson { tool_name, raw_input, .. })
+                if tool_name.as_ref() == "read_file" && raw_input.as_ref() == "{\"path\":"
+        ));
+    }
+
+    #[test]
+    fn test_into_extension_request() {
+        let request = into_extension_request(LanguageModelRequest {
+            messages: vec![
+                LanguageModelRequestMessage {
+                    role: Role::Assistant,
+                    content: vec![
+                        MessageContent::RedactedThinking("secret".into()),
+                        MessageContent::ToolUse(LanguageModelToolUse {
+                            id: "tool-1".into(),
+                            name: "read_file".into(),
+                            raw_input: "{}".into(),
+                            input: serde_json::json!({ "path": "a.rs" }),
+                            is_input_complete: true,
+                        }),
+                    ],
+                    cache: false,
+                },
+                LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: vec![MessageContent::ToolResult(LanguageModelToolResult {
+                        tool_use_id: "tool-1".into(),
+                        tool_name: "read_file".into(),
+                        is_error: true,
+                        content: LanguageModelToolResultContent::Text("Not found".into()),
+                        output: None,
+                    })],
+                    cache: false,
+                },
+            ],
+            tools: vec![LanguageModelRequestTool {
+                name: "read_file".into(),
+                description: "Reads a file".into(),
+                input_schema: serde_json::json!({ "type": "object" }),
+            }],
+            tool_choice: Some(LanguageModelToolChoice::Any),
+            stop: vec!["\n\n".into()],
+            ..Default::default()
+        });
+
+        // Redacted thinking can't be sent to other providers, so it's left out.
+        assert!(matches!(
+            request.messages[0].content.as_slice(),
+            [LlmMessageContent::ToolUse(tool_use)]
+                if tool_use.id == "tool-1" && tool_use.input == r#"{"path":"a.rs"}"#
+        ));
+        assert!(matches!(
+            request.messages[1].content.as_slice(),
+            [LlmMessageContent::ToolResult(result)]
+                if result.tool_use_id == "tool-1" && result.is_error && result.content == "Not found"
+        ));
+        assert_eq!(request.tools.len(), 1);
+        assert_eq!(request.tools[0].input_schema, r#"{"type":"object"}"#);
+        assert!(matches!(request.tool_choice, Some(LlmToolChoice::Any)));
+        assert_eq!(request.stop, ["\n\n"]);
+    }
+
+    #[gpui::test]
+    fn test_provider_id_collision(cx: &mut App) {
+        LanguageModelRegistry::test(cx);
+        let registry = LanguageModelRegistry::global(cx);
+        let proxy = LanguageModelProviderRegistryProxy::new(registry.clone());
+        let extension: Arc<dyn Extension> = Arc::new(FakeExtension::new("acme"));
+        let provider_name = |id: &str, cx: &App| {
+            registry
+                .read(cx)
+                .provider(&LanguageModelProviderId(id.to_string().into()))
+                .map(|provider| provider.name().0.to_string())
+        };
+
+        // Extensions can't replace built-in providers, nor unregister them.
+        let built_in_id = fake_provider::provider_id().0.to_string();
+        proxy.register_language_model_provider(
+            extension.clone(),
+            built_in_id.as_str().into(),
+            provider_entry("Impostor"),
+            cx,
+        );
+        let built_in_name = fake_provider::provider_name().0.to_string();
+        assert_eq!(provider_name(&built_in_id, cx), Some(built_in_name.clone()));
+        proxy.unregister_language_model_provider("acme".into(), built_in_id.as_str().into(), cx);
+        assert_eq!(provider_name(&built_in_id, cx), Some(built_in_name));
+
+        // Reloading an extension replaces its own providers.
+        proxy.register_language_model_provider(
+            extension.clone(),
+            "acme-llm".into(),
+            provider_entry("Acme"),
+            cx,
+        );
+        proxy.register_language_model_provider(
+            extension.clone(),
+            "acme-llm".into(),
+            provider_entry("Acme 2"),
+            cx,
+        );
+        assert_eq!(provider_name("acme-llm", cx), Some("Acme 2".to_string()));
+
+        // Other extensions can neither replace nor unregister the provider.
+        proxy.register_language_model_provider(
+            Arc::new(FakeExtension::new("other")),
+            "acme-llm".into(),
+            provider_entry("Other"),
+            cx,
+        );
+        proxy.unregister_language_model_provider("other".into(), "acme-llm".into(), cx);
+        assert_eq!(provider_name("acme-llm", cx), Some("Acme 2".to_string()));
+
+        proxy.unregister_language_model_provider("acme".into(), "acme-llm".into(), cx);
+        assert_eq!(provider_name("acme-llm", cx), None);
+    }
+}
@@ -1,34 +1,40 @@
 use anyhow::{Context as _, Result, anyhow};
-use collections::BTreeMap;
+use collections::{BTreeMap, HashMap};
 use credentials_provider::CredentialsProvider;
 use editor::{Editor, EditorElement, EditorStyle};
-use futures::{FutureExt, Stream, StreamExt, future::BoxFuture};
+use futures::{
+    FutureExt, Stream, StreamExt, channel::oneshot, future::BoxFuture, stream::BoxStream,
+};
 use google_ai::{
-    FunctionDeclaration, GenerateContentResponse, GoogleModelMode, Part, SystemInstruction,
-    ThinkingConfig, UsageMetadata,
+    CreateCachedContentRequest, FunctionDeclaration, GenerateContentRequest,
+    GenerateContentResponse, GoogleModelMode, Part, SystemInstruction, ThinkingConfig,
+    UsageMetadata,
 };
 use gpui::{
     AnyView, App, AsyncApp, Context, Entity, FontStyle, Subscription, Task, TextStyle, WhiteSpace,
 };
 use http_client::HttpClient;
 use language_model::{
-    AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelToolChoice, LanguageModelToolSchemaFormat, LanguageModelToolUse,
-    LanguageModelToolUseId, MessageContent, StopReason,
+    AuthenticateError, LanguageModelCacheConfiguration, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelToolChoice, LanguageModelToolSchemaFormat,
+    LanguageModelToolUse, LanguageModelToolUseId, MessageContent, StopReason,
 };
 use language_model::{
-    LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModel, LanguageModelId, LanguageModelName, LanguageModelPricing, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
     LanguageModelRequest, RateLimiter, Role,
 };
+use parking_lot::Mutex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::pin::Pin;
 use std::sync::{
     Arc,
     atomic::{self, AtomicU64},
 };
+use std::time::{Duration, Instant};
 use strum::IntoEnumIterator;
 use theme::ThemeSettings;
 use ui::{Icon, IconName, List, Tooltip, prelude::*};
@@ -40,6 +46,12 @@ use crate::ui::InstructionListItem;
 const PROVIDER_ID: &str = "google";
 const PROVIDER_NAME: &str = "Google AI";
 
+/// How long the beginning of requests is cached for.
+const CONTEXT_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+/// How long before a context cache expires it stops being used, so that requests
+/// don't refer to a cache that expired by the time they're received.
+const CONTEXT_CACHE_EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct GoogleSettings {
     pub api_url: String,
@@ -81,11 +93,18 @@ pub struct AvailableModel {
     display_name: Option<String>,
     max_tokens: u64,
     mode: Option<ModelMode>,
+    /// Creates a context cache of the conversation once it's at least `min_total_token`
+    /// tokens long. Caching isn't enabled by default, as each cache is billed for its
+    /// storage in addition to the reduced price of the tokens read from it.
+    cache_configuration: Option<LanguageModelCacheConfiguration>,
+    /// The model's price, to estimate the cost of requests. Defaults to the price of the Google AI model with the same name, if any.
+    pricing: Option<LanguageModelPricing>,
 }
 
 pub struct GoogleLanguageModelProvider {
     http_client: Arc<dyn HttpClient>,
     state: gpui::Entity<State>,
+    context_caches: Arc<Mutex<ContextCaches>>,
 }
 
 pub struct State {
@@ -183,15 +202,27 @@ impl GoogleLanguageModelProvider {
             }),
         });
 
-        Self { http_client, state }
+        Self {
+            http_client,
+            state,
+            context_caches: Arc::default(),
+        }
     }
 
-    fn create_language_model(&self, model: google_ai::Model) -> Arc<dyn LanguageModel> {
+    fn create_language_model(
+        &self,
+        model: google_ai::Model,
+        cache_configuration: Option<LanguageModelCacheConfiguration>,
+        pricing: Option<LanguageModelPricing>,
+    ) -> Arc<dyn LanguageModel> {
         Arc::new(GoogleLanguageModel {
             id: LanguageModelId::from(model.id().to_string()),
+            pricing: pricing.or_else(|| google_model_pricing(&model)),
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
+            cache_configuration,
+            context_caches: self.context_caches.clone(),
             request_limiter: RateLimiter::new(4),
         })
     }
@@ -219,11 +250,11 @@ impl LanguageModelProvider for GoogleLanguageModelProvider {
     }
 
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(self.create_language_model(google_ai::Model::default()))
+        Some(self.create_language_model(google_ai::Model::default(), None, None))
     }
 
     fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(self.create_language_model(google_ai::Model::default_fast()))
+        Some(self.create_language_model(google_ai::Model::default_fast(), None, None))
     }
 
     fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
@@ -232,7 +263,7 @@ impl LanguageModelProvider for GoogleLanguageModelProvider {
         // Add base models from google_ai::Model::iter()
         for model in google_ai::Model::iter() {
             if !matches!(model, google_ai::Model::Custom { .. }) {
-                models.insert(model.id().to_string(), model);
+                models.insert(model.id().to_string(), (model, None, None));
             }
         }
 
@@ -243,25 +274,23 @@ impl LanguageModelProvider for GoogleLanguageModelProvider {
         {
             models.insert(
                 model.name.clone(),
-                google_ai::Model::Custom {
-                    name: model.name.clone(),
-                    display_name: model.display_name.clone(),
-                    max_tokens: model.max_tokens,
-                    mode: model.mode.unwrap_or_default().into(),
-                },
+                (
+                    google_ai::Model::Custom {
+                        name: model.name.clone(),
+                        display_name: model.display_name.clone(),
+                        max_tokens: model.max_tokens,
+                        mode: model.mode.unwrap_or_default().into(),
+                    },
+                    model.cache_configuration.clone(),
+                    model.pricing,
+                ),
             );
         }
 
         models
             .into_values()
-            .map(|model| {
-                Arc::new(GoogleLanguageModel {
-                    id: LanguageModelId::from(model.id().to_string()),
-                    model,
-                    state: self.state.clone(),
-                    http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
-                }) as Arc<dyn LanguageModel>
+            .map(|(model, cache_configuration, pricing)| {
+                self.create_language_model(model, cache_configuration, pricing)
             })
             .collect()
     }
@@ -287,8 +316,11 @@ impl LanguageModelProvider for GoogleLanguageModelProvider {
 pub struct GoogleLanguageModel {
     id: LanguageModelId,
     model: google_ai::Model,
+    pricing: Option<LanguageModelPricing>,
     state: gpui::Entity<State>,
     http_client: Arc<dyn HttpClient>,
+    cache_configuration: Option<LanguageModelCacheConfiguration>,
+    context_caches: Arc<Mutex<ContextCaches>>,
     request_limiter: RateLimiter,
 }
 
@@ -322,6 +354,183 @@ impl GoogleLanguageModel {
         }
         .boxed()
     }
+
+    /// Caches the first contents of the request once its response shows that it's large
+    /// enough to be cached, so that following requests starting with them can refer to
+    /// the cache. Only models with a cache configuration are cached.
+    fn create_context_cache(
+        &self,
+        request: &GenerateContentRequest,
+        cacheable_content_count: usize,
+        prompt_token_count: oneshot::Receiver<u64>,
+        cx: &AsyncApp,
+    ) {
+        let Some(min_token_count) = self
+            .cache_configuration()
+            .map(|config| config.min_total_token)
+        else {
+            return;
+        };
+        if cacheable_content_count == 0 {
+            return;
+        }
+        let Some(key) = context_cache_keys(request, cacheable_content_count)
+            .log_err()
+            .and_then(|keys| keys.last().copied())
+        else {
+            return;
+        };
+        if self.context_caches.lock().contains(key, Instant::now()) {
+            return;
+        }
+
+        let Ok((api_key, api_url)) = cx.read_entity(&self.state, |state, cx| {
+            let settings = &AllLanguageModelSettings::get_global(cx).google;
+            (state.api_key.clone(), settings.api_url.clone())
+        }) else {
+            return;
+        };
+        let cache_request = CreateCachedContentRequest {
+            model: request.model.clone(),
+            contents: request.contents[..cacheable_content_count].to_vec(),
+            system_instruction: request.system_instruction.clone(),
+            tools: request.tools.clone(),
+            tool_config: request.tool_config.clone(),
+            ttl: format!("{}s", CONTEXT_CACHE_TTL.as_secs()),
+        };
+        let http_client = self.http_client.clone();
+        let context_caches = self.context_caches.clone();
+        cx.background_executor()
+            .spawn(async move {
+                let Some(api_key) = api_key else {
+                    return;
+                };
+                let Ok(prompt_token_count) = prompt_token_count.await else {
+                    return;
+                };
+                if prompt_token_count < min_token_count {
+                    return;
+                }
+
+                let expires_at = Instant::now() + CONTEXT_CACHE_TTL - CONTEXT_CACHE_EXPIRY_MARGIN;
+                match google_ai::create_cached_content(
+                    http_client.as_ref(),
+                    &api_url,
+                    &api_key,
+                    cache_request,
+                )
+                .await
+                {
+                    Ok(cached_content) => {
+                        context_caches
+                            .lock()
+                            .insert(key, cached_content.name, expires_at);
+                    }
+                    Err(error) => {
+                        log::error!("failed to create Google AI context cache: {error:#}");
+                    }
+                }
+            })
+            .detach();
+    }
+}
+
+/// The context caches created for requests to Google AI, keyed by a hash of the model
+/// and the first contents of the request they contain.
+#[derive(Default)]
+struct ContextCaches {
+    caches: HashMap<u64, ContextCache>,
+}
+
+struct ContextCache {
+    name: String,
+    expires_at: Instant,
+}
+
+impl ContextCaches {
+    fn contains(&self, key: u64, now: Instant) -> bool {
+        self.caches
+            .get(&key)
+            .is_some_and(|cache| cache.expires_at > now)
+    }
+
+    fn insert(&mut self, key: u64, name: String, expires_at: Instant) {
+        self.caches.insert(key, ContextCache { name, expires_at });
+    }
+
+    /// Makes the request refer to the cache containing the most of its first contents,
+    /// instead of sending them again.
+    fn apply(&mut self, request: &mut GenerateContentRequest, now: Instant) {
+        self.caches.retain(|_, cache| cache.expires_at > now);
+        if self.caches.is_empty() {
+            return;
+        }
+
+        // The request must contain some contents besides the cached ones.
+        let Some(keys) =
+            context_cache_keys(request, request.contents.len().saturating_sub(1)).log_err()
+        else {
+            return;
+        };
+        let Some((cached_content_count, cache)) = keys
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(ix, key)| Some((ix + 1, self.caches.get(key)?)))
+        else {
+            return;
+        };
+
+        request.cached_content = Some(cache.name.clone());
+        request.contents.drain(..cached_content_count);
+        // The cache provides these, and requests using it must not set them.
+        request.system_instruction = None;
+        request.tools = None;
+        request.tool_config = None;
+    }
+}
+
+/// Returns the keys of the context caches that would contain the first content of the
+/// request, its first two contents, and so on up to the given number of contents.
+fn context_cache_keys(request: &GenerateContentRequest, content_count: usize) -> Result<Vec<u64>> {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(&(
+        &request.model,
+        &request.system_instruction,
+        &request.tools,
+        &request.tool_config,
+    ))?
+    .hash(&mut hasher);
+
+    let mut keys = Vec::with_capacity(content_count);
+    for content in request.contents.iter().take(content_count) {
+        serde_json::to_string(content)?.hash(&mut hasher);
+        keys.push(hasher.clone().finish());
+    }
+    Ok(keys)
+}
+
+/// Reports the number of tokens in the prompt once the response tells it.
+fn report_prompt_token_count(
+    response: BoxStream<'static, Result<GenerateContentResponse>>,
+    prompt_token_count_tx: oneshot::Sender<u64>,
+) -> BoxStream<'static, Result<GenerateContentResponse>> {
+    let mut prompt_token_count_tx = Some(prompt_token_count_tx);
+    response
+        .inspect(move |event| {
+            let prompt_token_count = event.as_ref().ok().and_then(|event| {
+                event
+                    .usage_metadata
+                    .as_ref()
+                    .and_then(|usage| usage.prompt_token_count)
+            });
+            if let Some(prompt_token_count) = prompt_token_count {
+                if let Some(tx) = prompt_token_count_tx.take() {
+                    tx.send(prompt_token_count).ok();
+                }
+            }
+        })
+        .boxed()
 }
 
 impl LanguageModel for GoogleLanguageModel {
@@ -373,6 +582,25 @@ impl LanguageModel for GoogleLanguageModel {
         self.model.max_output_tokens()
     }
 
+    fn cache_configuration(&self) -> Option<LanguageModelCacheConfiguration> {
+        let config = self.cache_configuration.as_ref()?;
+        // Google AI only caches the contents up to a single breakpoint, and rejects caches
+        // smaller than the model's minimum.
+        let min_total_token = match self.model.min_context_cache_token_count() {
+            Some(min_token_count) => config.min_total_token.max(min_token_count),
+            None => config.min_total_token,
+        };
+        Some(LanguageModelCacheConfiguration {
+            max_cache_anchors: 1,
+            should_speculate: false,
+            min_total_token,
+        })
+    }
+
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        self.pricing
+    }
+
     fn count_tokens(
         &self,
         request: LanguageModelRequest,
@@ -416,27 +644,74 @@ impl LanguageModel for GoogleLanguageModel {
             LanguageModelCompletionError,
         >,
     > {
-        let request = into_google(
+        let (mut request, cacheable_content_count) = into_google_with_cache_breakpoint(
             request,
             self.model.request_id().to_string(),
             self.model.mode(),
         );
+        let (prompt_token_count_tx, prompt_token_count_rx) = oneshot::channel();
+        self.create_context_cache(&request, cacheable_content_count, prompt_token_count_rx, cx);
+        self.context_caches
+            .lock()
+            .apply(&mut request, Instant::now());
+
         let request = self.stream_completion(request, cx);
         let future = self.request_limiter.stream(async move {
             let response = request
                 .await
                 .map_err(|err| LanguageModelCompletionError::Other(anyhow!(err)))?;
+            let response = report_prompt_token_count(response, prompt_token_count_tx);
             Ok(GoogleEventMapper::new().map_stream(response))
         });
         async move { Ok(future.await?.boxed()) }.boxed()
     }
 }
 
+/// Returns the price of the given model, or of the Google AI model a custom model is named after.
+///
+/// Prices are those of prompts up to 128k tokens, or 200k tokens for Gemini 2.5 Pro. Longer
+/// prompts are billed at a higher rate. Context cache storage isn't included.
+fn google_model_pricing(model: &google_ai::Model) -> Option<LanguageModelPricing> {
+    let (input, output, cache_read_input) = match model {
+        google_ai::Model::Gemini15Pro => (1.25, 5., Some(0.3125)),
+        google_ai::Model::Gemini15Flash8b => (0.0375, 0.15, Some(0.01)),
+        google_ai::Model::Gemini15Flash => (0.075, 0.3, Some(0.01875)),
+        google_ai::Model::Gemini20FlashLite => (0.075, 0.3, None),
+        google_ai::Model::Gemini20Flash | google_ai::Model::Gemini25FlashLitePreview => {
+            (0.1, 0.4, Some(0.025))
+        }
+        google_ai::Model::Gemini25Flash => (0.3, 2.5, Some(0.075)),
+        google_ai::Model::Gemini25Pro => (1.25, 10., Some(0.31)),
+        google_ai::Model::Custom { name, .. } => {
+            return google_ai::Model::iter()
+                .filter(|model| !matches!(model, google_ai::Model::Custom { .. }))
+                .find(|model| model.id() == name)
+                .and_then(|model| google_model_pricing(&model));
+        }
+    };
+    Some(LanguageModelPricing {
+        input,
+        output,
+        cache_creation_input: None,
+        cache_read_input,
+    })
+}
+
 pub fn into_google(
-    mut request: LanguageModelRequest,
+    request: LanguageModelRequest,
     model_id: String,
     mode: GoogleModelMode,
 ) -> google_ai::GenerateContentRequest {
+    into_google_with_cache_breakpoint(request, model_id, mode).0
+}
+
+/// Converts the request like [`into_google`], also returning the number of contents up
+/// to the last message marked as cacheable.
+fn into_google_with_cache_breakpoint(
+    mut request: LanguageModelRequest,
+    model_id: String,
+    mode: GoogleModelMode,
+) -> (google_ai::GenerateContentRequest, usize) {
     fn map_content(content: Vec<MessageContent>) -> Vec<Part> {
         content
             .into_iter()
@@ -534,28 +809,29 @@ pub fn into_google(
         None
     };
 
-    google_ai::GenerateContentRequest {
+    let mut contents = Vec::new();
+    let mut cacheable_content_count = 0;
+    for message in request.messages {
+        let parts = map_content(message.content);
+        if !parts.is_empty() {
+            contents.push(google_ai::Content {
+                parts,
+                role: match message.role {
+                    Role::User => google_ai::Role::User,
+                    Role::Assistant => google_ai::Role::Model,
+                    Role::System => google_ai::Role::User, // Google AI doesn't have a system role
+                },
+            });
+        }
+        if message.cache {
+            cacheable_content_count = contents.len();
+        }
+    }
+
+    let google_request = google_ai::GenerateContentRequest {
         model: google_ai::ModelName { model_id },
         system_instruction: system_instructions,
-        contents: request
-            .messages
-            .into_iter()
-            .filter_map(|message| {
-                let parts = map_content(message.content);
-                if parts.is_empty() {
-                    None
-                } else {
-                    Some(google_ai::Content {
-                        parts,
-                        role: match message.role {
-                            Role::User => google_ai::Role::User,
-                            Role::Assistant => google_ai::Role::Model,
-                            Role::System => google_ai::Role::User, // Google AI doesn't have a system role
-                        },
-                    })
-                }
-            })
-            .collect(),
+        contents,
         generation_config: Some(google_ai::GenerationConfig {
             candidate_count: Some(1),
             stop_sequences: Some(request.stop),
@@ -594,7 +870,9 @@ pub fn into_google(
                 allowed_function_names: None,
             },
         }),
-    }
+        cached_content: None,
+    };
+    (google_request, cacheable_content_count)
 }
 
 pub struct GoogleEventMapper {
@@ -945,3 +1223,122 @@ impl Render for ConfigurationView {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language_model::LanguageModelRequestMessage;
+
+    fn message(role: Role, text: &str, cache: bool) -> LanguageModelRequestMessage {
+        LanguageModelRequestMessage {
+            role,
+            content: vec![MessageContent::Text(text.into())],
+            cache,
+        }
+    }
+
+    fn into_request(messages: Vec<LanguageModelRequestMessage>) -> (GenerateContentRequest, usize) {
+        into_google_with_cache_breakpoint(
+            LanguageModelRequest {
+                messages,
+                ..Default::default()
+            },
+            "gemini-2.5-flash".into(),
+            GoogleModelMode::Default,
+        )
+    }
+
+    #[test]
+    fn test_context_caches() {
+        let first_messages = vec![
+            message(Role::System, "You are a helpful assistant.", false),
+            message(Role::User, "Hello", false),
+            message(Role::Assistant, "", false),
+            message(Role::Assistant, "Hi!", false),
+            message(Role::User, "How are you?", true),
+        ];
+        let (first_request, cacheable_content_count) = into_request(first_messages.clone());
+        assert_eq!(cacheable_content_count, 3);
+
+        let now = Instant::now();
+        let mut caches = ContextCaches::default();
+        let keys = context_cache_keys(&first_request, cacheable_content_count).unwrap();
+        caches.insert(
+            *keys.last().unwrap(),
+            "cachedContents/first".into(),
+            now + CONTEXT_CACHE_TTL,
+        );
+
+        // Requests starting with the cached contents refer to the cache instead.
+        let mut messages = first_messages.clone();
+        messages[4].cache = false;
+        messages.push(message(Role::Assistant, "Great!", false));
+        messages.push(message(Role::User, "Bye", true));
+        let (mut request, cacheable_content_count) = into_request(messages.clone());
+        assert_eq!(cacheable_content_count, 5);
+        caches.apply(&mut request, now);
+        assert_eq!(
+            request.cached_content.as_deref(),
+            Some("cachedContents/first")
+        );
+        assert_eq!(request.contents.len(), 2);
+        assert!(matches!(
+            &request.contents[0].parts[0],
+            Part::TextPart(part) if part.text == "Great!"
+        ));
+        assert!(request.system_instruction.is_none());
+
+        // Requests must contain some contents besides the cached ones.
+        let (mut request, _) = into_request(first_messages);
+        caches.apply(&mut request, now);
+        assert!(request.cached_content.is_none());
+        assert_eq!(request.contents.len(), 3);
+
+        // Requests starting with other contents don't refer to the cache.
+        let mut other_messages = messages.clone();
+        other_messages[1] = message(Role::User, "Hey", false);
+        let (mut request, _) = into_request(other_messages);
+        caches.apply(&mut request, now);
+        assert!(request.cached_content.is_none());
+        assert_eq!(request.contents.len(), 5);
+        assert!(request.system_instruction.is_some());
+
+        // Expired caches aren't used.
+        let (mut request, _) = into_request(messages);
+        caches.apply(&mut request, now + CONTEXT_CACHE_TTL);
+        assert!(request.cached_content.is_none());
+        assert_eq!(request.contents.len(), 5);
+    }
+
+    #[test]
+    fn test_google_model_pricing() {
+        let custom_model = google_ai::Model::Custom {
+            name: "gemini-2.5-pro".into(),
+            display_name: None,
+            max_tokens: 1_000_000,
+            mode: GoogleModelMode::Default,
+        };
+        let pricing = google_model_pricing(&custom_model).unwrap();
+        assert_eq!(
+            Some(pricing),
+            google_model_pricing(&google_ai::Model::Gemini25Pro)
+        );
+
+        // Tokens read from a context cache are priced at the cache read price.
+        let usage = convert_usage(&UsageMetadata {
+            prompt_token_count: Some(1_000_000),
+            cached_content_token_count: Some(800_000),
+            candidates_token_count: Some(100_000),
+            ..Default::default()
+        });
+        assert!((pricing.cost(&usage) - 1.498).abs() < 1e-9);
+
+        let unknown_model = google_ai::Model::Custom {
+            name: "gemini-next".into(),
+            display_name: None,
+            max_tokens: 1_000_000,
+            mode: GoogleModelMode::Default,
+        };
+        assert_eq!(google_model_pricing(&unknown_model), None);
+    }
+}
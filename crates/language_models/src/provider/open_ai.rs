@@ -9,10 +9,10 @@ use gpui::{AnyView, App, AsyncApp, Context, Entity, Subscription, Task, Window};
 use http_client::HttpClient;
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
-    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
-    RateLimiter, Role, StopReason, TokenUsage,
+    LanguageModelId, LanguageModelName, LanguageModelPricing, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent,
+    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, TokenUsage,
 };
 use menu;
 use open_ai::{ImageUrl, Model, ResponseStreamEvent, stream_completion};
@@ -47,6 +47,8 @@ pub struct AvailableModel {
     pub max_tokens: u64,
     pub max_output_tokens: Option<u64>,
     pub max_completion_tokens: Option<u64>,
+    /// The model's price, to estimate the cost of requests.
+    pub pricing: Option<LanguageModelPricing>,
 }
 
 pub struct OpenAiLanguageModelProvider {
@@ -152,9 +154,14 @@ impl OpenAiLanguageModelProvider {
         Self { http_client, state }
     }
 
-    fn create_language_model(&self, model: open_ai::Model) -> Arc<dyn LanguageModel> {
+    fn create_language_model(
+        &self,
+        model: open_ai::Model,
+        pricing: Option<LanguageModelPricing>,
+    ) -> Arc<dyn LanguageModel> {
         Arc::new(OpenAiLanguageModel {
             id: LanguageModelId::from(model.id().to_string()),
+            pricing: pricing.or_else(|| open_ai_model_pricing(&model)),
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
@@ -185,11 +192,11 @@ impl LanguageModelProvider for OpenAiLanguageModelProvider {
     }
 
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(self.create_language_model(open_ai::Model::default()))
+        Some(self.create_language_model(open_ai::Model::default(), None))
     }
 
     fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(self.create_language_model(open_ai::Model::default_fast()))
+        Some(self.create_language_model(open_ai::Model::default_fast(), None))
     }
 
     fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
@@ -198,7 +205,7 @@ impl LanguageModelProvider for OpenAiLanguageModelProvider {
         // Add base models from open_ai::Model::iter()
         for model in open_ai::Model::iter() {
             if !matches!(model, open_ai::Model::Custom { .. }) {
-                models.insert(model.id().to_string(), model);
+                models.insert(model.id().to_string(), (model, None));
             }
         }
 
@@ -209,19 +216,22 @@ impl LanguageModelProvider for OpenAiLanguageModelProvider {
         {
             models.insert(
                 model.name.clone(),
-                open_ai::Model::Custom {
-                    name: model.name.clone(),
-                    display_name: model.display_name.clone(),
-                    max_tokens: model.max_tokens,
-                    max_output_tokens: model.max_output_tokens,
-                    max_completion_tokens: model.max_completion_tokens,
-                },
+                (
+                    open_ai::Model::Custom {
+                        name: model.name.clone(),
+                        display_name: model.display_name.clone(),
+                        max_tokens: model.max_tokens,
+                        max_output_tokens: model.max_output_tokens,
+                        max_completion_tokens: model.max_completion_tokens,
+                    },
+                    model.pricing,
+                ),
             );
         }
 
         models
             .into_values()
-            .map(|model| self.create_language_model(model))
+            .map(|(model, pricing)| self.create_language_model(model, pricing))
             .collect()
     }
 
@@ -246,6 +256,7 @@ impl LanguageModelProvider for OpenAiLanguageModelProvider {
 pub struct OpenAiLanguageModel {
     id: LanguageModelId,
     model: open_ai::Model,
+    pricing: Option<LanguageModelPricing>,
     state: gpui::Entity<State>,
     http_client: Arc<dyn HttpClient>,
     request_limiter: RateLimiter,
@@ -330,6 +341,10 @@ impl LanguageModel for OpenAiLanguageModel {
         count_open_ai_tokens(request, self.model.clone(), cx)
     }
 
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        self.pricing
+    }
+
     fn stream_completion(
         &self,
         request: LanguageModelRequest,
@@ -359,6 +374,30 @@ impl LanguageModel for OpenAiLanguageModel {
     }
 }
 
+fn open_ai_model_pricing(model: &open_ai::Model) -> Option<LanguageModelPricing> {
+    let (input, output, cache_read_input) = match model {
+        Model::ThreePointFiveTurbo => (0.5, 1.5, None),
+        Model::Four => (30., 60., None),
+        Model::FourTurbo => (10., 30., None),
+        Model::FourOmni => (2.5, 10., Some(1.25)),
+        Model::FourOmniMini => (0.15, 0.6, Some(0.075)),
+        Model::FourPointOne => (2., 8., Some(0.5)),
+        Model::FourPointOneMini => (0.4, 1.6, Some(0.1)),
+        Model::FourPointOneNano => (0.1, 0.4, Some(0.025)),
+        Model::O1 => (15., 60., Some(7.5)),
+        Model::O3 => (2., 8., Some(0.5)),
+        Model::O3Mini => (1.1, 4.4, Some(0.55)),
+        Model::O4Mini => (1.1, 4.4, Some(0.275)),
+        Model::Custom { .. } => return None,
+    };
+    Some(LanguageModelPricing {
+        input,
+        output,
+        cache_creation_input: None,
+        cache_read_input,
+    })
+}
+
 pub fn into_open_ai(
     request: LanguageModelRequest,
     model_id: &str,
@@ -536,11 +575,15 @@ impl OpenAiEventMapper {
     ) -> Vec<Result<LanguageModelCompletionEvent, LanguageModelCompletionError>> {
         let mut events = Vec::new();
         if let Some(usage) = event.usage {
+            // The prompt tokens include the ones that were read from the cache.
+            let cached_tokens = usage
+                .prompt_tokens_details
+                .map_or(0, |details| details.cached_tokens);
             events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
-                input_tokens: usage.prompt_tokens,
+                input_tokens: usage.prompt_tokens.saturating_sub(cached_tokens),
                 output_tokens: usage.completion_tokens,
                 cache_creation_input_tokens: 0,
-                cache_read_input_tokens: 0,
+                cache_read_input_tokens: cached_tokens,
             })));
         }
 
@@ -0,0 +1,836 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::{BTreeMap, HashSet};
+use credentials_provider::CredentialsProvider;
+use futures::{FutureExt, StreamExt, future::BoxFuture};
+use gpui::{AnyView, App, AsyncApp, Context, Entity, Subscription, Task, Window};
+use http_client::HttpClient;
+use language_model::{
+    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelPricing, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRegistry, LanguageModelRequest, LanguageModelToolChoice, RateLimiter,
+};
+use menu;
+use open_ai::ResponseStreamEvent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use ui::{ElevationIndex, Tooltip, prelude::*};
+use ui_input::SingleLineInput;
+use util::ResultExt;
+
+use crate::AllLanguageModelSettings;
+use crate::provider::open_ai::{OpenAiEventMapper, count_open_ai_tokens, into_open_ai};
+
+/// The context length assumed for listed models whose context length isn't reported by the API.
+const DEFAULT_MAX_TOKENS: u64 = 32_768;
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct OpenAiCompatibleSettings {
+    pub api_url: String,
+    pub auth_header: Option<String>,
+    pub capabilities: ModelCapabilities,
+    pub available_models: Vec<AvailableModel>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ModelCapabilities {
+    /// Whether the model supports tool calls.
+    pub tools: bool,
+    /// Whether the model supports images in its input.
+    pub images: bool,
+    /// Whether the API accepts the `parallel_tool_calls` parameter.
+    pub parallel_tool_calls: bool,
+}
+
+impl Default for ModelCapabilities {
+    fn default() -> Self {
+        Self {
+            tools: true,
+            images: false,
+            parallel_tool_calls: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AvailableModel {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub max_tokens: u64,
+    pub max_output_tokens: Option<u64>,
+    /// Overrides the capabilities of the provider for this model.
+    pub capabilities: Option<ModelCapabilities>,
+    /// The model's price, to estimate the cost of requests.
+    pub pricing: Option<LanguageModelPricing>,
+}
+
+/// Registers a provider for each OpenAI-compatible API in the settings, and keeps them in
+/// sync as the settings change.
+pub fn init(http_client: Arc<dyn HttpClient>, cx: &mut App) {
+    let mut registered_providers = HashSet::default();
+    register_providers(&http_client, &mut registered_providers, cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        register_providers(&http_client, &mut registered_providers, cx);
+    })
+    .detach();
+}
+
+fn register_providers(
+    http_client: &Arc<dyn HttpClient>,
+    registered_providers: &mut HashSet<Arc<str>>,
+    cx: &mut App,
+) {
+    let names = AllLanguageModelSettings::get_global(cx)
+        .openai_compatible
+        .keys()
+        .cloned()
+        .collect::<HashSet<_>>();
+    if names == *registered_providers {
+        return;
+    }
+
+    LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+        for name in registered_providers.difference(&names) {
+            registry.unregister_provider(LanguageModelProviderId(name.clone().into()), cx);
+        }
+        registered_providers.retain(|name| names.contains(name));
+
+        for name in names {
+            if registered_providers.contains(&name) {
+                continue;
+            }
+            if registry
+                .provider(&LanguageModelProviderId(name.clone().into()))
+                .is_some()
+            {
+                log::error!(
+                    "not registering OpenAI-compatible provider {name:?}, because a provider with the same ID already exists"
+                );
+                continue;
+            }
+
+            let provider =
+                OpenAiCompatibleLanguageModelProvider::new(name.clone(), http_client.clone(), cx);
+            registry.register_provider(provider, cx);
+            registered_providers.insert(name);
+        }
+    });
+}
+
+fn provider_settings<'a>(name: &str, cx: &'a App) -> Option<&'a OpenAiCompatibleSettings> {
+    AllLanguageModelSettings::get_global(cx)
+        .openai_compatible
+        .get(name)
+}
+
+/// Returns the header to authenticate requests to the API with, if an API key is set.
+fn auth_header(
+    settings: &OpenAiCompatibleSettings,
+    api_key: Option<&str>,
+) -> Option<(String, String)> {
+    let api_key = api_key?;
+    Some(match settings.auth_header.as_ref() {
+        Some(header) => (header.clone(), api_key.to_string()),
+        None => ("Authorization".to_string(), format!("Bearer {api_key}")),
+    })
+}
+
+/// Lists the models of the API. Listed models use the capabilities configured for the provider.
+async fn list_models(
+    http_client: &dyn HttpClient,
+    settings: &OpenAiCompatibleSettings,
+    api_key: Option<&str>,
+) -> Result<Vec<AvailableModel>> {
+    let auth_header = auth_header(settings, api_key);
+    let models = open_ai::list_models(
+        http_client,
+        &settings.api_url,
+        auth_header
+            .as_ref()
+            .map(|(name, value)| (name.as_str(), value.as_str())),
+    )
+    .await?;
+
+    Ok(models
+        .into_iter()
+        .map(|model| AvailableModel {
+            name: model.id,
+            display_name: None,
+            max_tokens: model.max_model_len.unwrap_or(DEFAULT_MAX_TOKENS),
+            max_output_tokens: None,
+            capabilities: None,
+            pricing: None,
+        })
+        .collect())
+}
+
+pub struct OpenAiCompatibleLanguageModelProvider {
+    id: LanguageModelProviderId,
+    name: LanguageModelProviderName,
+    http_client: Arc<dyn HttpClient>,
+    state: Entity<State>,
+}
+
+pub struct State {
+    name: Arc<str>,
+    http_client: Arc<dyn HttpClient>,
+    api_key: Option<String>,
+    /// The models listed by the API, or `None` if they couldn't be listed yet.
+    listed_models: Option<Vec<AvailableModel>>,
+    fetch_models_task: Option<Task<Result<()>>>,
+    _subscription: Subscription,
+}
+
+impl State {
+    /// Returns whether the provider can be used, which is the case when an API key is
+    /// stored or models are available. Failing to list the models only limits the
+    /// models that are available.
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.api_key.is_some()
+            || self
+                .listed_models
+                .as_ref()
+                .is_some_and(|models| !models.is_empty())
+            || provider_settings(&self.name, cx)
+                .is_some_and(|settings| !settings.available_models.is_empty())
+    }
+
+    fn reset_api_key(&self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let Some(api_url) = provider_settings(&self.name, cx).map(|s| s.api_url.clone()) else {
+            return Task::ready(Ok(()));
+        };
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .delete_credentials(&api_url, &cx)
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.api_key = None;
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn set_api_key(&mut self, api_key: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let Some(api_url) = provider_settings(&self.name, cx).map(|s| s.api_url.clone()) else {
+            return Task::ready(Ok(()));
+        };
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .write_credentials(&api_url, "Bearer", api_key.as_bytes(), &cx)
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.api_key = Some(api_key);
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn authenticate(&mut self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.api_key.is_some()
+            || self
+                .listed_models
+                .as_ref()
+                .is_some_and(|models| !models.is_empty())
+        {
+            return Task::ready(Ok(()));
+        }
+
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let Some(api_url) = provider_settings(&self.name, cx).map(|s| s.api_url.clone()) else {
+            return Task::ready(Err(anyhow!("{} is not configured", self.name).into()));
+        };
+        let name = self.name.clone();
+        cx.spawn(async move |this, cx| {
+            let api_key = credentials_provider
+                .read_credentials(&api_url, &cx)
+                .await?
+                .map(|(_, api_key)| String::from_utf8(api_key))
+                .transpose()
+                .with_context(|| format!("invalid {name} API key"))?;
+
+            let fetch_models_task = this.update(cx, |this, cx| {
+                this.api_key = api_key;
+                this.fetch_models(cx)
+            })?;
+            let fetch_models_result = fetch_models_task.await;
+            this.update(cx, |this, cx| {
+                if this.is_authenticated(cx) {
+                    fetch_models_result.log_err();
+                    Ok(())
+                } else {
+                    fetch_models_result?;
+                    Err(anyhow!("{name} has no API key or models").into())
+                }
+            })?
+        })
+    }
+
+    fn fetch_models(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some(settings) = provider_settings(&self.name, cx).cloned() else {
+            return Task::ready(Ok(()));
+        };
+        let http_client = self.http_client.clone();
+        let api_key = self.api_key.clone();
+        cx.spawn(async move |this, cx| {
+            let models = list_models(http_client.as_ref(), &settings, api_key.as_deref()).await;
+            this.update(cx, |this, cx| {
+                this.listed_models = models.as_ref().ok().cloned();
+                cx.notify();
+            })?;
+            models.map(|_| ())
+        })
+    }
+
+    fn restart_fetch_models_task(&mut self, cx: &mut Context<Self>) {
+        let task = self.fetch_models(cx);
+        self.fetch_models_task.replace(task);
+    }
+}
+
+impl OpenAiCompatibleLanguageModelProvider {
+    pub fn new(name: Arc<str>, http_client: Arc<dyn HttpClient>, cx: &mut App) -> Self {
+        let state = cx.new(|cx| {
+            let subscription = cx.observe_global::<SettingsStore>({
+                let name = name.clone();
+                let mut settings = provider_settings(&name, cx).cloned();
+                move |this: &mut State, cx| {
+                    let new_settings = provider_settings(&name, cx);
+                    if settings.as_ref() != new_settings {
+                        settings = new_settings.cloned();
+                        this.restart_fetch_models_task(cx);
+                        cx.notify();
+                    }
+                }
+            });
+
+            State {
+                name: name.clone(),
+                http_client: http_client.clone(),
+                api_key: None,
+                listed_models: None,
+                fetch_models_task: None,
+                _subscription: subscription,
+            }
+        });
+
+        Self {
+            id: LanguageModelProviderId(name.clone().into()),
+            name: LanguageModelProviderName(name.into()),
+            http_client,
+            state,
+        }
+    }
+
+    fn create_language_model(
+        &self,
+        model: AvailableModel,
+        capabilities: ModelCapabilities,
+    ) -> Arc<dyn LanguageModel> {
+        Arc::new(OpenAiCompatibleLanguageModel {
+            id: LanguageModelId::from(model.name.clone()),
+            model,
+            capabilities,
+            provider_id: self.id.clone(),
+            provider_name: self.name.clone(),
+            state: self.state.clone(),
+            http_client: self.http_client.clone(),
+            request_limiter: RateLimiter::new(4),
+        })
+    }
+}
+
+impl LanguageModelProviderState for OpenAiCompatibleLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<gpui::Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for OpenAiCompatibleLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.name.clone()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::AiOpenAi
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        self.provided_models(cx).into_iter().next()
+    }
+
+    fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        None
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        let Some(settings) = provider_settings(&self.name.0, cx) else {
+            return Vec::new();
+        };
+
+        let mut models = BTreeMap::default();
+
+        // Add models listed by the API
+        for model in self.state.read(cx).listed_models.iter().flatten() {
+            models.insert(model.name.clone(), model.clone());
+        }
+
+        // Override with available models from settings
+        for model in &settings.available_models {
+            models.insert(model.name.clone(), model.clone());
+        }
+
+        models
+            .into_values()
+            .map(|model| {
+                let capabilities = model.capabilities.unwrap_or(settings.capabilities);
+                self.create_language_model(model, capabilities)
+            })
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated(cx)
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, window: &mut Window, cx: &mut App) -> AnyView {
+        cx.new(|cx| ConfigurationView::new(self.state.clone(), window, cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.reset_api_key(cx))
+    }
+}
+
+pub struct OpenAiCompatibleLanguageModel {
+    id: LanguageModelId,
+    model: AvailableModel,
+    capabilities: ModelCapabilities,
+    provider_id: LanguageModelProviderId,
+    provider_name: LanguageModelProviderName,
+    state: Entity<State>,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl OpenAiCompatibleLanguageModel {
+    fn stream_completion(
+        &self,
+        request: open_ai::Request,
+        cx: &AsyncApp,
+    ) -> BoxFuture<'static, Result<futures::stream::BoxStream<'static, Result<ResponseStreamEvent>>>>
+    {
+        let http_client = self.http_client.clone();
+        let Ok(settings) = cx.read_entity(&self.state, |state, cx| {
+            provider_settings(&state.name, cx)
+                .map(|settings| (settings.clone(), state.api_key.clone()))
+        }) else {
+            return futures::future::ready(Err(anyhow!("App state dropped"))).boxed();
+        };
+
+        let future = self.request_limiter.stream(async move {
+            let (settings, api_key) = settings.context("provider is no longer configured")?;
+            let auth_header = auth_header(&settings, api_key.as_deref());
+            let response = open_ai::stream_completion_with_auth(
+                http_client.as_ref(),
+                &settings.api_url,
+                auth_header
+                    .as_ref()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+                request,
+            )
+            .await?;
+            Ok(response)
+        });
+
+        async move { Ok(future.await?.boxed()) }.boxed()
+    }
+}
+
+impl LanguageModel for OpenAiCompatibleLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(
+            self.model
+                .display_name
+                .clone()
+                .unwrap_or_else(|| self.model.name.clone()),
+        )
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.provider_id.clone()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.capabilities.tools
+    }
+
+    fn supports_images(&self) -> bool {
+        self.capabilities.images
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        match choice {
+            LanguageModelToolChoice::Auto
+            | LanguageModelToolChoice::Any
+            | LanguageModelToolChoice::None => self.capabilities.tools,
+        }
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("{}/{}", self.provider_id, self.model.name)
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.model.max_tokens
+    }
+
+    fn max_output_tokens(&self) -> Option<u64> {
+        self.model.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        let model = open_ai::Model::Custom {
+            name: self.model.name.clone(),
+            display_name: self.model.display_name.clone(),
+            max_tokens: self.model.max_tokens,
+            max_output_tokens: self.model.max_output_tokens,
+            max_completion_tokens: None,
+        };
+        count_open_ai_tokens(request, model, cx)
+    }
+
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        self.model.pricing
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            futures::stream::BoxStream<
+                'static,
+                Result<LanguageModelCompletionEvent, LanguageModelCompletionError>,
+            >,
+            LanguageModelCompletionError,
+        >,
+    > {
+        let request = into_open_ai(
+            request,
+            &self.model.name,
+            self.capabilities.parallel_tool_calls,
+            self.max_output_tokens(),
+        );
+        let completions = self.stream_completion(request, cx);
+        async move {
+            let mapper = OpenAiEventMapper::new();
+            Ok(mapper.map_stream(completions.await?).boxed())
+        }
+        .boxed()
+    }
+}
+
+struct ConfigurationView {
+    api_key_editor: Entity<SingleLineInput>,
+    state: Entity<State>,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: Entity<State>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let api_key_editor = cx
+            .new(|cx| SingleLineInput::new(window, cx, "Paste your API key here").label("API key"));
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn_in(window, {
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    // We don't log an error, because an unreachable API is also an error.
+                    let _ = task.await;
+                }
+                this.update(cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let api_key = self
+            .api_key_editor
+            .read(cx)
+            .editor()
+            .read(cx)
+            .text(cx)
+            .trim()
+            .to_string();
+        if api_key.is_empty() {
+            return;
+        }
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(api_key, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn reset_api_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.api_key_editor.update(cx, |input, cx| {
+            input.editor.update(cx, |editor, cx| {
+                editor.set_text("", window, cx);
+            });
+        });
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state.update(cx, |state, cx| state.reset_api_key(cx))?.await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let name = state.name.clone();
+        let has_api_key = state.api_key.is_some();
+        let is_authenticated = state.is_authenticated(cx);
+        let api_url = provider_settings(&name, cx)
+            .map(|settings| settings.api_url.clone())
+            .unwrap_or_default();
+
+        if self.load_credentials_task.is_some() {
+            div().child(Label::new("Loading credentials…")).into_any()
+        } else if !is_authenticated {
+            v_flex()
+                .size_full()
+                .on_action(cx.listener(Self::save_api_key))
+                .child(Label::new(format!(
+                    "Zed couldn't list the models of {name} at {api_url}. If the API requires a key, paste it below and hit enter."
+                )))
+                .child(self.api_key_editor.clone())
+                .into_any()
+        } else {
+            h_flex()
+                .mt_1()
+                .p_1()
+                .justify_between()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().background)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(Label::new(format!("Connected to {api_url}."))),
+                )
+                .when(has_api_key, |this| {
+                    this.child(
+                        Button::new("reset-api-key", "Reset API Key")
+                            .label_size(LabelSize::Small)
+                            .icon(IconName::Undo)
+                            .icon_size(IconSize::Small)
+                            .icon_position(IconPosition::Start)
+                            .layer(ElevationIndex::ModalSurface)
+                            .tooltip(Tooltip::text("Remove the API key from your keychain"))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.reset_api_key(window, cx)),
+                            ),
+                    )
+                })
+                .into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::OpenAiCompatibleSettingsContent;
+    use http_client::{FakeHttpClient, Response};
+
+    #[gpui::test]
+    fn test_is_authenticated(cx: &mut App) {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        AllLanguageModelSettings::register(cx);
+
+        let set_available_models = |available_models: Vec<AvailableModel>, cx: &mut App| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<AllLanguageModelSettings>(cx, |settings| {
+                    settings.openai_compatible = Some(BTreeMap::from_iter([(
+                        "local".into(),
+                        OpenAiCompatibleSettingsContent {
+                            api_url: "http://localhost:8000/v1".into(),
+                            auth_header: None,
+                            capabilities: None,
+                            available_models: Some(available_models),
+                        },
+                    )]));
+                });
+            });
+        };
+        set_available_models(Vec::new(), cx);
+
+        // Listing the models fails, as the API responds with 404s.
+        let provider = OpenAiCompatibleLanguageModelProvider::new(
+            "local".into(),
+            FakeHttpClient::with_404_response(),
+            cx,
+        );
+        assert!(!provider.is_authenticated(cx));
+
+        provider.state.update(cx, |state, _| {
+            state.listed_models = Some(Vec::new());
+        });
+        assert!(!provider.is_authenticated(cx));
+
+        provider.state.update(cx, |state, _| {
+            state.api_key = Some("secret".into());
+        });
+        assert!(provider.is_authenticated(cx));
+
+        provider.state.update(cx, |state, _| {
+            state.api_key = None;
+            state.listed_models = None;
+        });
+        set_available_models(
+            vec![AvailableModel {
+                name: "qwen-coder".into(),
+                display_name: None,
+                max_tokens: 131072,
+                max_output_tokens: None,
+                capabilities: None,
+                pricing: None,
+            }],
+            cx,
+        );
+        assert!(provider.is_authenticated(cx));
+        assert_eq!(provider.provided_models(cx).len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_list_models() {
+        let http_client = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.uri().path(), "/v1/models");
+            let authorized = request
+                .headers()
+                .get("x-api-key")
+                .is_some_and(|value| value == "secret");
+            if !authorized {
+                return Ok(Response::builder().status(401).body("".into()).unwrap());
+            }
+
+            Ok(Response::builder()
+                .status(200)
+                .body(
+                    serde_json::json!({
+                        "object": "list",
+                        "data": [
+                            { "id": "qwen-coder", "object": "model", "max_model_len": 131072 },
+                            { "id": "llama", "object": "model" },
+                        ]
+                    })
+                    .to_string()
+                    .into(),
+                )
+                .unwrap())
+        });
+        let settings = OpenAiCompatibleSettings {
+            api_url: "http://localhost:8000/v1".into(),
+            auth_header: Some("x-api-key".into()),
+            ..Default::default()
+        };
+
+        assert!(
+            list_models(http_client.as_ref(), &settings, None)
+                .await
+                .is_err()
+        );
+
+        let models = list_models(http_client.as_ref(), &settings, Some("secret"))
+            .await
+            .unwrap();
+        assert_eq!(
+            models
+                .iter()
+                .map(|model| (model.name.as_str(), model.max_tokens))
+                .collect::<Vec<_>>(),
+            [("qwen-coder", 131072), ("llama", DEFAULT_MAX_TOKENS)]
+        );
+    }
+
+    #[test]
+    fn test_auth_header() {
+        let mut settings = OpenAiCompatibleSettings::default();
+        assert_eq!(auth_header(&settings, None), None);
+        assert_eq!(
+            auth_header(&settings, Some("secret")),
+            Some(("Authorization".into(), "Bearer secret".into()))
+        );
+
+        settings.auth_header = Some("api-key".into());
+        assert_eq!(
+            auth_header(&settings, Some("secret")),
+            Some(("api-key".into(), "secret".into()))
+        );
+    }
+}
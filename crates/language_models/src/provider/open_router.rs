@@ -9,10 +9,11 @@ use gpui::{
 use http_client::HttpClient;
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
-    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolSchemaFormat,
-    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, TokenUsage,
+    LanguageModelId, LanguageModelName, LanguageModelPricing, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent,
+    LanguageModelToolSchemaFormat, LanguageModelToolUse, MessageContent, RateLimiter, Role,
+    StopReason, TokenUsage,
 };
 use open_router::{
     Model, ModelMode as OpenRouterModelMode, ResponseStreamEvent, list_models, stream_completion,
@@ -278,6 +279,7 @@ impl LanguageModelProvider for OpenRouterLanguageModelProvider {
                 supports_tools: model.supports_tools,
                 supports_images: model.supports_images,
                 mode: model.mode.clone().unwrap_or_default().into(),
+                pricing: None,
             });
         }
 
@@ -286,7 +288,12 @@ impl LanguageModelProvider for OpenRouterLanguageModelProvider {
                 .iter()
                 .position(|m| m.name == settings_model.name)
             {
-                models_from_api[pos] = settings_model.clone();
+                // Keep the prices OpenRouter lists for the model, as settings can't provide them.
+                let pricing = models_from_api[pos].pricing.take();
+                models_from_api[pos] = open_router::Model {
+                    pricing,
+                    ..settings_model.clone()
+                };
             } else {
                 models_from_api.push(settings_model.clone());
             }
@@ -407,6 +414,13 @@ impl LanguageModel for OpenRouterLanguageModel {
         self.model.supports_images.unwrap_or(false)
     }
 
+    fn pricing(&self) -> Option<LanguageModelPricing> {
+        self.model
+            .pricing
+            .as_ref()
+            .and_then(into_language_model_pricing)
+    }
+
     fn count_tokens(
         &self,
         request: LanguageModelRequest,
@@ -439,6 +453,29 @@ impl LanguageModel for OpenRouterLanguageModel {
     }
 }
 
+/// Converts OpenRouter's per-token prices into per-million-token prices.
+fn into_language_model_pricing(
+    pricing: &open_router::ModelPricing,
+) -> Option<LanguageModelPricing> {
+    fn per_million_tokens(price: &str) -> Option<f64> {
+        let price = price.trim().parse::<f64>().ok()?;
+        (price >= 0.0).then_some(price * 1_000_000.0)
+    }
+
+    Some(LanguageModelPricing {
+        input: per_million_tokens(&pricing.prompt)?,
+        output: per_million_tokens(&pricing.completion)?,
+        cache_creation_input: pricing
+            .input_cache_write
+            .as_deref()
+            .and_then(per_million_tokens),
+        cache_read_input: pricing
+            .input_cache_read
+            .as_deref()
+            .and_then(per_million_tokens),
+    })
+}
+
 pub fn into_open_router(
     request: LanguageModelRequest,
     model: &Model,
@@ -449,7 +486,10 @@ pub fn into_open_router(
         for content in message.content {
             match content {
                 MessageContent::Text(text) => add_message_content_part(
-                    open_router::MessagePart::Text { text },
+                    open_router::MessagePart::Text {
+                        text,
+                        cache_control: None,
+                    },
                     message.role,
                     &mut messages,
                 ),
@@ -492,6 +532,7 @@ pub fn into_open_router(
                         LanguageModelToolResultContent::Text(text) => {
                             vec![open_router::MessagePart::Text {
                                 text: text.to_string(),
+                                cache_control: None,
                             }]
                         }
                         LanguageModelToolResultContent::Image(image) => {
@@ -508,6 +549,24 @@ pub fn into_open_router(
                 }
             }
         }
+
+        if message.cache {
+            let content = match messages.last_mut() {
+                Some(
+                    open_router::RequestMessage::User { content }
+                    | open_router::RequestMessage::System { content }
+                    | open_router::RequestMessage::Tool { content, .. }
+                    | open_router::RequestMessage::Assistant {
+                        content: Some(content),
+                        ..
+                    },
+                ) => Some(content),
+                _ => None,
+            };
+            if let Some(content) = content {
+                content.set_cache_control();
+            }
+        }
     }
 
     open_router::Request {
@@ -659,11 +718,15 @@ impl OpenRouterEventMapper {
         }
 
         if let Some(usage) = event.usage {
+            // The prompt tokens include the ones that were read from the cache.
+            let cached_tokens = usage
+                .prompt_tokens_details
+                .map_or(0, |details| details.cached_tokens);
             events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
-                input_tokens: usage.prompt_tokens,
+                input_tokens: usage.prompt_tokens.saturating_sub(cached_tokens),
                 output_tokens: usage.completion_tokens,
                 cache_creation_input_tokens: 0,
-                cache_read_input_tokens: 0,
+                cache_read_input_tokens: cached_tokens,
             })));
         }
 
@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use anyhow::Result;
+use collections::BTreeMap;
 use gpui::App;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -15,6 +18,7 @@ use crate::provider::{
     mistral::MistralSettings,
     ollama::OllamaSettings,
     open_ai::OpenAiSettings,
+    open_ai_compatible::{self, OpenAiCompatibleSettings},
     open_router::OpenRouterSettings,
     vercel::VercelSettings,
 };
@@ -30,6 +34,7 @@ pub struct AllLanguageModelSettings {
     pub bedrock: AmazonBedrockSettings,
     pub ollama: OllamaSettings,
     pub openai: OpenAiSettings,
+    pub openai_compatible: BTreeMap<Arc<str>, OpenAiCompatibleSettings>,
     pub open_router: OpenRouterSettings,
     pub zed_dot_dev: ZedDotDevSettings,
     pub google: GoogleSettings,
@@ -47,6 +52,7 @@ pub struct AllLanguageModelSettingsContent {
     pub ollama: Option<OllamaSettingsContent>,
     pub lmstudio: Option<LmStudioSettingsContent>,
     pub openai: Option<OpenAiSettingsContent>,
+    pub openai_compatible: Option<BTreeMap<Arc<str>, OpenAiCompatibleSettingsContent>>,
     pub open_router: Option<OpenRouterSettingsContent>,
     #[serde(rename = "zed.dev")]
     pub zed_dot_dev: Option<ZedDotDevSettingsContent>,
@@ -102,6 +108,17 @@ pub struct OpenAiSettingsContent {
     pub available_models: Option<Vec<provider::open_ai::AvailableModel>>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct OpenAiCompatibleSettingsContent {
+    /// The base URL of the API, such as `http://localhost:8000/v1`.
+    pub api_url: String,
+    /// The header to send the API key in, instead of `Authorization: Bearer <key>`.
+    pub auth_header: Option<String>,
+    /// The capabilities of the models listed by the API.
+    pub capabilities: Option<open_ai_compatible::ModelCapabilities>,
+    pub available_models: Option<Vec<open_ai_compatible::AvailableModel>>,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct VercelSettingsContent {
     pub api_url: Option<String>,
@@ -219,6 +236,19 @@ impl settings::Settings for AllLanguageModelSettings {
                 openai.as_ref().and_then(|s| s.available_models.clone()),
             );
 
+            // OpenAI-compatible
+            for (name, provider) in value.openai_compatible.iter().flatten() {
+                settings.openai_compatible.insert(
+                    name.clone(),
+                    OpenAiCompatibleSettings {
+                        api_url: provider.api_url.clone(),
+                        auth_header: provider.auth_header.clone(),
+                        capabilities: provider.capabilities.unwrap_or_default(),
+                        available_models: provider.available_models.clone().unwrap_or_default(),
+                    },
+                );
+            }
+
             // Vercel
             let vercel = value.vercel.clone();
             merge(
@@ -367,6 +367,15 @@ pub struct Usage {
     pub prompt_tokens: u64,
     pub completion_tokens: u64,
     pub total_tokens: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PromptTokensDetails {
+    /// The number of prompt tokens that were read from the prompt cache.
+    #[serde(default)]
+    pub cached_tokens: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -395,13 +404,33 @@ pub async fn stream_completion(
     api_url: &str,
     api_key: &str,
     request: Request,
+) -> Result<BoxStream<'static, Result<ResponseStreamEvent>>> {
+    let authorization = format!("Bearer {}", api_key);
+    stream_completion_with_auth(
+        client,
+        api_url,
+        Some(("Authorization", &authorization)),
+        request,
+    )
+    .await
+}
+
+/// Like [`stream_completion`], but authenticates with the given header name and value,
+/// if any, for OpenAI-compatible APIs that don't use bearer tokens.
+pub async fn stream_completion_with_auth(
+    client: &dyn HttpClient,
+    api_url: &str,
+    auth_header: Option<(&str, &str)>,
+    request: Request,
 ) -> Result<BoxStream<'static, Result<ResponseStreamEvent>>> {
     let uri = format!("{api_url}/chat/completions");
-    let request_builder = HttpRequest::builder()
+    let mut request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_key));
+        .header("Content-Type", "application/json");
+    if let Some((name, value)) = auth_header {
+        request_builder = request_builder.header(name, value);
+    }
 
     let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
     let mut response = client.send(request).await?;
@@ -458,6 +487,52 @@ pub async fn stream_completion(
     }
 }
 
+#[derive(Deserialize, Debug)]
+pub struct ListModelsResponse {
+    pub data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ModelEntry {
+    pub id: String,
+    /// The model's context length, which vLLM and some other OpenAI-compatible
+    /// servers report alongside the model.
+    #[serde(default, alias = "context_length")]
+    pub max_model_len: Option<u64>,
+}
+
+/// Lists the models available at the given API URL, authenticating with the given
+/// header name and value, if any.
+pub async fn list_models(
+    client: &dyn HttpClient,
+    api_url: &str,
+    auth_header: Option<(&str, &str)>,
+) -> Result<Vec<ModelEntry>> {
+    let uri = format!("{api_url}/models");
+    let mut request_builder = HttpRequest::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Accept", "application/json");
+    if let Some((name, value)) = auth_header {
+        request_builder = request_builder.header(name, value);
+    }
+
+    let request = request_builder.body(AsyncBody::default())?;
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to list models: {} {}",
+        response.status(),
+        body,
+    );
+    let response: ListModelsResponse =
+        serde_json::from_str(&body).context("failed to parse models response")?;
+    Ok(response.data)
+}
+
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum OpenAiEmbeddingModel {
     #[serde(rename = "text-embedding-3-small")]
@@ -55,6 +55,20 @@ pub struct Model {
     pub supports_images: Option<bool>,
     #[serde(default)]
     pub mode: ModelMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// The price of a model, in US dollars per token, as returned by the OpenRouter API.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModelPricing {
+    pub prompt: String,
+    pub completion: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_cache_read: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_cache_write: Option<String>,
 }
 
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
@@ -98,6 +112,7 @@ impl Model {
             supports_tools,
             supports_images,
             mode: mode.unwrap_or(ModelMode::Default),
+            pricing: None,
         }
     }
 
@@ -229,18 +244,47 @@ impl MessageContent {
             Self::Plain(text) => {
                 let text_part = MessagePart::Text {
                     text: std::mem::take(text),
+                    cache_control: None,
                 };
                 *self = Self::Multipart(vec![text_part, part]);
             }
             Self::Multipart(parts) => parts.push(part),
         }
     }
+
+    /// Marks the last text part of the content as a cache breakpoint, for the models that
+    /// support prompt caching.
+    pub fn set_cache_control(&mut self) {
+        if let Self::Plain(text) = self {
+            if text.is_empty() {
+                return;
+            }
+            *self = Self::Multipart(vec![MessagePart::Text {
+                text: std::mem::take(text),
+                cache_control: None,
+            }]);
+        }
+
+        if let Self::Multipart(parts) = self {
+            if let Some(MessagePart::Text { cache_control, .. }) = parts
+                .iter_mut()
+                .rev()
+                .find(|part| matches!(part, MessagePart::Text { .. }))
+            {
+                *cache_control = Some(CacheControl::Ephemeral);
+            }
+        }
+    }
 }
 
 impl From<Vec<MessagePart>> for MessageContent {
     fn from(parts: Vec<MessagePart>) -> Self {
         if parts.len() == 1 {
-            if let MessagePart::Text { text } = &parts[0] {
+            if let MessagePart::Text {
+                text,
+                cache_control: None,
+            } = &parts[0]
+            {
                 return Self::Plain(text.clone());
             }
         }
@@ -265,7 +309,7 @@ impl MessageContent {
         match self {
             Self::Plain(text) => Some(text),
             Self::Multipart(parts) if parts.len() == 1 => {
-                if let MessagePart::Text { text } = &parts[0] {
+                if let MessagePart::Text { text, .. } = &parts[0] {
                     Some(text)
                 } else {
                     None
@@ -281,7 +325,7 @@ impl MessageContent {
             Self::Multipart(parts) => parts
                 .iter()
                 .filter_map(|part| {
-                    if let MessagePart::Text { text } = part {
+                    if let MessagePart::Text { text, .. } = part {
                         Some(text.as_str())
                     } else {
                         None
@@ -298,11 +342,19 @@ impl MessageContent {
 pub enum MessagePart {
     Text {
         text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     #[serde(rename = "image_url")]
-    Image {
-        image_url: String,
-    },
+    Image { image_url: String },
+}
+
+/// Marks where a prompt can be cached, which OpenRouter passes through to the model
+/// providers that support prompt caching, such as Anthropic and Google.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CacheControl {
+    Ephemeral,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -351,6 +403,15 @@ pub struct Usage {
     pub prompt_tokens: u64,
     pub completion_tokens: u64,
     pub total_tokens: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PromptTokensDetails {
+    /// The number of prompt tokens that were read from the prompt cache.
+    #[serde(default)]
+    pub cached_tokens: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -404,6 +465,8 @@ pub struct ModelEntry {
     pub supported_parameters: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub architecture: Option<ModelArchitecture>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
@@ -631,6 +694,7 @@ pub async fn list_models(client: &dyn HttpClient, api_url: &str) -> Result<Vec<M
                 } else {
                     ModelMode::Default
                 },
+                pricing: entry.pricing,
             })
             .collect();
 
@@ -233,7 +233,7 @@ impl DapLocator for GoLocator {
 mod tests {
     use super::*;
     use gpui::TestAppContext;
-    use task::{HideStrategy, RevealStrategy, RevealTarget, Shell, TaskTemplate};
+    use task::{DependsOrder, HideStrategy, RevealStrategy, RevealTarget, Shell, TaskTemplate};
 
     #[gpui::test]
     async fn test_create_scenario_for_go_build(_: &mut TestAppContext) {
@@ -253,6 +253,11 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            problem_matchers: Vec::new(),
+            depends_on: Vec::new(),
+            depends_order: DependsOrder::default(),
+            ready_pattern: None,
+            watch: Vec::new(),
         };
 
         let scenario = locator
@@ -280,6 +285,11 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            problem_matchers: Vec::new(),
+            depends_on: Vec::new(),
+            depends_order: DependsOrder::default(),
+            ready_pattern: None,
+            watch: Vec::new(),
         };
 
         let scenario = locator
@@ -418,6 +428,11 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            problem_matchers: Vec::new(),
+            depends_on: Vec::new(),
+            depends_order: DependsOrder::default(),
+            ready_pattern: None,
+            watch: Vec::new(),
         };
 
         let scenario = locator
@@ -2425,4 +2425,45 @@ impl Session {
     pub fn thread_state(&self, thread_id: ThreadId) -> Option<ThreadStatus> {
         self.thread_states.thread_state(thread_id)
     }
+
+    /// Requests the threads from the debug adapter, bypassing the cache behind [`Self::threads`],
+    /// so that callers can wait for the response instead of waiting for [`SessionEvent::Threads`].
+    pub fn fetch_threads(&self) -> Task<Result<Vec<dap::Thread>>> {
+        self.mode.request_dap(ThreadsCommand)
+    }
+
+    /// Requests the stack frames of a stopped thread from the debug adapter, bypassing the cache
+    /// behind [`Self::stack_frames`].
+    pub fn fetch_stack_frames(
+        &self,
+        thread_id: ThreadId,
+        levels: Option<u64>,
+    ) -> Task<Result<Vec<dap::StackFrame>>> {
+        self.mode.request_dap(StackTraceCommand {
+            thread_id: thread_id.0,
+            start_frame: None,
+            levels,
+        })
+    }
+
+    /// Requests the scopes of a stack frame from the debug adapter, bypassing the cache behind
+    /// [`Self::scopes`].
+    pub fn fetch_scopes(&self, stack_frame_id: StackFrameId) -> Task<Result<Vec<dap::Scope>>> {
+        self.mode.request_dap(ScopesCommand { stack_frame_id })
+    }
+
+    /// Requests the variables of a scope or a structured variable from the debug adapter,
+    /// bypassing the cache behind [`Self::variables`].
+    pub fn fetch_variables(
+        &self,
+        variables_reference: VariableReference,
+    ) -> Task<Result<Vec<dap::Variable>>> {
+        self.mode.request_dap(VariablesCommand {
+            variables_reference,
+            filter: None,
+            start: None,
+            count: None,
+            format: None,
+        })
+    }
 }
@@ -24,8 +24,8 @@ use git::{
     parse_git_remote_url,
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus,
+        GitRepository, GitRepositoryCheckpoint, LogQuery, PushOptions, Remote, RemoteCommandOutput,
+        RepoPath, ResetMode, UpstreamTrackingStatus,
     },
     status::{
         FileStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
@@ -423,6 +423,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
+        client.add_entity_request_handler(Self::handle_log);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
@@ -972,6 +973,7 @@ impl GitStore {
             return cx.spawn(async move |cx| {
                 let provider_registry = cx.update(GitHostingProviderRegistry::default_global)?;
                 get_permalink_in_rust_registry_src(provider_registry, file_path, selection)
+                    .await
                     .context("no permalink available")
             });
 
@@ -1002,20 +1004,23 @@ impl GitStore {
 
                         let (provider, remote) =
                             parse_git_remote_url(provider_registry, &origin_url)
+                                .await
                                 .context("parsing Git remote URL")?;
 
                         let path = repo_path.to_str().with_context(|| {
                             format!("converting repo path {repo_path:?} to string")
                         })?;
 
-                        Ok(provider.build_permalink(
-                            remote,
-                            BuildPermalinkParams {
-                                sha: &sha,
-                                path,
-                                selection: Some(selection),
-                            },
-                        ))
+                        Ok(provider
+                            .build_permalink(
+                                remote,
+                                BuildPermalinkParams {
+                                    sha: &sha,
+                                    path,
+                                    selection: Some(selection),
+                                },
+                            )
+                            .await)
                     }
                     RepositoryState::Remote { project_id, client } => {
                         let response = client
@@ -1875,6 +1880,30 @@ impl GitStore {
         })
     }
 
+    async fn handle_log(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let query = LogQuery {
+            path: envelope
+                .payload
+                .path
+                .map(|path| RepoPath(Arc::<Path>::from_proto(path))),
+            skip: envelope.payload.skip as usize,
+            limit: envelope.payload.limit as usize,
+        };
+        let commits = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.log(query))?
+            .await??;
+        Ok(proto::GitLogResponse {
+            commits: commits.iter().map(commit_details_to_proto).collect(),
+        })
+    }
+
     async fn handle_reset(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitReset>,
@@ -3301,6 +3330,31 @@ impl Repository {
         })
     }
 
+    pub fn log(&mut self, query: LogQuery) -> oneshot::Receiver<Result<Vec<CommitDetails>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.log(query).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: query.path.map(|path| path.as_ref().to_proto()),
+                            skip: query.skip as u64,
+                            limit: query.limit as u64,
+                        })
+                        .await?;
+                    Ok(response
+                        .commits
+                        .iter()
+                        .map(proto_to_commit_details)
+                        .collect())
+                }
+            }
+        })
+    }
+
     fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
         Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
     }
@@ -4331,7 +4385,7 @@ impl Repository {
     }
 }
 
-fn get_permalink_in_rust_registry_src(
+async fn get_permalink_in_rust_registry_src(
     provider_registry: Arc<GitHostingProviderRegistry>,
     path: PathBuf,
     selection: Range<u32>,
@@ -4367,16 +4421,19 @@ fn get_permalink_in_rust_registry_src(
     let cargo_toml = std::fs::read_to_string(dir.join("Cargo.toml"))?;
     let manifest = toml::from_str::<CargoToml>(&cargo_toml)?;
     let (provider, remote) = parse_git_remote_url(provider_registry, &manifest.package.repository)
+        .await
         .context("parsing package.repository field of manifest")?;
     let path = PathBuf::from(cargo_vcs_info.path_in_vcs).join(path.strip_prefix(dir).unwrap());
-    let permalink = provider.build_permalink(
-        remote,
-        BuildPermalinkParams {
-            sha: &cargo_vcs_info.git.sha1,
-            path: &path.to_string_lossy(),
-            selection: Some(selection),
-        },
-    );
+    let permalink = provider
+        .build_permalink(
+            remote,
+            BuildPermalinkParams {
+                sha: &cargo_vcs_info.git.sha1,
+                path: &path.to_string_lossy(),
+                selection: Some(selection),
+            },
+        )
+        .await;
     Ok(permalink)
 }
 
@@ -1,4 +1,6 @@
 pub mod clangd_ext;
+pub mod extension_code_actions;
+pub mod extension_formatter;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
 
@@ -91,6 +93,7 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
+use task::{Problem, ProblemSeverity, TaskId};
 use text::{Anchor, BufferId, LineEnding, OffsetRangeExt};
 use url::Url;
 use util::{
@@ -171,6 +174,9 @@ pub struct LocalLspStore {
     lsp_tree: Entity<LanguageServerTree>,
     registered_buffers: HashMap<BufferId, usize>,
     buffer_pull_diagnostics_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, Option<String>>>,
+    /// Diagnostics produced by tasks' problem matchers are stored under a language server id
+    /// reserved for each task, along with the paths they were published for.
+    task_diagnostics: HashMap<TaskId, (LanguageServerId, HashSet<PathBuf>)>,
 }
 
 impl LocalLspStore {
@@ -1479,6 +1485,30 @@ impl LocalLspStore {
                         },
                     )?;
                 }
+                Formatter::Extension { name } => {
+                    let logger = zlog::scoped!(logger => "extension");
+                    zlog::trace!(logger => "formatting");
+                    let _timer = zlog::time!(logger => "Formatting buffer via extension");
+
+                    let diff = extension_formatter::format_via_extension(buffer, name, cx)
+                        .await
+                        .with_context(|| {
+                            format!("Failed to format buffer via extension formatter: {}", name)
+                        })?;
+                    let Some(diff) = diff else {
+                        zlog::trace!(logger => "No changes");
+                        continue;
+                    };
+
+                    extend_formatting_transaction(
+                        buffer,
+                        formatting_transaction_id,
+                        cx,
+                        |buffer, cx| {
+                            buffer.apply_diff(diff, cx);
+                        },
+                    )?;
+                }
                 Formatter::LanguageServer { name } => {
                     let logger = zlog::scoped!(logger => "language-server");
                     zlog::trace!(logger => "formatting");
@@ -3621,6 +3651,7 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_multi_lsp_query);
         client.add_entity_request_handler(Self::handle_restart_language_servers);
         client.add_entity_request_handler(Self::handle_stop_language_servers);
+        client.add_entity_request_handler(Self::handle_update_task_diagnostics);
         client.add_entity_request_handler(Self::handle_cancel_language_server_work);
         client.add_entity_message_handler(Self::handle_start_language_server);
         client.add_entity_message_handler(Self::handle_update_language_server);
@@ -3782,6 +3813,7 @@ impl LspStore {
                 lsp_tree: LanguageServerTree::new(manifest_tree, languages.clone(), cx),
                 registered_buffers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                task_diagnostics: HashMap::default(),
             }),
             last_formatting_failure: None,
             downstream_client: None,
@@ -7277,6 +7309,93 @@ impl LspStore {
         Ok(())
     }
 
+    /// Replaces the diagnostics produced by the problem matchers of the given task.
+    /// Passing no problems clears them, which is done whenever the task is rerun.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_id: TaskId,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = client.request(proto::UpdateTaskDiagnostics {
+                project_id,
+                task_id: task_id.0,
+                problems: problems.into_iter().map(serialize_task_problem).collect(),
+            });
+            cx.background_spawn(request).detach_and_log_err(cx);
+            return Ok(());
+        }
+
+        let local = self
+            .as_local_mut()
+            .context("cannot update task diagnostics on a remote LspStore")?;
+        let (server_id, old_paths) = match local.task_diagnostics.get_mut(&task_id) {
+            Some((server_id, paths)) => (*server_id, mem::take(paths)),
+            // Tasks that never reported any problems have nothing to clear.
+            None if problems.is_empty() => return Ok(()),
+            None => (
+                local.languages.next_language_server_id(),
+                HashSet::default(),
+            ),
+        };
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let severity = match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            };
+            let start = Unclipped(PointUtf16::new(problem.row, problem.column));
+            let end = Unclipped(PointUtf16::new(problem.end_row, problem.end_column));
+            diagnostics_by_path
+                .entry(problem.path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: start..end,
+                    diagnostic: Diagnostic {
+                        source: problem.source,
+                        source_kind: DiagnosticSourceKind::Other,
+                        code: problem.code.map(lsp::NumberOrString::String),
+                        code_description: None,
+                        severity,
+                        message: problem.message,
+                        markdown: None,
+                        group_id: post_inc(&mut local.next_diagnostic_group_id),
+                        is_primary: true,
+                        is_disk_based: true,
+                        is_unnecessary: false,
+                        underline: true,
+                        data: None,
+                    },
+                });
+        }
+
+        let new_paths = diagnostics_by_path.keys().cloned().collect::<HashSet<_>>();
+        for path in old_paths {
+            diagnostics_by_path.entry(path).or_default();
+        }
+        local
+            .task_diagnostics
+            .insert(task_id, (server_id, new_paths));
+
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            self.merge_diagnostic_entries(
+                server_id,
+                abs_path,
+                None,
+                None,
+                diagnostics,
+                |_, _, _| false,
+                cx,
+            )
+            .log_err();
+        }
+        Ok(())
+    }
+
     fn update_worktree_diagnostics(
         &mut self,
         worktree_id: WorktreeId,
@@ -9110,6 +9229,23 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_update_task_diagnostics(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UpdateTaskDiagnostics>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |lsp_store, cx| {
+            let problems = envelope
+                .payload
+                .problems
+                .into_iter()
+                .map(deserialize_task_problem)
+                .collect();
+            lsp_store.update_task_diagnostics(TaskId(envelope.payload.task_id), problems, cx)
+        })??;
+        Ok(proto::Ack {})
+    }
+
     pub async fn handle_stop_language_servers(
         lsp_store: Entity<Self>,
         envelope: TypedEnvelope<proto::StopLanguageServers>,
@@ -10942,6 +11078,46 @@ fn lsp_workspace_diagnostics_refresh(
     Some((tx, workspace_query_language_server))
 }
 
+fn serialize_task_problem(problem: Problem) -> proto::TaskProblem {
+    let severity = match problem.severity {
+        ProblemSeverity::Error => proto::task_problem::Severity::Error,
+        ProblemSeverity::Warning => proto::task_problem::Severity::Warning,
+        ProblemSeverity::Info => proto::task_problem::Severity::Info,
+        ProblemSeverity::Hint => proto::task_problem::Severity::Hint,
+    };
+    proto::TaskProblem {
+        path: problem.path.to_string_lossy().into_owned(),
+        row: problem.row,
+        column: problem.column,
+        end_row: problem.end_row,
+        end_column: problem.end_column,
+        severity: severity as i32,
+        code: problem.code,
+        message: problem.message,
+        source: problem.source,
+    }
+}
+
+fn deserialize_task_problem(problem: proto::TaskProblem) -> Problem {
+    let severity = match problem.severity() {
+        proto::task_problem::Severity::Error => ProblemSeverity::Error,
+        proto::task_problem::Severity::Warning => ProblemSeverity::Warning,
+        proto::task_problem::Severity::Info => ProblemSeverity::Info,
+        proto::task_problem::Severity::Hint => ProblemSeverity::Hint,
+    };
+    Problem {
+        path: PathBuf::from(problem.path),
+        row: problem.row,
+        column: problem.column,
+        end_row: problem.end_row,
+        end_column: problem.end_column,
+        severity,
+        code: problem.code,
+        message: problem.message,
+        source: problem.source,
+    }
+}
+
 fn resolve_word_completion(snapshot: &BufferSnapshot, completion: &mut Completion) {
     let CompletionSource::BufferWord {
         word_range,
@@ -0,0 +1,413 @@
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use collections::BTreeMap;
+use extension::{
+    CodeActionRequest, Extension, ExtensionCodeActionProviderProxy, ExtensionHostProxy,
+};
+use futures::future::join_all;
+use gpui::{App, AppContext as _, Entity, Global, ReadGlobal as _, Task};
+use language::{Anchor, Buffer, Diff, LanguageName, LanguageServerId};
+use lsp::CodeActionKind;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use text::OffsetRangeExt as _;
+use util::ResultExt as _;
+
+use super::extension_formatter::sort_and_validate_edits;
+use crate::{CodeAction, LspAction, ProjectTransaction};
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_code_action_provider_proxy(ExtensionCodeActionRegistryProxy {
+        code_action_registry: ExtensionCodeActionRegistry::default_global(cx),
+    });
+}
+
+#[derive(Default)]
+struct GlobalExtensionCodeActionRegistry(Arc<ExtensionCodeActionRegistry>);
+
+impl Global for GlobalExtensionCodeActionRegistry {}
+
+struct CodeActionProvider {
+    extension: Arc<dyn Extension>,
+    languages: Vec<LanguageName>,
+}
+
+/// The code action providers offered by extensions, keyed by their IDs.
+#[derive(Default)]
+pub struct ExtensionCodeActionRegistry {
+    providers: RwLock<BTreeMap<Arc<str>, CodeActionProvider>>,
+}
+
+impl ExtensionCodeActionRegistry {
+    /// Returns the global [`ExtensionCodeActionRegistry`].
+    pub fn global(cx: &App) -> Arc<Self> {
+        GlobalExtensionCodeActionRegistry::global(cx).0.clone()
+    }
+
+    /// Returns the global [`ExtensionCodeActionRegistry`].
+    ///
+    /// Inserts a default [`ExtensionCodeActionRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut App) -> Arc<Self> {
+        cx.default_global::<GlobalExtensionCodeActionRegistry>()
+            .0
+            .clone()
+    }
+
+    pub fn register(
+        &self,
+        provider_id: Arc<str>,
+        extension: Arc<dyn Extension>,
+        languages: Vec<LanguageName>,
+    ) {
+        self.providers.write().insert(
+            provider_id,
+            CodeActionProvider {
+                extension,
+                languages,
+            },
+        );
+    }
+
+    pub fn unregister(&self, provider_id: &str) {
+        self.providers.write().remove(provider_id);
+    }
+
+    /// Returns the providers that offer code actions for buffers of the given language.
+    pub fn providers_for_language(
+        &self,
+        language: Option<&LanguageName>,
+    ) -> Vec<(Arc<str>, Arc<dyn Extension>)> {
+        self.providers
+            .read()
+            .iter()
+            .filter(|(_, provider)| {
+                provider.languages.is_empty()
+                    || language.is_some_and(|language| provider.languages.contains(language))
+            })
+            .map(|(provider_id, provider)| (provider_id.clone(), provider.extension.clone()))
+            .collect()
+    }
+}
+
+struct ExtensionCodeActionRegistryProxy {
+    code_action_registry: Arc<ExtensionCodeActionRegistry>,
+}
+
+impl ExtensionCodeActionProviderProxy for ExtensionCodeActionRegistryProxy {
+    fn register_code_action_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        languages: Vec<LanguageName>,
+    ) {
+        self.code_action_registry
+            .register(provider_id, extension, languages);
+    }
+
+    fn unregister_code_action_provider(&self, provider_id: Arc<str>) {
+        self.code_action_registry.unregister(&provider_id);
+    }
+}
+
+/// The data attached to a code action provided by an extension.
+///
+/// Extensions return their edits along with the code action, so the edits are carried in the
+/// action's `data` and applied directly, without a language server to resolve them.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtensionCodeActionData {
+    extension_code_action_provider: Arc<str>,
+    /// The version of the buffer the edits were computed against.
+    version: Vec<clock::Lamport>,
+    edits: Vec<(Range<usize>, String)>,
+}
+
+/// Returns the code actions that extensions offer for the given range of the buffer.
+///
+/// Providers that fail are logged and skipped, so that they don't hide the code actions of other
+/// providers or language servers.
+pub(crate) fn code_actions(
+    buffer: &Entity<Buffer>,
+    range: Range<Anchor>,
+    kinds: Option<Vec<CodeActionKind>>,
+    abs_path: Option<PathBuf>,
+    cx: &mut App,
+) -> Task<Vec<CodeAction>> {
+    let Some(registry) = cx.try_global::<GlobalExtensionCodeActionRegistry>() else {
+        return Task::ready(Vec::new());
+    };
+    let buffer = buffer.read(cx);
+    let language_name = buffer.language().map(|language| language.name());
+    let providers = registry.0.providers_for_language(language_name.as_ref());
+    if providers.is_empty() {
+        return Task::ready(Vec::new());
+    }
+
+    let snapshot = buffer.text_snapshot();
+    let request = CodeActionRequest {
+        text: snapshot.text(),
+        range: range.to_offset(&snapshot),
+        language_name: language_name.map(|name| name.to_string()),
+        path: abs_path.map(|path| path.to_string_lossy().into_owned()),
+    };
+
+    cx.background_spawn(async move {
+        let responses = join_all(providers.into_iter().map(|(provider_id, extension)| {
+            let request = request.clone();
+            async move {
+                let actions = extension.code_actions(provider_id.clone(), request).await;
+                (provider_id, actions)
+            }
+        }))
+        .await;
+
+        let mut code_actions = Vec::new();
+        for (provider_id, actions) in responses {
+            let actions = match actions {
+                Ok(actions) => actions,
+                Err(error) => {
+                    log::error!(
+                        "failed to fetch code actions from extension provider {provider_id:?}: {error:#}"
+                    );
+                    continue;
+                }
+            };
+
+            for mut action in actions {
+                let kind = action.kind.map(CodeActionKind::from);
+                if let Some((requested_kinds, kind)) = kinds.as_ref().zip(kind.as_ref()) {
+                    if !requested_kinds.contains(kind) {
+                        continue;
+                    }
+                }
+
+                if let Err(range) = sort_and_validate_edits(&mut action.edits, &snapshot) {
+                    log::error!(
+                        "code action {:?} from extension provider {provider_id:?} has an invalid edit range: {range:?}",
+                        action.title
+                    );
+                    continue;
+                }
+
+                let Some(data) = serde_json::to_value(ExtensionCodeActionData {
+                    extension_code_action_provider: provider_id.clone(),
+                    version: snapshot.version().iter().collect(),
+                    edits: action
+                        .edits
+                        .into_iter()
+                        .map(|edit| (edit.range, edit.new_text))
+                        .collect(),
+                })
+                .log_err() else {
+                    continue;
+                };
+
+                code_actions.push(CodeAction {
+                    server_id: LanguageServerId(0),
+                    range: range.clone(),
+                    lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                        title: action.title,
+                        kind,
+                        data: Some(data),
+                        ..Default::default()
+                    })),
+                    resolved: true,
+                });
+            }
+        }
+        code_actions
+    })
+}
+
+/// Applies the edits of a code action provided by an extension.
+///
+/// Returns `None` if the code action wasn't provided by an extension. Edits that conflict with
+/// changes made to the buffer since the code action was requested are skipped.
+pub(crate) fn apply_code_action(
+    buffer: Entity<Buffer>,
+    action: &CodeAction,
+    push_to_history: bool,
+    cx: &mut App,
+) -> Option<ProjectTransaction> {
+    let LspAction::Action(lsp_action) = &action.lsp_action else {
+        return None;
+    };
+    let data = serde_json::from_value::<ExtensionCodeActionData>(lsp_action.data.clone()?).ok()?;
+
+    let transaction = buffer.update(cx, |buffer, cx| {
+        buffer.finalize_last_transaction();
+        let diff = Diff {
+            base_version: data.version.into_iter().collect(),
+            line_ending: buffer.line_ending(),
+            edits: data
+                .edits
+                .into_iter()
+                .map(|(range, new_text)| (range, new_text.into()))
+                .collect(),
+        };
+        let transaction_id = buffer.apply_diff(diff, cx)?;
+        let transaction = buffer.finalize_last_transaction()?.clone();
+        if !push_to_history {
+            buffer.forget_transaction(transaction_id);
+        }
+        Some(transaction)
+    });
+
+    let mut project_transaction = ProjectTransaction::default();
+    if let Some(transaction) = transaction {
+        project_transaction.0.insert(buffer, transaction);
+    }
+    Some(project_transaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use extension::{ExtensionCodeAction, FakeExtension, FormatEdit};
+    use gpui::{AppContext as _, TestAppContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    fn init_test(languages: Vec<LanguageName>, cx: &mut TestAppContext) -> Arc<FakeExtension> {
+        let extension = Arc::new(FakeExtension::new("acme"));
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            ExtensionCodeActionRegistry::default_global(cx).register(
+                "acme-actions".into(),
+                extension.clone(),
+                languages,
+            );
+        });
+        extension
+    }
+
+    fn code_actions_for(
+        buffer: &Entity<Buffer>,
+        range: Range<usize>,
+        cx: &mut TestAppContext,
+    ) -> Task<Vec<CodeAction>> {
+        cx.update(|cx| {
+            let range = {
+                let buffer = buffer.read(cx);
+                buffer.anchor_before(range.start)..buffer.anchor_after(range.end)
+            };
+            code_actions(
+                buffer,
+                range,
+                None,
+                Some(PathBuf::from("/project/main.rs")),
+                cx,
+            )
+        })
+    }
+
+    fn wrap_in_call_action() -> ExtensionCodeAction {
+        ExtensionCodeAction {
+            title: "Wrap in call".into(),
+            kind: Some("refactor.rewrite".into()),
+            edits: vec![
+                FormatEdit {
+                    range: 5..5,
+                    new_text: ")".into(),
+                },
+                FormatEdit {
+                    range: 4..4,
+                    new_text: "call(".into(),
+                },
+            ],
+        }
+    }
+
+    #[gpui::test]
+    async fn test_extension_code_actions(cx: &mut TestAppContext) {
+        let extension = init_test(Vec::new(), cx);
+        let requests = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        extension.on_code_actions({
+            let requests = requests.clone();
+            move |provider_id, request| {
+                assert_eq!(provider_id.as_ref(), "acme-actions");
+                requests.lock().push(request);
+                Ok(vec![wrap_in_call_action()])
+            }
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("let a;", cx));
+        let actions = code_actions_for(&buffer, 4..5, cx).await;
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].lsp_action.title(), "Wrap in call");
+        assert!(actions[0].resolved);
+
+        let request = requests.lock().pop().unwrap();
+        assert_eq!(request.text, "let a;");
+        assert_eq!(request.range, 4..5);
+        assert_eq!(request.language_name, None);
+        assert_eq!(request.path.as_deref(), Some("/project/main.rs"));
+
+        // Edits made since the code action was requested are preserved.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// x\n")], None, cx));
+        let project_transaction = cx
+            .update(|cx| apply_code_action(buffer.clone(), &actions[0], true, cx))
+            .unwrap();
+        assert_eq!(project_transaction.0.len(), 1);
+        buffer.update(cx, |buffer, cx| {
+            assert_eq!(buffer.text(), "// x\nlet call(a);");
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "// x\nlet a;");
+        });
+    }
+
+    #[gpui::test]
+    async fn test_extension_code_actions_skip_failing_providers(cx: &mut TestAppContext) {
+        let extension = init_test(Vec::new(), cx);
+        let buffer = cx.new(|cx| Buffer::local("héllo", cx));
+
+        extension.on_code_actions(|_, _| anyhow::bail!("provider crashed"));
+        assert!(code_actions_for(&buffer, 0..1, cx).await.is_empty());
+
+        // Within a multi-byte character.
+        extension.on_code_actions(|_, _| {
+            Ok(vec![ExtensionCodeAction {
+                title: "Invalid".into(),
+                kind: None,
+                edits: vec![FormatEdit {
+                    range: 2..2,
+                    new_text: "x".into(),
+                }],
+            }])
+        });
+        assert!(code_actions_for(&buffer, 0..1, cx).await.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_extension_code_actions_for_other_languages(cx: &mut TestAppContext) {
+        let extension = init_test(vec!["Python".into()], cx);
+        extension.on_code_actions(|_, _| Ok(vec![wrap_in_call_action()]));
+
+        let buffer = cx.new(|cx| Buffer::local("let a;", cx));
+        assert!(code_actions_for(&buffer, 4..5, cx).await.is_empty());
+    }
+
+    #[gpui::test]
+    fn test_apply_language_server_code_action(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("let a;", cx));
+        let action = CodeAction {
+            server_id: LanguageServerId(1),
+            range: Anchor::MIN..Anchor::MAX,
+            lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                title: "Server action".into(),
+                data: Some(serde_json::json!({ "id": 1 })),
+                ..Default::default()
+            })),
+            resolved: false,
+        };
+        assert!(
+            cx.update(|cx| apply_code_action(buffer, &action, true, cx))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use extension::{
+    Extension, ExtensionFormatterProxy, ExtensionHostProxy, FormatEdit, FormatRequest, FormatResult,
+};
+use gpui::{App, AsyncApp, Global, ReadGlobal as _};
+use language::{Bias, Diff, TextBufferSnapshot, language_settings::language_settings};
+use parking_lot::RwLock;
+use text::OffsetRangeExt as _;
+
+use super::FormattableBuffer;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_formatter_proxy(ExtensionFormatterRegistryProxy {
+        formatter_registry: ExtensionFormatterRegistry::default_global(cx),
+    });
+}
+
+#[derive(Default)]
+struct GlobalExtensionFormatterRegistry(Arc<ExtensionFormatterRegistry>);
+
+impl Global for GlobalExtensionFormatterRegistry {}
+
+/// The formatters provided by extensions, keyed by their IDs.
+#[derive(Default)]
+pub struct ExtensionFormatterRegistry {
+    formatters: RwLock<HashMap<Arc<str>, Arc<dyn Extension>>>,
+}
+
+impl ExtensionFormatterRegistry {
+    /// Returns the global [`ExtensionFormatterRegistry`].
+    pub fn global(cx: &App) -> Arc<Self> {
+        GlobalExtensionFormatterRegistry::global(cx).0.clone()
+    }
+
+    /// Returns the global [`ExtensionFormatterRegistry`].
+    ///
+    /// Inserts a default [`ExtensionFormatterRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut App) -> Arc<Self> {
+        cx.default_global::<GlobalExtensionFormatterRegistry>()
+            .0
+            .clone()
+    }
+
+    pub fn register(&self, formatter_id: Arc<str>, extension: Arc<dyn Extension>) {
+        self.formatters.write().insert(formatter_id, extension);
+    }
+
+    pub fn unregister(&self, formatter_id: &str) {
+        self.formatters.write().remove(formatter_id);
+    }
+
+    pub fn formatter(&self, formatter_id: &str) -> Option<Arc<dyn Extension>> {
+        self.formatters.read().get(formatter_id).cloned()
+    }
+}
+
+struct ExtensionFormatterRegistryProxy {
+    formatter_registry: Arc<ExtensionFormatterRegistry>,
+}
+
+impl ExtensionFormatterProxy for ExtensionFormatterRegistryProxy {
+    fn register_formatter(&self, extension: Arc<dyn Extension>, formatter_id: Arc<str>) {
+        self.formatter_registry.register(formatter_id, extension);
+    }
+
+    fn unregister_formatter(&self, formatter_id: Arc<str>) {
+        self.formatter_registry.unregister(&formatter_id);
+    }
+}
+
+pub(super) async fn format_via_extension(
+    buffer: &FormattableBuffer,
+    formatter_id: &Arc<str>,
+    cx: &mut AsyncApp,
+) -> Result<Option<Diff>> {
+    let extension = cx
+        .update(|cx| {
+            cx.try_global::<GlobalExtensionFormatterRegistry>()
+                .and_then(|registry| registry.0.formatter(formatter_id))
+        })?
+        .with_context(|| format!("no extension provides a formatter named {formatter_id:?}"))?;
+
+    let (snapshot, request) = buffer.handle.read_with(cx, |buffer_handle, cx| {
+        let snapshot = buffer_handle.text_snapshot();
+        let language = buffer_handle.language();
+        let settings = language_settings(
+            language.map(|language| language.name()),
+            buffer_handle.file(),
+            cx,
+        );
+        let request = FormatRequest {
+            text: snapshot.text(),
+            ranges: buffer
+                .ranges
+                .iter()
+                .flatten()
+                .map(|range| range.to_offset(&snapshot))
+                .collect(),
+            language_name: language.map(|language| language.name().to_string()),
+            path: buffer
+                .abs_path
+                .as_ref()
+                .map(|path| path.to_string_lossy().into_owned()),
+            tab_size: settings.tab_size.get(),
+            hard_tabs: settings.hard_tabs,
+        };
+        (snapshot, request)
+    })?;
+
+    match extension
+        .format_buffer(formatter_id.clone(), request)
+        .await?
+    {
+        FormatResult::Unchanged => Ok(None),
+        FormatResult::Text(text) => Ok(Some(
+            buffer
+                .handle
+                .update(cx, |buffer, cx| buffer.diff(text, cx))?
+                .await,
+        )),
+        FormatResult::Edits(mut edits) => {
+            if let Err(range) = sort_and_validate_edits(&mut edits, &snapshot) {
+                anyhow::bail!("formatter returned an invalid edit range: {range:?}");
+            }
+
+            Ok(Some(Diff {
+                base_version: snapshot.version().clone(),
+                line_ending: snapshot.line_ending(),
+                edits: edits
+                    .into_iter()
+                    .map(|edit| (edit.range, edit.new_text.into()))
+                    .collect(),
+            }))
+        }
+    }
+}
+
+/// Sorts edits returned by an extension and checks that they are valid for the given snapshot.
+///
+/// Returns the first range that is out of bounds, not on a character boundary, or overlaps the
+/// previous edit.
+pub(super) fn sort_and_validate_edits(
+    edits: &mut [FormatEdit],
+    snapshot: &TextBufferSnapshot,
+) -> Result<(), Range<usize>> {
+    edits.sort_by_key(|edit| edit.range.start);
+    let mut previous_end = 0;
+    for edit in edits.iter() {
+        let range = &edit.range;
+        if range.start < previous_end
+            || range.start > range.end
+            || range.end > snapshot.len()
+            || snapshot.clip_offset(range.start, Bias::Left) != range.start
+            || snapshot.clip_offset(range.end, Bias::Left) != range.end
+        {
+            return Err(range.clone());
+        }
+        previous_end = range.end;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use extension::FakeExtension;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::Buffer;
+    use settings::SettingsStore;
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<FakeExtension> {
+        let extension = Arc::new(FakeExtension::new("acme"));
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            ExtensionFormatterRegistry::default_global(cx)
+                .register("acme-fmt".into(), extension.clone());
+        });
+        extension
+    }
+
+    fn formattable_buffer(
+        text: &str,
+        ranges: Option<Vec<Range<usize>>>,
+        cx: &mut TestAppContext,
+    ) -> FormattableBuffer {
+        let handle = cx.new(|cx| Buffer::local(text, cx));
+        let ranges = ranges.map(|ranges| {
+            handle.read_with(cx, |buffer, _| {
+                ranges
+                    .into_iter()
+                    .map(|range| buffer.anchor_before(range.start)..buffer.anchor_after(range.end))
+                    .collect()
+            })
+        });
+        FormattableBuffer {
+            handle,
+            abs_path: Some(PathBuf::from("/project/main.rs")),
+            env: None,
+            ranges,
+        }
+    }
+
+    async fn format(buffer: &FormattableBuffer, cx: &mut TestAppContext) -> Result<String> {
+        let diff = format_via_extension(buffer, &"acme-fmt".into(), &mut cx.to_async()).await?;
+        Ok(buffer.handle.update(cx, |buffer, cx| {
+            if let Some(diff) = diff {
+                buffer.apply_diff(diff, cx);
+            }
+            buffer.text()
+        }))
+    }
+
+    #[gpui::test]
+    async fn test_format_via_extension_text(cx: &mut TestAppContext) {
+        let extension = init_test(cx);
+        let requests = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        extension.on_format_buffer({
+            let requests = requests.clone();
+            move |formatter_id, request| {
+                assert_eq!(formatter_id.as_ref(), "acme-fmt");
+                requests.lock().push(request);
+                Ok(FormatResult::Text("fn main() {\n    run();\n}\n".into()))
+            }
+        });
+
+        let buffer = formattable_buffer("fn main(){\nrun();\n}\n", Some(vec![11..17]), cx);
+        assert_eq!(
+            format(&buffer, cx).await.unwrap(),
+            "fn main() {\n    run();\n}\n"
+        );
+
+        let request = requests.lock().pop().unwrap();
+        assert_eq!(request.text, "fn main(){\nrun();\n}\n");
+        assert_eq!(request.ranges, [11..17]);
+        assert_eq!(request.language_name, None);
+        assert_eq!(request.path.as_deref(), Some("/project/main.rs"));
+        assert_eq!(request.tab_size, 4);
+        assert!(!request.hard_tabs);
+
+        extension.on_format_buffer(|_, _| Ok(FormatResult::Unchanged));
+        assert_eq!(
+            format(&buffer, cx).await.unwrap(),
+            "fn main() {\n    run();\n}\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_format_via_extension_edits(cx: &mut TestAppContext) {
+        let extension = init_test(cx);
+        // Extensions may return their edits in any order.
+        extension.on_format_buffer(|_, _| {
+            Ok(FormatResult::Edits(vec![
+                FormatEdit {
+                    range: 4..4,
+                    new_text: " ".into(),
+                },
+                FormatEdit {
+                    range: 0..1,
+                    new_text: "call".into(),
+                },
+            ]))
+        });
+
+        let buffer = formattable_buffer("f(a,b)", None, cx);
+        assert_eq!(format(&buffer, cx).await.unwrap(), "call(a, b)");
+    }
+
+    #[gpui::test]
+    async fn test_format_via_extension_invalid_edits(cx: &mut TestAppContext) {
+        let extension = init_test(cx);
+        let buffer = formattable_buffer("héllo", None, cx);
+
+        let invalid_ranges = [
+            // Past the end of the buffer.
+            vec![0..100],
+            // Ends before it starts.
+            vec![Range { start: 3, end: 1 }],
+            // Overlaps another edit.
+            vec![0..3, 2..4],
+            // Within a multi-byte character.
+            vec![2..2],
+        ];
+        for ranges in invalid_ranges {
+            extension.on_format_buffer(move |_, _| {
+                Ok(FormatResult::Edits(
+                    ranges
+                        .iter()
+                        .map(|range| FormatEdit {
+                            range: range.clone(),
+                            new_text: "x".into(),
+                        })
+                        .collect(),
+                ))
+            });
+            let error = format(&buffer, cx).await.unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("formatter returned an invalid edit range"),
+                "unexpected error: {error}"
+            );
+        }
+        buffer
+            .handle
+            .read_with(cx, |buffer, _| assert_eq!(buffer.text(), "héllo"));
+    }
+
+    #[gpui::test]
+    async fn test_format_via_unknown_extension_formatter(cx: &mut TestAppContext) {
+        init_test(cx);
+        let buffer = formattable_buffer("text", None, cx);
+        let error = format_via_extension(&buffer, &"unknown".into(), &mut cx.to_async())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no extension provides a formatter named \"unknown\""
+        );
+    }
+}
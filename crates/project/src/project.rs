@@ -111,6 +111,7 @@ use std::{
     time::Duration,
 };
 
+use task::ResolvedTask;
 use task_store::TaskStore;
 use terminals::Terminals;
 use text::{Anchor, BufferId, Point};
@@ -131,7 +132,8 @@ pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
-    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskContexts, TaskSourceKind,
+    BasicContextProvider, CompoundTaskStatus, ContextProviderWithTasks, Inventory, TaskContexts,
+    TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -321,6 +323,8 @@ pub enum Event {
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
     AgentLocationChanged,
+    /// Files watched by a task changed, so it should be rerun.
+    WatchedTaskChanged(TaskSourceKind, ResolvedTask),
 }
 
 pub struct AgentLocationChanged;
@@ -973,6 +977,8 @@ impl Project {
         DapStore::init(&client, cx);
         BreakpointStore::init(&client);
         context_server_store::init(cx);
+        lsp_store::extension_formatter::init(cx);
+        lsp_store::extension_code_actions::init(cx);
     }
 
     pub fn local(
@@ -1064,6 +1070,8 @@ impl Project {
             });
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
+            cx.subscribe(&task_store, Self::on_task_store_event)
+                .detach();
 
             let lsp_store = cx.new(|cx| {
                 LspStore::new_local(
@@ -1205,6 +1213,8 @@ impl Project {
             });
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
+            cx.subscribe(&task_store, Self::on_task_store_event)
+                .detach();
 
             let environment = cx.new(|_| ProjectEnvironment::new(None));
 
@@ -1306,6 +1316,7 @@ impl Project {
             ssh.subscribe_to_entity(SSH_PROJECT_ID, &this.lsp_store);
             ssh.subscribe_to_entity(SSH_PROJECT_ID, &this.dap_store);
             ssh.subscribe_to_entity(SSH_PROJECT_ID, &this.settings_observer);
+            ssh.subscribe_to_entity(SSH_PROJECT_ID, &this.task_store);
             ssh.subscribe_to_entity(SSH_PROJECT_ID, &this.git_store);
 
             ssh_proto.add_entity_message_handler(Self::handle_create_buffer_for_peer);
@@ -1501,6 +1512,8 @@ impl Project {
             cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
+            cx.subscribe(&task_store, Self::on_task_store_event)
+                .detach();
 
             cx.subscribe(&dap_store, Self::on_dap_store_event).detach();
 
@@ -2839,6 +2852,12 @@ impl Project {
         }
     }
 
+    fn on_task_store_event(&mut self, _: Entity<TaskStore>, event: &Event, cx: &mut Context<Self>) {
+        if let Event::WatchedTaskChanged(..) = event {
+            cx.emit(event.clone());
+        }
+    }
+
     fn on_lsp_store_event(
         &mut self,
         _: Entity<LspStore>,
@@ -3584,8 +3603,23 @@ impl Project {
     ) -> Task<Result<Vec<CodeAction>>> {
         let buffer = buffer_handle.read(cx);
         let range = buffer.anchor_before(range.start)..buffer.anchor_before(range.end);
-        self.lsp_store.update(cx, |lsp_store, cx| {
+        let abs_path = buffer
+            .project_path(cx)
+            .and_then(|project_path| self.absolute_path(&project_path, cx));
+        let extension_code_actions = lsp_store::extension_code_actions::code_actions(
+            buffer_handle,
+            range.clone(),
+            kinds.clone(),
+            abs_path,
+            cx,
+        );
+        let code_actions = self.lsp_store.update(cx, |lsp_store, cx| {
             lsp_store.code_actions(buffer_handle, range, kinds, cx)
+        });
+        cx.background_spawn(async move {
+            let mut code_actions = code_actions.await?;
+            code_actions.extend(extension_code_actions.await);
+            Ok(code_actions)
         })
     }
 
@@ -3624,6 +3658,15 @@ impl Project {
         push_to_history: bool,
         cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
+        if let Some(project_transaction) = lsp_store::extension_code_actions::apply_code_action(
+            buffer_handle.clone(),
+            &action,
+            push_to_history,
+            cx,
+        ) {
+            return Task::ready(Ok(project_transaction));
+        }
+
         self.lsp_store.update(cx, |lsp_store, cx| {
             lsp_store.apply_code_action(buffer_handle, action, push_to_history, cx)
         })
@@ -37,7 +37,7 @@ use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
 use std::{env, mem, num::NonZeroU32, ops::Range, str::FromStr, sync::OnceLock, task::Poll};
-use task::{ResolvedTask, TaskContext};
+use task::{Problem, ProblemSeverity, ResolvedTask, TaskContext, TaskId, TaskTemplate};
 use unindent::Unindent as _;
 use util::{
     TryFutureExt as _, assert_set_eq, maybe, path,
@@ -2503,6 +2503,195 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({ "a.rs": "one two three", "b.rs": "four five" }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store.clone());
+    let problem = |path: &str, severity| Problem {
+        path: PathBuf::from(path),
+        row: 0,
+        column: 4,
+        end_row: 0,
+        end_column: 7,
+        severity,
+        code: None,
+        message: "bad".to_string(),
+        source: Some("rustc".to_string()),
+    };
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                TaskId("build".to_string()),
+                vec![
+                    problem(path!("/dir/a.rs"), ProblemSeverity::Error),
+                    problem(path!("/dir/b.rs"), ProblemSeverity::Warning),
+                ],
+                cx,
+            )
+            .unwrap();
+        lsp_store
+            .update_task_diagnostics(
+                TaskId("lint".to_string()),
+                vec![problem(path!("/dir/b.rs"), ProblemSeverity::Warning)],
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 2,
+            }
+        );
+
+        // Rerunning a task replaces its diagnostics without affecting the ones of other tasks.
+        lsp_store
+            .update_task_diagnostics(
+                TaskId("build".to_string()),
+                vec![problem(path!("/dir/a.rs"), ProblemSeverity::Warning)],
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 0,
+                warning_count: 2,
+            }
+        );
+
+        lsp_store
+            .update_task_diagnostics(TaskId("build".to_string()), Vec::new(), cx)
+            .unwrap();
+        lsp_store
+            .update_task_diagnostics(TaskId("lint".to_string()), Vec::new(), cx)
+            .unwrap();
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary::default()
+        );
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                TaskId("build".to_string()),
+                vec![problem(path!("/dir/a.rs"), ProblemSeverity::Error)],
+                cx,
+            )
+            .unwrap();
+    });
+    buffer.update(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        assert_eq!(
+            snapshot
+                .diagnostics_in_range::<_, Point>(0..snapshot.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message.clone()))
+                .collect::<Vec<_>>(),
+            vec![(Point::new(0, 4)..Point::new(0, 7), "bad".to_string())]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_watched_task_reruns_on_save(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({ "src": { "main.rs": "fn main() {}" }, "README.md": "" }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let task = TaskTemplate {
+        label: "check".to_string(),
+        command: "cargo check".to_string(),
+        watch: vec!["src/**/*.rs".to_string()],
+        ..TaskTemplate::default()
+    }
+    .resolve_task("test", &TaskContext::default())
+    .unwrap();
+    let task_id = task.id.clone();
+
+    let reruns = Arc::new(Mutex::new(Vec::new()));
+    let _subscription = cx.update(|cx| {
+        let reruns = reruns.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let Event::WatchedTaskChanged(_, task) = event {
+                reruns.lock().push(task.id.clone());
+            }
+        })
+    });
+    let task_store = project.read_with(cx, |project, _| project.task_store().clone());
+    task_store
+        .update(cx, |task_store, cx| {
+            task_store.watch_task(TaskSourceKind::UserInput, task, cx)
+        })
+        .await
+        .unwrap();
+
+    async fn save(
+        project: &Entity<Project>,
+        path: &str,
+        text: &str,
+        cx: &mut gpui::TestAppContext,
+    ) {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx))
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+    }
+
+    save(&project, path!("/dir/README.md"), "changed", cx).await;
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(reruns.lock().as_slice(), &[] as &[TaskId]);
+
+    // Saves in quick succession rerun the task once.
+    save(&project, path!("/dir/src/main.rs"), "fn main() { 1 }", cx).await;
+    save(&project, path!("/dir/src/main.rs"), "fn main() { 2 }", cx).await;
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(reruns.lock().as_slice(), &[task_id.clone()]);
+
+    // Files written outside of Zed, such as the task's own output, don't rerun it.
+    fs.insert_file(path!("/dir/src/generated.rs"), "pub fn f() {}".into())
+        .await;
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(reruns.lock().as_slice(), &[task_id.clone()]);
+
+    task_store.update(cx, |task_store, _| task_store.unwatch_task(&task_id));
+    save(&project, path!("/dir/src/main.rs"), "fn main() { 3 }", cx).await;
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(reruns.lock().as_slice(), &[task_id]);
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
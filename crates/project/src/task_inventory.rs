@@ -38,6 +38,7 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<DebugScenario>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    compound_task_statuses: HashMap<TaskId, CompoundTaskStatus>,
 }
 
 impl std::fmt::Debug for Inventory {
@@ -47,6 +48,7 @@ impl std::fmt::Debug for Inventory {
             .field("last_scheduled_scenarios", &self.last_scheduled_scenarios)
             .field("templates_from_settings", &self.templates_from_settings)
             .field("scenarios_from_settings", &self.scenarios_from_settings)
+            .field("compound_task_statuses", &self.compound_task_statuses)
             .finish()
     }
 }
@@ -125,6 +127,14 @@ impl<T> Default for InventoryFor<T> {
     }
 }
 
+/// Combined status of a task and the tasks it depends on, from its last run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompoundTaskStatus {
+    Running,
+    Succeeded,
+    Failed(SharedString),
+}
+
 /// Kind of a source the tasks are fetched from, used to display more source information in the UI.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TaskSourceKind {
@@ -242,6 +252,7 @@ impl Inventory {
             last_scheduled_scenarios: VecDeque::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
+            compound_task_statuses: HashMap::default(),
         })
     }
 
@@ -584,6 +595,15 @@ impl Inventory {
         }
     }
 
+    /// Returns the status of the last run of a task with dependencies, if it was run in this session.
+    pub fn compound_task_status(&self, id: &TaskId) -> Option<&CompoundTaskStatus> {
+        self.compound_task_statuses.get(id)
+    }
+
+    pub fn set_compound_task_status(&mut self, id: TaskId, status: CompoundTaskStatus) {
+        self.compound_task_statuses.insert(id, status);
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
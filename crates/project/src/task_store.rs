@@ -1,27 +1,33 @@
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context as _;
 use collections::HashMap;
 use fs::Fs;
-use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Task, WeakEntity};
+use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
 use language::{
-    ContextLocation, ContextProvider as _, LanguageToolchainStore, Location,
+    Buffer, BufferEvent, ContextLocation, ContextProvider as _, LanguageToolchainStore, Location,
     proto::{deserialize_anchor, serialize_anchor},
 };
 use rpc::{AnyProtoClient, TypedEnvelope, proto};
 use settings::{InvalidSettingsError, SettingsLocation};
-use task::{TaskContext, TaskVariables, VariableName};
+use task::{ResolvedTask, TaskContext, TaskId, TaskVariables, VariableName};
 use text::{BufferId, OffsetRangeExt};
-use util::ResultExt;
+use util::{ResultExt, paths::PathMatcher};
+use worktree::WorktreeId;
 
 use crate::{
-    BasicContextProvider, Inventory, ProjectEnvironment, buffer_store::BufferStore,
+    BasicContextProvider, Inventory, ProjectEnvironment, TaskSourceKind,
+    buffer_store::{BufferStore, BufferStoreEvent},
     worktree_store::WorktreeStore,
 };
 
+/// How long to wait for more saves of the files a task watches before rerunning it.
+const WATCHED_TASK_DEBOUNCE: Duration = Duration::from_millis(300);
+
 // platform-dependent warning
 pub enum TaskStore {
     Functional(StoreState),
@@ -34,6 +40,18 @@ pub struct StoreState {
     buffer_store: WeakEntity<BufferStore>,
     worktree_store: Entity<WorktreeStore>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
+    watched_tasks: HashMap<TaskId, WatchedTask>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// A task that gets rerun when the files matching its `watch` globs are saved.
+struct WatchedTask {
+    worktree_id: Option<WorktreeId>,
+    matcher: PathMatcher,
+    /// The task to rerun, `None` if it is watched on behalf of the downstream client, which reruns it.
+    task: Option<(TaskSourceKind, ResolvedTask)>,
+    /// A pending rerun, replaced on every save to debounce them.
+    rerun: Option<Task<()>>,
 }
 
 enum StoreMode {
@@ -59,9 +77,58 @@ impl TaskStore {
     pub fn init(client: Option<&AnyProtoClient>) {
         if let Some(client) = client {
             client.add_entity_request_handler(Self::handle_task_context_for_location);
+            client.add_entity_request_handler(Self::handle_watch_task_paths);
+            client.add_entity_message_handler(Self::handle_unwatch_task_paths);
+            client.add_entity_message_handler(Self::handle_watched_task_paths_changed);
         }
     }
 
+    async fn handle_watch_task_paths(
+        store: Entity<Self>,
+        envelope: TypedEnvelope<proto::WatchTaskPaths>,
+        mut cx: AsyncApp,
+    ) -> anyhow::Result<proto::Ack> {
+        let matcher = PathMatcher::new(&envelope.payload.globs)?;
+        store.update(&mut cx, |store, _| {
+            if let TaskStore::Functional(state) = store {
+                state.watched_tasks.insert(
+                    TaskId(envelope.payload.task_id),
+                    WatchedTask {
+                        worktree_id: envelope.payload.worktree_id.map(WorktreeId::from_proto),
+                        matcher,
+                        task: None,
+                        rerun: None,
+                    },
+                );
+            }
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_unwatch_task_paths(
+        store: Entity<Self>,
+        envelope: TypedEnvelope<proto::UnwatchTaskPaths>,
+        mut cx: AsyncApp,
+    ) -> anyhow::Result<()> {
+        store.update(&mut cx, |store, _| {
+            if let TaskStore::Functional(state) = store {
+                state
+                    .watched_tasks
+                    .remove(&TaskId(envelope.payload.task_id));
+            }
+        })
+    }
+
+    async fn handle_watched_task_paths_changed(
+        store: Entity<Self>,
+        envelope: TypedEnvelope<proto::WatchedTaskPathsChanged>,
+        mut cx: AsyncApp,
+    ) -> anyhow::Result<()> {
+        store.update(&mut cx, |store, cx| {
+            store.watched_task_changed(&TaskId(envelope.payload.task_id), cx)
+        })
+    }
+
     async fn handle_task_context_for_location(
         store: Entity<Self>,
         envelope: TypedEnvelope<proto::TaskContextForLocation>,
@@ -166,6 +233,20 @@ impl TaskStore {
         environment: Entity<ProjectEnvironment>,
         cx: &mut Context<Self>,
     ) -> Self {
+        // Only saves rerun watched tasks, so that the files a task writes itself, such as build
+        // output, don't rerun it.
+        let buffer_store_subscription = buffer_store.upgrade().map(|buffer_store| {
+            cx.subscribe(&buffer_store, |_, _, event, cx| {
+                if let BufferStoreEvent::BufferAdded(buffer) = event {
+                    cx.subscribe(buffer, |store, buffer, event, cx| {
+                        if let BufferEvent::Saved = event {
+                            store.buffer_saved(&buffer, cx);
+                        }
+                    })
+                    .detach();
+                }
+            })
+        });
         Self::Functional(StoreState {
             mode: StoreMode::Local {
                 downstream_client: None,
@@ -175,6 +256,8 @@ impl TaskStore {
             buffer_store,
             toolchain_store,
             worktree_store,
+            watched_tasks: HashMap::default(),
+            _subscriptions: buffer_store_subscription.into_iter().collect(),
         })
     }
 
@@ -196,6 +279,8 @@ impl TaskStore {
             buffer_store,
             toolchain_store,
             worktree_store,
+            watched_tasks: HashMap::default(),
+            _subscriptions: Vec::new(),
         })
     }
 
@@ -239,6 +324,159 @@ impl TaskStore {
         }
     }
 
+    /// Starts rerunning the task whenever the files matching its `watch` globs are saved, until it is unwatched.
+    /// Saves are detected where the worktrees live, i.e. on the remote server for remote projects.
+    pub fn watch_task(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let TaskStore::Functional(state) = self else {
+            return Task::ready(Ok(()));
+        };
+        if state.watched_tasks.contains_key(&task.id) {
+            return Task::ready(Ok(()));
+        }
+        let globs = task.original_task().watch.clone();
+        let matcher = match PathMatcher::new(&globs) {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                return Task::ready(Err(anyhow::anyhow!(
+                    "invalid watch globs of task {:?}: {e}",
+                    task.resolved_label
+                )));
+            }
+        };
+        let worktree_id = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
+        let task_id = task.id.clone();
+        state.watched_tasks.insert(
+            task_id.clone(),
+            WatchedTask {
+                worktree_id,
+                matcher,
+                task: Some((task_source_kind, task)),
+                rerun: None,
+            },
+        );
+        match &state.mode {
+            StoreMode::Local { .. } => Task::ready(Ok(())),
+            StoreMode::Remote {
+                upstream_client,
+                project_id,
+            } => {
+                let request = upstream_client.request(proto::WatchTaskPaths {
+                    project_id: *project_id,
+                    task_id: task_id.0,
+                    worktree_id: worktree_id.map(|id| id.to_proto()),
+                    globs,
+                });
+                cx.background_spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    pub fn unwatch_task(&mut self, task_id: &TaskId) {
+        let TaskStore::Functional(state) = self else {
+            return;
+        };
+        if state.watched_tasks.remove(task_id).is_none() {
+            return;
+        }
+        if let StoreMode::Remote {
+            upstream_client,
+            project_id,
+        } = &state.mode
+        {
+            upstream_client
+                .send(proto::UnwatchTaskPaths {
+                    project_id: *project_id,
+                    task_id: task_id.0.clone(),
+                })
+                .log_err();
+        }
+    }
+
+    pub fn unwatch_all_tasks(&mut self) {
+        let task_ids = match self {
+            TaskStore::Functional(state) => state.watched_tasks.keys().cloned().collect::<Vec<_>>(),
+            TaskStore::Noop => return,
+        };
+        for task_id in task_ids {
+            self.unwatch_task(&task_id);
+        }
+    }
+
+    pub fn is_task_watched(&self, task_id: &TaskId) -> bool {
+        match self {
+            TaskStore::Functional(state) => state.watched_tasks.contains_key(task_id),
+            TaskStore::Noop => false,
+        }
+    }
+
+    fn buffer_saved(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let TaskStore::Functional(state) = self else {
+            return;
+        };
+        let Some(file) = buffer.read(cx).file() else {
+            return;
+        };
+        let worktree_id = file.worktree_id(cx);
+        let path = file.path().clone();
+        for (task_id, watched_task) in &mut state.watched_tasks {
+            if watched_task
+                .worktree_id
+                .is_some_and(|watched_worktree_id| watched_worktree_id != worktree_id)
+            {
+                continue;
+            }
+            if watched_task.matcher.is_match(&path) {
+                let task_id = task_id.clone();
+                watched_task.rerun = Some(cx.spawn(async move |store, cx| {
+                    cx.background_executor().timer(WATCHED_TASK_DEBOUNCE).await;
+                    store
+                        .update(cx, |store, cx| store.watched_task_changed(&task_id, cx))
+                        .ok();
+                }));
+            }
+        }
+    }
+
+    fn watched_task_changed(&mut self, task_id: &TaskId, cx: &mut Context<Self>) {
+        let TaskStore::Functional(state) = self else {
+            return;
+        };
+        let Some(watched_task) = state.watched_tasks.get(task_id) else {
+            return;
+        };
+        match &watched_task.task {
+            Some((task_source_kind, task)) => cx.emit(crate::Event::WatchedTaskChanged(
+                task_source_kind.clone(),
+                task.clone(),
+            )),
+            None => {
+                if let StoreMode::Local {
+                    downstream_client: Some((downstream_client, project_id)),
+                    ..
+                } = &state.mode
+                {
+                    downstream_client
+                        .send(proto::WatchedTaskPathsChanged {
+                            project_id: *project_id,
+                            task_id: task_id.0.clone(),
+                        })
+                        .log_err();
+                }
+            }
+        }
+    }
+
     pub fn shared(&mut self, remote_id: u64, new_downstream_client: AnyProtoClient, _cx: &mut App) {
         if let Self::Functional(StoreState {
             mode: StoreMode::Local {
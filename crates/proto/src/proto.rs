@@ -217,6 +217,8 @@ messages!(
     (RenameChannelResponse, Foreground),
     (RenameProjectEntry, Foreground),
     (RequestContact, Foreground),
+    (RequestExtensionCapabilityConsent, Foreground),
+    (RequestExtensionCapabilityConsentResponse, Foreground),
     (ResolveCompletionDocumentation, Background),
     (ResolveCompletionDocumentationResponse, Background),
     (ResolveInlayHint, Background),
@@ -313,7 +315,13 @@ messages!(
     (LogToDebugConsole, Background),
     (GetDocumentDiagnostics, Background),
     (GetDocumentDiagnosticsResponse, Background),
-    (PullWorkspaceDiagnostics, Background)
+    (PullWorkspaceDiagnostics, Background),
+    (WatchTaskPaths, Background),
+    (UnwatchTaskPaths, Background),
+    (WatchedTaskPathsChanged, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (UpdateTaskDiagnostics, Background)
 );
 
 request_messages!(
@@ -460,6 +468,10 @@ request_messages!(
     (CancelLanguageServerWork, Ack),
     (SyncExtensions, SyncExtensionsResponse),
     (InstallExtension, Ack),
+    (
+        RequestExtensionCapabilityConsent,
+        RequestExtensionCapabilityConsentResponse
+    ),
     (RegisterBufferWithLanguageServers, Ack),
     (GitShow, GitCommitDetails),
     (GitReset, Ack),
@@ -479,7 +491,10 @@ request_messages!(
     (GetDebugAdapterBinary, DebugAdapterBinary),
     (RunDebugLocators, DebugRequest),
     (GetDocumentDiagnostics, GetDocumentDiagnosticsResponse),
-    (PullWorkspaceDiagnostics, Ack)
+    (PullWorkspaceDiagnostics, Ack),
+    (WatchTaskPaths, Ack),
+    (GitLog, GitLogResponse),
+    (UpdateTaskDiagnostics, Ack)
 );
 
 entity_messages!(
@@ -609,7 +624,12 @@ entity_messages!(
     GetDebugAdapterBinary,
     LogToDebugConsole,
     GetDocumentDiagnostics,
-    PullWorkspaceDiagnostics
+    PullWorkspaceDiagnostics,
+    WatchTaskPaths,
+    UnwatchTaskPaths,
+    WatchedTaskPathsChanged,
+    GitLog,
+    UpdateTaskDiagnostics
 );
 
 entity_messages!(
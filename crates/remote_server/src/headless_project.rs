@@ -2,7 +2,7 @@ use ::proto::{FromProto, ToProto};
 use anyhow::{Context as _, Result, anyhow};
 
 use extension::ExtensionHostProxy;
-use extension_host::headless_host::HeadlessExtensionStore;
+use extension_host::headless_host::{HeadlessExtensionStore, RemoteCapabilityConsent};
 use fs::Fs;
 use gpui::{App, AppContext as _, AsyncApp, Context, Entity, PromptLevel};
 use http_client::HttpClient;
@@ -201,13 +201,16 @@ impl HeadlessProject {
             fs.clone(),
             http_client.clone(),
             paths::remote_extensions_dir().to_path_buf(),
-            proxy,
+            proxy.clone(),
             node_runtime,
             cx,
         );
 
         let client: AnyProtoClient = session.clone().into();
 
+        // ssh -> local_machine requests
+        proxy.register_capability_proxy(RemoteCapabilityConsent::new(client.clone()));
+
         // local_machine -> ssh handlers
         session.subscribe_to_entity(SSH_PROJECT_ID, &worktree_store);
         session.subscribe_to_entity(SSH_PROJECT_ID, &buffer_store);
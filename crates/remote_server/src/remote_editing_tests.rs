@@ -8,7 +8,7 @@ use client::{Client, UserStore};
 use clock::FakeSystemClock;
 use language_model::{LanguageModelRequest, fake_provider::FakeLanguageModel};
 
-use extension::ExtensionHostProxy;
+use extension::{ExtensionCapability, ExtensionCapabilityProxy as _, ExtensionHostProxy};
 use fs::{FakeFs, Fs};
 use gpui::{AppContext as _, Entity, SemanticVersion, TestAppContext};
 use http_client::{BlockedHttpClient, FakeHttpClient};
@@ -19,18 +19,21 @@ use language::{
 use lsp::{CompletionContext, CompletionResponse, CompletionTriggerKind, LanguageServerName};
 use node_runtime::NodeRuntime;
 use project::{
-    Project, ProjectPath,
+    Project, ProjectPath, TaskSourceKind,
     search::{SearchQuery, SearchResult},
 };
 use remote::SshRemoteClient;
+use rpc::{TypedEnvelope, proto};
 use serde_json::json;
 use settings::{Settings, SettingsLocation, SettingsStore, initial_server_settings_content};
 use smol::stream::StreamExt;
 use std::{
     collections::HashSet,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
+use task::{Problem, ProblemSeverity, TaskContext, TaskId, TaskTemplate};
 #[cfg(not(windows))]
 use unindent::Unindent as _;
 use util::path;
@@ -1217,6 +1220,214 @@ async fn test_remote_rename_entry(cx: &mut TestAppContext, server_cx: &mut TestA
     });
 }
 
+#[gpui::test]
+async fn test_remote_watched_task(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "src": { "lib.rs": "" },
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+
+    let (project, _) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+    cx.run_until_parked();
+
+    let task = TaskTemplate {
+        label: "test".to_string(),
+        command: "cargo test".to_string(),
+        watch: vec!["src/*.rs".to_string()],
+        ..TaskTemplate::default()
+    }
+    .resolve_task("test", &TaskContext::default())
+    .unwrap();
+    let reruns = Arc::new(Mutex::new(Vec::new()));
+    let _subscription = cx.update(|cx| {
+        let reruns = reruns.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let project::Event::WatchedTaskChanged(_, task) = event {
+                reruns.lock().unwrap().push(task.resolved_label.clone());
+            }
+        })
+    });
+    let task_store = project.read_with(cx, |project, _| project.task_store().clone());
+    task_store
+        .update(cx, |task_store, cx| {
+            task_store.watch_task(TaskSourceKind::UserInput, task, cx)
+        })
+        .await
+        .unwrap();
+
+    // Saves are matched against the globs on the server, which tells the client to rerun the task.
+    for (path, text) in [("src/lib.rs", "pub fn f() {}"), ("README.md", "# changed")] {
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_buffer((worktree_id, Path::new(path)), cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx))
+            .await
+            .unwrap();
+        server_cx.run_until_parked();
+        server_cx.executor().advance_clock(Duration::from_secs(1));
+        server_cx.run_until_parked();
+        cx.run_until_parked();
+        assert_eq!(*reruns.lock().unwrap(), ["test".to_string()]);
+    }
+}
+
+#[gpui::test]
+async fn test_remote_task_diagnostics(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({ "project1": { "src": { "lib.rs": "fn one() {}" } } }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let problem = Problem {
+        path: PathBuf::from(path!("/code/project1/src/lib.rs")),
+        row: 0,
+        column: 3,
+        end_row: 0,
+        end_column: 6,
+        severity: ProblemSeverity::Error,
+        code: None,
+        message: "bad".to_string(),
+        source: Some("rustc".to_string()),
+    };
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    for problems in [vec![problem], Vec::new()] {
+        let error_count = problems.len();
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_task_diagnostics(TaskId("build".to_string()), problems, cx)
+                .unwrap()
+        });
+        cx.run_until_parked();
+        server_cx.run_until_parked();
+        cx.run_until_parked();
+
+        // The problems are published as diagnostics by the server, which shares them with the client.
+        headless.read_with(server_cx, |headless, cx| {
+            assert_eq!(
+                headless
+                    .lsp_store
+                    .read(cx)
+                    .diagnostic_summary(false, cx)
+                    .error_count,
+                error_count
+            );
+        });
+        project.read_with(cx, |project, cx| {
+            assert_eq!(
+                project.diagnostic_summary(false, cx).error_count,
+                error_count
+            );
+        });
+    }
+}
+
+#[gpui::test]
+async fn test_remote_extension_capability_consent(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+
+    // The client only grants network access.
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let ssh = project.read_with(cx, |project, _| project.ssh_client().unwrap());
+    ssh.read_with(cx, |ssh, _| {
+        let requests = requests.clone();
+        ssh.proto_client().add_request_handler(
+            project.downgrade(),
+            move |_, envelope: TypedEnvelope<proto::RequestExtensionCapabilityConsent>, _| {
+                let requests = requests.clone();
+                async move {
+                    let capability: ExtensionCapability =
+                        serde_json::from_str(&envelope.payload.capability_json)?;
+                    let granted = matches!(capability, ExtensionCapability::NetworkFetch { .. });
+                    requests.lock().unwrap().push((
+                        envelope.payload.extension_id,
+                        envelope.payload.extension_name,
+                        capability,
+                    ));
+                    Ok(proto::RequestExtensionCapabilityConsentResponse { granted })
+                }
+            },
+        );
+    });
+
+    // The server can't prompt the user, so it asks the client for consent.
+    let proxy = headless.read_with(server_cx, |headless, cx| {
+        headless.extensions.read(cx).proxy.clone()
+    });
+    let network_fetch = ExtensionCapability::NetworkFetch {
+        host: "example.com".to_string(),
+    };
+    let process_exec = ExtensionCapability::ProcessExec {
+        command: "cargo".to_string(),
+        args: vec!["**".to_string()],
+    };
+    let consents = server_cx.update(|cx| {
+        [network_fetch.clone(), process_exec.clone()].map(|capability| {
+            proxy.request_capability_consent(
+                "test-extension".into(),
+                "Test Extension".into(),
+                capability,
+                cx,
+            )
+        })
+    });
+    server_cx.run_until_parked();
+    cx.run_until_parked();
+    server_cx.run_until_parked();
+
+    let [network_fetch_consent, process_exec_consent] = consents;
+    assert!(network_fetch_consent.await.unwrap());
+    assert!(!process_exec_consent.await.unwrap());
+    assert_eq!(
+        *requests.lock().unwrap(),
+        [
+            (
+                "test-extension".to_string(),
+                "Test Extension".to_string(),
+                network_fetch
+            ),
+            (
+                "test-extension".to_string(),
+                "Test Extension".to_string(),
+                process_exec
+            ),
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_copy_file_into_remote_project(
     cx: &mut TestAppContext,
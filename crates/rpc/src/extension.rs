@@ -16,6 +16,9 @@ pub struct ExtensionApiManifest {
     pub wasm_api_version: Option<String>,
     #[serde(default)]
     pub provides: BTreeSet<ExtensionProvides>,
+    /// The hex-encoded SHA-256 checksum of the extension's `archive.tar.gz`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_sha256: Option<String>,
 }
 
 #[derive(
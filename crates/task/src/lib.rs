@@ -2,6 +2,7 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
 mod task_template;
@@ -21,8 +22,11 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    Problem, ProblemMatcher, ProblemPattern, ProblemSeverity, TaskProblemMatcher,
+};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
     substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use vscode_debug_format::VsCodeDebugTaskFile;
@@ -72,6 +76,10 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Problem matchers to run over the task's output once it finishes, with built-in matchers expanded.
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// A regex that marks a background task as ready for the tasks depending on it, once a line of its output matches it.
+    pub ready_pattern: Option<String>,
 }
 
 impl SpawnInTerminal {
@@ -119,6 +127,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task was resolved with, used to resolve the tasks it depends on.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: SpawnInTerminal,
@@ -139,6 +149,16 @@ impl ResolvedTask {
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
     }
+
+    /// The context the task was resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
+    /// Whether the task only runs the tasks it depends on, without a command of its own.
+    pub fn is_compound(&self) -> bool {
+        self.resolved.command.trim().is_empty()
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
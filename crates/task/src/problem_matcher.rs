@@ -0,0 +1,589 @@
+//! VS Code–style problem matchers, which turn the output of a task into a list of problems
+//! (e.g. compiler errors) that can be shown as diagnostics.
+
+use std::path::{Path, PathBuf};
+
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A problem matcher attached to a task, either referencing a built-in matcher by name or defined in place.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum TaskProblemMatcher {
+    /// The name of a built-in problem matcher: `$rustc`, `$gcc`, `$tsc`, `$tsc-watch`,
+    /// `$eslint-stylish` or `$eslint-compact`.
+    Named(String),
+    /// A problem matcher defined in place, optionally extending a built-in one.
+    Custom(ProblemMatcher),
+}
+
+impl TaskProblemMatcher {
+    /// Returns the matcher to run over the task's output, with built-in matchers expanded.
+    /// Logs and returns `None` if a referenced built-in matcher does not exist.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Named(name) => builtin_problem_matcher(name),
+            Self::Custom(matcher) => {
+                let Some(base) = matcher.base.as_deref() else {
+                    return Some(matcher.clone());
+                };
+                let base = builtin_problem_matcher(base)?;
+                Some(ProblemMatcher {
+                    base: None,
+                    source: matcher.source.clone().or(base.source),
+                    severity: matcher.severity.or(base.severity),
+                    relative_to: matcher.relative_to.clone().or(base.relative_to),
+                    pattern: if matcher.pattern.is_empty() {
+                        base.pattern
+                    } else {
+                        matcher.pattern.clone()
+                    },
+                })
+            }
+        }
+    }
+}
+
+/// Describes how to find problems in the output of a task.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// The name of a built-in problem matcher to extend, e.g. `$tsc`.
+    /// Fields set on this matcher take precedence over the ones of the built-in matcher.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// The name to show as the source of the produced diagnostics.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// The severity of problems whose pattern does not capture one, defaults to `error`.
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+    /// Directory that relative file paths in the output are resolved against,
+    /// defaults to the task's working directory.
+    #[serde(default)]
+    pub relative_to: Option<String>,
+    /// Regular expressions that are matched against consecutive lines of the output.
+    ///
+    /// A problem is reported once every pattern has matched. If the last pattern has `loop` set,
+    /// it is matched repeatedly and reports a problem for every line it matches.
+    #[serde(default)]
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// A regular expression matching a single line of task output, along with the indices of
+/// the capture groups that contain the parts of a problem.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match a line against.
+    pub regexp: String,
+    /// The group that contains the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The group that contains the location, as `line`, `line,column` or `line,column,end_line,end_column`.
+    #[serde(default)]
+    pub location: Option<usize>,
+    /// The group that contains the 1-based line number.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The group that contains the 1-based column number.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The group that contains the 1-based line number the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// The group that contains the 1-based column number the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The group that contains the severity, e.g. `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The group that contains the problem's code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The group that contains the message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether this pattern is matched repeatedly. Only allowed on the last pattern.
+    #[serde(default)]
+    pub r#loop: bool,
+}
+
+/// How severe a matched problem is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_ascii_lowercase();
+        if text.starts_with('e') {
+            Some(Self::Error)
+        } else if text.starts_with('w') {
+            Some(Self::Warning)
+        } else if text.starts_with("info") || text == "note" {
+            Some(Self::Info)
+        } else if text == "hint" || text == "help" {
+            Some(Self::Hint)
+        } else {
+            None
+        }
+    }
+}
+
+/// A problem found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The file the problem is in. Relative paths are resolved against the matcher's `relative_to`
+    /// directory or the task's working directory, if any.
+    pub path: PathBuf,
+    /// The 0-based row the problem starts at.
+    pub row: u32,
+    /// The 0-based column the problem starts at.
+    pub column: u32,
+    /// The 0-based row the problem ends at.
+    pub end_row: u32,
+    /// The 0-based column the problem ends at.
+    pub end_column: u32,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    pub source: Option<String>,
+}
+
+#[derive(Clone, Default)]
+struct MatchedFields {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl MatchedFields {
+    fn extend(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |ix: Option<usize>| {
+            ix.and_then(|ix| captures.get(ix))
+                .map(|group| group.as_str().to_string())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix).and_then(|text| text.trim().parse().ok());
+
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file);
+        }
+        if let Some(location) = group(pattern.location) {
+            let mut numbers = location.split(',').map(|part| part.trim().parse().ok());
+            self.line = numbers.next().flatten();
+            self.column = numbers.next().flatten();
+            self.end_line = numbers.next().flatten();
+            self.end_column = numbers.next().flatten();
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code);
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message);
+        }
+    }
+}
+
+impl ProblemMatcher {
+    /// Finds all problems in the output of a task that ran in `cwd`.
+    pub fn match_output(&self, output: &str, cwd: Option<&Path>) -> Vec<Problem> {
+        let mut patterns = Vec::with_capacity(self.pattern.len());
+        for pattern in &self.pattern {
+            match Regex::new(&pattern.regexp) {
+                Ok(regex) => patterns.push((pattern, regex)),
+                Err(error) => {
+                    log::error!(
+                        "invalid problem matcher pattern {:?}: {error}",
+                        pattern.regexp
+                    );
+                    return Vec::new();
+                }
+            }
+        }
+        if patterns.is_empty() {
+            return Vec::new();
+        }
+
+        let lines = output.lines().collect::<Vec<_>>();
+        let mut problems = Vec::new();
+        let mut ix = 0;
+        while ix < lines.len() {
+            match self.match_lines(&patterns, &lines[ix..], cwd, &mut problems) {
+                Some(matched_lines) => ix += matched_lines.max(1),
+                None => ix += 1,
+            }
+        }
+        problems
+    }
+
+    /// Matches the patterns against the start of `lines`, returning the number of lines consumed.
+    fn match_lines(
+        &self,
+        patterns: &[(&ProblemPattern, Regex)],
+        lines: &[&str],
+        cwd: Option<&Path>,
+        problems: &mut Vec<Problem>,
+    ) -> Option<usize> {
+        let mut fields = MatchedFields::default();
+        for (ix, (pattern, regex)) in patterns.iter().enumerate() {
+            let is_last = ix + 1 == patterns.len();
+            if is_last && pattern.r#loop {
+                let mut line_ix = ix;
+                while let Some(captures) = lines.get(line_ix).and_then(|line| regex.captures(line))
+                {
+                    let mut fields = fields.clone();
+                    fields.extend(pattern, &captures);
+                    problems.extend(self.problem(fields, cwd));
+                    line_ix += 1;
+                }
+                return (line_ix > ix).then_some(line_ix);
+            }
+
+            let captures = regex.captures(lines.get(ix)?)?;
+            fields.extend(pattern, &captures);
+        }
+        problems.extend(self.problem(fields, cwd));
+        Some(patterns.len())
+    }
+
+    fn problem(&self, fields: MatchedFields, cwd: Option<&Path>) -> Option<Problem> {
+        let file = fields.file?;
+        let message = fields.message?;
+        let row = fields.line.unwrap_or(1).saturating_sub(1);
+        let column = fields.column.unwrap_or(1).saturating_sub(1);
+        Some(Problem {
+            path: self.resolve_path(file.trim(), cwd),
+            row,
+            column,
+            end_row: fields.end_line.map_or(row, |line| line.saturating_sub(1)),
+            end_column: fields
+                .end_column
+                .map_or(column, |column| column.saturating_sub(1)),
+            severity: fields
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::parse)
+                .or(self.severity)
+                .unwrap_or_default(),
+            code: fields.code,
+            message: message.trim().to_string(),
+            source: self.source.clone(),
+        })
+    }
+
+    fn resolve_path(&self, path: &str, cwd: Option<&Path>) -> PathBuf {
+        let root = match (self.relative_to.as_deref(), cwd) {
+            (Some(relative_to), Some(cwd)) => Some(cwd.join(relative_to)),
+            (Some(relative_to), None) => Some(PathBuf::from(relative_to)),
+            (None, cwd) => cwd.map(Path::to_path_buf),
+        };
+        match root {
+            Some(root) => root.join(path),
+            None => PathBuf::from(path),
+        }
+    }
+}
+
+fn builtin_problem_matcher(name: &str) -> Option<ProblemMatcher> {
+    let matcher = |source: &str, pattern: Vec<ProblemPattern>| ProblemMatcher {
+        source: Some(source.to_string()),
+        pattern,
+        ..ProblemMatcher::default()
+    };
+    let tsc_pattern = || {
+        ProblemPattern {
+        regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$".to_string(),
+        file: Some(1),
+        line: Some(2),
+        column: Some(3),
+        severity: Some(4),
+        code: Some(5),
+        message: Some(6),
+        ..ProblemPattern::default()
+    }
+    };
+    match name {
+        "$rustc" => Some(matcher(
+            "rustc",
+            vec![
+                ProblemPattern {
+                    regexp: r"^(warning|warn|error)(?:\[(.*?)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^[\s\->=]*(.*?):(\d*):(\d*)\s*$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+        )),
+        "$gcc" => Some(matcher(
+            "gcc",
+            vec![ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+        )),
+        "$tsc" | "$tsc-watch" => Some(matcher("ts", vec![tsc_pattern()])),
+        "$eslint-compact" => Some(matcher(
+            "eslint",
+            vec![ProblemPattern {
+                regexp:
+                    r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                        .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..ProblemPattern::default()
+            }],
+        )),
+        "$eslint-stylish" => Some(matcher(
+            "eslint",
+            vec![
+                ProblemPattern {
+                    regexp: r"^([^\s].*)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(.*))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    r#loop: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+        )),
+        _ => {
+            log::warn!("unknown problem matcher {name:?}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn problem(path: &str, row: u32, column: u32, severity: ProblemSeverity) -> Problem {
+        Problem {
+            path: PathBuf::from(path),
+            row,
+            column,
+            end_row: row,
+            end_column: column,
+            severity,
+            code: None,
+            message: String::new(),
+            source: None,
+        }
+    }
+
+    fn resolve(name: &str) -> ProblemMatcher {
+        TaskProblemMatcher::Named(name.to_string())
+            .resolve()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rustc_matcher() {
+        let output = "\
+   Compiling foo v0.1.0 (/project)
+error[E0425]: cannot find value `y` in this scope
+ --> src/main.rs:3:13
+  |
+3 |     let x = y;
+  |             ^ not found in this scope
+
+warning: unused variable: `x`
+  --> src/lib.rs:10:9
+   |
+error: could not compile `foo` (bin \"foo\") due to 1 previous error
+";
+        let problems = resolve("$rustc").match_output(output, Some(Path::new("/project")));
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    code: Some("E0425".to_string()),
+                    message: "cannot find value `y` in this scope".to_string(),
+                    source: Some("rustc".to_string()),
+                    ..problem("/project/src/main.rs", 2, 12, ProblemSeverity::Error)
+                },
+                Problem {
+                    message: "unused variable: `x`".to_string(),
+                    source: Some("rustc".to_string()),
+                    ..problem("/project/src/lib.rs", 9, 8, ProblemSeverity::Warning)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_matchers() {
+        let problems = resolve("$gcc").match_output(
+            "main.c:4:5: error: expected ';' before 'return'\n/usr/include/foo.h:1:1: warning: bar\n",
+            Some(Path::new("/src")),
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (problem.path.as_path(), problem.row, problem.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new("/src/main.c"), 3, ProblemSeverity::Error),
+                (Path::new("/usr/include/foo.h"), 0, ProblemSeverity::Warning),
+            ]
+        );
+
+        let problems = resolve("$tsc").match_output(
+            "src/index.ts(12,7): error TS2322: Type 'string' is not assignable to type 'number'.\nsrc/util.ts:1:10 - warning TS6133: 'x' is declared but never used.",
+            None,
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.as_path(),
+                    problem.row,
+                    problem.column,
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new("src/index.ts"), 11, 6, Some("TS2322")),
+                (Path::new("src/util.ts"), 0, 9, Some("TS6133")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_looping_pattern() {
+        let output = "\
+/project/src/a.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement     no-console
+
+/project/src/b.js
+  7:2  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        let problems = resolve("$eslint-stylish").match_output(output, None);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_str().unwrap(),
+                    problem.row,
+                    problem.message.as_str(),
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/project/src/a.js",
+                    0,
+                    "'foo' is defined but never used",
+                    Some("no-unused-vars")
+                ),
+                (
+                    "/project/src/a.js",
+                    2,
+                    "Unexpected console statement",
+                    Some("no-console")
+                ),
+                ("/project/src/b.js", 6, "Missing semicolon", Some("semi")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matchers: Vec<TaskProblemMatcher> = serde_json::from_str(
+            r#"[
+                {
+                    "source": "lint",
+                    "severity": "warning",
+                    "pattern": [{ "regexp": "^(\\S+) (\\d+,\\d+): (.*)$", "file": 1, "location": 2, "message": 3 }]
+                },
+                { "base": "$tsc", "relative_to": "web" },
+                "$unknown"
+            ]"#,
+        )
+        .unwrap();
+        let matchers = matchers
+            .iter()
+            .filter_map(TaskProblemMatcher::resolve)
+            .collect::<Vec<_>>();
+        assert_eq!(matchers.len(), 2);
+        assert_eq!(matchers[1].pattern, resolve("$tsc").pattern);
+
+        let output = "a.py 2,5: line too long\nindex.ts(1,1): error TS1005: ';' expected.";
+        let cwd = Some(Path::new("/project"));
+        assert_eq!(
+            matchers[0].match_output(output, cwd),
+            vec![Problem {
+                message: "line too long".to_string(),
+                source: Some("lint".to_string()),
+                ..problem("/project/a.py", 1, 4, ProblemSeverity::Warning)
+            }]
+        );
+        assert_eq!(
+            matchers[1]
+                .match_output(output, cwd)
+                .into_iter()
+                .map(|problem| problem.path)
+                .collect::<Vec<_>>(),
+            vec![PathBuf::from("/project/web/index.ts")]
+        );
+    }
+}
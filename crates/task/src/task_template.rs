@@ -8,8 +8,8 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX,
+    AttachRequest, ProblemMatcher, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext,
+    TaskId, TaskProblemMatcher, VariableName, ZED_VARIABLE_NAME_PREFIX,
     serde_helpers::{non_empty_string_vec, non_empty_string_vec_json_schema},
 };
 
@@ -72,6 +72,45 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Problem matchers that turn the task's output into diagnostics once it finishes.
+    /// Either the name of a built-in matcher (`$rustc`, `$gcc`, `$tsc`, `$tsc-watch`, `$eslint-stylish`, `$eslint-compact`)
+    /// or an object with a list of regular expression `pattern`s, optionally extending a built-in matcher with `base`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub problem_matchers: Vec<TaskProblemMatcher>,
+    /// Labels of the tasks to run before this one. If any of them fails, this task is not run.
+    /// A task with dependencies may omit its `command`, to only run the tasks it depends on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks in `depends_on`:
+    /// * `parallel` — start all of them at once (default)
+    /// * `sequence` — run them one after another, in the order they are listed
+    #[serde(default, skip_serializing_if = "DependsOrder::is_parallel")]
+    pub depends_order: DependsOrder,
+    /// Marks the task as a background task (e.g. a watcher or a dev server) that does not need to exit
+    /// before the tasks depending on it start: they start as soon as a line of the task's output matches this regex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_pattern: Option<String>,
+    /// Globs of worktree-relative paths (e.g. `src/**/*.rs`) to watch once the task has been spawned:
+    /// whenever a matching file is saved, the task is rerun in the same terminal, cancelling the run in progress.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watch: Vec<String>,
+}
+
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all dependencies at once.
+    #[default]
+    Parallel,
+    /// Run the dependencies one after another, in the order they are listed.
+    Sequence,
+}
+
+impl DependsOrder {
+    fn is_parallel(&self) -> bool {
+        *self == Self::Parallel
+    }
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -133,7 +172,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -209,6 +250,27 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let mut problem_matchers = Vec::with_capacity(self.problem_matchers.len());
+        for matcher in self
+            .problem_matchers
+            .iter()
+            .filter_map(TaskProblemMatcher::resolve)
+        {
+            let relative_to = match matcher.relative_to.as_deref() {
+                Some(relative_to) => Some(substitute_all_template_variables_in_str(
+                    relative_to,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )?),
+                None => None,
+            };
+            problem_matchers.push(ProblemMatcher {
+                relative_to,
+                ..matcher
+            });
+        }
+
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
             .log_err()?;
@@ -240,6 +302,7 @@ impl TaskTemplate {
         Some(ResolvedTask {
             id: id.clone(),
             substituted_variables,
+            task_context: cx.clone(),
             original_task: self.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
@@ -267,6 +330,8 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
+                ready_pattern: self.ready_pattern.clone(),
             },
         })
     }
@@ -464,6 +529,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_compound_templates() {
+        let compound_task = TaskTemplate {
+            label: "build all".to_string(),
+            depends_on: vec!["build server".to_string(), "build client".to_string()],
+            depends_order: DependsOrder::Sequence,
+            ..TaskTemplate::default()
+        };
+        let resolved_task = compound_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("should resolve a task without a command if it has dependencies");
+        assert!(resolved_task.is_compound());
+        assert_eq!(resolved_task.original_task().depends_on.len(), 2);
+
+        let parsed: TaskTemplate = serde_json::from_str(
+            r#"{ "label": "watch", "command": "tsc --watch", "ready_pattern": "Watching for file changes" }"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.depends_order, DependsOrder::Parallel);
+        let resolved_task = parsed
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap();
+        assert!(!resolved_task.is_compound());
+        assert_eq!(
+            resolved_task.resolved.ready_pattern.as_deref(),
+            Some("Watching for file changes")
+        );
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
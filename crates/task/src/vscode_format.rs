@@ -3,7 +3,10 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    DependsOrder, EnvVariableReplacer, ProblemMatcher, ProblemPattern, ProblemSeverity,
+    TaskProblemMatcher, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +25,147 @@ struct VsCodeTaskDefinition {
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    problem_matcher: Option<OneOrMany<VsCodeProblemMatcher>>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            Self::One(item) => vec![item],
+            Self::Many(items) => items,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Custom(VsCodeCustomProblemMatcher),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeCustomProblemMatcher {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<ProblemSeverity>,
+    file_location: Option<OneOrMany<String>>,
+    pattern: Option<VsCodeProblemPatterns>,
+    background: Option<VsCodeBackgroundMatcher>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeBackgroundMatcher {
+    ends_pattern: Option<VsCodeBackgroundPattern>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeBackgroundPattern {
+    Regexp(String),
+    Pattern { regexp: String },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Named(String),
+    Patterns(OneOrMany<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    location: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default)]
+    r#loop: bool,
+}
+
+impl VsCodeProblemMatcher {
+    /// The pattern that marks a background task as ready, i.e. done with its initial work.
+    fn ends_pattern(&self) -> Option<String> {
+        let base = match self {
+            Self::Named(name) => Some(name.as_str()),
+            Self::Custom(matcher) => {
+                if let Some(ends_pattern) = matcher
+                    .background
+                    .as_ref()
+                    .and_then(|background| background.ends_pattern.as_ref())
+                {
+                    return Some(match ends_pattern {
+                        VsCodeBackgroundPattern::Regexp(regexp)
+                        | VsCodeBackgroundPattern::Pattern { regexp } => regexp.clone(),
+                    });
+                }
+                matcher.base.as_deref()
+            }
+        };
+        // Code's built-in `$tsc-watch` matcher considers a compilation done once this message is printed.
+        (base == Some("$tsc-watch")).then(|| "Watching for file changes".to_string())
+    }
+
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> Option<TaskProblemMatcher> {
+        let matcher = match self {
+            Self::Named(name) => return Some(TaskProblemMatcher::Named(name)),
+            Self::Custom(matcher) => matcher,
+        };
+        let pattern = match matcher.pattern {
+            Some(VsCodeProblemPatterns::Named(name)) => {
+                log::warn!("Skipping problem matcher with unsupported named pattern {name:?}");
+                return None;
+            }
+            Some(VsCodeProblemPatterns::Patterns(patterns)) => patterns
+                .into_vec()
+                .into_iter()
+                .map(|pattern| ProblemPattern {
+                    regexp: pattern.regexp,
+                    file: pattern.file,
+                    location: pattern.location,
+                    line: pattern.line,
+                    column: pattern.column,
+                    end_line: pattern.end_line,
+                    end_column: pattern.end_column,
+                    severity: pattern.severity,
+                    code: pattern.code,
+                    message: pattern.message,
+                    r#loop: pattern.r#loop,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        // `fileLocation` is either a mode (`absolute`, `relative` or `autoDetect`), or a mode followed by the directory
+        // relative paths are resolved against. Zed always resolves relative paths, so only the directory matters.
+        let relative_to = matcher
+            .file_location
+            .and_then(|location| location.into_vec().into_iter().nth(1))
+            .map(|directory| replacer.replace(&directory));
+        Some(TaskProblemMatcher::Custom(ProblemMatcher {
+            base: matcher.base,
+            source: matcher.source.or(matcher.owner),
+            severity: matcher.severity,
+            relative_to,
+            pattern,
+        }))
+    }
 }
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -46,30 +190,62 @@ impl VsCodeTaskDefinition {
         self,
         replacer: &EnvVariableReplacer,
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!("Skipping deserializing of a task with the unsupported `dependsOn` key");
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            Some(serde_json_lenient::Value::String(label)) => vec![label.clone()],
+            Some(serde_json_lenient::Value::Array(labels)) => labels
+                .iter()
+                .filter_map(|label| Some(label.as_str()?.to_owned()))
+                .collect(),
+            _ => Vec::new(),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        let depends_order = match self
+            .other_attributes
+            .get("dependsOrder")
+            .and_then(|order| order.as_str())
+        {
+            Some("sequence") => DependsOrder::Sequence,
+            _ => DependsOrder::Parallel,
+        };
+        // `type` is not set in tasks that only run the tasks they depend on; we still want to deserialize the whole object though (hence command is an Option),
+        // as that way we can provide more specific description of why deserialization failed.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let problem_matchers = self
+            .problem_matcher
+            .map(OneOrMany::into_vec)
+            .unwrap_or_default();
+        let is_background = self
+            .other_attributes
+            .get("isBackground")
+            .and_then(|is_background| is_background.as_bool())
+            .unwrap_or(false);
+        let ready_pattern = if is_background {
+            problem_matchers
+                .iter()
+                .find_map(VsCodeProblemMatcher::ends_pattern)
+        } else {
+            None
+        };
+        let problem_matchers = problem_matchers
+            .into_iter()
+            .filter_map(|matcher| matcher.into_zed_format(replacer))
+            .collect();
         let mut template = TaskTemplate {
             label: self.label,
             command,
             args,
+            problem_matchers,
+            depends_on,
+            depends_order,
+            ready_pattern,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
@@ -121,7 +297,7 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        ProblemMatcher, TaskProblemMatcher, TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -131,10 +307,12 @@ mod tests {
         assert_eq!(
             VsCodeTaskDefinition {
                 other_attributes: Default::default(),
+                problem_matcher: None,
                 ..lhs
             },
             VsCodeTaskDefinition {
                 other_attributes: Default::default(),
+                problem_matcher: None,
                 ..rhs
             },
         );
@@ -179,6 +357,7 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "tsc: watch ./src".to_string(),
@@ -193,6 +372,7 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "npm: build:compiler".to_string(),
@@ -201,6 +381,7 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "npm: build:tests".to_string(),
@@ -209,6 +390,7 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                problem_matcher: None,
             },
         ];
 
@@ -224,6 +406,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![TaskProblemMatcher::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -235,18 +418,22 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![TaskProblemMatcher::Named("$tsc-watch".to_string())],
+                ready_pattern: Some("Watching for file changes".to_string()),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![TaskProblemMatcher::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![TaskProblemMatcher::Named("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -268,6 +455,7 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -276,6 +464,7 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -285,6 +474,7 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -294,6 +484,7 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -302,18 +493,21 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                problem_matcher: None,
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                problem_matcher: None,
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -322,33 +516,61 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let extension_matcher = |base: &str| {
+            TaskProblemMatcher::Custom(ProblemMatcher {
+                base: Some(base.to_string()),
+                relative_to: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                ..Default::default()
+            })
+        };
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![extension_matcher("$tsc-watch")],
+                ready_pattern: Some("Watching for file changes".to_string()),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![extension_matcher("$tsc")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![TaskProblemMatcher::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![TaskProblemMatcher::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![extension_matcher("$tsc")],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matchers: vec![TaskProblemMatcher::Named("$rustc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matchers: vec![TaskProblemMatcher::Named("$rustc".to_string())],
                 ..Default::default()
             },
         ];
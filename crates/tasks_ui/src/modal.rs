@@ -10,7 +10,7 @@ use gpui::{
 };
 use itertools::Itertools;
 use picker::{Picker, PickerDelegate, highlighted_match_with_paths::HighlightedMatch};
-use project::{TaskSourceKind, task_store::TaskStore};
+use project::{CompoundTaskStatus, TaskSourceKind, task_store::TaskStore};
 use task::{DebugScenario, ResolvedTask, RevealTarget, TaskContext, TaskTemplate};
 use ui::{
     ActiveTheme, Clickable, FluentBuilder as _, IconButtonShape, IconWithIndicator, Indicator,
@@ -473,6 +473,38 @@ impl PickerDelegate for TasksModalDelegate {
                     .as_str(),
             );
         }
+        let compound_task_status = if template.depends_on.is_empty() {
+            None
+        } else {
+            if !tooltip_label_text.trim().is_empty() {
+                tooltip_label_text.push('\n');
+            }
+            tooltip_label_text.push_str(&format!(
+                "Depends on: {}",
+                template.depends_on.iter().join(", ")
+            ));
+            let status = self
+                .task_store
+                .read(cx)
+                .task_inventory()
+                .and_then(|inventory| {
+                    inventory
+                        .read(cx)
+                        .compound_task_status(&resolved_task.id)
+                        .cloned()
+                });
+            match &status {
+                Some(CompoundTaskStatus::Running) => tooltip_label_text.push_str("\nRunning"),
+                Some(CompoundTaskStatus::Succeeded) => {
+                    tooltip_label_text.push_str("\nLast run succeeded")
+                }
+                Some(CompoundTaskStatus::Failed(message)) => {
+                    tooltip_label_text.push_str(&format!("\nLast run failed: {message}"))
+                }
+                None => {}
+            }
+            status
+        };
         let tooltip_label = if tooltip_label_text.trim().is_empty() {
             None
         } else {
@@ -498,7 +530,13 @@ impl PickerDelegate for TasksModalDelegate {
                 .map(Icon::from_path),
         }
         .map(|icon| icon.color(Color::Muted).size(IconSize::Small));
-        let indicator = if matches!(source_kind, TaskSourceKind::Lsp { .. }) {
+        let indicator = if let Some(status) = &compound_task_status {
+            Some(Indicator::dot().color(match status {
+                CompoundTaskStatus::Running => Color::Info,
+                CompoundTaskStatus::Succeeded => Color::Success,
+                CompoundTaskStatus::Failed(_) => Color::Error,
+            }))
+        } else if matches!(source_kind, TaskSourceKind::Lsp { .. }) {
             Some(Indicator::icon(
                 Icon::new(IconName::Bolt).size(IconSize::Small),
             ))
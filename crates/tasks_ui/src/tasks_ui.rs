@@ -2,7 +2,7 @@ use std::{path::Path, sync::Arc};
 
 use collections::HashMap;
 use editor::Editor;
-use gpui::{App, AppContext as _, Context, Entity, Task, Window};
+use gpui::{App, AppContext as _, Context, Entity, Task, Window, actions};
 use project::{Location, TaskContexts, TaskSourceKind, Worktree};
 use task::{RevealTarget, TaskContext, TaskId, TaskTemplate, TaskVariables, VariableName};
 use workspace::Workspace;
@@ -11,11 +11,20 @@ mod modal;
 
 pub use modal::{Rerun, ShowAttachModal, Spawn, TaskOverrides, TasksModal};
 
+actions!(task, [StopWatching]);
+
 pub fn init(cx: &mut App) {
     cx.observe_new(
         |workspace: &mut Workspace, _: Option<&mut Window>, _: &mut Context<Workspace>| {
             workspace
                 .register_action(spawn_task_or_modal)
+                .register_action(|workspace, _: &StopWatching, _, cx| {
+                    workspace.project().update(cx, |project, cx| {
+                        project
+                            .task_store()
+                            .update(cx, |task_store, _| task_store.unwatch_all_tasks())
+                    });
+                })
                 .register_action(move |workspace, action: &modal::Rerun, window, cx| {
                     if let Some((task_source_kind, mut last_scheduled_task)) = workspace
                         .project()
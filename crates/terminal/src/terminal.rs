@@ -1318,6 +1318,28 @@ impl Terminal {
         term.bounds_to_string(start, end)
     }
 
+    /// Returns the index of the line the cursor is on, counted from the top of the scrollback.
+    /// Lines above it have been fully written.
+    pub fn cursor_line(&self) -> usize {
+        let term = self.term.lock_unfair();
+        (term.history_size() as i32 + term.grid().cursor.point.line.0).max(0) as usize
+    }
+
+    /// Returns the content from the given line, counted from the top of the scrollback, through
+    /// the line the cursor is on.
+    pub fn get_content_from_line(&self, line: usize) -> String {
+        let term = self.term.lock_unfair();
+        let start = Line(line as i32 - term.history_size() as i32).max(term.topmost_line());
+        let end = term.grid().cursor.point.line;
+        if start > end {
+            return String::new();
+        }
+        term.bounds_to_string(
+            AlacPoint::new(start, Column(0)),
+            AlacPoint::new(end, term.last_column()),
+        )
+    }
+
     pub fn last_n_non_empty_lines(&self, n: usize) -> Vec<String> {
         let term = self.term.clone();
         let terminal = term.lock_unfair();
@@ -9,7 +9,10 @@ use crate::{
 use breadcrumbs::Breadcrumbs;
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use futures::{channel::oneshot, future::join_all};
+use futures::{
+    channel::oneshot,
+    future::{self, Either, join_all},
+};
 use gpui::{
     Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity, EventEmitter,
     ExternalPaths, FocusHandle, Focusable, IntoElement, ParentElement, Pixels, Render, Styled,
@@ -17,9 +20,10 @@ use gpui::{
 };
 use itertools::Itertools;
 use project::{Fs, Project, ProjectEntryId, terminals::TerminalKind};
+use regex::Regex;
 use search::{BufferSearchBar, buffer_search::DivRegistrar};
 use settings::Settings;
-use task::{RevealStrategy, RevealTarget, ShellBuilder, SpawnInTerminal, TaskId};
+use task::{Problem, RevealStrategy, RevealTarget, ShellBuilder, SpawnInTerminal, TaskId};
 use terminal::{
     Terminal,
     terminal_settings::{TerminalDockPosition, TerminalSettings},
@@ -554,6 +558,27 @@ impl TerminalPanel {
         cx.spawn(async move |_, _| rx.await?)
     }
 
+    /// Publishes the problems found in a task's output as diagnostics, replacing the ones from its previous run.
+    fn update_task_diagnostics(
+        &self,
+        task: &SpawnInTerminal,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) {
+        if task.problem_matchers.is_empty() {
+            return;
+        }
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let lsp_store = workspace.read(cx).project().read(cx).lsp_store();
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_task_diagnostics(task.id.clone(), problems, cx)
+                .log_err();
+        });
+    }
+
     fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
@@ -1467,22 +1492,124 @@ impl workspace::TerminalProvider for TerminalProvider {
         window.spawn(cx, async move |cx| {
             let terminal = terminal_panel
                 .update_in(cx, |terminal_panel, window, cx| {
+                    terminal_panel.update_task_diagnostics(&task, Vec::new(), cx);
                     terminal_panel.spawn_task(&task, window, cx)
                 })
                 .ok()?
                 .await;
             match terminal {
-                Ok(terminal) => {
-                    let exit_status = terminal
-                        .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
-                        .ok()?
-                        .await?;
-                    Some(Ok(exit_status))
-                }
+                Ok(terminal) => wait_for_task_completion(terminal_panel, terminal, task, cx)
+                    .await
+                    .map(Ok),
                 Err(e) => Some(Err(e)),
             }
         })
     }
+
+    fn spawn_until_ready(
+        &self,
+        task: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let terminal_panel = self.0.clone();
+        let ready_pattern = task.ready_pattern.as_deref().map(Regex::new).transpose();
+        window.spawn(cx, async move |cx| {
+            let label = task.label.clone();
+            let ready_pattern = ready_pattern
+                .with_context(|| format!("invalid ready pattern of task {label:?}"))?;
+            let terminal = terminal_panel
+                .update_in(cx, |terminal_panel, window, cx| {
+                    terminal_panel.update_task_diagnostics(&task, Vec::new(), cx);
+                    terminal_panel.spawn_task(&task, window, cx)
+                })?
+                .await?;
+
+            let (ready_tx, ready_rx) = oneshot::channel();
+            let _ready_subscription = match ready_pattern {
+                Some(ready_pattern) => {
+                    let terminal = terminal.upgrade().context("task terminal was closed")?;
+                    let mut ready_tx = Some(ready_tx);
+                    Some(cx.update(|_, cx| {
+                        // Only scan the output written since the spawn, and only the lines that
+                        // haven't been scanned yet, besides the one still being written.
+                        let mut unscanned_line = terminal.read(cx).cursor_line();
+                        cx.subscribe(&terminal, move |terminal, event, cx| {
+                            if !matches!(event, terminal::Event::Wakeup) || ready_tx.is_none() {
+                                return;
+                            }
+                            let terminal = terminal.read(cx);
+                            let content = terminal.get_content_from_line(unscanned_line);
+                            unscanned_line = terminal.cursor_line();
+                            let is_ready = content.lines().any(|line| ready_pattern.is_match(line));
+                            if let Some(ready_tx) = ready_tx.take_if(|_| is_ready) {
+                                ready_tx.send(()).ok();
+                            }
+                        })
+                    })?)
+                }
+                None => {
+                    drop(ready_tx);
+                    None
+                }
+            };
+
+            let completion = cx.spawn(async move |cx| {
+                wait_for_task_completion(terminal_panel, terminal, task, cx).await
+            });
+            let exit_status = match future::select(ready_rx, completion).await {
+                Either::Left((Ok(()), completion)) => {
+                    completion.detach();
+                    return Ok(());
+                }
+                Either::Left((Err(_), completion)) => completion.await,
+                Either::Right((exit_status, _)) => exit_status,
+            };
+            match exit_status {
+                Some(exit_status) if exit_status.success() => Ok(()),
+                Some(exit_status) => Err(anyhow!(
+                    "task {label:?} failed with exit code {:?}",
+                    exit_status.code()
+                )),
+                None => Err(anyhow!("task {label:?} was cancelled")),
+            }
+        })
+    }
+}
+
+/// Waits for the task running in the given terminal to finish, then publishes the problems its
+/// output matched as diagnostics.
+async fn wait_for_task_completion(
+    terminal_panel: WeakEntity<TerminalPanel>,
+    terminal: WeakEntity<Terminal>,
+    task: SpawnInTerminal,
+    cx: &mut AsyncWindowContext,
+) -> Option<ExitStatus> {
+    let exit_status = terminal
+        .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
+        .ok()?
+        .await?;
+    if !task.problem_matchers.is_empty() {
+        let output = terminal
+            .read_with(cx, |terminal, _| terminal.get_content())
+            .ok()?;
+        let matchers = task.problem_matchers.clone();
+        let cwd = task.cwd.clone();
+        let problems = cx
+            .background_spawn(async move {
+                matchers
+                    .iter()
+                    .flat_map(|matcher| matcher.match_output(&output, cwd.as_deref()))
+                    .collect::<Vec<_>>()
+            })
+            .await;
+        terminal_panel
+            .update(cx, |terminal_panel, cx| {
+                terminal_panel.update_task_diagnostics(&task, problems, cx)
+            })
+            .ok();
+    }
+    Some(exit_status)
 }
 
 struct InlineAssistTabBarButton {
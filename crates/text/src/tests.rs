@@ -621,6 +621,193 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_undo_tree() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc");
+
+    buffer.start_transaction_at(now);
+    buffer.edit([(3..3, "d")]);
+    let (transaction_1, _) = buffer.end_transaction_at(now).unwrap();
+    assert_eq!(buffer.text(), "abcd");
+
+    now += Duration::from_secs(60);
+    buffer.start_transaction_at(now);
+    buffer.edit([(4..4, "e")]);
+    let (transaction_2, _) = buffer.end_transaction_at(now).unwrap();
+    assert_eq!(buffer.text(), "abcde");
+
+    // Editing after an undo starts a new branch instead of discarding the redo stack.
+    buffer.undo();
+    now += Duration::from_secs(60);
+    buffer.start_transaction_at(now);
+    buffer.edit([(4..4, "X")]);
+    let (transaction_3, _) = buffer.end_transaction_at(now).unwrap();
+    assert_eq!(buffer.text(), "abcdX");
+    assert!(buffer.redo().is_none());
+
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.len(), 3);
+    assert_eq!(
+        tree.children(Some(transaction_1))
+            .map(|node| node.transaction_id)
+            .collect::<Vec<_>>(),
+        [transaction_2, transaction_3]
+    );
+    assert_eq!(
+        tree.path_to(Some(transaction_3)),
+        [transaction_1, transaction_3]
+    );
+    assert_eq!(buffer.undo_tree_state(), Some(transaction_3));
+
+    // Jump to the abandoned branch.
+    assert!(buffer.go_to_undo_tree_state(Some(transaction_2)).is_some());
+    assert_eq!(buffer.text(), "abcde");
+    assert_eq!(buffer.undo_tree_state(), Some(transaction_2));
+    assert!(buffer.go_to_undo_tree_state(Some(transaction_2)).is_none());
+
+    // Plain undo and redo follow the branch that was jumped to.
+    buffer.undo();
+    assert_eq!(buffer.text(), "abcd");
+    buffer.undo();
+    assert_eq!(buffer.text(), "abc");
+    buffer.redo();
+    buffer.redo();
+    assert_eq!(buffer.text(), "abcde");
+
+    // Moving through states in the order in which they were created.
+    let tree = buffer.undo_tree();
+    let state = buffer.undo_tree_state();
+    assert_eq!(tree.chronological_state(state, -1), Some(transaction_1));
+    assert_eq!(tree.chronological_state(state, -2), None);
+    assert_eq!(tree.chronological_state(state, 1), Some(transaction_3));
+    assert_eq!(tree.chronological_state(None, 10), Some(transaction_3));
+    assert_eq!(
+        tree.state_at(now - Duration::from_secs(30)),
+        Some(transaction_2)
+    );
+    assert_eq!(tree.state_at(now - Duration::from_secs(300)), None);
+
+    // Previewing a state doesn't modify the buffer.
+    assert_eq!(buffer.text_for_undo_tree_state(None).to_string(), "abc");
+    assert_eq!(
+        buffer
+            .text_for_undo_tree_state(Some(transaction_3))
+            .to_string(),
+        "abcdX"
+    );
+    assert_eq!(buffer.text(), "abcde");
+
+    buffer.go_to_undo_tree_state(None);
+    assert_eq!(buffer.text(), "abc");
+    buffer.redo();
+    assert_eq!(buffer.text(), "abcd");
+}
+
+#[test]
+fn test_undo_tree_prunes_abandoned_states() {
+    let now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "");
+
+    buffer.start_transaction_at(now);
+    buffer.edit([(0..0, "a")]);
+    let (first_transaction, _) = buffer.end_transaction_at(now).unwrap();
+
+    // Every edit made after an undo abandons the branch that was undone.
+    for ix in 0..MAX_ABANDONED_TRANSACTIONS + 10 {
+        buffer.start_transaction_at(now);
+        buffer.edit([(1..1, ix.to_string())]);
+        buffer.end_transaction_at(now).unwrap();
+        buffer.undo();
+    }
+    buffer.start_transaction_at(now);
+    buffer.edit([(1..1, "b")]);
+    let (last_transaction, _) = buffer.end_transaction_at(now).unwrap();
+
+    assert_eq!(buffer.history.abandoned.len(), MAX_ABANDONED_TRANSACTIONS);
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.len(), MAX_ABANDONED_TRANSACTIONS + 2);
+    assert!(tree.contains(Some(first_transaction)));
+    assert_eq!(
+        tree.path_to(Some(last_transaction)),
+        [first_transaction, last_transaction]
+    );
+
+    // The oldest branches were forgotten, and the remaining ones can still be jumped to.
+    let oldest_state = tree.chronological_state(Some(first_transaction), 1);
+    assert!(buffer.go_to_undo_tree_state(oldest_state).is_some());
+    assert_eq!(buffer.text(), "a10");
+}
+
+#[test]
+fn test_undo_tree_transactions_between() {
+    let now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "");
+    let transaction = |buffer: &mut Buffer, text: &str| {
+        buffer.start_transaction_at(now);
+        let len = buffer.len();
+        buffer.edit([(len..len, text)]);
+        buffer.end_transaction_at(now).unwrap().0
+    };
+
+    let transaction_1 = transaction(&mut buffer, "a");
+    let transaction_2 = transaction(&mut buffer, "b");
+    let transaction_3 = transaction(&mut buffer, "c");
+    buffer.undo();
+    buffer.undo();
+    let transaction_4 = transaction(&mut buffer, "d");
+    let transaction_5 = transaction(&mut buffer, "e");
+
+    let tree = buffer.undo_tree();
+    assert_eq!(
+        tree.transactions_between(Some(transaction_3), Some(transaction_5)),
+        (
+            vec![transaction_3, transaction_2],
+            vec![transaction_4, transaction_5]
+        )
+    );
+    assert_eq!(
+        tree.transactions_between(Some(transaction_5), None),
+        (vec![transaction_5, transaction_4, transaction_1], vec![])
+    );
+    assert!(tree.is_applied_in(transaction_1, Some(transaction_5)));
+    assert!(!tree.is_applied_in(transaction_2, Some(transaction_5)));
+
+    // Undoing a transaction out of order leaves the states after it applied.
+    buffer.undo_transaction(transaction_4);
+    assert_eq!(buffer.text(), "ae");
+    buffer.go_to_undo_tree_state(Some(transaction_5));
+    assert_eq!(buffer.text(), "ade");
+    buffer.go_to_undo_tree_state(Some(transaction_3));
+    assert_eq!(buffer.text(), "abc");
+}
+
+#[test]
+fn test_undo_tree_convergence() {
+    let mut buffer1 = Buffer::new(1, BufferId::new(1).unwrap(), "abc");
+    let mut buffer2 = Buffer::new(2, BufferId::new(1).unwrap(), "abc");
+    let now = Instant::now();
+
+    let mut ops = Vec::new();
+    buffer1.start_transaction_at(now);
+    ops.push(buffer1.edit([(0..0, "1")]));
+    let (transaction_1, _) = buffer1.end_transaction_at(now).unwrap();
+    ops.push(buffer1.undo().unwrap().1);
+    buffer1.start_transaction_at(now);
+    ops.push(buffer1.edit([(0..0, "2")]));
+    buffer1.end_transaction_at(now).unwrap();
+    ops.push(buffer2.edit([(3..3, "3")]));
+
+    buffer1.apply_op(ops[3].clone());
+    ops.extend(buffer1.go_to_undo_tree_state(Some(transaction_1)));
+    for op in ops[..3].iter().chain(&ops[4..]) {
+        buffer2.apply_op(op.clone());
+    }
+
+    assert_eq!(buffer1.text(), "1abc3");
+    assert_eq!(buffer2.text(), buffer1.text());
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -9,6 +9,7 @@ pub mod subscription;
 #[cfg(test)]
 mod tests;
 mod undo_map;
+mod undo_tree;
 
 pub use anchor::*;
 use anyhow::{Context as _, Result};
@@ -39,6 +40,7 @@ pub use subscription::*;
 pub use sum_tree::Bias;
 use sum_tree::{FilterCursor, SumTree, TreeMap, TreeSet};
 use undo_map::UndoMap;
+pub use undo_tree::{UndoTree, UndoTreeNode};
 
 #[cfg(any(test, feature = "test-support"))]
 use util::RandomCharIter;
@@ -142,11 +144,18 @@ impl HistoryEntry {
     }
 }
 
+/// The number of abandoned undo tree states that are kept before the oldest ones are forgotten.
+const MAX_ABANDONED_TRANSACTIONS: usize = 1000;
+
 struct History {
     base_text: Rope,
     operations: TreeMap<clock::Lamport, Operation>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    /// Entries that used to be on the redo stack before a new transaction was committed on top
+    /// of an earlier state. They're kept so that those states remain reachable via the tree.
+    abandoned: Vec<HistoryEntry>,
+    tree: UndoTree,
     transaction_depth: usize,
     group_interval: Duration,
 }
@@ -191,6 +200,8 @@ impl History {
             operations: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            abandoned: Vec::new(),
+            tree: UndoTree::default(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -244,7 +255,8 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                self.abandon_redo_stack();
+                self.push_tree_node(now);
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -294,10 +306,13 @@ impl History {
                 for edit_id in &entry.transaction.edit_ids {
                     last_entry.transaction.edit_ids.push(*edit_id);
                 }
+                self.tree.remove(entry.transaction.id);
             }
 
             if let Some(entry) = entries_to_merge.last_mut() {
                 last_entry.last_edit_at = entry.last_edit_at;
+                self.tree
+                    .set_edited_at(last_entry.transaction.id, last_entry.last_edit_at);
             }
         }
 
@@ -320,7 +335,42 @@ impl History {
             last_edit_at: now,
             suppress_grouping: false,
         });
-        self.redo_stack.clear();
+        self.abandon_redo_stack();
+        self.push_tree_node(now);
+    }
+
+    /// Records the transaction on top of the undo stack as a new state in the undo tree,
+    /// branching off the state below it.
+    fn push_tree_node(&mut self, now: Instant) {
+        let mut entries = self.undo_stack.iter().rev();
+        if let Some(entry) = entries.next() {
+            let parent = entries.next().map(|entry| entry.transaction.id);
+            self.tree.push(entry.transaction.id, parent, now);
+        }
+    }
+
+    fn abandon_redo_stack(&mut self) {
+        self.abandoned.append(&mut self.redo_stack);
+        self.prune_abandoned();
+    }
+
+    /// Forgets the oldest abandoned states once there are too many of them. Only leaves are
+    /// removed, so that the text of the remaining states doesn't change.
+    fn prune_abandoned(&mut self) {
+        while self.abandoned.len() > MAX_ABANDONED_TRANSACTIONS {
+            let Some(ix) = self
+                .abandoned
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| self.tree.is_leaf(Some(entry.transaction.id)))
+                .min_by_key(|(_, entry)| self.tree.sequence(Some(entry.transaction.id)))
+                .map(|(ix, _)| ix)
+            else {
+                break;
+            };
+            let entry = self.abandoned.remove(ix);
+            self.tree.remove(entry.transaction.id);
+        }
     }
 
     fn push_undo(&mut self, op_id: clock::Lamport) {
@@ -370,7 +420,7 @@ impl History {
 
     fn forget(&mut self, transaction_id: TransactionId) -> Option<Transaction> {
         assert_eq!(self.transaction_depth, 0);
-        if let Some(entry_ix) = self
+        let transaction = if let Some(entry_ix) = self
             .undo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
@@ -382,9 +432,19 @@ impl History {
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
             Some(self.redo_stack.remove(entry_ix).transaction)
+        } else if let Some(entry_ix) = self
+            .abandoned
+            .iter()
+            .position(|entry| entry.transaction.id == transaction_id)
+        {
+            Some(self.abandoned.remove(entry_ix).transaction)
         } else {
             None
+        };
+        if transaction.is_some() {
+            self.tree.remove(transaction_id);
         }
+        transaction
     }
 
     fn transaction(&self, transaction_id: TransactionId) -> Option<&Transaction> {
@@ -396,6 +456,11 @@ impl History {
                 self.redo_stack
                     .iter()
                     .rfind(|entry| entry.transaction.id == transaction_id)
+            })
+            .or_else(|| {
+                self.abandoned
+                    .iter()
+                    .find(|entry| entry.transaction.id == transaction_id)
             })?;
         Some(&entry.transaction)
     }
@@ -409,6 +474,11 @@ impl History {
                 self.redo_stack
                     .iter_mut()
                     .rfind(|entry| entry.transaction.id == transaction_id)
+            })
+            .or_else(|| {
+                self.abandoned
+                    .iter_mut()
+                    .find(|entry| entry.transaction.id == transaction_id)
             })?;
         Some(&mut entry.transaction)
     }
@@ -445,6 +515,43 @@ impl History {
         }
         &self.undo_stack[undo_stack_start_len..]
     }
+
+    /// Returns the state at the top of the undo stack.
+    fn current_state(&self) -> Option<TransactionId> {
+        self.undo_stack.last().map(|entry| entry.transaction.id)
+    }
+
+    /// Rearranges the undo and redo stacks so that undo walks back from `state` towards the
+    /// original text and redo follows the most recent branch created from `state`.
+    fn move_to_state(&mut self, state: Option<TransactionId>) {
+        assert_eq!(self.transaction_depth, 0);
+
+        let mut entries = self
+            .undo_stack
+            .drain(..)
+            .chain(self.redo_stack.drain(..))
+            .chain(self.abandoned.drain(..))
+            .map(|entry| (entry.transaction.id, entry))
+            .collect::<HashMap<_, _>>();
+
+        self.undo_stack = self
+            .tree
+            .path_to(state)
+            .into_iter()
+            .filter_map(|transaction_id| entries.remove(&transaction_id))
+            .collect();
+        self.redo_stack = self
+            .tree
+            .redo_path_from(state)
+            .into_iter()
+            .rev()
+            .filter_map(|transaction_id| entries.remove(&transaction_id))
+            .collect();
+
+        let mut abandoned = entries.into_values().collect::<Vec<_>>();
+        abandoned.sort_unstable_by_key(|entry| entry.transaction.id);
+        self.abandoned = abandoned;
+    }
 }
 
 struct Edits<'a, D: TextDimension, F: FnMut(&FragmentSummary) -> bool> {
@@ -1467,6 +1574,87 @@ impl Buffer {
             .collect()
     }
 
+    pub fn undo_tree(&self) -> &UndoTree {
+        &self.history.tree
+    }
+
+    /// Returns the state of the undo tree the buffer is currently in, or `None` if every
+    /// transaction has been undone.
+    pub fn undo_tree_state(&self) -> Option<TransactionId> {
+        self.history.current_state()
+    }
+
+    /// Moves the buffer to the given state of its undo tree, undoing and redoing transactions
+    /// across branches as needed. All of the changes are applied as a single undo operation so
+    /// that collaborators converge on the same text.
+    pub fn go_to_undo_tree_state(&mut self, state: Option<TransactionId>) -> Option<Operation> {
+        if self.history.transaction_depth > 0 || !self.history.tree.contains(state) {
+            return None;
+        }
+
+        let counts = self.undo_counts_for_tree_state(state);
+        self.history.move_to_state(state);
+        if counts.is_empty() {
+            return None;
+        }
+
+        let operation = self.undo_operations(counts);
+        self.history.push(operation.clone());
+        Some(operation)
+    }
+
+    /// Returns the text the buffer would contain in the given state of its undo tree, without
+    /// modifying the buffer.
+    pub fn text_for_undo_tree_state(&self, state: Option<TransactionId>) -> Rope {
+        let counts = self.undo_counts_for_tree_state(state);
+        if counts.is_empty() {
+            return self.as_rope().clone();
+        }
+
+        let mut branch = self.branch();
+        branch.undo_operations(counts);
+        branch.as_rope().clone()
+    }
+
+    fn undo_counts_for_tree_state(
+        &self,
+        state: Option<TransactionId>,
+    ) -> HashMap<clock::Lamport, u32> {
+        let tree = &self.history.tree;
+        let (undone, redone) = tree.transactions_between(self.history.current_state(), state);
+        let mut transactions = undone
+            .into_iter()
+            .map(|transaction_id| (transaction_id, false))
+            .chain(
+                redone
+                    .into_iter()
+                    .map(|transaction_id| (transaction_id, true)),
+            )
+            .collect::<HashMap<_, _>>();
+        // Transactions on the redo stack may have been undone out of order, while the states
+        // around them remained applied.
+        for entry in &self.history.redo_stack {
+            let transaction_id = entry.transaction.id;
+            transactions
+                .entry(transaction_id)
+                .or_insert_with(|| tree.is_applied_in(transaction_id, state));
+        }
+
+        let mut counts = HashMap::default();
+        for (transaction_id, should_be_applied) in transactions {
+            let Some(transaction) = self.history.transaction(transaction_id) else {
+                continue;
+            };
+            for edit_id in &transaction.edit_ids {
+                let undo_count = self.undo_map.undo_count(*edit_id);
+                if (undo_count % 2 == 0) != should_be_applied {
+                    counts.insert(*edit_id, undo_count.saturating_add(1));
+                }
+            }
+        }
+        counts
+    }
+
     fn undo_or_redo(&mut self, transaction: Transaction) -> Operation {
         let mut counts = HashMap::default();
         for edit_id in transaction.edit_ids {
@@ -0,0 +1,239 @@
+use crate::TransactionId;
+use collections::HashMap;
+use std::time::Instant;
+
+/// A state in a buffer's undo tree.
+///
+/// Every committed transaction produces a new state whose parent is the state the buffer was in
+/// when the transaction started. Undoing and then editing again starts a new branch instead of
+/// discarding the redo history, so every state the buffer has ever been in remains reachable.
+#[derive(Clone, Debug)]
+pub struct UndoTreeNode {
+    pub transaction_id: TransactionId,
+    /// The state this node was created from, or `None` if it was created from the original text.
+    pub parent: Option<TransactionId>,
+    /// The order in which this state was created, starting at 1. The original text is state 0.
+    pub sequence: usize,
+    pub edited_at: Instant,
+}
+
+/// The branching history of a buffer, as used by `g-`, `g+`, `:earlier` and `:later` in Vim.
+///
+/// States are identified by the transaction that produced them, with `None` denoting the
+/// buffer's original text.
+#[derive(Clone, Debug, Default)]
+pub struct UndoTree {
+    /// The states in the order in which they were created, which is also the order of their
+    /// sequence numbers.
+    nodes: Vec<UndoTreeNode>,
+    indices: HashMap<TransactionId, usize>,
+    children: HashMap<Option<TransactionId>, Vec<TransactionId>>,
+    next_sequence: usize,
+    version: usize,
+}
+
+impl UndoTree {
+    pub(crate) fn push(
+        &mut self,
+        transaction_id: TransactionId,
+        parent: Option<TransactionId>,
+        now: Instant,
+    ) {
+        self.next_sequence += 1;
+        self.version += 1;
+        self.indices.insert(transaction_id, self.nodes.len());
+        self.children
+            .entry(parent)
+            .or_default()
+            .push(transaction_id);
+        self.nodes.push(UndoTreeNode {
+            transaction_id,
+            parent,
+            sequence: self.next_sequence,
+            edited_at: now,
+        });
+    }
+
+    /// Removes a state from the tree, attaching its children to its parent.
+    pub(crate) fn remove(&mut self, transaction_id: TransactionId) {
+        let Some(ix) = self.indices.remove(&transaction_id) else {
+            return;
+        };
+        self.version += 1;
+        let removed = self.nodes.remove(ix);
+        for (ix, node) in self.nodes.iter().enumerate().skip(ix) {
+            self.indices.insert(node.transaction_id, ix);
+        }
+
+        if let Some(siblings) = self.children.get_mut(&removed.parent) {
+            siblings.retain(|sibling| *sibling != transaction_id);
+        }
+        if let Some(children) = self.children.remove(&Some(transaction_id)) {
+            for child in &children {
+                if let Some(ix) = self.indices.get(child) {
+                    self.nodes[*ix].parent = removed.parent;
+                }
+            }
+            let siblings = self.children.entry(removed.parent).or_default();
+            siblings.extend(children);
+            siblings.sort_unstable_by_key(|sibling| self.indices.get(sibling).copied());
+        }
+    }
+
+    pub(crate) fn set_edited_at(&mut self, transaction_id: TransactionId, edited_at: Instant) {
+        if let Some(ix) = self.indices.get(&transaction_id) {
+            self.nodes[*ix].edited_at = edited_at;
+            self.version += 1;
+        }
+    }
+
+    /// A number that changes whenever the tree does, so that views can cheaply skip updates.
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns all states in the order in which they were created.
+    pub fn nodes(&self) -> impl Iterator<Item = &UndoTreeNode> {
+        self.nodes.iter()
+    }
+
+    pub fn node(&self, transaction_id: TransactionId) -> Option<&UndoTreeNode> {
+        self.indices.get(&transaction_id).map(|ix| &self.nodes[*ix])
+    }
+
+    pub fn contains(&self, state: Option<TransactionId>) -> bool {
+        state.map_or(true, |transaction_id| {
+            self.indices.contains_key(&transaction_id)
+        })
+    }
+
+    pub fn sequence(&self, state: Option<TransactionId>) -> usize {
+        state
+            .and_then(|transaction_id| self.node(transaction_id))
+            .map_or(0, |node| node.sequence)
+    }
+
+    fn parent(&self, state: Option<TransactionId>) -> Option<TransactionId> {
+        state
+            .and_then(|transaction_id| self.node(transaction_id))
+            .and_then(|node| node.parent)
+    }
+
+    /// Returns the states created directly from the given state, oldest first.
+    pub fn children(&self, state: Option<TransactionId>) -> impl Iterator<Item = &UndoTreeNode> {
+        self.children
+            .get(&state)
+            .into_iter()
+            .flatten()
+            .filter_map(|transaction_id| self.node(*transaction_id))
+    }
+
+    /// Returns whether the given state has no states created from it.
+    pub fn is_leaf(&self, state: Option<TransactionId>) -> bool {
+        self.children
+            .get(&state)
+            .map_or(true, |children| children.is_empty())
+    }
+
+    /// Returns the transactions that are applied in the given state, starting from the root.
+    pub fn path_to(&self, state: Option<TransactionId>) -> Vec<TransactionId> {
+        let mut path = Vec::new();
+        let mut current = state;
+        while let Some(node) = current.and_then(|transaction_id| self.node(transaction_id)) {
+            path.push(node.transaction_id);
+            current = node.parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Returns whether the transaction is applied in the given state.
+    pub fn is_applied_in(
+        &self,
+        transaction_id: TransactionId,
+        state: Option<TransactionId>,
+    ) -> bool {
+        // Parents are always created before their children, so the walk can stop as soon as it
+        // reaches a state older than the transaction.
+        let sequence = self.sequence(Some(transaction_id));
+        let mut current = state;
+        while current.is_some() && self.sequence(current) >= sequence {
+            if current == Some(transaction_id) {
+                return true;
+            }
+            current = self.parent(current);
+        }
+        false
+    }
+
+    /// Returns the transactions that need to be undone and redone to go from one state to the
+    /// other, without visiting the part of the tree they have in common.
+    pub fn transactions_between(
+        &self,
+        from: Option<TransactionId>,
+        to: Option<TransactionId>,
+    ) -> (Vec<TransactionId>, Vec<TransactionId>) {
+        let mut undone = Vec::new();
+        let mut redone = Vec::new();
+        let mut from = from.filter(|transaction_id| self.indices.contains_key(transaction_id));
+        let mut to = to.filter(|transaction_id| self.indices.contains_key(transaction_id));
+        while from != to {
+            if self.sequence(from) >= self.sequence(to) {
+                undone.extend(from);
+                from = self.parent(from);
+            } else {
+                redone.extend(to);
+                to = self.parent(to);
+            }
+        }
+        redone.reverse();
+        (undone, redone)
+    }
+
+    /// Returns the branch that redo follows from the given state, which is always the most
+    /// recently created child.
+    pub fn redo_path_from(&self, state: Option<TransactionId>) -> Vec<TransactionId> {
+        let mut path = Vec::new();
+        let mut current = state;
+        while let Some(child) = self.children(current).max_by_key(|node| node.sequence) {
+            path.push(child.transaction_id);
+            current = Some(child.transaction_id);
+        }
+        path
+    }
+
+    /// Returns the state `delta` steps away from `state` in creation order, like Vim's `g-` and
+    /// `g+`. Moving past either end of the history stops at the oldest or newest state.
+    pub fn chronological_state(
+        &self,
+        state: Option<TransactionId>,
+        delta: isize,
+    ) -> Option<TransactionId> {
+        // Position 0 is the original text, and position `ix + 1` is the node at `ix`.
+        let position = state
+            .and_then(|transaction_id| self.indices.get(&transaction_id))
+            .map_or(0, |ix| ix + 1);
+        let target_position = position.saturating_add_signed(delta).min(self.nodes.len());
+        target_position
+            .checked_sub(1)
+            .map(|ix| self.nodes[ix].transaction_id)
+    }
+
+    /// Returns the most recently created state that existed at the given time, like Vim's
+    /// `:earlier` and `:later` with a time argument.
+    pub fn state_at(&self, time: Instant) -> Option<TransactionId> {
+        self.nodes
+            .iter()
+            .rev()
+            .find(|node| node.edited_at <= time)
+            .map(|node| node.transaction_id)
+    }
+}
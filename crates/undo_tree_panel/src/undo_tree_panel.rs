@@ -0,0 +1,570 @@
+use std::{
+    cmp::Reverse,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use collections::HashMap;
+use editor::Editor;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    ScrollStrategy, SharedString, Subscription, Task, UniformListScrollHandle, WeakEntity, Window,
+    actions, uniform_list,
+};
+use language::{Buffer, TransactionId, UndoTree};
+use menu::{Confirm, SelectNext, SelectPrevious};
+use ui::{Indicator, ListItem, ListItemSpacing, Tab, prelude::*};
+use util::ResultExt;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(undo_tree_panel, [ToggleFocus]);
+
+const DEFAULT_WIDTH: Pixels = px(280.);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<UndoTreePanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+/// Shows the branching undo history of the active editor's buffer and lets the user jump to any
+/// state in it.
+pub struct UndoTreePanel {
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    active_editor: Option<ActiveEditor>,
+    entries: Vec<UndoTreeEntry>,
+    selected_entry: Option<usize>,
+    /// The version of the undo tree and the state the entries were laid out for.
+    entries_key: Option<(usize, Option<TransactionId>)>,
+    /// The changes that produced each state that has been selected, by state.
+    diffs: HashMap<Option<TransactionId>, StateDiff>,
+    diff_task: Task<()>,
+    position: DockPosition,
+    width: Option<Pixels>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct ActiveEditor {
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    title: SharedString,
+    _buffer_subscription: Subscription,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct UndoTreeEntry {
+    state: Option<TransactionId>,
+    parent: Option<TransactionId>,
+    sequence: usize,
+    column: usize,
+    edited_at: Option<Instant>,
+    is_current: bool,
+}
+
+struct StateDiff {
+    parent: Option<TransactionId>,
+    edited_at: Option<Instant>,
+    lines: Vec<SharedString>,
+}
+
+impl StateDiff {
+    fn is_valid_for(&self, entry: &UndoTreeEntry) -> bool {
+        self.parent == entry.parent && self.edited_at == entry.edited_at
+    }
+}
+
+impl UndoTreePanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            Self::new(workspace, window, cx)
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let workspace_handle = cx.entity();
+        cx.new(|cx| {
+            let workspace_subscription = cx.subscribe_in(
+                &workspace_handle,
+                window,
+                |this, workspace, event, window, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        this.set_active_editor(
+                            workspace_active_editor(workspace.read(cx), cx),
+                            window,
+                            cx,
+                        );
+                    }
+                },
+            );
+
+            let mut this = Self {
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                active_editor: None,
+                entries: Vec::new(),
+                selected_entry: None,
+                entries_key: None,
+                diffs: HashMap::default(),
+                diff_task: Task::ready(()),
+                position: DockPosition::Right,
+                width: None,
+                _subscriptions: vec![workspace_subscription],
+            };
+            this.set_active_editor(workspace_active_editor(workspace, cx), window, cx);
+            this
+        })
+    }
+
+    fn set_active_editor(
+        &mut self,
+        editor: Option<Entity<Editor>>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer = editor
+            .as_ref()
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton());
+        let (Some(editor), Some(buffer)) = (editor, buffer) else {
+            return;
+        };
+        if self
+            .active_editor
+            .as_ref()
+            .is_some_and(|active_editor| active_editor.buffer == buffer)
+        {
+            return;
+        }
+
+        let title = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "untitled".into());
+        let buffer_subscription = cx.observe(&buffer, |this, _, cx| {
+            this.update_entries(cx);
+        });
+        self.active_editor = Some(ActiveEditor {
+            editor: editor.downgrade(),
+            buffer,
+            title,
+            _buffer_subscription: buffer_subscription,
+        });
+        self.selected_entry = None;
+        self.entries_key = None;
+        self.diffs.clear();
+        self.update_entries(cx);
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        let Some(active_editor) = self.active_editor.as_ref() else {
+            return;
+        };
+        let buffer = active_editor.buffer.read(cx);
+        let tree = buffer.undo_tree();
+        let entries_key = Some((tree.version(), buffer.undo_tree_state()));
+        if entries_key == self.entries_key {
+            return;
+        }
+        self.entries_key = entries_key;
+        let entries = layout_undo_tree(tree, buffer.undo_tree_state());
+        if entries == self.entries {
+            return;
+        }
+
+        let selected_state = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .map(|entry| entry.state);
+        self.entries = entries;
+        self.diffs.retain(|state, diff| {
+            self.entries
+                .iter()
+                .any(|entry| entry.state == *state && diff.is_valid_for(entry))
+        });
+        self.selected_entry = selected_state
+            .and_then(|state| self.entries.iter().position(|entry| entry.state == state))
+            .or_else(|| self.entries.iter().position(|entry| entry.is_current));
+        self.update_diff(cx);
+        cx.notify();
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix >= self.entries.len() {
+            return;
+        }
+        self.selected_entry = Some(ix);
+        self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
+        self.update_diff(cx);
+        cx.notify();
+    }
+
+    /// Computes the changes that produced the selected state from its parent, unless they're
+    /// already known.
+    fn update_diff(&mut self, cx: &mut Context<Self>) {
+        let Some(entry) = self.selected_entry.and_then(|ix| self.entries.get(ix)) else {
+            return;
+        };
+        let Some(active_editor) = self.active_editor.as_ref() else {
+            return;
+        };
+        if self
+            .diffs
+            .get(&entry.state)
+            .is_some_and(|diff| diff.is_valid_for(entry))
+        {
+            return;
+        }
+
+        let state = entry.state;
+        let parent = entry.parent;
+        let edited_at = entry.edited_at;
+        if state.is_none() {
+            self.diffs.insert(
+                state,
+                StateDiff {
+                    parent,
+                    edited_at,
+                    lines: Vec::new(),
+                },
+            );
+            return;
+        }
+
+        let buffer = active_editor.buffer.read(cx);
+        let old_text = buffer.text_for_undo_tree_state(parent);
+        let new_text = buffer.text_for_undo_tree_state(state);
+        let diff = cx.background_spawn(async move {
+            language::unified_diff(&old_text.to_string(), &new_text.to_string())
+        });
+        self.diff_task = cx.spawn(async move |this, cx| {
+            let diff = diff.await;
+            this.update(cx, |this, cx| {
+                this.diffs.insert(
+                    state,
+                    StateDiff {
+                        parent,
+                        edited_at,
+                        lines: diff
+                            .lines()
+                            .map(|line| SharedString::from(line.to_string()))
+                            .collect(),
+                    },
+                );
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_entry.map_or(0, |ix| ix + 1);
+        self.select_entry(ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry.and_then(|ix| ix.checked_sub(1)) {
+            self.select_entry(ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.go_to_entry(ix, window, cx);
+        }
+    }
+
+    fn go_to_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let Some(editor) = self
+            .active_editor
+            .as_ref()
+            .and_then(|active_editor| active_editor.editor.upgrade())
+        else {
+            return;
+        };
+        let state = entry.state;
+        editor.update(cx, |editor, cx| {
+            editor.go_to_undo_tree_state(state, window, cx);
+        });
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> ListItem {
+        let entry = &self.entries[ix];
+        let label = match entry.state {
+            Some(_) => format!("#{}", entry.sequence),
+            None => "Original".to_string(),
+        };
+        let age = entry
+            .edited_at
+            .map(|edited_at| format_age(Instant::now().saturating_duration_since(edited_at)));
+
+        ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(entry.column)
+            .indent_step_size(px(12.))
+            .toggle_state(self.selected_entry == Some(ix))
+            .start_slot(Indicator::dot().color(if entry.is_current {
+                Color::Accent
+            } else {
+                Color::Muted
+            }))
+            .child(
+                Label::new(label)
+                    .size(LabelSize::Small)
+                    .color(if entry.is_current {
+                        Color::Default
+                    } else {
+                        Color::Muted
+                    }),
+            )
+            .end_slot::<Label>(
+                age.map(|age| Label::new(age).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .on_click(
+                cx.listener(move |this, event: &gpui::ClickEvent, window, cx| {
+                    this.select_entry(ix, cx);
+                    if event.down.click_count > 1 {
+                        this.go_to_entry(ix, window, cx);
+                    }
+                }),
+            )
+    }
+
+    fn render_diff(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let lines = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .and_then(|entry| self.diffs.get(&entry.state))
+            .map(|diff| diff.lines.clone())
+            .unwrap_or_default();
+
+        v_flex()
+            .id("undo-tree-diff")
+            .h_1_3()
+            .p_2()
+            .gap_0p5()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .overflow_y_scroll()
+            .when(lines.is_empty(), |this| {
+                this.child(
+                    Label::new("No changes")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .children(lines.into_iter().map(|line| {
+                let color = if line.starts_with('+') {
+                    Color::Created
+                } else if line.starts_with('-') {
+                    Color::Deleted
+                } else if line.starts_with("@@") {
+                    Color::Accent
+                } else {
+                    Color::Muted
+                };
+                Label::new(line)
+                    .size(LabelSize::Small)
+                    .buffer_font(cx)
+                    .single_line()
+                    .color(color)
+            }))
+    }
+}
+
+/// Lays out the undo tree with the newest states first. The most recent branch from each state
+/// stays in its parent's column, and older branches are indented into new columns.
+fn layout_undo_tree(tree: &UndoTree, current: Option<TransactionId>) -> Vec<UndoTreeEntry> {
+    let mut columns = HashMap::default();
+    let mut next_column = 1;
+    let mut stack = vec![(None, 0)];
+    while let Some((state, column)) = stack.pop() {
+        let mut children = tree.children(state).collect::<Vec<_>>();
+        children.sort_by_key(|node| Reverse(node.sequence));
+        for (ix, child) in children.into_iter().enumerate() {
+            let child_column = if ix == 0 {
+                column
+            } else {
+                next_column += 1;
+                next_column - 1
+            };
+            columns.insert(child.transaction_id, child_column);
+            stack.push((Some(child.transaction_id), child_column));
+        }
+    }
+
+    let mut entries = tree
+        .nodes()
+        .map(|node| UndoTreeEntry {
+            state: Some(node.transaction_id),
+            parent: node.parent,
+            sequence: node.sequence,
+            column: columns.get(&node.transaction_id).copied().unwrap_or(0),
+            edited_at: Some(node.edited_at),
+            is_current: current == Some(node.transaction_id),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| Reverse(entry.sequence));
+    entries.push(UndoTreeEntry {
+        state: None,
+        parent: None,
+        sequence: 0,
+        column: 0,
+        edited_at: None,
+        is_current: current.is_none(),
+    });
+    entries
+}
+
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    if seconds < 5 {
+        "just now".to_string()
+    } else if seconds < 60 {
+        format!("{seconds}s ago")
+    } else if seconds < 60 * 60 {
+        format!("{}m ago", seconds / 60)
+    } else if seconds < 24 * 60 * 60 {
+        format!("{}h ago", seconds / (60 * 60))
+    } else {
+        format!("{}d ago", seconds / (24 * 60 * 60))
+    }
+}
+
+fn workspace_active_editor(workspace: &Workspace, cx: &App) -> Option<Entity<Editor>> {
+    workspace
+        .active_item(cx)?
+        .act_as::<Editor>(cx)
+        .filter(|editor| editor.read(cx).mode().is_full())
+}
+
+impl Render for UndoTreePanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = self
+            .active_editor
+            .as_ref()
+            .map(|active_editor| active_editor.title.clone())
+            .unwrap_or_else(|| "Undo Tree".into());
+
+        v_flex()
+            .id("undo-tree-panel")
+            .key_context("UndoTreePanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(title)),
+            )
+            .map(|this| {
+                if self.active_editor.is_none() {
+                    this.child(v_flex().p_2().child(
+                        Label::new("Open a file to see its undo history").color(Color::Muted),
+                    ))
+                } else {
+                    this.child(
+                        uniform_list(
+                            "undo-tree-entries",
+                            self.entries.len(),
+                            cx.processor(|this, range: Range<usize>, _, cx| {
+                                range.map(|ix| this.render_entry(ix, cx)).collect()
+                            }),
+                        )
+                        .track_scroll(self.scroll_handle.clone())
+                        .flex_1(),
+                    )
+                    .child(self.render_diff(cx))
+                }
+            })
+    }
+}
+
+impl Focusable for UndoTreePanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for UndoTreePanel {}
+
+impl Panel for UndoTreePanel {
+    fn persistent_name() -> &'static str {
+        "UndoTreePanel"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.width.unwrap_or(DEFAULT_WIDTH)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        Some(IconName::HistoryRerun)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Undo Tree")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(1)), "just now");
+        assert_eq!(format_age(Duration::from_secs(42)), "42s ago");
+        assert_eq!(format_age(Duration::from_secs(600)), "10m ago");
+        assert_eq!(format_age(Duration::from_secs(3 * 60 * 60)), "3h ago");
+        assert_eq!(format_age(Duration::from_secs(2 * 24 * 60 * 60)), "2d ago");
+    }
+}
@@ -38,8 +38,13 @@ use crate::{
     normal::{
         JoinLines,
         search::{FindCommand, ReplaceCommand, Replacement},
+        undo_tree::UndoTreeDistance,
     },
     object::Object,
+    quickfix::{
+        QuickfixDo, QuickfixFromDiagnostics, QuickfixFromReferences, QuickfixGoTo, QuickfixOpen,
+        QuickfixTarget, VimGrep, VimMake,
+    },
     state::{Mark, Mode},
     visual::VisualDeleteLine,
 };
@@ -168,6 +173,13 @@ struct VimSave {
     pub filename: String,
 }
 
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct VimUndoTreeJump {
+    older: bool,
+    distance: UndoTreeDistance,
+}
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = vim, no_json, no_register)]
 enum DeleteMarks {
@@ -372,6 +384,10 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         });
     });
 
+    Vim::action(editor, cx, |vim, action: &VimUndoTreeJump, window, cx| {
+        vim.move_in_undo_tree(action.distance, action.older, window, cx);
+    });
+
     Vim::action(editor, cx, |vim, action: &VimEdit, window, cx| {
         vim.update_editor(window, cx, |vim, editor, window, cx| {
             let Some(workspace) = vim.workspace(window) else {
@@ -1037,12 +1053,117 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
             save_intent: Some(SaveIntent::Skip),
         }),
         VimCommand::str(("cl", "ist"), "diagnostics::Deploy"),
-        VimCommand::new(("cc", ""), editor::actions::Hover),
+        VimCommand::new(("cc", ""), editor::actions::Hover).args(|_, args| {
+            Some(
+                QuickfixGoTo {
+                    target: QuickfixTarget::Number(args.parse().ok()?),
+                }
+                .boxed_clone(),
+            )
+        }),
         VimCommand::new(("ll", ""), editor::actions::Hover),
-        VimCommand::new(("cn", "ext"), editor::actions::GoToDiagnostic).range(wrap_count),
-        VimCommand::new(("cp", "revious"), editor::actions::GoToPreviousDiagnostic)
-            .range(wrap_count),
-        VimCommand::new(("cN", "ext"), editor::actions::GoToPreviousDiagnostic).range(wrap_count),
+        VimCommand::new(
+            ("cn", "ext"),
+            QuickfixGoTo {
+                target: QuickfixTarget::Next,
+            },
+        )
+        .range(wrap_count),
+        VimCommand::new(
+            ("cp", "revious"),
+            QuickfixGoTo {
+                target: QuickfixTarget::Previous,
+            },
+        )
+        .range(wrap_count),
+        VimCommand::new(
+            ("cN", "ext"),
+            QuickfixGoTo {
+                target: QuickfixTarget::Previous,
+            },
+        )
+        .range(wrap_count),
+        VimCommand::new(
+            ("cnf", "ile"),
+            QuickfixGoTo {
+                target: QuickfixTarget::NextFile,
+            },
+        )
+        .range(wrap_count),
+        VimCommand::new(
+            ("cpf", "ile"),
+            QuickfixGoTo {
+                target: QuickfixTarget::PreviousFile,
+            },
+        )
+        .range(wrap_count),
+        VimCommand::new(
+            ("cNf", "ile"),
+            QuickfixGoTo {
+                target: QuickfixTarget::PreviousFile,
+            },
+        )
+        .range(wrap_count),
+        VimCommand::new(
+            ("cfir", "st"),
+            QuickfixGoTo {
+                target: QuickfixTarget::First,
+            },
+        ),
+        VimCommand::new(
+            ("cr", "ewind"),
+            QuickfixGoTo {
+                target: QuickfixTarget::First,
+            },
+        ),
+        VimCommand::new(
+            ("cla", "st"),
+            QuickfixGoTo {
+                target: QuickfixTarget::Last,
+            },
+        ),
+        VimCommand::new(("cope", "n"), QuickfixOpen),
+        VimCommand::new(("cdia", "gnostics"), QuickfixFromDiagnostics),
+        VimCommand::new(("cref", "erences"), QuickfixFromReferences),
+        VimCommand::new(("cdo", ""), ArgumentRequired).args(|_, command| {
+            Some(
+                QuickfixDo {
+                    command,
+                    per_file: false,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(("cfdo", ""), ArgumentRequired).args(|_, command| {
+            Some(
+                QuickfixDo {
+                    command,
+                    per_file: true,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(("vim", "grep"), ArgumentRequired)
+            .bang(ArgumentRequired)
+            .args(|_, args| Some(VimGrep::parse(&args)?.boxed_clone())),
+        VimCommand::new(
+            ("mak", "e"),
+            VimMake {
+                args: String::new(),
+                jump: true,
+            },
+        )
+        .bang(VimMake {
+            args: String::new(),
+            jump: false,
+        })
+        .args(|action, args| {
+            let jump = action
+                .as_any()
+                .downcast_ref::<VimMake>()
+                .map_or(true, |action| action.jump);
+            Some(VimMake { args, jump }.boxed_clone())
+        }),
         VimCommand::new(("lp", "revious"), editor::actions::GoToPreviousDiagnostic)
             .range(wrap_count),
         VimCommand::new(("lN", "ext"), editor::actions::GoToPreviousDiagnostic).range(wrap_count),
@@ -1072,6 +1193,39 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("delm", "arks"), ArgumentRequired)
             .bang(DeleteMarks::AllLocal)
             .args(|_, args| Some(DeleteMarks::Marks(args).boxed_clone())),
+        VimCommand::new(
+            ("ea", "rlier"),
+            VimUndoTreeJump {
+                older: true,
+                distance: UndoTreeDistance::Steps(1),
+            },
+        )
+        .args(|_, args| {
+            Some(
+                VimUndoTreeJump {
+                    older: true,
+                    distance: UndoTreeDistance::parse(&args)?,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(
+            ("lat", "er"),
+            VimUndoTreeJump {
+                older: false,
+                distance: UndoTreeDistance::Steps(1),
+            },
+        )
+        .args(|_, args| {
+            Some(
+                VimUndoTreeJump {
+                    older: false,
+                    distance: UndoTreeDistance::parse(&args)?,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::str(("undot", "ree"), "undo_tree_panel::ToggleFocus"),
         VimCommand::new(("sor", "t"), SortLinesCaseSensitive).range(select_range),
         VimCommand::new(("sort i", ""), SortLinesCaseInsensitive).range(select_range),
         VimCommand::str(("E", "xplore"), "project_panel::ToggleFocus"),
@@ -1282,6 +1436,40 @@ fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     positions
 }
 
+pub(crate) fn is_pattern_delimiter(c: char) -> bool {
+    !c.is_alphanumeric() && c != '"' && c != '|' && c != '\'' && c != '!'
+}
+
+/// Reads a Vim pattern up to the given (unescaped) delimiter, converting it to the regex syntax
+/// used by Zed.
+pub(crate) fn parse_pattern(chars: &mut Peekable<Chars>, delimiter: char) -> String {
+    let mut search = String::new();
+    let mut escaped = false;
+
+    while let Some(c) = chars.next() {
+        if escaped {
+            escaped = false;
+            // unescape escaped parens
+            if c != '(' && c != ')' && c != delimiter {
+                search.push('\\')
+            }
+            search.push(c)
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter {
+            break;
+        } else {
+            // escape unescaped parens
+            if c == '(' || c == ')' {
+                search.push('\\')
+            }
+            search.push(c)
+        }
+    }
+
+    search
+}
+
 #[derive(Debug, PartialEq, Clone, Action)]
 #[action(namespace = vim, no_json, no_register)]
 pub(crate) struct OnMatchingLines {
@@ -1302,34 +1490,8 @@ impl OnMatchingLines {
         range: CommandRange,
         cx: &App,
     ) -> Option<Self> {
-        let delimiter = chars.next().filter(|c| {
-            !c.is_alphanumeric() && *c != '"' && *c != '|' && *c != '\'' && *c != '!'
-        })?;
-
-        let mut search = String::new();
-        let mut escaped = false;
-
-        while let Some(c) = chars.next() {
-            if escaped {
-                escaped = false;
-                // unescape escaped parens
-                if c != '(' && c != ')' && c != delimiter {
-                    search.push('\\')
-                }
-                search.push(c)
-            } else if c == '\\' {
-                escaped = true;
-            } else if c == delimiter {
-                break;
-            } else {
-                // escape unescaped parens
-                if c == '(' || c == ')' {
-                    search.push('\\')
-                }
-                search.push(c)
-            }
-        }
-
+        let delimiter = chars.next().filter(|c| is_pattern_delimiter(*c))?;
+        let search = parse_pattern(&mut chars, delimiter);
         let command: String = chars.collect();
 
         let action = WrappedAction(
@@ -1683,6 +1845,8 @@ impl ShellExec {
                     show_summary: false,
                     show_command: false,
                     show_rerun: false,
+                    problem_matchers: Vec::new(),
+                    ready_pattern: None,
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
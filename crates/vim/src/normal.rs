@@ -9,6 +9,7 @@ mod scroll;
 pub(crate) mod search;
 pub mod substitute;
 mod toggle_comments;
+pub(crate) mod undo_tree;
 pub(crate) mod yank;
 
 use std::collections::HashMap;
@@ -173,6 +174,7 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     search::register(editor, cx);
     substitute::register(editor, cx);
     increment::register(editor, cx);
+    undo_tree::register(editor, cx);
 }
 
 impl Vim {
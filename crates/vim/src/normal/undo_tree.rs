@@ -0,0 +1,163 @@
+use crate::Vim;
+use editor::Editor;
+use gpui::{Context, Window, actions};
+use std::time::Duration;
+
+actions!(vim, [UndoTreeOlder, UndoTreeNewer]);
+
+/// How far to move through the undo tree with `g-`, `g+`, `:earlier` and `:later`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum UndoTreeDistance {
+    /// A number of states, in the order in which they were created.
+    Steps(usize),
+    /// An amount of time relative to when the current state was created.
+    Time(Duration),
+}
+
+impl UndoTreeDistance {
+    /// Parses the argument of `:earlier` and `:later`, e.g. `3`, `10s`, `5m`, `1h` or `2d`.
+    pub(crate) fn parse(arg: &str) -> Option<Self> {
+        let arg = arg.trim();
+        let (count, unit) = match arg.find(|c: char| !c.is_ascii_digit()) {
+            Some(ix) => arg.split_at(ix),
+            None => (arg, ""),
+        };
+        let count = if count.is_empty() {
+            1
+        } else {
+            count.parse::<u64>().ok()?
+        };
+        let seconds = match unit {
+            "" => return Some(Self::Steps(count as usize)),
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return None,
+        };
+        Some(Self::Time(Duration::from_secs(
+            count.saturating_mul(seconds),
+        )))
+    }
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, _: &UndoTreeOlder, window, cx| {
+        let times = Vim::take_count(cx).unwrap_or(1);
+        Vim::take_forced_motion(cx);
+        vim.move_in_undo_tree(UndoTreeDistance::Steps(times), true, window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &UndoTreeNewer, window, cx| {
+        let times = Vim::take_count(cx).unwrap_or(1);
+        Vim::take_forced_motion(cx);
+        vim.move_in_undo_tree(UndoTreeDistance::Steps(times), false, window, cx);
+    });
+}
+
+impl Vim {
+    pub(crate) fn move_in_undo_tree(
+        &mut self,
+        distance: UndoTreeDistance,
+        older: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let buffer = buffer.read(cx);
+            let tree = buffer.undo_tree();
+            let current = buffer.undo_tree_state();
+            let target = match distance {
+                UndoTreeDistance::Steps(steps) => {
+                    let steps = steps as isize;
+                    tree.chronological_state(current, if older { -steps } else { steps })
+                }
+                UndoTreeDistance::Time(duration) => {
+                    let current_time = current
+                        .and_then(|transaction_id| tree.node(transaction_id))
+                        .or_else(|| tree.nodes().next())
+                        .map(|node| node.edited_at);
+                    let Some(current_time) = current_time else {
+                        return;
+                    };
+                    if older {
+                        current_time
+                            .checked_sub(duration)
+                            .and_then(|time| tree.state_at(time))
+                    } else {
+                        let target = current_time
+                            .checked_add(duration)
+                            .map_or(tree.chronological_state(current, isize::MAX), |time| {
+                                tree.state_at(time)
+                            });
+                        if tree.sequence(target) > tree.sequence(current) {
+                            target
+                        } else {
+                            current
+                        }
+                    }
+                }
+            };
+            if target != current {
+                editor.go_to_undo_tree_state(target, window, cx);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::UndoTreeDistance;
+    use crate::{state::Mode, test::VimTestContext};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_undo_tree_distance() {
+        assert_eq!(
+            UndoTreeDistance::parse(""),
+            Some(UndoTreeDistance::Steps(1))
+        );
+        assert_eq!(
+            UndoTreeDistance::parse("3"),
+            Some(UndoTreeDistance::Steps(3))
+        );
+        assert_eq!(
+            UndoTreeDistance::parse("10m"),
+            Some(UndoTreeDistance::Time(Duration::from_secs(600)))
+        );
+        assert_eq!(
+            UndoTreeDistance::parse("2h"),
+            Some(UndoTreeDistance::Time(Duration::from_secs(7200)))
+        );
+        assert_eq!(UndoTreeDistance::parse("1f"), None);
+        assert_eq!(UndoTreeDistance::parse("x"), None);
+    }
+
+    #[gpui::test]
+    async fn test_undo_tree_older_newer(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes("A space t w o escape");
+        assert_eq!(cx.buffer_text(), "one two");
+        cx.simulate_keystrokes("u");
+        assert_eq!(cx.buffer_text(), "one");
+        cx.simulate_keystrokes("A space t h r e e escape");
+        assert_eq!(cx.buffer_text(), "one three");
+
+        // "one two" can't be reached with `u` anymore, but `g-` walks back through it.
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "one two");
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "one");
+        cx.simulate_keystrokes("2 g +");
+        assert_eq!(cx.buffer_text(), "one three");
+
+        cx.simulate_keystrokes(": e a r l i e r space 2 enter");
+        assert_eq!(cx.buffer_text(), "one");
+        cx.simulate_keystrokes(": l a t e r enter");
+        assert_eq!(cx.buffer_text(), "one two");
+    }
+}
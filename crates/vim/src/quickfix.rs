@@ -0,0 +1,810 @@
+use std::{collections::BTreeSet, path::PathBuf, process::Stdio};
+
+use anyhow::{Context as _, Result};
+use editor::{Bias, Editor, MultibufferSelectionMode};
+use futures::{channel::oneshot, future::join_all};
+use gpui::{Action, App, AsyncWindowContext, Context, Entity, Task, Window, actions};
+use language::{DiagnosticSeverity, Location, Point, ToPoint as _};
+use project::{
+    ProjectPath,
+    search::{SearchQuery, SearchResult},
+};
+use regex::Regex;
+use settings::Settings as _;
+use util::{ResultExt as _, paths::PathMatcher};
+use workspace::{
+    Workspace,
+    notifications::{NotifyResultExt as _, NotifyTaskExt as _},
+    quickfix::QuickfixEntry,
+};
+
+use crate::{
+    Vim, VimSettings,
+    command::{command_interceptor, is_pattern_delimiter, parse_pattern},
+};
+
+actions!(
+    vim,
+    [
+        QuickfixOpen,
+        QuickfixFromDiagnostics,
+        QuickfixFromReferences
+    ]
+);
+
+/// The entry of the quickfix list that [`QuickfixGoTo`] moves to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum QuickfixTarget {
+    Next,
+    Previous,
+    NextFile,
+    PreviousFile,
+    First,
+    Last,
+    /// An entry by its one-based number, as in `:cc 3`.
+    Number(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixGoTo {
+    pub(crate) target: QuickfixTarget,
+}
+
+/// Runs an ex command on each entry (`:cdo`) or each file (`:cfdo`) in the quickfix list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixDo {
+    pub(crate) command: String,
+    pub(crate) per_file: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimGrep {
+    pub(crate) pattern: String,
+    pub(crate) files: Vec<String>,
+    pub(crate) all_matches: bool,
+    pub(crate) jump: bool,
+}
+
+impl VimGrep {
+    /// Parses the arguments of `:vimgrep`, e.g. `/pattern/gj src/**/*.rs`.
+    pub(crate) fn parse(args: &str) -> Option<Self> {
+        let mut chars = args.trim_start().chars().peekable();
+        let delimiter = *chars.peek()?;
+        let pattern = if is_pattern_delimiter(delimiter) {
+            chars.next();
+            parse_pattern(&mut chars, delimiter)
+        } else {
+            parse_pattern(&mut chars, ' ')
+        };
+        if pattern.is_empty() {
+            return None;
+        }
+
+        let mut all_matches = false;
+        let mut jump = true;
+        while let Some(flag) = chars.peek() {
+            match flag {
+                'g' => all_matches = true,
+                'j' => jump = false,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        let files = chars.collect::<String>();
+        Some(Self {
+            pattern,
+            files: files.split_whitespace().map(ToOwned::to_owned).collect(),
+            all_matches,
+            jump,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimMake {
+    pub(crate) args: String,
+    pub(crate) jump: bool,
+}
+
+/// A pattern for parsing compiler output into quickfix entries, in the style of Vim's
+/// `errorformat`.
+///
+/// Supports `%f` (file), `%l` (line), `%c` (column), `%t` (type, e.g. `e`rror or `w`arning),
+/// `%m` (message), `%.%#` (any text) and `%%`. Patterns without `%f` match a message that is
+/// used for the next location without one, like rustc's `error: ...` followed by ` --> file`.
+#[derive(Debug)]
+pub(crate) struct ErrorFormat {
+    regex: Regex,
+}
+
+impl ErrorFormat {
+    pub(crate) fn new(format: &str) -> Result<Self> {
+        let mut pattern = String::from("^");
+        let mut rest = format;
+        while let Some(ix) = rest.find('%') {
+            pattern.push_str(&regex::escape(&rest[..ix]));
+            rest = &rest[ix + 1..];
+            let (item, len) = if rest.starts_with(".%#") {
+                (".*?", 3)
+            } else {
+                match rest.chars().next() {
+                    Some('f') => (r"(?P<file>(?:[A-Za-z]:)?.+?)", 1),
+                    Some('l') => (r"(?P<line>\d+)", 1),
+                    Some('c') => (r"(?P<column>\d+)", 1),
+                    Some('t') => ("(?P<kind>[A-Za-z])", 1),
+                    Some('m') => ("(?P<message>.*)", 1),
+                    Some('%') => ("%", 1),
+                    _ => anyhow::bail!("unsupported item in error format {format:?}"),
+                }
+            };
+            pattern.push_str(item);
+            rest = &rest[len..];
+        }
+        pattern.push_str(&regex::escape(rest));
+        pattern.push('$');
+        Ok(Self {
+            regex: Regex::new(&pattern)?,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct ParsedError {
+    pub(crate) path: PathBuf,
+    /// One-based, as printed by compilers.
+    pub(crate) line: u32,
+    /// One-based, as printed by compilers.
+    pub(crate) column: Option<u32>,
+    pub(crate) severity: Option<DiagnosticSeverity>,
+    pub(crate) message: String,
+}
+
+pub(crate) fn parse_errors(output: &str, formats: &[ErrorFormat]) -> Vec<ParsedError> {
+    let mut errors = Vec::new();
+    let mut pending_message = None;
+    for line in output.lines() {
+        let line = line.trim_end();
+        let Some(captures) = formats
+            .iter()
+            .find_map(|format| format.regex.captures(line))
+        else {
+            continue;
+        };
+        let severity = captures.name("kind").and_then(|kind| match kind.as_str() {
+            "e" | "E" => Some(DiagnosticSeverity::ERROR),
+            "w" | "W" => Some(DiagnosticSeverity::WARNING),
+            "i" | "I" => Some(DiagnosticSeverity::INFORMATION),
+            "n" | "N" | "h" | "H" => Some(DiagnosticSeverity::HINT),
+            _ => None,
+        });
+        let message = captures
+            .name("message")
+            .map(|message| message.as_str().trim().to_string())
+            .filter(|message| !message.is_empty());
+
+        let Some(path) = captures.name("file") else {
+            if let Some(message) = message {
+                pending_message = Some((severity, message));
+            }
+            continue;
+        };
+        let Some(line) = captures
+            .name("line")
+            .and_then(|line| line.as_str().parse().ok())
+        else {
+            continue;
+        };
+        let column = captures
+            .name("column")
+            .and_then(|column| column.as_str().parse().ok());
+        let pending = pending_message.take();
+        let (severity, message) = match message {
+            Some(message) => (severity, message),
+            None => pending.unwrap_or((severity, String::new())),
+        };
+        errors.push(ParsedError {
+            path: PathBuf::from(path.as_str()),
+            line,
+            column,
+            severity,
+            message,
+        });
+    }
+    errors
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, action: &QuickfixGoTo, window, cx| {
+        vim.quickfix_go_to(action.target, window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &QuickfixOpen, window, cx| {
+        vim.open_quickfix_list(window, cx);
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixDo, window, cx| {
+        vim.quickfix_do(action, window, cx);
+    });
+    Vim::action(editor, cx, |vim, action: &VimGrep, window, cx| {
+        vim.vimgrep(action, window, cx);
+    });
+    Vim::action(editor, cx, |vim, action: &VimMake, window, cx| {
+        vim.make(action, window, cx);
+    });
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &QuickfixFromDiagnostics, window, cx| {
+            vim.quickfix_from_diagnostics(window, cx);
+        },
+    );
+    Vim::action(editor, cx, |vim, _: &QuickfixFromReferences, window, cx| {
+        vim.quickfix_from_references(window, cx);
+    });
+}
+
+impl Vim {
+    fn quickfix_go_to(
+        &mut self,
+        target: QuickfixTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let quickfix_list = workspace.read(cx).quickfix_list().clone();
+        if quickfix_list.read(cx).is_empty() {
+            // Without a quickfix list, `:cnext` and `:cprevious` move between diagnostics instead.
+            match target {
+                QuickfixTarget::Next => {
+                    window.dispatch_action(editor::actions::GoToDiagnostic.boxed_clone(), cx)
+                }
+                QuickfixTarget::Previous => window
+                    .dispatch_action(editor::actions::GoToPreviousDiagnostic.boxed_clone(), cx),
+                _ => {}
+            }
+            return;
+        }
+
+        let entry = quickfix_list.update(cx, |list, cx| {
+            match target {
+                QuickfixTarget::Next => list.select_next(1, cx),
+                QuickfixTarget::Previous => list.select_previous(1, cx),
+                QuickfixTarget::NextFile => list.select_next_file(1, cx),
+                QuickfixTarget::PreviousFile => list.select_previous_file(1, cx),
+                QuickfixTarget::First => list.select(0, cx),
+                QuickfixTarget::Last => list.select(usize::MAX, cx),
+                QuickfixTarget::Number(number) => list.select(number.saturating_sub(1), cx),
+            }
+            .cloned()
+        });
+        if let Some(entry) = entry {
+            workspace.update(cx, |workspace, cx| {
+                open_quickfix_entry(workspace, entry, window, cx).detach_and_log_err(cx);
+            });
+        }
+    }
+
+    fn open_quickfix_list(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let (title, entries) = {
+            let list = workspace.read(cx).quickfix_list().read(cx);
+            (list.title().clone(), list.entries().to_vec())
+        };
+        if entries.is_empty() {
+            return;
+        }
+
+        let project = workspace.read(cx).project().clone();
+        let buffers = project.update(cx, |project, cx| {
+            entries
+                .iter()
+                .map(|entry| project.open_buffer(entry.path.clone(), cx))
+                .collect::<Vec<_>>()
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            let buffers = join_all(buffers).await;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let locations = entries
+                    .iter()
+                    .zip(buffers)
+                    .filter_map(|(entry, buffer)| {
+                        let buffer = buffer.log_err()?;
+                        let snapshot = buffer.read(cx);
+                        let start = snapshot.clip_point(entry.range.start, Bias::Left);
+                        let end = snapshot.clip_point(entry.range.end, Bias::Left);
+                        let range = snapshot.anchor_before(start)..snapshot.anchor_after(end);
+                        Some(Location { buffer, range })
+                    })
+                    .collect::<Vec<_>>();
+                if !locations.is_empty() {
+                    Editor::open_locations_in_multibuffer(
+                        workspace,
+                        locations,
+                        title.to_string(),
+                        false,
+                        MultibufferSelectionMode::First,
+                        window,
+                        cx,
+                    );
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn quickfix_do(&mut self, action: &QuickfixDo, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let Some(command) = command_interceptor(&action.command, cx)
+            .first()
+            .map(|result| result.action.boxed_clone())
+        else {
+            workspace.update(cx, |workspace, cx| {
+                Err::<(), _>(anyhow::anyhow!("Not an editor command: {}", action.command))
+                    .notify_err(workspace, cx);
+            });
+            return;
+        };
+        let quickfix_list = workspace.read(cx).quickfix_list().clone();
+        let indices = {
+            let list = quickfix_list.read(cx);
+            if action.per_file {
+                list.file_entry_indices()
+            } else {
+                (0..list.len()).collect()
+            }
+        };
+
+        cx.spawn_in(window, async move |_, cx| {
+            for ix in indices {
+                let Some(entry) =
+                    quickfix_list.update(cx, |list, cx| list.select(ix, cx).cloned())?
+                else {
+                    break;
+                };
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        open_quickfix_entry(workspace, entry, window, cx)
+                    })?
+                    .await?;
+                // Actions are dispatched through the rendered frame, so the editor that was just
+                // opened needs to be drawn before it can receive the command.
+                next_frame(cx).await?;
+                cx.update(|window, cx| window.dispatch_action(command.boxed_clone(), cx))?;
+                next_frame(cx).await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn vimgrep(&mut self, action: &VimGrep, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let query = PathMatcher::new(&action.files)
+            .map_err(anyhow::Error::from)
+            .and_then(|files_to_include| {
+                SearchQuery::regex(
+                    &action.pattern,
+                    false,
+                    true,
+                    false,
+                    !action.all_matches,
+                    files_to_include,
+                    PathMatcher::default(),
+                    false,
+                    None,
+                )
+            });
+        let Some(query) = workspace.update(cx, |workspace, cx| query.notify_err(workspace, cx))
+        else {
+            return;
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let results = project.update(cx, |project, cx| project.search(query, cx));
+        let title = format!(":vimgrep /{}/", action.pattern);
+        let jump = action.jump;
+        cx.spawn_in(window, async move |_, cx| {
+            let mut entries = Vec::new();
+            while let Ok(result) = results.recv().await {
+                let SearchResult::Buffer { buffer, ranges } = result else {
+                    continue;
+                };
+                cx.update(|_, cx| {
+                    entries.extend(ranges.into_iter().filter_map(|range| {
+                        quickfix_entry_for_location(
+                            &Location {
+                                buffer: buffer.clone(),
+                                range,
+                            },
+                            cx,
+                        )
+                    }));
+                })?;
+            }
+            if entries.is_empty() {
+                anyhow::bail!("No match: {title}");
+            }
+            entries.sort_by(|a, b| {
+                a.path
+                    .cmp(&b.path)
+                    .then_with(|| a.range.start.cmp(&b.range.start))
+            });
+            set_quickfix_list(&workspace, title, entries, jump, cx).await
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn make(&mut self, action: &VimMake, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let settings = VimSettings::get_global(cx);
+        let formats = settings
+            .error_format
+            .iter()
+            .filter_map(|format| ErrorFormat::new(format).log_err())
+            .collect::<Vec<_>>();
+        let mut command = settings.make_program.clone();
+        if !action.args.is_empty() {
+            command.push(' ');
+            command.push_str(&action.args);
+        }
+
+        let project = workspace.read(cx).project().clone();
+        let cwd = project.read(cx).first_project_directory(cx);
+        let mut process = project.read(cx).exec_in_shell(command, cx);
+        process.stdin(Stdio::null());
+        process.stdout(Stdio::piped());
+        process.stderr(Stdio::piped());
+        util::set_pre_exec_to_start_new_session(&mut process);
+
+        let title = format!(":make {}", action.args).trim_end().to_string();
+        let jump = action.jump;
+        let task = cx.spawn_in(window, async move |vim, cx| {
+            let result = async {
+                let output = cx.background_spawn(async move { process.output() }).await?;
+                let errors = cx
+                    .background_spawn(async move {
+                        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+                        text.push_str(&String::from_utf8_lossy(&output.stderr));
+                        parse_errors(&text, &formats)
+                    })
+                    .await;
+                let entries = project.read_with(cx, |project, cx| {
+                    errors
+                        .into_iter()
+                        .filter_map(|error| {
+                            let abs_path = cwd
+                                .as_ref()
+                                .map_or_else(|| error.path.clone(), |cwd| cwd.join(&error.path));
+                            let path = project
+                                .project_path_for_absolute_path(&abs_path, cx)
+                                .or_else(|| project.find_project_path(&error.path, cx))?;
+                            let point = Point::new(
+                                error.line.saturating_sub(1),
+                                error.column.unwrap_or(1).saturating_sub(1),
+                            );
+                            Some(QuickfixEntry {
+                                path,
+                                range: point..point,
+                                severity: error.severity,
+                                message: error.message.into(),
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                set_quickfix_list(&workspace, title, entries, jump, cx).await
+            }
+            .await;
+            result.notify_async_err(cx);
+            vim.update_in(cx, |vim, window, cx| {
+                vim.cancel_running_command(window, cx);
+            })
+            .log_err();
+        });
+        self.running_command.replace(task);
+    }
+
+    fn quickfix_from_diagnostics(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let paths = project
+            .read(cx)
+            .diagnostic_summaries(false, cx)
+            .map(|(path, _, _)| path)
+            .collect::<BTreeSet<_>>();
+        let buffers = project.update(cx, |project, cx| {
+            paths
+                .iter()
+                .map(|path| project.open_buffer(path.clone(), cx))
+                .collect::<Vec<_>>()
+        });
+
+        cx.spawn_in(window, async move |_, cx| {
+            let mut entries = Vec::new();
+            for (path, buffer) in paths.into_iter().zip(join_all(buffers).await) {
+                let Some(buffer) = buffer.log_err() else {
+                    continue;
+                };
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+                entries.extend(
+                    snapshot
+                        .diagnostics_in_range::<_, Point>(
+                            Point::zero()..snapshot.max_point(),
+                            false,
+                        )
+                        .filter(|entry| {
+                            entry.diagnostic.is_primary
+                                && entry.diagnostic.severity <= DiagnosticSeverity::WARNING
+                        })
+                        .map(|entry| QuickfixEntry {
+                            path: path.clone(),
+                            range: entry.range,
+                            severity: Some(entry.diagnostic.severity),
+                            message: entry.diagnostic.message.into(),
+                        }),
+                );
+            }
+            set_quickfix_list(&workspace, "Diagnostics".to_string(), entries, false, cx).await
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn quickfix_from_references(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let Some((buffer, position)) = self
+            .update_editor(window, cx, |_, editor, _, cx| {
+                let head = editor.selections.newest::<usize>(cx).head();
+                editor.buffer().read(cx).text_anchor_for_position(head, cx)
+            })
+            .flatten()
+        else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let references =
+            project.update(cx, |project, cx| project.references(&buffer, position, cx));
+
+        cx.spawn_in(window, async move |_, cx| {
+            let locations = references.await?;
+            let entries = cx.update(|_, cx| {
+                locations
+                    .iter()
+                    .filter_map(|location| quickfix_entry_for_location(location, cx))
+                    .collect::<Vec<_>>()
+            })?;
+            set_quickfix_list(&workspace, "References".to_string(), entries, false, cx).await
+        })
+        .detach_and_notify_err(window, cx);
+    }
+}
+
+fn quickfix_entry_for_location(location: &Location, cx: &App) -> Option<QuickfixEntry> {
+    let buffer = location.buffer.read(cx);
+    let path = ProjectPath::from_file(buffer.file()?.as_ref(), cx);
+    let range = location.range.start.to_point(buffer)..location.range.end.to_point(buffer);
+    let row = range.start.row;
+    let line = buffer
+        .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+        .collect::<String>();
+    Some(QuickfixEntry {
+        path,
+        range,
+        severity: None,
+        message: line.trim().to_string().into(),
+    })
+}
+
+/// Replaces the workspace's quickfix list, optionally jumping to its first entry.
+async fn set_quickfix_list(
+    workspace: &Entity<Workspace>,
+    title: String,
+    entries: Vec<QuickfixEntry>,
+    jump: bool,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let open_first_entry = workspace.update_in(cx, |workspace, window, cx| {
+        let first_entry = entries.first().cloned();
+        workspace
+            .quickfix_list()
+            .update(cx, |list, cx| list.set_entries(title, entries, cx));
+        first_entry
+            .filter(|_| jump)
+            .map(|entry| open_quickfix_entry(workspace, entry, window, cx))
+    })?;
+    if let Some(open_first_entry) = open_first_entry {
+        open_first_entry.await?;
+    }
+    Ok(())
+}
+
+fn open_quickfix_entry(
+    workspace: &mut Workspace,
+    entry: QuickfixEntry,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<Entity<Editor>>> {
+    let open = workspace.open_path(entry.path.clone(), None, true, window, cx);
+    cx.spawn_in(window, async move |_, cx| {
+        let editor = open
+            .await?
+            .downcast::<Editor>()
+            .context("quickfix entry is not a text file")?;
+        editor.update_in(cx, |editor, window, cx| {
+            editor.go_to_singleton_buffer_point(entry.range.start, window, cx);
+        })?;
+        Ok(editor)
+    })
+}
+
+async fn next_frame(cx: &mut AsyncWindowContext) -> Result<()> {
+    let (tx, rx) = oneshot::channel();
+    cx.update(|window, _| {
+        window.refresh();
+        window.on_next_frame(move |_, _| {
+            tx.send(()).ok();
+        });
+    })?;
+    rx.await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ErrorFormat, ParsedError, VimGrep, parse_errors};
+    use crate::{state::Mode, test::VimTestContext};
+    use editor::Editor;
+    use indoc::indoc;
+    use language::{DiagnosticSeverity, Point};
+    use std::path::PathBuf;
+    use util::path;
+
+    #[test]
+    fn test_parse_vimgrep() {
+        assert_eq!(
+            VimGrep::parse("/foo(bar)/gj src/**/*.rs *.md"),
+            Some(VimGrep {
+                pattern: "foo\\(bar\\)".into(),
+                files: vec!["src/**/*.rs".into(), "*.md".into()],
+                all_matches: true,
+                jump: false,
+            })
+        );
+        assert_eq!(
+            VimGrep::parse("needle"),
+            Some(VimGrep {
+                pattern: "needle".into(),
+                files: Vec::new(),
+                all_matches: false,
+                jump: true,
+            })
+        );
+        assert_eq!(VimGrep::parse("//"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let formats = [
+            "%f:%l:%c: %t%.%#: %m",
+            "%f:%l:%c: %m",
+            "%f(%l,%c): %t%.%#: %m",
+            "%t%.%#: %m",
+            "%.%#--> %f:%l:%c",
+        ]
+        .into_iter()
+        .map(|format| ErrorFormat::new(format).unwrap())
+        .collect::<Vec<_>>();
+
+        let output = indoc! {"
+            src/main.c:3:5: error: expected ';' before '}' token
+            src/util.c:10:1: unused label
+            src/app.ts(7,12): error TS2322: Type 'string' is not assignable to type 'number'.
+            warning: unused variable: `x`
+              --> src/lib.rs:4:9
+            some unrelated output
+        "};
+        assert_eq!(
+            parse_errors(output, &formats),
+            vec![
+                ParsedError {
+                    path: PathBuf::from("src/main.c"),
+                    line: 3,
+                    column: Some(5),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    message: "expected ';' before '}' token".into(),
+                },
+                ParsedError {
+                    path: PathBuf::from("src/util.c"),
+                    line: 10,
+                    column: Some(1),
+                    severity: None,
+                    message: "unused label".into(),
+                },
+                ParsedError {
+                    path: PathBuf::from("src/app.ts"),
+                    line: 7,
+                    column: Some(12),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    message: "Type 'string' is not assignable to type 'number'.".into(),
+                },
+                ParsedError {
+                    path: PathBuf::from("src/lib.rs"),
+                    line: 4,
+                    column: Some(9),
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    message: "unused variable: `x`".into(),
+                },
+            ]
+        );
+        assert!(ErrorFormat::new("%f:%z").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_vimgrep_and_cnext(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(
+                path!("/root/dir/a.txt"),
+                b"one\nneedle\nneedle two\n".to_vec(),
+            )
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.txt"), b"three needle\n".to_vec())
+            .await;
+        cx.run_until_parked();
+        cx.set_state("ˇhello", Mode::Normal);
+
+        cx.simulate_keystrokes(": v i m g r e p space / n e e d l e / space * . t x t enter");
+        cx.run_until_parked();
+
+        let quickfix_list = cx.workspace(|workspace, _, _| workspace.quickfix_list().clone());
+        cx.workspace(|_, _, cx| {
+            let list = quickfix_list.read(cx);
+            assert_eq!(list.len(), 3);
+            assert_eq!(list.selected_index(), Some(0));
+        });
+        let assert_active_position = |cx: &mut VimTestContext, file: &str, point: Point| {
+            cx.workspace(|workspace, _, cx| {
+                let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+                let editor = editor.read(cx);
+                let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+                let path = buffer.read(cx).file().unwrap().path().clone();
+                assert_eq!(path.file_name().unwrap(), file);
+                assert_eq!(editor.selections.newest::<Point>(cx).head(), point);
+            });
+        };
+        assert_active_position(&mut cx, "a.txt", Point::new(1, 0));
+
+        cx.simulate_keystrokes(": c n enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "a.txt", Point::new(2, 0));
+
+        cx.simulate_keystrokes(": c n enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "b.txt", Point::new(0, 6));
+
+        cx.simulate_keystrokes(": c c space 2 enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "a.txt", Point::new(2, 0));
+
+        cx.simulate_keystrokes(": c f i r s t enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "a.txt", Point::new(1, 0));
+    }
+}
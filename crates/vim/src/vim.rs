@@ -13,6 +13,7 @@ mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod quickfix;
 mod replace;
 mod rewrap;
 mod state;
@@ -723,6 +724,7 @@ impl Vim {
             object::register(editor, cx);
             visual::register(editor, cx);
             change_list::register(editor, cx);
+            quickfix::register(editor, cx);
             digraph::register(editor, cx);
 
             cx.defer_in(window, |vim, window, cx| {
@@ -1740,6 +1742,8 @@ struct VimSettings {
     pub custom_digraphs: HashMap<String, Arc<str>>,
     pub highlight_on_yank_duration: u64,
     pub cursor_shape: CursorShapeSettings,
+    pub make_program: String,
+    pub error_format: Vec<String>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1751,6 +1755,8 @@ struct VimSettingsContent {
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
     pub highlight_on_yank_duration: Option<u64>,
     pub cursor_shape: Option<CursorShapeSettings>,
+    pub make_program: Option<String>,
+    pub error_format: Option<Vec<String>>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1807,6 +1813,8 @@ impl Settings for VimSettings {
                 .highlight_on_yank_duration
                 .ok_or_else(Self::missing_default)?,
             cursor_shape: settings.cursor_shape.ok_or_else(Self::missing_default)?,
+            make_program: settings.make_program.ok_or_else(Self::missing_default)?,
+            error_format: settings.error_format.ok_or_else(Self::missing_default)?,
         })
     }
 
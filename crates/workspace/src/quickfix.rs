@@ -0,0 +1,212 @@
+use std::ops::Range;
+
+use collections::HashSet;
+use gpui::{Context, EventEmitter, SharedString};
+use language::{DiagnosticSeverity, Point};
+use project::ProjectPath;
+
+/// A location in the quickfix list, such as a search match, a compiler error or a reference.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuickfixEntry {
+    pub path: ProjectPath,
+    pub range: Range<Point>,
+    pub severity: Option<DiagnosticSeverity>,
+    pub message: SharedString,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuickfixEvent {
+    /// The list was replaced with new entries.
+    Updated,
+    /// A different entry became the current one.
+    Selected(usize),
+}
+
+/// The workspace-wide list of locations that commands like Vim's `:cnext`, `:cdo` and `:copen`
+/// operate on.
+///
+/// The list is populated in one go (e.g. by `:vimgrep`, `:make` or from the project's
+/// diagnostics) and keeps track of the entry that was visited last.
+#[derive(Default)]
+pub struct QuickfixList {
+    title: SharedString,
+    entries: Vec<QuickfixEntry>,
+    selected: usize,
+}
+
+impl EventEmitter<QuickfixEvent> for QuickfixList {}
+
+impl QuickfixList {
+    pub fn set_entries(
+        &mut self,
+        title: impl Into<SharedString>,
+        entries: Vec<QuickfixEntry>,
+        cx: &mut Context<Self>,
+    ) {
+        self.title = title.into();
+        self.entries = entries;
+        self.selected = 0;
+        cx.emit(QuickfixEvent::Updated);
+        cx.notify();
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.set_entries(SharedString::default(), Vec::new(), cx);
+    }
+
+    pub fn title(&self) -> &SharedString {
+        &self.title
+    }
+
+    pub fn entries(&self) -> &[QuickfixEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        (!self.entries.is_empty()).then_some(self.selected)
+    }
+
+    pub fn selected_entry(&self) -> Option<&QuickfixEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Makes the entry at the given index current, clamping it to the bounds of the list.
+    pub fn select(&mut self, ix: usize, cx: &mut Context<Self>) -> Option<&QuickfixEntry> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let ix = ix.min(self.entries.len() - 1);
+        if ix != self.selected {
+            self.selected = ix;
+            cx.emit(QuickfixEvent::Selected(ix));
+            cx.notify();
+        }
+        self.entries.get(ix)
+    }
+
+    /// Moves `count` entries forward, returning `None` when already at the last entry.
+    pub fn select_next(&mut self, count: usize, cx: &mut Context<Self>) -> Option<&QuickfixEntry> {
+        if self.selected + 1 >= self.entries.len() {
+            return None;
+        }
+        self.select(self.selected.saturating_add(count), cx)
+    }
+
+    /// Moves `count` entries backward, returning `None` when already at the first entry.
+    pub fn select_previous(
+        &mut self,
+        count: usize,
+        cx: &mut Context<Self>,
+    ) -> Option<&QuickfixEntry> {
+        if self.selected == 0 || self.entries.is_empty() {
+            return None;
+        }
+        self.select(self.selected.saturating_sub(count), cx)
+    }
+
+    /// Moves to the first entry of the `count`th file after the current one.
+    pub fn select_next_file(
+        &mut self,
+        count: usize,
+        cx: &mut Context<Self>,
+    ) -> Option<&QuickfixEntry> {
+        let mut ix = self.selected;
+        for _ in 0..count {
+            let path = &self.entries.get(ix)?.path;
+            ix = ix
+                + self.entries[ix..]
+                    .iter()
+                    .position(|entry| &entry.path != path)?;
+        }
+        self.select(ix, cx)
+    }
+
+    /// Moves to the last entry of the `count`th file before the current one.
+    pub fn select_previous_file(
+        &mut self,
+        count: usize,
+        cx: &mut Context<Self>,
+    ) -> Option<&QuickfixEntry> {
+        let mut ix = self.selected;
+        for _ in 0..count {
+            let path = &self.entries.get(ix)?.path;
+            ix = self.entries[..ix]
+                .iter()
+                .rposition(|entry| &entry.path != path)?;
+        }
+        self.select(ix, cx)
+    }
+
+    /// Returns the index of the first entry for each file in the list, in the order the files
+    /// first appear. This is what `:cfdo` iterates over.
+    pub fn file_entry_indices(&self) -> Vec<usize> {
+        let mut seen = HashSet::default();
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| seen.insert(&entry.path))
+            .map(|(ix, _)| ix)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use project::WorktreeId;
+    use std::{path::Path, sync::Arc};
+
+    fn entry(path: &str, row: u32) -> QuickfixEntry {
+        QuickfixEntry {
+            path: ProjectPath {
+                worktree_id: WorktreeId::from_usize(1),
+                path: Arc::from(Path::new(path)),
+            },
+            range: Point::new(row, 0)..Point::new(row, 0),
+            severity: None,
+            message: SharedString::default(),
+        }
+    }
+
+    #[gpui::test]
+    fn test_quickfix_navigation(cx: &mut TestAppContext) {
+        let list = cx.new(|_| QuickfixList::default());
+        list.update(cx, |list, cx| {
+            assert_eq!(list.select_next(1, cx), None);
+            list.set_entries(
+                "test",
+                vec![
+                    entry("a.rs", 1),
+                    entry("a.rs", 5),
+                    entry("b.rs", 2),
+                    entry("c.rs", 3),
+                    entry("a.rs", 9),
+                ],
+                cx,
+            );
+            assert_eq!(list.selected_index(), Some(0));
+            assert_eq!(list.select_next(1, cx), Some(&entry("a.rs", 5)));
+            assert_eq!(list.select_next(10, cx), Some(&entry("a.rs", 9)));
+            assert_eq!(list.select_next(1, cx), None);
+            assert_eq!(list.select_previous(2, cx), Some(&entry("b.rs", 2)));
+            assert_eq!(list.select_previous_file(1, cx), Some(&entry("a.rs", 5)));
+            assert_eq!(list.select_previous_file(1, cx), None);
+            assert_eq!(list.select_next_file(2, cx), Some(&entry("c.rs", 3)));
+            assert_eq!(list.select(0, cx), Some(&entry("a.rs", 1)));
+            assert_eq!(list.file_entry_indices(), vec![0, 2, 3]);
+
+            list.clear(cx);
+            assert_eq!(list.selected_index(), None);
+            assert_eq!(list.select(3, cx), None);
+        });
+    }
+}
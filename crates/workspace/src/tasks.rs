@@ -1,11 +1,19 @@
-use std::process::ExitStatus;
+use std::{cell::RefCell, process::ExitStatus, rc::Rc, sync::Arc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{LocalBoxFuture, Shared, try_join_all},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
+use itertools::Itertools as _;
 use language::Buffer;
-use project::TaskSourceKind;
+use project::{CompoundTaskStatus, Inventory, TaskSourceKind};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{
+    DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TaskTemplate,
+};
 use ui::Window;
 
 use crate::Workspace;
@@ -65,12 +73,24 @@ impl Workspace {
                     project.task_store().read(cx).task_inventory().cloned()
                 {
                     task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task.clone());
                     })
                 }
             });
         }
 
+        if !resolved_task.original_task().watch.is_empty() {
+            let task_store = self.project.read(cx).task_store().clone();
+            let watch = task_store.update(cx, |task_store, cx| {
+                task_store.watch_task(task_source_kind.clone(), resolved_task.clone(), cx)
+            });
+            watch.detach_and_log_err(cx);
+        }
+
+        if !resolved_task.original_task().depends_on.is_empty() {
+            self.schedule_task_with_dependencies(task_source_kind, resolved_task, window, cx);
+            return;
+        }
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
             cx.background_spawn(async move {
@@ -90,6 +110,74 @@ impl Workspace {
         }
     }
 
+    /// Runs the tasks a task depends on, in the order its template asks for, and then the task itself.
+    /// The first task that fails stops the whole run; the outcome is stored in the task inventory.
+    fn schedule_task_with_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(inventory) = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            return;
+        };
+        let task_id = resolved_task.id.clone();
+        inventory.update(cx, |inventory, _| {
+            inventory.set_compound_task_status(task_id.clone(), CompoundTaskStatus::Running)
+        });
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let label = resolved_task.resolved_label.clone();
+            let chain = vec![resolved_task.original_task().label.clone()];
+            let result = async {
+                spawn_dependencies(
+                    workspace.clone(),
+                    inventory.clone(),
+                    task_source_kind,
+                    &resolved_task,
+                    chain,
+                    DependencyRuns::default(),
+                    cx,
+                )
+                .await?;
+                if resolved_task.is_compound() {
+                    return Ok(());
+                }
+                let status = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.spawn_in_terminal(resolved_task.resolved.clone(), window, cx)
+                    })?
+                    .await
+                    .with_context(|| format!("task {label:?} was cancelled"))??;
+                anyhow::ensure!(
+                    status.success(),
+                    "task {label:?} failed with exit code {:?}",
+                    status.code()
+                );
+                anyhow::Ok(())
+            }
+            .await;
+
+            let status = match &result {
+                Ok(()) => CompoundTaskStatus::Succeeded,
+                Err(e) => CompoundTaskStatus::Failed(format!("{e:#}").into()),
+            };
+            inventory.update(cx, |inventory, _| {
+                inventory.set_compound_task_status(task_id, status)
+            })?;
+            result
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -116,3 +204,430 @@ impl Workspace {
         }
     }
 }
+
+/// The dependencies spawned during a single run of a task, so that a task that several others
+/// depend on is only spawned once.
+type DependencyRuns =
+    Rc<RefCell<HashMap<TaskId, Shared<LocalBoxFuture<'static, Result<(), Arc<anyhow::Error>>>>>>>;
+
+/// Resolves the tasks the given task depends on and spawns them, waiting for each to be ready.
+/// Dependencies of dependencies are spawned first; `chain` holds the labels of the tasks
+/// being resolved, to detect cycles.
+fn spawn_dependencies(
+    workspace: WeakEntity<Workspace>,
+    inventory: Entity<Inventory>,
+    task_source_kind: TaskSourceKind,
+    task: &ResolvedTask,
+    chain: Vec<String>,
+    runs: DependencyRuns,
+    cx: &AsyncWindowContext,
+) -> LocalBoxFuture<'static, Result<()>> {
+    let template = task.original_task().clone();
+    let task_context = task.task_context().clone();
+    let cx = cx.clone();
+    async move {
+        if template.depends_on.is_empty() {
+            return Ok(());
+        }
+        let worktree_id = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
+        let available_tasks = inventory
+            .read_with(&cx, |inventory, cx| {
+                inventory.list_tasks(None, None, worktree_id, cx)
+            })?
+            .await;
+
+        let mut dependencies = Vec::with_capacity(template.depends_on.len());
+        for label in &template.depends_on {
+            if chain.contains(label) {
+                anyhow::bail!(
+                    "task dependency cycle: {} -> {label}",
+                    chain.iter().join(" -> ")
+                );
+            }
+            let (source_kind, dependency) = available_tasks
+                .iter()
+                .find(|(_, template)| &template.label == label)
+                .with_context(|| {
+                    format!(
+                        "task {label:?} that {:?} depends on was not found",
+                        template.label
+                    )
+                })?;
+            let resolved = dependency
+                .resolve_task(&source_kind.to_id_base(), &task_context)
+                .with_context(|| format!("failed to resolve task {label:?}"))?;
+            dependencies.push((source_kind.clone(), resolved));
+        }
+
+        let dependency_runs = dependencies.into_iter().map(|(source_kind, dependency)| {
+            let workspace = workspace.clone();
+            let inventory = inventory.clone();
+            let runs = runs.clone();
+            let mut chain = chain.clone();
+            chain.push(dependency.original_task().label.clone());
+            let mut cx = cx.clone();
+            async move {
+                let run = runs
+                    .borrow_mut()
+                    .entry(dependency.id.clone())
+                    .or_insert_with(|| {
+                        let runs = runs.clone();
+                        async move {
+                            spawn_dependencies(
+                                workspace.clone(),
+                                inventory,
+                                source_kind,
+                                &dependency,
+                                chain,
+                                runs,
+                                &cx,
+                            )
+                            .await?;
+                            if dependency.is_compound() {
+                                return Ok(());
+                            }
+                            workspace
+                                .update_in(&mut cx, |workspace, window, cx| {
+                                    match workspace.terminal_provider.as_ref() {
+                                        Some(terminal_provider) => terminal_provider
+                                            .spawn_until_ready(
+                                                dependency.resolved.clone(),
+                                                window,
+                                                cx,
+                                            ),
+                                        None => Task::ready(Err(anyhow!(
+                                            "no terminal to spawn tasks in"
+                                        ))),
+                                    }
+                                })?
+                                .await
+                        }
+                        .map(|result| result.map_err(Arc::new))
+                        .boxed_local()
+                        .shared()
+                    })
+                    .clone();
+                run.await.map_err(|error| anyhow!("{error:#}"))
+            }
+        });
+        match template.depends_order {
+            DependsOrder::Parallel => {
+                try_join_all(dependency_runs).await?;
+            }
+            DependsOrder::Sequence => {
+                for run in dependency_runs {
+                    run.await?;
+                }
+            }
+        }
+        Ok(())
+    }
+    .boxed_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TerminalProvider;
+    use futures::channel::oneshot;
+    use gpui::{App, TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_dependencies_in_sequence(cx: &mut TestAppContext) {
+        let (workspace, terminals, cx) = init_test(
+            json!([
+                { "label": "a", "command": "a" },
+                { "label": "b", "command": "b" },
+                { "label": "main", "command": "main", "depends_on": ["a", "b"], "depends_order": "sequence" },
+            ]),
+            cx,
+        )
+        .await;
+
+        let task_id = schedule(&workspace, "main", cx).await;
+        assert_eq!(terminals.spawned(), ["a"]);
+        assert_eq!(
+            status(&workspace, &task_id, cx),
+            Some(CompoundTaskStatus::Running)
+        );
+
+        // Each dependency only starts once the previous one is ready.
+        terminals.finish("a", true);
+        cx.run_until_parked();
+        assert_eq!(terminals.spawned(), ["a", "b"]);
+
+        terminals.finish("b", true);
+        cx.run_until_parked();
+        assert_eq!(terminals.spawned(), ["a", "b", "main"]);
+
+        terminals.finish("main", true);
+        cx.run_until_parked();
+        assert_eq!(
+            status(&workspace, &task_id, cx),
+            Some(CompoundTaskStatus::Succeeded)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_dependencies_in_parallel(cx: &mut TestAppContext) {
+        let (workspace, terminals, cx) = init_test(
+            json!([
+                { "label": "a", "command": "a" },
+                { "label": "b", "command": "b" },
+                { "label": "main", "command": "main", "depends_on": ["a", "b"] },
+            ]),
+            cx,
+        )
+        .await;
+
+        schedule(&workspace, "main", cx).await;
+        assert_eq!(terminals.spawned(), ["a", "b"]);
+
+        // The task waits for all of its dependencies to be ready.
+        terminals.finish("b", true);
+        cx.run_until_parked();
+        assert_eq!(terminals.spawned(), ["a", "b"]);
+
+        terminals.finish("a", true);
+        cx.run_until_parked();
+        assert_eq!(terminals.spawned(), ["a", "b", "main"]);
+    }
+
+    #[gpui::test]
+    async fn test_failed_dependency(cx: &mut TestAppContext) {
+        let (workspace, terminals, cx) = init_test(
+            json!([
+                { "label": "a", "command": "a" },
+                { "label": "b", "command": "b" },
+                { "label": "main", "command": "main", "depends_on": ["a", "b"], "depends_order": "sequence" },
+            ]),
+            cx,
+        )
+        .await;
+
+        let task_id = schedule(&workspace, "main", cx).await;
+        terminals.finish("a", false);
+        cx.run_until_parked();
+
+        // Neither the remaining dependencies nor the task itself are spawned.
+        assert_eq!(terminals.spawned(), ["a"]);
+        let Some(CompoundTaskStatus::Failed(message)) = status(&workspace, &task_id, cx) else {
+            panic!("expected the task to fail");
+        };
+        assert!(message.contains("task \"a\" failed"), "{message}");
+    }
+
+    #[gpui::test]
+    async fn test_dependency_cycle(cx: &mut TestAppContext) {
+        let (workspace, terminals, cx) = init_test(
+            json!([
+                { "label": "a", "command": "a", "depends_on": ["b"] },
+                { "label": "b", "command": "b", "depends_on": ["c"] },
+                { "label": "c", "command": "c", "depends_on": ["a"] },
+            ]),
+            cx,
+        )
+        .await;
+
+        let task_id = schedule(&workspace, "a", cx).await;
+        assert!(terminals.spawned().is_empty());
+        let Some(CompoundTaskStatus::Failed(message)) = status(&workspace, &task_id, cx) else {
+            panic!("expected the task to fail");
+        };
+        assert!(
+            message.contains("task dependency cycle: a -> b -> c -> a"),
+            "{message}"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_shared_dependency_is_spawned_once(cx: &mut TestAppContext) {
+        let (workspace, terminals, cx) = init_test(
+            json!([
+                { "label": "shared", "command": "shared" },
+                { "label": "left", "command": "left", "depends_on": ["shared"] },
+                { "label": "right", "command": "right", "depends_on": ["shared"] },
+                { "label": "main", "command": "main", "depends_on": ["left", "right"] },
+            ]),
+            cx,
+        )
+        .await;
+
+        let task_id = schedule(&workspace, "main", cx).await;
+        assert_eq!(terminals.spawned(), ["shared"]);
+
+        terminals.finish("shared", true);
+        cx.run_until_parked();
+        assert_eq!(terminals.spawned(), ["shared", "left", "right"]);
+
+        terminals.finish("left", true);
+        terminals.finish("right", true);
+        cx.run_until_parked();
+        terminals.finish("main", true);
+        cx.run_until_parked();
+        assert_eq!(terminals.spawned(), ["shared", "left", "right", "main"]);
+        assert_eq!(
+            status(&workspace, &task_id, cx),
+            Some(CompoundTaskStatus::Succeeded)
+        );
+    }
+
+    /// Records the tasks it spawns, which run until the test finishes them.
+    #[derive(Clone, Default)]
+    struct FakeTerminalProvider {
+        spawned: Rc<RefCell<Vec<String>>>,
+        running: Rc<RefCell<HashMap<String, oneshot::Sender<bool>>>>,
+    }
+
+    impl FakeTerminalProvider {
+        fn spawned(&self) -> Vec<String> {
+            self.spawned.borrow().clone()
+        }
+
+        fn finish(&self, label: &str, success: bool) {
+            let running = self.running.borrow_mut().remove(label);
+            running
+                .unwrap_or_else(|| panic!("task {label:?} is not running"))
+                .send(success)
+                .ok();
+        }
+
+        fn start(&self, task: &SpawnInTerminal) -> oneshot::Receiver<bool> {
+            let (tx, rx) = oneshot::channel();
+            self.spawned.borrow_mut().push(task.label.clone());
+            self.running.borrow_mut().insert(task.label.clone(), tx);
+            rx
+        }
+    }
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            let finished = self.start(&task);
+            cx.background_spawn(async move {
+                finished.await.ok()?;
+                Some(Ok(ExitStatus::default()))
+            })
+        }
+
+        fn spawn_until_ready(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Result<()>> {
+            let finished = self.start(&task);
+            cx.background_spawn(async move {
+                let label = task.label;
+                anyhow::ensure!(finished.await?, "task {label:?} failed");
+                Ok(())
+            })
+        }
+    }
+
+    async fn init_test(
+        tasks: serde_json::Value,
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Workspace>,
+        FakeTerminalProvider,
+        &mut VisualTestContext,
+    ) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            crate::init_settings(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({ ".zed": { "tasks.json": tasks.to_string() } }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let terminals = FakeTerminalProvider::default();
+        workspace.update(cx, |workspace, _| {
+            workspace.set_terminal_provider(terminals.clone())
+        });
+        cx.run_until_parked();
+        (workspace, terminals, cx)
+    }
+
+    async fn schedule(
+        workspace: &Entity<Workspace>,
+        label: &str,
+        cx: &mut VisualTestContext,
+    ) -> TaskId {
+        let (inventory, worktree_id) = workspace.read_with(cx, |workspace, cx| {
+            let project = workspace.project().read(cx);
+            (
+                project
+                    .task_store()
+                    .read(cx)
+                    .task_inventory()
+                    .cloned()
+                    .unwrap(),
+                project.worktrees(cx).next().unwrap().read(cx).id(),
+            )
+        });
+        let (source_kind, template) = inventory
+            .update(cx, |inventory, cx| {
+                inventory.list_tasks(None, None, Some(worktree_id), cx)
+            })
+            .await
+            .into_iter()
+            .find(|(_, template)| template.label == label)
+            .unwrap();
+        let task_id = template
+            .resolve_task(&source_kind.to_id_base(), &TaskContext::default())
+            .unwrap()
+            .id;
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.schedule_task(
+                source_kind,
+                &template,
+                &TaskContext::default(),
+                false,
+                window,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        task_id
+    }
+
+    fn status(
+        workspace: &Entity<Workspace>,
+        task_id: &TaskId,
+        cx: &mut VisualTestContext,
+    ) -> Option<CompoundTaskStatus> {
+        workspace.read_with(cx, |workspace, cx| {
+            let inventory = workspace
+                .project()
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()?;
+            inventory.read(cx).compound_task_status(task_id).cloned()
+        })
+    }
+}
@@ -6,6 +6,7 @@ pub mod notifications;
 pub mod pane;
 pub mod pane_group;
 mod persistence;
+pub mod quickfix;
 pub mod searchable;
 pub mod shared_screen;
 mod status_bar;
@@ -70,6 +71,7 @@ use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
     debugger::{breakpoint_store::BreakpointStoreEvent, session::ThreadStatus},
 };
+use quickfix::QuickfixList;
 use remote::{SshClientDelegate, SshConnectionOptions, ssh_session::ConnectionIdentifier};
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -137,6 +139,15 @@ pub trait TerminalProvider {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Option<Result<ExitStatus>>>;
+
+    /// Spawns a task that others depend on, resolving once its `ready_pattern` shows up in the
+    /// output, or once it exits successfully if it has none.
+    fn spawn_until_ready(
+        &self,
+        task: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<()>>;
 }
 
 pub trait DebuggerProvider {
@@ -942,6 +953,7 @@ pub struct Workspace {
     status_bar: Entity<StatusBar>,
     modal_layer: Entity<ModalLayer>,
     toast_layer: Entity<ToastLayer>,
+    quickfix_list: Entity<QuickfixList>,
     titlebar_item: Option<AnyView>,
     notifications: Notifications,
     suppressed_notifications: HashSet<NotificationId>,
@@ -1082,6 +1094,20 @@ impl Workspace {
                     this.handle_agent_location_changed(window, cx)
                 }
 
+                project::Event::WatchedTaskChanged(task_source_kind, resolved_task) => {
+                    let mut resolved_task = resolved_task.clone();
+                    // Cancel the run in progress and reuse its terminal.
+                    resolved_task.resolved.allow_concurrent_runs = true;
+                    resolved_task.resolved.use_new_terminal = false;
+                    this.schedule_resolved_task(
+                        task_source_kind.clone(),
+                        resolved_task,
+                        true,
+                        window,
+                        cx,
+                    );
+                }
+
                 _ => {}
             }
             cx.notify()
@@ -1166,6 +1192,7 @@ impl Workspace {
         cx.emit(Event::WorkspaceCreated(weak_handle.clone()));
         let modal_layer = cx.new(|_| ModalLayer::new());
         let toast_layer = cx.new(|_| ToastLayer::new());
+        let quickfix_list = cx.new(|_| QuickfixList::default());
         cx.subscribe(
             &modal_layer,
             |_, _, _: &modal_layer::ModalOpenedEvent, cx| {
@@ -1268,6 +1295,7 @@ impl Workspace {
             status_bar,
             modal_layer,
             toast_layer,
+            quickfix_list,
             titlebar_item: None,
             notifications: Notifications::default(),
             suppressed_notifications: HashSet::default(),
@@ -1573,6 +1601,10 @@ impl Workspace {
         &self.project
     }
 
+    pub fn quickfix_list(&self) -> &Entity<QuickfixList> {
+        &self.quickfix_list
+    }
+
     pub fn recently_activated_items(&self, cx: &App) -> HashMap<EntityId, usize> {
         let mut history: HashMap<EntityId, usize> = HashMap::default();
 
@@ -520,6 +520,7 @@ pub fn main() {
         web_search::init(cx);
         web_search_providers::init(app_state.client.clone(), cx);
         snippet_provider::init(cx);
+        extension_edit_prediction::init(cx);
         inline_completion_registry::init(
             app_state.client.clone(),
             app_state.user_store.clone(),
@@ -564,6 +565,7 @@ pub fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        undo_tree_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -58,6 +58,7 @@ use std::{borrow::Cow, path::Path, sync::Arc};
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use theme::{ActiveTheme, ThemeSettings};
 use ui::{PopoverMenuHandle, prelude::*};
+use undo_tree_panel::UndoTreePanel;
 use util::markdown::MarkdownString;
 use util::{ResultExt, asset_str};
 use uuid::Uuid;
@@ -488,6 +489,7 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let undo_tree_panel = UndoTreePanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         let (
@@ -498,6 +500,7 @@ fn initialize_panels(
             channels_panel,
             chat_panel,
             notification_panel,
+            undo_tree_panel,
             debug_panel,
         ) = futures::try_join!(
             project_panel,
@@ -507,6 +510,7 @@ fn initialize_panels(
             channels_panel,
             chat_panel,
             notification_panel,
+            undo_tree_panel,
             debug_panel,
         )?;
 
@@ -518,6 +522,7 @@ fn initialize_panels(
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
+            workspace.add_panel(undo_tree_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
         })?;
 
@@ -4439,6 +4444,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            undo_tree_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
@@ -2,6 +2,7 @@ use client::{Client, UserStore};
 use collections::HashMap;
 use copilot::{Copilot, CopilotCompletionProvider};
 use editor::Editor;
+use extension_edit_prediction::ExtensionEditPredictionRegistry;
 use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, WeakEntity};
 use language::language_settings::{EditPredictionProvider, all_language_settings};
 use settings::SettingsStore;
@@ -59,6 +60,10 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
     cx.on_action(clear_zeta_edit_history);
 
     let mut provider = all_language_settings(None, cx).edit_predictions.provider;
+    let mut extension_provider = all_language_settings(None, cx)
+        .edit_predictions
+        .extension_provider
+        .clone();
     cx.spawn({
         let user_store = user_store.clone();
         let editors = editors.clone();
@@ -88,7 +93,27 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
         let user_store = user_store.clone();
         move |cx| {
             let new_provider = all_language_settings(None, cx).edit_predictions.provider;
+            let new_extension_provider = all_language_settings(None, cx)
+                .edit_predictions
+                .extension_provider
+                .clone();
 
+            if new_provider == EditPredictionProvider::Extension
+                && new_provider == provider
+                && new_extension_provider != extension_provider
+            {
+                extension_provider = new_extension_provider;
+                assign_edit_prediction_providers(
+                    &editors,
+                    provider,
+                    &client,
+                    user_store.clone(),
+                    cx,
+                );
+                return;
+            }
+
+            extension_provider = new_extension_provider;
             if new_provider != provider {
                 let tos_accepted = user_store
                     .read(cx)
@@ -129,13 +154,36 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
                         }
                         EditPredictionProvider::None
                         | EditPredictionProvider::Copilot
-                        | EditPredictionProvider::Supermaven => {}
+                        | EditPredictionProvider::Supermaven
+                        | EditPredictionProvider::Extension => {}
                     }
                 }
             }
         }
     })
     .detach();
+
+    // Extensions may register their edit prediction providers after editors
+    // have been opened, so reassign the providers whenever extensions change.
+    if let Some(extension_events) = extension::ExtensionEvents::try_global(cx) {
+        cx.subscribe(&extension_events, move |_, event, cx| {
+            if !matches!(event, extension::Event::ExtensionsInstalledChanged) {
+                return;
+            }
+
+            let provider = all_language_settings(None, cx).edit_predictions.provider;
+            if provider == EditPredictionProvider::Extension {
+                assign_edit_prediction_providers(
+                    &editors,
+                    provider,
+                    &client,
+                    user_store.clone(),
+                    cx,
+                );
+            }
+        })
+        .detach();
+    }
 }
 
 fn clear_zeta_edit_history(_: &zeta::ClearHistory, cx: &mut App) {
@@ -241,6 +289,21 @@ fn assign_edit_prediction_provider(
                 editor.set_edit_prediction_provider(Some(provider), window, cx);
             }
         }
+        EditPredictionProvider::Extension => {
+            let provider = all_language_settings(None, cx)
+                .edit_predictions
+                .extension_provider
+                .clone()
+                .zip(ExtensionEditPredictionRegistry::try_global(cx))
+                .and_then(|(provider_id, registry)| registry.build_provider(&provider_id));
+            if let Some(provider) = provider {
+                let provider = cx.new(|_| provider);
+                editor.set_edit_prediction_provider(Some(provider), window, cx);
+            } else {
+                editor
+                    .set_edit_prediction_provider::<ZetaInlineCompletionProvider>(None, window, cx);
+            }
+        }
         EditPredictionProvider::Zed => {
             if client.status().borrow().is_connected() {
                 let mut worktree = None;